@@ -14,6 +14,7 @@ echo "Building the simulator packages..."
 
 (set -x; cd simulator; cargo build)
 (set -x; cd simulator; cargo test --no-run)
+(set -x; cd simulator; cargo build --features wasmer --bin replay)
 
 echo "Building the engine in different configurations..."
 
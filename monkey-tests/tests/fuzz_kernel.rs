@@ -23,7 +23,7 @@ use rand_chacha::rand_core::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
-use transaction::model::PreAllocatedAddress;
+use transaction::model::{ExecutableFeeSponsor, ExecutableSubintent, PreAllocatedAddress};
 
 struct TestCallFrameData;
 
@@ -61,6 +61,8 @@ impl KernelCallbackObject for TestCallbackObject {
         _: &Vec<PreAllocatedAddress>,
         _: &IndexSet<Reference>,
         _: &IndexMap<Hash, Vec<u8>>,
+        _: &[ExecutableSubintent],
+        _: Option<&ExecutableFeeSponsor>,
     ) -> Result<Vec<u8>, RuntimeError>
     where
         Y: KernelApi<Self>,
@@ -87,6 +87,136 @@ impl SerializableCustomExtension for ManifestCustomExtension {
     }
 }
 
+/// The context required to deserialize Manifest custom values from their serde representation.
+///
+/// Names of buckets, proofs, address reservations and named addresses cannot be resolved, so
+/// these are only accepted in their numeric form.
+#[derive(Clone, Copy, Default)]
+pub struct ManifestValueDeserializationContext<'a> {
+    pub address_bech32_decoder: Option<&'a AddressBech32Decoder>,
+}
+
+impl<'a> ManifestValueDeserializationContext<'a> {
+    pub fn no_context() -> Self {
+        Self {
+            address_bech32_decoder: None,
+        }
+    }
+
+    pub fn with_optional_bech32(address_bech32_decoder: Option<&'a AddressBech32Decoder>) -> Self {
+        Self {
+            address_bech32_decoder,
+        }
+    }
+
+    fn parse_address(&self, value: &str) -> Result<ManifestAddress, String> {
+        if let Ok(address_id) = value.parse::<u32>() {
+            return Ok(ManifestAddress::Named(address_id));
+        }
+        let bytes = if let Ok(bytes) = hex::decode(value) {
+            bytes
+        } else if let Some(decoder) = self.address_bech32_decoder {
+            decoder
+                .validate_and_decode(value)
+                .map_err(|err| format!("{:?}", err))?
+                .1
+        } else {
+            AddressBech32Decoder::validate_and_decode_ignore_hrp(value)
+                .map_err(|err| format!("{:?}", err))?
+                .2
+        };
+        let bytes: [u8; NodeId::LENGTH] = bytes
+            .try_into()
+            .map_err(|_| format!("Address must be {} bytes long", NodeId::LENGTH))?;
+        Ok(ManifestAddress::Static(NodeId(bytes)))
+    }
+}
+
+impl<'a> Into<ManifestValueDeserializationContext<'a>> for &'a AddressBech32Decoder {
+    fn into(self) -> ManifestValueDeserializationContext<'a> {
+        ManifestValueDeserializationContext::with_optional_bech32(Some(self))
+    }
+}
+
+fn parse_id(value: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("Expected a numeric id, but got: {}", value))
+}
+
+impl DeserializableCustomExtension for ManifestCustomExtension {
+    type CustomValue = ManifestCustomValue;
+    type CustomDeserializationContext<'a> = ManifestValueDeserializationContext<'a>;
+
+    fn custom_value_kind_from_name(name: &str) -> Option<Self::CustomValueKind> {
+        Some(match name {
+            "Address" => ManifestCustomValueKind::Address,
+            "Bucket" => ManifestCustomValueKind::Bucket,
+            "Proof" => ManifestCustomValueKind::Proof,
+            "Expression" => ManifestCustomValueKind::Expression,
+            "Blob" => ManifestCustomValueKind::Blob,
+            "Decimal" => ManifestCustomValueKind::Decimal,
+            "PreciseDecimal" => ManifestCustomValueKind::PreciseDecimal,
+            "NonFungibleLocalId" => ManifestCustomValueKind::NonFungibleLocalId,
+            "AddressReservation" => ManifestCustomValueKind::AddressReservation,
+            _ => return None,
+        })
+    }
+
+    fn parse_custom_value<'a>(
+        context: &Self::CustomDeserializationContext<'a>,
+        custom_value_kind: Self::CustomValueKind,
+        value: &str,
+    ) -> Result<Self::CustomValue, String> {
+        Ok(match custom_value_kind {
+            ManifestCustomValueKind::Address => {
+                ManifestCustomValue::Address(context.parse_address(value)?)
+            }
+            ManifestCustomValueKind::Bucket => {
+                ManifestCustomValue::Bucket(ManifestBucket(parse_id(value)?))
+            }
+            ManifestCustomValueKind::Proof => {
+                ManifestCustomValue::Proof(ManifestProof(parse_id(value)?))
+            }
+            ManifestCustomValueKind::AddressReservation => ManifestCustomValue::AddressReservation(
+                ManifestAddressReservation(parse_id(value)?),
+            ),
+            ManifestCustomValueKind::Expression => ManifestCustomValue::Expression(match value {
+                "ENTIRE_WORKTOP" => ManifestExpression::EntireWorktop,
+                "ENTIRE_AUTH_ZONE" => ManifestExpression::EntireAuthZone,
+                _ => return Err(format!("Unknown expression: {}", value)),
+            }),
+            ManifestCustomValueKind::Blob => {
+                let bytes = hex::decode(value).map_err(|err| format!("{:?}", err))?;
+                ManifestCustomValue::Blob(ManifestBlobRef(
+                    bytes
+                        .try_into()
+                        .map_err(|_| "Blob hash must be 32 bytes long".to_string())?,
+                ))
+            }
+            ManifestCustomValueKind::Decimal => ManifestCustomValue::Decimal(from_decimal(
+                &value
+                    .parse::<Decimal>()
+                    .map_err(|err| format!("{:?}", err))?,
+            )),
+            ManifestCustomValueKind::PreciseDecimal => {
+                ManifestCustomValue::PreciseDecimal(from_precise_decimal(
+                    &value
+                        .parse::<PreciseDecimal>()
+                        .map_err(|err| format!("{:?}", err))?,
+                ))
+            }
+            ManifestCustomValueKind::NonFungibleLocalId => {
+                ManifestCustomValue::NonFungibleLocalId(from_non_fungible_local_id(
+                    value
+                        .parse::<NonFungibleLocalId>()
+                        .map_err(|err| format!("{:?}", err))?,
+                ))
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "serde")] // Ensures that VS Code runs this module with the features serde tag!
 mod tests {
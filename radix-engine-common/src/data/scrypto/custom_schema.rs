@@ -196,6 +196,35 @@ impl CustomSchema for ScryptoCustomSchema {
         }
     }
 
+    fn custom_type_validation_is_backwards_compatible(
+        old: &Self::CustomTypeValidation,
+        new: &Self::CustomTypeValidation,
+    ) -> bool {
+        match (old, new) {
+            (
+                ScryptoCustomTypeValidation::Reference(old),
+                ScryptoCustomTypeValidation::Reference(new),
+            ) => match (old, new) {
+                (old, new) if old == new => true,
+                (
+                    ReferenceValidation::IsGlobalPackage
+                    | ReferenceValidation::IsGlobalComponent
+                    | ReferenceValidation::IsGlobalResourceManager
+                    | ReferenceValidation::IsGlobalTyped(_, _),
+                    ReferenceValidation::IsGlobal,
+                ) => true,
+                (ReferenceValidation::IsInternalTyped(_, _), ReferenceValidation::IsInternal) => {
+                    true
+                }
+                _ => false,
+            },
+            (ScryptoCustomTypeValidation::Own(old), ScryptoCustomTypeValidation::Own(new)) => {
+                old == new
+            }
+            _ => false,
+        }
+    }
+
     fn empty_schema() -> &'static Schema<Self> {
         &EMPTY_SCHEMA
     }
@@ -40,12 +40,108 @@ impl SerializableCustomExtension for ScryptoCustomExtension {
     }
 }
 
+/// The context required to deserialize Scrypto custom values from their serde representation.
+#[derive(Clone, Copy, Default)]
+pub struct ScryptoValueDeserializationContext<'a> {
+    pub address_bech32_decoder: Option<&'a AddressBech32Decoder>,
+}
+
+impl<'a> ScryptoValueDeserializationContext<'a> {
+    pub fn no_context() -> Self {
+        Self {
+            address_bech32_decoder: None,
+        }
+    }
+
+    pub fn with_optional_bech32(address_bech32_decoder: Option<&'a AddressBech32Decoder>) -> Self {
+        Self {
+            address_bech32_decoder,
+        }
+    }
+
+    /// Parses a node id in any of the formats output by `NodeId::to_string()`.
+    /// Without a decoder, Bech32m addresses of any network are accepted.
+    pub fn parse_node_id(&self, value: &str) -> Result<NodeId, String> {
+        let bytes = if let Some(hex) = value
+            .strip_prefix("NodeId(")
+            .and_then(|rest| rest.strip_suffix(")"))
+        {
+            hex::decode(hex).map_err(|err| format!("{:?}", err))?
+        } else if let Some(decoder) = self.address_bech32_decoder {
+            decoder
+                .validate_and_decode(value)
+                .map_err(|err| format!("{:?}", err))?
+                .1
+        } else {
+            AddressBech32Decoder::validate_and_decode_ignore_hrp(value)
+                .map_err(|err| format!("{:?}", err))?
+                .2
+        };
+        let bytes: [u8; NodeId::LENGTH] = bytes
+            .try_into()
+            .map_err(|_| format!("Node id must be {} bytes long", NodeId::LENGTH))?;
+        Ok(NodeId(bytes))
+    }
+}
+
+impl<'a> Into<ScryptoValueDeserializationContext<'a>> for &'a AddressBech32Decoder {
+    fn into(self) -> ScryptoValueDeserializationContext<'a> {
+        ScryptoValueDeserializationContext::with_optional_bech32(Some(self))
+    }
+}
+
+impl DeserializableCustomExtension for ScryptoCustomExtension {
+    type CustomValue = ScryptoCustomValue;
+    type CustomDeserializationContext<'a> = ScryptoValueDeserializationContext<'a>;
+
+    fn custom_value_kind_from_name(name: &str) -> Option<Self::CustomValueKind> {
+        Some(match name {
+            "Reference" => ScryptoCustomValueKind::Reference,
+            "Own" => ScryptoCustomValueKind::Own,
+            "Decimal" => ScryptoCustomValueKind::Decimal,
+            "PreciseDecimal" => ScryptoCustomValueKind::PreciseDecimal,
+            "NonFungibleLocalId" => ScryptoCustomValueKind::NonFungibleLocalId,
+            _ => return None,
+        })
+    }
+
+    fn parse_custom_value<'a>(
+        context: &Self::CustomDeserializationContext<'a>,
+        custom_value_kind: Self::CustomValueKind,
+        value: &str,
+    ) -> Result<Self::CustomValue, String> {
+        Ok(match custom_value_kind {
+            ScryptoCustomValueKind::Reference => {
+                ScryptoCustomValue::Reference(Reference(context.parse_node_id(value)?))
+            }
+            ScryptoCustomValueKind::Own => {
+                ScryptoCustomValue::Own(Own(context.parse_node_id(value)?))
+            }
+            ScryptoCustomValueKind::Decimal => ScryptoCustomValue::Decimal(
+                value
+                    .parse::<Decimal>()
+                    .map_err(|err| format!("{:?}", err))?,
+            ),
+            ScryptoCustomValueKind::PreciseDecimal => ScryptoCustomValue::PreciseDecimal(
+                value
+                    .parse::<PreciseDecimal>()
+                    .map_err(|err| format!("{:?}", err))?,
+            ),
+            ScryptoCustomValueKind::NonFungibleLocalId => ScryptoCustomValue::NonFungibleLocalId(
+                value
+                    .parse::<NonFungibleLocalId>()
+                    .map_err(|err| format!("{:?}", err))?,
+            ),
+        })
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "serde")] // Ensures that VS Code runs this module with the features serde tag!
 mod tests {
     use super::*;
     use crate::address::test_addresses::*;
-    use crate::address::AddressBech32Encoder;
+    use crate::address::{AddressBech32Decoder, AddressBech32Encoder};
     use crate::data::scrypto::model::*;
     use crate::data::scrypto::{scrypto_encode, ScryptoValue};
     use crate::math::*;
@@ -236,6 +332,62 @@ mod tests {
         assert_programmatic_json_matches(&value, context, expected_programmatic);
     }
 
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn test_programmatic_json_round_trip() {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let decoder = AddressBech32Decoder::for_simulator();
+        let value = ScryptoValue::Tuple {
+            fields: vec![
+                Value::Custom {
+                    value: ScryptoCustomValue::Reference(Reference(FUNGIBLE_RESOURCE_NODE_ID)),
+                },
+                Value::Custom {
+                    value: ScryptoCustomValue::Own(Own(FUNGIBLE_RESOURCE_NODE_ID)),
+                },
+                Value::Custom {
+                    value: ScryptoCustomValue::Decimal(Decimal::ONE.checked_div(100).unwrap()),
+                },
+                Value::Custom {
+                    value: ScryptoCustomValue::PreciseDecimal(PreciseDecimal::ZERO),
+                },
+                Value::Custom {
+                    value: ScryptoCustomValue::NonFungibleLocalId(
+                        NonFungibleLocalId::string("hello").unwrap(),
+                    ),
+                },
+            ],
+        };
+        let payload = scrypto_encode(&value).unwrap();
+
+        for (encoder, decoder) in [(Some(&encoder), Some(&decoder)), (None, None)] {
+            let json = to_value(
+                &ScryptoRawPayload::new_from_valid_slice(&payload).serializable(
+                    SerializationParameters::Schemaless {
+                        mode: SerializationMode::Programmatic,
+                        custom_context: ScryptoValueDisplayContext::with_optional_bech32(encoder),
+                        depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                    },
+                ),
+            )
+            .unwrap();
+
+            let deserialized = deserialize_payload(
+                &json,
+                &DeserializationParameters::<ScryptoCustomExtension>::Schemaless {
+                    custom_context: ScryptoValueDeserializationContext::with_optional_bech32(
+                        decoder,
+                    ),
+                    depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                },
+            )
+            .unwrap();
+
+            assert_eq!(deserialized.value, value);
+            assert_eq!(deserialized.payload, payload);
+        }
+    }
+
     fn assert_natural_json_matches<
         'a,
         T: ScryptoEncode,
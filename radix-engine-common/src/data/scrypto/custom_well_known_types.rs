@@ -529,6 +529,55 @@ create_well_known_lookup!(
             ROLE_ASSIGNMENT_TYPES_START + 7,
             named_transparent("RoleKey", string_type_data(),)
         ),
+        (
+            TIME_RULE,
+            ROLE_ASSIGNMENT_TYPES_START + 8,
+            named_enum(
+                "TimeRule",
+                [
+                    (0u8, named_tuple("AtOrAfterInstant", [INSTANT_TYPE])),
+                    (1u8, named_tuple("BeforeInstant", [INSTANT_TYPE])),
+                    (2u8, named_tuple("AtOrAfterEpoch", [U64_TYPE])),
+                    (3u8, named_tuple("BeforeEpoch", [U64_TYPE])),
+                ],
+            )
+        ),
+        // The access rule types of the access rule extensions protocol update, which additionally
+        // allow the `CountOf` and `TimeRule` access rule nodes.
+        (
+            ACCESS_RULE_V2,
+            ROLE_ASSIGNMENT_TYPES_START + 9,
+            named_enum(
+                "AccessRule",
+                [
+                    (0u8, named_tuple("AllowAll", [])),
+                    (1u8, named_tuple("DenyAll", [])),
+                    (2u8, named_tuple("Protected", [ACCESS_RULE_NODE_V2_TYPE])),
+                ],
+            )
+        ),
+        (
+            ACCESS_RULE_NODE_V2,
+            ROLE_ASSIGNMENT_TYPES_START + 10,
+            named_enum(
+                "AccessRuleNode",
+                [
+                    (0u8, named_tuple("ProofRule", [PROOF_RULE_TYPE])),
+                    (1u8, named_tuple("AnyOf", [ACCESS_RULE_NODE_LIST_V2_TYPE])),
+                    (2u8, named_tuple("AllOf", [ACCESS_RULE_NODE_LIST_V2_TYPE])),
+                    (
+                        3u8,
+                        named_tuple("CountOf", [U8_TYPE, ACCESS_RULE_NODE_LIST_V2_TYPE])
+                    ),
+                    (4u8, named_tuple("TimeRule", [TIME_RULE_TYPE])),
+                ],
+            )
+        ),
+        (
+            ACCESS_RULE_NODE_LIST_V2,
+            ROLE_ASSIGNMENT_TYPES_START + 11,
+            array_of(ACCESS_RULE_NODE_V2_TYPE)
+        ),
         // OTHER MODULE TYPES
         (
             MODULE_ID,
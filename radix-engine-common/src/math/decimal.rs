@@ -17,6 +17,7 @@ use crate::data::scrypto::*;
 use crate::math::bnum_integer::*;
 use crate::math::rounding_mode::*;
 use crate::math::traits::*;
+use crate::math::transcendental;
 use crate::math::PreciseDecimal;
 use crate::well_known_scrypto_custom_type;
 use crate::*;
@@ -269,6 +270,50 @@ impl Decimal {
             Some(Decimal(nth_root))
         }
     }
+
+    // The transcendental functions below are evaluated without floats, with a rounding which is
+    // specified in the docs of the `transcendental` module.
+
+    /// Calculates `e^self`, rounded with the given mode.
+    pub fn checked_exp(&self, mode: RoundingMode) -> Option<Self> {
+        let exp = transcendental::exp(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(exp).ok().map(Self)
+    }
+
+    /// Calculates the natural logarithm, rounded with the given mode.
+    /// Returns `None` if the number is not positive.
+    pub fn checked_ln(&self, mode: RoundingMode) -> Option<Self> {
+        let ln = transcendental::ln(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(ln).ok().map(Self)
+    }
+
+    /// Calculates the base 2 logarithm, rounded with the given mode.
+    /// Returns `None` if the number is not positive.
+    pub fn checked_log2(&self, mode: RoundingMode) -> Option<Self> {
+        let log2 = transcendental::log2(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(log2).ok().map(Self)
+    }
+
+    /// Calculates the base 10 logarithm, rounded with the given mode.
+    /// Returns `None` if the number is not positive.
+    pub fn checked_log10(&self, mode: RoundingMode) -> Option<Self> {
+        let log10 = transcendental::log10(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(log10).ok().map(Self)
+    }
+
+    /// Calculates power with a fractional exponent, rounded with the given mode.
+    ///
+    /// Returns `None` on overflow, if the number is zero and the exponent negative, or if the
+    /// number is negative and the exponent not an integer. By convention, `0^0` is `1`.
+    pub fn checked_pow(&self, exp: Self, mode: RoundingMode) -> Option<Self> {
+        let pow = transcendental::pow(
+            &BigInt::from(self.0),
+            &BigInt::from(exp.0),
+            Self::SCALE,
+            mode,
+        )?;
+        I192::try_from(pow).ok().map(Self)
+    }
 }
 
 macro_rules! from_primitive_type {
@@ -1932,6 +1977,123 @@ mod tests {
         assert_eq!(root_0, None);
     }
 
+    #[test]
+    fn test_exp() {
+        let e = test_dec!(1);
+        assert_eq!(
+            e.checked_exp(RoundingMode::ToNegativeInfinity),
+            Some(test_dec!("2.718281828459045235"))
+        );
+        assert_eq!(
+            e.checked_exp(RoundingMode::ToPositiveInfinity),
+            Some(test_dec!("2.718281828459045236"))
+        );
+        assert_eq!(
+            test_dec!("-1").checked_exp(RoundingMode::ToNearestMidpointToEven),
+            Some(test_dec!("0.367879441171442322"))
+        );
+        assert_eq!(
+            Decimal::ZERO.checked_exp(RoundingMode::ToZero),
+            Some(Decimal::ONE)
+        );
+        assert_eq!(
+            test_dec!(90).checked_exp(RoundingMode::ToZero),
+            Some(test_dec!(
+                "1220403294317840802002710035136369753970.746421099767546244"
+            ))
+        );
+        assert_eq!(test_dec!(91).checked_exp(RoundingMode::ToZero), None);
+        assert_eq!(
+            test_dec!("-100").checked_exp(RoundingMode::ToPositiveInfinity),
+            Some(test_dec!("0.000000000000000001"))
+        );
+        assert_eq!(
+            test_dec!("-100").checked_exp(RoundingMode::ToNegativeInfinity),
+            Some(Decimal::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_ln_and_log() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_dec!(2).checked_ln(mode),
+            Some(test_dec!("0.693147180559945309"))
+        );
+        assert_eq!(
+            test_dec!("0.5").checked_ln(RoundingMode::ToNegativeInfinity),
+            Some(test_dec!("-0.69314718055994531"))
+        );
+        assert_eq!(
+            Decimal(I192::ONE).checked_ln(mode),
+            Some(test_dec!("-41.446531673892822312"))
+        );
+        assert_eq!(Decimal::ONE.checked_ln(mode), Some(Decimal::ZERO));
+        assert_eq!(Decimal::ZERO.checked_ln(mode), None);
+        assert_eq!(test_dec!("-1").checked_ln(mode), None);
+        assert_eq!(
+            test_dec!(3).checked_log2(mode),
+            Some(test_dec!("1.584962500721156181"))
+        );
+        assert_eq!(
+            test_dec!("0.125").checked_log2(RoundingMode::ToNegativeInfinity),
+            Some(test_dec!("-3"))
+        );
+        assert_eq!(
+            test_dec!(2).checked_log10(mode),
+            Some(test_dec!("0.301029995663981195"))
+        );
+        assert_eq!(
+            test_dec!("0.001").checked_log10(RoundingMode::ToPositiveInfinity),
+            Some(test_dec!("-3"))
+        );
+        assert_eq!(test_dec!(0).checked_log10(mode), None);
+    }
+
+    #[test]
+    fn test_pow() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_dec!(2).checked_pow(test_dec!("0.5"), mode),
+            Some(test_dec!("1.414213562373095049"))
+        );
+        assert_eq!(
+            test_dec!(2).checked_pow(test_dec!("0.5"), RoundingMode::ToZero),
+            Some(test_dec!("1.414213562373095048"))
+        );
+        assert_eq!(
+            test_dec!(2).checked_pow(test_dec!("0.5"), RoundingMode::ToPositiveInfinity),
+            Some(test_dec!("1.414213562373095049"))
+        );
+        assert_eq!(
+            test_dec!("1.05").checked_pow(test_dec!("-2.5"), mode),
+            Some(test_dec!("0.885170134193680888"))
+        );
+        assert_eq!(
+            test_dec!(4).checked_pow(test_dec!("0.5"), RoundingMode::ToNegativeInfinity),
+            Some(test_dec!(2))
+        );
+        assert_eq!(
+            test_dec!("-1.5").checked_pow(test_dec!(3), RoundingMode::ToNegativeInfinity),
+            Some(test_dec!("-3.375"))
+        );
+        assert_eq!(test_dec!("-1.5").checked_pow(test_dec!("0.5"), mode), None);
+        assert_eq!(
+            test_dec!(10).checked_pow(test_dec!(39), RoundingMode::ToZero),
+            Some(test_dec!("1000000000000000000000000000000000000000"))
+        );
+        assert_eq!(test_dec!(10).checked_pow(test_dec!(40), mode), None);
+        assert_eq!(
+            Decimal::ZERO.checked_pow(Decimal::ZERO, mode),
+            Some(Decimal::ONE)
+        );
+        assert_eq!(
+            Decimal::ZERO.checked_pow(test_dec!("0.5"), mode),
+            Some(Decimal::ZERO)
+        );
+        assert_eq!(Decimal::ZERO.checked_pow(test_dec!("-0.5"), mode), None);
+    }
+
     #[test]
     fn no_panic_with_18_decimal_places() {
         // Arrange
@@ -19,6 +19,7 @@ use crate::math::bnum_integer::*;
 use crate::math::decimal::*;
 use crate::math::rounding_mode::*;
 use crate::math::traits::*;
+use crate::math::transcendental;
 use crate::well_known_scrypto_custom_type;
 use crate::*;
 
@@ -293,6 +294,50 @@ impl PreciseDecimal {
             Some(Self(nth_root))
         }
     }
+
+    // The transcendental functions below are evaluated without floats, with a rounding which is
+    // specified in the docs of the `transcendental` module.
+
+    /// Calculates `e^self`, rounded with the given mode.
+    pub fn checked_exp(&self, mode: RoundingMode) -> Option<Self> {
+        let exp = transcendental::exp(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(exp).ok().map(Self)
+    }
+
+    /// Calculates the natural logarithm, rounded with the given mode.
+    /// Returns `None` if the number is not positive.
+    pub fn checked_ln(&self, mode: RoundingMode) -> Option<Self> {
+        let ln = transcendental::ln(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(ln).ok().map(Self)
+    }
+
+    /// Calculates the base 2 logarithm, rounded with the given mode.
+    /// Returns `None` if the number is not positive.
+    pub fn checked_log2(&self, mode: RoundingMode) -> Option<Self> {
+        let log2 = transcendental::log2(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(log2).ok().map(Self)
+    }
+
+    /// Calculates the base 10 logarithm, rounded with the given mode.
+    /// Returns `None` if the number is not positive.
+    pub fn checked_log10(&self, mode: RoundingMode) -> Option<Self> {
+        let log10 = transcendental::log10(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(log10).ok().map(Self)
+    }
+
+    /// Calculates power with a fractional exponent, rounded with the given mode.
+    ///
+    /// Returns `None` on overflow, if the number is zero and the exponent negative, or if the
+    /// number is negative and the exponent not an integer. By convention, `0^0` is `1`.
+    pub fn checked_pow(&self, exp: Self, mode: RoundingMode) -> Option<Self> {
+        let pow = transcendental::pow(
+            &BigInt::from(self.0),
+            &BigInt::from(exp.0),
+            Self::SCALE,
+            mode,
+        )?;
+        I256::try_from(pow).ok().map(Self)
+    }
 }
 
 macro_rules! from_primitive_type {
@@ -2134,6 +2179,81 @@ mod tests {
         assert_eq!(root_0, None);
     }
 
+    #[test]
+    fn test_exp() {
+        assert_eq!(
+            test_pdec!(1).checked_exp(RoundingMode::ToNegativeInfinity),
+            Some(test_pdec!("2.718281828459045235360287471352662497"))
+        );
+        assert_eq!(
+            test_pdec!(1).checked_exp(RoundingMode::ToPositiveInfinity),
+            Some(test_pdec!("2.718281828459045235360287471352662498"))
+        );
+        assert_eq!(
+            test_pdec!("-1").checked_exp(RoundingMode::ToNearestMidpointToEven),
+            Some(test_pdec!("0.367879441171442321595523770161460867"))
+        );
+        assert_eq!(
+            test_pdec!(91).checked_exp(RoundingMode::ToZero),
+            Some(test_pdec!(
+                "3317400098335742625755516107852591909603.014581182330827048807260069147012798"
+            ))
+        );
+        assert_eq!(test_pdec!(94).checked_exp(RoundingMode::ToZero), None);
+        assert_eq!(
+            test_pdec!("-100").checked_exp(RoundingMode::ToPositiveInfinity),
+            Some(test_pdec!("0.000000000000000000000000000000000001"))
+        );
+    }
+
+    #[test]
+    fn test_ln_and_log() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_pdec!(2).checked_ln(mode),
+            Some(test_pdec!("0.693147180559945309417232121458176568"))
+        );
+        assert_eq!(
+            PreciseDecimal(I256::ONE).checked_ln(mode),
+            Some(test_pdec!("-82.893063347785644624647692368637111474"))
+        );
+        assert_eq!(PreciseDecimal::ZERO.checked_ln(mode), None);
+        assert_eq!(
+            test_pdec!(3).checked_log2(mode),
+            Some(test_pdec!("1.584962500721156181453738943947816509"))
+        );
+        assert_eq!(
+            test_pdec!(2).checked_log10(mode),
+            Some(test_pdec!("0.301029995663981195213738894724493027"))
+        );
+        assert_eq!(
+            test_pdec!("0.001").checked_log10(RoundingMode::ToPositiveInfinity),
+            Some(test_pdec!("-3"))
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_pdec!(2).checked_pow(test_pdec!("0.5"), mode),
+            Some(test_pdec!("1.414213562373095048801688724209698079"))
+        );
+        assert_eq!(
+            test_pdec!("1.05").checked_pow(test_pdec!("-2.5"), mode),
+            Some(test_pdec!("0.885170134193680888303296722213242802"))
+        );
+        assert_eq!(
+            test_pdec!("-1.5").checked_pow(test_pdec!(3), RoundingMode::ToPositiveInfinity),
+            Some(test_pdec!("-3.375"))
+        );
+        assert_eq!(
+            test_pdec!(10).checked_pow(test_pdec!(40), RoundingMode::ToZero),
+            Some(test_pdec!("10000000000000000000000000000000000000000"))
+        );
+        assert_eq!(test_pdec!(10).checked_pow(test_pdec!(41), mode), None);
+    }
+
     #[test]
     fn no_panic_with_36_decimal_places() {
         // Arrange
@@ -0,0 +1,299 @@
+//! Deterministic, integer-only evaluation of the transcendental functions of [`Decimal`] and
+//! [`PreciseDecimal`], such as `checked_exp`, `checked_ln` and `checked_pow`.
+//!
+//! The functions are evaluated in fixed point arithmetic on [`BigInt`]s, where `n` represents
+//! `n / 10^scale`, with [`GUARD_DIGITS`] more digits than the scale of the result. The value is
+//! first snapped to the nearest multiple of `10^-10` units in the last place (ulp), which absorbs
+//! the evaluation errors, and then rounded with the requested [`RoundingMode`]:
+//! * Exact results (eg. `4^0.5` or `log10(1000)`) are returned as is, whatever the rounding mode,
+//!   and exact midpoints are rounded as such.
+//! * Otherwise, the exact result rounded with the mode is returned, except if the exact result
+//!   is within `10^-10` ulp of a rounding boundary, in which case it may be off by one ulp.
+//!
+//! Positive results too small to be snapped, such as `e^x` for a very negative `x`, are rounded
+//! as is: eg. `e^-100` is the smallest positive number when rounded toward positive infinity,
+//! and zero when rounded toward negative infinity.
+//!
+//! [`Decimal`]: crate::math::Decimal
+//! [`PreciseDecimal`]: crate::math::PreciseDecimal
+
+use crate::math::rounding_mode::*;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Pow, Signed, Zero};
+use sbor::rust::prelude::*;
+
+/// The number of digits the results are evaluated with, in addition to their scale.
+pub const GUARD_DIGITS: u32 = 20;
+
+/// The results are snapped to the nearest multiple of `10^-SNAP_DIGITS` ulp before being rounded.
+const SNAP_DIGITS: u32 = 10;
+
+/// The extra digits intermediate values are carried with, which absorb their truncation errors.
+const EXTRA_DIGITS: u32 = 10;
+
+/// The largest argument to `exp`, as `e^256` is way beyond the max of the decimal types.
+const MAX_EXP_ARGUMENT: u32 = 256;
+
+/// Returns `e^x` at the given scale.
+pub(crate) fn exp(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    let result = exp_fixed(x, scale, scale + GUARD_DIGITS)?;
+    Some(round_result(&result.max(BigInt::one()), mode))
+}
+
+/// Returns `ln(x)` at the given scale, or `None` if `x` is not positive.
+pub(crate) fn ln(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    if !x.is_positive() {
+        return None;
+    }
+    Some(round_result(
+        &ln_fixed(x, scale, scale + GUARD_DIGITS),
+        mode,
+    ))
+}
+
+/// Returns `log2(x)` at the given scale, or `None` if `x` is not positive.
+pub(crate) fn log2(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    if !x.is_positive() {
+        return None;
+    }
+    let working_scale = scale + GUARD_DIGITS;
+    let ln = ln_fixed(x, scale, working_scale + EXTRA_DIGITS);
+    let result = ln * pow10(working_scale) / ln2(working_scale + EXTRA_DIGITS);
+    Some(round_result(&result, mode))
+}
+
+/// Returns `log10(x)` at the given scale, or `None` if `x` is not positive.
+pub(crate) fn log10(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    if !x.is_positive() {
+        return None;
+    }
+    let working_scale = scale + GUARD_DIGITS;
+    let ln = ln_fixed(x, scale, working_scale + EXTRA_DIGITS);
+    let result = ln * pow10(working_scale) / ln10(working_scale + EXTRA_DIGITS);
+    Some(round_result(&result, mode))
+}
+
+/// Returns `x^y` at the given scale, where both `x` and `y` are at that scale.
+///
+/// This is `None` if `x` is zero and `y` negative, or if `x` is negative and `y` is not an
+/// integer. By convention, `0^0` is `1`.
+pub(crate) fn pow(x: &BigInt, y: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    let one = pow10(scale);
+    if y.is_zero() {
+        return Some(one);
+    }
+    if x.is_zero() {
+        return if y.is_positive() {
+            Some(BigInt::zero())
+        } else {
+            None
+        };
+    }
+
+    let working_scale = scale + GUARD_DIGITS;
+    if x.is_negative() {
+        let (integer, fraction) = y.div_mod_floor(&one);
+        if !fraction.is_zero() {
+            return None;
+        }
+        let magnitude = pow_fixed(&-x, y, scale, working_scale)?.max(BigInt::one());
+        let result = if integer.is_odd() {
+            -magnitude
+        } else {
+            magnitude
+        };
+        Some(round_result(&result, mode))
+    } else {
+        let result = pow_fixed(x, y, scale, working_scale)?.max(BigInt::one());
+        Some(round_result(&result, mode))
+    }
+}
+
+fn pow10(n: u32) -> BigInt {
+    BigInt::from(10u8).pow(n)
+}
+
+fn rescale(n: &BigInt, from_scale: u32, to_scale: u32) -> BigInt {
+    if to_scale >= from_scale {
+        n * pow10(to_scale - from_scale)
+    } else {
+        n / pow10(from_scale - to_scale)
+    }
+}
+
+fn count_digits(n: &BigInt) -> u32 {
+    n.abs().to_string().len() as u32
+}
+
+/// Rounds the given number of digits off `n`.
+fn round(n: &BigInt, digits: u32, mode: RoundingMode) -> BigInt {
+    let divisor = pow10(digits);
+    let (quotient, remainder) = n.div_mod_floor(&divisor);
+    if remainder.is_zero() {
+        return quotient;
+    }
+
+    let resolved_strategy = ResolvedRoundingStrategy::from_mode(mode, n.is_positive(), || {
+        remainder.cmp(&(&divisor >> 1))
+    });
+    match resolved_strategy {
+        ResolvedRoundingStrategy::RoundUp => quotient + 1,
+        ResolvedRoundingStrategy::RoundDown => quotient,
+        ResolvedRoundingStrategy::RoundToEven => {
+            if quotient.is_even() {
+                quotient
+            } else {
+                quotient + 1
+            }
+        }
+    }
+}
+
+/// Rounds a result evaluated with the guard digits, after snapping it to the nearest multiple of
+/// `10^-SNAP_DIGITS` ulp.
+fn round_result(n: &BigInt, mode: RoundingMode) -> BigInt {
+    let snapped = round(
+        n,
+        GUARD_DIGITS - SNAP_DIGITS,
+        RoundingMode::ToNearestMidpointAwayFromZero,
+    );
+    if snapped.is_zero() {
+        round(n, GUARD_DIGITS, mode)
+    } else {
+        round(&snapped, SNAP_DIGITS, mode)
+    }
+}
+
+/// Returns `atanh(1 / q)`, for `q > 1`.
+fn atanh_inv(q: u32, scale: u32) -> BigInt {
+    // atanh(z) = z + z^3 / 3 + z^5 / 5 + ...
+    let q_squared = BigInt::from(q) * q;
+    let mut power = pow10(scale) / q;
+    let mut sum = BigInt::zero();
+    let mut n = 1u32;
+    while !power.is_zero() {
+        sum += &power / n;
+        power /= &q_squared;
+        n += 2;
+    }
+    sum
+}
+
+/// Returns `ln(2) = 2 atanh(1 / 3)`.
+fn ln2(scale: u32) -> BigInt {
+    let s = scale + EXTRA_DIGITS;
+    rescale(&(atanh_inv(3, s) * 2), s, scale)
+}
+
+/// Returns `ln(10) = 3 ln(2) + ln(5 / 4) = 6 atanh(1 / 3) + 2 atanh(1 / 9)`.
+fn ln10(scale: u32) -> BigInt {
+    let s = scale + EXTRA_DIGITS;
+    rescale(&(atanh_inv(3, s) * 6 + atanh_inv(9, s) * 2), s, scale)
+}
+
+/// Returns `ln(x)` at the given scale, for a positive `x` at `x_scale`, within a few units.
+fn ln_fixed(x: &BigInt, x_scale: u32, scale: u32) -> BigInt {
+    let s = scale + EXTRA_DIGITS;
+    let one = pow10(s);
+
+    // x = 2^k m, with 2/3 <= m < 4/3
+    let mut k = x.bits() as i64 - pow10(x_scale).bits() as i64;
+    let mut m = if k >= 0 {
+        x * &one / (pow10(x_scale) << k as usize)
+    } else {
+        (x * &one << (-k) as usize) / pow10(x_scale)
+    };
+    while &m * 3 >= &one * 4 {
+        m = m >> 1;
+        k += 1;
+    }
+    while &m * 3 < &one * 2 {
+        m = m << 1;
+        k -= 1;
+    }
+
+    // ln(m) = 2 atanh((m - 1) / (m + 1)), where |(m - 1) / (m + 1)| <= 1 / 5
+    let z = (&m - &one) * &one / (&m + &one);
+    let z_squared = &z * &z / &one;
+    let mut power = z;
+    let mut sum = BigInt::zero();
+    let mut n = 1u32;
+    while !power.is_zero() {
+        sum += &power / n;
+        power = power * &z_squared / &one;
+        n += 2;
+    }
+
+    rescale(&(sum * 2 + ln2(s) * k), s, scale)
+}
+
+/// Returns `e^x` at the given scale, for an `x` at `x_scale`, within a few units. This is `None`
+/// if `x` is beyond [`MAX_EXP_ARGUMENT`], and zero if it is below its opposite.
+fn exp_fixed(x: &BigInt, x_scale: u32, scale: u32) -> Option<BigInt> {
+    let max_argument = BigInt::from(MAX_EXP_ARGUMENT) * pow10(x_scale);
+    if x > &max_argument {
+        return None;
+    }
+    if x < &-max_argument {
+        return Some(BigInt::zero());
+    }
+
+    // x = k ln(2) + r, with |r| < ln(2), so that e^x = 2^k e^r. The digits of 2^k are carried
+    // along, so that the result is within a few units whatever its magnitude.
+    let k = i64::try_from(x / ln2(x_scale)).ok()?;
+    let result_digits = if k > 0 {
+        k as u32 * 30103 / 100000 + 1
+    } else {
+        0
+    };
+    let s = scale + EXTRA_DIGITS + result_digits;
+    let one = pow10(s);
+    let r = rescale(x, x_scale, s) - ln2(s) * k;
+
+    // e^r = (e^(r / 2^8))^(2^8), as the Taylor series converges faster for smaller arguments
+    let r = r / 256;
+    let mut term = one.clone();
+    let mut sum = one.clone();
+    let mut n = 1u32;
+    loop {
+        term = term * &r / (&one * n);
+        if term.is_zero() {
+            break;
+        }
+        sum += &term;
+        n += 1;
+    }
+    for _ in 0..8 {
+        sum = &sum * &sum / &one;
+    }
+
+    let result = if k >= 0 {
+        sum << k as usize
+    } else {
+        sum >> (-k) as usize
+    };
+    Some(rescale(&result, s, scale))
+}
+
+/// Returns `x^y = e^(y ln(x))` at the given scale, for a positive `x` and `y` at `xy_scale`.
+fn pow_fixed(x: &BigInt, y: &BigInt, xy_scale: u32, scale: u32) -> Option<BigInt> {
+    // Estimates y ln(x) at scale 2, which bounds the digits of the result. Any error of y ln(x)
+    // is an error relative to the result, so it's evaluated with all of them.
+    let y_digits = count_digits(&(y / pow10(xy_scale)));
+    let estimate = y * ln_fixed(x, xy_scale, 2 + y_digits) / pow10(xy_scale + y_digits);
+    let max_estimate = BigInt::from(MAX_EXP_ARGUMENT * 100);
+    if estimate > max_estimate {
+        return None;
+    }
+    if estimate < -max_estimate {
+        return Some(BigInt::zero());
+    }
+    // The result has at most y ln(x) / ln(10) + 1 integer digits
+    let estimate_digits = (estimate / 100 + 1) / 2 + 1;
+    let result_digits = u32::try_from(estimate_digits.max(BigInt::zero())).ok()?;
+
+    let s = scale + EXTRA_DIGITS + result_digits + y_digits;
+    let exponent = y * ln_fixed(x, xy_scale, s) / pow10(xy_scale);
+    exp_fixed(&exponent, s, scale)
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "radix_engine_fuzzing")]
+use arbitrary::Arbitrary;
 use sbor::Sbor;
 
 /// An index of a specific validator within the current validator set.
@@ -10,6 +12,10 @@ use sbor::Sbor;
 pub type ValidatorIndex = u8;
 
 /// A type-safe consensus epoch number.
+#[cfg_attr(
+    feature = "radix_engine_fuzzing",
+    derive(Arbitrary, serde::Serialize, serde::Deserialize)
+)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Sbor)]
 #[sbor(transparent)]
 pub struct Epoch(u64);
@@ -33,6 +33,27 @@ pub trait ClientActorIndexApi<E> {
         )
     }
 
+    /// Reads the value of an entry of an index
+    fn actor_index_get(
+        &mut self,
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, E>;
+
+    /// Reads the value of an entry of an index
+    fn actor_index_get_typed<V: ScryptoDecode>(
+        &mut self,
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+    ) -> Result<Option<V>, E> {
+        let rtn = self
+            .actor_index_get(object_handle, collection_index, key)?
+            .map(|e| scrypto_decode(&e).unwrap());
+        Ok(rtn)
+    }
+
     /// Removes an entry from an index
     fn actor_index_remove(
         &mut self,
@@ -65,6 +65,16 @@ pub trait ClientActorSortedIndexApi<E> {
         count: u32,
     ) -> Result<Vec<(SortedKey, Vec<u8>)>, E>;
 
+    /// Scans the elements of count following the given sorted key (or the first ones, if none)
+    /// from a sorted index
+    fn actor_sorted_index_scan_after(
+        &mut self,
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        after_sorted_key: Option<&SortedKey>,
+        count: u32,
+    ) -> Result<Vec<(SortedKey, Vec<u8>)>, E>;
+
     /// Scans the first elements of count from a sorted index
     fn actor_sorted_index_scan_typed<K: ScryptoDecode, V: ScryptoDecode>(
         &mut self,
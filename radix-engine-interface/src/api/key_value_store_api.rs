@@ -154,4 +154,17 @@ pub trait ClientKeyValueStoreApi<E> {
         node_id: &NodeId,
         key: &Vec<u8>,
     ) -> Result<Vec<u8>, E>;
+
+    /// Reads up to `limit` keys of the non-empty entries of a key value store, in a deterministic
+    /// order, starting after `after_key` (if given). Passing the last returned key as `after_key`
+    /// reads the next page.
+    fn key_value_store_scan_keys(
+        &mut self,
+        node_id: &NodeId,
+        after_key: Option<&Vec<u8>>,
+        limit: u32,
+    ) -> Result<Vec<Vec<u8>>, E>;
+
+    /// Counts the non-empty entries of a key value store
+    fn key_value_store_len(&mut self, node_id: &NodeId) -> Result<u32, E>;
 }
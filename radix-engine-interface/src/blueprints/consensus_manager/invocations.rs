@@ -92,6 +92,43 @@ impl ConsensusManagerConfig {
     }
 }
 
+/// The jailing (and slashing) of validators which repeatedly miss the `min_validator_reliability`.
+/// It is a part of the consensus manager configuration since the validator jailing protocol
+/// update (before it, validators are never jailed).
+#[derive(Debug, Clone, Eq, PartialEq, ScryptoSbor, ManifestSbor)]
+pub struct ValidatorJailingConfig {
+    /// A number of consecutive epochs in which an active validator does not reach the
+    /// `min_validator_reliability`, after which it gets jailed (i.e. excluded from the active
+    /// validator set)
+    pub num_unreliable_epochs_before_jailing: u64,
+    /// A number of epochs a jailed validator is excluded from the active validator set for, before
+    /// its owner can unjail it
+    pub num_jail_epochs: u64,
+    /// The proportion of the staked XRD which is slashed when a validator gets jailed
+    /// Must be between 0 and 1 (inclusive), see [`ValidatorJailingConfig::is_valid`]
+    /// Note: the XRD of pending unstakes is not slashed, since it is owed to the holders of the
+    /// already-issued claim NFTs
+    pub slash_factor: Decimal,
+    /// Where the slashed XRD goes
+    pub slashed_xrd_destination: SlashedXrdDestination,
+}
+
+impl ValidatorJailingConfig {
+    /// Returns whether the `slash_factor` is a proper fraction.
+    pub fn is_valid(&self) -> bool {
+        !self.slash_factor.is_negative() && self.slash_factor <= Decimal::one()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ScryptoSbor, ManifestSbor)]
+pub enum SlashedXrdDestination {
+    /// The slashed XRD is burnt
+    Burn,
+    /// The slashed XRD is added to the rewards of the validator set, and distributed along with
+    /// the transaction fees at the epoch change
+    Redistribute,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default, ScryptoSbor, ManifestSbor)]
 pub struct EpochChangeCondition {
     /// A minimum number of rounds that *must* happen in an epoch.
@@ -511,6 +548,26 @@ pub struct ValidatorApplyRewardInput {
 
 pub type ValidatorApplyRewardOutput = ();
 
+pub const VALIDATOR_APPLY_RELIABILITY_IDENT: &str = "apply_reliability";
+
+#[derive(Debug, Eq, PartialEq, ScryptoSbor)]
+pub struct ValidatorApplyReliabilityInput {
+    /// Whether this validator reached the `min_validator_reliability` during the concluded epoch.
+    pub is_reliable: bool,
+    /// The *concluded* epoch's number.
+    pub epoch: Epoch,
+}
+
+/// The slashed XRD, if this validator got jailed.
+pub type ValidatorApplyReliabilityOutput = Option<Bucket>;
+
+pub const VALIDATOR_UNJAIL_IDENT: &str = "unjail";
+
+#[derive(Debug, Clone, Eq, PartialEq, Sbor)]
+pub struct ValidatorUnjailInput {}
+
+pub type ValidatorUnjailOutput = ();
+
 pub const VALIDATOR_LOCK_OWNER_STAKE_UNITS_IDENT: &str = "lock_owner_stake_units";
 
 #[derive(Debug, Eq, PartialEq, ScryptoSbor)]
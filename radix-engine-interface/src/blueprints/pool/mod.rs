@@ -1,7 +1,11 @@
 mod multi_resource_pool;
 mod one_resource_pool;
+mod stable_swap_pool;
 mod two_resource_pool;
+mod weighted_pool;
 
 pub use multi_resource_pool::*;
 pub use one_resource_pool::*;
+pub use stable_swap_pool::*;
 pub use two_resource_pool::*;
+pub use weighted_pool::*;
@@ -96,6 +96,45 @@ impl Describe<ScryptoCustomTypeKind> for ProofRule {
     }
 }
 
+/// Consensus clock rules, evaluated against the current round timestamp (with a precision of
+/// seconds) or the current epoch of the consensus manager.
+#[cfg_attr(
+    feature = "radix_engine_fuzzing",
+    derive(Arbitrary, serde::Serialize, serde::Deserialize)
+)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    ManifestSbor,
+    ScryptoCategorize,
+    ScryptoEncode,
+    ScryptoDecode,
+)]
+pub enum TimeRule {
+    /// Satisfied from the given instant onwards.
+    AtOrAfterInstant(Instant),
+    /// Satisfied until the given instant is reached.
+    BeforeInstant(Instant),
+    /// Satisfied from the given epoch onwards.
+    AtOrAfterEpoch(Epoch),
+    /// Satisfied until the given epoch is reached.
+    BeforeEpoch(Epoch),
+}
+
+impl Describe<ScryptoCustomTypeKind> for TimeRule {
+    const TYPE_ID: RustTypeId =
+        RustTypeId::WellKnown(well_known_scrypto_custom_types::TIME_RULE_TYPE);
+
+    fn type_data() -> ScryptoTypeData<RustTypeId> {
+        well_known_scrypto_custom_types::time_rule_type_data()
+    }
+}
+
 impl From<ResourceAddress> for AccessRuleNode {
     fn from(resource_address: ResourceAddress) -> Self {
         AccessRuleNode::ProofRule(ProofRule::Require(resource_address.into()))
@@ -135,6 +174,14 @@ pub enum AccessRuleNode {
     ProofRule(ProofRule),
     AnyOf(Vec<AccessRuleNode>),
     AllOf(Vec<AccessRuleNode>),
+    /// Satisfied if at least the given number of the child nodes are satisfied.
+    ///
+    /// Only accepted by the role assignment module once the access rule extensions protocol
+    /// update has been applied.
+    CountOf(u8, Vec<AccessRuleNode>),
+    /// Only accepted by the role assignment module once the access rule extensions protocol
+    /// update has been applied.
+    TimeRule(TimeRule),
 }
 
 impl Describe<ScryptoCustomTypeKind> for AccessRuleNode {
@@ -220,6 +267,34 @@ where
     AccessRuleNode::ProofRule(ProofRule::AmountOf(amount.into(), resource.into()))
 }
 
+/// A requirement for at least `count` of the given rules to be satisfied.
+pub fn require_n_of_rules<C>(count: C, rules: Vec<AccessRuleNode>) -> AccessRuleNode
+where
+    C: Into<u8>,
+{
+    AccessRuleNode::CountOf(count.into(), rules)
+}
+
+/// A requirement for the current time to be at or after the given instant.
+pub fn after_instant(instant: Instant) -> AccessRuleNode {
+    AccessRuleNode::TimeRule(TimeRule::AtOrAfterInstant(instant))
+}
+
+/// A requirement for the current time to be before the given instant.
+pub fn before_instant(instant: Instant) -> AccessRuleNode {
+    AccessRuleNode::TimeRule(TimeRule::BeforeInstant(instant))
+}
+
+/// A requirement for the current epoch to be at or after the given epoch.
+pub fn after_epoch(epoch: Epoch) -> AccessRuleNode {
+    AccessRuleNode::TimeRule(TimeRule::AtOrAfterEpoch(epoch))
+}
+
+/// A requirement for the current epoch to be before the given epoch.
+pub fn before_epoch(epoch: Epoch) -> AccessRuleNode {
+    AccessRuleNode::TimeRule(TimeRule::BeforeEpoch(epoch))
+}
+
 #[cfg_attr(
     feature = "radix_engine_fuzzing",
     derive(Arbitrary, serde::Serialize, serde::Deserialize)
@@ -284,8 +359,10 @@ impl AccessRuleNode {
         visitor.visit(self, depth)?;
 
         match self {
-            AccessRuleNode::ProofRule(..) => {}
-            AccessRuleNode::AnyOf(nodes) | AccessRuleNode::AllOf(nodes) => {
+            AccessRuleNode::ProofRule(..) | AccessRuleNode::TimeRule(..) => {}
+            AccessRuleNode::AnyOf(nodes)
+            | AccessRuleNode::AllOf(nodes)
+            | AccessRuleNode::CountOf(_, nodes) => {
                 for node in nodes {
                     node.dfs_traverse_recursive(visitor, depth + 1)?;
                 }
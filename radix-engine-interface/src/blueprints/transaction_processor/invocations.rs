@@ -6,6 +6,12 @@ pub const TRANSACTION_PROCESSOR_BLUEPRINT: &str = "TransactionProcessor";
 
 pub const TRANSACTION_PROCESSOR_RUN_IDENT: &str = "run";
 
+/// Runs a root manifest which yields to child intents
+pub const TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT: &str = "run_with_subintents";
+
+/// Runs a child intent, when the root manifest yields to it
+pub const TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT: &str = "run_subintent";
+
 // TransactionProcessorInput in the engine
 
 pub type TransactionProcessorRunOutput = Vec<InstructionOutput>;
@@ -30,7 +30,7 @@ mod tests {
         let access_rule = AccessRule::Protected(access_rule_node.clone());
 
         test_equivalence(ACCESS_RULE_TYPE, access_rule);
-        test_equivalence(ACCESS_RULE_NODE_TYPE, access_rule_node);
+        test_equivalence(ACCESS_RULE_NODE_TYPE, access_rule_node.clone());
         test_statically_valid(ACCESS_RULE_NODE_LIST_TYPE, access_rule_node_list);
         test_equivalence(PROOF_RULE_TYPE, proof_rule);
         test_equivalence(RESOURCE_OR_NON_FUNGIBLE_TYPE, resource_or_non_fungible_1);
@@ -41,6 +41,30 @@ mod tests {
         );
         test_equivalence(OWNER_ROLE_TYPE, OwnerRole::None);
         test_equivalence(ROLE_KEY_TYPE, RoleKey::from("MyRoleName"));
+        test_equivalence(
+            TIME_RULE_TYPE,
+            TimeRule::AtOrAfterInstant(Instant::new(1_700_000_000)),
+        );
+        test_equivalence(TIME_RULE_TYPE, TimeRule::BeforeEpoch(Epoch::of(10)));
+        let access_rule_node_v2 = AccessRuleNode::CountOf(
+            1,
+            vec![
+                access_rule_node.clone(),
+                AccessRuleNode::TimeRule(TimeRule::BeforeEpoch(Epoch::of(10))),
+            ],
+        );
+        test_statically_valid(ACCESS_RULE_V2_TYPE, AccessRule::AllowAll);
+        test_statically_valid(
+            ACCESS_RULE_V2_TYPE,
+            AccessRule::Protected(access_rule_node_v2.clone()),
+        );
+        test_statically_valid(ACCESS_RULE_NODE_V2_TYPE, access_rule_node.clone());
+        test_statically_valid(ACCESS_RULE_NODE_V2_TYPE, access_rule_node_v2.clone());
+        test_statically_valid(
+            ACCESS_RULE_NODE_LIST_V2_TYPE,
+            vec![access_rule_node, access_rule_node_v2.clone()],
+        );
+        test_statically_invalid(ACCESS_RULE_TYPE, AccessRule::Protected(access_rule_node_v2));
 
         // OTHER MODULE TYPES
         test_equivalence(MODULE_ID_TYPE, ModuleId::Main);
@@ -70,6 +94,22 @@ mod tests {
         });
     }
 
+    fn test_statically_invalid<T: ScryptoEncode>(id: WellKnownTypeId, value: T) {
+        let type_name = core::any::type_name::<T>();
+
+        let result = validate_payload_against_schema::<ScryptoCustomExtension, _>(
+            &scrypto_encode(&value).unwrap(),
+            &ScryptoCustomSchema::empty_schema(),
+            id.into(),
+            &(),
+            10,
+        );
+        assert!(
+            result.is_err(),
+            "Expected value for {type_name} to not match well known type"
+        );
+    }
+
     fn test_type_data_equivalent<T: ScryptoDescribe>(id: WellKnownTypeId) {
         let type_name = core::any::type_name::<T>();
 
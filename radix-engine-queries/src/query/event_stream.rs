@@ -0,0 +1,269 @@
+use radix_engine::system::system_db_reader::{SystemDatabaseReader, SystemReaderError};
+use radix_engine::transaction::{
+    CommitResult, EventSystemStructure, TransactionReceipt, TransactionResult,
+};
+use radix_engine::types::*;
+use radix_engine_common::data::scrypto::{ScryptoRawPayload, ScryptoValueDisplayContext};
+use radix_engine_interface::api::ModuleId;
+use radix_engine_store_interface::interface::SubstateDatabase;
+use sbor::representations::{SerializationMode, SerializationParameters};
+use sbor::rust::prelude::*;
+use utils::ContextualSerialize;
+
+type EventPredicate = Box<dyn Fn(&EventTypeIdentifier, &[u8]) -> bool>;
+
+/// Selects the events yielded by an [`EventStream`].
+///
+/// An event must match every criterion which is set, and a criterion with several values matches
+/// if any of its values does.
+#[derive(Default)]
+pub struct EventFilter {
+    emitters: Option<IndexSet<NodeId>>,
+    blueprints: Option<IndexSet<BlueprintId>>,
+    packages: Option<IndexSet<PackageAddress>>,
+    event_names: Option<IndexSet<String>>,
+    predicates: Vec<EventPredicate>,
+}
+
+impl EventFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches the events emitted by any module of the given global entity.
+    ///
+    /// Note that the events of its owned objects (e.g. its vaults) are emitted by those objects
+    /// instead.
+    pub fn emitted_by(mut self, address: impl Into<GlobalAddress>) -> Self {
+        let address: GlobalAddress = address.into();
+        self.emitters
+            .get_or_insert_with(index_set_new)
+            .insert(address.into_node_id());
+        self
+    }
+
+    /// Matches the events emitted by the methods and functions of the given blueprint.
+    pub fn of_blueprint(mut self, blueprint_id: BlueprintId) -> Self {
+        self.blueprints
+            .get_or_insert_with(index_set_new)
+            .insert(blueprint_id);
+        self
+    }
+
+    /// Matches the events emitted by the blueprints of the given package.
+    pub fn of_package(mut self, package_address: PackageAddress) -> Self {
+        self.packages
+            .get_or_insert_with(index_set_new)
+            .insert(package_address);
+        self
+    }
+
+    pub fn named(mut self, event_name: impl Into<String>) -> Self {
+        self.event_names
+            .get_or_insert_with(index_set_new)
+            .insert(event_name.into());
+        self
+    }
+
+    /// Matches the events which decode as `T` and satisfy the given predicate.
+    ///
+    /// This is usually combined with [`Self::named`], as unrelated events may share an encoding.
+    pub fn matching<T: ScryptoDecode, F: Fn(&T) -> bool + 'static>(mut self, predicate: F) -> Self {
+        self.predicates.push(Box::new(move |_, payload| {
+            match scrypto_decode::<T>(payload) {
+                Ok(event) => predicate(&event),
+                Err(_) => false,
+            }
+        }));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamedEvent {
+    /// The position of the receipt in the stream.
+    pub receipt_index: usize,
+    /// The position of the event amongst the events of its receipt.
+    pub event_index: usize,
+    pub event_type_identifier: EventTypeIdentifier,
+    pub payload: Vec<u8>,
+    /// The payload in the programmatic JSON format, annotated by the schema of the event.
+    pub programmatic_json: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventStreamError {
+    MissingEventSystemStructure(EventTypeIdentifier),
+    SystemReaderError(SystemReaderError),
+    SerializationError(String),
+}
+
+impl From<SystemReaderError> for EventStreamError {
+    fn from(value: SystemReaderError) -> Self {
+        Self::SystemReaderError(value)
+    }
+}
+
+/// Filters and decodes the events of a stream of transaction receipts.
+///
+/// The schemas (and blueprints, if filtering by blueprint) are looked up in the given database,
+/// so it must contain the state committed by the receipts, e.g. by committing each receipt before
+/// streaming it. Rejected and aborted receipts have no events.
+pub struct EventStream<'a, S: SubstateDatabase> {
+    system_reader: SystemDatabaseReader<'a, S>,
+    filter: EventFilter,
+    address_encoder: Option<&'a AddressBech32Encoder>,
+}
+
+impl<'a, S: SubstateDatabase> EventStream<'a, S> {
+    pub fn new(substate_db: &'a S, filter: EventFilter) -> Self {
+        Self {
+            system_reader: SystemDatabaseReader::new(substate_db),
+            filter,
+            address_encoder: None,
+        }
+    }
+
+    /// Encodes the addresses in the programmatic JSON as Bech32, instead of hex.
+    pub fn with_address_encoder(mut self, address_encoder: &'a AddressBech32Encoder) -> Self {
+        self.address_encoder = Some(address_encoder);
+        self
+    }
+
+    pub fn stream<'r, I>(
+        &'r self,
+        receipts: I,
+    ) -> impl Iterator<Item = Result<StreamedEvent, EventStreamError>> + 'r
+    where
+        I: IntoIterator<Item = &'r TransactionReceipt>,
+        I::IntoIter: 'r,
+    {
+        receipts
+            .into_iter()
+            .enumerate()
+            .flat_map(move |(receipt_index, receipt)| match &receipt.result {
+                TransactionResult::Commit(commit) => self.events_of(receipt_index, commit),
+                TransactionResult::Reject(_) | TransactionResult::Abort(_) => vec![],
+            })
+    }
+
+    /// Returns the matching events of a single committed receipt, in the order of emission.
+    pub fn events_of(
+        &self,
+        receipt_index: usize,
+        commit: &CommitResult,
+    ) -> Vec<Result<StreamedEvent, EventStreamError>> {
+        let mut events = Vec::new();
+        for (event_index, (event_type_identifier, payload)) in
+            commit.application_events.iter().enumerate()
+        {
+            let event = match self.is_matching(commit, event_type_identifier, payload) {
+                Ok(true) => self
+                    .to_programmatic_json(commit, event_type_identifier, payload)
+                    .map(|programmatic_json| StreamedEvent {
+                        receipt_index,
+                        event_index,
+                        event_type_identifier: event_type_identifier.clone(),
+                        payload: payload.clone(),
+                        programmatic_json,
+                    }),
+                Ok(false) => continue,
+                Err(error) => Err(error),
+            };
+            events.push(event);
+        }
+        events
+    }
+
+    fn is_matching(
+        &self,
+        commit: &CommitResult,
+        event_type_identifier: &EventTypeIdentifier,
+        payload: &[u8],
+    ) -> Result<bool, EventStreamError> {
+        let filter = &self.filter;
+        let EventTypeIdentifier(emitter, event_name) = event_type_identifier;
+
+        if let Some(event_names) = &filter.event_names {
+            if !event_names.contains(event_name) {
+                return Ok(false);
+            }
+        }
+        if let Some(emitters) = &filter.emitters {
+            match emitter {
+                Emitter::Method(node_id, _) if emitters.contains(node_id) => {}
+                _ => return Ok(false),
+            }
+        }
+        if let Some(packages) = &filter.packages {
+            // Events are always defined by the package of their emitter's blueprint
+            let package_address = &Self::event_system_structure(commit, event_type_identifier)?
+                .package_type_reference
+                .full_type_id
+                .0;
+            if !packages.contains(package_address) {
+                return Ok(false);
+            }
+        }
+        if let Some(blueprints) = &filter.blueprints {
+            let blueprint_id = match emitter {
+                Emitter::Function(blueprint_id) => blueprint_id.clone(),
+                Emitter::Method(node_id, ModuleId::Main) => self
+                    .system_reader
+                    .get_blueprint_id(node_id, ModuleId::Main)?,
+                Emitter::Method(_, module_id) => module_id.static_blueprint().unwrap(),
+            };
+            if !blueprints.contains(&blueprint_id) {
+                return Ok(false);
+            }
+        }
+
+        Ok(filter
+            .predicates
+            .iter()
+            .all(|predicate| predicate(event_type_identifier, payload)))
+    }
+
+    fn to_programmatic_json(
+        &self,
+        commit: &CommitResult,
+        event_type_identifier: &EventTypeIdentifier,
+        payload: &[u8],
+    ) -> Result<serde_json::Value, EventStreamError> {
+        let FullyScopedTypeId(package_address, schema_hash, type_id) =
+            &Self::event_system_structure(commit, event_type_identifier)?
+                .package_type_reference
+                .full_type_id;
+        let schema = self
+            .system_reader
+            .get_schema(package_address.as_node_id(), schema_hash)?;
+
+        serde_json::to_value(
+            ScryptoRawPayload::new_from_valid_slice(payload).serializable(
+                SerializationParameters::WithSchema {
+                    mode: SerializationMode::Programmatic,
+                    custom_context: ScryptoValueDisplayContext::with_optional_bech32(
+                        self.address_encoder,
+                    ),
+                    schema: schema.v1(),
+                    type_id: *type_id,
+                    depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                },
+            ),
+        )
+        .map_err(|error| EventStreamError::SerializationError(error.to_string()))
+    }
+
+    fn event_system_structure<'c>(
+        commit: &'c CommitResult,
+        event_type_identifier: &EventTypeIdentifier,
+    ) -> Result<&'c EventSystemStructure, EventStreamError> {
+        commit
+            .system_structure
+            .event_system_structures
+            .get(event_type_identifier)
+            .ok_or_else(|| {
+                EventStreamError::MissingEventSystemStructure(event_type_identifier.clone())
+            })
+    }
+}
@@ -1,7 +1,11 @@
 mod accounter;
+#[cfg(feature = "event_stream")]
+mod event_stream;
 mod traverse;
 mod vault_finder;
 
 pub use accounter::*;
+#[cfg(feature = "event_stream")]
+pub use event_stream::*;
 pub use traverse::*;
 pub use vault_finder::*;
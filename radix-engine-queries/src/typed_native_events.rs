@@ -250,6 +250,20 @@ define_structure! {
             MultiResourcePoolWithdrawEvent,
             MultiResourcePoolDepositEvent,
         ],
+        WeightedPool => [
+            WeightedPoolContributionEvent,
+            WeightedPoolRedemptionEvent,
+            WeightedPoolSwapEvent,
+            WeightedPoolWithdrawEvent,
+            WeightedPoolDepositEvent,
+        ],
+        StableSwapPool => [
+            StableSwapPoolContributionEvent,
+            StableSwapPoolRedemptionEvent,
+            StableSwapPoolSwapEvent,
+            StableSwapPoolWithdrawEvent,
+            StableSwapPoolDepositEvent,
+        ],
     },
     Resource => {
         FungibleVault => [
@@ -317,6 +331,18 @@ type MultiResourcePoolRedemptionEvent = pool_events::multi_resource_pool::Redemp
 type MultiResourcePoolWithdrawEvent = pool_events::multi_resource_pool::WithdrawEvent;
 type MultiResourcePoolDepositEvent = pool_events::multi_resource_pool::DepositEvent;
 
+type WeightedPoolContributionEvent = pool_events::weighted_pool::ContributionEvent;
+type WeightedPoolRedemptionEvent = pool_events::weighted_pool::RedemptionEvent;
+type WeightedPoolSwapEvent = pool_events::weighted_pool::SwapEvent;
+type WeightedPoolWithdrawEvent = pool_events::weighted_pool::WithdrawEvent;
+type WeightedPoolDepositEvent = pool_events::weighted_pool::DepositEvent;
+
+type StableSwapPoolContributionEvent = pool_events::stable_swap_pool::ContributionEvent;
+type StableSwapPoolRedemptionEvent = pool_events::stable_swap_pool::RedemptionEvent;
+type StableSwapPoolSwapEvent = pool_events::stable_swap_pool::SwapEvent;
+type StableSwapPoolWithdrawEvent = pool_events::stable_swap_pool::WithdrawEvent;
+type StableSwapPoolDepositEvent = pool_events::stable_swap_pool::DepositEvent;
+
 type FungibleVaultLockFeeEvent = fungible_vault::LockFeeEvent;
 type FungibleVaultPayFeeEvent = fungible_vault::PayFeeEvent;
 type FungibleVaultWithdrawEvent = fungible_vault::WithdrawEvent;
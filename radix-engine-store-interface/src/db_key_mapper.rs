@@ -155,7 +155,7 @@ impl SpreadPrefixKeyMapper {
     ///
     /// Note: hashing will not be applied to [`FieldKey`] (which is a single byte, and hence does
     /// not create the risk of long common prefixes).
-    const HASHED_PREFIX_LENGTH: usize = 20;
+    pub const HASHED_PREFIX_LENGTH: usize = 20;
 
     /// Returns the given bytes prefixed by their known-length hash (see [`Self::HASHED_PREFIX_LENGTH`]).
     fn to_hash_prefixed(plain_bytes: &[u8]) -> Vec<u8> {
@@ -1,3 +1,4 @@
+use crate::hash_tree::proof::{SubstateProof, TierProof};
 use crate::hash_tree::tree_store::StaleTreePart;
 use crate::hash_tree::types::{LeafKey, LeafNode, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use jellyfish::JellyfishMerkleTree;
@@ -14,11 +15,12 @@ use utils::rust::collections::{index_map_new, IndexMap};
 use utils::rust::vec::Vec;
 
 pub mod hash_tree_facade;
+pub mod proof;
 pub mod tree_store;
 
-// The sources copied from Aptos (the `jellyfish` and `types` modules) contain support for
-// generating proofs, which we plan to use in near future. Hence, we do not delete that code, but
-// suppress warnings.
+// The sources copied from Aptos (the `jellyfish` and `types` modules) contain more proof-related
+// code (e.g. range proofs) than we currently use (see `get_substate_proof_at_version()`). Hence, we
+// do not delete that code, but suppress warnings.
 
 #[allow(dead_code)]
 mod jellyfish;
@@ -106,8 +108,70 @@ pub fn list_substate_hashes_at_version<S: ReadableTreeStore>(
     by_db_partition
 }
 
+/// Generates a proof of the value hash of the Substate under the given key (or of its absence) at
+/// the given version of the "3-Tier JMT" persisted within the given `TreeStore`.
+/// The proof consists of a proof within the ReNode-Tier tree and - only if the ReNode (and then its
+/// Partition) exists - proofs within the Partition-Tier and Substate-Tier trees.
+/// The result can be checked against the root hash of that version using `verify_substate_proof()`.
+///
+/// # Panics
+/// Panics if a root node for `node_root_version` does not exist.
+pub fn get_substate_proof_at_version<S: ReadableTreeStore>(
+    node_tier_store: &S,
+    node_root_version: Version,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+) -> SubstateProof {
+    let (partition_root_version, node_tier) =
+        get_tier_proof(node_tier_store, node_root_version, &partition_key.node_key);
+    let Some(partition_root_version) = partition_root_version else {
+        return SubstateProof {
+            node_tier,
+            partition_tier: None,
+            substate_tier: None,
+        };
+    };
+    let partition_tier_store =
+        NestedTreeStore::new(node_tier_store, partition_key.node_key.clone());
+    let partition_leaf_bytes = vec![partition_key.partition_num];
+    let (substate_root_version, partition_tier) = get_tier_proof(
+        &partition_tier_store,
+        partition_root_version,
+        &partition_leaf_bytes,
+    );
+    let Some(substate_root_version) = substate_root_version else {
+        return SubstateProof {
+            node_tier,
+            partition_tier: Some(partition_tier),
+            substate_tier: None,
+        };
+    };
+    let substate_tier_store = NestedTreeStore::new(&partition_tier_store, partition_leaf_bytes);
+    let (_, substate_tier) =
+        get_tier_proof(&substate_tier_store, substate_root_version, &sort_key.0);
+    SubstateProof {
+        node_tier,
+        partition_tier: Some(partition_tier),
+        substate_tier: Some(substate_tier),
+    }
+}
+
 // only internals below
 
+fn get_tier_proof<S: ReadableTreeStore>(
+    store: &S,
+    version: Version,
+    leaf_bytes: &[u8],
+) -> (Option<Version>, TierProof) {
+    let (leaf_node_data, proof) = JellyfishMerkleTree::new(store)
+        .get_with_proof(&LeafKey::new(leaf_bytes), version)
+        .expect("error while reading tree during proof generation");
+    (
+        leaf_node_data.map(|(_hash, last_hash_change_version, _version)| last_hash_change_version),
+        TierProof::from(proof),
+    )
+}
+
 fn list_leaves<S: ReadableTreeStore>(tree_store: &S, version: Version) -> Vec<LeafNode<Version>> {
     let mut leaves = Vec::new();
     list_leaves_recursively(tree_store, NodeKey::new_empty_path(version), &mut leaves);
@@ -0,0 +1,197 @@
+use super::types::{IteratedLeafKey, LeafKey, SparseMerkleInternalNode, SparseMerkleLeafNode};
+use super::types::{SparseMerkleProof, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use radix_engine_common::crypto::Hash;
+use radix_engine_store_interface::interface::{DbPartitionKey, DbSortKey};
+use sbor::rust::vec;
+use sbor::rust::vec::Vec;
+use sbor::*;
+
+/// A tier of the "3-Tier JMT" (see `put_at_next_version()` for the overall design).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Sbor)]
+pub enum StateTreeTier {
+    /// The top-level tree, keyed by `DbNodeKey` and holding Partition-Tier root hashes.
+    ReNode,
+    /// A tree of a single ReNode, keyed by `DbPartitionNum` and holding Substate-Tier root hashes.
+    Partition,
+    /// A tree of a single Partition, keyed by `DbSortKey` and holding Substate value hashes.
+    Substate,
+}
+
+/// A leaf encountered at the end of a proven path within a single tier's tree.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Sbor)]
+pub struct TierProofLeaf {
+    /// The full key of the leaf.
+    pub key: Vec<u8>,
+    /// The hash held by the leaf (i.e. a lower tier's root hash, or a Substate value's hash).
+    pub value_hash: Hash,
+}
+
+/// A Merkle proof of a single key's presence (or absence) within a single tier's tree.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Sbor)]
+pub struct TierProof {
+    /// The leaf found at the end of the key's path:
+    /// - If it has the requested key, this is an inclusion proof.
+    /// - If it has a different key, this is a non-inclusion proof (the leaf is the only one in
+    ///   the subtree where the requested key would have to live).
+    /// - If this is `None`, this is also a non-inclusion proof (the subtree is empty).
+    pub leaf: Option<TierProofLeaf>,
+    /// All the siblings' hashes along the path, ordered from the bottom level to the root level.
+    pub siblings: Vec<Hash>,
+}
+
+/// A proof of a value hash of a Substate (or of its absence) at a specific state version, spanning
+/// all tiers of the "3-Tier JMT".
+/// Lower tiers' proofs are only present if the higher tier proves the existence of the subtree.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Sbor)]
+pub struct SubstateProof {
+    pub node_tier: TierProof,
+    pub partition_tier: Option<TierProof>,
+    pub substate_tier: Option<TierProof>,
+}
+
+/// A reason of the [`SubstateProof`] verification failure.
+#[derive(Clone, PartialEq, Eq, Debug, Sbor)]
+pub enum ProofVerificationError {
+    /// The root hash computed from the tier's proof differs from the one expected at that tier.
+    RootHashMismatch {
+        tier: StateTreeTier,
+        expected: Hash,
+        actual: Hash,
+    },
+    /// The tier's proof has more siblings than the number of bits in the proven key.
+    TooManySiblings {
+        tier: StateTreeTier,
+        sibling_count: usize,
+        key_bit_count: usize,
+    },
+    /// The tier's non-inclusion proof contains a leaf which cannot prove the key's absence.
+    InvalidNonInclusionLeaf { tier: StateTreeTier },
+    /// The higher tier proves the existence of the subtree, but no proof for it was provided.
+    MissingTierProof { tier: StateTreeTier },
+    /// The higher tier proves the absence of the subtree, but a proof for it was provided.
+    UnexpectedTierProof { tier: StateTreeTier },
+}
+
+impl From<SparseMerkleProof> for TierProof {
+    fn from(proof: SparseMerkleProof) -> Self {
+        Self {
+            leaf: proof.leaf().map(|leaf| TierProofLeaf {
+                key: leaf.key().bytes.clone(),
+                value_hash: *leaf.value_hash(),
+            }),
+            siblings: proof.siblings().to_vec(),
+        }
+    }
+}
+
+impl TierProof {
+    /// Verifies this proof against the given root hash of the tier's tree, and returns the value
+    /// hash proven to be held under the given key (or `None` if the key's absence is proven).
+    pub fn verify(
+        &self,
+        tier: StateTreeTier,
+        expected_root_hash: &Hash,
+        key: &[u8],
+    ) -> Result<Option<Hash>, ProofVerificationError> {
+        let key = LeafKey::new(key);
+        let key_bit_count = key.bytes.len() * 8;
+        if self.siblings.len() > key_bit_count {
+            return Err(ProofVerificationError::TooManySiblings {
+                tier,
+                sibling_count: self.siblings.len(),
+                key_bit_count,
+            });
+        }
+
+        let leaf = self
+            .leaf
+            .as_ref()
+            .map(|leaf| SparseMerkleLeafNode::new(LeafKey::new(&leaf.key), leaf.value_hash));
+        let proven_value_hash = match &leaf {
+            Some(leaf) if leaf.key() == &key => Some(*leaf.value_hash()),
+            Some(leaf) => {
+                // The found leaf must be the only one in the subtree at which the path ends, and
+                // the requested key would have to belong to that very subtree.
+                let common_prefix_bit_count = key
+                    .iter_bits()
+                    .zip(leaf.key().iter_bits())
+                    .take_while(|(key_bit, leaf_key_bit)| key_bit == leaf_key_bit)
+                    .count();
+                if common_prefix_bit_count < self.siblings.len() {
+                    return Err(ProofVerificationError::InvalidNonInclusionLeaf { tier });
+                }
+                None
+            }
+            None => None,
+        };
+
+        let actual_root_hash = self
+            .siblings
+            .iter()
+            .zip(
+                key.iter_bits()
+                    .rev()
+                    .skip(key_bit_count - self.siblings.len()),
+            )
+            .fold(
+                leaf.map_or(SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash()),
+                |hash, (sibling_hash, bit)| {
+                    if bit {
+                        SparseMerkleInternalNode::new(*sibling_hash, hash).hash()
+                    } else {
+                        SparseMerkleInternalNode::new(hash, *sibling_hash).hash()
+                    }
+                },
+            );
+        if actual_root_hash != *expected_root_hash {
+            return Err(ProofVerificationError::RootHashMismatch {
+                tier,
+                expected: *expected_root_hash,
+                actual: actual_root_hash,
+            });
+        }
+
+        Ok(proven_value_hash)
+    }
+}
+
+/// Verifies the given proof against the given state root hash (i.e. the one returned by
+/// `put_at_next_version()` for the proven version), and returns the Substate value hash proven to
+/// be held under the given key (or `None` if the Substate's absence is proven).
+/// This does not require any access to the tree storage, so it can be performed by a light client.
+pub fn verify_substate_proof(
+    root_hash: &Hash,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+    proof: &SubstateProof,
+) -> Result<Option<Hash>, ProofVerificationError> {
+    let tiers = [
+        (
+            StateTreeTier::ReNode,
+            Some(&proof.node_tier),
+            partition_key.node_key.clone(),
+        ),
+        (
+            StateTreeTier::Partition,
+            proof.partition_tier.as_ref(),
+            vec![partition_key.partition_num],
+        ),
+        (
+            StateTreeTier::Substate,
+            proof.substate_tier.as_ref(),
+            sort_key.0.clone(),
+        ),
+    ];
+    let mut tier_root_hash = Some(*root_hash);
+    for (tier, tier_proof, key) in tiers {
+        tier_root_hash = match (tier_root_hash, tier_proof) {
+            (Some(expected_root_hash), Some(tier_proof)) => {
+                tier_proof.verify(tier, &expected_root_hash, &key)?
+            }
+            (Some(_), None) => return Err(ProofVerificationError::MissingTierProof { tier }),
+            (None, Some(_)) => return Err(ProofVerificationError::UnexpectedTierProof { tier }),
+            (None, None) => None,
+        };
+    }
+    Ok(tier_root_hash)
+}
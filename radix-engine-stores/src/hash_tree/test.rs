@@ -1,11 +1,14 @@
 use super::types::{Nibble, NibblePath, Version, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use crate::hash_tree::jellyfish::JellyfishMerkleTree;
-use crate::hash_tree::put_at_next_version;
+use crate::hash_tree::proof::{
+    verify_substate_proof, ProofVerificationError, StateTreeTier, SubstateProof,
+};
 use crate::hash_tree::tree_store::{
     SerializedInMemoryTreeStore, StaleTreePart, TreeChildEntry, TreeInternalNode, TreeLeafNode,
     TreeNode, TreeStore, TypedInMemoryTreeStore,
 };
 use crate::hash_tree::types::{LeafKey, NodeKey};
+use crate::hash_tree::{get_substate_proof_at_version, put_at_next_version};
 use itertools::Itertools;
 use radix_engine_common::crypto::{hash, Hash};
 use radix_engine_common::data::scrypto::{scrypto_decode, scrypto_encode};
@@ -418,6 +421,151 @@ fn serialized_keys_are_strictly_increasing() {
     assert!(min_next_key > max_previous_key);
 }
 
+#[test]
+fn proves_inclusion_of_substate_value_hash() {
+    let mut tester = HashTreeTester::new_empty();
+    tester.put_substate_changes(vec![
+        change(1, 6, 2, Some(30)),
+        change(1, 6, 5, Some(40)),
+        change(1, 7, 2, Some(50)),
+        change(3, 6, 2, Some(60)),
+    ]);
+    let root_hash = tester.put_substate_changes(vec![change(1, 6, 2, Some(70))]);
+    for (node_key_seed, partition_num, sort_key_seed, value_seed) in
+        [(1, 6, 2, 70), (1, 6, 5, 40), (1, 7, 2, 50), (3, 6, 2, 60)]
+    {
+        let (partition_key, sort_key) = substate_key(node_key_seed, partition_num, sort_key_seed);
+        let proof = tester.get_substate_proof(&partition_key, &sort_key);
+        assert_eq!(
+            verify_substate_proof(&root_hash, &partition_key, &sort_key, &proof),
+            Ok(Some(hash(from_seed(value_seed))))
+        );
+    }
+}
+
+#[test]
+fn proves_absence_of_substate_at_each_tier() {
+    let mut tester = HashTreeTester::new_empty();
+    let root_hash = tester.put_substate_changes(vec![
+        change(1, 6, 2, Some(30)),
+        change(1, 6, 5, Some(40)),
+        change(3, 7, 1, Some(50)),
+    ]);
+
+    // missing ReNode
+    let (partition_key, sort_key) = substate_key(2, 6, 2);
+    let proof = tester.get_substate_proof(&partition_key, &sort_key);
+    assert_eq!(proof.partition_tier, None);
+    assert_eq!(
+        verify_substate_proof(&root_hash, &partition_key, &sort_key, &proof),
+        Ok(None)
+    );
+
+    // missing Partition
+    let (partition_key, sort_key) = substate_key(1, 8, 2);
+    let proof = tester.get_substate_proof(&partition_key, &sort_key);
+    assert!(proof.partition_tier.is_some());
+    assert_eq!(proof.substate_tier, None);
+    assert_eq!(
+        verify_substate_proof(&root_hash, &partition_key, &sort_key, &proof),
+        Ok(None)
+    );
+
+    // missing Substate
+    let (partition_key, sort_key) = substate_key(1, 6, 4);
+    let proof = tester.get_substate_proof(&partition_key, &sort_key);
+    assert!(proof.substate_tier.is_some());
+    assert_eq!(
+        verify_substate_proof(&root_hash, &partition_key, &sort_key, &proof),
+        Ok(None)
+    );
+}
+
+#[test]
+fn proves_absence_of_deleted_substate() {
+    let mut tester = HashTreeTester::new_empty();
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(30)), change(1, 6, 5, Some(40))]);
+    let root_hash = tester.put_substate_changes(vec![change(1, 6, 2, None)]);
+    let (partition_key, sort_key) = substate_key(1, 6, 2);
+    let proof = tester.get_substate_proof(&partition_key, &sort_key);
+    assert_eq!(
+        verify_substate_proof(&root_hash, &partition_key, &sort_key, &proof),
+        Ok(None)
+    );
+}
+
+#[test]
+fn proves_absence_of_substate_in_empty_state() {
+    let mut tester = HashTreeTester::new_empty();
+    let root_hash = tester.put_substate_changes(vec![]);
+    let (partition_key, sort_key) = substate_key(1, 6, 2);
+    let proof = tester.get_substate_proof(&partition_key, &sort_key);
+    assert_eq!(
+        verify_substate_proof(&root_hash, &partition_key, &sort_key, &proof),
+        Ok(None)
+    );
+}
+
+#[test]
+fn proof_verification_fails_against_different_root_hash() {
+    let mut tester = HashTreeTester::new_empty();
+    let old_root_hash = tester.put_substate_changes(vec![change(1, 6, 2, Some(30))]);
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(40))]);
+    let (partition_key, sort_key) = substate_key(1, 6, 2);
+    let proof = tester.get_substate_proof(&partition_key, &sort_key);
+    assert!(matches!(
+        verify_substate_proof(&old_root_hash, &partition_key, &sort_key, &proof),
+        Err(ProofVerificationError::RootHashMismatch {
+            tier: StateTreeTier::ReNode,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn proof_verification_fails_when_tampered_with() {
+    let mut tester = HashTreeTester::new_empty();
+    let root_hash = tester.put_substate_changes(vec![
+        change(1, 6, 2, Some(30)),
+        change(1, 6, 5, Some(40)),
+        change(3, 7, 1, Some(50)),
+    ]);
+    let (partition_key, sort_key) = substate_key(1, 6, 2);
+    let proof = tester.get_substate_proof(&partition_key, &sort_key);
+
+    let mut tampered_value_proof = proof.clone();
+    tampered_value_proof
+        .substate_tier
+        .as_mut()
+        .unwrap()
+        .leaf
+        .as_mut()
+        .unwrap()
+        .value_hash = hash(from_seed(99));
+    assert!(matches!(
+        verify_substate_proof(&root_hash, &partition_key, &sort_key, &tampered_value_proof),
+        Err(ProofVerificationError::RootHashMismatch {
+            tier: StateTreeTier::Substate,
+            ..
+        })
+    ));
+
+    let mut truncated_proof = proof.clone();
+    truncated_proof.substate_tier = None;
+    assert_eq!(
+        verify_substate_proof(&root_hash, &partition_key, &sort_key, &truncated_proof),
+        Err(ProofVerificationError::MissingTierProof {
+            tier: StateTreeTier::Substate
+        })
+    );
+
+    // the same (valid) proof cannot be used for a different key
+    let (other_partition_key, other_sort_key) = substate_key(1, 6, 5);
+    assert!(
+        verify_substate_proof(&root_hash, &other_partition_key, &other_sort_key, &proof).is_err()
+    );
+}
+
 type SingleSubstateChange = (DbSubstateKey, DatabaseUpdate);
 
 fn change(
@@ -454,6 +602,10 @@ pub fn change_exact(
     )
 }
 
+fn substate_key(node_key_seed: u8, partition_num: u8, sort_key_seed: u8) -> DbSubstateKey {
+    change(node_key_seed, partition_num, sort_key_seed, None).0
+}
+
 fn from_seed(node_key_seed: u8) -> Vec<u8> {
     vec![node_key_seed; node_key_seed as usize]
 }
@@ -515,6 +667,19 @@ impl<S: TreeStore> HashTreeTester<S> {
         })
     }
 
+    pub fn get_substate_proof(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> SubstateProof {
+        get_substate_proof_at_version(
+            &self.tree_store,
+            self.current_version.unwrap(),
+            partition_key,
+            sort_key,
+        )
+    }
+
     fn apply_database_updates(&mut self, database_updates: &DatabaseUpdates) -> Hash {
         let next_version = self.current_version.unwrap_or(0) + 1;
         let current_version = self.current_version.replace(next_version);
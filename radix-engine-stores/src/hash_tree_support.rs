@@ -1,5 +1,8 @@
+use crate::hash_tree::proof::{SubstateProof, TierProof};
 use crate::hash_tree::tree_store::{TypedInMemoryTreeStore, Version};
-use crate::hash_tree::{list_substate_hashes_at_version, put_at_next_version};
+use crate::hash_tree::{
+    get_substate_proof_at_version, list_substate_hashes_at_version, put_at_next_version,
+};
 use radix_engine_common::prelude::*;
 use radix_engine_store_interface::interface::{
     CommittableSubstateDatabase, DatabaseUpdates, DbPartitionKey, DbSortKey, DbSubstateValue,
@@ -36,6 +39,30 @@ impl<D> HashTreeUpdatingDatabase<D> {
         list_substate_hashes_at_version(&mut self.tree_store, self.current_version)
     }
 
+    pub fn get_substate_proof(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> SubstateProof {
+        if self.current_version == 0 {
+            // An empty state (i.e. no root node persisted yet) is trivially proven by an empty proof
+            return SubstateProof {
+                node_tier: TierProof {
+                    leaf: None,
+                    siblings: vec![],
+                },
+                partition_tier: None,
+                substate_tier: None,
+            };
+        }
+        get_substate_proof_at_version(
+            &self.tree_store,
+            self.current_version,
+            partition_key,
+            sort_key,
+        )
+    }
+
     fn update_with(&mut self, db_updates: &DatabaseUpdates) {
         self.current_hash = put_at_next_version(
             &mut self.tree_store,
@@ -1,44 +1,296 @@
 use itertools::Itertools;
 use radix_engine_common::constants::MAX_SUBSTATE_KEY_SIZE;
+use radix_engine_common::data::scrypto::{scrypto_decode, scrypto_encode};
+use radix_engine_common::types::{EntityType, NodeId};
+use radix_engine_derive::ScryptoSbor;
+use radix_engine_store_interface::db_key_mapper::{DatabaseKeyMapper, SpreadPrefixKeyMapper};
 use radix_engine_store_interface::interface::*;
 pub use rocksdb::{BlockBasedOptions, LogLevel, Options};
 use rocksdb::{
-    ColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode,
-    SingleThreaded, DB,
+    ColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode, ReadOptions,
+    SingleThreaded, SliceTransform, WriteBatch, DB,
 };
 use sbor::rust::prelude::*;
 use std::path::PathBuf;
 use utils::copy_u8_array;
 
+/// The column family holding all partitions not assigned to a dedicated column family.
+/// Its name dates back to the times when it was the only column family (needed only for the
+/// delete range API), and is kept for compatibility with existing databases.
+const DEFAULT_SUBSTATES_CF: &str = "the_only";
+
+/// The key (within rocksdb's own "default" column family) of the encoded [`RocksdbLayout`].
+pub(crate) const LAYOUT_KEY: &[u8] = b"layout";
+
+/// The key (within rocksdb's own "default" column family) of the encoded [`RocksdbLayout`] which
+/// the database is being migrated to, present only while a migration is in progress.
+pub(crate) const MIGRATION_TARGET_LAYOUT_KEY: &[u8] = b"migration_target_layout";
+
+const BLOOM_FILTER_BITS_PER_KEY: f64 = 10.0;
+const MEMTABLE_PREFIX_BLOOM_RATIO: f64 = 0.1;
+const MIGRATION_BATCH_SIZE: usize = 10_000;
+
+/// Selects database partitions by the entity type of their node and/or their partition number.
+/// An empty list matches everything.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct PartitionSelector {
+    pub entity_types: Vec<EntityType>,
+    pub partition_nums: Vec<DbPartitionNum>,
+}
+
+impl PartitionSelector {
+    pub fn matches(&self, partition_key: &DbPartitionKey) -> bool {
+        let entity_type_matches = self.entity_types.is_empty()
+            || entity_type_of(&partition_key.node_key)
+                .map(|entity_type| self.entity_types.contains(&entity_type))
+                .unwrap_or(false);
+        let partition_num_matches = self.partition_nums.is_empty()
+            || self.partition_nums.contains(&partition_key.partition_num);
+        entity_type_matches && partition_num_matches
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct DedicatedColumnFamily {
+    pub name: String,
+    pub selector: PartitionSelector,
+}
+
+/// An assignment of database partitions to column families.
+///
+/// A partition is stored in the first dedicated column family whose selector matches it, or in
+/// the default column family otherwise. Each substate column family uses a prefix extractor
+/// (capturing the whole partition key) and bloom filters, which serve both the point lookups of
+/// `get_substate()` and the partition scans of `list_entries_from()`.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct RocksdbLayout {
+    pub dedicated_column_families: Vec<DedicatedColumnFamily>,
+}
+
+impl RocksdbLayout {
+    /// The layout of databases created before the layout was configurable.
+    pub fn single_column_family() -> Self {
+        Self {
+            dedicated_column_families: vec![],
+        }
+    }
+
+    /// A layout with dedicated column families for the most frequently read partitions.
+    pub fn standard() -> Self {
+        Self {
+            dedicated_column_families: vec![
+                DedicatedColumnFamily {
+                    name: "type_info".to_string(),
+                    selector: PartitionSelector {
+                        entity_types: vec![],
+                        // `TYPE_INFO_FIELD_PARTITION`
+                        partition_nums: vec![0],
+                    },
+                },
+                DedicatedColumnFamily {
+                    name: "vaults".to_string(),
+                    selector: PartitionSelector {
+                        entity_types: vec![
+                            EntityType::InternalFungibleVault,
+                            EntityType::InternalNonFungibleVault,
+                        ],
+                        partition_nums: vec![],
+                    },
+                },
+                DedicatedColumnFamily {
+                    name: "kv_entries".to_string(),
+                    selector: PartitionSelector {
+                        entity_types: vec![EntityType::InternalKeyValueStore],
+                        partition_nums: vec![],
+                    },
+                },
+            ],
+        }
+    }
+
+    pub fn column_family_for(&self, partition_key: &DbPartitionKey) -> &str {
+        self.dedicated_column_family_for(partition_key)
+            .unwrap_or(DEFAULT_SUBSTATES_CF)
+    }
+
+    pub fn column_family_names(&self) -> Vec<&str> {
+        self.column_family_names_with_default(DEFAULT_SUBSTATES_CF)
+    }
+
+    /// Returns the name of the dedicated column family of the given partition, if any.
+    pub fn dedicated_column_family_for(&self, partition_key: &DbPartitionKey) -> Option<&str> {
+        self.dedicated_column_families
+            .iter()
+            .find(|column_family| column_family.selector.matches(partition_key))
+            .map(|column_family| column_family.name.as_str())
+    }
+
+    /// Returns the names of all substate column families, given the name of the default one.
+    pub fn column_family_names_with_default<'a>(&'a self, default_name: &'a str) -> Vec<&'a str> {
+        let mut names = vec![default_name];
+        names.extend(
+            self.dedicated_column_families
+                .iter()
+                .map(|column_family| column_family.name.as_str()),
+        );
+        names
+    }
+}
+
 pub struct RocksdbSubstateStore {
     db: DBWithThreadMode<SingleThreaded>,
+    layout: RocksdbLayout,
 }
 
 impl RocksdbSubstateStore {
-    // Technically we don't need CFs here at all; however, delete range API is only available for CF
-    const THE_ONLY_CF: &'static str = "the_only";
-
     pub fn standard(root: PathBuf) -> Self {
         Self::with_options(&Options::default(), root)
     }
+
+    /// Opens the database using the layout it was created with (i.e. [`RocksdbLayout::standard()`]
+    /// for a new database, and [`RocksdbLayout::single_column_family()`] for a database created
+    /// before the layout was configurable).
+    ///
+    /// An interrupted migration (see [`Self::migrate_layout()`]) is resumed first.
     pub fn with_options(options: &Options, root: PathBuf) -> Self {
+        let layout = match Self::read_layouts(options, &root) {
+            Some((_, Some(target_layout))) => {
+                return Self::migrate_layout(options, root, target_layout)
+            }
+            Some((layout, None)) => layout,
+            None => RocksdbLayout::standard(),
+        };
+        Self::with_layout(options, root, layout)
+    }
+
+    /// Opens the database using the given layout, resuming an interrupted migration to it.
+    ///
+    /// # Panics
+    /// If the existing database uses (or is being migrated to) a different layout - see
+    /// [`Self::migrate_layout()`].
+    pub fn with_layout(options: &Options, root: PathBuf, layout: RocksdbLayout) -> Self {
+        match Self::read_layouts(options, &root) {
+            Some((_, Some(target_layout))) => {
+                assert_eq!(
+                    target_layout, layout,
+                    "The database is being migrated to a different layout"
+                );
+                return Self::migrate_layout(options, root, layout);
+            }
+            Some((existing_layout, None)) => {
+                assert_eq!(
+                    existing_layout, layout,
+                    "The database uses a different layout and has to be migrated first"
+                );
+            }
+            None => {}
+        }
+        let db = Self::open(options, &root, layout.column_family_names());
+        db.put(LAYOUT_KEY, scrypto_encode(&layout).unwrap())
+            .expect("IO error");
+        Self { db, layout }
+    }
+
+    /// Moves all substates of the database into the column families of the given layout (dropping
+    /// the column families which are no longer used), and opens the database.
+    ///
+    /// The target layout is recorded before any substate is moved, so that a migration which was
+    /// interrupted (e.g. by a crash) is resumed by the next open, or by migrating again.
+    pub fn migrate_layout(options: &Options, root: PathBuf, layout: RocksdbLayout) -> Self {
+        let (existing_layout, interrupted_target_layout) = match Self::read_layouts(options, &root)
+        {
+            Some(layouts) => layouts,
+            None => return Self::with_layout(options, root, layout),
+        };
+
+        // The substates may be in the column families of both layouts of an interrupted migration
+        let mut source_names = existing_layout.column_family_names();
+        if let Some(interrupted_target_layout) = &interrupted_target_layout {
+            for name in interrupted_target_layout.column_family_names() {
+                if !source_names.contains(&name) {
+                    source_names.push(name);
+                }
+            }
+        }
+        let mut column_family_names = source_names.clone();
+        for name in layout.column_family_names() {
+            if !column_family_names.contains(&name) {
+                column_family_names.push(name);
+            }
+        }
+        let mut db = Self::open(options, &root, column_family_names);
+        db.put(
+            MIGRATION_TARGET_LAYOUT_KEY,
+            scrypto_encode(&layout).unwrap(),
+        )
+        .expect("IO error");
+
+        move_substates(&db, &source_names, |partition_key| {
+            layout.column_family_for(partition_key)
+        });
+
+        let layout_names = layout.column_family_names();
+        for name in source_names {
+            if !layout_names.contains(&name) {
+                db.drop_cf(name).expect("IO error");
+            }
+        }
+        let mut batch = WriteBatch::default();
+        batch.put(LAYOUT_KEY, scrypto_encode(&layout).unwrap());
+        batch.delete(MIGRATION_TARGET_LAYOUT_KEY);
+        db.write(batch).expect("IO error");
+        Self { db, layout }
+    }
+
+    pub fn layout(&self) -> &RocksdbLayout {
+        &self.layout
+    }
+
+    /// Reads the layout of an existing database, and the layout it is being migrated to (if a
+    /// migration was interrupted), or returns [`None`] if there is no database.
+    fn read_layouts(
+        options: &Options,
+        root: &PathBuf,
+    ) -> Option<(RocksdbLayout, Option<RocksdbLayout>)> {
+        let column_family_names = DB::list_cf(options, root.as_path()).ok()?;
+        let db = DB::open_cf_for_read_only(options, root.as_path(), &column_family_names, false)
+            .expect("IO error");
+        let layout = match db.get(LAYOUT_KEY).expect("IO error") {
+            Some(bytes) => scrypto_decode(&bytes).expect("Failed to decode the database layout"),
+            None => RocksdbLayout::single_column_family(),
+        };
+        let target_layout = db
+            .get(MIGRATION_TARGET_LAYOUT_KEY)
+            .expect("IO error")
+            .map(|bytes| {
+                scrypto_decode(&bytes).expect("Failed to decode the migration target layout")
+            });
+        Some((layout, target_layout))
+    }
+
+    fn open(
+        options: &Options,
+        root: &PathBuf,
+        column_family_names: Vec<&str>,
+    ) -> DBWithThreadMode<SingleThreaded> {
         let mut options = options.clone();
         options.create_if_missing(true);
         options.create_missing_column_families(true);
-        let db = DB::open_cf_descriptors(
+        DB::open_cf_descriptors(
             &options,
             root.as_path(),
-            vec![ColumnFamilyDescriptor::new(
-                Self::THE_ONLY_CF,
-                Options::default(),
-            )],
+            column_family_names
+                .into_iter()
+                .map(|name| ColumnFamilyDescriptor::new(name, substates_cf_options()))
+                .collect::<Vec<_>>(),
         )
-        .unwrap();
-        Self { db }
+        .unwrap()
     }
 
-    fn cf(&self) -> &ColumnFamily {
-        self.db.cf_handle(Self::THE_ONLY_CF).unwrap()
+    fn cf(&self, partition_key: &DbPartitionKey) -> &ColumnFamily {
+        self.db
+            .cf_handle(self.layout.column_family_for(partition_key))
+            .unwrap()
     }
 }
 
@@ -49,7 +301,9 @@ impl SubstateDatabase for RocksdbSubstateStore {
         sort_key: &DbSortKey,
     ) -> Option<DbSubstateValue> {
         let key_bytes = encode_to_rocksdb_bytes(partition_key, sort_key);
-        self.db.get_cf(self.cf(), &key_bytes).expect("IO Error")
+        self.db
+            .get_cf(self.cf(partition_key), &key_bytes)
+            .expect("IO Error")
     }
 
     fn list_entries_from(
@@ -57,14 +311,19 @@ impl SubstateDatabase for RocksdbSubstateStore {
         partition_key: &DbPartitionKey,
         from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        let cf = self.cf(partition_key);
         let partition_key = partition_key.clone();
         let empty_sort_key = DbSortKey(vec![]);
         let from_sort_key = from_sort_key.unwrap_or(&empty_sort_key);
         let start_key_bytes = encode_to_rocksdb_bytes(&partition_key, from_sort_key);
+        // All keys of a partition share the extracted prefix, so the prefix bloom filters apply
+        let mut read_options = ReadOptions::default();
+        read_options.set_prefix_same_as_start(true);
         let iter = self
             .db
-            .iterator_cf(
-                self.cf(),
+            .iterator_cf_opt(
+                cf,
+                read_options,
                 IteratorMode::From(&start_key_bytes, Direction::Forward),
             )
             .map(|kv| {
@@ -87,15 +346,16 @@ impl CommittableSubstateDatabase for RocksdbSubstateStore {
                     node_key: node_key.clone(),
                     partition_num: *partition_num,
                 };
+                let cf = self.cf(&partition_key);
                 match partition_updates {
                     PartitionDatabaseUpdates::Delta { substate_updates } => {
                         for (sort_key, update) in substate_updates {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
                             match update {
                                 DatabaseUpdate::Set(value_bytes) => {
-                                    self.db.put_cf(self.cf(), key_bytes, value_bytes)
+                                    self.db.put_cf(cf, key_bytes, value_bytes)
                                 }
-                                DatabaseUpdate::Delete => self.db.delete_cf(self.cf(), key_bytes),
+                                DatabaseUpdate::Delete => self.db.delete_cf(cf, key_bytes),
                             }
                             .expect("IO error");
                         }
@@ -103,11 +363,11 @@ impl CommittableSubstateDatabase for RocksdbSubstateStore {
                     PartitionDatabaseUpdates::Reset {
                         new_substate_values,
                     } => {
-                        // Note: a plain `delete_range()` is missing from rocksdb's API, and
-                        // (at the moment of writing) this is the only reason of having CF.
+                        // Note: a plain `delete_range()` is missing from rocksdb's API, hence the
+                        // CF-based one.
                         self.db
                             .delete_range_cf(
-                                self.cf(),
+                                cf,
                                 encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
                                 encode_to_rocksdb_bytes(
                                     &partition_key,
@@ -118,7 +378,7 @@ impl CommittableSubstateDatabase for RocksdbSubstateStore {
                         for (sort_key, value_bytes) in new_substate_values {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
                             self.db
-                                .put_cf(self.cf(), key_bytes, value_bytes)
+                                .put_cf(cf, key_bytes, value_bytes)
                                 .expect("IO error");
                         }
                     }
@@ -129,22 +389,104 @@ impl CommittableSubstateDatabase for RocksdbSubstateStore {
 }
 
 impl ListableSubstateDatabase for RocksdbSubstateStore {
+    /// Lists the partition keys in the order of their encoding, as a single column family would.
     fn list_partition_keys(&self) -> Box<dyn Iterator<Item = DbPartitionKey> + '_> {
         Box::new(
-            self.db
-                .iterator_cf(self.cf(), IteratorMode::Start)
-                .map(|kv| {
-                    let (iter_key_bytes, _) = kv.as_ref().unwrap();
-                    let (iter_key, _) = decode_from_rocksdb_bytes(iter_key_bytes);
-                    iter_key
+            self.layout
+                .column_family_names()
+                .into_iter()
+                .map(move |name| {
+                    self.db
+                        .iterator_cf_opt(
+                            self.db.cf_handle(name).unwrap(),
+                            total_order_read_options(),
+                            IteratorMode::Start,
+                        )
+                        .map(|kv| {
+                            let (iter_key_bytes, _) = kv.as_ref().unwrap();
+                            partition_key_prefix(iter_key_bytes).to_vec()
+                        })
                 })
-                // Rocksdb iterator returns sorted entries, so ok to to eliminate
-                // duplicates with dedup()
-                .dedup(),
+                // Each column family returns sorted keys, and holds whole partitions, so the
+                // merged keys are sorted too, and ok to eliminate duplicates with dedup()
+                .kmerge()
+                .dedup()
+                .map(|partition_key_bytes| decode_from_rocksdb_bytes(&partition_key_bytes).0),
         )
     }
 }
 
+/// The options of a substate column family.
+pub(crate) fn substates_cf_options() -> Options {
+    let mut block_options = BlockBasedOptions::default();
+    block_options.set_bloom_filter(BLOOM_FILTER_BITS_PER_KEY, false);
+    block_options.set_whole_key_filtering(true);
+
+    let mut options = Options::default();
+    options.set_prefix_extractor(SliceTransform::create(
+        "partition_key",
+        partition_key_prefix,
+        Some(is_in_partition_key_prefix_domain),
+    ));
+    options.set_memtable_prefix_bloom_ratio(MEMTABLE_PREFIX_BLOOM_RATIO);
+    options.set_block_based_table_factory(&block_options);
+    options
+}
+
+/// Read options for iterating over a column family regardless of the partition key prefix.
+pub(crate) fn total_order_read_options() -> ReadOptions {
+    let mut read_options = ReadOptions::default();
+    read_options.set_total_order_seek(true);
+    read_options
+}
+
+/// Moves the substates of the given source column families into the column families assigned to
+/// their partitions, in batches.
+pub(crate) fn move_substates<'a>(
+    db: &DBWithThreadMode<SingleThreaded>,
+    source_names: &[&str],
+    column_family_for: impl Fn(&DbPartitionKey) -> &'a str,
+) {
+    for source_name in source_names.iter().copied() {
+        let source_cf = db.cf_handle(source_name).unwrap();
+        let mut batch = WriteBatch::default();
+        for kv in db.iterator_cf_opt(source_cf, total_order_read_options(), IteratorMode::Start) {
+            let (key_bytes, value) = kv.expect("IO error");
+            let (partition_key, _) = decode_from_rocksdb_bytes(&key_bytes);
+            let target_name = column_family_for(&partition_key);
+            if target_name != source_name {
+                batch.put_cf(db.cf_handle(target_name).unwrap(), &key_bytes, &value);
+                batch.delete_cf(source_cf, &key_bytes);
+            }
+            if batch.len() >= MIGRATION_BATCH_SIZE {
+                db.write(std::mem::take(&mut batch)).expect("IO error");
+            }
+        }
+        db.write(batch).expect("IO error");
+    }
+}
+
+/// Returns the entity type of the given node key, assuming the [`SpreadPrefixKeyMapper`].
+fn entity_type_of(node_key: &DbNodeKey) -> Option<EntityType> {
+    if node_key.len() != SpreadPrefixKeyMapper::HASHED_PREFIX_LENGTH + NodeId::LENGTH {
+        return None;
+    }
+    SpreadPrefixKeyMapper::from_db_node_key(node_key).entity_type()
+}
+
+/// The length of the encoded partition key part of the given key (see [`encode_to_rocksdb_bytes`]).
+fn partition_key_prefix_length(key: &[u8]) -> usize {
+    4 + usize::try_from(u32::from_be_bytes(copy_u8_array(&key[..4]))).unwrap() + 1
+}
+
+pub(crate) fn partition_key_prefix(key: &[u8]) -> &[u8] {
+    &key[..partition_key_prefix_length(key)]
+}
+
+fn is_in_partition_key_prefix_domain(key: &[u8]) -> bool {
+    key.len() >= 4 && key.len() >= partition_key_prefix_length(key)
+}
+
 pub fn encode_to_rocksdb_bytes(partition_key: &DbPartitionKey, sort_key: &DbSortKey) -> Vec<u8> {
     let mut buffer = Vec::new();
     buffer.extend(
@@ -174,8 +516,8 @@ pub fn decode_from_rocksdb_bytes(buffer: &[u8]) -> DbSubstateKey {
 mod tests {
     use super::*;
     use radix_engine_store_interface::interface::{
-        CommittableSubstateDatabase, DatabaseUpdates, DbSortKey, NodeDatabaseUpdates,
-        PartitionDatabaseUpdates,
+        CommittableSubstateDatabase, DatabaseUpdate, DatabaseUpdates, DbSortKey,
+        NodeDatabaseUpdates, PartitionDatabaseUpdates,
     };
 
     #[cfg(not(feature = "alloc"))]
@@ -224,4 +566,168 @@ mod tests {
         });
         assert_eq!(db.list_partition_keys().count(), 8);
     }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_layout_migration() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let updates = DatabaseUpdates {
+            node_updates: indexmap! {
+                vec![1] => NodeDatabaseUpdates {
+                    partition_updates: indexmap! {
+                        0 => PartitionDatabaseUpdates::Delta {
+                            substate_updates: indexmap! {
+                                DbSortKey(vec![2]) => DatabaseUpdate::Set(vec![3])
+                            }
+                        },
+                        64 => PartitionDatabaseUpdates::Delta {
+                            substate_updates: indexmap! {
+                                DbSortKey(vec![4]) => DatabaseUpdate::Set(vec![5]),
+                                DbSortKey(vec![6]) => DatabaseUpdate::Set(vec![7])
+                            }
+                        }
+                    }
+                }
+            },
+        };
+        let type_info_partition = DbPartitionKey {
+            node_key: vec![1],
+            partition_num: 0,
+        };
+        let main_partition = DbPartitionKey {
+            node_key: vec![1],
+            partition_num: 64,
+        };
+
+        {
+            let mut db = RocksdbSubstateStore::with_layout(
+                &Options::default(),
+                root.clone(),
+                RocksdbLayout::single_column_family(),
+            );
+            db.commit(&updates);
+        }
+        // The layout of an existing database is kept
+        assert_eq!(
+            RocksdbSubstateStore::standard(root.clone()).layout(),
+            &RocksdbLayout::single_column_family()
+        );
+
+        let db = RocksdbSubstateStore::migrate_layout(
+            &Options::default(),
+            root.clone(),
+            RocksdbLayout::standard(),
+        );
+        assert_eq!(
+            db.get_substate(&type_info_partition, &DbSortKey(vec![2])),
+            Some(vec![3])
+        );
+        assert_eq!(
+            db.list_entries(&main_partition).collect::<Vec<_>>(),
+            vec![(DbSortKey(vec![4]), vec![5]), (DbSortKey(vec![6]), vec![7])]
+        );
+        assert_eq!(db.list_partition_keys().count(), 2);
+        drop(db);
+
+        assert_eq!(
+            RocksdbSubstateStore::standard(root).layout(),
+            &RocksdbLayout::standard()
+        );
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_interrupted_layout_migration_is_resumed_on_open() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let updates = DatabaseUpdates {
+            node_updates: indexmap! {
+                vec![1] => NodeDatabaseUpdates {
+                    partition_updates: indexmap! {
+                        0 => PartitionDatabaseUpdates::Delta {
+                            substate_updates: indexmap! {
+                                DbSortKey(vec![2]) => DatabaseUpdate::Set(vec![3])
+                            }
+                        },
+                        64 => PartitionDatabaseUpdates::Delta {
+                            substate_updates: indexmap! {
+                                DbSortKey(vec![4]) => DatabaseUpdate::Set(vec![5])
+                            }
+                        }
+                    }
+                },
+                vec![0] => NodeDatabaseUpdates {
+                    partition_updates: indexmap! {
+                        64 => PartitionDatabaseUpdates::Delta {
+                            substate_updates: indexmap! {
+                                DbSortKey(vec![6]) => DatabaseUpdate::Set(vec![7])
+                            }
+                        }
+                    }
+                }
+            },
+        };
+
+        {
+            let mut db = RocksdbSubstateStore::with_layout(
+                &Options::default(),
+                root.clone(),
+                RocksdbLayout::single_column_family(),
+            );
+            db.commit(&updates);
+            // A migration interrupted right after it started
+            db.db
+                .put(
+                    MIGRATION_TARGET_LAYOUT_KEY,
+                    scrypto_encode(&RocksdbLayout::standard()).unwrap(),
+                )
+                .unwrap();
+        }
+
+        let db = RocksdbSubstateStore::standard(root);
+        assert_eq!(db.layout(), &RocksdbLayout::standard());
+        assert_eq!(
+            db.get_substate(
+                &DbPartitionKey {
+                    node_key: vec![1],
+                    partition_num: 0,
+                },
+                &DbSortKey(vec![2])
+            ),
+            Some(vec![3])
+        );
+        // The partitions are listed in order, across column families
+        assert_eq!(
+            db.list_partition_keys().collect::<Vec<_>>(),
+            vec![
+                DbPartitionKey {
+                    node_key: vec![0],
+                    partition_num: 64,
+                },
+                DbPartitionKey {
+                    node_key: vec![1],
+                    partition_num: 0,
+                },
+                DbPartitionKey {
+                    node_key: vec![1],
+                    partition_num: 64,
+                },
+            ]
+        );
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic]
+    fn test_opening_with_different_layout_should_panic() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        drop(RocksdbSubstateStore::standard(root.clone()));
+        RocksdbSubstateStore::with_layout(
+            &Options::default(),
+            root,
+            RocksdbLayout::single_column_family(),
+        );
+    }
 }
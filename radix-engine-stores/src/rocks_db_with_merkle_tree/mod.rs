@@ -9,23 +9,30 @@ use radix_engine_derive::ScryptoSbor;
 use radix_engine_store_interface::interface::*;
 pub use rocksdb::{BlockBasedOptions, LogLevel, Options};
 use rocksdb::{
-    ColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode,
+    ColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode, ReadOptions,
     SingleThreaded, WriteBatch, DB,
 };
 use sbor::prelude::*;
 use std::path::PathBuf;
 
 mod state_tree;
-use crate::rocks_db::{decode_from_rocksdb_bytes, encode_to_rocksdb_bytes};
+use crate::rocks_db::{
+    decode_from_rocksdb_bytes, encode_to_rocksdb_bytes, move_substates, partition_key_prefix,
+    substates_cf_options, total_order_read_options, RocksdbLayout, LAYOUT_KEY,
+    MIGRATION_TARGET_LAYOUT_KEY,
+};
 pub use state_tree::*;
 
 const META_CF: &str = "meta";
+/// The column family holding all partitions not assigned to a dedicated column family by the
+/// [`RocksdbLayout`].
 const SUBSTATES_CF: &str = "substates";
 const MERKLE_NODES_CF: &str = "merkle_nodes";
 const STALE_MERKLE_TREE_PARTS_CF: &str = "stale_merkle_tree_parts";
 
 pub struct RocksDBWithMerkleTreeSubstateStore {
     db: DBWithThreadMode<SingleThreaded>,
+    layout: RocksdbLayout,
     pruning_enabled: bool,
 }
 
@@ -46,31 +53,191 @@ impl RocksDBWithMerkleTreeSubstateStore {
         Self::with_options(&options, root, true)
     }
 
+    /// Opens the database using the substate column family layout it was created with (i.e.
+    /// [`RocksdbLayout::standard()`] for a new database, and
+    /// [`RocksdbLayout::single_column_family()`] for a database created before the layout was
+    /// configurable), resuming an interrupted migration first - see [`RocksdbSubstateStore`].
+    ///
+    /// [`RocksdbSubstateStore`]: crate::rocks_db::RocksdbSubstateStore
     pub fn with_options(options: &Options, root: PathBuf, pruning_enabled: bool) -> Self {
-        let db = DB::open_cf_descriptors(
+        let layout = match Self::read_layouts(options, &root) {
+            Some((_, Some(target_layout))) => {
+                return Self::migrate_layout(options, root, pruning_enabled, target_layout)
+            }
+            Some((layout, None)) => layout,
+            None => RocksdbLayout::standard(),
+        };
+        Self::with_layout(options, root, pruning_enabled, layout)
+    }
+
+    /// Opens the database using the given substate column family layout, resuming an interrupted
+    /// migration to it.
+    ///
+    /// # Panics
+    /// If the existing database uses (or is being migrated to) a different layout - see
+    /// [`Self::migrate_layout()`].
+    pub fn with_layout(
+        options: &Options,
+        root: PathBuf,
+        pruning_enabled: bool,
+        layout: RocksdbLayout,
+    ) -> Self {
+        match Self::read_layouts(options, &root) {
+            Some((_, Some(target_layout))) => {
+                assert_eq!(
+                    target_layout, layout,
+                    "The database is being migrated to a different layout"
+                );
+                return Self::migrate_layout(options, root, pruning_enabled, layout);
+            }
+            Some((existing_layout, None)) => {
+                assert_eq!(
+                    existing_layout, layout,
+                    "The database uses a different layout and has to be migrated first"
+                );
+            }
+            None => {}
+        }
+        let db = Self::open(
             options,
-            root.as_path(),
-            [
-                META_CF,
-                SUBSTATES_CF,
-                MERKLE_NODES_CF,
-                STALE_MERKLE_TREE_PARTS_CF,
-            ]
-            .into_iter()
-            .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
-            .collect::<Vec<_>>(),
+            &root,
+            layout.column_family_names_with_default(SUBSTATES_CF),
+        );
+        db.put_cf(
+            db.cf_handle(META_CF).unwrap(),
+            LAYOUT_KEY,
+            scrypto_encode(&layout).unwrap(),
+        )
+        .expect("IO error");
+        Self {
+            db,
+            layout,
+            pruning_enabled,
+        }
+    }
+
+    /// Moves all substates of the database into the column families of the given layout (dropping
+    /// the column families which are no longer used), and opens the database.
+    ///
+    /// The merkle tree is not affected, as it does not depend on where the substates are stored.
+    pub fn migrate_layout(
+        options: &Options,
+        root: PathBuf,
+        pruning_enabled: bool,
+        layout: RocksdbLayout,
+    ) -> Self {
+        let (existing_layout, interrupted_target_layout) = match Self::read_layouts(options, &root)
+        {
+            Some(layouts) => layouts,
+            None => return Self::with_layout(options, root, pruning_enabled, layout),
+        };
+
+        // The substates may be in the column families of both layouts of an interrupted migration
+        let mut source_names = existing_layout.column_family_names_with_default(SUBSTATES_CF);
+        if let Some(interrupted_target_layout) = &interrupted_target_layout {
+            for name in interrupted_target_layout.column_family_names_with_default(SUBSTATES_CF) {
+                if !source_names.contains(&name) {
+                    source_names.push(name);
+                }
+            }
+        }
+        let mut substate_names = source_names.clone();
+        for name in layout.column_family_names_with_default(SUBSTATES_CF) {
+            if !substate_names.contains(&name) {
+                substate_names.push(name);
+            }
+        }
+        let mut db = Self::open(options, &root, substate_names);
+        db.put_cf(
+            db.cf_handle(META_CF).unwrap(),
+            MIGRATION_TARGET_LAYOUT_KEY,
+            scrypto_encode(&layout).unwrap(),
         )
-        .unwrap();
+        .expect("IO error");
+
+        move_substates(&db, &source_names, |partition_key| {
+            layout
+                .dedicated_column_family_for(partition_key)
+                .unwrap_or(SUBSTATES_CF)
+        });
+
+        let layout_names = layout.column_family_names_with_default(SUBSTATES_CF);
+        for name in source_names {
+            if !layout_names.contains(&name) {
+                db.drop_cf(name).expect("IO error");
+            }
+        }
+        let meta_cf = db.cf_handle(META_CF).unwrap();
+        let mut batch = WriteBatch::default();
+        batch.put_cf(meta_cf, LAYOUT_KEY, scrypto_encode(&layout).unwrap());
+        batch.delete_cf(meta_cf, MIGRATION_TARGET_LAYOUT_KEY);
+        db.write(batch).expect("IO error");
         Self {
             db,
+            layout,
             pruning_enabled,
         }
     }
 
+    pub fn layout(&self) -> &RocksdbLayout {
+        &self.layout
+    }
+
+    /// Reads the layout of an existing database, and the layout it is being migrated to (if a
+    /// migration was interrupted), or returns [`None`] if there is no database.
+    fn read_layouts(
+        options: &Options,
+        root: &PathBuf,
+    ) -> Option<(RocksdbLayout, Option<RocksdbLayout>)> {
+        let column_family_names = DB::list_cf(options, root.as_path()).ok()?;
+        let db = DB::open_cf_for_read_only(options, root.as_path(), &column_family_names, false)
+            .expect("IO error");
+        let read_meta = |key: &[u8]| match db.cf_handle(META_CF) {
+            Some(meta_cf) => db.get_cf(meta_cf, key).expect("IO error"),
+            None => None,
+        };
+        let layout = match read_meta(LAYOUT_KEY) {
+            Some(bytes) => scrypto_decode(&bytes).expect("Failed to decode the database layout"),
+            None => RocksdbLayout::single_column_family(),
+        };
+        let target_layout = read_meta(MIGRATION_TARGET_LAYOUT_KEY).map(|bytes| {
+            scrypto_decode(&bytes).expect("Failed to decode the migration target layout")
+        });
+        Some((layout, target_layout))
+    }
+
+    fn open(
+        options: &Options,
+        root: &PathBuf,
+        substate_column_family_names: Vec<&str>,
+    ) -> DBWithThreadMode<SingleThreaded> {
+        let mut options = options.clone();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        let column_families = [META_CF, MERKLE_NODES_CF, STALE_MERKLE_TREE_PARTS_CF]
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
+            .chain(
+                substate_column_family_names
+                    .into_iter()
+                    .map(|name| ColumnFamilyDescriptor::new(name, substates_cf_options())),
+            )
+            .collect::<Vec<_>>();
+        DB::open_cf_descriptors(&options, root.as_path(), column_families).unwrap()
+    }
+
     fn cf(&self, cf: &str) -> &ColumnFamily {
         self.db.cf_handle(cf).unwrap()
     }
 
+    fn substates_cf(&self, partition_key: &DbPartitionKey) -> &ColumnFamily {
+        let name = self
+            .layout
+            .dedicated_column_family_for(partition_key)
+            .unwrap_or(SUBSTATES_CF);
+        self.cf(name)
+    }
+
     pub fn get_current_version(&self) -> u64 {
         self.db
             .get_cf(self.cf(META_CF), &[])
@@ -104,7 +271,7 @@ impl SubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     ) -> Option<DbSubstateValue> {
         let key_bytes = encode_to_rocksdb_bytes(partition_key, sort_key);
         self.db
-            .get_cf(self.cf(SUBSTATES_CF), &key_bytes)
+            .get_cf(self.substates_cf(partition_key), &key_bytes)
             .expect("IO Error")
     }
 
@@ -113,14 +280,19 @@ impl SubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
         partition_key: &DbPartitionKey,
         from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        let cf = self.substates_cf(partition_key);
         let partition_key = partition_key.clone();
         let empty_sort_key = DbSortKey(vec![]);
         let from_sort_key = from_sort_key.unwrap_or(&empty_sort_key);
         let start_key_bytes = encode_to_rocksdb_bytes(&partition_key, from_sort_key);
+        // All keys of a partition share the extracted prefix, so the prefix bloom filters apply
+        let mut read_options = ReadOptions::default();
+        read_options.set_prefix_same_as_start(true);
         let iter = self
             .db
-            .iterator_cf(
-                self.cf(SUBSTATES_CF),
+            .iterator_cf_opt(
+                cf,
+                read_options,
                 IteratorMode::From(&start_key_bytes, Direction::Forward),
             )
             .map(|kv| {
@@ -160,18 +332,16 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                     node_key: node_key.clone(),
                     partition_num: *partition_num,
                 };
+                let cf = self.substates_cf(&partition_key);
                 match partition_updates {
                     PartitionDatabaseUpdates::Delta { substate_updates } => {
                         for (sort_key, update) in substate_updates {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
                             match update {
                                 DatabaseUpdate::Set(value_bytes) => {
-                                    self.db
-                                        .put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
-                                }
-                                DatabaseUpdate::Delete => {
-                                    self.db.delete_cf(self.cf(SUBSTATES_CF), key_bytes)
+                                    self.db.put_cf(cf, key_bytes, value_bytes)
                                 }
+                                DatabaseUpdate::Delete => self.db.delete_cf(cf, key_bytes),
                             }
                             .expect("IO error");
                         }
@@ -179,11 +349,11 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                     PartitionDatabaseUpdates::Reset {
                         new_substate_values,
                     } => {
-                        // Note: a plain `delete_range()` is missing from rocksdb's API, and
-                        // (at the moment of writing) this is the only reason of having CF.
+                        // Note: a plain `delete_range()` is missing from rocksdb's API, hence the
+                        // CF-based one.
                         self.db
                             .delete_range_cf(
-                                self.cf(SUBSTATES_CF),
+                                cf,
                                 encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
                                 encode_to_rocksdb_bytes(
                                     &partition_key,
@@ -194,7 +364,7 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                         for (sort_key, value_bytes) in new_substate_values {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
                             self.db
-                                .put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
+                                .put_cf(cf, key_bytes, value_bytes)
                                 .expect("IO error");
                         }
                     }
@@ -281,18 +451,29 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
 }
 
 impl ListableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
+    /// Lists the partition keys in the order of their encoding, as a single column family would.
     fn list_partition_keys(&self) -> Box<dyn Iterator<Item = DbPartitionKey> + '_> {
         Box::new(
-            self.db
-                .iterator_cf(self.cf(SUBSTATES_CF), IteratorMode::Start)
-                .map(|kv| {
-                    let (iter_key_bytes, _) = kv.as_ref().unwrap();
-                    let (iter_key, _) = decode_from_rocksdb_bytes(iter_key_bytes);
-                    iter_key
+            self.layout
+                .column_family_names_with_default(SUBSTATES_CF)
+                .into_iter()
+                .map(move |name| {
+                    self.db
+                        .iterator_cf_opt(
+                            self.cf(name),
+                            total_order_read_options(),
+                            IteratorMode::Start,
+                        )
+                        .map(|kv| {
+                            let (iter_key_bytes, _) = kv.as_ref().unwrap();
+                            partition_key_prefix(iter_key_bytes).to_vec()
+                        })
                 })
-                // Rocksdb iterator returns sorted entries, so ok to to eliminate
-                // duplicates with dedup()
-                .dedup(),
+                // Each column family returns sorted keys, and holds whole partitions, so the
+                // merged keys are sorted too, and ok to eliminate duplicates with dedup()
+                .kmerge()
+                .dedup()
+                .map(|partition_key_bytes| decode_from_rocksdb_bytes(&partition_key_bytes).0),
         )
     }
 }
@@ -317,8 +498,8 @@ struct Metadata {
 mod tests {
     use super::*;
     use radix_engine_store_interface::interface::{
-        CommittableSubstateDatabase, DatabaseUpdates, DbSortKey, NodeDatabaseUpdates,
-        PartitionDatabaseUpdates,
+        CommittableSubstateDatabase, DatabaseUpdate, DatabaseUpdates, DbSortKey,
+        NodeDatabaseUpdates, PartitionDatabaseUpdates,
     };
 
     #[cfg(not(feature = "alloc"))]
@@ -367,4 +548,77 @@ mod tests {
         });
         assert_eq!(db.list_partition_keys().count(), 8);
     }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_layout_migration() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let type_info_partition = DbPartitionKey {
+            node_key: vec![1],
+            partition_num: 0,
+        };
+        let main_partition = DbPartitionKey {
+            node_key: vec![1],
+            partition_num: 64,
+        };
+        let root_hash = {
+            let mut db = RocksDBWithMerkleTreeSubstateStore::with_layout(
+                &Options::default(),
+                root.clone(),
+                true,
+                RocksdbLayout::single_column_family(),
+            );
+            db.commit(&DatabaseUpdates {
+                node_updates: indexmap! {
+                    vec![1] => NodeDatabaseUpdates {
+                        partition_updates: indexmap! {
+                            0 => PartitionDatabaseUpdates::Delta {
+                                substate_updates: indexmap! {
+                                    DbSortKey(vec![2]) => DatabaseUpdate::Set(vec![3])
+                                }
+                            },
+                            64 => PartitionDatabaseUpdates::Delta {
+                                substate_updates: indexmap! {
+                                    DbSortKey(vec![4]) => DatabaseUpdate::Set(vec![5])
+                                }
+                            }
+                        }
+                    }
+                },
+            });
+            db.get_current_root_hash()
+        };
+        // The layout of an existing database is kept
+        assert_eq!(
+            RocksDBWithMerkleTreeSubstateStore::standard(root.clone()).layout(),
+            &RocksdbLayout::single_column_family()
+        );
+
+        let db = RocksDBWithMerkleTreeSubstateStore::migrate_layout(
+            &Options::default(),
+            root.clone(),
+            true,
+            RocksdbLayout::standard(),
+        );
+        assert_eq!(
+            db.get_substate(&type_info_partition, &DbSortKey(vec![2])),
+            Some(vec![3])
+        );
+        assert_eq!(
+            db.list_entries(&main_partition).collect::<Vec<_>>(),
+            vec![(DbSortKey(vec![4]), vec![5])]
+        );
+        assert_eq!(
+            db.list_partition_keys().collect::<Vec<_>>(),
+            vec![type_info_partition, main_partition]
+        );
+        assert_eq!(db.get_current_root_hash(), root_hash);
+        drop(db);
+
+        assert_eq!(
+            RocksDBWithMerkleTreeSubstateStore::standard(root).layout(),
+            &RocksdbLayout::standard()
+        );
+    }
 }
@@ -0,0 +1,70 @@
+use scrypto::prelude::*;
+
+#[blueprint]
+#[collections(
+    players: Index<String, u32>,
+    scores: SortedIndex<String, u32>,
+    #[allow_ownership]
+    prizes: Index<String, Vault>,
+)]
+mod leaderboard {
+    struct Leaderboard {}
+
+    impl Leaderboard {
+        pub fn new() -> Global<Leaderboard> {
+            Self {}
+                .instantiate()
+                .prepare_to_globalize(OwnerRole::None)
+                .globalize()
+        }
+
+        pub fn submit(&mut self, player: String, score: u16) {
+            if let Some(previous_score) = Self::players().remove(&player) {
+                Self::scores().remove(Self::sort_prefix(previous_score as u16), &player);
+            }
+            Self::players().insert(player.clone(), score as u32);
+            Self::scores().insert(Self::sort_prefix(score), player, score as u32);
+        }
+
+        pub fn withdraw(&mut self, player: String) -> Option<u32> {
+            let score = Self::players().remove(&player)?;
+            Self::scores().remove(Self::sort_prefix(score as u16), &player)
+        }
+
+        pub fn score(&self, player: String) -> Option<u32> {
+            Self::players().get(&player)
+        }
+
+        pub fn list_players(&self, limit: u32) -> Vec<String> {
+            Self::players().scan_keys(limit)
+        }
+
+        pub fn top_scores(&self, limit: u32) -> Vec<(String, u32)> {
+            Self::scores()
+                .scan(limit)
+                .into_iter()
+                .map(|(_sort_prefix, player, score)| (player, score))
+                .collect()
+        }
+
+        pub fn top_scores_after(
+            &self,
+            after: Option<(String, u32)>,
+            limit: u32,
+        ) -> Vec<(String, u32)> {
+            let after = after
+                .as_ref()
+                .map(|(player, score)| (Self::sort_prefix(*score as u16), player));
+            Self::scores()
+                .scan_after(after, limit)
+                .into_iter()
+                .map(|(_sort_prefix, player, score)| (player, score))
+                .collect()
+        }
+
+        // The sorted index scans in an ascending order
+        fn sort_prefix(score: u16) -> u16 {
+            u16::MAX - score
+        }
+    }
+}
@@ -24,6 +24,20 @@ mod basic {
                 .globalize()
         }
 
+        pub fn new_with_entries(count: u32, page_size: u32) -> (Global<Basic>, Vec<String>) {
+            let map = KeyValueStore::new();
+            for i in 0..count {
+                map.insert(i.to_string(), i.to_string());
+            }
+            let basic = Self { map };
+            let keys = basic.keys(page_size);
+            let component = basic
+                .instantiate()
+                .prepare_to_globalize(OwnerRole::None)
+                .globalize();
+            (component, keys)
+        }
+
         pub fn multiple_reads() -> Global<Basic> {
             let map = KeyValueStore::new();
             map.insert("hello".to_owned(), "hello".to_owned());
@@ -63,6 +77,49 @@ mod basic {
         pub fn remove(&mut self, key: String) -> Option<String> {
             self.map.remove(&key)
         }
+
+        pub fn keys(&self, page_size: u32) -> Vec<String> {
+            let mut keys = self.map.iter_keys(page_size);
+            let mut page_len = keys.len();
+            while page_len == page_size as usize {
+                let page = self.map.iter_keys_after(keys.last().unwrap(), page_size);
+                page_len = page.len();
+                keys.extend(page);
+            }
+            keys
+        }
+
+        pub fn len(&self) -> u32 {
+            self.map.len()
+        }
+
+        pub fn keys_and_len_with_entry_locked(&mut self, key: String) -> (Vec<String>, u32) {
+            let handle = ScryptoVmV1Api::kv_store_open_entry(
+                self.map.id.as_node_id(),
+                &scrypto_encode(&key).unwrap(),
+                LockFlags::MUTABLE,
+            );
+            let keys = self.map.iter_keys(10);
+            let len = self.map.len();
+            ScryptoVmV1Api::kv_entry_close(handle);
+            (keys, len)
+        }
+
+        pub fn iterate_local() {
+            let map = KeyValueStore::new();
+            assert!(map.is_empty());
+            for i in 0..5u32 {
+                map.insert(i.to_string(), i.to_string());
+            }
+            map.remove(&"2".to_owned());
+
+            let mut keys = map.iter_keys(2);
+            keys.extend(map.iter_keys_after(keys.last().unwrap(), 10));
+            keys.sort();
+            assert_eq!(keys, vec!["0", "1", "3", "4"]);
+            assert_eq!(map.len(), 4);
+            assert!(!map.is_empty());
+        }
     }
 }
 
@@ -0,0 +1,133 @@
+use radix_engine::system::system_modules::costing::{CostProfile, CostProfileCode};
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use radix_engine_tests::common::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn transfer_resource_between_two_components() -> TransactionReceipt {
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("execution_trace"));
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee(account, 500)
+        .call_function(
+            package_address,
+            "ExecutionTraceTest",
+            "transfer_resource_between_two_components",
+            manifest_args!(10u8),
+        )
+        .build();
+    test_runner.execute_manifest_with_cost_profile(
+        manifest,
+        vec![NonFungibleGlobalId::from_public_key(&public_key)],
+    )
+}
+
+fn frame_index(cost_profile: &CostProfile, blueprint_name: &str, ident: &str) -> usize {
+    cost_profile
+        .frames
+        .iter()
+        .position(|frame| {
+            frame.ident == ident
+                && frame
+                    .blueprint_id
+                    .as_ref()
+                    .map(|blueprint_id| blueprint_id.blueprint_name.as_str())
+                    == Some(blueprint_name)
+        })
+        .unwrap()
+}
+
+#[test]
+fn cost_profile_attributes_all_cost_units_to_the_call_frames() {
+    // Arrange
+    let receipt = transfer_resource_between_two_components();
+
+    // Act
+    let cost_profile = receipt.cost_profile.as_ref().unwrap();
+
+    // Assert
+    receipt.expect_commit_success();
+    let self_cost_units: u32 = cost_profile
+        .frames
+        .iter()
+        .flat_map(|frame| frame.self_cost_units.values())
+        .sum();
+    assert_eq!(
+        self_cost_units,
+        receipt.fee_summary.total_execution_cost_units_consumed
+            + receipt.fee_summary.total_finalization_cost_units_consumed
+    );
+    assert_eq!(cost_profile.total_cost_units(), self_cost_units);
+
+    let wasm_frame = frame_index(
+        cost_profile,
+        "ExecutionTraceTest",
+        "transfer_resource_between_two_components",
+    );
+    assert_eq!(
+        cost_profile.frames[wasm_frame].code,
+        Some(CostProfileCode::Wasm)
+    );
+
+    let deposit_frame = frame_index(cost_profile, FUNGIBLE_VAULT_BLUEPRINT, "put");
+    assert_eq!(
+        cost_profile.frames[deposit_frame].code,
+        Some(CostProfileCode::Native)
+    );
+    let mut ancestor = cost_profile.frames[deposit_frame].parent;
+    while ancestor.is_some() && ancestor != Some(wasm_frame) {
+        ancestor = cost_profile.frames[ancestor.unwrap()].parent;
+    }
+    assert_eq!(ancestor, Some(wasm_frame));
+
+    for frame in &cost_profile.frames {
+        if let Some(parent) = frame.parent {
+            let parent = &cost_profile.frames[parent];
+            assert!(parent.start_cost_units <= frame.start_cost_units);
+            assert!(frame.end_cost_units <= parent.end_cost_units);
+        }
+    }
+}
+
+#[test]
+fn cost_profile_can_be_exported() {
+    // Arrange
+    let receipt = transfer_resource_between_two_components();
+    let cost_profile = receipt.cost_profile.as_ref().unwrap();
+    let address_encoder = AddressBech32Encoder::for_simulator();
+
+    // Act
+    let folded_stacks = cost_profile.to_folded_stacks(Some(&address_encoder));
+    let speedscope: serde_json::Value =
+        serde_json::from_str(&cost_profile.to_speedscope_json(Some(&address_encoder))).unwrap();
+    let chrome_trace: serde_json::Value =
+        serde_json::from_str(&cost_profile.to_chrome_trace_json(Some(&address_encoder))).unwrap();
+
+    // Assert
+    let folded_cost_units: u32 = folded_stacks
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u32>().unwrap())
+        .sum();
+    assert_eq!(folded_cost_units, cost_profile.total_cost_units());
+    assert!(folded_stacks.lines().any(|line| line.starts_with(&format!(
+        "Transaction;{}::TransactionProcessor::run;",
+        address_encoder
+            .encode(TRANSACTION_PROCESSOR_PACKAGE.as_node_id().as_bytes())
+            .unwrap()
+    ))));
+
+    let events = speedscope["profiles"][0]["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2 * cost_profile.frames.len());
+    assert_eq!(
+        speedscope["profiles"][0]["endValue"],
+        cost_profile.total_cost_units()
+    );
+
+    let trace_events = chrome_trace["traceEvents"].as_array().unwrap();
+    assert_eq!(trace_events.len(), cost_profile.frames.len());
+    assert_eq!(trace_events[0]["name"], "Transaction");
+    assert_eq!(trace_events[0]["dur"], cost_profile.total_cost_units());
+}
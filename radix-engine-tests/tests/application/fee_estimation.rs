@@ -0,0 +1,117 @@
+use radix_engine::transaction::{FeeEstimationError, FeeEstimationParams};
+use radix_engine::types::*;
+use radix_engine_interface::blueprints::account::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+#[test]
+fn estimated_fee_is_locked_on_the_fee_payer() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let (_, _, other_account) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .withdraw_from_account(account, XRD, 10)
+        .try_deposit_entire_worktop_or_abort(other_account, None)
+        .build();
+    let params = FeeEstimationParams::new(account)
+        .with_signer_public_keys(vec![public_key.into()])
+        .with_safety_margin(dec!("0.5"));
+
+    // Act
+    let (manifest, estimate) = test_runner.lock_estimated_fee(manifest, &params).unwrap();
+
+    // Assert
+    assert!(estimate.estimated_fee.is_positive());
+    assert_eq!(estimate.estimated_fee, estimate.fee_summary.total_cost());
+    assert_eq!(
+        estimate.fee_to_lock,
+        estimate.estimated_fee.checked_mul(dec!("1.5")).unwrap()
+    );
+    assert_eq!(manifest.instructions.len(), 3);
+    match &manifest.instructions[0] {
+        InstructionV1::CallMethod {
+            method_name, args, ..
+        } => {
+            assert_eq!(method_name, ACCOUNT_LOCK_FEE_IDENT);
+            assert_eq!(
+                args,
+                &to_manifest_value_and_unwrap!(&AccountLockFeeInput {
+                    amount: estimate.fee_to_lock
+                })
+            );
+        }
+        instruction => panic!("Unexpected first instruction: {:?}", instruction),
+    }
+
+    let receipt = test_runner.execute_manifest(
+        manifest,
+        vec![NonFungibleGlobalId::from_public_key(&public_key)],
+    );
+    receipt.expect_commit_success();
+    assert!(receipt.fee_summary.total_cost() <= estimate.fee_to_lock);
+}
+
+#[test]
+fn fee_cannot_be_estimated_for_manifest_which_locks_fee() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee(account, 10)
+        .drop_auth_zone_proofs()
+        .build();
+
+    // Act
+    let result = test_runner.lock_estimated_fee(manifest, &FeeEstimationParams::new(account));
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        FeeEstimationError::ManifestAlreadyLocksFee
+    );
+}
+
+#[test]
+fn fee_cannot_be_estimated_with_negative_safety_margin() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .withdraw_from_account(account, XRD, 10)
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let params = FeeEstimationParams::new(account)
+        .with_signer_public_keys(vec![public_key.into()])
+        .with_safety_margin(dec!("-0.5"));
+
+    // Act
+    let result = test_runner.lock_estimated_fee(manifest, &params);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        FeeEstimationError::InvalidSafetyMargin(dec!("-0.5"))
+    );
+}
+
+#[test]
+fn fee_cannot_be_estimated_for_failing_manifest() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .withdraw_from_account(account, XRD, 1_000_000_000)
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let params = FeeEstimationParams::new(account).with_signer_public_keys(vec![public_key.into()]);
+
+    // Act
+    let result = test_runner.lock_estimated_fee(manifest, &params);
+
+    // Assert
+    assert!(matches!(
+        result.unwrap_err(),
+        FeeEstimationError::TransactionFailed(..)
+    ));
+}
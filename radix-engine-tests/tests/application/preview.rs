@@ -1,6 +1,8 @@
+use radix_engine::errors::RejectionReason;
 use radix_engine::system::system_modules::costing::FeeTable;
 use radix_engine::transaction::CostingParameters;
 use radix_engine::transaction::ExecutionConfig;
+use radix_engine::transaction::PreviewOverrides;
 use radix_engine::types::*;
 use radix_engine_interface::rule;
 use scrypto_unit::*;
@@ -143,6 +145,130 @@ fn test_assume_all_signature_proofs_flag_method_authorization() {
     result.unwrap().expect_commit_success();
 }
 
+#[test]
+fn test_fungible_vault_balance_override() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let network = NetworkDefinition::simulator();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let (_, _, other_account) = test_runner.new_allocated_account();
+    let balance = test_runner.get_component_balance(account, XRD);
+    let vault_id = test_runner.get_component_vaults(account, XRD)[0];
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_from_account(account, XRD, dec!(1000000))
+        .try_deposit_entire_worktop_or_abort(other_account, None)
+        .build();
+    let (_, mut preview_intent) = prepare_matching_test_tx_and_preview_intent(
+        &mut test_runner,
+        &network,
+        manifest,
+        &PreviewFlags::default(),
+    );
+    preview_intent.signer_public_keys = vec![public_key.into()];
+    let overrides = PreviewOverrides {
+        fungible_vault_balances: indexmap!(vault_id => dec!(1000000)),
+        ..Default::default()
+    };
+
+    // Act
+    let receipt_without_overrides = test_runner
+        .preview(preview_intent.clone(), &network)
+        .unwrap();
+    let receipt_with_overrides = test_runner
+        .preview_with_overrides(preview_intent, &network, &overrides)
+        .unwrap();
+
+    // Assert
+    receipt_without_overrides.expect_commit_failure();
+    receipt_with_overrides.expect_commit_success();
+    assert_eq!(test_runner.get_component_balance(account, XRD), balance);
+}
+
+#[test]
+fn test_auth_zone_overrides_impersonate_signers_and_badge_holders() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let network = NetworkDefinition::simulator();
+    let (_, _, other_account) = test_runner.new_allocated_account();
+    let badge = test_runner.create_fungible_resource(dec!(1), 0, other_account);
+    let public_key = Secp256k1PrivateKey::from_u64(99).unwrap().public_key();
+    let key_account = test_runner.new_account_advanced(OwnerRole::Fixed(rule!(require(
+        NonFungibleGlobalId::from_public_key(&public_key)
+    ))));
+    let badge_account = test_runner.new_account_advanced(OwnerRole::Fixed(rule!(require(badge))));
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_from_account(key_account, XRD, 1)
+        .withdraw_from_account(badge_account, XRD, 1)
+        .try_deposit_entire_worktop_or_abort(other_account, None)
+        .build();
+    let (_, preview_intent) = prepare_matching_test_tx_and_preview_intent(
+        &mut test_runner,
+        &network,
+        manifest,
+        &PreviewFlags::default(),
+    );
+    let overrides = PreviewOverrides {
+        auth_zone_params: AuthZoneParams {
+            initial_proofs: btreeset!(NonFungibleGlobalId::from_public_key(&public_key)),
+            virtual_resources: btreeset!(badge),
+        },
+        ..Default::default()
+    };
+
+    // Act
+    let receipt_without_overrides = test_runner
+        .preview(preview_intent.clone(), &network)
+        .unwrap();
+    let receipt_with_overrides = test_runner
+        .preview_with_overrides(preview_intent, &network, &overrides)
+        .unwrap();
+
+    // Assert
+    receipt_without_overrides.expect_commit_failure();
+    receipt_with_overrides.expect_commit_success();
+}
+
+#[test]
+fn test_epoch_override() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let network = NetworkDefinition::simulator();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .drop_auth_zone_proofs()
+        .build();
+    let (_, preview_intent) = prepare_matching_test_tx_and_preview_intent(
+        &mut test_runner,
+        &network,
+        manifest,
+        &PreviewFlags::default(),
+    );
+    let epoch_before = test_runner.get_current_epoch();
+    let overrides = PreviewOverrides {
+        epoch: Some(Epoch::of(99)),
+        ..Default::default()
+    };
+
+    // Act
+    let receipt = test_runner
+        .preview_with_overrides(preview_intent, &network, &overrides)
+        .unwrap();
+
+    // Assert
+    receipt.expect_specific_rejection(|e| {
+        matches!(
+            e,
+            RejectionReason::TransactionEpochNoLongerValid { current_epoch, .. }
+                if *current_epoch == Epoch::of(99)
+        )
+    });
+    assert_eq!(test_runner.get_current_epoch(), epoch_before);
+}
+
 fn prepare_matching_test_tx_and_preview_intent(
     test_runner: &mut DefaultTestRunner,
     network: &NetworkDefinition,
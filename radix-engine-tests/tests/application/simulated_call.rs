@@ -0,0 +1,128 @@
+use radix_engine::blueprints::resource::fungible_vault;
+use radix_engine::errors::{RuntimeError, SystemModuleError};
+use radix_engine::system::system_modules::auth::AuthError;
+use radix_engine::transaction::{SimulatedCall, SimulatedCallError};
+use radix_engine::types::*;
+use radix_engine_interface::blueprints::account::*;
+use radix_engine_interface::blueprints::resource::RESOURCE_MANAGER_GET_TOTAL_SUPPLY_IDENT;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+#[test]
+fn simulated_call_returns_the_typed_output() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+    let resource_address = test_runner.create_fungible_resource(dec!(150), 18, account);
+
+    // Act
+    let result = test_runner
+        .simulate_call(&SimulatedCall::method(
+            resource_address,
+            RESOURCE_MANAGER_GET_TOTAL_SUPPLY_IDENT,
+            manifest_args!(),
+        ))
+        .unwrap();
+
+    // Assert
+    assert_eq!(result.output::<Option<Decimal>>().unwrap(), Some(dec!(150)));
+}
+
+#[test]
+fn simulated_call_runs_with_the_configured_auth_zone() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let call = SimulatedCall::method(
+        account,
+        ACCOUNT_CREATE_PROOF_OF_AMOUNT_IDENT,
+        AccountCreateProofOfAmountInput {
+            resource_address: XRD,
+            amount: dec!(1),
+        },
+    );
+
+    // Act
+    let without_proofs = test_runner.simulate_call(&call);
+    let with_proofs = test_runner.simulate_call(&call.with_auth_zone_params(AuthZoneParams {
+        initial_proofs: btreeset!(NonFungibleGlobalId::from_public_key(&public_key)),
+        virtual_resources: btreeset!(),
+    }));
+
+    // Assert
+    assert!(matches!(
+        without_proofs,
+        Err(SimulatedCallError::Failed(RuntimeError::SystemModuleError(
+            SystemModuleError::AuthError(AuthError::Unauthorized(..))
+        )))
+    ));
+    assert!(with_proofs.is_ok());
+}
+
+#[test]
+fn simulated_call_returns_its_application_events() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+
+    // Act
+    let result = test_runner
+        .simulate_call(
+            &SimulatedCall::method(
+                account,
+                ACCOUNT_LOCK_FEE_IDENT,
+                AccountLockFeeInput { amount: dec!(100) },
+            )
+            .with_auth_zone_params(AuthZoneParams {
+                initial_proofs: btreeset!(NonFungibleGlobalId::from_public_key(&public_key)),
+                virtual_resources: btreeset!(),
+            }),
+        )
+        .unwrap();
+
+    // Assert
+    assert!(result
+        .application_events
+        .iter()
+        .any(|(event_type_identifier, _)| test_runner
+            .is_event_name_equal::<fungible_vault::LockFeeEvent>(event_type_identifier)));
+}
+
+#[test]
+fn simulated_call_does_not_change_the_database() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let auth_zone_params = AuthZoneParams {
+        initial_proofs: btreeset!(NonFungibleGlobalId::from_public_key(&public_key)),
+        virtual_resources: btreeset!(),
+    };
+    let database = test_runner.substate_db().clone();
+
+    // Act
+    test_runner
+        .simulate_call(
+            &SimulatedCall::method(
+                account,
+                ACCOUNT_LOCK_FEE_IDENT,
+                AccountLockFeeInput { amount: dec!(100) },
+            )
+            .with_auth_zone_params(auth_zone_params.clone()),
+        )
+        .unwrap();
+    test_runner
+        .simulate_call(
+            &SimulatedCall::method(
+                account,
+                ACCOUNT_SET_DEFAULT_DEPOSIT_RULE_IDENT,
+                AccountSetDefaultDepositRuleInput {
+                    default: DefaultDepositRule::Reject,
+                },
+            )
+            .with_auth_zone_params(auth_zone_params),
+        )
+        .unwrap();
+
+    // Assert
+    assert!(test_runner.substate_db() == &database);
+}
@@ -0,0 +1,333 @@
+use radix_engine::{
+    errors::{ApplicationError, RuntimeError},
+    transaction::TransactionReceipt,
+    types::*,
+};
+use radix_engine_interface::blueprints::pool::*;
+use scrypto_unit::{DefaultTestRunner, TestRunnerBuilder};
+use transaction::prelude::*;
+
+use radix_engine::blueprints::pool::v1::errors::stable_swap_pool::Error as StableSwapPoolError;
+use radix_engine::blueprints::pool::v1::events::stable_swap_pool::*;
+
+#[test]
+fn stable_swap_pool_can_be_instantiated() {
+    TestEnvironment::<3>::new(dec!(100), dec!("0.0004"));
+}
+
+#[test]
+fn stable_swap_pool_can_not_be_instantiated_with_amplification_below_one() {
+    // Act
+    let receipt = TestEnvironment::<2>::try_new(dec!("0.5"), dec!(0))
+        .err()
+        .unwrap();
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::StableSwapPoolError(
+                StableSwapPoolError::InvalidAmplification { .. }
+            ))
+        )
+    });
+}
+
+#[test]
+fn stable_swap_pool_can_not_be_instantiated_with_a_single_resource() {
+    // Act
+    let receipt = TestEnvironment::<1>::try_new(dec!(100), dec!(0))
+        .err()
+        .unwrap();
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::StableSwapPoolError(
+                StableSwapPoolError::CantCreatePoolWithLessThanTwoResources
+            ))
+        )
+    });
+}
+
+#[test]
+fn initial_contribution_of_balanced_reserves_mints_their_sum() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new(dec!(100), dec!(0));
+
+    // Act
+    let receipt = test_runner.contribute([dec!(100), dec!(100), dec!(100)], true);
+
+    // Assert
+    let commit_result = receipt.expect_commit_success();
+    let event = commit_result
+        .application_events
+        .iter()
+        .find_map(|(event_type_identifier, data)| {
+            (test_runner.test_runner.event_name(event_type_identifier) == "ContributionEvent")
+                .then(|| scrypto_decode::<ContributionEvent>(data).unwrap())
+        })
+        .unwrap();
+    assert_eq!(event.pool_units_minted, dec!(300));
+}
+
+#[test]
+fn initial_contribution_of_imbalanced_reserves_mints_the_invariant() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new(dec!(10), dec!(0));
+
+    // Act
+    let receipt = test_runner.contribute([dec!(100), dec!(200), dec!(300)], true);
+
+    // Assert
+    let commit_result = receipt.expect_commit_success();
+    let event = commit_result
+        .application_events
+        .iter()
+        .find_map(|(event_type_identifier, data)| {
+            (test_runner.test_runner.event_name(event_type_identifier) == "ContributionEvent")
+                .then(|| scrypto_decode::<ContributionEvent>(data).unwrap())
+        })
+        .unwrap();
+    // D = 599.270934089591808400...
+    assert!(event.pool_units_minted >= dec!("599.270934089591808"));
+    assert!(event.pool_units_minted <= dec!("599.270934089591809"));
+}
+
+#[test]
+fn swap_near_the_balance_point_is_close_to_one_to_one() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new(dec!(100), dec!(0));
+    test_runner
+        .contribute([dec!(1000), dec!(1000)], true)
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(10), 1, true);
+
+    // Assert
+    receipt.expect_commit_success();
+    // The exact output is 9.999502463057881923..., while it would be 9.900990099009900990 in a
+    // constant product pool.
+    let output = test_runner.balance(1);
+    assert!(output <= dec!("9.999502463057881923"));
+    assert!(output >= dec!("9.999502463057881"));
+}
+
+#[test]
+fn swap_fee_is_kept_by_the_pool() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new(dec!(100), dec!("0.01"));
+    test_runner
+        .contribute([dec!(1000), dec!(1000)], true)
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(10), 1, true);
+
+    // Assert
+    receipt.expect_commit_success();
+    // The output for the input net of fees, which is 9.9, is 9.899512364761151036...
+    let output = test_runner.balance(1);
+    assert!(output <= dec!("9.899512364761151036"));
+    assert!(output >= dec!("9.899512364761151"));
+    assert_eq!(test_runner.get_vault_amounts()[0], dec!(1010));
+}
+
+#[test]
+fn get_swap_output_amount_is_the_output_of_the_swap() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new(dec!(250), dec!("0.0004"));
+    test_runner
+        .contribute([dec!(1000), dec!(1200), dec!(900)], true)
+        .expect_commit_success();
+
+    // Act
+    let quoted_amount = test_runner.get_swap_output_amount(2, dec!(42), 1);
+    test_runner
+        .swap(2, dec!(42), 1, true)
+        .expect_commit_success();
+
+    // Assert
+    assert_eq!(test_runner.balance(1), quoted_amount);
+}
+
+#[test]
+fn swap_event_is_emitted() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new(dec!(100), dec!(0));
+    test_runner
+        .contribute([dec!(1000), dec!(1000)], true)
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(10), 1, true);
+
+    // Assert
+    let commit_result = receipt.expect_commit_success();
+    let event = commit_result
+        .application_events
+        .iter()
+        .find_map(|(event_type_identifier, data)| {
+            (test_runner.test_runner.event_name(event_type_identifier) == "SwapEvent")
+                .then(|| scrypto_decode::<SwapEvent>(data).unwrap())
+        })
+        .unwrap();
+    assert_eq!(event.input_resource_address, test_runner.pool_resources[0]);
+    assert_eq!(event.input_amount, dec!(10));
+    assert_eq!(event.output_resource_address, test_runner.pool_resources[1]);
+    assert_eq!(event.output_amount, test_runner.balance(1));
+}
+
+struct TestEnvironment<const N: usize> {
+    test_runner: DefaultTestRunner,
+
+    pool_component_address: ComponentAddress,
+
+    pool_resources: [ResourceAddress; N],
+
+    account_public_key: PublicKey,
+    account_component_address: ComponentAddress,
+}
+
+impl<const N: usize> TestEnvironment<N> {
+    pub fn new(amplification: Decimal, swap_fee: Decimal) -> Self {
+        match Self::try_new(amplification, swap_fee) {
+            Ok(test_environment) => test_environment,
+            Err(receipt) => panic!("Pool instantiation failed: {:?}", receipt),
+        }
+    }
+
+    pub fn try_new(amplification: Decimal, swap_fee: Decimal) -> Result<Self, TransactionReceipt> {
+        let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+        let (public_key, _, account) = test_runner.new_account(false);
+        let virtual_signature_badge = NonFungibleGlobalId::from_public_key(&public_key);
+
+        let resource_addresses = [(); N].map(|_| {
+            test_runner.create_freely_mintable_and_burnable_fungible_resource(
+                OwnerRole::None,
+                None,
+                18,
+                account,
+            )
+        });
+
+        let manifest = ManifestBuilder::new()
+            .create_stable_swap_pool(
+                OwnerRole::None,
+                rule!(require(virtual_signature_badge)),
+                resource_addresses.into_iter().collect(),
+                amplification,
+                swap_fee,
+            )
+            .build();
+        let receipt = test_runner.execute_manifest_ignoring_fee(manifest, vec![]);
+        if !receipt.is_commit_success() {
+            return Err(receipt);
+        }
+        let pool_component = receipt.expect_commit_success().new_component_addresses()[0];
+
+        Ok(Self {
+            test_runner,
+            pool_component_address: pool_component,
+            pool_resources: resource_addresses,
+            account_public_key: public_key.into(),
+            account_component_address: account,
+        })
+    }
+
+    pub fn contribute(&mut self, amounts: [Decimal; N], sign: bool) -> TransactionReceipt {
+        let mut manifest_builder = ManifestBuilder::new();
+        for (resource_address, amount) in self.pool_resources.iter().zip(amounts) {
+            manifest_builder = manifest_builder.mint_fungible(*resource_address, amount)
+        }
+        let manifest = manifest_builder
+            .call_method(
+                self.pool_component_address,
+                STABLE_SWAP_POOL_CONTRIBUTE_IDENT,
+                manifest_args!(ManifestExpression::EntireWorktop),
+            )
+            .try_deposit_entire_worktop_or_abort(self.account_component_address, None)
+            .build();
+        self.execute_manifest(manifest, sign)
+    }
+
+    pub fn swap(
+        &mut self,
+        input_index: usize,
+        input_amount: Decimal,
+        output_index: usize,
+        sign: bool,
+    ) -> TransactionReceipt {
+        let input_resource_address = self.pool_resources[input_index];
+        let manifest = ManifestBuilder::new()
+            .mint_fungible(input_resource_address, input_amount)
+            .take_all_from_worktop(input_resource_address, "input")
+            .swap_in_pool(
+                self.pool_component_address,
+                "input",
+                self.pool_resources[output_index],
+            )
+            .try_deposit_entire_worktop_or_abort(self.account_component_address, None)
+            .build();
+        self.execute_manifest(manifest, sign)
+    }
+
+    pub fn get_swap_output_amount(
+        &mut self,
+        input_index: usize,
+        input_amount: Decimal,
+        output_index: usize,
+    ) -> StableSwapPoolGetSwapOutputAmountOutput {
+        let manifest = ManifestBuilder::new()
+            .call_method(
+                self.pool_component_address,
+                STABLE_SWAP_POOL_GET_SWAP_OUTPUT_AMOUNT_IDENT,
+                StableSwapPoolGetSwapOutputAmountManifestInput {
+                    input_resource_address: self.pool_resources[input_index],
+                    input_amount,
+                    output_resource_address: self.pool_resources[output_index],
+                },
+            )
+            .build();
+        let receipt = self.execute_manifest(manifest, false);
+        receipt.expect_commit_success().output(1)
+    }
+
+    pub fn get_vault_amounts(&mut self) -> [Decimal; N] {
+        let manifest = ManifestBuilder::new()
+            .call_method(
+                self.pool_component_address,
+                STABLE_SWAP_POOL_GET_VAULT_AMOUNTS_IDENT,
+                StableSwapPoolGetVaultAmountsManifestInput,
+            )
+            .build();
+        let receipt = self.execute_manifest(manifest, false);
+        let vault_amounts: StableSwapPoolGetVaultAmountsOutput =
+            receipt.expect_commit_success().output(1);
+        self.pool_resources
+            .map(|resource_address| *vault_amounts.get(&resource_address).unwrap())
+    }
+
+    pub fn balance(&mut self, index: usize) -> Decimal {
+        self.test_runner
+            .get_component_balance(self.account_component_address, self.pool_resources[index])
+    }
+
+    fn execute_manifest(
+        &mut self,
+        manifest: TransactionManifestV1,
+        sign: bool,
+    ) -> TransactionReceipt {
+        let initial_proofs = if sign {
+            vec![NonFungibleGlobalId::from_public_key(
+                &self.account_public_key,
+            )]
+        } else {
+            vec![]
+        };
+        self.test_runner
+            .execute_manifest_ignoring_fee(manifest, initial_proofs)
+    }
+}
@@ -0,0 +1,394 @@
+use radix_engine::{
+    errors::{ApplicationError, RuntimeError},
+    transaction::TransactionReceipt,
+    types::*,
+};
+use radix_engine_interface::blueprints::pool::*;
+use scrypto_unit::{is_auth_error, DefaultTestRunner, TestRunnerBuilder};
+use transaction::prelude::*;
+
+use radix_engine::blueprints::pool::v1::errors::weighted_pool::Error as WeightedPoolError;
+use radix_engine::blueprints::pool::v1::events::weighted_pool::*;
+
+#[test]
+fn weighted_pool_can_be_instantiated() {
+    TestEnvironment::new([dec!("0.8"), dec!("0.2")], dec!(0));
+}
+
+#[test]
+fn weighted_pool_can_not_be_instantiated_with_weights_not_summing_to_one() {
+    // Act
+    let receipt = TestEnvironment::try_new([dec!("0.8"), dec!("0.3")], dec!(0))
+        .err()
+        .unwrap();
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::WeightedPoolError(
+                WeightedPoolError::WeightsDoNotSumToOne { .. }
+            ))
+        )
+    });
+}
+
+#[test]
+fn weighted_pool_can_not_be_instantiated_with_weight_below_minimum() {
+    // Act
+    let receipt = TestEnvironment::try_new([dec!("0.995"), dec!("0.005")], dec!(0))
+        .err()
+        .unwrap();
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::WeightedPoolError(
+                WeightedPoolError::InvalidWeight { .. }
+            ))
+        )
+    });
+}
+
+#[test]
+fn weighted_pool_can_not_be_instantiated_with_swap_fee_of_one() {
+    // Act
+    let receipt = TestEnvironment::try_new([dec!("0.5"), dec!("0.5")], dec!(1))
+        .err()
+        .unwrap();
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::WeightedPoolError(
+                WeightedPoolError::InvalidSwapFee { .. }
+            ))
+        )
+    });
+}
+
+#[test]
+fn initial_contribution_mints_the_weighted_geometric_mean_of_the_contributions() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.75"), dec!("0.25")], dec!(0));
+
+    // Act
+    let receipt = test_runner.contribute([dec!(16), dec!(81)], true);
+
+    // Assert
+    let commit_result = receipt.expect_commit_success();
+    let event = commit_result
+        .application_events
+        .iter()
+        .find_map(|(event_type_identifier, data)| {
+            (test_runner.test_runner.event_name(event_type_identifier) == "ContributionEvent")
+                .then(|| scrypto_decode::<ContributionEvent>(data).unwrap())
+        })
+        .unwrap();
+    // 16^0.75 * 81^0.25 = 8 * 3
+    assert_eq!(event.pool_units_minted, dec!(24));
+}
+
+#[test]
+fn initial_contribution_must_be_of_all_resources() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.5"), dec!("0.5")], dec!(0));
+
+    // Act
+    let receipt = test_runner.contribute([dec!(100), dec!(0)], true);
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::WeightedPoolError(
+                WeightedPoolError::MissingOrEmptyBuckets { .. }
+            ))
+        )
+    });
+}
+
+#[test]
+fn swap_in_equally_weighted_pool_is_a_constant_product_swap() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.5"), dec!("0.5")], dec!(0));
+    test_runner
+        .contribute([dec!(100), dec!(100)], true)
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(100), 1, true);
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(test_runner.balance(1), dec!(50));
+    assert_eq!(test_runner.get_vault_amounts(), [dec!(200), dec!(50)]);
+}
+
+#[test]
+fn swap_in_weighted_pool_never_outputs_more_than_the_exact_amount() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.8"), dec!("0.2")], dec!(0));
+    test_runner
+        .contribute([dec!(100), dec!(100)], true)
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(21), 1, true);
+
+    // Assert
+    receipt.expect_commit_success();
+    // 100 (1 - (100 / 121)^(0.8 / 0.2)) = 53.349261979026658568...
+    let output = test_runner.balance(1);
+    assert!(output <= dec!("53.349261979026658568"));
+    assert!(output >= dec!("53.349261979026658"));
+}
+
+#[test]
+fn swap_fee_is_kept_by_the_pool() {
+    // Arrange
+    let mut without_fee = TestEnvironment::new([dec!("0.5"), dec!("0.5")], dec!(0));
+    let mut with_fee = TestEnvironment::new([dec!("0.5"), dec!("0.5")], dec!("0.01"));
+    for test_runner in [&mut without_fee, &mut with_fee] {
+        test_runner
+            .contribute([dec!(100), dec!(100)], true)
+            .expect_commit_success();
+    }
+
+    // Act
+    without_fee
+        .swap(0, dec!(10), 1, true)
+        .expect_commit_success();
+    with_fee.swap(0, dec!(10), 1, true).expect_commit_success();
+
+    // Assert
+    assert!(with_fee.balance(1) < without_fee.balance(1));
+    assert_eq!(with_fee.get_vault_amounts()[0], dec!(110));
+}
+
+#[test]
+fn get_swap_output_amount_is_the_output_of_the_swap() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.6"), dec!("0.4")], dec!("0.003"));
+    test_runner
+        .contribute([dec!(1000), dec!(500)], true)
+        .expect_commit_success();
+
+    // Act
+    let quoted_amount = test_runner.get_swap_output_amount(1, dec!(42), 0);
+    test_runner
+        .swap(1, dec!(42), 0, true)
+        .expect_commit_success();
+
+    // Assert
+    assert_eq!(test_runner.balance(0), quoted_amount);
+}
+
+#[test]
+fn swap_requires_the_pool_manager_role() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.5"), dec!("0.5")], dec!(0));
+    test_runner
+        .contribute([dec!(100), dec!(100)], true)
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(10), 1, false);
+
+    // Assert
+    receipt.expect_specific_failure(is_auth_error);
+}
+
+#[test]
+fn swap_to_the_same_resource_fails() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.5"), dec!("0.5")], dec!(0));
+    test_runner
+        .contribute([dec!(100), dec!(100)], true)
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(10), 0, true);
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::WeightedPoolError(
+                WeightedPoolError::SwapOfSameResource { .. }
+            ))
+        )
+    });
+}
+
+#[test]
+fn swap_in_empty_pool_fails() {
+    // Arrange
+    let mut test_runner = TestEnvironment::new([dec!("0.5"), dec!("0.5")], dec!(0));
+
+    // Act
+    let receipt = test_runner.swap(0, dec!(10), 1, true);
+
+    // Assert
+    receipt.expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::ApplicationError(ApplicationError::WeightedPoolError(
+                WeightedPoolError::ZeroReservesForSwap
+            ))
+        )
+    });
+}
+
+struct TestEnvironment<const N: usize> {
+    test_runner: DefaultTestRunner,
+
+    pool_component_address: ComponentAddress,
+
+    pool_resources: [ResourceAddress; N],
+
+    account_public_key: PublicKey,
+    account_component_address: ComponentAddress,
+}
+
+impl<const N: usize> TestEnvironment<N> {
+    pub fn new(weights: [Decimal; N], swap_fee: Decimal) -> Self {
+        match Self::try_new(weights, swap_fee) {
+            Ok(test_environment) => test_environment,
+            Err(receipt) => panic!("Pool instantiation failed: {:?}", receipt),
+        }
+    }
+
+    pub fn try_new(weights: [Decimal; N], swap_fee: Decimal) -> Result<Self, TransactionReceipt> {
+        let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+        let (public_key, _, account) = test_runner.new_account(false);
+        let virtual_signature_badge = NonFungibleGlobalId::from_public_key(&public_key);
+
+        let resource_addresses = weights.map(|_| {
+            test_runner.create_freely_mintable_and_burnable_fungible_resource(
+                OwnerRole::None,
+                None,
+                18,
+                account,
+            )
+        });
+
+        let manifest = ManifestBuilder::new()
+            .create_weighted_pool(
+                OwnerRole::None,
+                rule!(require(virtual_signature_badge)),
+                resource_addresses.into_iter().zip(weights).collect(),
+                swap_fee,
+            )
+            .build();
+        let receipt = test_runner.execute_manifest_ignoring_fee(manifest, vec![]);
+        if !receipt.is_commit_success() {
+            return Err(receipt);
+        }
+        let pool_component = receipt.expect_commit_success().new_component_addresses()[0];
+
+        Ok(Self {
+            test_runner,
+            pool_component_address: pool_component,
+            pool_resources: resource_addresses,
+            account_public_key: public_key.into(),
+            account_component_address: account,
+        })
+    }
+
+    pub fn contribute(&mut self, amounts: [Decimal; N], sign: bool) -> TransactionReceipt {
+        let mut manifest_builder = ManifestBuilder::new();
+        for (resource_address, amount) in self.pool_resources.iter().zip(amounts) {
+            manifest_builder = manifest_builder.mint_fungible(*resource_address, amount)
+        }
+        let manifest = manifest_builder
+            .call_method(
+                self.pool_component_address,
+                WEIGHTED_POOL_CONTRIBUTE_IDENT,
+                manifest_args!(ManifestExpression::EntireWorktop),
+            )
+            .try_deposit_entire_worktop_or_abort(self.account_component_address, None)
+            .build();
+        self.execute_manifest(manifest, sign)
+    }
+
+    pub fn swap(
+        &mut self,
+        input_index: usize,
+        input_amount: Decimal,
+        output_index: usize,
+        sign: bool,
+    ) -> TransactionReceipt {
+        let input_resource_address = self.pool_resources[input_index];
+        let manifest = ManifestBuilder::new()
+            .mint_fungible(input_resource_address, input_amount)
+            .take_all_from_worktop(input_resource_address, "input")
+            .swap_in_pool(
+                self.pool_component_address,
+                "input",
+                self.pool_resources[output_index],
+            )
+            .try_deposit_entire_worktop_or_abort(self.account_component_address, None)
+            .build();
+        self.execute_manifest(manifest, sign)
+    }
+
+    pub fn get_swap_output_amount(
+        &mut self,
+        input_index: usize,
+        input_amount: Decimal,
+        output_index: usize,
+    ) -> WeightedPoolGetSwapOutputAmountOutput {
+        let manifest = ManifestBuilder::new()
+            .call_method(
+                self.pool_component_address,
+                WEIGHTED_POOL_GET_SWAP_OUTPUT_AMOUNT_IDENT,
+                WeightedPoolGetSwapOutputAmountManifestInput {
+                    input_resource_address: self.pool_resources[input_index],
+                    input_amount,
+                    output_resource_address: self.pool_resources[output_index],
+                },
+            )
+            .build();
+        let receipt = self.execute_manifest(manifest, false);
+        receipt.expect_commit_success().output(1)
+    }
+
+    pub fn get_vault_amounts(&mut self) -> [Decimal; N] {
+        let manifest = ManifestBuilder::new()
+            .call_method(
+                self.pool_component_address,
+                WEIGHTED_POOL_GET_VAULT_AMOUNTS_IDENT,
+                WeightedPoolGetVaultAmountsManifestInput,
+            )
+            .build();
+        let receipt = self.execute_manifest(manifest, false);
+        let vault_amounts: WeightedPoolGetVaultAmountsOutput =
+            receipt.expect_commit_success().output(1);
+        self.pool_resources
+            .map(|resource_address| *vault_amounts.get(&resource_address).unwrap())
+    }
+
+    pub fn balance(&mut self, index: usize) -> Decimal {
+        self.test_runner
+            .get_component_balance(self.account_component_address, self.pool_resources[index])
+    }
+
+    fn execute_manifest(
+        &mut self,
+        manifest: TransactionManifestV1,
+        sign: bool,
+    ) -> TransactionReceipt {
+        let initial_proofs = if sign {
+            vec![NonFungibleGlobalId::from_public_key(
+                &self.account_public_key,
+            )]
+        } else {
+            vec![]
+        };
+        self.test_runner
+            .execute_manifest_ignoring_fee(manifest, initial_proofs)
+    }
+}
@@ -0,0 +1,167 @@
+use radix_engine::errors::{RuntimeError, SystemModuleError};
+use radix_engine::system::system_modules::auth::AuthError;
+use radix_engine::transaction::{CostingParameters, ExecutionConfig, TransactionReceipt};
+use radix_engine::types::*;
+use radix_engine_interface::blueprints::transaction_processor::{
+    TRANSACTION_PROCESSOR_BLUEPRINT, TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT,
+};
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+#[derive(Debug, Eq, PartialEq, ManifestSbor)]
+pub struct ManifestTransactionProcessorRunSubintentInput {
+    pub subintent_index: u32,
+    pub manifest_encoded_instructions: Vec<u8>,
+    pub references: Vec<()>,
+    pub blobs: IndexMap<Hash, Vec<u8>>,
+    pub args: (),
+}
+
+fn execute_with_subintent(
+    test_runner: &mut DefaultTestRunner,
+    manifest: TransactionManifestV1,
+    child_manifest: TransactionManifestV1,
+    child_signer: &Secp256k1PrivateKey,
+) -> TransactionReceipt {
+    let current_epoch = test_runner.get_current_epoch();
+    let child = SubintentBuilder::new()
+        .header(SubintentHeaderV2 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: current_epoch,
+            end_epoch_exclusive: current_epoch.after(10).unwrap(),
+            nonce: test_runner.next_transaction_nonce(),
+        })
+        .manifest(child_manifest)
+        .sign(child_signer)
+        .build();
+
+    let notary = Secp256k1PrivateKey::from_u64(1).unwrap();
+    let transaction = TransactionV2Builder::new()
+        .header(TransactionHeaderV1 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: current_epoch,
+            end_epoch_exclusive: current_epoch.after(10).unwrap(),
+            nonce: test_runner.next_transaction_nonce(),
+            notary_public_key: notary.public_key().into(),
+            notary_is_signatory: true,
+            tip_percentage: 0,
+        })
+        .manifest(manifest)
+        .add_child(child)
+        .notarize(&notary)
+        .build();
+
+    let validated = NotarizedTransactionValidator::new(ValidationConfig::simulator())
+        .validate_v2_from_payload_bytes(&transaction.to_payload_bytes().unwrap())
+        .expect("Expected transaction to be valid");
+    test_runner.execute_transaction(
+        validated.get_executable(),
+        CostingParameters::default(),
+        ExecutionConfig::for_notarized_transaction(NetworkDefinition::simulator()),
+    )
+}
+
+#[test]
+fn subintent_can_withdraw_from_its_signer_account_and_yield_to_parent() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, child_key, child_account) = test_runner.new_allocated_account();
+    let (_, _, parent_account) = test_runner.new_allocated_account();
+    let parent_balance = test_runner.get_component_balance(parent_account, XRD);
+
+    // Act
+    let child_manifest = ManifestBuilder::new()
+        .withdraw_from_account(child_account, XRD, dec!(10))
+        .take_all_from_worktop(XRD, "bucket")
+        .then(|builder| {
+            let bucket = builder.bucket("bucket");
+            builder.yield_to_parent(manifest_args!(bucket))
+        })
+        .build();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .yield_to_child(0, ())
+        .try_deposit_entire_worktop_or_abort(parent_account, None)
+        .build();
+    let receipt = execute_with_subintent(&mut test_runner, manifest, child_manifest, &child_key);
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(
+        test_runner.get_component_balance(parent_account, XRD),
+        parent_balance + dec!(10)
+    );
+}
+
+#[test]
+fn subintent_cannot_use_the_proofs_of_the_root_signers() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, child_key, _) = test_runner.new_allocated_account();
+    let notary = Secp256k1PrivateKey::from_u64(1).unwrap();
+    let notary_account = ComponentAddress::virtual_account_from_public_key(&notary.public_key());
+
+    // Act
+    let child_manifest = ManifestBuilder::new()
+        .withdraw_from_account(notary_account, XRD, dec!(10))
+        .take_all_from_worktop(XRD, "bucket")
+        .then(|builder| {
+            let bucket = builder.bucket("bucket");
+            builder.yield_to_parent(manifest_args!(bucket))
+        })
+        .build();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .yield_to_child(0, ())
+        .try_deposit_entire_worktop_or_abort(notary_account, None)
+        .build();
+    let receipt = execute_with_subintent(&mut test_runner, manifest, child_manifest, &child_key);
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::SystemModuleError(SystemModuleError::AuthError(AuthError::Unauthorized(
+                ..
+            )))
+        )
+    });
+}
+
+#[test]
+fn should_not_be_able_to_run_subintent_from_manifest() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let instructions = vec![InstructionV1::YieldToParent {
+        args: ManifestValue::Tuple { fields: vec![] },
+    }];
+    let manifest_encoded_instructions = manifest_encode(&instructions).unwrap();
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            TRANSACTION_PROCESSOR_PACKAGE,
+            TRANSACTION_PROCESSOR_BLUEPRINT,
+            TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT,
+            ManifestTransactionProcessorRunSubintentInput {
+                subintent_index: 0,
+                manifest_encoded_instructions,
+                references: vec![],
+                blobs: index_map_new(),
+                args: (),
+            },
+        )
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::SystemModuleError(SystemModuleError::AuthError(AuthError::Unauthorized(
+                ..
+            )))
+        )
+    });
+}
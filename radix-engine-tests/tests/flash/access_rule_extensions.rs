@@ -0,0 +1,78 @@
+use radix_engine::errors::{RuntimeError, SystemError};
+use radix_engine::system::system_type_checker::TypeCheckError;
+use radix_engine::types::*;
+use radix_engine::utils::generate_access_rule_extensions_state_updates;
+use radix_engine_interface::blueprints::resource::{after_epoch, require, require_n_of_rules};
+use radix_engine_store_interface::db_key_mapper::SpreadPrefixKeyMapper;
+use radix_engine_store_interface::interface::CommittableSubstateDatabase;
+use scrypto_unit::TestRunnerBuilder;
+use transaction::prelude::*;
+
+#[test]
+fn setting_count_of_rule_without_state_flash_should_fail() {
+    run_flash_test(
+        rule!(require_n_of_rules(1u8, vec![require(XRD)])),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn setting_count_of_rule_with_state_flash_should_succeed() {
+    run_flash_test(
+        rule!(require_n_of_rules(1u8, vec![require(XRD)])),
+        true,
+        true,
+    );
+}
+
+#[test]
+fn setting_time_rule_without_state_flash_should_fail() {
+    run_flash_test(rule!(after_epoch(Epoch::of(10))), false, false);
+}
+
+#[test]
+fn setting_time_rule_with_state_flash_should_succeed() {
+    run_flash_test(rule!(after_epoch(Epoch::of(10))), true, true);
+}
+
+#[test]
+fn setting_genesis_rule_without_state_flash_should_succeed() {
+    run_flash_test(rule!(require(XRD)), false, true);
+}
+
+fn run_flash_test(rule: AccessRule, flash_substates: bool, expect_success: bool) {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new()
+        .without_access_rule_extensions_update()
+        .build();
+    let resource_address =
+        test_runner.create_everything_allowed_non_fungible_resource(OwnerRole::None);
+    if flash_substates {
+        let substate_db = test_runner.substate_db_mut();
+        let state_updates = generate_access_rule_extensions_state_updates(substate_db);
+        let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+        substate_db.commit(&db_updates);
+    }
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .set_main_role(resource_address, "minter", rule)
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    if expect_success {
+        receipt.expect_commit_success();
+    } else {
+        receipt.expect_specific_failure(|e| {
+            matches!(
+                e,
+                RuntimeError::SystemError(SystemError::TypeCheckError(
+                    TypeCheckError::BlueprintPayloadValidationError(..)
+                ))
+            )
+        });
+    }
+}
@@ -23,6 +23,7 @@ fn run_flash_test(flash_substates: bool, expect_success: bool) {
     // Arrange
     let mut test_runner = TestRunnerBuilder::new()
         .without_crypto_utils_update()
+        .without_scrypto_collections_update()
         .with_custom_genesis(CustomGenesis::default(
             Epoch::of(1),
             CustomGenesis::default_consensus_manager_config(),
@@ -401,3 +401,52 @@ fn single_sided_contributions_to_multi_resource_pool_are_only_allowed_after_prot
         dec!(200)
     );
 }
+
+#[test]
+fn weighted_and_stable_swap_pools_can_only_be_instantiated_after_protocol_update() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new()
+        .without_pools_v1_1()
+        .without_trace()
+        .build();
+
+    let (pk, _, account) = test_runner.new_account(false);
+    let virtual_signature_badge = NonFungibleGlobalId::from_public_key(&pk);
+
+    let fungible1 = test_runner.create_fungible_resource(dec!(200), 18, account);
+    let fungible2 = test_runner.create_fungible_resource(dec!(200), 18, account);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_weighted_pool(
+            OwnerRole::None,
+            rule!(require(virtual_signature_badge.clone())),
+            indexmap! {fungible1 => dec!("0.8"), fungible2 => dec!("0.2")},
+            dec!(0),
+        )
+        .create_stable_swap_pool(
+            OwnerRole::None,
+            rule!(require(virtual_signature_badge)),
+            indexset! {fungible1, fungible2},
+            dec!(100),
+            dec!(0),
+        )
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    test_runner
+        .execute_manifest(manifest.clone(), vec![])
+        .expect_commit_failure();
+
+    // Act
+    {
+        let substate_db = test_runner.substate_db_mut();
+        let state_updates = generate_pools_v1_1_state_updates(substate_db);
+        let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+        substate_db.commit(&db_updates);
+    }
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    receipt.expect_commit_success();
+    test_runner.check_database();
+}
@@ -0,0 +1,57 @@
+use radix_engine::blueprints::package::PackageError;
+use radix_engine::errors::{ApplicationError, RuntimeError};
+use radix_engine::types::*;
+use radix_engine::utils::generate_vm_boot_scrypto_collections_state_updates;
+use radix_engine_store_interface::db_key_mapper::SpreadPrefixKeyMapper;
+use radix_engine_store_interface::interface::CommittableSubstateDatabase;
+use radix_engine_tests::common::PackageLoader;
+use scrypto_unit::TestRunnerBuilder;
+
+#[test]
+fn publishing_kv_store_iteration_without_state_flash_should_fail() {
+    run_flash_test("kv_store", false, false);
+}
+
+#[test]
+fn publishing_kv_store_iteration_with_state_flash_should_succeed() {
+    run_flash_test("kv_store", true, true);
+}
+
+#[test]
+fn publishing_index_collections_without_state_flash_should_fail() {
+    run_flash_test("collections", false, false);
+}
+
+#[test]
+fn publishing_index_collections_with_state_flash_should_succeed() {
+    run_flash_test("collections", true, true);
+}
+
+fn run_flash_test(package: &str, flash_substates: bool, expect_success: bool) {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new()
+        .without_scrypto_collections_update()
+        .build();
+    if flash_substates {
+        let state_updates = generate_vm_boot_scrypto_collections_state_updates();
+        let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+        test_runner.substate_db_mut().commit(&db_updates);
+    }
+
+    // Act
+    let receipt = test_runner.try_publish_package(PackageLoader::get(package));
+
+    // Assert
+    if expect_success {
+        receipt.expect_commit_success();
+    } else {
+        receipt.expect_specific_failure(|e| {
+            matches!(
+                e,
+                RuntimeError::ApplicationError(ApplicationError::PackageError(
+                    PackageError::InvalidWasm(..)
+                ))
+            )
+        });
+    }
+}
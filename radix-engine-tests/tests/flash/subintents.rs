@@ -0,0 +1,110 @@
+use scrypto_test::prelude::*;
+use scrypto_unit::*;
+
+fn execute_with_subintent(
+    test_runner: &mut DefaultTestRunner,
+    manifest: TransactionManifestV1,
+    child_manifest: TransactionManifestV1,
+    child_signer: &Secp256k1PrivateKey,
+) -> TransactionReceipt {
+    let current_epoch = test_runner.get_current_epoch();
+    let child = SubintentBuilder::new()
+        .header(SubintentHeaderV2 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: current_epoch,
+            end_epoch_exclusive: current_epoch.after(10).unwrap(),
+            nonce: test_runner.next_transaction_nonce(),
+        })
+        .manifest(child_manifest)
+        .sign(child_signer)
+        .build();
+
+    let notary = Secp256k1PrivateKey::from_u64(1).unwrap();
+    let transaction = TransactionV2Builder::new()
+        .header(TransactionHeaderV1 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: current_epoch,
+            end_epoch_exclusive: current_epoch.after(10).unwrap(),
+            nonce: test_runner.next_transaction_nonce(),
+            notary_public_key: notary.public_key().into(),
+            notary_is_signatory: true,
+            tip_percentage: 0,
+        })
+        .manifest(manifest)
+        .add_child(child)
+        .notarize(&notary)
+        .build();
+
+    let validated = NotarizedTransactionValidator::new(ValidationConfig::simulator())
+        .validate_v2_from_payload_bytes(&transaction.to_payload_bytes().unwrap())
+        .expect("Expected transaction to be valid");
+    test_runner.execute_transaction(
+        validated.get_executable(),
+        CostingParameters::default(),
+        ExecutionConfig::for_notarized_transaction(NetworkDefinition::simulator()),
+    )
+}
+
+#[test]
+fn database_is_consistent_before_and_after_protocol_update() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new()
+        .without_subintents_update()
+        .without_trace()
+        .build();
+    test_runner.check_database();
+
+    // Act
+    {
+        let substate_db = test_runner.substate_db_mut();
+        let state_updates = generate_subintents_state_updates(substate_db);
+        let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+        substate_db.commit(&db_updates);
+    }
+
+    // Assert
+    test_runner.check_database();
+}
+
+#[test]
+fn subintents_can_only_be_run_after_protocol_update() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new()
+        .without_subintents_update()
+        .without_trace()
+        .build();
+    let (_, child_key, child_account) = test_runner.new_allocated_account();
+    let (_, _, parent_account) = test_runner.new_allocated_account();
+    let child_manifest = ManifestBuilder::new()
+        .withdraw_from_account(child_account, XRD, dec!(10))
+        .take_all_from_worktop(XRD, "bucket")
+        .then(|builder| {
+            let bucket = builder.bucket("bucket");
+            builder.yield_to_parent(manifest_args!(bucket))
+        })
+        .build();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .yield_to_child(0, ())
+        .try_deposit_entire_worktop_or_abort(parent_account, None)
+        .build();
+    execute_with_subintent(
+        &mut test_runner,
+        manifest.clone(),
+        child_manifest.clone(),
+        &child_key,
+    )
+    .expect_commit_failure();
+
+    // Act
+    {
+        let substate_db = test_runner.substate_db_mut();
+        let state_updates = generate_subintents_state_updates(substate_db);
+        let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+        substate_db.commit(&db_updates);
+    }
+
+    // Assert
+    let receipt = execute_with_subintent(&mut test_runner, manifest, child_manifest, &child_key);
+    receipt.expect_commit_success();
+}
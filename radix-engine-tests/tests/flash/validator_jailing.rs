@@ -0,0 +1,116 @@
+use radix_engine::blueprints::consensus_manager::ValidatorError;
+use radix_engine::errors::{ApplicationError, RuntimeError};
+use scrypto_test::prelude::*;
+use scrypto_unit::*;
+
+fn jailing_config() -> ValidatorJailingConfig {
+    ValidatorJailingConfig {
+        num_unreliable_epochs_before_jailing: 2,
+        num_jail_epochs: 1,
+        slash_factor: dec!("0.1"),
+        slashed_xrd_destination: SlashedXrdDestination::Burn,
+    }
+}
+
+#[test]
+fn database_is_consistent_before_and_after_protocol_update() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new()
+        .without_validator_jailing_update()
+        .without_trace()
+        .build();
+    let (pub_key, _, account) = test_runner.new_account(false);
+    test_runner.new_validator_with_pub_key(pub_key, account);
+    test_runner.check_database();
+
+    // Act
+    {
+        let substate_db = test_runner.substate_db_mut();
+        let state_updates =
+            generate_validator_jailing_state_updates(substate_db, Some(jailing_config()));
+        let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+        substate_db.commit(&db_updates);
+    }
+
+    // Assert
+    test_runner.check_database();
+    test_runner
+        .advance_to_round(Round::of(1))
+        .expect_commit_success();
+    test_runner.check_database();
+}
+
+#[test]
+#[should_panic(expected = "Invalid validator jailing configuration")]
+fn protocol_update_rejects_slash_factor_above_one() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new()
+        .without_validator_jailing_update()
+        .without_trace()
+        .build();
+    let config = ValidatorJailingConfig {
+        slash_factor: dec!("1.1"),
+        ..jailing_config()
+    };
+
+    // Act
+    generate_validator_jailing_state_updates(test_runner.substate_db(), Some(config));
+}
+
+#[test]
+#[should_panic(expected = "Invalid validator jailing configuration")]
+fn protocol_update_rejects_negative_slash_factor() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new()
+        .without_validator_jailing_update()
+        .without_trace()
+        .build();
+    let config = ValidatorJailingConfig {
+        slash_factor: dec!("-0.1"),
+        ..jailing_config()
+    };
+
+    // Act
+    generate_validator_jailing_state_updates(test_runner.substate_db(), Some(config));
+}
+
+#[test]
+fn validator_can_only_be_unjailed_after_protocol_update() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new()
+        .without_validator_jailing_update()
+        .without_trace()
+        .build();
+    let (pub_key, _, account) = test_runner.new_account(false);
+    let validator_address = test_runner.new_validator_with_pub_key(pub_key, account);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(
+            account,
+            VALIDATOR_OWNER_BADGE,
+            [NonFungibleLocalId::bytes(validator_address.as_node_id().0).unwrap()],
+        )
+        .unjail_validator(validator_address)
+        .build();
+    let initial_proofs = vec![NonFungibleGlobalId::from_public_key(&pub_key)];
+    test_runner
+        .execute_manifest(manifest.clone(), initial_proofs.clone())
+        .expect_commit_failure();
+
+    // Act
+    {
+        let substate_db = test_runner.substate_db_mut();
+        let state_updates =
+            generate_validator_jailing_state_updates(substate_db, Some(jailing_config()));
+        let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+        substate_db.commit(&db_updates);
+    }
+
+    // Assert
+    let receipt = test_runner.execute_manifest(manifest, initial_proofs);
+    receipt.expect_specific_failure(|e| {
+        e == &RuntimeError::ApplicationError(ApplicationError::ValidatorError(
+            ValidatorError::ValidatorIsNotJailed,
+        ))
+    });
+}
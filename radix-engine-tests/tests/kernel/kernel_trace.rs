@@ -0,0 +1,98 @@
+use radix_engine::system::system_modules::kernel_trace::{
+    KernelTraceEvent, KernelTraceRecord, KernelTraceSink, KernelTraceWriter,
+};
+use radix_engine::transaction::{CostingParameters, ExecutionConfig, TransactionReceipt};
+use radix_engine::types::*;
+use scrypto_unit::*;
+use std::sync::{Arc, Mutex};
+use transaction::prelude::*;
+
+fn execute_with_kernel_trace_sink(sink: KernelTraceSink) -> TransactionReceipt {
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_from_account(account, XRD, 10)
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+
+    test_runner.execute_transaction(
+        TestTransaction::new_from_nonce(manifest, 1)
+            .prepare()
+            .unwrap()
+            .get_executable(btreeset!(NonFungibleGlobalId::from_public_key(&public_key))),
+        CostingParameters::default(),
+        ExecutionConfig::for_test_transaction().with_kernel_trace_sink(sink),
+    )
+}
+
+#[test]
+fn kernel_trace_can_be_collected_in_the_receipt() {
+    // Act
+    let receipt = execute_with_kernel_trace_sink(KernelTraceSink::Receipt);
+
+    // Assert
+    receipt.expect_commit_success();
+    let records = receipt.kernel_trace.as_ref().unwrap();
+    match &records[0].event {
+        KernelTraceEvent::Invoke { actor, .. } => {
+            assert_eq!(records[0].depth, 0);
+            assert!(actor.contains("TransactionProcessor"));
+        }
+        event => panic!("Unexpected first event: {:?}", event),
+    }
+
+    let invocations: Vec<usize> = records
+        .iter()
+        .filter(|record| matches!(record.event, KernelTraceEvent::Invoke { .. }))
+        .map(|record| record.depth)
+        .collect();
+    let returns: Vec<usize> = records
+        .iter()
+        .filter(|record| matches!(record.event, KernelTraceEvent::Return { .. }))
+        .map(|record| record.depth)
+        .collect();
+    assert_eq!(invocations.len(), returns.len());
+    assert!(records.iter().any(
+        |record| matches!(record.event, KernelTraceEvent::CreateNode { size, .. } if size > 0)
+    ));
+    assert!(records
+        .iter()
+        .any(|record| matches!(record.event, KernelTraceEvent::WriteSubstate { .. })));
+}
+
+#[derive(Debug, Default)]
+struct JsonLinesWriter {
+    lines: Vec<String>,
+}
+
+impl KernelTraceWriter for JsonLinesWriter {
+    fn write(&mut self, record: &KernelTraceRecord) {
+        self.lines.push(record.to_json());
+    }
+}
+
+#[test]
+fn kernel_trace_can_be_written_as_json_lines() {
+    // Arrange
+    let writer = Arc::new(Mutex::new(JsonLinesWriter::default()));
+
+    // Act
+    let receipt = execute_with_kernel_trace_sink(KernelTraceSink::Writer(writer.clone()));
+
+    // Assert
+    receipt.expect_commit_success();
+    assert!(receipt.kernel_trace.is_none());
+    let records: Vec<serde_json::Value> = writer
+        .lock()
+        .unwrap()
+        .lines
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records[0]["type"], "Invoke");
+    assert_eq!(records[0]["depth"], 0);
+    assert!(records
+        .iter()
+        .any(|record| record["type"] == "ReadSubstate" && record["size"].as_u64().unwrap() > 0));
+}
@@ -146,6 +146,7 @@ impl KernelSubstateApi<SystemLockData> for MockKernel {
         &mut self,
         _: &NodeId,
         _: PartitionNumber,
+        _: ScanKeysFrom,
         _: u32,
     ) -> Result<Vec<SubstateKey>, RuntimeError> {
         panic1!()
@@ -0,0 +1,237 @@
+#[cfg(not(feature = "alloc"))]
+#[cfg(not(feature = "resource_tracker"))]
+mod parallel_execution_test {
+    use radix_engine::kernel::kernel_api::KernelInvocation;
+    use radix_engine::system::actor::Actor;
+    use radix_engine::system::bootstrap::Bootstrapper;
+    use radix_engine::system::system_callback_api::SystemCallbackObject;
+    use radix_engine::system::system_modules::extension::SystemModuleExtension;
+    use radix_engine::transaction::{
+        execute_and_commit_transaction, execute_transactions_in_parallel, CostingParameters,
+        ExecutionConfig, TransactionReceipt, TransactionResult,
+    };
+    use radix_engine::types::*;
+    use radix_engine::vm::wasm::DefaultWasmEngine;
+    use radix_engine::vm::{DefaultNativeVm, ScryptoVm, Vm};
+    use radix_engine_interface::rule;
+    use radix_engine_stores::memory_db::InMemorySubstateDatabase;
+    use std::sync::{Arc, Mutex};
+    use transaction::model::{PreparedTestTransaction, TestTransaction};
+    use transaction::prelude::*;
+
+    fn assert_receipts_equal(parallel: &TransactionReceipt, sequential: &TransactionReceipt) {
+        assert_eq!(
+            scrypto_encode(&parallel.fee_summary).unwrap(),
+            scrypto_encode(&sequential.fee_summary).unwrap()
+        );
+        assert_eq!(
+            scrypto_encode(&parallel.result).unwrap(),
+            scrypto_encode(&sequential.result).unwrap()
+        );
+    }
+
+    fn prepare(manifests: Vec<TransactionManifestV1>, nonce: &str) -> Vec<PreparedTestTransaction> {
+        manifests
+            .into_iter()
+            .enumerate()
+            .map(|(i, manifest)| {
+                TestTransaction::new(manifest, hash(format!("{}: {}", nonce, i)))
+                    .prepare()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    /// Bootstraps a database with two funded accounts, returning the accounts and the proofs of
+    /// their owners.
+    fn bootstrap_with_funded_accounts<V: SystemCallbackObject + Clone>(
+        vm: &V,
+    ) -> (
+        InMemorySubstateDatabase,
+        [ComponentAddress; 2],
+        BTreeSet<NonFungibleGlobalId>,
+    ) {
+        let mut substate_db = InMemorySubstateDatabase::standard();
+        Bootstrapper::new(
+            NetworkDefinition::simulator(),
+            &mut substate_db,
+            vm.clone(),
+            false,
+        )
+        .bootstrap_test_default()
+        .unwrap();
+        let public_key1 = Secp256k1PrivateKey::from_u64(1).unwrap().public_key();
+        let public_key2 = Secp256k1PrivateKey::from_u64(2).unwrap().public_key();
+        let account1 = ComponentAddress::virtual_account_from_public_key(&public_key1);
+        let account2 = ComponentAddress::virtual_account_from_public_key(&public_key2);
+        let initial_proofs = btreeset![
+            NonFungibleGlobalId::from_public_key(&public_key1),
+            NonFungibleGlobalId::from_public_key(&public_key2)
+        ];
+
+        let funding = [account1, account2]
+            .into_iter()
+            .map(|account| {
+                ManifestBuilder::new()
+                    .lock_fee_from_faucet()
+                    .get_free_xrd_from_faucet()
+                    .try_deposit_entire_worktop_or_abort(account, None)
+                    .build()
+            })
+            .collect();
+        for prepared in prepare(funding, "Funding") {
+            execute_and_commit_transaction(
+                &mut substate_db,
+                vm.clone(),
+                &CostingParameters::default(),
+                &ExecutionConfig::for_test_transaction(),
+                &prepared.get_executable(initial_proofs.clone()),
+            )
+            .expect_commit_success();
+        }
+
+        (substate_db, [account1, account2], initial_proofs)
+    }
+
+    fn transfer(account: ComponentAddress) -> TransactionManifestV1 {
+        ManifestBuilder::new()
+            .lock_fee(account, 10)
+            .withdraw_from_account(account, XRD, 1)
+            .deposit_batch(account)
+            .build()
+    }
+
+    #[test]
+    fn parallel_execution_should_yield_the_same_receipts_as_sequential_execution() {
+        // Arrange
+        let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
+        let vm = Vm {
+            scrypto_vm: &scrypto_vm,
+            native_vm: DefaultNativeVm::new(),
+        };
+        let (substate_db, [account1, account2], initial_proofs) =
+            bootstrap_with_funded_accounts(&vm);
+        let public_key1 = Secp256k1PrivateKey::from_u64(1).unwrap().public_key();
+
+        let manifests = vec![
+            // Rejected, as no fee is locked
+            ManifestBuilder::new().get_free_xrd_from_faucet().build(),
+            transfer(account1),
+            // Only shares the fee substates with the previous one
+            transfer(account2),
+            // Only locks a fee from the account written by the second one
+            ManifestBuilder::new()
+                .lock_fee(account1, 10)
+                .new_account_advanced(
+                    OwnerRole::Fixed(rule!(require(NonFungibleGlobalId::from_public_key(
+                        &public_key1
+                    )))),
+                    None,
+                )
+                .build(),
+            // Withdraws from the account written by the third one
+            transfer(account2),
+        ];
+        let prepared = prepare(manifests, "Parallel");
+        let executables = prepared
+            .iter()
+            .map(|prepared| prepared.get_executable(initial_proofs.clone()))
+            .collect::<Vec<_>>();
+
+        // Act
+        let result = execute_transactions_in_parallel(
+            &substate_db,
+            vm.clone(),
+            &CostingParameters::default(),
+            &ExecutionConfig::for_test_transaction(),
+            &executables,
+        );
+
+        // Assert
+        let mut sequential_db = substate_db.clone();
+        for (executable, parallel_receipt) in executables.iter().zip(&result.receipts) {
+            let sequential_receipt = execute_and_commit_transaction(
+                &mut sequential_db,
+                vm.clone(),
+                &CostingParameters::default(),
+                &ExecutionConfig::for_test_transaction(),
+                executable,
+            );
+            assert_receipts_equal(parallel_receipt, &sequential_receipt);
+        }
+        // The updates of the fee substates are rebased, so only the transaction withdrawing from
+        // an account written by an earlier one is re-executed.
+        assert!(matches!(
+            result.receipts[0].result,
+            TransactionResult::Reject(_)
+        ));
+        assert_eq!(result.re_executed, vec![4]);
+    }
+
+    #[derive(Debug, Default)]
+    struct LockFeeCounter {
+        lock_fees: usize,
+    }
+
+    impl SystemModuleExtension for LockFeeCounter {
+        fn name(&self) -> String {
+            "LockFeeCounter".to_string()
+        }
+
+        fn before_invoke(
+            &mut self,
+            _depth: usize,
+            invocation: &KernelInvocation<Actor>,
+        ) -> Result<(), String> {
+            if let Actor::Method(method_actor) = &invocation.call_frame_data {
+                if method_actor.ident == ACCOUNT_LOCK_FEE_IDENT {
+                    self.lock_fees += 1;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn system_module_extensions_should_observe_each_transaction_once() {
+        // Arrange
+        let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
+        let vm = Vm {
+            scrypto_vm: &scrypto_vm,
+            native_vm: DefaultNativeVm::new(),
+        };
+        let (substate_db, [account1, account2], initial_proofs) =
+            bootstrap_with_funded_accounts(&vm);
+        let manifests = vec![transfer(account1), transfer(account2), transfer(account1)];
+        let prepared = prepare(manifests, "Extension");
+        let executables = prepared
+            .iter()
+            .map(|prepared| prepared.get_executable(initial_proofs.clone()))
+            .collect::<Vec<_>>();
+        let counter = Arc::new(Mutex::new(LockFeeCounter::default()));
+
+        // Act
+        let result = execute_transactions_in_parallel(
+            &substate_db,
+            vm.clone(),
+            &CostingParameters::default(),
+            &ExecutionConfig::for_test_transaction()
+                .with_system_module_extension(counter.clone(), false),
+            &executables,
+        );
+
+        // Assert
+        assert_eq!(counter.lock().unwrap().lock_fees, 3);
+        let mut sequential_db = substate_db.clone();
+        for (executable, parallel_receipt) in executables.iter().zip(&result.receipts) {
+            let sequential_receipt = execute_and_commit_transaction(
+                &mut sequential_db,
+                vm.clone(),
+                &CostingParameters::default(),
+                &ExecutionConfig::for_test_transaction(),
+                executable,
+            );
+            assert_receipts_equal(parallel_receipt, &sequential_receipt);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use radix_engine_interface::blueprints::resource::{
+    after_epoch, after_instant, before_epoch, require, require_n_of_rules, FromPublicKey,
+};
+use radix_engine_interface::{metadata, metadata_init, mint_roles};
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn create_resource_with_minter_rule(
+    test_runner: &mut DefaultTestRunner,
+    account: ComponentAddress,
+    minter_rule: AccessRule,
+) -> ResourceAddress {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_fungible_resource(
+            OwnerRole::None,
+            true,
+            18u8,
+            FungibleResourceRoles {
+                mint_roles: mint_roles! {
+                    minter => rule!(deny_all);
+                    minter_updater => rule!(allow_all);
+                },
+                ..Default::default()
+            },
+            metadata!(),
+            None,
+        )
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+    let resource_address = receipt.expect_commit_success().new_resource_addresses()[0];
+
+    // The resource blueprints don't accept these rules, only the role assignment module does
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .set_main_role(resource_address, "minter", minter_rule)
+        .build();
+    test_runner
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success();
+    resource_address
+}
+
+fn mint(
+    test_runner: &mut DefaultTestRunner,
+    account: ComponentAddress,
+    resource_address: ResourceAddress,
+    initial_proofs: Vec<NonFungibleGlobalId>,
+) -> TransactionReceipt {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_amount(account, XRD, 1)
+        .mint_fungible(resource_address, 1)
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    test_runner.execute_manifest(manifest, initial_proofs)
+}
+
+#[test]
+fn before_epoch_rule_should_only_pass_before_the_given_epoch() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let initial_proofs = vec![NonFungibleGlobalId::from_public_key(&public_key)];
+    let deadline = test_runner.get_current_epoch().after(10).unwrap();
+    let resource_address =
+        create_resource_with_minter_rule(&mut test_runner, account, rule!(before_epoch(deadline)));
+
+    // Act & Assert
+    mint(
+        &mut test_runner,
+        account,
+        resource_address,
+        initial_proofs.clone(),
+    )
+    .expect_commit_success();
+    test_runner.set_current_epoch(deadline);
+    mint(&mut test_runner, account, resource_address, initial_proofs)
+        .expect_specific_failure(is_auth_error);
+}
+
+#[test]
+fn after_instant_rule_should_only_pass_from_the_given_instant() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let initial_proofs = vec![NonFungibleGlobalId::from_public_key(&public_key)];
+    let now_ms = test_runner.get_current_proposer_timestamp_ms();
+    let unlock_at = Instant::new(now_ms / 1000 + 60);
+    let resource_address = create_resource_with_minter_rule(
+        &mut test_runner,
+        account,
+        rule!(after_instant(unlock_at)),
+    );
+
+    // Act & Assert
+    mint(
+        &mut test_runner,
+        account,
+        resource_address,
+        initial_proofs.clone(),
+    )
+    .expect_specific_failure(is_auth_error);
+    test_runner
+        .advance_to_round_at_timestamp(Round::of(1), unlock_at.seconds_since_unix_epoch * 1000)
+        .expect_commit_success();
+    mint(&mut test_runner, account, resource_address, initial_proofs).expect_commit_success();
+}
+
+#[test]
+fn count_of_rule_should_pass_once_enough_child_rules_pass() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let initial_proofs = vec![NonFungibleGlobalId::from_public_key(&public_key)];
+    let badge = test_runner.create_non_fungible_resource(account);
+    let activation = test_runner.get_current_epoch().after(10).unwrap();
+    let resource_address = create_resource_with_minter_rule(
+        &mut test_runner,
+        account,
+        rule!(require_n_of_rules(
+            2u8,
+            vec![require(XRD), require(badge), after_epoch(activation)]
+        )),
+    );
+
+    // Act & Assert
+    mint(
+        &mut test_runner,
+        account,
+        resource_address,
+        initial_proofs.clone(),
+    )
+    .expect_specific_failure(is_auth_error);
+    test_runner.set_current_epoch(activation);
+    mint(&mut test_runner, account, resource_address, initial_proofs).expect_commit_success();
+}
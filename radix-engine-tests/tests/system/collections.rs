@@ -0,0 +1,200 @@
+use radix_engine::types::*;
+use radix_engine_tests::common::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn create_leaderboard(test_runner: &mut DefaultTestRunner) -> ComponentAddress {
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("collections"));
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(package_address, "Leaderboard", "new", manifest_args!())
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+    receipt.expect_commit_success().new_component_addresses()[0]
+}
+
+fn submit(
+    test_runner: &mut DefaultTestRunner,
+    component: ComponentAddress,
+    scores: &[(&str, u16)],
+) {
+    let mut builder = ManifestBuilder::new().lock_fee_from_faucet();
+    for (player, score) in scores {
+        builder = builder.call_method(
+            component,
+            "submit",
+            manifest_args!(player.to_string(), *score),
+        );
+    }
+    test_runner
+        .execute_manifest(builder.build(), vec![])
+        .expect_commit_success();
+}
+
+#[test]
+fn sorted_index_scan_should_return_entries_in_sort_prefix_order() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let component = create_leaderboard(&mut test_runner);
+    submit(
+        &mut test_runner,
+        component,
+        &[("alice", 30), ("bob", 50), ("carol", 10), ("dave", 40)],
+    );
+    submit(&mut test_runner, component, &[("carol", 60)]);
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(component, "top_scores", manifest_args!(3u32))
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    let top_scores: Vec<(String, u32)> = receipt.expect_commit_success().output(1);
+    assert_eq!(
+        top_scores,
+        vec![
+            ("carol".to_string(), 60),
+            ("bob".to_string(), 50),
+            ("dave".to_string(), 40),
+        ]
+    );
+}
+
+#[test]
+fn index_scan_should_not_return_removed_entries() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let component = create_leaderboard(&mut test_runner);
+    submit(
+        &mut test_runner,
+        component,
+        &[("alice", 30), ("bob", 50), ("carol", 10)],
+    );
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(component, "withdraw", manifest_args!("bob".to_string()))
+        .call_method(component, "list_players", manifest_args!(10u32))
+        .call_method(component, "top_scores", manifest_args!(10u32))
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    let result = receipt.expect_commit_success();
+    let withdrawn: Option<u32> = result.output(1);
+    assert_eq!(withdrawn, Some(50));
+    let mut players: Vec<String> = result.output(2);
+    players.sort();
+    assert_eq!(players, vec!["alice".to_string(), "carol".to_string()]);
+    let top_scores: Vec<(String, u32)> = result.output(3);
+    assert_eq!(
+        top_scores,
+        vec![("alice".to_string(), 30), ("carol".to_string(), 10)]
+    );
+}
+
+#[test]
+fn index_get_should_return_the_value_of_an_existing_entry() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let component = create_leaderboard(&mut test_runner);
+    submit(&mut test_runner, component, &[("alice", 30), ("bob", 50)]);
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(component, "score", manifest_args!("bob".to_string()))
+        .call_method(component, "score", manifest_args!("carol".to_string()))
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    let result = receipt.expect_commit_success();
+    let score: Option<u32> = result.output(1);
+    assert_eq!(score, Some(50));
+    let score: Option<u32> = result.output(2);
+    assert_eq!(score, None);
+}
+
+#[test]
+fn sorted_index_scan_after_should_continue_from_the_given_entry() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let component = create_leaderboard(&mut test_runner);
+    submit(
+        &mut test_runner,
+        component,
+        &[
+            ("alice", 30),
+            ("bob", 50),
+            ("carol", 10),
+            ("dave", 50),
+            ("erin", 40),
+            ("frank", 50),
+            ("grace", 20),
+        ],
+    );
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(component, "top_scores", manifest_args!(10u32))
+        .build();
+    let all_scores: Vec<(String, u32)> = test_runner
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success()
+        .output(1);
+
+    // Act
+    let mut paged_scores: Vec<(String, u32)> = Vec::new();
+    loop {
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_method(
+                component,
+                "top_scores_after",
+                manifest_args!(paged_scores.last().cloned(), 2u32),
+            )
+            .build();
+        let page: Vec<(String, u32)> = test_runner
+            .execute_manifest(manifest, vec![])
+            .expect_commit_success()
+            .output(1);
+        if page.is_empty() {
+            break;
+        }
+        paged_scores.extend(page);
+    }
+
+    // Assert
+    assert_eq!(all_scores.len(), 7);
+    assert_eq!(paged_scores, all_scores);
+}
+
+#[test]
+fn collections_declared_with_allow_ownership_should_allow_ownership_in_their_schema() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+
+    // Act
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("collections"));
+
+    // Assert
+    let definitions = test_runner.get_package_blueprint_definitions(&package_address);
+    let definition = definitions
+        .get(&BlueprintVersionKey::new_default("Leaderboard"))
+        .unwrap();
+    let allow_ownership: Vec<bool> = definition
+        .interface
+        .state
+        .collections
+        .iter()
+        .map(|(_partition, schema)| match schema {
+            BlueprintCollectionSchema::Index(schema)
+            | BlueprintCollectionSchema::SortedIndex(schema)
+            | BlueprintCollectionSchema::KeyValueStore(schema) => schema.allow_ownership,
+        })
+        .collect();
+    assert_eq!(allow_ownership, vec![false, false, true]);
+}
@@ -0,0 +1,98 @@
+use radix_engine::blueprints::resource::fungible_vault;
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use radix_engine_interface::api::ModuleId;
+use radix_engine_queries::query::{EventFilter, EventStream};
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn withdraw_and_deposit(
+    test_runner: &mut DefaultTestRunner,
+    amounts: &[Decimal],
+) -> (ComponentAddress, Vec<TransactionReceipt>) {
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let (_, _, other_account) = test_runner.new_allocated_account();
+    let receipts = amounts
+        .iter()
+        .map(|amount| {
+            let manifest = ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .withdraw_from_account(account, XRD, *amount)
+                .try_deposit_entire_worktop_or_abort(other_account, None)
+                .build();
+            test_runner.execute_manifest(
+                manifest,
+                vec![NonFungibleGlobalId::from_public_key(&public_key)],
+            )
+        })
+        .collect();
+    (account, receipts)
+}
+
+#[test]
+fn event_stream_filters_by_blueprint_name_and_typed_predicate() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, receipts) = withdraw_and_deposit(&mut test_runner, &[dec!(10), dec!(20)]);
+
+    // Act
+    let filter = EventFilter::new()
+        .of_blueprint(BlueprintId::new(
+            &RESOURCE_PACKAGE,
+            FUNGIBLE_VAULT_BLUEPRINT,
+        ))
+        .named("WithdrawEvent")
+        .matching(|event: &fungible_vault::WithdrawEvent| event.amount == dec!(10));
+    let stream = EventStream::new(test_runner.substate_db(), filter);
+    let events = stream
+        .stream(&receipts)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    // Assert
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.receipt_index, 0);
+    assert_eq!(event.programmatic_json["type_name"], "WithdrawEvent");
+    assert_eq!(event.programmatic_json["fields"][0]["field_name"], "amount");
+    assert_eq!(event.programmatic_json["fields"][0]["value"], "10");
+}
+
+#[test]
+fn event_stream_filters_by_emitter_across_receipts() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (account, receipts) = withdraw_and_deposit(&mut test_runner, &[dec!(10), dec!(20)]);
+    let address_encoder = AddressBech32Encoder::for_simulator();
+
+    // Act
+    let filter = EventFilter::new()
+        .emitted_by(account)
+        .named("WithdrawEvent");
+    let stream =
+        EventStream::new(test_runner.substate_db(), filter).with_address_encoder(&address_encoder);
+    let events = stream
+        .stream(&receipts)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.receipt_index)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    for event in &events {
+        assert_eq!(
+            event.event_type_identifier.0,
+            Emitter::Method(account.into_node_id(), ModuleId::Main)
+        );
+        assert_eq!(event.programmatic_json["variant_name"], "Fungible");
+        assert_eq!(
+            event.programmatic_json["fields"][0]["value"],
+            address_encoder.encode(XRD.as_node_id().as_bytes()).unwrap()
+        );
+    }
+}
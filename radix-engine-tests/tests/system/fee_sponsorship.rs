@@ -0,0 +1,131 @@
+use radix_engine::errors::{RejectionReason, RuntimeError, SystemModuleError};
+use radix_engine::system::system_modules::auth::AuthError;
+use radix_engine::transaction::{CostingParameters, ExecutionConfig, TransactionReceipt};
+use radix_engine::types::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn execute_sponsored_transaction(
+    test_runner: &mut DefaultTestRunner,
+    manifest: TransactionManifestV1,
+    signer: &Secp256k1PrivateKey,
+    sponsor_account: ComponentAddress,
+    sponsor: &Secp256k1PrivateKey,
+) -> TransactionReceipt {
+    let current_epoch = test_runner.get_current_epoch();
+    let transaction = TransactionV2Builder::new()
+        .header(TransactionHeaderV1 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: current_epoch,
+            end_epoch_exclusive: current_epoch.after(10).unwrap(),
+            nonce: test_runner.next_transaction_nonce(),
+            notary_public_key: signer.public_key().into(),
+            notary_is_signatory: true,
+            tip_percentage: 0,
+        })
+        .manifest(manifest)
+        .sponsor(sponsor_account, dec!(100), sponsor)
+        .notarize(signer)
+        .build();
+
+    let validated = NotarizedTransactionValidator::new(ValidationConfig::simulator())
+        .validate_v2_from_payload_bytes(&transaction.to_payload_bytes().unwrap())
+        .expect("Expected transaction to be valid");
+    test_runner.execute_transaction(
+        validated.get_executable(),
+        CostingParameters::default(),
+        ExecutionConfig::for_notarized_transaction(NetworkDefinition::simulator()),
+    )
+}
+
+#[test]
+fn sponsor_can_pay_the_fees_of_a_user_without_xrd() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, sponsor_key, sponsor_account) = test_runner.new_allocated_account();
+    let user_key = Secp256k1PrivateKey::from_u64(1234).unwrap();
+    let sponsor_balance = test_runner.get_component_balance(sponsor_account, XRD);
+
+    // Act
+    let receipt = execute_sponsored_transaction(
+        &mut test_runner,
+        ManifestBuilder::new().drop_auth_zone_proofs().build(),
+        &user_key,
+        sponsor_account,
+        &sponsor_key,
+    );
+
+    // Assert
+    let result = receipt.expect_commit_success();
+    let sponsor = result
+        .fee_source
+        .sponsor()
+        .expect("Expected the fees to be sponsored");
+    assert_eq!(sponsor.account, sponsor_account);
+    assert_eq!(&sponsor.paying_vaults, result.fee_source.paying_vaults());
+    assert_eq!(
+        test_runner.get_component_balance(sponsor_account, XRD),
+        sponsor_balance
+            .checked_sub(receipt.fee_summary.total_cost())
+            .unwrap()
+    );
+}
+
+#[test]
+fn sponsor_cannot_pay_from_an_account_it_does_not_own() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, sponsor_account) = test_runner.new_allocated_account();
+    let not_the_owner_key = Secp256k1PrivateKey::from_u64(4321).unwrap();
+    let user_key = Secp256k1PrivateKey::from_u64(1234).unwrap();
+
+    // Act
+    let receipt = execute_sponsored_transaction(
+        &mut test_runner,
+        ManifestBuilder::new().drop_auth_zone_proofs().build(),
+        &user_key,
+        sponsor_account,
+        &not_the_owner_key,
+    );
+
+    // Assert
+    receipt.expect_specific_rejection(|e| {
+        matches!(
+            e,
+            RejectionReason::ErrorBeforeLoanAndDeferredCostsRepaid(
+                RuntimeError::SystemModuleError(SystemModuleError::AuthError(
+                    AuthError::Unauthorized(..)
+                ))
+            )
+        )
+    });
+}
+
+#[test]
+fn user_proofs_cannot_be_used_to_lock_the_sponsor_fee() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, user_key, user_account) = test_runner.new_allocated_account();
+    let not_the_owner_key = Secp256k1PrivateKey::from_u64(4321).unwrap();
+
+    // Act
+    let receipt = execute_sponsored_transaction(
+        &mut test_runner,
+        ManifestBuilder::new().drop_auth_zone_proofs().build(),
+        &user_key,
+        user_account,
+        &not_the_owner_key,
+    );
+
+    // Assert
+    receipt.expect_specific_rejection(|e| {
+        matches!(
+            e,
+            RejectionReason::ErrorBeforeLoanAndDeferredCostsRepaid(
+                RuntimeError::SystemModuleError(SystemModuleError::AuthError(
+                    AuthError::Unauthorized(..)
+                ))
+            )
+        )
+    });
+}
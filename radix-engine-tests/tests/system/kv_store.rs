@@ -1,9 +1,9 @@
-use radix_engine_tests::common::*;
 use radix_engine::errors::{CallFrameError, KernelError, RuntimeError};
 use radix_engine::kernel::call_frame::{
     OpenSubstateError, ProcessSubstateError, TakeNodeError, WriteSubstateError,
 };
 use radix_engine::types::*;
+use radix_engine_tests::common::*;
 use scrypto_unit::*;
 use transaction::prelude::*;
 
@@ -530,3 +530,154 @@ fn nested_kv_stores_works() {
     // Assert
     receipt.expect_commit_success();
 }
+
+#[test]
+fn iterate_local_map_should_work() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("kv_store"));
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(package_address, "Basic", "iterate_local", manifest_args!())
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    receipt.expect_commit_success();
+}
+
+#[test]
+fn iterate_stored_map_should_skip_removed_entries() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("kv_store"));
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(package_address, "Basic", "new", manifest_args!())
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+    let component = receipt.expect_commit_success().new_component_addresses()[0];
+    let mut builder = ManifestBuilder::new().lock_fee_from_faucet();
+    for i in 0..7u32 {
+        builder = builder.call_method(
+            component,
+            "insert",
+            manifest_args!(i.to_string(), i.to_string()),
+        );
+    }
+    test_runner
+        .execute_manifest(builder.build(), vec![])
+        .expect_commit_success();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(component, "remove", manifest_args!("3".to_string()))
+        .call_method(
+            component,
+            "insert",
+            manifest_args!("7".to_string(), "7".to_string()),
+        )
+        .build();
+    test_runner
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success();
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(component, "keys", manifest_args!(3u32))
+        .call_method(component, "len", manifest_args!())
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    let result = receipt.expect_commit_success();
+    let mut keys: Vec<String> = result.output(1);
+    keys.sort();
+    assert_eq!(keys, vec!["0", "1", "2", "4", "5", "6", "7"]);
+    let len: u32 = result.output(2);
+    assert_eq!(len, 7);
+}
+
+#[test]
+fn iterate_local_and_stored_map_should_yield_the_same_order() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("kv_store"));
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "Basic",
+            "new_with_entries",
+            manifest_args!(10u32, 3u32),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+    let result = receipt.expect_commit_success();
+    let component = result.new_component_addresses()[0];
+    let (_, local_keys): (ComponentAddress, Vec<String>) = result.output(1);
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(component, "keys", manifest_args!(3u32))
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    let stored_keys: Vec<String> = receipt.expect_commit_success().output(1);
+    assert_eq!(local_keys.len(), 10);
+    assert_eq!(stored_keys, local_keys);
+}
+
+#[test]
+fn iterate_stored_map_with_write_locked_entry_should_fail() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("kv_store"));
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "Basic",
+            "new_with_entry",
+            manifest_args!("0".to_string(), "0".to_string()),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+    let component = receipt.expect_commit_success().new_component_addresses()[0];
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(
+            component,
+            "insert",
+            manifest_args!("1".to_string(), "1".to_string()),
+        )
+        .build();
+    test_runner
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success();
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(
+            component,
+            "keys_and_len_with_entry_locked",
+            manifest_args!("1".to_string()),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::KernelError(KernelError::CallFrameError(
+                CallFrameError::OpenSubstateError(OpenSubstateError::SubstateLocked(..))
+            ))
+        )
+    });
+}
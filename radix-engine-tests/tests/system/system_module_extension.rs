@@ -0,0 +1,134 @@
+use radix_engine::errors::{RuntimeError, SystemModuleError};
+use radix_engine::kernel::kernel_api::KernelInvocation;
+use radix_engine::system::actor::Actor;
+use radix_engine::system::system_modules::extension::{
+    SystemModuleExtension, SystemModuleExtensionError,
+};
+use radix_engine::system::system_modules::transaction_runtime::Event;
+use radix_engine::transaction::{CostingParameters, ExecutionConfig, TransactionReceipt};
+use radix_engine::types::*;
+use scrypto_unit::*;
+use std::sync::{Arc, Mutex};
+use transaction::prelude::*;
+
+#[derive(Debug, Default)]
+struct RecordingExtension {
+    fail_on_event: Option<&'static str>,
+    invocations: Vec<(usize, String)>,
+    events: usize,
+}
+
+impl SystemModuleExtension for RecordingExtension {
+    fn name(&self) -> String {
+        "RecordingExtension".to_string()
+    }
+
+    fn before_invoke(
+        &mut self,
+        depth: usize,
+        invocation: &KernelInvocation<Actor>,
+    ) -> Result<(), String> {
+        if let Actor::Method(method_actor) = &invocation.call_frame_data {
+            self.invocations.push((depth, method_actor.ident.clone()));
+        }
+        Ok(())
+    }
+
+    fn on_event(&mut self, event: &Event) -> Result<(), String> {
+        self.events += 1;
+        match self.fail_on_event {
+            Some(event_name) if event.type_identifier.1 == event_name => {
+                Err(format!("{} is not allowed", event_name))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn execute_with_extension(
+    extension: Arc<Mutex<RecordingExtension>>,
+    affects_consensus: bool,
+) -> TransactionReceipt {
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_from_account(account, XRD, 10)
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+
+    test_runner.execute_transaction(
+        TestTransaction::new_from_nonce(manifest, 1)
+            .prepare()
+            .unwrap()
+            .get_executable(btreeset!(NonFungibleGlobalId::from_public_key(&public_key))),
+        CostingParameters::default(),
+        ExecutionConfig::for_test_transaction()
+            .with_system_module_extension(extension, affects_consensus),
+    )
+}
+
+#[test]
+fn system_module_extension_observes_the_transaction() {
+    // Arrange
+    let extension = Arc::new(Mutex::new(RecordingExtension::default()));
+
+    // Act
+    let receipt = execute_with_extension(extension.clone(), false);
+
+    // Assert
+    let commit_result = receipt.expect_commit_success();
+    assert!(receipt.system_module_extension_errors.is_empty());
+    let extension = extension.lock().unwrap();
+    // The events emitted by the fee finalization are not part of the execution
+    assert!(extension.events > 0);
+    assert!(extension.events < commit_result.application_events.len());
+    assert!(extension
+        .invocations
+        .iter()
+        .any(|(depth, ident)| *depth > 0 && ident == "withdraw"));
+}
+
+#[test]
+fn system_module_extension_errors_are_reported_unless_it_affects_consensus() {
+    // Arrange
+    let extension = Arc::new(Mutex::new(RecordingExtension {
+        fail_on_event: Some("WithdrawEvent"),
+        ..Default::default()
+    }));
+
+    // Act
+    let receipt = execute_with_extension(extension, false);
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(
+        receipt.system_module_extension_errors,
+        vec![SystemModuleExtensionError {
+            extension: "RecordingExtension".to_string(),
+            message: "WithdrawEvent is not allowed".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn system_module_extension_affecting_consensus_can_fail_the_transaction() {
+    // Arrange
+    let extension = Arc::new(Mutex::new(RecordingExtension {
+        fail_on_event: Some("WithdrawEvent"),
+        ..Default::default()
+    }));
+
+    // Act
+    let receipt = execute_with_extension(extension, true);
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::SystemModuleError(SystemModuleError::ExtensionError(
+                SystemModuleExtensionError { extension, .. }
+            )) if extension == "RecordingExtension"
+        )
+    });
+}
@@ -0,0 +1,277 @@
+use radix_engine::blueprints::consensus_manager::{
+    ValidatorError, ValidatorJailedEvent, ValidatorRewardAppliedEvent, ValidatorSlashedEvent,
+    ValidatorUnjailedEvent,
+};
+use radix_engine::errors::{ApplicationError, RuntimeError};
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use radix_engine_interface::blueprints::consensus_manager::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+const ROUNDS_PER_EPOCH: u64 = 4; // we will simulate 3 gap rounds + 1 successfully made proposal...
+const UNRELIABLE_VALIDATOR_STAKE: Decimal = dec!(200);
+const RELIABLE_VALIDATOR_STAKE: Decimal = dec!(100);
+
+struct JailingTest {
+    test_runner: DefaultTestRunner,
+    genesis_epoch: Epoch,
+    unreliable_validator_key: Secp256k1PublicKey,
+    unreliable_validator_address: ComponentAddress,
+    reliable_validator_address: ComponentAddress,
+}
+
+impl JailingTest {
+    fn new(slashed_xrd_destination: SlashedXrdDestination) -> Self {
+        let genesis_epoch = Epoch::of(7);
+        // The validator with the higher stake leads all the rounds (see `advance_to_round()`)
+        let unreliable_validator_key = Secp256k1PrivateKey::from_u64(1).unwrap().public_key();
+        let reliable_validator_key = Secp256k1PrivateKey::from_u64(2).unwrap().public_key();
+        let genesis = CustomGenesis::validators_and_single_staker(
+            vec![
+                (unreliable_validator_key, UNRELIABLE_VALIDATOR_STAKE),
+                (reliable_validator_key, RELIABLE_VALIDATOR_STAKE),
+            ],
+            ComponentAddress::virtual_account_from_public_key(&unreliable_validator_key),
+            Decimal::ZERO,
+            genesis_epoch,
+            CustomGenesis::default_consensus_manager_config()
+                .with_epoch_change_condition(EpochChangeCondition {
+                    min_round_count: ROUNDS_PER_EPOCH,
+                    max_round_count: ROUNDS_PER_EPOCH,
+                    target_duration_millis: 1000,
+                })
+                .with_total_emission_xrd_per_epoch(Decimal::ZERO)
+                .with_min_validator_reliability(dec!("0.3")), // ...which does NOT meet the threshold
+        );
+        let test_runner = TestRunnerBuilder::new()
+            .with_custom_genesis(genesis)
+            .with_validator_jailing(ValidatorJailingConfig {
+                num_unreliable_epochs_before_jailing: 2,
+                num_jail_epochs: 1,
+                slash_factor: dec!("0.1"),
+                slashed_xrd_destination,
+            })
+            .build();
+        let unreliable_validator_address =
+            test_runner.get_active_validator_with_key(&unreliable_validator_key);
+        let reliable_validator_address =
+            test_runner.get_active_validator_with_key(&reliable_validator_key);
+
+        Self {
+            test_runner,
+            genesis_epoch,
+            unreliable_validator_key,
+            unreliable_validator_address,
+            reliable_validator_address,
+        }
+    }
+
+    fn initial_epoch(&self) -> Epoch {
+        self.genesis_epoch.next().unwrap()
+    }
+
+    fn advance_to_next_epoch(&mut self) -> TransactionReceipt {
+        self.test_runner
+            .advance_to_round(Round::of(ROUNDS_PER_EPOCH))
+    }
+
+    fn stake_of(&mut self, validator_address: ComponentAddress) -> Decimal {
+        let validator_substate = self.test_runner.get_validator_info(validator_address);
+        self.test_runner
+            .inspect_vault_balance(validator_substate.stake_xrd_vault_id.0)
+            .unwrap()
+    }
+
+    fn unjail(&mut self) -> TransactionReceipt {
+        let owner_address =
+            ComponentAddress::virtual_account_from_public_key(&self.unreliable_validator_key);
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .create_proof_from_account_of_non_fungibles(
+                owner_address,
+                VALIDATOR_OWNER_BADGE,
+                [
+                    NonFungibleLocalId::bytes(self.unreliable_validator_address.as_node_id().0)
+                        .unwrap(),
+                ],
+            )
+            .unjail_validator(self.unreliable_validator_address)
+            .build();
+        self.test_runner.execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(
+                &self.unreliable_validator_key,
+            )],
+        )
+    }
+}
+
+#[test]
+fn validator_is_jailed_and_slashed_after_consecutive_unreliable_epochs() {
+    // Arrange
+    let mut test = JailingTest::new(SlashedXrdDestination::Burn);
+    let receipt = test.advance_to_next_epoch();
+    let result = receipt.expect_commit_success();
+    assert!(test
+        .test_runner
+        .extract_events_of_type::<ValidatorJailedEvent>(result)
+        .is_empty());
+    let validator_substate = test
+        .test_runner
+        .get_validator_info(test.unreliable_validator_address);
+    assert_eq!(validator_substate.num_consecutive_unreliable_epochs, 1);
+
+    // Act
+    let receipt = test.advance_to_next_epoch();
+
+    // Assert
+    let concluded_epoch = test.initial_epoch().next().unwrap();
+    let result = receipt.expect_commit_success();
+    assert_eq!(
+        test.test_runner
+            .extract_events_of_type::<ValidatorJailedEvent>(result),
+        vec![ValidatorJailedEvent {
+            epoch: concluded_epoch,
+            jailed_until_epoch: concluded_epoch.after(2).unwrap(),
+        }]
+    );
+    assert_eq!(
+        test.test_runner
+            .extract_events_of_type::<ValidatorSlashedEvent>(result),
+        vec![ValidatorSlashedEvent {
+            epoch: concluded_epoch,
+            slashed_xrd: dec!("20"),
+        }]
+    );
+    // The slashed XRDs are burnt, so nobody is rewarded with them
+    assert!(test
+        .test_runner
+        .extract_events_of_type::<ValidatorRewardAppliedEvent>(result)
+        .is_empty());
+
+    let next_epoch_validators = &result
+        .next_epoch()
+        .expect("Should have next epoch")
+        .validator_set
+        .validators_by_stake_desc;
+    assert!(!next_epoch_validators.contains_key(&test.unreliable_validator_address));
+    assert!(next_epoch_validators.contains_key(&test.reliable_validator_address));
+
+    let validator_substate = test
+        .test_runner
+        .get_validator_info(test.unreliable_validator_address);
+    assert_eq!(validator_substate.num_consecutive_unreliable_epochs, 0);
+    assert_eq!(
+        validator_substate.jailed_until_epoch,
+        Some(concluded_epoch.after(2).unwrap())
+    );
+    assert!(validator_substate.is_registered);
+    assert_eq!(validator_substate.sorted_key, None);
+    assert_eq!(
+        test.stake_of(test.unreliable_validator_address),
+        dec!("180")
+    );
+    assert_eq!(
+        test.stake_of(test.reliable_validator_address),
+        RELIABLE_VALIDATOR_STAKE
+    );
+}
+
+#[test]
+fn slashed_xrd_can_be_redistributed_to_the_validator_set() {
+    // Arrange
+    let mut test = JailingTest::new(SlashedXrdDestination::Redistribute);
+    test.advance_to_next_epoch().expect_commit_success();
+
+    // Act
+    let receipt = test.advance_to_next_epoch();
+
+    // Assert
+    let result = receipt.expect_commit_success();
+    let reward_events = test
+        .test_runner
+        .extract_events_of_type::<ValidatorRewardAppliedEvent>(result);
+    assert_eq!(reward_events.len(), 1);
+    assert!(reward_events[0].amount >= dec!("20"));
+    assert_eq!(
+        test.stake_of(test.unreliable_validator_address),
+        dec!("180")
+    );
+    assert_eq!(
+        test.stake_of(test.reliable_validator_address),
+        RELIABLE_VALIDATOR_STAKE
+            .checked_add(reward_events[0].amount)
+            .unwrap()
+    );
+}
+
+#[test]
+fn validator_cannot_be_unjailed_before_jail_period_ends() {
+    // Arrange
+    let mut test = JailingTest::new(SlashedXrdDestination::Burn);
+    test.advance_to_next_epoch().expect_commit_success();
+    test.advance_to_next_epoch().expect_commit_success();
+
+    // Act
+    let receipt = test.unjail();
+
+    // Assert
+    let jailed_until_epoch = test.initial_epoch().after(3).unwrap();
+    receipt.expect_specific_failure(|e| {
+        e == &RuntimeError::ApplicationError(ApplicationError::ValidatorError(
+            ValidatorError::JailPeriodHasNotEndedYet { jailed_until_epoch },
+        ))
+    });
+}
+
+#[test]
+fn validator_which_is_not_jailed_cannot_be_unjailed() {
+    // Arrange
+    let mut test = JailingTest::new(SlashedXrdDestination::Burn);
+
+    // Act
+    let receipt = test.unjail();
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        e == &RuntimeError::ApplicationError(ApplicationError::ValidatorError(
+            ValidatorError::ValidatorIsNotJailed,
+        ))
+    });
+}
+
+#[test]
+fn unjailed_validator_rejoins_the_validator_set_on_epoch_change() {
+    // Arrange
+    let mut test = JailingTest::new(SlashedXrdDestination::Burn);
+    test.advance_to_next_epoch().expect_commit_success();
+    test.advance_to_next_epoch().expect_commit_success();
+    test.advance_to_next_epoch().expect_commit_success();
+
+    // Act
+    let receipt = test.unjail();
+
+    // Assert
+    let result = receipt.expect_commit_success();
+    assert_eq!(
+        test.test_runner
+            .extract_events_of_type::<ValidatorUnjailedEvent>(result)
+            .len(),
+        1
+    );
+    let validator_substate = test
+        .test_runner
+        .get_validator_info(test.unreliable_validator_address);
+    assert_eq!(validator_substate.jailed_until_epoch, None);
+    assert!(validator_substate.sorted_key.is_some());
+
+    let receipt = test.advance_to_next_epoch();
+    let result = receipt.expect_commit_success();
+    assert!(result
+        .next_epoch()
+        .expect("Should have next epoch")
+        .validator_set
+        .validators_by_stake_desc
+        .contains_key(&test.unreliable_validator_address));
+}
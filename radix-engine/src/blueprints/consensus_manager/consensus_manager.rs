@@ -26,9 +26,40 @@ const MILLIS_IN_SECOND: i64 = 1000;
 const SECONDS_IN_MINUTE: i64 = 60;
 const MILLIS_IN_MINUTE: i64 = MILLIS_IN_SECOND * SECONDS_IN_MINUTE;
 
+/// The consensus manager configuration, as defined at genesis.
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+#[sbor(type_name = "ConsensusManagerConfigSubstate")]
+pub struct ConsensusManagerConfigSubstateV1 {
+    pub config: ConsensusManagerConfig,
+}
+
+/// The consensus manager configuration, as extended by the validator jailing protocol update.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+#[sbor(type_name = "ConsensusManagerConfigSubstateV2")]
 pub struct ConsensusManagerConfigSubstate {
     pub config: ConsensusManagerConfig,
+    /// The jailing (and slashing) of validators which repeatedly miss the
+    /// `min_validator_reliability`, or `None` if validators are never jailed.
+    pub validator_jailing: Option<ValidatorJailingConfig>,
+}
+
+impl From<ConsensusManagerConfigSubstateV1> for ConsensusManagerConfigSubstate {
+    fn from(substate: ConsensusManagerConfigSubstateV1) -> Self {
+        Self {
+            config: substate.config,
+            validator_jailing: None,
+        }
+    }
+}
+
+/// The [`VersionedConsensusManagerConfiguration`] as defined at genesis, i.e. before
+/// [`ConsensusManagerConfigurationV2`] was added by the validator jailing protocol update. It keeps
+/// the genesis schema unchanged.
+#[derive(Debug, PartialEq, Eq, ScryptoSbor)]
+#[sbor(type_name = "VersionedConsensusManagerConfiguration")]
+#[repr(u8)]
+enum GenesisVersionedConsensusManagerConfiguration {
+    V1(ConsensusManagerConfigurationV1) = 1,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
@@ -228,7 +259,9 @@ declare_native_blueprint_state! {
         config: {
             ident: Configuration,
             field_type: {
-                kind: StaticSingleVersioned,
+                kind: StaticMultiVersioned,
+                previous_versions: [1 => { updates_to: 2 }],
+                latest_version: 2,
             },
             condition: Condition::Always,
         },
@@ -330,7 +363,8 @@ impl SortedIndexKeyFullContent<ConsensusManagerRegisteredValidatorByStakeKeyPayl
     }
 }
 
-pub type ConsensusManagerConfigurationV1 = ConsensusManagerConfigSubstate;
+pub type ConsensusManagerConfigurationV1 = ConsensusManagerConfigSubstateV1;
+pub type ConsensusManagerConfigurationV2 = ConsensusManagerConfigSubstate;
 pub type ConsensusManagerStateV1 = ConsensusManagerSubstate;
 pub type ConsensusManagerValidatorRewardsV1 = ValidatorRewardsSubstate;
 pub type ConsensusManagerCurrentValidatorSetV1 = CurrentValidatorSetSubstate;
@@ -348,7 +382,7 @@ impl ConsensusManagerBlueprint {
         let mut aggregator = TypeAggregator::<ScryptoCustomTypeKind>::new();
 
         let feature_set = ConsensusManagerFeatureSet::all_features();
-        let state = ConsensusManagerStateSchemaInit::create_schema_init(&mut aggregator);
+        let state = Self::genesis_state_schema_init(&mut aggregator);
 
         let mut functions = index_map_new();
         functions.insert(
@@ -506,6 +540,49 @@ impl ConsensusManagerBlueprint {
         }
     }
 
+    /// The state schema of the genesis definition, which only knows the
+    /// [`ConsensusManagerConfigurationV1`].
+    fn genesis_state_schema_init(
+        aggregator: &mut TypeAggregator<ScryptoCustomTypeKind>,
+    ) -> BlueprintStateSchemaInit {
+        BlueprintStateSchemaInit {
+            fields: vec![
+                FieldSchema::static_field(aggregator.add_child_type_and_descendents::<
+                    GenesisVersionedConsensusManagerConfiguration,
+                >()),
+                FieldSchema::static_field(
+                    aggregator.add_child_type_and_descendents::<ConsensusManagerStateFieldPayload>(),
+                ),
+                FieldSchema::static_field(aggregator.add_child_type_and_descendents::<
+                    ConsensusManagerValidatorRewardsFieldPayload,
+                >()),
+                FieldSchema::static_field(aggregator.add_child_type_and_descendents::<
+                    ConsensusManagerCurrentValidatorSetFieldPayload,
+                >()),
+                FieldSchema::static_field(aggregator.add_child_type_and_descendents::<
+                    ConsensusManagerCurrentProposalStatisticFieldPayload,
+                >()),
+                FieldSchema::static_field(aggregator.add_child_type_and_descendents::<
+                    ConsensusManagerProposerMinuteTimestampFieldPayload,
+                >()),
+                FieldSchema::static_field(aggregator.add_child_type_and_descendents::<
+                    ConsensusManagerProposerMilliTimestampFieldPayload,
+                >()),
+            ],
+            collections: vec![BlueprintCollectionSchema::SortedIndex(
+                BlueprintKeyValueSchema {
+                    key: TypeRef::Static(aggregator.add_child_type_and_descendents::<
+                        ConsensusManagerRegisteredValidatorByStakeKeyContent,
+                    >()),
+                    value: TypeRef::Static(aggregator.add_child_type_and_descendents::<
+                        ConsensusManagerRegisteredValidatorByStakeEntryPayload,
+                    >()),
+                    allow_ownership: false,
+                },
+            )],
+        }
+    }
+
     pub(crate) fn create<Y>(
         validator_token_address_reservation: GlobalAddressReservation,
         consensus_manager_address_reservation: GlobalAddressReservation,
@@ -559,7 +636,9 @@ impl ConsensusManagerBlueprint {
         };
 
         let consensus_manager_id = {
-            let config = ConsensusManagerConfigSubstate {
+            // The genesis configuration is stored in its original version, the validator jailing
+            // is only configured by a protocol update
+            let config = ConsensusManagerConfigSubstateV1 {
                 config: initial_config,
             };
             let consensus_manager = ConsensusManagerSubstate {
@@ -691,7 +770,12 @@ impl ConsensusManagerBlueprint {
                     ),
                 ))?;
 
-        Self::epoch_change(post_genesis_epoch, &config_substate.config, api)?;
+        Self::epoch_change(
+            post_genesis_epoch,
+            &config_substate.config,
+            config_substate.validator_jailing.as_ref(),
+            api,
+        )?;
         manager_substate.started = true;
         manager_substate.epoch = post_genesis_epoch;
         manager_substate.round = Round::zero();
@@ -903,7 +987,7 @@ impl ConsensusManagerBlueprint {
         Instant::new(epoch_milli / MILLIS_IN_SECOND)
     }
 
-    fn milli_to_minute(epoch_milli: i64) -> Option<i32> {
+    pub(crate) fn milli_to_minute(epoch_milli: i64) -> Option<i32> {
         i32::try_from(epoch_milli / MILLIS_IN_MINUTE).ok() // safe until A.D. 5700
     }
 
@@ -973,7 +1057,12 @@ impl ConsensusManagerBlueprint {
                                 ConsensusManagerError::EpochMathOverflow,
                             ),
                         ))?;
-                Self::epoch_change(next_epoch, config, api)?;
+                Self::epoch_change(
+                    next_epoch,
+                    config,
+                    config_substate.validator_jailing.as_ref(),
+                    api,
+                )?;
                 manager_substate.epoch = next_epoch;
                 manager_substate.round = Round::zero();
                 manager_substate.actual_epoch_start_milli = proposer_timestamp_milli;
@@ -1175,6 +1264,7 @@ impl ConsensusManagerBlueprint {
     fn epoch_change<Y>(
         next_epoch: Epoch,
         config: &ConsensusManagerConfig,
+        validator_jailing: Option<&ValidatorJailingConfig>,
         api: &mut Y,
     ) -> Result<(), RuntimeError>
     where
@@ -1212,15 +1302,30 @@ impl ConsensusManagerBlueprint {
             .field_read_typed::<ConsensusManagerValidatorRewardsFieldPayload>(rewards_handle)?
             .into_latest();
 
+        let concluded_epoch = next_epoch.previous().ok_or(RuntimeError::ApplicationError(
+            ApplicationError::ConsensusManagerError(ConsensusManagerError::EpochMathOverflow),
+        ))?;
+
+        // Jail (and slash) the persistently unreliable validators
+        if let Some(jailing_config) = validator_jailing {
+            Self::apply_validator_jailing(
+                &previous_validator_set,
+                &previous_statistics,
+                config.min_validator_reliability,
+                jailing_config,
+                &mut rewards_substate,
+                concluded_epoch,
+                api,
+            )?;
+        }
+
         // Apply emissions
         Self::apply_validator_emissions_and_rewards(
             previous_validator_set,
             previous_statistics,
             config,
             &mut rewards_substate,
-            next_epoch.previous().ok_or(RuntimeError::ApplicationError(
-                ApplicationError::ConsensusManagerError(ConsensusManagerError::EpochMathOverflow),
-            ))?,
+            concluded_epoch,
             api,
         )?;
 
@@ -1352,6 +1457,46 @@ impl ConsensusManagerBlueprint {
         Ok(())
     }
 
+    /// Reports the reliability of each validator of the concluded epoch's set, so that the ones which
+    /// stay unreliable for too long get jailed (i.e. removed from the registered validators index).
+    /// The XRDs slashed from the jailed validators are either burnt, or put into the rewards vault
+    /// (to be distributed to the validator set along with the fees).
+    fn apply_validator_jailing<Y>(
+        validator_set: &ActiveValidatorSet,
+        validator_statistics: &[ProposalStatistic],
+        min_validator_reliability: Decimal,
+        jailing_config: &ValidatorJailingConfig,
+        validator_rewards: &mut ValidatorRewardsSubstate,
+        epoch: Epoch, // the concluded epoch
+        api: &mut Y,
+    ) -> Result<(), RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        for (index, (address, _validator)) in
+            validator_set.validators_by_stake_desc.iter().enumerate()
+        {
+            let is_reliable =
+                validator_statistics[index].success_ratio()? >= min_validator_reliability;
+            let rtn = api.call_method(
+                address.as_node_id(),
+                VALIDATOR_APPLY_RELIABILITY_IDENT,
+                scrypto_encode(&ValidatorApplyReliabilityInput { is_reliable, epoch }).unwrap(),
+            )?;
+            let slashed_xrd_bucket: ValidatorApplyReliabilityOutput = scrypto_decode(&rtn).unwrap();
+            if let Some(slashed_xrd_bucket) = slashed_xrd_bucket {
+                match jailing_config.slashed_xrd_destination {
+                    SlashedXrdDestination::Burn => slashed_xrd_bucket.burn(api)?,
+                    SlashedXrdDestination::Redistribute => validator_rewards
+                        .rewards_vault
+                        .put(slashed_xrd_bucket, api)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Emits a configured XRD amount ([`ConsensusManagerConfigSubstate.total_emission_xrd_per_epoch`])
     /// and distributes it across the given validator set, according to their stake.
     fn apply_validator_emissions_and_rewards<Y>(
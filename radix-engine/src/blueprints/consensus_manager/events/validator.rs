@@ -70,3 +70,22 @@ pub struct ValidatorRewardAppliedEvent {
     /// The reward amount
     pub amount: Decimal,
 }
+
+#[derive(ScryptoSbor, ScryptoEvent, PartialEq, Eq, Debug)]
+pub struct ValidatorJailedEvent {
+    /// An epoch number of the *concluded* epoch (i.e. the last unreliable one).
+    pub epoch: Epoch,
+    /// An epoch number at (or after) which the validator's owner may unjail it.
+    pub jailed_until_epoch: Epoch,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, PartialEq, Eq, Debug)]
+pub struct ValidatorSlashedEvent {
+    /// An epoch number of the *concluded* epoch (i.e. the last unreliable one).
+    pub epoch: Epoch,
+    /// An amount of XRD taken from the validator's stake pool.
+    pub slashed_xrd: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, PartialEq, Eq, Debug)]
+pub struct ValidatorUnjailedEvent;
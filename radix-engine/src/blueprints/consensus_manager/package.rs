@@ -269,6 +269,21 @@ impl ConsensusManagerNativePackage {
                 let rtn = ValidatorBlueprint::apply_reward(input.xrd_bucket, input.epoch, api)?;
                 Ok(IndexedScryptoValue::from_typed(&rtn))
             }
+            VALIDATOR_APPLY_RELIABILITY_IDENT => {
+                let input: ValidatorApplyReliabilityInput = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn =
+                    ValidatorBlueprint::apply_reliability(input.is_reliable, input.epoch, api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+            VALIDATOR_UNJAIL_IDENT => {
+                let _: ValidatorUnjailInput = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = ValidatorBlueprint::unjail(api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
             _ => Err(RuntimeError::ApplicationError(
                 ApplicationError::ExportDoesNotExist(export_name.to_string()),
             )),
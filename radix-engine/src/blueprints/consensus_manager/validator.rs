@@ -35,7 +35,31 @@ pub const VALIDATOR_PROTOCOL_VERSION_NAME_LEN: usize = 32;
 /// operations on any validator's owner's stake units vault.
 pub const OWNER_STAKE_UNITS_PENDING_WITHDRAWALS_LIMIT: usize = 100;
 
+/// The validator state, as defined at genesis (i.e. a [`ValidatorSubstate`] without the jailing
+/// state). See [`ValidatorSubstate`] for the documentation of its fields.
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+#[sbor(type_name = "ValidatorSubstate")]
+pub struct ValidatorSubstateV1 {
+    pub sorted_key: Option<SortedKey>,
+    pub key: Secp256k1PublicKey,
+    pub is_registered: bool,
+    pub accepts_delegated_stake: bool,
+    pub validator_fee_factor: Decimal,
+    pub validator_fee_change_request: Option<ValidatorFeeChangeRequest>,
+    pub stake_unit_resource: ResourceAddress,
+    pub stake_xrd_vault_id: Own,
+    pub claim_nft: ResourceAddress,
+    pub pending_xrd_withdraw_vault_id: Own,
+    pub locked_owner_stake_unit_vault_id: Own,
+    pub pending_owner_stake_unit_unlock_vault_id: Own,
+    pub pending_owner_stake_unit_withdrawals: BTreeMap<Epoch, Decimal>,
+    pub already_unlocked_owner_stake_unit_amount: Decimal,
+}
+
+/// The validator state, as extended by the validator jailing protocol update (see
+/// [`ValidatorSubstateV1`] for the original one).
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+#[sbor(type_name = "ValidatorSubstateV2")]
 pub struct ValidatorSubstate {
     /// A key used internally for storage of registered validators sorted by their stake descending.
     /// It is only useful when the validator is registered and has non-zero stake - hence, the field
@@ -112,6 +136,18 @@ pub struct ValidatorSubstate {
     /// in the [`pending_owner_stake_unit_withdrawals`] and was automatically moved from there.
     /// The very next [`finish_unlock_owner_stake_units()`] operation will release this amount.
     pub already_unlocked_owner_stake_unit_amount: Decimal,
+
+    /// A number of consecutive epochs (up to the most recently concluded one) in which this
+    /// validator was active but did not reach the [`ConsensusManagerConfig.min_validator_reliability`].
+    /// Note: it is only tracked if [`ConsensusManagerConfig.validator_jailing`] is configured, and
+    /// it is reset when the validator gets jailed.
+    pub num_consecutive_unreliable_epochs: u64,
+
+    /// An epoch number at (or after) which this validator's owner may unjail it, or [`None`] if the
+    /// validator is not jailed.
+    /// A jailed validator is kept out of the registered validators index (regardless of its
+    /// [`is_registered`] status), and thus cannot become a part of the active validator set.
+    pub jailed_until_epoch: Option<Epoch>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
@@ -162,6 +198,8 @@ pub enum ValidatorError {
     ValidatorIsNotAcceptingDelegatedStake,
     InvalidProtocolVersionNameLength { expected: usize, actual: usize },
     EpochMathOverflow,
+    ValidatorIsNotJailed,
+    JailPeriodHasNotEndedYet { jailed_until_epoch: Epoch },
 }
 
 declare_native_blueprint_state! {
@@ -173,7 +211,9 @@ declare_native_blueprint_state! {
         state: {
             ident: State,
             field_type: {
-                kind: StaticSingleVersioned,
+                kind: StaticMultiVersioned,
+                previous_versions: [1 => { updates_to: 2 }],
+                latest_version: 2,
             },
             condition: Condition::Always,
         },
@@ -189,9 +229,105 @@ declare_native_blueprint_state! {
     }
 }
 
-pub type ValidatorStateV1 = ValidatorSubstate;
+pub type ValidatorStateV1 = ValidatorSubstateV1;
+pub type ValidatorStateV2 = ValidatorSubstate;
 pub type ValidatorProtocolUpdateReadinessSignalV1 = ValidatorProtocolUpdateReadinessSignalSubstate;
 
+impl From<ValidatorSubstateV1> for ValidatorSubstate {
+    fn from(substate: ValidatorSubstateV1) -> Self {
+        let ValidatorSubstateV1 {
+            sorted_key,
+            key,
+            is_registered,
+            accepts_delegated_stake,
+            validator_fee_factor,
+            validator_fee_change_request,
+            stake_unit_resource,
+            stake_xrd_vault_id,
+            claim_nft,
+            pending_xrd_withdraw_vault_id,
+            locked_owner_stake_unit_vault_id,
+            pending_owner_stake_unit_unlock_vault_id,
+            pending_owner_stake_unit_withdrawals,
+            already_unlocked_owner_stake_unit_amount,
+        } = substate;
+        Self {
+            sorted_key,
+            key,
+            is_registered,
+            accepts_delegated_stake,
+            validator_fee_factor,
+            validator_fee_change_request,
+            stake_unit_resource,
+            stake_xrd_vault_id,
+            claim_nft,
+            pending_xrd_withdraw_vault_id,
+            locked_owner_stake_unit_vault_id,
+            pending_owner_stake_unit_unlock_vault_id,
+            pending_owner_stake_unit_withdrawals,
+            already_unlocked_owner_stake_unit_amount,
+            num_consecutive_unreliable_epochs: 0,
+            jailed_until_epoch: None,
+        }
+    }
+}
+
+impl ValidatorSubstate {
+    /// Creates the field payload of this state, in the [`ValidatorStateV1`] layout unless some
+    /// jailing state is set.
+    /// This keeps the validators valid against the state schema of the networks which have not
+    /// enacted the validator jailing protocol update yet (where no jailing state can be set), see
+    /// [`generate_validator_jailing_state_updates()`](crate::utils::generate_validator_jailing_state_updates).
+    pub fn into_compatible_payload(self) -> ValidatorStateFieldPayload {
+        if self.num_consecutive_unreliable_epochs != 0 || self.jailed_until_epoch.is_some() {
+            return ValidatorStateFieldPayload::from_content_source(self);
+        }
+        let ValidatorSubstate {
+            sorted_key,
+            key,
+            is_registered,
+            accepts_delegated_stake,
+            validator_fee_factor,
+            validator_fee_change_request,
+            stake_unit_resource,
+            stake_xrd_vault_id,
+            claim_nft,
+            pending_xrd_withdraw_vault_id,
+            locked_owner_stake_unit_vault_id,
+            pending_owner_stake_unit_unlock_vault_id,
+            pending_owner_stake_unit_withdrawals,
+            already_unlocked_owner_stake_unit_amount,
+            num_consecutive_unreliable_epochs: _,
+            jailed_until_epoch: _,
+        } = self;
+        ValidatorStateFieldPayload::from_content_source(ValidatorSubstateV1 {
+            sorted_key,
+            key,
+            is_registered,
+            accepts_delegated_stake,
+            validator_fee_factor,
+            validator_fee_change_request,
+            stake_unit_resource,
+            stake_xrd_vault_id,
+            claim_nft,
+            pending_xrd_withdraw_vault_id,
+            locked_owner_stake_unit_vault_id,
+            pending_owner_stake_unit_unlock_vault_id,
+            pending_owner_stake_unit_withdrawals,
+            already_unlocked_owner_stake_unit_amount,
+        })
+    }
+}
+
+/// The [`VersionedValidatorState`] as defined at genesis, i.e. before [`ValidatorStateV2`] was
+/// added by the validator jailing protocol update. It keeps the genesis schema unchanged.
+#[derive(Debug, PartialEq, Eq, ScryptoSbor)]
+#[sbor(type_name = "VersionedValidatorState")]
+#[repr(u8)]
+enum GenesisVersionedValidatorState {
+    V1(ValidatorStateV1) = 1,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, ScryptoSbor, ManifestSbor)]
 enum UpdateSecondaryIndex {
     Create {
@@ -216,11 +352,26 @@ enum UpdateSecondaryIndex {
 pub struct ValidatorBlueprint;
 
 impl ValidatorBlueprint {
+    /// The blueprint definition, as published at genesis.
     pub fn definition() -> BlueprintDefinitionInit {
+        Self::definition_internal(false)
+    }
+
+    /// The blueprint definition after the validator jailing protocol update, which adds the
+    /// [`ValidatorStateV2`], the `unjail` and `apply_reliability` methods and the jailing events.
+    pub fn validator_jailing_definition() -> BlueprintDefinitionInit {
+        Self::definition_internal(true)
+    }
+
+    fn definition_internal(validator_jailing: bool) -> BlueprintDefinitionInit {
         let mut aggregator = TypeAggregator::<ScryptoCustomTypeKind>::new();
 
         let feature_set = ValidatorFeatureSet::all_features();
-        let state = ValidatorStateSchemaInit::create_schema_init(&mut aggregator);
+        let state = if validator_jailing {
+            ValidatorStateSchemaInit::create_schema_init(&mut aggregator)
+        } else {
+            Self::genesis_state_schema_init(&mut aggregator)
+        };
 
         let mut functions = index_map_new();
         functions.insert(
@@ -482,8 +633,38 @@ impl ValidatorBlueprint {
                 export: VALIDATOR_APPLY_REWARD_IDENT.to_string(),
             },
         );
+        if validator_jailing {
+            functions.insert(
+                VALIDATOR_UNJAIL_IDENT.to_string(),
+                FunctionSchemaInit {
+                    receiver: Some(ReceiverInfo::normal_ref_mut()),
+                    input: TypeRef::Static(
+                        aggregator.add_child_type_and_descendents::<ValidatorUnjailInput>(),
+                    ),
+                    output: TypeRef::Static(
+                        aggregator.add_child_type_and_descendents::<ValidatorUnjailOutput>(),
+                    ),
+                    export: VALIDATOR_UNJAIL_IDENT.to_string(),
+                },
+            );
+            functions.insert(
+                VALIDATOR_APPLY_RELIABILITY_IDENT.to_string(),
+                FunctionSchemaInit {
+                    receiver: Some(ReceiverInfo::normal_ref_mut()),
+                    input: TypeRef::Static(
+                        aggregator
+                            .add_child_type_and_descendents::<ValidatorApplyReliabilityInput>(),
+                    ),
+                    output: TypeRef::Static(
+                        aggregator
+                            .add_child_type_and_descendents::<ValidatorApplyReliabilityOutput>(),
+                    ),
+                    export: VALIDATOR_APPLY_RELIABILITY_IDENT.to_string(),
+                },
+            );
+        }
 
-        let event_schema = event_schema! {
+        let mut event_schema = event_schema! {
             aggregator,
             [
                 RegisterValidatorEvent,
@@ -497,9 +678,56 @@ impl ValidatorBlueprint {
                 ValidatorRewardAppliedEvent
             ]
         };
+        if validator_jailing {
+            let jailing_event_schema = event_schema! {
+                aggregator,
+                [
+                    ValidatorJailedEvent,
+                    ValidatorSlashedEvent,
+                    ValidatorUnjailedEvent
+                ]
+            };
+            event_schema
+                .event_schema
+                .extend(jailing_event_schema.event_schema);
+        }
 
         let schema = generate_full_schema(aggregator);
 
+        let mut method_auth = roles_template! {
+            methods {
+                VALIDATOR_UNSTAKE_IDENT => MethodAccessibility::Public;
+                VALIDATOR_CLAIM_XRD_IDENT => MethodAccessibility::Public;
+                VALIDATOR_STAKE_IDENT => MethodAccessibility::Public;
+                VALIDATOR_ACCEPTS_DELEGATED_STAKE_IDENT => MethodAccessibility::Public;
+                VALIDATOR_TOTAL_STAKE_XRD_AMOUNT_IDENT => MethodAccessibility::Public;
+                VALIDATOR_TOTAL_STAKE_UNIT_SUPPLY_IDENT => MethodAccessibility::Public;
+                VALIDATOR_GET_REDEMPTION_VALUE_IDENT => MethodAccessibility::Public;
+                VALIDATOR_STAKE_AS_OWNER_IDENT => [OWNER_ROLE];
+                VALIDATOR_REGISTER_IDENT => [OWNER_ROLE];
+                VALIDATOR_UNREGISTER_IDENT => [OWNER_ROLE];
+                VALIDATOR_UPDATE_KEY_IDENT => [OWNER_ROLE];
+                VALIDATOR_UPDATE_FEE_IDENT => [OWNER_ROLE];
+                VALIDATOR_LOCK_OWNER_STAKE_UNITS_IDENT => [OWNER_ROLE];
+                VALIDATOR_START_UNLOCK_OWNER_STAKE_UNITS_IDENT => [OWNER_ROLE];
+                VALIDATOR_FINISH_UNLOCK_OWNER_STAKE_UNITS_IDENT => [OWNER_ROLE];
+                VALIDATOR_UPDATE_ACCEPT_DELEGATED_STAKE_IDENT => [OWNER_ROLE];
+                VALIDATOR_SIGNAL_PROTOCOL_UPDATE_READINESS => [OWNER_ROLE];
+                VALIDATOR_GET_PROTOCOL_UPDATE_READINESS_IDENT => MethodAccessibility::OuterObjectOnly;
+                VALIDATOR_APPLY_EMISSION_IDENT => MethodAccessibility::OuterObjectOnly;
+                VALIDATOR_APPLY_REWARD_IDENT => MethodAccessibility::OuterObjectOnly;
+            }
+        };
+        if validator_jailing {
+            method_auth
+                .methods
+                .insert(VALIDATOR_UNJAIL_IDENT.into(), [OWNER_ROLE].into());
+            method_auth.methods.insert(
+                VALIDATOR_APPLY_RELIABILITY_IDENT.into(),
+                MethodAccessibility::OuterObjectOnly,
+            );
+        }
+
         BlueprintDefinitionInit {
             blueprint_type: BlueprintType::Inner {
                 outer_blueprint: CONSENSUS_MANAGER_BLUEPRINT.to_string(),
@@ -519,34 +747,28 @@ impl ValidatorBlueprint {
             royalty_config: PackageRoyaltyConfig::default(),
             auth_config: AuthConfig {
                 function_auth: FunctionAuth::AllowAll,
-                method_auth: MethodAuthTemplate::StaticRoleDefinition(roles_template! {
-                    methods {
-                        VALIDATOR_UNSTAKE_IDENT => MethodAccessibility::Public;
-                        VALIDATOR_CLAIM_XRD_IDENT => MethodAccessibility::Public;
-                        VALIDATOR_STAKE_IDENT => MethodAccessibility::Public;
-                        VALIDATOR_ACCEPTS_DELEGATED_STAKE_IDENT => MethodAccessibility::Public;
-                        VALIDATOR_TOTAL_STAKE_XRD_AMOUNT_IDENT => MethodAccessibility::Public;
-                        VALIDATOR_TOTAL_STAKE_UNIT_SUPPLY_IDENT => MethodAccessibility::Public;
-                        VALIDATOR_GET_REDEMPTION_VALUE_IDENT => MethodAccessibility::Public;
-                        VALIDATOR_STAKE_AS_OWNER_IDENT => [OWNER_ROLE];
-                        VALIDATOR_REGISTER_IDENT => [OWNER_ROLE];
-                        VALIDATOR_UNREGISTER_IDENT => [OWNER_ROLE];
-                        VALIDATOR_UPDATE_KEY_IDENT => [OWNER_ROLE];
-                        VALIDATOR_UPDATE_FEE_IDENT => [OWNER_ROLE];
-                        VALIDATOR_LOCK_OWNER_STAKE_UNITS_IDENT => [OWNER_ROLE];
-                        VALIDATOR_START_UNLOCK_OWNER_STAKE_UNITS_IDENT => [OWNER_ROLE];
-                        VALIDATOR_FINISH_UNLOCK_OWNER_STAKE_UNITS_IDENT => [OWNER_ROLE];
-                        VALIDATOR_UPDATE_ACCEPT_DELEGATED_STAKE_IDENT => [OWNER_ROLE];
-                        VALIDATOR_SIGNAL_PROTOCOL_UPDATE_READINESS => [OWNER_ROLE];
-                        VALIDATOR_GET_PROTOCOL_UPDATE_READINESS_IDENT => MethodAccessibility::OuterObjectOnly;
-                        VALIDATOR_APPLY_EMISSION_IDENT => MethodAccessibility::OuterObjectOnly;
-                        VALIDATOR_APPLY_REWARD_IDENT => MethodAccessibility::OuterObjectOnly;
-                    }
-                }),
+                method_auth: MethodAuthTemplate::StaticRoleDefinition(method_auth),
             },
         }
     }
 
+    /// The state schema of the genesis definition, which only knows the [`ValidatorStateV1`].
+    fn genesis_state_schema_init(
+        aggregator: &mut TypeAggregator<ScryptoCustomTypeKind>,
+    ) -> BlueprintStateSchemaInit {
+        BlueprintStateSchemaInit {
+            fields: vec![
+                FieldSchema::static_field(
+                    aggregator.add_child_type_and_descendents::<GenesisVersionedValidatorState>(),
+                ),
+                FieldSchema::static_field(aggregator.add_child_type_and_descendents::<
+                    ValidatorProtocolUpdateReadinessSignalFieldPayload,
+                >()),
+            ],
+            collections: vec![],
+        }
+    }
+
     pub fn register<Y>(api: &mut Y) -> Result<(), RuntimeError>
     where
         Y: ClientApi<RuntimeError>,
@@ -629,10 +851,7 @@ impl ValidatorBlueprint {
             Self::index_update(&validator, validator.is_registered, new_stake_amount, api)?;
 
         validator.sorted_key = new_index_key;
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(validator),
-        )?;
+        api.field_write_typed(handle, &validator.into_compatible_payload())?;
 
         Runtime::emit_event(
             api,
@@ -724,10 +943,7 @@ impl ValidatorBlueprint {
         )?;
 
         validator_substate.sorted_key = new_index_key;
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(validator_substate),
-        )?;
+        api.field_write_typed(handle, &validator_substate.into_compatible_payload())?;
 
         Runtime::emit_event(
             api,
@@ -826,10 +1042,7 @@ impl ValidatorBlueprint {
 
         validator.is_registered = new_registered;
         validator.sorted_key = index_key;
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(validator),
-        )?;
+        api.field_write_typed(handle, &validator.into_compatible_payload())?;
 
         if new_registered {
             Runtime::emit_event(api, RegisterValidatorEvent)?;
@@ -851,8 +1064,12 @@ impl ValidatorBlueprint {
     {
         let validator_address: ComponentAddress =
             ComponentAddress::new_or_panic(api.actor_get_node_id(ACTOR_REF_GLOBAL)?.into());
-        let new_sorted_key =
-            Self::to_sorted_key(new_registered, new_stake_amount, validator_address)?;
+        // A jailed validator stays out of the index until it is unjailed
+        let new_sorted_key = Self::to_sorted_key(
+            new_registered && validator.jailed_until_epoch.is_none(),
+            new_stake_amount,
+            validator_address,
+        )?;
 
         let update = if let Some(cur_index_key) = &validator.sorted_key {
             if let Some(new_index_key) = &new_sorted_key {
@@ -977,10 +1194,7 @@ impl ValidatorBlueprint {
         }
 
         validator.key = key;
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(validator),
-        )?;
+        api.field_write_typed(handle, &validator.into_compatible_payload())?;
 
         Ok(())
     }
@@ -1047,10 +1261,7 @@ impl ValidatorBlueprint {
             epoch_effective,
             new_fee_factor,
         });
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(substate),
-        )?;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
         api.field_close(handle)?;
 
         Ok(())
@@ -1169,10 +1380,7 @@ impl ValidatorBlueprint {
             .field_read_typed::<ValidatorStateFieldPayload>(handle)?
             .into_latest();
         substate.accepts_delegated_stake = accept_delegated_stake;
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(substate),
-        )?;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
         api.field_close(handle)?;
 
         Runtime::emit_event(
@@ -1278,10 +1486,7 @@ impl ValidatorBlueprint {
         let mut locked_owner_stake_unit_vault = Vault(substate.locked_owner_stake_unit_vault_id);
         let mut pending_owner_stake_unit_unlock_vault =
             Vault(substate.pending_owner_stake_unit_unlock_vault_id);
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(substate),
-        )?;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
 
         // move the requested stake units from the "locked vault" to the "pending withdrawal vault"
         let pending_unlock_stake_unit_bucket =
@@ -1329,10 +1534,7 @@ impl ValidatorBlueprint {
 
         let mut pending_owner_stake_unit_unlock_vault =
             Vault(substate.pending_owner_stake_unit_unlock_vault_id);
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(substate),
-        )?;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
 
         // return the already-available withdrawals
         let already_available_stake_unit_bucket =
@@ -1453,10 +1655,7 @@ impl ValidatorBlueprint {
 
         // ...end the read+modify+write of the validator substate (event can be emitted afterwards)
         substate.sorted_key = new_index_key;
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(substate),
-        )?;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
         api.field_close(handle)?;
 
         Runtime::emit_event(
@@ -1523,10 +1722,7 @@ impl ValidatorBlueprint {
 
         // Flush validator substate changes
         substate.sorted_key = new_index_key;
-        api.field_write_typed(
-            handle,
-            &ValidatorStateFieldPayload::from_content_source(substate),
-        )?;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
         api.field_close(handle)?;
 
         Runtime::emit_event(
@@ -1540,6 +1736,173 @@ impl ValidatorBlueprint {
         Ok(())
     }
 
+    /// Tracks this validator's reliability in the just-concluded epoch and, if it has been
+    /// unreliable for [`ValidatorJailingConfig.num_unreliable_epochs_before_jailing`] consecutive
+    /// epochs, jails it and slashes its stake.
+    /// Only the XRDs staked in the `stake_xrd_vault` are slashed: the XRDs of pending unstakes are
+    /// owed to the holders of the already-minted claim NFTs (which state a fixed amount), so they
+    /// are not subject to the penalty.
+    /// Returns the slashed XRDs (if any), so that the consensus manager can dispose of them
+    /// according to the [`ValidatorJailingConfig.slashed_xrd_destination`].
+    pub fn apply_reliability<Y>(
+        is_reliable: bool,
+        concluded_epoch: Epoch,
+        api: &mut Y,
+    ) -> Result<Option<Bucket>, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        let config_handle = api.actor_open_field(
+            ACTOR_STATE_OUTER_OBJECT,
+            ConsensusManagerField::Configuration.into(),
+            LockFlags::read_only(),
+        )?;
+        let config_substate = api
+            .field_read_typed::<ConsensusManagerConfigurationFieldPayload>(config_handle)?
+            .into_latest();
+        api.field_close(config_handle)?;
+        let Some(jailing_config) = config_substate.validator_jailing else {
+            return Ok(None);
+        };
+
+        // begin the read+modify+write of the validator substate...
+        let handle = api.actor_open_field(
+            ACTOR_STATE_SELF,
+            ValidatorField::State.into(),
+            LockFlags::MUTABLE,
+        )?;
+        let mut substate = api
+            .field_read_typed::<ValidatorStateFieldPayload>(handle)?
+            .into_latest();
+
+        if is_reliable {
+            // No update
+            if substate.num_consecutive_unreliable_epochs != 0 {
+                substate.num_consecutive_unreliable_epochs = 0;
+                api.field_write_typed(handle, &substate.into_compatible_payload())?;
+            }
+            api.field_close(handle)?;
+            return Ok(None);
+        }
+
+        substate.num_consecutive_unreliable_epochs += 1;
+        if substate.num_consecutive_unreliable_epochs
+            < jailing_config.num_unreliable_epochs_before_jailing
+        {
+            api.field_write_typed(handle, &substate.into_compatible_payload())?;
+            api.field_close(handle)?;
+            return Ok(None);
+        }
+
+        // - jail the validator (the jail period starts with the upcoming epoch)
+        let jailed_until_epoch = concluded_epoch
+            .after(jailing_config.num_jail_epochs.saturating_add(1))
+            .ok_or(RuntimeError::ApplicationError(
+                ApplicationError::ValidatorError(ValidatorError::EpochMathOverflow),
+            ))?;
+        substate.num_consecutive_unreliable_epochs = 0;
+        substate.jailed_until_epoch = Some(jailed_until_epoch);
+
+        // - slash the stake
+        let mut stake_xrd_vault = Vault(substate.stake_xrd_vault_id);
+        let starting_stake_pool_xrd = stake_xrd_vault.amount(api)?;
+        // The configuration is validated when installed, but a slash factor out of [0, 1] must
+        // never be able to take more than the stake (or to take a negative amount)
+        let slashed_xrd = jailing_config
+            .slash_factor
+            .clamp(Decimal::zero(), Decimal::one())
+            .checked_mul(starting_stake_pool_xrd)
+            .ok_or(RuntimeError::ApplicationError(
+                ApplicationError::ValidatorError(ValidatorError::UnexpectedDecimalComputationError),
+            ))?;
+        let slashed_xrd_bucket = stake_xrd_vault.take(slashed_xrd, api)?;
+        let new_stake_xrd = stake_xrd_vault.amount(api)?;
+
+        // - remove the validator from the index (since it is now jailed)
+        let new_index_key =
+            Self::index_update(&substate, substate.is_registered, new_stake_xrd, api)?;
+
+        // ...end the read+modify+write of the validator substate (events can be emitted afterwards)
+        substate.sorted_key = new_index_key;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
+        api.field_close(handle)?;
+
+        Runtime::emit_event(
+            api,
+            ValidatorJailedEvent {
+                epoch: concluded_epoch,
+                jailed_until_epoch,
+            },
+        )?;
+        if slashed_xrd.is_positive() {
+            Runtime::emit_event(
+                api,
+                ValidatorSlashedEvent {
+                    epoch: concluded_epoch,
+                    slashed_xrd,
+                },
+            )?;
+        }
+
+        Ok(Some(slashed_xrd_bucket))
+    }
+
+    pub fn unjail<Y>(api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        let manager_handle = api.actor_open_field(
+            ACTOR_STATE_OUTER_OBJECT,
+            ConsensusManagerField::State.into(),
+            LockFlags::read_only(),
+        )?;
+        let manager_substate = api
+            .field_read_typed::<ConsensusManagerStateFieldPayload>(manager_handle)?
+            .into_latest();
+        let current_epoch = manager_substate.epoch;
+        api.field_close(manager_handle)?;
+
+        let handle = api.actor_open_field(
+            ACTOR_STATE_SELF,
+            ValidatorField::State.into(),
+            LockFlags::MUTABLE,
+        )?;
+        let mut substate = api
+            .field_read_typed::<ValidatorStateFieldPayload>(handle)?
+            .into_latest();
+
+        let Some(jailed_until_epoch) = substate.jailed_until_epoch else {
+            api.field_close(handle)?;
+            return Err(RuntimeError::ApplicationError(
+                ApplicationError::ValidatorError(ValidatorError::ValidatorIsNotJailed),
+            ));
+        };
+        if current_epoch < jailed_until_epoch {
+            api.field_close(handle)?;
+            return Err(RuntimeError::ApplicationError(
+                ApplicationError::ValidatorError(ValidatorError::JailPeriodHasNotEndedYet {
+                    jailed_until_epoch,
+                }),
+            ));
+        }
+
+        substate.jailed_until_epoch = None;
+        substate.num_consecutive_unreliable_epochs = 0;
+
+        // Put the validator back into the index (if it is still registered)
+        let stake_amount = Vault(substate.stake_xrd_vault_id).amount(api)?;
+        let new_index_key =
+            Self::index_update(&substate, substate.is_registered, stake_amount, api)?;
+
+        substate.sorted_key = new_index_key;
+        api.field_write_typed(handle, &substate.into_compatible_payload())?;
+        api.field_close(handle)?;
+
+        Runtime::emit_event(api, ValidatorUnjailedEvent)?;
+
+        Ok(())
+    }
+
     fn to_sorted_key(
         registered: bool,
         stake: Decimal,
@@ -1839,6 +2202,8 @@ impl ValidatorCreator {
             pending_owner_stake_unit_unlock_vault_id: pending_owner_stake_unit_unlock_vault.0,
             pending_owner_stake_unit_withdrawals,
             already_unlocked_owner_stake_unit_amount: Decimal::zero(),
+            num_consecutive_unreliable_epochs: 0,
+            jailed_until_epoch: None,
         };
 
         let protocol_update_readiness_signal = ValidatorProtocolUpdateReadinessSignalSubstate {
@@ -1848,7 +2213,7 @@ impl ValidatorCreator {
         let validator_id = api.new_simple_object(
             VALIDATOR_BLUEPRINT,
             indexmap! {
-                ValidatorField::State.field_index() => FieldValue::new(&ValidatorStateFieldPayload::from_content_source(substate)),
+                ValidatorField::State.field_index() => FieldValue::new(&substate.into_compatible_payload()),
                 ValidatorField::ProtocolUpdateReadinessSignal.field_index() => FieldValue::new(&ValidatorProtocolUpdateReadinessSignalFieldPayload::from_content_source(protocol_update_readiness_signal)),
             },
         )?;
@@ -2,6 +2,8 @@
 pub const ONE_RESOURCE_POOL_BLUEPRINT_IDENT: &str = "OneResourcePool";
 pub const TWO_RESOURCE_POOL_BLUEPRINT_IDENT: &str = "TwoResourcePool";
 pub const MULTI_RESOURCE_POOL_BLUEPRINT_IDENT: &str = "MultiResourcePool";
+pub const WEIGHTED_POOL_BLUEPRINT_IDENT: &str = "WeightedPool";
+pub const STABLE_SWAP_POOL_BLUEPRINT_IDENT: &str = "StableSwapPool";
 
 // Roles
 pub const POOL_MANAGER_ROLE: &str = "pool_manager_role";
@@ -97,3 +97,106 @@ pub mod multi_resource_pool {
         }
     }
 }
+
+pub mod weighted_pool {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+    pub enum Error {
+        NonFungibleResourcesAreNotAccepted {
+            resource_address: ResourceAddress,
+        },
+        NonZeroPoolUnitSupplyButZeroReserves,
+        InvalidPoolUnitResource {
+            expected: ResourceAddress,
+            actual: ResourceAddress,
+        },
+        ResourceDoesNotBelongToPool {
+            resource_address: ResourceAddress,
+        },
+        MissingOrEmptyBuckets {
+            resource_addresses: IndexSet<ResourceAddress>,
+        },
+        PoolCreationWithSameResource,
+        CantCreatePoolWithLessThanTwoResources,
+        InvalidSwapFee {
+            swap_fee: Decimal,
+        },
+        InvalidWeight {
+            resource_address: ResourceAddress,
+            weight: Decimal,
+        },
+        WeightsDoNotSumToOne {
+            sum: Decimal,
+        },
+        DecimalOverflowError,
+        InvalidGetRedemptionAmount,
+        NoMinimumRatio,
+        ZeroPoolUnitsMinted,
+        LargerContributionRequiredToMeetRatio,
+        SwapOfSameResource {
+            resource_address: ResourceAddress,
+        },
+        InvalidSwapInputAmount {
+            amount: Decimal,
+        },
+        ZeroReservesForSwap,
+        ZeroSwapOutput,
+    }
+
+    impl From<Error> for RuntimeError {
+        fn from(error: Error) -> Self {
+            Self::ApplicationError(ApplicationError::WeightedPoolError(error))
+        }
+    }
+}
+
+pub mod stable_swap_pool {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+    pub enum Error {
+        NonFungibleResourcesAreNotAccepted {
+            resource_address: ResourceAddress,
+        },
+        NonZeroPoolUnitSupplyButZeroReserves,
+        InvalidPoolUnitResource {
+            expected: ResourceAddress,
+            actual: ResourceAddress,
+        },
+        ResourceDoesNotBelongToPool {
+            resource_address: ResourceAddress,
+        },
+        MissingOrEmptyBuckets {
+            resource_addresses: IndexSet<ResourceAddress>,
+        },
+        PoolCreationWithSameResource,
+        CantCreatePoolWithLessThanTwoResources,
+        InvalidSwapFee {
+            swap_fee: Decimal,
+        },
+        InvalidAmplification {
+            amplification: Decimal,
+        },
+        DecimalOverflowError,
+        InvalidGetRedemptionAmount,
+        NoMinimumRatio,
+        ZeroPoolUnitsMinted,
+        LargerContributionRequiredToMeetRatio,
+        SwapOfSameResource {
+            resource_address: ResourceAddress,
+        },
+        InvalidSwapInputAmount {
+            amount: Decimal,
+        },
+        ZeroReservesForSwap,
+        ZeroSwapOutput,
+        InvariantDidNotConverge,
+    }
+
+    impl From<Error> for RuntimeError {
+        fn from(error: Error) -> Self {
+            Self::ApplicationError(ApplicationError::StableSwapPoolError(error))
+        }
+    }
+}
@@ -81,3 +81,75 @@ pub mod multi_resource_pool {
         pub amount: Decimal,
     }
 }
+
+pub mod weighted_pool {
+    use super::*;
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct ContributionEvent {
+        pub contributed_resources: IndexMap<ResourceAddress, Decimal>,
+        pub pool_units_minted: Decimal,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct RedemptionEvent {
+        pub pool_unit_tokens_redeemed: Decimal,
+        pub redeemed_resources: IndexMap<ResourceAddress, Decimal>,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct SwapEvent {
+        pub input_resource_address: ResourceAddress,
+        pub input_amount: Decimal,
+        pub output_resource_address: ResourceAddress,
+        pub output_amount: Decimal,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct WithdrawEvent {
+        pub resource_address: ResourceAddress,
+        pub amount: Decimal,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct DepositEvent {
+        pub resource_address: ResourceAddress,
+        pub amount: Decimal,
+    }
+}
+
+pub mod stable_swap_pool {
+    use super::*;
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct ContributionEvent {
+        pub contributed_resources: IndexMap<ResourceAddress, Decimal>,
+        pub pool_units_minted: Decimal,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct RedemptionEvent {
+        pub pool_unit_tokens_redeemed: Decimal,
+        pub redeemed_resources: IndexMap<ResourceAddress, Decimal>,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct SwapEvent {
+        pub input_resource_address: ResourceAddress,
+        pub input_amount: Decimal,
+        pub output_resource_address: ResourceAddress,
+        pub output_amount: Decimal,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct WithdrawEvent {
+        pub resource_address: ResourceAddress,
+        pub amount: Decimal,
+    }
+
+    #[derive(ScryptoSbor, ScryptoEvent, Debug)]
+    pub struct DepositEvent {
+        pub resource_address: ResourceAddress,
+        pub amount: Decimal,
+    }
+}
@@ -3,7 +3,9 @@
 use super::constants::*;
 use super::substates::multi_resource_pool::*;
 use super::substates::one_resource_pool::*;
+use super::substates::stable_swap_pool::*;
 use super::substates::two_resource_pool::*;
+use super::substates::weighted_pool::*;
 use crate::internal_prelude::*;
 use crate::kernel::kernel_api::*;
 use crate::system::system_callback::*;
@@ -459,6 +461,259 @@ impl PoolNativePackage {
                 Ok(IndexedScryptoValue::from_typed(&rtn))
             }
 
+            // The weighted and stable-swap pools only exist from v1.1 onwards
+            _ => match minor_version {
+                PoolV1MinorVersion::Zero => Err(RuntimeError::ApplicationError(
+                    ApplicationError::ExportDoesNotExist(export_name.to_string()),
+                )),
+                PoolV1MinorVersion::One => Self::invoke_v1_1_only_export(export_name, input, api),
+            },
+        }
+    }
+
+    fn invoke_v1_1_only_export<Y>(
+        export_name: &str,
+        input: &IndexedScryptoValue,
+        api: &mut Y,
+    ) -> Result<IndexedScryptoValue, RuntimeError>
+    where
+        Y: KernelNodeApi + KernelSubstateApi<SystemLockData> + ClientApi<RuntimeError>,
+    {
+        match export_name {
+            WEIGHTED_POOL_INSTANTIATE_EXPORT_NAME => {
+                let WeightedPoolInstantiateInput {
+                    resource_weights,
+                    swap_fee,
+                    pool_manager_rule,
+                    owner_role,
+                    address_reservation,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::instantiate(
+                    resource_weights,
+                    swap_fee,
+                    owner_role,
+                    pool_manager_rule,
+                    address_reservation,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_CONTRIBUTE_EXPORT_NAME => {
+                let WeightedPoolContributeInput { buckets } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::contribute(buckets, api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_REDEEM_EXPORT_NAME => {
+                let WeightedPoolRedeemInput { bucket } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::redeem(bucket, api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_SWAP_EXPORT_NAME => {
+                let WeightedPoolSwapInput {
+                    input_bucket,
+                    output_resource_address,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::swap(
+                    input_bucket,
+                    output_resource_address,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_PROTECTED_DEPOSIT_EXPORT_NAME => {
+                let WeightedPoolProtectedDepositInput { bucket } =
+                    input.as_typed().map_err(|e| {
+                        RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                    })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::protected_deposit(bucket, api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_PROTECTED_WITHDRAW_EXPORT_NAME => {
+                let WeightedPoolProtectedWithdrawInput {
+                    resource_address,
+                    amount,
+                    withdraw_strategy,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::protected_withdraw(
+                    resource_address,
+                    amount,
+                    withdraw_strategy,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_GET_REDEMPTION_VALUE_EXPORT_NAME => {
+                let WeightedPoolGetRedemptionValueInput {
+                    amount_of_pool_units,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::get_redemption_value(
+                    amount_of_pool_units,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_GET_VAULT_AMOUNTS_EXPORT_NAME => {
+                let WeightedPoolGetVaultAmountsInput {} = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::get_vault_amounts(api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            WEIGHTED_POOL_GET_SWAP_OUTPUT_AMOUNT_EXPORT_NAME => {
+                let WeightedPoolGetSwapOutputAmountInput {
+                    input_resource_address,
+                    input_amount,
+                    output_resource_address,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::WeightedPoolBlueprint::get_swap_output_amount(
+                    input_resource_address,
+                    input_amount,
+                    output_resource_address,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_INSTANTIATE_EXPORT_NAME => {
+                let StableSwapPoolInstantiateInput {
+                    resource_addresses,
+                    amplification,
+                    swap_fee,
+                    pool_manager_rule,
+                    owner_role,
+                    address_reservation,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::instantiate(
+                    resource_addresses,
+                    amplification,
+                    swap_fee,
+                    owner_role,
+                    pool_manager_rule,
+                    address_reservation,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_CONTRIBUTE_EXPORT_NAME => {
+                let StableSwapPoolContributeInput { buckets } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::contribute(buckets, api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_REDEEM_EXPORT_NAME => {
+                let StableSwapPoolRedeemInput { bucket } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::redeem(bucket, api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_SWAP_EXPORT_NAME => {
+                let StableSwapPoolSwapInput {
+                    input_bucket,
+                    output_resource_address,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::swap(
+                    input_bucket,
+                    output_resource_address,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_PROTECTED_DEPOSIT_EXPORT_NAME => {
+                let StableSwapPoolProtectedDepositInput { bucket } =
+                    input.as_typed().map_err(|e| {
+                        RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                    })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::protected_deposit(bucket, api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_PROTECTED_WITHDRAW_EXPORT_NAME => {
+                let StableSwapPoolProtectedWithdrawInput {
+                    resource_address,
+                    amount,
+                    withdraw_strategy,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::protected_withdraw(
+                    resource_address,
+                    amount,
+                    withdraw_strategy,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_GET_REDEMPTION_VALUE_EXPORT_NAME => {
+                let StableSwapPoolGetRedemptionValueInput {
+                    amount_of_pool_units,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::get_redemption_value(
+                    amount_of_pool_units,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_GET_VAULT_AMOUNTS_EXPORT_NAME => {
+                let StableSwapPoolGetVaultAmountsInput {} = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::get_vault_amounts(api)?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
+            STABLE_SWAP_POOL_GET_SWAP_OUTPUT_AMOUNT_EXPORT_NAME => {
+                let StableSwapPoolGetSwapOutputAmountInput {
+                    input_resource_address,
+                    input_amount,
+                    output_resource_address,
+                } = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+                let rtn = super::v1_1::StableSwapPoolBlueprint::get_swap_output_amount(
+                    input_resource_address,
+                    input_amount,
+                    output_resource_address,
+                    api,
+                )?;
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+
             _ => Err(RuntimeError::ApplicationError(
                 ApplicationError::ExportDoesNotExist(export_name.to_string()),
             )),
@@ -466,7 +721,7 @@ impl PoolNativePackage {
     }
 
     pub fn definition(minor_version: PoolV1MinorVersion) -> PackageDefinition {
-        let blueprints = indexmap!(
+        let mut blueprints = indexmap!(
             ONE_RESOURCE_POOL_BLUEPRINT_IDENT.to_string()
                 => Self::one_resource_pool_blueprint_definition(minor_version),
             TWO_RESOURCE_POOL_BLUEPRINT_IDENT.to_string()
@@ -475,6 +730,18 @@ impl PoolNativePackage {
                 => Self::multi_resource_pool_blueprint_definition(minor_version),
         );
 
+        // The weighted and stable-swap pools only exist from v1.1 onwards
+        if minor_version == PoolV1MinorVersion::One {
+            blueprints.insert(
+                WEIGHTED_POOL_BLUEPRINT_IDENT.to_string(),
+                Self::weighted_pool_blueprint_definition(),
+            );
+            blueprints.insert(
+                STABLE_SWAP_POOL_BLUEPRINT_IDENT.to_string(),
+                Self::stable_swap_pool_blueprint_definition(),
+            );
+        }
+
         PackageDefinition { blueprints }
     }
 
@@ -973,4 +1240,392 @@ impl PoolNativePackage {
             },
         }
     }
+
+    pub fn weighted_pool_blueprint_definition() -> BlueprintDefinitionInit {
+        let mut aggregator = TypeAggregator::<ScryptoCustomTypeKind>::new();
+
+        let feature_set = WeightedPoolFeatureSet::all_features();
+        let state = WeightedPoolStateSchemaInit::create_schema_init(&mut aggregator);
+
+        let mut functions = index_map_new();
+
+        functions.insert(
+            WEIGHTED_POOL_INSTANTIATE_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: None,
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolInstantiateInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolInstantiateOutput>(),
+                ),
+                export: WEIGHTED_POOL_INSTANTIATE_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_CONTRIBUTE_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolContributeInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolContributeOutput>(),
+                ),
+                export: WEIGHTED_POOL_CONTRIBUTE_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_REDEEM_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolRedeemInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolRedeemOutput>(),
+                ),
+                export: WEIGHTED_POOL_REDEEM_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_PROTECTED_DEPOSIT_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolProtectedDepositInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolProtectedDepositOutput>(),
+                ),
+                export: WEIGHTED_POOL_PROTECTED_DEPOSIT_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_PROTECTED_WITHDRAW_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolProtectedWithdrawInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolProtectedWithdrawOutput>(),
+                ),
+                export: WEIGHTED_POOL_PROTECTED_WITHDRAW_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_GET_REDEMPTION_VALUE_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolGetRedemptionValueInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolGetRedemptionValueOutput>(),
+                ),
+                export: WEIGHTED_POOL_GET_REDEMPTION_VALUE_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_GET_VAULT_AMOUNTS_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref()),
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolGetVaultAmountsInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolGetVaultAmountsOutput>(),
+                ),
+                export: WEIGHTED_POOL_GET_VAULT_AMOUNTS_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_SWAP_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolSwapInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<WeightedPoolSwapOutput>(),
+                ),
+                export: WEIGHTED_POOL_SWAP_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            WEIGHTED_POOL_GET_SWAP_OUTPUT_AMOUNT_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolGetSwapOutputAmountInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<WeightedPoolGetSwapOutputAmountOutput>(),
+                ),
+                export: WEIGHTED_POOL_GET_SWAP_OUTPUT_AMOUNT_EXPORT_NAME.to_string(),
+            },
+        );
+
+        let event_schema = event_schema! {
+            aggregator,
+            [
+                super::events::weighted_pool::ContributionEvent,
+                super::events::weighted_pool::RedemptionEvent,
+                super::events::weighted_pool::SwapEvent,
+                super::events::weighted_pool::WithdrawEvent,
+                super::events::weighted_pool::DepositEvent
+            ]
+        };
+
+        let schema = generate_full_schema(aggregator);
+
+        BlueprintDefinitionInit {
+            blueprint_type: BlueprintType::default(),
+            is_transient: false,
+            dependencies: indexset!(),
+            feature_set,
+
+            schema: BlueprintSchemaInit {
+                generics: vec![],
+                schema,
+                state,
+                events: event_schema,
+                types: BlueprintTypeSchemaInit::default(),
+                functions: BlueprintFunctionsSchemaInit { functions },
+                hooks: BlueprintHooksInit::default(),
+            },
+            royalty_config: PackageRoyaltyConfig::default(),
+            auth_config: AuthConfig {
+                function_auth: FunctionAuth::AllowAll,
+                method_auth: MethodAuthTemplate::StaticRoleDefinition(roles_template! {
+                    roles {
+                        POOL_MANAGER_ROLE;
+                    },
+                    methods {
+                        WEIGHTED_POOL_REDEEM_IDENT => MethodAccessibility::Public;
+                        WEIGHTED_POOL_GET_REDEMPTION_VALUE_IDENT => MethodAccessibility::Public;
+                        WEIGHTED_POOL_GET_VAULT_AMOUNTS_IDENT => MethodAccessibility::Public;
+                        WEIGHTED_POOL_GET_SWAP_OUTPUT_AMOUNT_IDENT => MethodAccessibility::Public;
+                        WEIGHTED_POOL_CONTRIBUTE_IDENT => [POOL_MANAGER_ROLE];
+                        WEIGHTED_POOL_SWAP_IDENT => [POOL_MANAGER_ROLE];
+                        WEIGHTED_POOL_PROTECTED_DEPOSIT_IDENT => [POOL_MANAGER_ROLE];
+                        WEIGHTED_POOL_PROTECTED_WITHDRAW_IDENT => [POOL_MANAGER_ROLE];
+                    }
+                }),
+            },
+        }
+    }
+
+    pub fn stable_swap_pool_blueprint_definition() -> BlueprintDefinitionInit {
+        let mut aggregator = TypeAggregator::<ScryptoCustomTypeKind>::new();
+
+        let feature_set = StableSwapPoolFeatureSet::all_features();
+        let state = StableSwapPoolStateSchemaInit::create_schema_init(&mut aggregator);
+
+        let mut functions = index_map_new();
+
+        functions.insert(
+            STABLE_SWAP_POOL_INSTANTIATE_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: None,
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolInstantiateInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolInstantiateOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_INSTANTIATE_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_CONTRIBUTE_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolContributeInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolContributeOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_CONTRIBUTE_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_REDEEM_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolRedeemInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolRedeemOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_REDEEM_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_PROTECTED_DEPOSIT_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolProtectedDepositInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolProtectedDepositOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_PROTECTED_DEPOSIT_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_PROTECTED_WITHDRAW_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolProtectedWithdrawInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolProtectedWithdrawOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_PROTECTED_WITHDRAW_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_GET_REDEMPTION_VALUE_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolGetRedemptionValueInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolGetRedemptionValueOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_GET_REDEMPTION_VALUE_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_GET_VAULT_AMOUNTS_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolGetVaultAmountsInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolGetVaultAmountsOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_GET_VAULT_AMOUNTS_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_SWAP_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref_mut()),
+                input: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolSwapInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator.add_child_type_and_descendents::<StableSwapPoolSwapOutput>(),
+                ),
+                export: STABLE_SWAP_POOL_SWAP_EXPORT_NAME.to_string(),
+            },
+        );
+
+        functions.insert(
+            STABLE_SWAP_POOL_GET_SWAP_OUTPUT_AMOUNT_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: Some(ReceiverInfo::normal_ref()),
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolGetSwapOutputAmountInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<StableSwapPoolGetSwapOutputAmountOutput>(
+                        ),
+                ),
+                export: STABLE_SWAP_POOL_GET_SWAP_OUTPUT_AMOUNT_EXPORT_NAME.to_string(),
+            },
+        );
+
+        let event_schema = event_schema! {
+            aggregator,
+            [
+                super::events::stable_swap_pool::ContributionEvent,
+                super::events::stable_swap_pool::RedemptionEvent,
+                super::events::stable_swap_pool::SwapEvent,
+                super::events::stable_swap_pool::WithdrawEvent,
+                super::events::stable_swap_pool::DepositEvent
+            ]
+        };
+
+        let schema = generate_full_schema(aggregator);
+
+        BlueprintDefinitionInit {
+            blueprint_type: BlueprintType::default(),
+            is_transient: false,
+            dependencies: indexset!(),
+            feature_set,
+
+            schema: BlueprintSchemaInit {
+                generics: vec![],
+                schema,
+                state,
+                events: event_schema,
+                types: BlueprintTypeSchemaInit::default(),
+                functions: BlueprintFunctionsSchemaInit { functions },
+                hooks: BlueprintHooksInit::default(),
+            },
+            royalty_config: PackageRoyaltyConfig::default(),
+            auth_config: AuthConfig {
+                function_auth: FunctionAuth::AllowAll,
+                method_auth: MethodAuthTemplate::StaticRoleDefinition(roles_template! {
+                    roles {
+                        POOL_MANAGER_ROLE;
+                    },
+                    methods {
+                        STABLE_SWAP_POOL_REDEEM_IDENT => MethodAccessibility::Public;
+                        STABLE_SWAP_POOL_GET_REDEMPTION_VALUE_IDENT => MethodAccessibility::Public;
+                        STABLE_SWAP_POOL_GET_VAULT_AMOUNTS_IDENT => MethodAccessibility::Public;
+                        STABLE_SWAP_POOL_GET_SWAP_OUTPUT_AMOUNT_IDENT => MethodAccessibility::Public;
+                        STABLE_SWAP_POOL_CONTRIBUTE_IDENT => [POOL_MANAGER_ROLE];
+                        STABLE_SWAP_POOL_SWAP_IDENT => [POOL_MANAGER_ROLE];
+                        STABLE_SWAP_POOL_PROTECTED_DEPOSIT_IDENT => [POOL_MANAGER_ROLE];
+                        STABLE_SWAP_POOL_PROTECTED_WITHDRAW_IDENT => [POOL_MANAGER_ROLE];
+                    }
+                }),
+            },
+        }
+    }
 }
@@ -134,3 +134,86 @@ pub mod multi_resource_pool {
         pub pool_unit_resource_manager: ResourceManager,
     }
 }
+
+pub mod weighted_pool {
+    use super::*;
+
+    declare_native_blueprint_state! {
+        blueprint_ident: WeightedPool,
+        blueprint_snake_case: weighted_pool,
+        features: {
+        },
+        fields: {
+            state:  {
+                ident: State,
+                field_type: {
+                    kind: StaticSingleVersioned,
+                },
+                condition: Condition::Always,
+            }
+        },
+        collections: {
+        }
+    }
+
+    pub type WeightedPoolStateV1 = Substate;
+
+    #[derive(Debug, PartialEq, Eq, ScryptoSbor)]
+    #[sbor(type_name = "WeightedPoolSubstate")]
+    pub struct Substate {
+        /// The vaults of the resources of the pool. Just like in the multi-resource pool, this is
+        /// an `IndexMap` as all of the operations on the pool need to read all of the vaults.
+        pub vaults: IndexMap<ResourceAddress, Vault>,
+
+        /// The weights of the resources of the pool, which are all positive and sum up to one.
+        pub weights: IndexMap<ResourceAddress, Decimal>,
+
+        /// The fraction of the input of the swaps which is kept by the pool.
+        pub swap_fee: Decimal,
+
+        /// The resource manager of the pool unit resource that the pool works with.
+        pub pool_unit_resource_manager: ResourceManager,
+    }
+}
+
+pub mod stable_swap_pool {
+    use super::*;
+
+    declare_native_blueprint_state! {
+        blueprint_ident: StableSwapPool,
+        blueprint_snake_case: stable_swap_pool,
+        features: {
+        },
+        fields: {
+            state:  {
+                ident: State,
+                field_type: {
+                    kind: StaticSingleVersioned,
+                },
+                condition: Condition::Always,
+            }
+        },
+        collections: {
+        }
+    }
+
+    pub type StableSwapPoolStateV1 = Substate;
+
+    #[derive(Debug, PartialEq, Eq, ScryptoSbor)]
+    #[sbor(type_name = "StableSwapPoolSubstate")]
+    pub struct Substate {
+        /// The vaults of the resources of the pool. Just like in the multi-resource pool, this is
+        /// an `IndexMap` as all of the operations on the pool need to read all of the vaults.
+        pub vaults: IndexMap<ResourceAddress, Vault>,
+
+        /// The amplification coefficient of the stable-swap invariant. The higher it is, the
+        /// flatter the curve is around the point where all of the reserves are equal.
+        pub amplification: Decimal,
+
+        /// The fraction of the input of the swaps which is kept by the pool.
+        pub swap_fee: Decimal,
+
+        /// The resource manager of the pool unit resource that the pool works with.
+        pub pool_unit_resource_manager: ResourceManager,
+    }
+}
@@ -1,7 +1,11 @@
 mod multi_resource_pool_blueprint;
 mod one_resource_pool_blueprint;
+mod stable_swap_pool_blueprint;
 mod two_resource_pool_blueprint;
+mod weighted_pool_blueprint;
 
 pub use multi_resource_pool_blueprint::*;
 pub use one_resource_pool_blueprint::*;
+pub use stable_swap_pool_blueprint::*;
 pub use two_resource_pool_blueprint::*;
+pub use weighted_pool_blueprint::*;
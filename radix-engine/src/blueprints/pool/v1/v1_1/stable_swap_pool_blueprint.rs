@@ -0,0 +1,900 @@
+use crate::blueprints::pool::v1::constants::*;
+use crate::blueprints::pool::v1::errors::stable_swap_pool::*;
+use crate::blueprints::pool::v1::events::stable_swap_pool::*;
+use crate::blueprints::pool::v1::substates::stable_swap_pool::*;
+use crate::internal_prelude::*;
+use crate::kernel::kernel_api::*;
+use native_sdk::modules::metadata::*;
+use native_sdk::modules::role_assignment::*;
+use native_sdk::modules::royalty::*;
+use native_sdk::resource::*;
+use native_sdk::runtime::*;
+use radix_engine_interface::blueprints::component::*;
+use radix_engine_interface::blueprints::pool::*;
+use radix_engine_interface::prelude::*;
+use radix_engine_interface::*;
+
+/// The maximum amplification coefficient of a stable-swap pool. Beyond it, the curve is so flat
+/// around the balance point that the pool offers no protection against depegs.
+pub const STABLE_SWAP_POOL_MAXIMUM_AMPLIFICATION: u32 = 1_000_000;
+
+/// The maximum number of iterations of the Newton's method used to solve the invariant.
+const MAXIMUM_ITERATIONS: usize = 255;
+
+/// The difference between two consecutive iterations of the Newton's method below which it has
+/// converged, which is `10^-18`.
+const CONVERGENCE_THRESHOLD: PreciseDecimal =
+    PreciseDecimal(I256::from_digits([1_000_000_000_000_000_000, 0, 0, 0]));
+
+/**
+A pool of two or more resources of (nearly) the same value, such as stablecoins or wrapped versions
+of the same asset, which swaps them at a price close to one for as long as its reserves are not too
+imbalanced. This is the stable-swap invariant of Curve.
+
+Let x<sub>1</sub>, x<sub>2</sub>, ..., x<sub>n</sub> be the reserves of the resources in the pool
+and A the amplification coefficient. Swaps keep the invariant D constant, before fees, where D
+solves:
+
+A n<sup>n</sup> &Sigma; x<sub>i</sub> + D = A n<sup>n</sup> D + D<sup>n + 1</sup> / (n<sup>n</sup>
+&Pi; x<sub>i</sub>)
+
+The invariant is a constant sum when A goes to infinity and a constant product when it is zero, so
+the higher the amplification is the closer the price stays to one. It is solved with the Newton's
+method, whose iterations are carried in [`PreciseDecimal`] arithmetic.
+
+Contributions and redemptions are proportional to the reserves, just like in the multi-resource
+pool, as they don't change the prices of the resources.
+*/
+pub struct StableSwapPoolBlueprint;
+impl StableSwapPoolBlueprint {
+    pub fn instantiate<Y>(
+        resource_addresses: IndexSet<ResourceAddress>,
+        amplification: Decimal,
+        swap_fee: Decimal,
+        owner_role: OwnerRole,
+        pool_manager_rule: AccessRule,
+        address_reservation: Option<GlobalAddressReservation>,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolInstantiateOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError> + KernelNodeApi,
+    {
+        // A pool can't be created where one of the resources is non-fungible - error out if any of
+        // them are
+        for resource_address in resource_addresses.iter() {
+            let resource_manager = ResourceManager(*resource_address);
+            if let ResourceType::NonFungible { .. } = resource_manager.resource_type(api)? {
+                return Err(Error::NonFungibleResourcesAreNotAccepted {
+                    resource_address: *resource_address,
+                }
+                .into());
+            }
+        }
+
+        // There can't be any swaps in a pool of a single resource.
+        if resource_addresses.len() < 2 {
+            return Err(Error::CantCreatePoolWithLessThanTwoResources.into());
+        }
+
+        if amplification < Decimal::ONE
+            || amplification > Decimal::from(STABLE_SWAP_POOL_MAXIMUM_AMPLIFICATION)
+        {
+            return Err(Error::InvalidAmplification { amplification }.into());
+        }
+
+        if swap_fee.is_negative() || swap_fee >= Decimal::ONE {
+            return Err(Error::InvalidSwapFee { swap_fee }.into());
+        }
+
+        // Allocating the address of the pool - this is going to be needed for the metadata of the
+        // pool unit resource.
+        let (address_reservation, address) = {
+            if let Some(address_reservation) = address_reservation {
+                let address = api.get_reservation_address(address_reservation.0.as_node_id())?;
+                (address_reservation, address)
+            } else {
+                api.allocate_global_address(BlueprintId {
+                    package_address: POOL_PACKAGE,
+                    blueprint_name: STABLE_SWAP_POOL_BLUEPRINT_IDENT.to_string(),
+                })?
+            }
+        };
+
+        // Creating the pool unit resource
+        let pool_unit_resource_manager = {
+            let component_caller_badge = NonFungibleGlobalId::global_caller_badge(address);
+
+            ResourceManager::new_fungible(
+                owner_role.clone(),
+                true,
+                18,
+                FungibleResourceRoles {
+                    mint_roles: mint_roles! {
+                        minter => rule!(require(component_caller_badge.clone()));
+                        minter_updater => rule!(deny_all);
+                    },
+                    burn_roles: burn_roles! {
+                        burner => rule!(require(component_caller_badge.clone()));
+                        burner_updater => rule!(deny_all);
+                    },
+                    ..Default::default()
+                },
+                metadata_init! {
+                    "pool" => address, locked;
+                },
+                None,
+                api,
+            )?
+        };
+
+        // Creating the pool nodes
+        let role_assignment = RoleAssignment::create(
+            owner_role,
+            indexmap! {
+                ModuleId::Main => roles_init! {
+                    RoleKey { key: POOL_MANAGER_ROLE.to_owned() } => pool_manager_rule;
+                }
+            },
+            api,
+        )?
+        .0;
+        let metadata = Metadata::create_with_data(
+            metadata_init! {
+                "pool_vault_number" => resource_addresses.len() as u64, locked;
+                "pool_resources" => resource_addresses.iter().cloned().map(GlobalAddress::from).collect::<Vec<_>>(), locked;
+                "pool_amplification" => amplification, locked;
+                "pool_swap_fee" => swap_fee, locked;
+                "pool_unit" => GlobalAddress::from(pool_unit_resource_manager.0), locked;
+            },
+            api,
+        )?;
+        let royalty = ComponentRoyalty::create(ComponentRoyaltyConfig::default(), api)?;
+        let object_id = {
+            let substate = Substate {
+                vaults: resource_addresses
+                    .into_iter()
+                    .map(|resource_address| {
+                        Vault::create(resource_address, api).map(|vault| (resource_address, vault))
+                    })
+                    .collect::<Result<_, _>>()?,
+                amplification,
+                swap_fee,
+                pool_unit_resource_manager,
+            };
+            api.new_simple_object(
+                STABLE_SWAP_POOL_BLUEPRINT_IDENT,
+                indexmap! {
+                    StableSwapPoolField::State.field_index() => FieldValue::new(&VersionedStableSwapPoolState::V1(substate)),
+                },
+            )?
+        };
+
+        api.globalize(
+            object_id,
+            indexmap!(
+                AttachedModuleId::RoleAssignment => role_assignment.0,
+                AttachedModuleId::Metadata => metadata.0,
+                AttachedModuleId::Royalty => royalty.0,
+            ),
+            Some(address_reservation),
+        )?;
+
+        Ok(Global::new(ComponentAddress::new_or_panic(
+            address.as_node_id().0,
+        )))
+    }
+
+    /**
+    Contributes resources to the pool in exchange for pool units.
+
+    When the pool is new, all of the resources must be contributed, as the invariant is undefined
+    when any of the reserves is zero. The amount of pool units minted is the value of the invariant
+    for these amounts, which is their sum when they are all equal.
+
+    Otherwise, the resources are accepted in the ratio of the reserves of the pool, and whatever
+    can't be accepted is returned as change. This is the same algorithm as the one of the
+    [`MultiResourcePool::contribute`] function.
+
+    [`MultiResourcePool::contribute`]: super::MultiResourcePoolBlueprint::contribute
+    */
+    pub fn contribute<Y>(
+        buckets: Vec<Bucket>,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolContributeOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            let pool_unit_total_supply = substate
+                .pool_unit_resource_manager
+                .total_supply(api)?
+                .expect("Total supply is always enabled for pool unit resource.");
+            let pool_unit_total_supply = PreciseDecimal::from(pool_unit_total_supply);
+
+            let contribution_information = {
+                let mut information = substate
+                    .vaults
+                    .iter()
+                    .map(|(resource_address, vault)| -> Result<_, RuntimeError> {
+                        Ok((
+                            *resource_address,
+                            ContributionInformation {
+                                resource_address: *resource_address,
+                                vault: Vault(vault.0),
+                                bucket: Bucket::create(*resource_address, api)?,
+                                reserves: vault.amount(api)?.into(),
+                                contribution: PreciseDecimal::ZERO,
+                            },
+                        ))
+                    })
+                    .collect::<Result<IndexMap<_, _>, _>>()?;
+
+                for bucket in buckets {
+                    let resource_address = bucket.resource_address(api)?;
+                    if let Some(information) = information.get_mut(&resource_address) {
+                        information.contribution = information
+                            .contribution
+                            .checked_add(bucket.amount(api)?)
+                            .ok_or(Error::DecimalOverflowError)?;
+                        information.bucket.put(bucket, api)?;
+                    } else {
+                        return Err(Error::ResourceDoesNotBelongToPool { resource_address }.into());
+                    }
+                }
+                information
+            };
+
+            let mut contributed_resources = index_map_new::<ResourceAddress, Decimal>();
+            let (pool_units_to_mint, change_buckets) = if pool_unit_total_supply.is_zero() {
+                // The invariant is only defined when none of the reserves are zero, so the first
+                // contribution must be of all of the resources.
+                let missing_resources = contribution_information
+                    .values()
+                    .filter(|information| information.contribution.is_zero())
+                    .map(|information| information.resource_address)
+                    .collect::<IndexSet<_>>();
+                if !missing_resources.is_empty() {
+                    return Err(Error::MissingOrEmptyBuckets {
+                        resource_addresses: missing_resources,
+                    }
+                    .into());
+                }
+
+                // Pool Units to Mint = D(c1, c2, ..., cn)
+                let contributions = contribution_information
+                    .values()
+                    .map(|information| information.contribution)
+                    .collect::<Vec<_>>();
+                let pool_units_to_mint =
+                    Self::calculate_invariant(&contributions, substate.amplification.into())?
+                        .checked_round(18, RoundingMode::ToZero)
+                        .ok_or(Error::DecimalOverflowError)?;
+
+                for mut information in contribution_information.into_values() {
+                    let amount = information.bucket.amount(api)?;
+                    contributed_resources.insert(information.resource_address, amount);
+                    information.vault.put(information.bucket, api)?;
+                }
+
+                (pool_units_to_mint, Vec::default())
+            } else {
+                let minimum_ratio = contribution_information
+                    .values()
+                    .filter_map(|information| {
+                        if !information.reserves.is_zero() {
+                            information.contribution.checked_div(information.reserves)
+                        } else {
+                            None
+                        }
+                    })
+                    .min()
+                    .ok_or(Error::NoMinimumRatio)?;
+
+                let mut change_buckets = Vec::new();
+                for mut information in contribution_information.into_values() {
+                    let amount_to_contribute = information
+                        .reserves
+                        .checked_mul(minimum_ratio)
+                        .and_then(|value| Decimal::try_from(value).ok())
+                        .ok_or(Error::DecimalOverflowError)?;
+                    let bucket_to_contribute = information.bucket.take_advanced(
+                        amount_to_contribute,
+                        WithdrawStrategy::Rounded(RoundingMode::ToNegativeInfinity),
+                        api,
+                    )?;
+                    let amount_to_contribute = bucket_to_contribute.amount(api)?;
+                    if amount_to_contribute.is_zero() && !information.reserves.is_zero() {
+                        return Err(Error::LargerContributionRequiredToMeetRatio.into());
+                    }
+
+                    information.vault.put(bucket_to_contribute, api)?;
+                    contributed_resources
+                        .insert(information.resource_address, amount_to_contribute);
+
+                    if information.bucket.is_empty(api)? {
+                        information.bucket.drop_empty(api)?;
+                    } else {
+                        change_buckets.push(information.bucket);
+                    }
+                }
+
+                (
+                    pool_unit_total_supply
+                        .checked_mul(minimum_ratio)
+                        .ok_or(Error::DecimalOverflowError)?,
+                    change_buckets,
+                )
+            };
+            let pool_units_to_mint =
+                Decimal::try_from(pool_units_to_mint).map_err(|_| Error::DecimalOverflowError)?;
+            if pool_units_to_mint.is_zero() {
+                return Err(Error::ZeroPoolUnitsMinted.into());
+            }
+
+            let pool_units = substate
+                .pool_unit_resource_manager
+                .mint_fungible(pool_units_to_mint, api)?;
+
+            Runtime::emit_event(
+                api,
+                ContributionEvent {
+                    contributed_resources,
+                    pool_units_minted: pool_units_to_mint,
+                },
+            )?;
+
+            Ok((pool_units, change_buckets))
+        })
+    }
+
+    pub fn redeem<Y>(
+        bucket: Bucket,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolRedeemOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            // Ensure that the passed pool resources are indeed pool resources
+            let bucket_resource_address = bucket.resource_address(api)?;
+            if bucket_resource_address != substate.pool_unit_resource_manager.0 {
+                return Err(Error::InvalidPoolUnitResource {
+                    expected: substate.pool_unit_resource_manager.0,
+                    actual: bucket_resource_address,
+                }
+                .into());
+            }
+
+            let pool_units_to_redeem = bucket.amount(api)?;
+            let pool_units_total_supply = substate
+                .pool_unit_resource_manager
+                .total_supply(api)?
+                .expect("Total supply is always enabled for pool unit resource.");
+            let reserves = Self::reserves(&substate, api)?;
+
+            let amounts_owed = Self::calculate_amount_owed(
+                pool_units_to_redeem,
+                pool_units_total_supply,
+                reserves,
+            )?;
+
+            bucket.burn(api)?;
+            Runtime::emit_event(
+                api,
+                RedemptionEvent {
+                    redeemed_resources: amounts_owed.clone(),
+                    pool_unit_tokens_redeemed: pool_units_to_redeem,
+                },
+            )?;
+
+            // The following part does some unwraps and panic-able operations but should never panic
+            amounts_owed
+                .into_iter()
+                .map(|(resource_address, amount)| {
+                    substate
+                        .vaults
+                        .get_mut(&resource_address)
+                        .unwrap()
+                        .take(amount, api)
+                })
+                .collect::<Result<Vec<Bucket>, _>>()
+        })
+    }
+
+    /**
+    Swaps the given bucket for the output resource, at the price set by the reserves of all of the
+    resources of the pool.
+
+    The input amount net of fees is added to the reserves of the input resource, and the reserves
+    of the output resource which keep the invariant constant are solved for. The output amount is
+    the difference with the current reserves of the output resource, less the convergence
+    threshold of the solver, and rounded down to the divisibility of the output resource, so any
+    rounding is to the benefit of the pool.
+    */
+    pub fn swap<Y>(
+        input_bucket: Bucket,
+        output_resource_address: ResourceAddress,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolSwapOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            let input_resource_address = input_bucket.resource_address(api)?;
+            let input_amount = input_bucket.amount(api)?;
+            let output_amount = Self::calculate_swap_output_amount(
+                &substate,
+                input_resource_address,
+                input_amount,
+                output_resource_address,
+                api,
+            )?;
+
+            substate
+                .vaults
+                .get_mut(&input_resource_address)
+                .expect("Checked when calculating the output amount")
+                .put(input_bucket, api)?;
+            let output_bucket = substate
+                .vaults
+                .get_mut(&output_resource_address)
+                .expect("Checked when calculating the output amount")
+                .take(output_amount, api)?;
+
+            Runtime::emit_event(
+                api,
+                SwapEvent {
+                    input_resource_address,
+                    input_amount,
+                    output_resource_address,
+                    output_amount,
+                },
+            )?;
+
+            Ok(output_bucket)
+        })
+    }
+
+    pub fn protected_deposit<Y>(
+        bucket: Bucket,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolProtectedDepositOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            let resource_address = bucket.resource_address(api)?;
+            let vault = substate.vaults.get_mut(&resource_address);
+            if let Some(vault) = vault {
+                let event = DepositEvent {
+                    amount: bucket.amount(api)?,
+                    resource_address,
+                };
+                vault.put(bucket, api)?;
+                Runtime::emit_event(api, event)?;
+                Ok(())
+            } else {
+                Err(Error::ResourceDoesNotBelongToPool { resource_address }.into())
+            }
+        })
+    }
+
+    pub fn protected_withdraw<Y>(
+        resource_address: ResourceAddress,
+        amount: Decimal,
+        withdraw_strategy: WithdrawStrategy,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolProtectedWithdrawOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            let vault = substate.vaults.get_mut(&resource_address);
+
+            if let Some(vault) = vault {
+                let bucket = vault.take_advanced(amount, withdraw_strategy, api)?;
+                let withdrawn_amount = bucket.amount(api)?;
+
+                Runtime::emit_event(
+                    api,
+                    WithdrawEvent {
+                        amount: withdrawn_amount,
+                        resource_address,
+                    },
+                )?;
+
+                Ok(bucket)
+            } else {
+                Err(Error::ResourceDoesNotBelongToPool { resource_address }.into())
+            }
+        })
+    }
+
+    pub fn get_redemption_value<Y>(
+        amount_of_pool_units: Decimal,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolGetRedemptionValueOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            let pool_units_total_supply = substate
+                .pool_unit_resource_manager
+                .total_supply(api)?
+                .expect("Total supply is always enabled for pool unit resource.");
+
+            if amount_of_pool_units.is_negative()
+                || amount_of_pool_units.is_zero()
+                || amount_of_pool_units > pool_units_total_supply
+            {
+                return Err(Error::InvalidGetRedemptionAmount.into());
+            }
+
+            let reserves = Self::reserves(&substate, api)?;
+            Self::calculate_amount_owed(amount_of_pool_units, pool_units_total_supply, reserves)
+        })
+    }
+
+    pub fn get_vault_amounts<Y>(
+        api: &mut Y,
+    ) -> Result<StableSwapPoolGetVaultAmountsOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            substate
+                .vaults
+                .into_iter()
+                .map(|(resource_address, vault)| {
+                    vault.amount(api).map(|amount| (resource_address, amount))
+                })
+                .collect::<Result<IndexMap<_, _>, _>>()
+        })
+    }
+
+    pub fn get_swap_output_amount<Y>(
+        input_resource_address: ResourceAddress,
+        input_amount: Decimal,
+        output_resource_address: ResourceAddress,
+        api: &mut Y,
+    ) -> Result<StableSwapPoolGetSwapOutputAmountOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            Self::calculate_swap_output_amount(
+                &substate,
+                input_resource_address,
+                input_amount,
+                output_resource_address,
+                api,
+            )
+        })
+    }
+
+    //===================
+    // Utility Functions
+    //===================
+
+    fn with_state<Y, F, O>(api: &mut Y, callback: F) -> Result<O, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+        F: FnOnce(Substate, &mut Y) -> Result<O, RuntimeError>,
+    {
+        // Open
+        let substate_key = StableSwapPoolField::State.into();
+        let handle =
+            api.actor_open_field(ACTOR_STATE_SELF, substate_key, LockFlags::read_only())?;
+        let substate = api
+            .field_read_typed::<VersionedStableSwapPoolState>(handle)?
+            .into_latest();
+
+        // Op
+        let rtn = callback(substate, api);
+
+        // Close
+        if rtn.is_ok() {
+            api.field_close(handle)?;
+        }
+        rtn
+    }
+
+    fn reserves<Y>(
+        substate: &Substate,
+        api: &mut Y,
+    ) -> Result<IndexMap<ResourceAddress, ReserveResourceInformation>, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        let mut reserves = index_map_new();
+        for (resource_address, vault) in substate.vaults.iter() {
+            reserves.insert(
+                *resource_address,
+                ReserveResourceInformation {
+                    reserves: vault.amount(api)?,
+                    divisibility: Self::divisibility(*resource_address, api)?,
+                },
+            );
+        }
+        Ok(reserves)
+    }
+
+    fn divisibility<Y>(resource_address: ResourceAddress, api: &mut Y) -> Result<u8, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        ResourceManager(resource_address)
+            .resource_type(api)
+            .map(|resource_type| {
+                if let ResourceType::Fungible { divisibility } = resource_type {
+                    divisibility
+                } else {
+                    panic!("Impossible case, we check for this in the constructor and have a test for this.")
+                }
+            })
+    }
+
+    fn calculate_swap_output_amount<Y>(
+        substate: &Substate,
+        input_resource_address: ResourceAddress,
+        input_amount: Decimal,
+        output_resource_address: ResourceAddress,
+        api: &mut Y,
+    ) -> Result<Decimal, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        if input_resource_address == output_resource_address {
+            return Err(Error::SwapOfSameResource {
+                resource_address: input_resource_address,
+            }
+            .into());
+        }
+        if !input_amount.is_positive() {
+            return Err(Error::InvalidSwapInputAmount {
+                amount: input_amount,
+            }
+            .into());
+        }
+
+        for resource_address in [input_resource_address, output_resource_address] {
+            if !substate.vaults.contains_key(&resource_address) {
+                return Err(Error::ResourceDoesNotBelongToPool { resource_address }.into());
+            }
+        }
+
+        let mut reserves = Vec::with_capacity(substate.vaults.len());
+        for vault in substate.vaults.values() {
+            let amount = vault.amount(api)?;
+            if amount.is_zero() {
+                return Err(Error::ZeroReservesForSwap.into());
+            }
+            reserves.push(PreciseDecimal::from(amount));
+        }
+        let input_index = substate
+            .vaults
+            .get_index_of(&input_resource_address)
+            .expect("Checked above");
+        let output_index = substate
+            .vaults
+            .get_index_of(&output_resource_address)
+            .expect("Checked above");
+
+        let amplification = PreciseDecimal::from(substate.amplification);
+        let invariant = Self::calculate_invariant(&reserves, amplification)?;
+
+        reserves[input_index] = PreciseDecimal::ONE
+            .checked_sub(substate.swap_fee)
+            .and_then(|net_fraction| net_fraction.checked_mul(input_amount))
+            .and_then(|net_input_amount| reserves[input_index].checked_add(net_input_amount))
+            .ok_or(Error::DecimalOverflowError)?;
+        let new_output_reserves =
+            Self::calculate_reserves(&reserves, output_index, invariant, amplification)?;
+
+        let output_amount = reserves[output_index]
+            .checked_sub(new_output_reserves)
+            .and_then(|value| value.checked_sub(CONVERGENCE_THRESHOLD))
+            .map(|value| value.max(PreciseDecimal::ZERO))
+            .and_then(|value| Decimal::try_from(value).ok())
+            .ok_or(Error::DecimalOverflowError)?;
+
+        let divisibility = Self::divisibility(output_resource_address, api)?;
+        let output_amount = output_amount
+            .checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+            .ok_or(Error::DecimalOverflowError)?;
+        if output_amount.is_zero() {
+            return Err(Error::ZeroSwapOutput.into());
+        }
+
+        Ok(output_amount)
+    }
+
+    /// Solves the invariant `D` for the given reserves, starting from their sum, with the iteration:
+    ///
+    /// `D = D (A n^n S + n D_P) / ((A n^n - 1) D + (n + 1) D_P)`, where `D_P = D^(n + 1) / (n^n P)`
+    ///
+    /// with `S` and `P` the sum and product of the reserves.
+    fn calculate_invariant(
+        reserves: &[PreciseDecimal],
+        amplification: PreciseDecimal,
+    ) -> Result<PreciseDecimal, RuntimeError> {
+        let n = PreciseDecimal::from(reserves.len() as u64);
+        let amplification_n_n = n
+            .checked_powi(reserves.len() as i64)
+            .and_then(|n_n| n_n.checked_mul(amplification))
+            .ok_or(Error::DecimalOverflowError)?;
+        let sum = reserves
+            .iter()
+            .try_fold(PreciseDecimal::ZERO, |accumulator, amount| {
+                accumulator.checked_add(*amount)
+            })
+            .ok_or(Error::DecimalOverflowError)?;
+        if sum.is_zero() {
+            return Ok(PreciseDecimal::ZERO);
+        }
+
+        let mut invariant = sum;
+        for _ in 0..MAXIMUM_ITERATIONS {
+            // The divisions are done first so that the intermediate values stay in the order of
+            // magnitude of the invariant.
+            let d_p = reserves
+                .iter()
+                .try_fold(invariant, |d_p, amount| {
+                    amount
+                        .checked_mul(n)
+                        .and_then(|denominator| invariant.checked_div(denominator))
+                        .and_then(|factor| d_p.checked_mul(factor))
+                })
+                .ok_or(Error::DecimalOverflowError)?;
+            let numerator = amplification_n_n
+                .checked_mul(sum)
+                .and_then(|value| value.checked_add(d_p.checked_mul(n)?));
+            let denominator = amplification_n_n
+                .checked_sub(PreciseDecimal::ONE)
+                .and_then(|value| value.checked_mul(invariant))
+                .and_then(|value| value.checked_add(d_p.checked_mul(n.checked_add(1)?)?));
+            let previous_invariant = invariant;
+            invariant = numerator
+                .zip(denominator)
+                .and_then(|(numerator, denominator)| numerator.checked_div(denominator))
+                .and_then(|factor| invariant.checked_mul(factor))
+                .ok_or(Error::DecimalOverflowError)?;
+
+            if invariant
+                .checked_sub(previous_invariant)
+                .ok_or(Error::DecimalOverflowError)?
+                .checked_abs()
+                .ok_or(Error::DecimalOverflowError)?
+                <= CONVERGENCE_THRESHOLD
+            {
+                return Ok(invariant);
+            }
+        }
+
+        Err(Error::InvariantDidNotConverge.into())
+    }
+
+    /// Solves the reserves of the resource at the given index for which the invariant is `D`, given
+    /// the reserves of the other resources, starting from `D`, with the iteration:
+    ///
+    /// `y = (y^2 + c) / (2 y + b - D)`, where `c = D^(n + 1) / (n^n P' A n^n n)` and
+    /// `b = S' + D / (A n^n)`
+    ///
+    /// with `S'` and `P'` the sum and product of the reserves of the other resources.
+    fn calculate_reserves(
+        reserves: &[PreciseDecimal],
+        index: usize,
+        invariant: PreciseDecimal,
+        amplification: PreciseDecimal,
+    ) -> Result<PreciseDecimal, RuntimeError> {
+        let n = PreciseDecimal::from(reserves.len() as u64);
+        let amplification_n_n = n
+            .checked_powi(reserves.len() as i64)
+            .and_then(|n_n| n_n.checked_mul(amplification))
+            .ok_or(Error::DecimalOverflowError)?;
+
+        let mut sum = PreciseDecimal::ZERO;
+        let mut c = invariant;
+        for (_, amount) in reserves.iter().enumerate().filter(|(i, _)| *i != index) {
+            sum = sum
+                .checked_add(*amount)
+                .ok_or(Error::DecimalOverflowError)?;
+            c = amount
+                .checked_mul(n)
+                .and_then(|denominator| invariant.checked_div(denominator))
+                .and_then(|factor| c.checked_mul(factor))
+                .ok_or(Error::DecimalOverflowError)?;
+        }
+        let c = amplification_n_n
+            .checked_mul(n)
+            .and_then(|denominator| invariant.checked_div(denominator))
+            .and_then(|factor| c.checked_mul(factor))
+            .ok_or(Error::DecimalOverflowError)?;
+        let b = invariant
+            .checked_div(amplification_n_n)
+            .and_then(|value| value.checked_add(sum))
+            .ok_or(Error::DecimalOverflowError)?;
+
+        let mut y = invariant;
+        for _ in 0..MAXIMUM_ITERATIONS {
+            let previous_y = y;
+            y = y
+                .checked_mul(y)
+                .and_then(|y_squared| y_squared.checked_add(c))
+                .zip(
+                    y.checked_mul(2)
+                        .and_then(|value| value.checked_add(b))
+                        .and_then(|value| value.checked_sub(invariant)),
+                )
+                .and_then(|(numerator, denominator)| numerator.checked_div(denominator))
+                .ok_or(Error::DecimalOverflowError)?;
+
+            if y.checked_sub(previous_y)
+                .ok_or(Error::DecimalOverflowError)?
+                .checked_abs()
+                .ok_or(Error::DecimalOverflowError)?
+                <= CONVERGENCE_THRESHOLD
+            {
+                return Ok(y);
+            }
+        }
+
+        Err(Error::InvariantDidNotConverge.into())
+    }
+
+    fn calculate_amount_owed(
+        pool_units_to_redeem: Decimal,
+        pool_units_total_supply: Decimal,
+        reserves: IndexMap<ResourceAddress, ReserveResourceInformation>,
+    ) -> Result<IndexMap<ResourceAddress, Decimal>, RuntimeError> {
+        let pool_units_to_redeem = PreciseDecimal::from(pool_units_to_redeem);
+        let pool_units_total_supply = PreciseDecimal::from(pool_units_total_supply);
+
+        reserves
+            .into_iter()
+            .map(
+                |(
+                    resource_address,
+                    ReserveResourceInformation {
+                        divisibility,
+                        reserves,
+                    },
+                )| {
+                    let reserves = PreciseDecimal::from(reserves);
+                    let amount_owed = pool_units_to_redeem
+                        .checked_div(pool_units_total_supply)
+                        .and_then(|d| d.checked_mul(reserves))
+                        .ok_or(Error::DecimalOverflowError)?;
+
+                    let amount_owed = Decimal::try_from(amount_owed)
+                        .ok()
+                        .and_then(|value| {
+                            value.checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+                        })
+                        .ok_or(Error::DecimalOverflowError)?;
+
+                    Ok((resource_address, amount_owed))
+                },
+            )
+            .collect()
+    }
+}
+
+struct ReserveResourceInformation {
+    reserves: Decimal,
+    divisibility: u8,
+}
+
+#[derive(Debug)]
+struct ContributionInformation {
+    /// The address of the resource.
+    pub resource_address: ResourceAddress,
+    /// The vault containing the reserves.
+    pub vault: Vault,
+    /// The bucket of the tokens the user wishes to contribute. Might not be contributed in full.
+    pub bucket: Bucket,
+    /// The amount of reserves in the vault.
+    pub reserves: PreciseDecimal,
+    /// The amount of resources the user wishes to contribute.
+    pub contribution: PreciseDecimal,
+}
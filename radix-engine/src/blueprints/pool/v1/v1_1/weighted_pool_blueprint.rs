@@ -0,0 +1,790 @@
+use crate::blueprints::pool::v1::constants::*;
+use crate::blueprints::pool::v1::errors::weighted_pool::*;
+use crate::blueprints::pool::v1::events::weighted_pool::*;
+use crate::blueprints::pool::v1::substates::weighted_pool::*;
+use crate::internal_prelude::*;
+use crate::kernel::kernel_api::*;
+use native_sdk::modules::metadata::*;
+use native_sdk::modules::role_assignment::*;
+use native_sdk::modules::royalty::*;
+use native_sdk::resource::*;
+use native_sdk::runtime::*;
+use radix_engine_interface::blueprints::component::*;
+use radix_engine_interface::blueprints::pool::*;
+use radix_engine_interface::prelude::*;
+use radix_engine_interface::*;
+
+/// The minimum weight of a resource in a weighted pool. This bounds the exponent of the swap
+/// formula to `(1 - MINIMUM_WEIGHT) / MINIMUM_WEIGHT`, which keeps it within what the decimal types
+/// can evaluate precisely.
+pub const WEIGHTED_POOL_MINIMUM_WEIGHT: Decimal = Decimal::ONE_HUNDREDTH;
+
+/**
+A pool of two or more resources, each with a weight, which holds the value of the reserves of each
+resource at its weight of the value of the pool. This is the generalization of the constant product
+pool where the weights may be arbitrary.
+
+Let b<sub>1</sub>, b<sub>2</sub>, ..., b<sub>n</sub> be the reserves of the resources in the pool
+and w<sub>1</sub>, w<sub>2</sub>, ..., w<sub>n</sub> their weights, which sum up to one. Swaps keep
+the invariant V = b<sub>1</sub><sup>w<sub>1</sub></sup> b<sub>2</sub><sup>w<sub>2</sub></sup> ...
+b<sub>n</sub><sup>w<sub>n</sub></sup> constant, before fees, which are kept by the pool and thus
+increase the value of the pool units.
+
+Contributions and redemptions are proportional to the reserves, just like in the multi-resource
+pool, as they don't change the prices of the resources.
+*/
+pub struct WeightedPoolBlueprint;
+impl WeightedPoolBlueprint {
+    pub fn instantiate<Y>(
+        resource_weights: IndexMap<ResourceAddress, Decimal>,
+        swap_fee: Decimal,
+        owner_role: OwnerRole,
+        pool_manager_rule: AccessRule,
+        address_reservation: Option<GlobalAddressReservation>,
+        api: &mut Y,
+    ) -> Result<WeightedPoolInstantiateOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError> + KernelNodeApi,
+    {
+        // A pool can't be created where one of the resources is non-fungible - error out if any of
+        // them are
+        for resource_address in resource_weights.keys() {
+            let resource_manager = ResourceManager(*resource_address);
+            if let ResourceType::NonFungible { .. } = resource_manager.resource_type(api)? {
+                return Err(Error::NonFungibleResourcesAreNotAccepted {
+                    resource_address: *resource_address,
+                }
+                .into());
+            }
+        }
+
+        // There can't be any swaps in a pool of a single resource.
+        if resource_weights.len() < 2 {
+            return Err(Error::CantCreatePoolWithLessThanTwoResources.into());
+        }
+
+        for (resource_address, weight) in resource_weights.iter() {
+            if *weight < WEIGHTED_POOL_MINIMUM_WEIGHT {
+                return Err(Error::InvalidWeight {
+                    resource_address: *resource_address,
+                    weight: *weight,
+                }
+                .into());
+            }
+        }
+        let sum = resource_weights
+            .values()
+            .try_fold(Decimal::ZERO, |accumulator, weight| {
+                accumulator.checked_add(*weight)
+            })
+            .ok_or(Error::DecimalOverflowError)?;
+        if sum != Decimal::ONE {
+            return Err(Error::WeightsDoNotSumToOne { sum }.into());
+        }
+
+        if swap_fee.is_negative() || swap_fee >= Decimal::ONE {
+            return Err(Error::InvalidSwapFee { swap_fee }.into());
+        }
+
+        // Allocating the address of the pool - this is going to be needed for the metadata of the
+        // pool unit resource.
+        let (address_reservation, address) = {
+            if let Some(address_reservation) = address_reservation {
+                let address = api.get_reservation_address(address_reservation.0.as_node_id())?;
+                (address_reservation, address)
+            } else {
+                api.allocate_global_address(BlueprintId {
+                    package_address: POOL_PACKAGE,
+                    blueprint_name: WEIGHTED_POOL_BLUEPRINT_IDENT.to_string(),
+                })?
+            }
+        };
+
+        // Creating the pool unit resource
+        let pool_unit_resource_manager = {
+            let component_caller_badge = NonFungibleGlobalId::global_caller_badge(address);
+
+            ResourceManager::new_fungible(
+                owner_role.clone(),
+                true,
+                18,
+                FungibleResourceRoles {
+                    mint_roles: mint_roles! {
+                        minter => rule!(require(component_caller_badge.clone()));
+                        minter_updater => rule!(deny_all);
+                    },
+                    burn_roles: burn_roles! {
+                        burner => rule!(require(component_caller_badge.clone()));
+                        burner_updater => rule!(deny_all);
+                    },
+                    ..Default::default()
+                },
+                metadata_init! {
+                    "pool" => address, locked;
+                },
+                None,
+                api,
+            )?
+        };
+
+        // Creating the pool nodes
+        let role_assignment = RoleAssignment::create(
+            owner_role,
+            indexmap! {
+                ModuleId::Main => roles_init! {
+                    RoleKey { key: POOL_MANAGER_ROLE.to_owned() } => pool_manager_rule;
+                }
+            },
+            api,
+        )?
+        .0;
+        let metadata = Metadata::create_with_data(
+            metadata_init! {
+                "pool_vault_number" => resource_weights.len() as u64, locked;
+                "pool_resources" => resource_weights.keys().cloned().map(GlobalAddress::from).collect::<Vec<_>>(), locked;
+                "pool_weights" => resource_weights.values().cloned().collect::<Vec<_>>(), locked;
+                "pool_swap_fee" => swap_fee, locked;
+                "pool_unit" => GlobalAddress::from(pool_unit_resource_manager.0), locked;
+            },
+            api,
+        )?;
+        let royalty = ComponentRoyalty::create(ComponentRoyaltyConfig::default(), api)?;
+        let object_id = {
+            let substate = Substate {
+                vaults: resource_weights
+                    .keys()
+                    .map(|resource_address| {
+                        Vault::create(*resource_address, api)
+                            .map(|vault| (*resource_address, vault))
+                    })
+                    .collect::<Result<_, _>>()?,
+                weights: resource_weights,
+                swap_fee,
+                pool_unit_resource_manager,
+            };
+            api.new_simple_object(
+                WEIGHTED_POOL_BLUEPRINT_IDENT,
+                indexmap! {
+                    WeightedPoolField::State.field_index() => FieldValue::new(&VersionedWeightedPoolState::V1(substate)),
+                },
+            )?
+        };
+
+        api.globalize(
+            object_id,
+            indexmap!(
+                AttachedModuleId::RoleAssignment => role_assignment.0,
+                AttachedModuleId::Metadata => metadata.0,
+                AttachedModuleId::Royalty => royalty.0,
+            ),
+            Some(address_reservation),
+        )?;
+
+        Ok(Global::new(ComponentAddress::new_or_panic(
+            address.as_node_id().0,
+        )))
+    }
+
+    /**
+    Contributes resources to the pool in exchange for pool units.
+
+    When the pool is new, all of the resources must be contributed, as their amounts set the prices
+    of the resources in the pool. The amount of pool units minted is the value of the invariant for
+    these amounts, that is, the weighted geometric mean of the contributions.
+
+    Otherwise, the resources are accepted in the ratio of the reserves of the pool, and whatever
+    can't be accepted is returned as change. This is the same algorithm as the one of the
+    [`MultiResourcePool::contribute`] function.
+
+    [`MultiResourcePool::contribute`]: super::MultiResourcePoolBlueprint::contribute
+    */
+    pub fn contribute<Y>(
+        buckets: Vec<Bucket>,
+        api: &mut Y,
+    ) -> Result<WeightedPoolContributeOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            let pool_unit_total_supply = substate
+                .pool_unit_resource_manager
+                .total_supply(api)?
+                .expect("Total supply is always enabled for pool unit resource.");
+            let pool_unit_total_supply = PreciseDecimal::from(pool_unit_total_supply);
+
+            let contribution_information = {
+                let mut information = substate
+                    .vaults
+                    .iter()
+                    .map(|(resource_address, vault)| -> Result<_, RuntimeError> {
+                        Ok((
+                            *resource_address,
+                            ContributionInformation {
+                                resource_address: *resource_address,
+                                vault: Vault(vault.0),
+                                bucket: Bucket::create(*resource_address, api)?,
+                                reserves: vault.amount(api)?.into(),
+                                contribution: PreciseDecimal::ZERO,
+                            },
+                        ))
+                    })
+                    .collect::<Result<IndexMap<_, _>, _>>()?;
+
+                for bucket in buckets {
+                    let resource_address = bucket.resource_address(api)?;
+                    if let Some(information) = information.get_mut(&resource_address) {
+                        information.contribution = information
+                            .contribution
+                            .checked_add(bucket.amount(api)?)
+                            .ok_or(Error::DecimalOverflowError)?;
+                        information.bucket.put(bucket, api)?;
+                    } else {
+                        return Err(Error::ResourceDoesNotBelongToPool { resource_address }.into());
+                    }
+                }
+                information
+            };
+
+            let mut contributed_resources = index_map_new::<ResourceAddress, Decimal>();
+            let (pool_units_to_mint, change_buckets) = if pool_unit_total_supply.is_zero() {
+                // The prices of the resources are set by the first contribution, which must thus
+                // be of all of the resources.
+                let missing_resources = contribution_information
+                    .values()
+                    .filter(|information| information.contribution.is_zero())
+                    .map(|information| information.resource_address)
+                    .collect::<IndexSet<_>>();
+                if !missing_resources.is_empty() {
+                    return Err(Error::MissingOrEmptyBuckets {
+                        resource_addresses: missing_resources,
+                    }
+                    .into());
+                }
+
+                // Pool Units to Mint = c1^w1 * c2^w2 * ... * cn^wn
+                let pool_units_to_mint = contribution_information
+                    .values()
+                    .try_fold(PreciseDecimal::ONE, |accumulator, information| {
+                        let weight = PreciseDecimal::from(
+                            *substate.weights.get(&information.resource_address)?,
+                        );
+                        information
+                            .contribution
+                            .checked_pow(weight, RoundingMode::ToZero)
+                            .and_then(|value| value.checked_mul(accumulator))
+                    })
+                    .and_then(|value| value.checked_round(18, RoundingMode::ToZero))
+                    .ok_or(Error::DecimalOverflowError)?;
+
+                for mut information in contribution_information.into_values() {
+                    let amount = information.bucket.amount(api)?;
+                    contributed_resources.insert(information.resource_address, amount);
+                    information.vault.put(information.bucket, api)?;
+                }
+
+                (pool_units_to_mint, Vec::default())
+            } else {
+                let minimum_ratio = contribution_information
+                    .values()
+                    .filter_map(|information| {
+                        if !information.reserves.is_zero() {
+                            information.contribution.checked_div(information.reserves)
+                        } else {
+                            None
+                        }
+                    })
+                    .min()
+                    .ok_or(Error::NoMinimumRatio)?;
+
+                let mut change_buckets = Vec::new();
+                for mut information in contribution_information.into_values() {
+                    let amount_to_contribute = information
+                        .reserves
+                        .checked_mul(minimum_ratio)
+                        .and_then(|value| Decimal::try_from(value).ok())
+                        .ok_or(Error::DecimalOverflowError)?;
+                    let bucket_to_contribute = information.bucket.take_advanced(
+                        amount_to_contribute,
+                        WithdrawStrategy::Rounded(RoundingMode::ToNegativeInfinity),
+                        api,
+                    )?;
+                    let amount_to_contribute = bucket_to_contribute.amount(api)?;
+                    if amount_to_contribute.is_zero() && !information.reserves.is_zero() {
+                        return Err(Error::LargerContributionRequiredToMeetRatio.into());
+                    }
+
+                    information.vault.put(bucket_to_contribute, api)?;
+                    contributed_resources
+                        .insert(information.resource_address, amount_to_contribute);
+
+                    if information.bucket.is_empty(api)? {
+                        information.bucket.drop_empty(api)?;
+                    } else {
+                        change_buckets.push(information.bucket);
+                    }
+                }
+
+                (
+                    pool_unit_total_supply
+                        .checked_mul(minimum_ratio)
+                        .ok_or(Error::DecimalOverflowError)?,
+                    change_buckets,
+                )
+            };
+            let pool_units_to_mint =
+                Decimal::try_from(pool_units_to_mint).map_err(|_| Error::DecimalOverflowError)?;
+            if pool_units_to_mint.is_zero() {
+                return Err(Error::ZeroPoolUnitsMinted.into());
+            }
+
+            let pool_units = substate
+                .pool_unit_resource_manager
+                .mint_fungible(pool_units_to_mint, api)?;
+
+            Runtime::emit_event(
+                api,
+                ContributionEvent {
+                    contributed_resources,
+                    pool_units_minted: pool_units_to_mint,
+                },
+            )?;
+
+            Ok((pool_units, change_buckets))
+        })
+    }
+
+    pub fn redeem<Y>(bucket: Bucket, api: &mut Y) -> Result<WeightedPoolRedeemOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            // Ensure that the passed pool resources are indeed pool resources
+            let bucket_resource_address = bucket.resource_address(api)?;
+            if bucket_resource_address != substate.pool_unit_resource_manager.0 {
+                return Err(Error::InvalidPoolUnitResource {
+                    expected: substate.pool_unit_resource_manager.0,
+                    actual: bucket_resource_address,
+                }
+                .into());
+            }
+
+            let pool_units_to_redeem = bucket.amount(api)?;
+            let pool_units_total_supply = substate
+                .pool_unit_resource_manager
+                .total_supply(api)?
+                .expect("Total supply is always enabled for pool unit resource.");
+            let reserves = Self::reserves(&substate, api)?;
+
+            let amounts_owed = Self::calculate_amount_owed(
+                pool_units_to_redeem,
+                pool_units_total_supply,
+                reserves,
+            )?;
+
+            bucket.burn(api)?;
+            Runtime::emit_event(
+                api,
+                RedemptionEvent {
+                    redeemed_resources: amounts_owed.clone(),
+                    pool_unit_tokens_redeemed: pool_units_to_redeem,
+                },
+            )?;
+
+            // The following part does some unwraps and panic-able operations but should never panic
+            amounts_owed
+                .into_iter()
+                .map(|(resource_address, amount)| {
+                    substate
+                        .vaults
+                        .get_mut(&resource_address)
+                        .unwrap()
+                        .take(amount, api)
+                })
+                .collect::<Result<Vec<Bucket>, _>>()
+        })
+    }
+
+    /**
+    Swaps the given bucket for the output resource, at the price set by the reserves and weights
+    of the two resources.
+
+    Let b<sub>i</sub> and w<sub>i</sub> be the reserves and weight of the input resource,
+    b<sub>o</sub> and w<sub>o</sub> the ones of the output resource, a<sub>i</sub> the amount of
+    the input and f the swap fee. The output amount keeps the invariant constant for the input net
+    of fees:
+
+    a<sub>o</sub> = b<sub>o</sub> (1 - (b<sub>i</sub> / (b<sub>i</sub> + a<sub>i</sub>
+    (1 - f)))<sup>w<sub>i</sub> / w<sub>o</sub></sup>)
+
+    The output amount is rounded down to the divisibility of the output resource, so any rounding
+    is to the benefit of the pool.
+    */
+    pub fn swap<Y>(
+        input_bucket: Bucket,
+        output_resource_address: ResourceAddress,
+        api: &mut Y,
+    ) -> Result<WeightedPoolSwapOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            let input_resource_address = input_bucket.resource_address(api)?;
+            let input_amount = input_bucket.amount(api)?;
+            let output_amount = Self::calculate_swap_output_amount(
+                &substate,
+                input_resource_address,
+                input_amount,
+                output_resource_address,
+                api,
+            )?;
+
+            substate
+                .vaults
+                .get_mut(&input_resource_address)
+                .expect("Checked when calculating the output amount")
+                .put(input_bucket, api)?;
+            let output_bucket = substate
+                .vaults
+                .get_mut(&output_resource_address)
+                .expect("Checked when calculating the output amount")
+                .take(output_amount, api)?;
+
+            Runtime::emit_event(
+                api,
+                SwapEvent {
+                    input_resource_address,
+                    input_amount,
+                    output_resource_address,
+                    output_amount,
+                },
+            )?;
+
+            Ok(output_bucket)
+        })
+    }
+
+    pub fn protected_deposit<Y>(
+        bucket: Bucket,
+        api: &mut Y,
+    ) -> Result<WeightedPoolProtectedDepositOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            let resource_address = bucket.resource_address(api)?;
+            let vault = substate.vaults.get_mut(&resource_address);
+            if let Some(vault) = vault {
+                let event = DepositEvent {
+                    amount: bucket.amount(api)?,
+                    resource_address,
+                };
+                vault.put(bucket, api)?;
+                Runtime::emit_event(api, event)?;
+                Ok(())
+            } else {
+                Err(Error::ResourceDoesNotBelongToPool { resource_address }.into())
+            }
+        })
+    }
+
+    pub fn protected_withdraw<Y>(
+        resource_address: ResourceAddress,
+        amount: Decimal,
+        withdraw_strategy: WithdrawStrategy,
+        api: &mut Y,
+    ) -> Result<WeightedPoolProtectedWithdrawOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |mut substate, api| {
+            let vault = substate.vaults.get_mut(&resource_address);
+
+            if let Some(vault) = vault {
+                let bucket = vault.take_advanced(amount, withdraw_strategy, api)?;
+                let withdrawn_amount = bucket.amount(api)?;
+
+                Runtime::emit_event(
+                    api,
+                    WithdrawEvent {
+                        amount: withdrawn_amount,
+                        resource_address,
+                    },
+                )?;
+
+                Ok(bucket)
+            } else {
+                Err(Error::ResourceDoesNotBelongToPool { resource_address }.into())
+            }
+        })
+    }
+
+    pub fn get_redemption_value<Y>(
+        amount_of_pool_units: Decimal,
+        api: &mut Y,
+    ) -> Result<WeightedPoolGetRedemptionValueOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            let pool_units_total_supply = substate
+                .pool_unit_resource_manager
+                .total_supply(api)?
+                .expect("Total supply is always enabled for pool unit resource.");
+
+            if amount_of_pool_units.is_negative()
+                || amount_of_pool_units.is_zero()
+                || amount_of_pool_units > pool_units_total_supply
+            {
+                return Err(Error::InvalidGetRedemptionAmount.into());
+            }
+
+            let reserves = Self::reserves(&substate, api)?;
+            Self::calculate_amount_owed(amount_of_pool_units, pool_units_total_supply, reserves)
+        })
+    }
+
+    pub fn get_vault_amounts<Y>(
+        api: &mut Y,
+    ) -> Result<WeightedPoolGetVaultAmountsOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            substate
+                .vaults
+                .into_iter()
+                .map(|(resource_address, vault)| {
+                    vault.amount(api).map(|amount| (resource_address, amount))
+                })
+                .collect::<Result<IndexMap<_, _>, _>>()
+        })
+    }
+
+    pub fn get_swap_output_amount<Y>(
+        input_resource_address: ResourceAddress,
+        input_amount: Decimal,
+        output_resource_address: ResourceAddress,
+        api: &mut Y,
+    ) -> Result<WeightedPoolGetSwapOutputAmountOutput, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        Self::with_state(api, |substate, api| {
+            Self::calculate_swap_output_amount(
+                &substate,
+                input_resource_address,
+                input_amount,
+                output_resource_address,
+                api,
+            )
+        })
+    }
+
+    //===================
+    // Utility Functions
+    //===================
+
+    fn with_state<Y, F, O>(api: &mut Y, callback: F) -> Result<O, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+        F: FnOnce(Substate, &mut Y) -> Result<O, RuntimeError>,
+    {
+        // Open
+        let substate_key = WeightedPoolField::State.into();
+        let handle =
+            api.actor_open_field(ACTOR_STATE_SELF, substate_key, LockFlags::read_only())?;
+        let substate = api
+            .field_read_typed::<VersionedWeightedPoolState>(handle)?
+            .into_latest();
+
+        // Op
+        let rtn = callback(substate, api);
+
+        // Close
+        if rtn.is_ok() {
+            api.field_close(handle)?;
+        }
+        rtn
+    }
+
+    fn reserves<Y>(
+        substate: &Substate,
+        api: &mut Y,
+    ) -> Result<IndexMap<ResourceAddress, ReserveResourceInformation>, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        let mut reserves = index_map_new();
+        for (resource_address, vault) in substate.vaults.iter() {
+            reserves.insert(
+                *resource_address,
+                ReserveResourceInformation {
+                    reserves: vault.amount(api)?,
+                    divisibility: Self::divisibility(*resource_address, api)?,
+                },
+            );
+        }
+        Ok(reserves)
+    }
+
+    fn divisibility<Y>(resource_address: ResourceAddress, api: &mut Y) -> Result<u8, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        ResourceManager(resource_address)
+            .resource_type(api)
+            .map(|resource_type| {
+                if let ResourceType::Fungible { divisibility } = resource_type {
+                    divisibility
+                } else {
+                    panic!("Impossible case, we check for this in the constructor and have a test for this.")
+                }
+            })
+    }
+
+    fn calculate_swap_output_amount<Y>(
+        substate: &Substate,
+        input_resource_address: ResourceAddress,
+        input_amount: Decimal,
+        output_resource_address: ResourceAddress,
+        api: &mut Y,
+    ) -> Result<Decimal, RuntimeError>
+    where
+        Y: ClientApi<RuntimeError>,
+    {
+        if input_resource_address == output_resource_address {
+            return Err(Error::SwapOfSameResource {
+                resource_address: input_resource_address,
+            }
+            .into());
+        }
+        if !input_amount.is_positive() {
+            return Err(Error::InvalidSwapInputAmount {
+                amount: input_amount,
+            }
+            .into());
+        }
+
+        let mut information = [input_resource_address, output_resource_address]
+            .into_iter()
+            .map(|resource_address| -> Result<_, RuntimeError> {
+                let (Some(vault), Some(weight)) = (
+                    substate.vaults.get(&resource_address),
+                    substate.weights.get(&resource_address),
+                ) else {
+                    return Err(Error::ResourceDoesNotBelongToPool { resource_address }.into());
+                };
+                let reserves = vault.amount(api)?;
+                if reserves.is_zero() {
+                    return Err(Error::ZeroReservesForSwap.into());
+                }
+                Ok((
+                    PreciseDecimal::from(reserves),
+                    PreciseDecimal::from(*weight),
+                ))
+            });
+        let (input_reserves, input_weight) = information.next().unwrap()?;
+        let (output_reserves, output_weight) = information.next().unwrap()?;
+
+        // The ratio and the power are rounded up (and the products and the exponent are truncated),
+        // so that the output is rounded down, in the pool's favour.
+        let output_amount = PreciseDecimal::ONE
+            .checked_sub(substate.swap_fee)
+            .and_then(|net_fraction| net_fraction.checked_mul(input_amount))
+            .and_then(|net_input_amount| input_reserves.checked_add(net_input_amount))
+            .and_then(|new_input_reserves| {
+                Self::checked_div_round_up(input_reserves, new_input_reserves)
+            })
+            .and_then(|ratio| {
+                input_weight
+                    .checked_div(output_weight)
+                    .and_then(|exponent| {
+                        ratio.checked_pow(exponent, RoundingMode::ToPositiveInfinity)
+                    })
+            })
+            .and_then(|power| PreciseDecimal::ONE.checked_sub(power))
+            .and_then(|fraction| output_reserves.checked_mul(fraction.max(PreciseDecimal::ZERO)))
+            .and_then(|value| Decimal::try_from(value).ok())
+            .ok_or(Error::DecimalOverflowError)?;
+
+        let divisibility = Self::divisibility(output_resource_address, api)?;
+        let output_amount = output_amount
+            .checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+            .ok_or(Error::DecimalOverflowError)?;
+        if output_amount.is_zero() {
+            return Err(Error::ZeroSwapOutput.into());
+        }
+
+        Ok(output_amount)
+    }
+
+    /// Divides the given (non-negative) values, rounding towards positive infinity rather than
+    /// truncating like [`CheckedDiv::checked_div()`].
+    fn checked_div_round_up(
+        numerator: PreciseDecimal,
+        denominator: PreciseDecimal,
+    ) -> Option<PreciseDecimal> {
+        let quotient = numerator.checked_div(denominator)?;
+        if quotient.checked_mul(denominator)? == numerator {
+            Some(quotient)
+        } else {
+            quotient.checked_add(PreciseDecimal(I256::ONE))
+        }
+    }
+
+    fn calculate_amount_owed(
+        pool_units_to_redeem: Decimal,
+        pool_units_total_supply: Decimal,
+        reserves: IndexMap<ResourceAddress, ReserveResourceInformation>,
+    ) -> Result<IndexMap<ResourceAddress, Decimal>, RuntimeError> {
+        let pool_units_to_redeem = PreciseDecimal::from(pool_units_to_redeem);
+        let pool_units_total_supply = PreciseDecimal::from(pool_units_total_supply);
+
+        reserves
+            .into_iter()
+            .map(
+                |(
+                    resource_address,
+                    ReserveResourceInformation {
+                        divisibility,
+                        reserves,
+                    },
+                )| {
+                    let reserves = PreciseDecimal::from(reserves);
+                    let amount_owed = pool_units_to_redeem
+                        .checked_div(pool_units_total_supply)
+                        .and_then(|d| d.checked_mul(reserves))
+                        .ok_or(Error::DecimalOverflowError)?;
+
+                    let amount_owed = Decimal::try_from(amount_owed)
+                        .ok()
+                        .and_then(|value| {
+                            value.checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+                        })
+                        .ok_or(Error::DecimalOverflowError)?;
+
+                    Ok((resource_address, amount_owed))
+                },
+            )
+            .collect()
+    }
+}
+
+struct ReserveResourceInformation {
+    reserves: Decimal,
+    divisibility: u8,
+}
+
+#[derive(Debug)]
+struct ContributionInformation {
+    /// The address of the resource.
+    pub resource_address: ResourceAddress,
+    /// The vault containing the reserves.
+    pub vault: Vault,
+    /// The bucket of the tokens the user wishes to contribute. Might not be contributed in full.
+    pub bucket: Bucket,
+    /// The amount of reserves in the vault.
+    pub reserves: PreciseDecimal,
+    /// The amount of resources the user wishes to contribute.
+    pub contribution: PreciseDecimal,
+}
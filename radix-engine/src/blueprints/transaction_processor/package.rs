@@ -16,11 +16,24 @@ use radix_engine_interface::schema::{
 
 use super::TransactionProcessorBlueprint;
 use super::TransactionProcessorRunInput;
+use super::TransactionProcessorRunSubintentInput;
+use super::TransactionProcessorRunSubintentOutput;
+use super::TransactionProcessorRunWithSubintentsInput;
 
 pub struct TransactionProcessorNativePackage;
 
 impl TransactionProcessorNativePackage {
     pub fn definition() -> PackageDefinition {
+        Self::definition_internal(false)
+    }
+
+    /// The definition installed by the subintents protocol update, which adds the
+    /// `run_with_subintents` and `run_subintent` functions.
+    pub fn subintents_definition() -> PackageDefinition {
+        Self::definition_internal(true)
+    }
+
+    fn definition_internal(with_subintents: bool) -> PackageDefinition {
         let mut aggregator = TypeAggregator::<ScryptoCustomTypeKind>::new();
 
         let fields = Vec::new();
@@ -39,6 +52,37 @@ impl TransactionProcessorNativePackage {
                 export: TRANSACTION_PROCESSOR_RUN_IDENT.to_string(),
             },
         );
+        if with_subintents {
+            functions.insert(
+                TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT.to_string(),
+                FunctionSchemaInit {
+                    receiver: None,
+                    input: TypeRef::Static(
+                        aggregator
+                            .add_child_type_and_descendents::<TransactionProcessorRunWithSubintentsInput>(),
+                    ),
+                    output: TypeRef::Static(
+                        aggregator.add_child_type_and_descendents::<TransactionProcessorRunOutput>(),
+                    ),
+                    export: TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT.to_string(),
+                },
+            );
+            functions.insert(
+                TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT.to_string(),
+                FunctionSchemaInit {
+                    receiver: None,
+                    input: TypeRef::Static(
+                        aggregator
+                            .add_child_type_and_descendents::<TransactionProcessorRunSubintentInput>(),
+                    ),
+                    output: TypeRef::Static(
+                        aggregator
+                            .add_child_type_and_descendents::<TransactionProcessorRunSubintentOutput>(),
+                    ),
+                    export: TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT.to_string(),
+                },
+            );
+        }
 
         let schema = generate_full_schema(aggregator);
         let blueprints = indexmap!(
@@ -99,6 +143,39 @@ impl TransactionProcessorNativePackage {
 
                 Ok(IndexedScryptoValue::from_typed(&rtn))
             }
+            TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT => {
+                let input: TransactionProcessorRunWithSubintentsInput =
+                    input.as_typed().map_err(|e| {
+                        RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                    })?;
+
+                let rtn = TransactionProcessorBlueprint::run_with_subintents(
+                    input.manifest_encoded_instructions,
+                    input.global_address_reservations,
+                    input.references,
+                    input.blobs,
+                    input.subintents,
+                    api,
+                )?;
+
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
+            TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT => {
+                let input: TransactionProcessorRunSubintentInput =
+                    input.as_typed().map_err(|e| {
+                        RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                    })?;
+
+                let rtn = TransactionProcessorBlueprint::run_subintent(
+                    input.manifest_encoded_instructions,
+                    input.references,
+                    input.blobs,
+                    input.args,
+                    api,
+                )?;
+
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
             _ => Err(RuntimeError::ApplicationError(
                 ApplicationError::ExportDoesNotExist(export_name.to_string()),
             )),
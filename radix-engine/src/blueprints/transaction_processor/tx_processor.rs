@@ -39,6 +39,53 @@ pub struct TransactionProcessorRunInputEfficientEncodable<'a> {
     pub blobs: &'a IndexMap<Hash, Vec<u8>>,
 }
 
+#[derive(Debug, Eq, PartialEq, ScryptoSbor)]
+pub struct TransactionProcessorSubintent {
+    pub manifest_encoded_instructions: Vec<u8>,
+    pub references: Vec<Reference>,
+    pub blobs: IndexMap<Hash, Vec<u8>>,
+}
+
+#[derive(Debug, Eq, PartialEq, ScryptoEncode)]
+pub struct TransactionProcessorSubintentEfficientEncodable<'a> {
+    pub manifest_encoded_instructions: &'a [u8],
+    pub references: &'a IndexSet<Reference>,
+    pub blobs: &'a IndexMap<Hash, Vec<u8>>,
+}
+
+#[derive(Debug, Eq, PartialEq, ScryptoSbor)]
+pub struct TransactionProcessorRunWithSubintentsInput {
+    pub manifest_encoded_instructions: Vec<u8>,
+    pub global_address_reservations: Vec<GlobalAddressReservation>,
+    pub references: Vec<Reference>, // Required so that the kernel passes the references to the processor frame
+    pub blobs: IndexMap<Hash, Vec<u8>>,
+    pub subintents: Vec<TransactionProcessorSubintent>,
+}
+
+// This needs to match the above, but is easily encodable to avoid cloning from the transaction payload to encode
+#[derive(Debug, Eq, PartialEq, ScryptoEncode)]
+pub struct TransactionProcessorRunWithSubintentsInputEfficientEncodable<'a> {
+    pub manifest_encoded_instructions: &'a [u8],
+    pub global_address_reservations: Vec<GlobalAddressReservation>,
+    pub references: &'a IndexSet<Reference>,
+    pub blobs: &'a IndexMap<Hash, Vec<u8>>,
+    pub subintents: Vec<TransactionProcessorSubintentEfficientEncodable<'a>>,
+}
+
+/// The input of a child intent, which the root transaction processor passes when yielding to it.
+/// The auth module checks that the instructions are those of the child intent at the index.
+#[derive(Debug, Eq, PartialEq, ScryptoSbor)]
+pub struct TransactionProcessorRunSubintentInput {
+    pub subintent_index: u32,
+    pub manifest_encoded_instructions: Vec<u8>,
+    pub references: Vec<Reference>, // Required so that the kernel passes the references to the processor frame
+    pub blobs: IndexMap<Hash, Vec<u8>>,
+    pub args: ScryptoValue,
+}
+
+/// The value the child intent yields back to its parent
+pub type TransactionProcessorRunSubintentOutput = ScryptoValue;
+
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
 pub enum TransactionProcessorError {
     BucketNotFound(u32),
@@ -53,6 +100,12 @@ pub enum TransactionProcessorError {
     AuthZoneIsEmpty,
     InvocationOutputDecodeError(DecodeError),
     ArgsEncodeError(EncodeError),
+    SubintentNotFound(u32),
+    SubintentAlreadyYieldedTo(u32),
+    SubintentNotYieldedTo(u32),
+    YieldToParentOutsideSubintent,
+    SubintentAlreadyYieldedToParent,
+    SubintentDidNotYieldToParent,
 }
 
 impl From<TransactionProcessorError> for RuntimeError {
@@ -90,6 +143,16 @@ where
     Ok(InstructionOutput::CallReturn(result.into()))
 }
 
+/// Whether the manifest is that of the root intent or a child intent
+enum ManifestIntent {
+    /// The root intent, which can yield to the child intents
+    Root {
+        subintents: Vec<Option<TransactionProcessorSubintent>>,
+    },
+    /// A child intent, which starts with the args it was yielded, and yields back to its parent
+    Subintent { args: ScryptoValue },
+}
+
 pub struct TransactionProcessorBlueprint;
 
 impl TransactionProcessorBlueprint {
@@ -100,6 +163,71 @@ impl TransactionProcessorBlueprint {
         blobs: IndexMap<Hash, Vec<u8>>,
         api: &mut Y,
     ) -> Result<Vec<InstructionOutput>, RuntimeError>
+    where
+        Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
+    {
+        let (outputs, _) = Self::run_manifest(
+            manifest_encoded_instructions,
+            global_address_reservations,
+            blobs,
+            ManifestIntent::Root { subintents: vec![] },
+            api,
+        )?;
+        Ok(outputs)
+    }
+
+    pub(crate) fn run_with_subintents<Y, L: Default>(
+        manifest_encoded_instructions: Vec<u8>,
+        global_address_reservations: Vec<GlobalAddressReservation>,
+        _references: Vec<Reference>, // Required so that the kernel passes the references to the processor frame
+        blobs: IndexMap<Hash, Vec<u8>>,
+        subintents: Vec<TransactionProcessorSubintent>,
+        api: &mut Y,
+    ) -> Result<Vec<InstructionOutput>, RuntimeError>
+    where
+        Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
+    {
+        let (outputs, _) = Self::run_manifest(
+            manifest_encoded_instructions,
+            global_address_reservations,
+            blobs,
+            ManifestIntent::Root {
+                subintents: subintents.into_iter().map(Some).collect(),
+            },
+            api,
+        )?;
+        Ok(outputs)
+    }
+
+    pub(crate) fn run_subintent<Y, L: Default>(
+        manifest_encoded_instructions: Vec<u8>,
+        _references: Vec<Reference>, // Required so that the kernel passes the references to the processor frame
+        blobs: IndexMap<Hash, Vec<u8>>,
+        args: ScryptoValue,
+        api: &mut Y,
+    ) -> Result<TransactionProcessorRunSubintentOutput, RuntimeError>
+    where
+        Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
+    {
+        let (_, yielded) = Self::run_manifest(
+            manifest_encoded_instructions,
+            vec![],
+            blobs,
+            ManifestIntent::Subintent { args },
+            api,
+        )?;
+        yielded.ok_or(TransactionProcessorError::SubintentDidNotYieldToParent.into())
+    }
+
+    /// Runs the instructions, returning their outputs and, for a child intent, the value it
+    /// yielded to its parent
+    fn run_manifest<Y, L: Default>(
+        manifest_encoded_instructions: Vec<u8>,
+        global_address_reservations: Vec<GlobalAddressReservation>,
+        blobs: IndexMap<Hash, Vec<u8>>,
+        intent: ManifestIntent,
+        api: &mut Y,
+    ) -> Result<(Vec<InstructionOutput>, Option<ScryptoValue>), RuntimeError>
     where
         Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
     {
@@ -136,6 +264,16 @@ impl TransactionProcessorBlueprint {
                 RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
             })?;
         let mut processor = TransactionProcessor::new(blobs, global_address_reservations);
+        let (mut subintents, is_subintent) = match intent {
+            ManifestIntent::Root { subintents } => (subintents, false),
+            ManifestIntent::Subintent { args } => {
+                // The yielded buckets and proofs go to the worktop and auth zone
+                let args = IndexedScryptoValue::from_typed(&args);
+                processor.handle_call_return_data(&args, &worktop, api)?;
+                (vec![], true)
+            }
+        };
+        let mut yielded = None;
         let mut outputs = Vec::new();
         for (index, inst) in instructions.into_iter().enumerate() {
             api.update_instruction_index(index)?;
@@ -402,13 +540,61 @@ impl TransactionProcessorBlueprint {
 
                     InstructionOutput::None
                 }
+                InstructionV1::YieldToChild { child_index, args } => {
+                    let subintent = subintents
+                        .get_mut(child_index as usize)
+                        .ok_or(TransactionProcessorError::SubintentNotFound(child_index))?
+                        .take()
+                        .ok_or(TransactionProcessorError::SubintentAlreadyYieldedTo(
+                            child_index,
+                        ))?;
+                    handle_invocation(api, &mut processor, &mut worktop, args, |api, args| {
+                        api.call_function(
+                            TRANSACTION_PROCESSOR_PACKAGE,
+                            TRANSACTION_PROCESSOR_BLUEPRINT,
+                            TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT,
+                            scrypto_encode(&TransactionProcessorRunSubintentInput {
+                                subintent_index: child_index,
+                                manifest_encoded_instructions: subintent
+                                    .manifest_encoded_instructions,
+                                references: subintent.references,
+                                blobs: subintent.blobs,
+                                args,
+                            })
+                            .map_err(TransactionProcessorError::ArgsEncodeError)?,
+                        )
+                    })?
+                }
+                InstructionV1::YieldToParent { args } => {
+                    if !is_subintent {
+                        return Err(TransactionProcessorError::YieldToParentOutsideSubintent.into());
+                    }
+                    if yielded.is_some() {
+                        return Err(
+                            TransactionProcessorError::SubintentAlreadyYieldedToParent.into()
+                        );
+                    }
+                    let mut processor_with_api = TransactionProcessorWithApi {
+                        worktop: &mut worktop,
+                        processor: &mut processor,
+                        api,
+                    };
+                    yielded = Some(transform(args, &mut processor_with_api)?);
+                    InstructionOutput::None
+                }
             };
             outputs.push(result);
         }
 
+        if let Some(child_index) = subintents.iter().position(Option::is_some) {
+            return Err(
+                TransactionProcessorError::SubintentNotYieldedTo(child_index as u32).into(),
+            );
+        }
+
         worktop.drop(api)?;
 
-        Ok(outputs)
+        Ok((outputs, yielded))
     }
 }
 
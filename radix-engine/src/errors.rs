@@ -5,7 +5,8 @@ use crate::blueprints::package::PackageError;
 use crate::blueprints::pool::v1::errors::{
     multi_resource_pool::Error as MultiResourcePoolError,
     one_resource_pool::Error as OneResourcePoolError,
-    two_resource_pool::Error as TwoResourcePoolError,
+    stable_swap_pool::Error as StableSwapPoolError,
+    two_resource_pool::Error as TwoResourcePoolError, weighted_pool::Error as WeightedPoolError,
 };
 use crate::blueprints::resource::{AuthZoneError, NonFungibleVaultError};
 use crate::blueprints::resource::{
@@ -25,6 +26,7 @@ use crate::system::attached_modules::role_assignment::RoleAssignmentError;
 use crate::system::attached_modules::royalty::ComponentRoyaltyError;
 use crate::system::system_modules::auth::AuthError;
 use crate::system::system_modules::costing::CostingError;
+use crate::system::system_modules::extension::SystemModuleExtensionError;
 use crate::system::system_modules::limits::TransactionLimitsError;
 use crate::system::system_type_checker::TypeCheckError;
 use crate::transaction::AbortReason;
@@ -322,6 +324,7 @@ pub enum SystemModuleError {
     CostingError(CostingError),
     TransactionLimitsError(TransactionLimitsError),
     EventError(Box<EventError>),
+    ExtensionError(SystemModuleExtensionError),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
@@ -469,6 +472,10 @@ pub enum ApplicationError {
     TwoResourcePoolError(TwoResourcePoolError),
 
     MultiResourcePoolError(MultiResourcePoolError),
+
+    WeightedPoolError(WeightedPoolError),
+
+    StableSwapPoolError(StableSwapPoolError),
 }
 
 impl From<TransactionProcessorError> for ApplicationError {
@@ -3,7 +3,9 @@ use crate::kernel::kernel_callback_api::CallFrameReferences;
 use crate::kernel::substate_io::{
     IOAccessHandler, SubstateDevice, SubstateIO, SubstateReadHandler,
 };
-use crate::track::interface::{CallbackError, CommitableSubstateStore, IOAccess, NodeSubstates};
+use crate::track::interface::{
+    CallbackError, CommitableSubstateStore, IOAccess, NodeSubstates, ScanKeysFrom,
+};
 use crate::types::*;
 use radix_engine_interface::api::field_api::LockFlags;
 use radix_engine_interface::types::{NodeId, SubstateHandle, SubstateKey};
@@ -1205,6 +1207,7 @@ impl<C, L: Clone> CallFrame<C, L> {
         substate_io: &'f mut SubstateIO<S>,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         limit: u32,
         handler: &mut impl CallFrameIOAccessHandler<C, L, E>,
     ) -> Result<Vec<SubstateKey>, CallbackError<CallFrameScanKeysError, E>> {
@@ -1219,8 +1222,14 @@ impl<C, L: Clone> CallFrame<C, L> {
             phantom: PhantomData::default(),
         };
 
-        let keys =
-            substate_io.scan_keys::<K, E>(device, node_id, partition_num, limit, &mut adapter)?;
+        let keys = substate_io.scan_keys::<K, E>(
+            device,
+            node_id,
+            partition_num,
+            from,
+            limit,
+            &mut adapter,
+        )?;
 
         for key in &keys {
             self.process_output_substate_key(key).map_err(|e| {
@@ -1,6 +1,8 @@
 use crate::track::interface::IOAccess;
-use crate::track::interface::{CallbackError, CanonicalSubstateKey, NodeSubstates};
+use crate::track::interface::{CallbackError, CanonicalSubstateKey, NodeSubstates, ScanKeysFrom};
 use crate::types::*;
+use radix_engine_store_interface::db_key_mapper::{DatabaseKeyMapper, SpreadPrefixKeyMapper};
+use radix_engine_store_interface::interface::DbSortKey;
 
 pub struct Heap {
     nodes: NonIterMap<NodeId, NodeSubstates>,
@@ -158,23 +160,44 @@ impl Heap {
         Ok(substate_value)
     }
 
-    /// Scans the keys of a node's partition. On an non-existing node/partition, this
-    /// will return an empty vector
+    /// Scans the keys of a node's partition, starting at the given [`ScanKeysFrom`]. On an
+    /// non-existing node/partition, this will return an empty vector
     pub fn scan_keys(
         &self,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         count: u32,
     ) -> Vec<SubstateKey> {
         let node_substates = self.nodes.get(node_id).and_then(|n| n.get(&partition_num));
         if let Some(substates) = node_substates {
-            let substate_keys: Vec<SubstateKey> = substates
-                .iter()
-                .map(|(key, _value)| key.clone())
-                .take(count.try_into().unwrap())
+            let count: usize = count.try_into().unwrap();
+            let after_key = match from {
+                ScanKeysFrom::Start => {
+                    return substates.keys().take(count).cloned().collect();
+                }
+                ScanKeysFrom::SortedStart => None,
+                ScanKeysFrom::SortedAfter(after_key) => Some(after_key),
+            };
+
+            // The sorted scans follow the database sort key order (rather than the order of the
+            // heap substates), as the track does, so that a scan continues in the same order
+            // once the node is stored
+            let after_db_sort_key = after_key.map(SpreadPrefixKeyMapper::to_db_sort_key);
+            let mut sorted_keys: Vec<(DbSortKey, &SubstateKey)> = substates
+                .keys()
+                .map(|key| (SpreadPrefixKeyMapper::to_db_sort_key(key), key))
+                .filter(|(db_sort_key, _)| match &after_db_sort_key {
+                    Some(after_db_sort_key) => db_sort_key > after_db_sort_key,
+                    None => true,
+                })
                 .collect();
-
-            substate_keys
+            sorted_keys.sort();
+            sorted_keys
+                .into_iter()
+                .take(count)
+                .map(|(_, key)| key.clone())
+                .collect()
         } else {
             vec![]
         }
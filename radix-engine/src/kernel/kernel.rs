@@ -20,7 +20,9 @@ use crate::kernel::substate_io::{SubstateDevice, SubstateIO};
 use crate::kernel::substate_locks::SubstateLocks;
 use crate::system::system_modules::execution_trace::{BucketSnapshot, ProofSnapshot};
 use crate::system::type_info::TypeInfoSubstate;
-use crate::track::interface::{CallbackError, CommitableSubstateStore, IOAccess, NodeSubstates};
+use crate::track::interface::{
+    CallbackError, CommitableSubstateStore, IOAccess, NodeSubstates, ScanKeysFrom,
+};
 use crate::track::BootStore;
 use crate::types::*;
 use radix_engine_interface::api::field_api::LockFlags;
@@ -28,7 +30,7 @@ use radix_engine_interface::blueprints::resource::*;
 use radix_engine_store_interface::db_key_mapper::SubstateKeyContent;
 use resources_tracker_macro::trace_resources;
 use sbor::rust::mem;
-use transaction::prelude::PreAllocatedAddress;
+use transaction::prelude::{ExecutableFeeSponsor, ExecutableSubintent, PreAllocatedAddress};
 
 /// Organizes the radix engine stack to make a function entrypoint available for execution
 pub struct BootLoader<'g, M: KernelCallbackObject, S: CommitableSubstateStore + BootStore> {
@@ -70,6 +72,8 @@ impl<'g, M: KernelCallbackObject, S: CommitableSubstateStore + BootStore> BootLo
         pre_allocated_addresses: &'a Vec<PreAllocatedAddress>,
         references: &'a IndexSet<Reference>,
         blobs: &'a IndexMap<Hash, Vec<u8>>,
+        subintents: &'a [ExecutableSubintent<'a>],
+        fee_sponsor: Option<&'a ExecutableFeeSponsor>,
     ) -> Result<Vec<u8>, RuntimeError> {
         #[cfg(feature = "resource_tracker")]
         radix_engine_profiling::QEMU_PLUGIN_CALIBRATOR.with(|v| {
@@ -147,6 +151,8 @@ impl<'g, M: KernelCallbackObject, S: CommitableSubstateStore + BootStore> BootLo
             pre_allocated_addresses,
             references,
             blobs,
+            subintents,
+            fee_sponsor,
         )?;
 
         // Sanity check call frame
@@ -986,6 +992,7 @@ where
         &mut self,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         limit: u32,
     ) -> Result<Vec<SubstateKey>, RuntimeError> {
         self.callback.on_scan_keys(ScanKeysEvent::Start)?;
@@ -1006,6 +1013,7 @@ where
                 &mut self.substate_io,
                 node_id,
                 partition_num,
+                from,
                 limit,
                 &mut handler,
             )
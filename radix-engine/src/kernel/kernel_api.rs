@@ -136,10 +136,12 @@ pub trait KernelSubstateApi<L> {
         count: u32,
     ) -> Result<Vec<(SortedKey, IndexedScryptoValue)>, RuntimeError>;
 
+    /// Reads keys of substates under a node, starting at the given [`ScanKeysFrom`]
     fn kernel_scan_keys<K: SubstateKeyContent + 'static>(
         &mut self,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         count: u32,
     ) -> Result<Vec<SubstateKey>, RuntimeError>;
 
@@ -7,7 +7,7 @@ use crate::track::interface::{IOAccess, NodeSubstates};
 use crate::track::BootStore;
 use crate::types::*;
 use radix_engine_interface::api::field_api::LockFlags;
-use transaction::prelude::PreAllocatedAddress;
+use transaction::prelude::{ExecutableFeeSponsor, ExecutableSubintent, PreAllocatedAddress};
 
 pub trait CallFrameReferences {
     fn root() -> Self;
@@ -141,6 +141,8 @@ pub trait KernelCallbackObject: Sized {
         pre_allocated_addresses: &Vec<PreAllocatedAddress>,
         references: &IndexSet<Reference>,
         blobs: &IndexMap<Hash, Vec<u8>>,
+        subintents: &[ExecutableSubintent],
+        fee_sponsor: Option<&ExecutableFeeSponsor>,
     ) -> Result<Vec<u8>, RuntimeError>
     where
         Y: KernelApi<Self>;
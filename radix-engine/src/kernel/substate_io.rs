@@ -7,7 +7,8 @@ use crate::kernel::call_frame::{
 use crate::kernel::heap::{Heap, HeapRemoveNodeError};
 use crate::kernel::substate_locks::SubstateLocks;
 use crate::track::interface::{
-    CallbackError, CommitableSubstateStore, IOAccess, NodeSubstates, TrackedSubstateInfo,
+    CallbackError, CommitableSubstateStore, IOAccess, NodeSubstates, ScanKeysFrom,
+    TrackedSubstateInfo,
 };
 use radix_engine_common::prelude::{NodeId, PartitionNumber};
 use radix_engine_common::types::{SortedKey, SubstateKey};
@@ -19,7 +20,7 @@ use sbor::prelude::Vec;
 use sbor::rust::collections::BTreeSet;
 use sbor::rust::collections::LinkedList;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ScryptoSbor)]
 pub enum SubstateDevice {
     Heap,
     Store,
@@ -590,14 +591,15 @@ impl<'g, S: CommitableSubstateStore + 'g> SubstateIO<'g, S> {
         device: SubstateDevice,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         count: u32,
         handler: &mut impl IOAccessHandler<E>,
     ) -> Result<Vec<SubstateKey>, CallbackError<CallFrameScanKeysError, E>> {
         let keys = match device {
-            SubstateDevice::Heap => self.heap.scan_keys(node_id, partition_num, count),
+            SubstateDevice::Heap => self.heap.scan_keys(node_id, partition_num, from, count),
             SubstateDevice::Store => self
                 .store
-                .scan_keys::<K, E, _>(node_id, partition_num, count, &mut |io_access| {
+                .scan_keys::<K, E, _>(node_id, partition_num, from, count, &mut |io_access| {
                     handler.on_io_access(&self.heap, io_access)
                 })
                 .map_err(|e| CallbackError::CallbackError(e))?,
@@ -156,6 +156,51 @@ impl RoleAssignmentNativePackage {
         PackageDefinition { blueprints }
     }
 
+    /// The definition of the role assignment blueprint once the access rule extensions protocol
+    /// update has been applied, which allows the `CountOf` and `TimeRule` access rule nodes.
+    ///
+    /// It only differs from [`Self::definition`] in the well-known access rule types its schema
+    /// refers to.
+    pub fn access_rule_extensions_definition() -> PackageDefinition {
+        fn upgrade_type_id(type_id: &mut LocalTypeId) {
+            if let LocalTypeId::WellKnown(well_known_type_id) = type_id {
+                if *well_known_type_id == ACCESS_RULE_TYPE {
+                    *well_known_type_id = ACCESS_RULE_V2_TYPE;
+                } else if *well_known_type_id == ACCESS_RULE_NODE_TYPE {
+                    *well_known_type_id = ACCESS_RULE_NODE_V2_TYPE;
+                } else if *well_known_type_id == ACCESS_RULE_NODE_LIST_TYPE {
+                    *well_known_type_id = ACCESS_RULE_NODE_LIST_V2_TYPE;
+                }
+            }
+        }
+
+        let mut definition = Self::definition();
+        for blueprint in definition.blueprints.values_mut() {
+            for type_kind in blueprint.schema.schema.v1_mut().type_kinds.iter_mut() {
+                match type_kind {
+                    TypeKind::Array { element_type } => upgrade_type_id(element_type),
+                    TypeKind::Tuple { field_types } => {
+                        field_types.iter_mut().for_each(upgrade_type_id)
+                    }
+                    TypeKind::Enum { variants } => variants
+                        .values_mut()
+                        .flat_map(|field_types| field_types.iter_mut())
+                        .for_each(upgrade_type_id),
+                    TypeKind::Map {
+                        key_type,
+                        value_type,
+                    } => {
+                        upgrade_type_id(key_type);
+                        upgrade_type_id(value_type);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        definition
+    }
+
     pub fn authorization<Y: KernelApi<SystemConfig<V>>, V: SystemCallbackObject>(
         global_address: &GlobalAddress,
         ident: &str,
@@ -4,12 +4,15 @@ use crate::blueprints::package::PackageBlueprintVersionDefinitionEntrySubstate;
 use crate::blueprints::resource::fungible_vault::LockFeeEvent;
 use crate::errors::{
     ApplicationError, CannotGlobalizeError, CreateObjectError, InvalidDropAccess,
-    InvalidGlobalizeAccess, InvalidModuleType, RuntimeError, SystemError, SystemModuleError,
+    InvalidGlobalizeAccess, InvalidModuleType, KernelError, RuntimeError, SystemError,
+    SystemModuleError,
 };
 use crate::errors::{EventError, SystemUpstreamError};
 use crate::internal_prelude::*;
 use crate::internal_prelude::{IndexEntrySubstate, SortedIndexEntrySubstate};
-use crate::kernel::call_frame::{NodeVisibility, ReferenceOrigin};
+use crate::kernel::call_frame::{
+    CallFrameError, NodeVisibility, OpenSubstateError, ReferenceOrigin,
+};
 use crate::kernel::kernel_api::*;
 use crate::system::actor::{Actor, FunctionActor, InstanceContext, MethodActor, MethodType};
 use crate::system::node_init::type_info_partition;
@@ -25,7 +28,7 @@ use crate::system::system_type_checker::{
     BlueprintTypeTarget, KVStoreTypeTarget, SchemaValidationMeta, SystemMapper,
 };
 use crate::system::type_info::{TypeInfoBlueprint, TypeInfoSubstate};
-use crate::track::interface::NodeSubstates;
+use crate::track::interface::{NodeSubstates, ScanKeysFrom};
 use crate::types::*;
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::actor_index_api::ClientActorIndexApi;
@@ -48,6 +51,10 @@ use resources_tracker_macro::trace_resources;
 use sbor::rust::string::ToString;
 use sbor::rust::vec::Vec;
 
+/// The maximum number of keys read from the kernel at once when scanning a key value store, which
+/// keeps the work done between the costing of the entry reads bounded.
+const KEY_VALUE_STORE_SCAN_BATCH_SIZE: u32 = 100;
+
 /// Provided to upper layer for invoking lower layer service
 pub struct SystemService<'a, Y: KernelApi<SystemConfig<V>>, V: SystemCallbackObject> {
     pub api: &'a mut Y,
@@ -1091,6 +1098,49 @@ where
         Ok(object_info)
     }
 
+    /// Scans up to `limit` entries of a key value store, in the sorted order following the
+    /// `after_key` (if any), and returns their keys along with whether they have a value.
+    ///
+    /// Each scanned entry is costed, as it is read whether it has a value or not. Fails if any
+    /// scanned entry is write-locked (e.g. by Scrypto's `get_mut()`), as its value can't be read.
+    fn scan_key_value_store_entries(
+        &mut self,
+        node_id: &NodeId,
+        after_key: Option<&SubstateKey>,
+        limit: u32,
+    ) -> Result<Vec<(SubstateKey, bool)>, RuntimeError> {
+        let from = match after_key {
+            Some(after_key) => ScanKeysFrom::SortedAfter(after_key),
+            None => ScanKeysFrom::SortedStart,
+        };
+        let substate_keys =
+            self.api
+                .kernel_scan_keys::<MapKey>(node_id, MAIN_BASE_PARTITION, from, limit)?;
+        self.api.kernel_get_system().modules.apply_execution_cost(
+            ExecutionCostingEntry::ScanKeyValueStore {
+                entries: substate_keys.len(),
+            },
+        )?;
+
+        // Removed entries are kept as empty substates, which must be told apart
+        let mut entries = Vec::new();
+        for substate_key in substate_keys {
+            let handle = self.api.kernel_open_substate(
+                node_id,
+                MAIN_BASE_PARTITION,
+                &substate_key,
+                LockFlags::read_only(),
+                SystemLockData::KeyValueEntry(KeyValueEntryLockData::Read),
+            )?;
+            let entry: KeyValueEntrySubstate<ScryptoValue> =
+                self.api.kernel_read_substate(handle)?.as_typed().unwrap();
+            self.api.kernel_close_substate(handle)?;
+            entries.push((substate_key, entry.into_value().is_some()));
+        }
+
+        Ok(entries)
+    }
+
     pub fn is_feature_enabled(
         &mut self,
         node_id: &NodeId,
@@ -1881,6 +1931,74 @@ where
         let handle = self.key_value_store_open_entry(node_id, key, LockFlags::MUTABLE)?;
         self.key_value_entry_remove_and_close_substate(handle)
     }
+
+    // Costed per scanned entry
+    #[trace_resources]
+    fn key_value_store_scan_keys(
+        &mut self,
+        node_id: &NodeId,
+        after_key: Option<&Vec<u8>>,
+        limit: u32,
+    ) -> Result<Vec<Vec<u8>>, RuntimeError> {
+        let type_info = TypeInfoBlueprint::get_type(&node_id, self.api)?;
+        if !matches!(type_info, TypeInfoSubstate::KeyValueStore(..)) {
+            return Err(RuntimeError::SystemError(SystemError::NotAKeyValueStore));
+        }
+
+        let mut keys = Vec::new();
+        let mut after_key = after_key.map(|key| SubstateKey::Map(key.clone()));
+        while (keys.len() as u32) < limit {
+            let batch_limit = u32::min(limit - keys.len() as u32, KEY_VALUE_STORE_SCAN_BATCH_SIZE);
+            let batch =
+                self.scan_key_value_store_entries(node_id, after_key.as_ref(), batch_limit)?;
+            let is_last_batch = (batch.len() as u32) < batch_limit;
+
+            for (substate_key, has_value) in batch {
+                if has_value {
+                    keys.push(substate_key.for_map().unwrap().clone());
+                }
+                after_key = Some(substate_key);
+            }
+
+            if is_last_batch {
+                break;
+            }
+        }
+
+        Ok(keys)
+    }
+
+    // Costed per scanned entry, as the whole store is scanned
+    fn key_value_store_len(&mut self, node_id: &NodeId) -> Result<u32, RuntimeError> {
+        let type_info = TypeInfoBlueprint::get_type(&node_id, self.api)?;
+        if !matches!(type_info, TypeInfoSubstate::KeyValueStore(..)) {
+            return Err(RuntimeError::SystemError(SystemError::NotAKeyValueStore));
+        }
+
+        let mut len = 0u32;
+        let mut after_key = None;
+        loop {
+            let batch = self.scan_key_value_store_entries(
+                node_id,
+                after_key.as_ref(),
+                KEY_VALUE_STORE_SCAN_BATCH_SIZE,
+            )?;
+            let is_last_batch = (batch.len() as u32) < KEY_VALUE_STORE_SCAN_BATCH_SIZE;
+
+            for (substate_key, has_value) in batch {
+                if has_value {
+                    len += 1;
+                }
+                after_key = Some(substate_key);
+            }
+
+            if is_last_batch {
+                break;
+            }
+        }
+
+        Ok(len)
+    }
 }
 
 #[cfg_attr(
@@ -1935,6 +2053,42 @@ where
             .kernel_set_substate(&node_id, partition_num, SubstateKey::Map(key), value)
     }
 
+    // Costing through kernel
+    fn actor_index_get(
+        &mut self,
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, RuntimeError> {
+        let actor_object_type: ActorStateRef = object_handle.try_into()?;
+
+        let (node_id, _info, partition_num) = self.get_actor_collection_partition_info(
+            actor_object_type,
+            collection_index,
+            &BlueprintPartitionType::IndexCollection,
+        )?;
+
+        // Unlike key value entries, missing index entries have no default value
+        let handle = match self.api.kernel_open_substate(
+            &node_id,
+            partition_num,
+            &SubstateKey::Map(key),
+            LockFlags::read_only(),
+            SystemLockData::default(),
+        ) {
+            Ok(handle) => handle,
+            Err(RuntimeError::KernelError(KernelError::CallFrameError(
+                CallFrameError::OpenSubstateError(OpenSubstateError::SubstateFault),
+            ))) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let value: IndexEntrySubstate<ScryptoValue> =
+            self.api.kernel_read_substate(handle)?.as_typed().unwrap();
+        self.api.kernel_close_substate(handle)?;
+
+        Ok(Some(scrypto_encode(value.value()).unwrap()))
+    }
+
     // Costing through kernel
     fn actor_index_remove(
         &mut self,
@@ -1978,7 +2132,7 @@ where
 
         let substates = self
             .api
-            .kernel_scan_keys::<MapKey>(&node_id, partition_num, limit)?
+            .kernel_scan_keys::<MapKey>(&node_id, partition_num, ScanKeysFrom::Start, limit)?
             .into_iter()
             .map(|key| key.into_map())
             .collect();
@@ -2135,6 +2289,55 @@ where
 
         Ok(substates)
     }
+
+    // Costing through kernel
+    #[trace_resources]
+    fn actor_sorted_index_scan_after(
+        &mut self,
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        after_sorted_key: Option<&SortedKey>,
+        limit: u32,
+    ) -> Result<Vec<(SortedKey, Vec<u8>)>, RuntimeError> {
+        let actor_object_type: ActorStateRef = object_handle.try_into()?;
+
+        let (node_id, _info, partition_num) = self.get_actor_collection_partition_info(
+            actor_object_type,
+            collection_index,
+            &BlueprintPartitionType::SortedIndexCollection,
+        )?;
+
+        // The keys are scanned in the same (database sort key) order as by
+        // `kernel_scan_sorted_substates()`, i.e. by their sort prefixes first
+        let after_key = after_sorted_key.map(|sorted_key| SubstateKey::Sorted(sorted_key.clone()));
+        let from = match &after_key {
+            Some(after_key) => ScanKeysFrom::SortedAfter(after_key),
+            None => ScanKeysFrom::SortedStart,
+        };
+        let substate_keys =
+            self.api
+                .kernel_scan_keys::<SortedKey>(&node_id, partition_num, from, limit)?;
+
+        let mut substates = Vec::new();
+        for substate_key in substate_keys {
+            let handle = self.api.kernel_open_substate(
+                &node_id,
+                partition_num,
+                &substate_key,
+                LockFlags::read_only(),
+                SystemLockData::default(),
+            )?;
+            let value: SortedIndexEntrySubstate<ScryptoValue> =
+                self.api.kernel_read_substate(handle)?.as_typed().unwrap();
+            self.api.kernel_close_substate(handle)?;
+            substates.push((
+                substate_key.for_sorted().unwrap().clone(),
+                scrypto_encode(value.value()).unwrap(),
+            ));
+        }
+
+        Ok(substates)
+    }
 }
 
 #[cfg_attr(
@@ -2158,7 +2361,8 @@ where
             RuntimeError::SystemUpstreamError(SystemUpstreamError::InputDecodeError(e))
         })?;
         let blueprint_id = BlueprintId::new(&package_address, blueprint_name);
-        let auth_zone = SystemModuleMixer::on_call_function(self, &blueprint_id, function_name)?;
+        let auth_zone =
+            SystemModuleMixer::on_call_function(self, &blueprint_id, function_name, &args)?;
 
         let rtn = self
             .api
@@ -3089,10 +3293,11 @@ where
         &mut self,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         limit: u32,
     ) -> Result<Vec<SubstateKey>, RuntimeError> {
         self.api
-            .kernel_scan_keys::<K>(node_id, partition_num, limit)
+            .kernel_scan_keys::<K>(node_id, partition_num, from, limit)
     }
 
     fn kernel_drain_substates<K: SubstateKeyContent + 'static>(
@@ -3,7 +3,11 @@ use crate::blueprints::account::ACCOUNT_CREATE_VIRTUAL_ED25519_ID;
 use crate::blueprints::account::ACCOUNT_CREATE_VIRTUAL_SECP256K1_ID;
 use crate::blueprints::identity::IDENTITY_CREATE_VIRTUAL_ED25519_ID;
 use crate::blueprints::identity::IDENTITY_CREATE_VIRTUAL_SECP256K1_ID;
-use crate::blueprints::transaction_processor::TransactionProcessorRunInputEfficientEncodable;
+use crate::blueprints::transaction_processor::{
+    TransactionProcessorRunInputEfficientEncodable,
+    TransactionProcessorRunWithSubintentsInputEfficientEncodable,
+    TransactionProcessorSubintentEfficientEncodable,
+};
 use crate::errors::*;
 use crate::kernel::call_frame::CallFrameMessage;
 use crate::kernel::kernel_api::{KernelApi, KernelInvocation};
@@ -33,6 +37,7 @@ use radix_engine_interface::blueprints::identity::IDENTITY_BLUEPRINT;
 use radix_engine_interface::blueprints::package::*;
 use radix_engine_interface::blueprints::transaction_processor::{
     TRANSACTION_PROCESSOR_BLUEPRINT, TRANSACTION_PROCESSOR_RUN_IDENT,
+    TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT,
 };
 use radix_engine_interface::hooks::OnDropInput;
 use radix_engine_interface::hooks::OnDropOutput;
@@ -41,7 +46,7 @@ use radix_engine_interface::hooks::OnMoveOutput;
 use radix_engine_interface::hooks::OnVirtualizeInput;
 use radix_engine_interface::hooks::OnVirtualizeOutput;
 use radix_engine_interface::schema::RefTypes;
-use transaction::model::PreAllocatedAddress;
+use transaction::model::{ExecutableFeeSponsor, ExecutableSubintent, PreAllocatedAddress};
 
 #[derive(Clone)]
 pub enum SystemLockData {
@@ -118,6 +123,8 @@ impl<C: SystemCallbackObject> KernelCallbackObject for SystemConfig<C> {
         pre_allocated_addresses: &Vec<PreAllocatedAddress>,
         references: &IndexSet<Reference>,
         blobs: &IndexMap<Hash, Vec<u8>>,
+        subintents: &[ExecutableSubintent],
+        fee_sponsor: Option<&ExecutableFeeSponsor>,
     ) -> Result<Vec<u8>, RuntimeError>
     where
         Y: KernelApi<Self>,
@@ -136,19 +143,69 @@ impl<C: SystemCallbackObject> KernelCallbackObject for SystemConfig<C> {
             global_address_reservations.push(global_address_reservation);
         }
 
+        // Lock the fee of the sponsor, in a TX processor frame with the sponsor's auth zone only
+        if let Some(fee_sponsor) = fee_sponsor {
+            system.call_function(
+                TRANSACTION_PROCESSOR_PACKAGE,
+                TRANSACTION_PROCESSOR_BLUEPRINT,
+                TRANSACTION_PROCESSOR_RUN_IDENT,
+                scrypto_encode(&TransactionProcessorRunInputEfficientEncodable {
+                    manifest_encoded_instructions: fee_sponsor.encoded_instructions(),
+                    global_address_reservations: vec![],
+                    references,
+                    blobs: &index_map_new(),
+                })
+                .unwrap(),
+            )?;
+            system
+                .kernel_get_system()
+                .modules
+                .costing
+                .fee_reserve
+                .record_fee_sponsor(*fee_sponsor.account());
+        }
+
         // Call TX processor
-        let rtn = system.call_function(
-            TRANSACTION_PROCESSOR_PACKAGE,
-            TRANSACTION_PROCESSOR_BLUEPRINT,
-            TRANSACTION_PROCESSOR_RUN_IDENT,
-            scrypto_encode(&TransactionProcessorRunInputEfficientEncodable {
-                manifest_encoded_instructions,
-                global_address_reservations,
-                references,
-                blobs,
-            })
-            .unwrap(),
-        )?;
+        let rtn = if subintents.is_empty() {
+            system.call_function(
+                TRANSACTION_PROCESSOR_PACKAGE,
+                TRANSACTION_PROCESSOR_BLUEPRINT,
+                TRANSACTION_PROCESSOR_RUN_IDENT,
+                scrypto_encode(&TransactionProcessorRunInputEfficientEncodable {
+                    manifest_encoded_instructions,
+                    global_address_reservations,
+                    references,
+                    blobs,
+                })
+                .unwrap(),
+            )?
+        } else {
+            let subintents = subintents
+                .iter()
+                .map(
+                    |subintent| TransactionProcessorSubintentEfficientEncodable {
+                        manifest_encoded_instructions: subintent.encoded_instructions(),
+                        references: subintent.references(),
+                        blobs: subintent.blobs(),
+                    },
+                )
+                .collect();
+            system.call_function(
+                TRANSACTION_PROCESSOR_PACKAGE,
+                TRANSACTION_PROCESSOR_BLUEPRINT,
+                TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT,
+                scrypto_encode(
+                    &TransactionProcessorRunWithSubintentsInputEfficientEncodable {
+                        manifest_encoded_instructions,
+                        global_address_reservations,
+                        references,
+                        blobs,
+                        subintents,
+                    },
+                )
+                .unwrap(),
+            )?
+        };
 
         Ok(rtn)
     }
@@ -1,11 +1,12 @@
 use super::Authorization;
 use crate::blueprints::package::PackageAuthNativeBlueprint;
 use crate::blueprints::resource::AuthZone;
+use crate::blueprints::transaction_processor::TransactionProcessorRunSubintentInput;
 use crate::errors::*;
 use crate::internal_prelude::*;
 use crate::kernel::call_frame::ReferenceOrigin;
 use crate::kernel::kernel_api::{KernelApi, KernelInternalApi, KernelNodeApi, KernelSubstateApi};
-use crate::system::actor::Actor;
+use crate::system::actor::{Actor, FunctionActor};
 use crate::system::attached_modules::role_assignment::RoleAssignmentNativePackage;
 use crate::system::module::{InitSystemModule, SystemModule};
 use crate::system::node_init::type_info_partition;
@@ -19,9 +20,12 @@ use radix_engine_interface::blueprints::package::{
     BlueprintVersion, BlueprintVersionKey, MethodAuthTemplate, RoleSpecification,
 };
 use radix_engine_interface::blueprints::resource::*;
-use radix_engine_interface::blueprints::transaction_processor::TRANSACTION_PROCESSOR_BLUEPRINT;
+use radix_engine_interface::blueprints::transaction_processor::{
+    TRANSACTION_PROCESSOR_BLUEPRINT, TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT,
+    TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT,
+};
 use radix_engine_interface::types::*;
-use transaction::model::AuthZoneParams;
+use transaction::model::{AuthZoneParams, ExecutableSubintent};
 
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
 pub enum AuthError {
@@ -31,6 +35,7 @@ pub enum AuthError {
     Unauthorized(Box<Unauthorized>),
     InnerBlueprintDoesNotExist(String),
     InvalidOuterObjectMapping,
+    InvalidSubintent(u32),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
@@ -48,6 +53,35 @@ pub struct Unauthorized {
 #[derive(Debug, Clone)]
 pub struct AuthModule {
     pub params: AuthZoneParams,
+    pub subintents: Vec<SubintentAuth>,
+    pub fee_sponsor: Option<FeeSponsorAuth>,
+}
+
+/// The signer proofs of the fee sponsor, which are only given to the transaction processor
+/// frame locking the sponsor's fee, which is the first one started at the root.
+#[derive(Debug, Clone)]
+pub struct FeeSponsorAuth {
+    pub params: AuthZoneParams,
+    pub is_started: bool,
+}
+
+/// The signer proofs of a child intent, which are only given to the transaction processor
+/// frame running the instructions the child intent signers signed.
+#[derive(Debug, Clone)]
+pub struct SubintentAuth {
+    pub instructions_hash: Hash,
+    pub params: AuthZoneParams,
+    pub is_started: bool,
+}
+
+impl SubintentAuth {
+    pub fn new(subintent: &ExecutableSubintent) -> Self {
+        Self {
+            instructions_hash: hash(subintent.encoded_instructions()),
+            params: subintent.auth_zone_params().clone(),
+            is_started: false,
+        }
+    }
 }
 
 pub enum AuthorizationCheckResult {
@@ -75,11 +109,24 @@ impl AuthModule {
         api: &mut SystemService<Y, V>,
         blueprint_id: &BlueprintId,
         ident: &str,
+        args: &IndexedScryptoValue,
     ) -> Result<NodeId, RuntimeError>
     where
         V: SystemCallbackObject,
         Y: KernelApi<SystemConfig<V>>,
     {
+        // A child intent is run by the root transaction processor, with the proofs of its signers
+        if let Some(params) =
+            Self::resolve_subintent_auth_zone_params(api, blueprint_id, ident, args)?
+        {
+            return Self::create_auth_zone(
+                api,
+                None,
+                params.virtual_resources,
+                params.initial_proofs,
+            );
+        }
+
         // Create AuthZone
         let auth_zone = {
             // TODO: Remove special casing use of transaction processor and just have virtual resources
@@ -93,10 +140,17 @@ impl AuthModule {
             let is_at_root = api.kernel_get_current_depth() == 0;
             let (virtual_resources, virtual_non_fungibles) =
                 if is_transaction_processor_blueprint && is_at_root {
-                    let auth_module = &api.kernel_get_system().modules.auth;
+                    let auth_module = &mut api.kernel_get_system().modules.auth;
+                    let params = match &mut auth_module.fee_sponsor {
+                        Some(fee_sponsor) if !fee_sponsor.is_started => {
+                            fee_sponsor.is_started = true;
+                            &fee_sponsor.params
+                        }
+                        _ => &auth_module.params,
+                    };
                     (
-                        auth_module.params.virtual_resources.clone(),
-                        auth_module.params.initial_proofs.clone(),
+                        params.virtual_resources.clone(),
+                        params.initial_proofs.clone(),
                     )
                 } else {
                     (BTreeSet::new(), BTreeSet::new())
@@ -126,6 +180,74 @@ impl AuthModule {
         Ok(auth_zone)
     }
 
+    /// Resolves the auth zone of a transaction processor frame starting a child intent.
+    /// Returns `None` if the call is not the start of a child intent by the root transaction
+    /// processor, in which case the usual function permission applies.
+    fn resolve_subintent_auth_zone_params<V, Y>(
+        api: &mut SystemService<Y, V>,
+        blueprint_id: &BlueprintId,
+        ident: &str,
+        args: &IndexedScryptoValue,
+    ) -> Result<Option<AuthZoneParams>, RuntimeError>
+    where
+        V: SystemCallbackObject,
+        Y: KernelApi<SystemConfig<V>>,
+    {
+        let is_transaction_processor_blueprint = blueprint_id
+            .package_address
+            .eq(&TRANSACTION_PROCESSOR_PACKAGE)
+            && blueprint_id
+                .blueprint_name
+                .eq(TRANSACTION_PROCESSOR_BLUEPRINT);
+        if !is_transaction_processor_blueprint
+            || !ident.eq(TRANSACTION_PROCESSOR_RUN_SUBINTENT_IDENT)
+            || api.kernel_get_current_depth() != 1
+        {
+            return Ok(None);
+        }
+
+        let is_called_by_root_processor = match api.current_actor() {
+            Actor::Function(FunctionActor {
+                blueprint_id,
+                ident,
+                ..
+            }) => {
+                blueprint_id
+                    .package_address
+                    .eq(&TRANSACTION_PROCESSOR_PACKAGE)
+                    && blueprint_id
+                        .blueprint_name
+                        .eq(TRANSACTION_PROCESSOR_BLUEPRINT)
+                    && ident.eq(TRANSACTION_PROCESSOR_RUN_WITH_SUBINTENTS_IDENT)
+            }
+            _ => false,
+        };
+        if !is_called_by_root_processor {
+            return Ok(None);
+        }
+
+        let Ok(input) = args.as_typed::<TransactionProcessorRunSubintentInput>() else {
+            return Ok(None);
+        };
+        let index = input.subintent_index;
+        let subintent = api
+            .kernel_get_system()
+            .modules
+            .auth
+            .subintents
+            .get_mut(index as usize)
+            .filter(|subintent| {
+                !subintent.is_started
+                    && subintent.instructions_hash == hash(&input.manifest_encoded_instructions)
+            })
+            .ok_or(RuntimeError::SystemModuleError(
+                SystemModuleError::AuthError(AuthError::InvalidSubintent(index)),
+            ))?;
+        subintent.is_started = true;
+
+        Ok(Some(subintent.params.clone()))
+    }
+
     pub fn on_call_function_finish<V, Y>(
         api: &mut SystemService<Y, V>,
         auth_zone: NodeId,
@@ -11,8 +11,10 @@ use crate::system::system_substates::FieldSubstate;
 use crate::system::system_substates::KeyValueEntrySubstate;
 use crate::types::*;
 use native_sdk::resource::{NativeNonFungibleProof, NativeProof};
+use native_sdk::runtime::Runtime;
 use num_traits::Zero;
 use radix_engine_interface::api::{ClientObjectApi, LockFlags, ModuleId};
+use radix_engine_interface::blueprints::consensus_manager::TimePrecision;
 use radix_engine_interface::blueprints::resource::*;
 use sbor::rust::ops::Fn;
 
@@ -320,6 +322,57 @@ impl Authorization {
 
                 return Ok(AuthorizationCheckResult::Authorized);
             }
+            AccessRuleNode::CountOf(count, rules) => {
+                let mut left = *count;
+                for r in rules {
+                    if left == 0 {
+                        break;
+                    }
+                    let rtn = Self::verify_auth_rule(auth_zone, r, api)?;
+                    if matches!(rtn, AuthorizationCheckResult::Authorized) {
+                        left -= 1;
+                    }
+                }
+
+                if left == 0 {
+                    Ok(AuthorizationCheckResult::Authorized)
+                } else {
+                    Ok(AuthorizationCheckResult::Failed(vec![
+                        AccessRule::Protected(auth_rule.clone()),
+                    ]))
+                }
+            }
+            AccessRuleNode::TimeRule(rule) => {
+                if Self::verify_time_rule(rule, api)? {
+                    Ok(AuthorizationCheckResult::Authorized)
+                } else {
+                    Ok(AuthorizationCheckResult::Failed(vec![
+                        AccessRule::Protected(auth_rule.clone()),
+                    ]))
+                }
+            }
+        }
+    }
+
+    fn verify_time_rule<Y: ClientObjectApi<RuntimeError>>(
+        time_rule: &TimeRule,
+        api: &mut Y,
+    ) -> Result<bool, RuntimeError> {
+        match time_rule {
+            TimeRule::AtOrAfterInstant(instant) => Runtime::compare_against_current_time(
+                api,
+                *instant,
+                TimePrecision::Second,
+                TimeComparisonOperator::Gte,
+            ),
+            TimeRule::BeforeInstant(instant) => Runtime::compare_against_current_time(
+                api,
+                *instant,
+                TimePrecision::Second,
+                TimeComparisonOperator::Lt,
+            ),
+            TimeRule::AtOrAfterEpoch(epoch) => Ok(Runtime::current_epoch(api)? >= *epoch),
+            TimeRule::BeforeEpoch(epoch) => Ok(Runtime::current_epoch(api)? < *epoch),
         }
     }
 
@@ -0,0 +1,292 @@
+use super::{ExecutionCostingEntry, FinalizationCostingEntry};
+use crate::system::actor::{Actor, BlueprintHookActor, FunctionActor};
+use crate::types::*;
+use crate::utils::to_json_string;
+
+/// The code run by an invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ScryptoSbor)]
+pub enum CostProfileCode {
+    Native,
+    Wasm,
+}
+
+/// An invocation of a [`CostProfile`], with the cost units it consumed.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct CostProfileFrame {
+    /// The index of the invoking frame, or `None` for the root frame of the transaction
+    pub parent: Option<usize>,
+    /// The invoked blueprint, or `None` for the root frame of the transaction
+    pub blueprint_id: Option<BlueprintId>,
+    /// The invoked function, method or hook
+    pub ident: String,
+    /// The code run by the invocation, if it ran any
+    pub code: Option<CostProfileCode>,
+    /// The cost units consumed by the transaction before the invocation started
+    pub start_cost_units: u32,
+    /// The cost units consumed by the transaction by the time the invocation ended
+    pub end_cost_units: u32,
+    /// The cost units consumed by the invocation itself, excluding its nested invocations,
+    /// keyed by costing entry
+    pub self_cost_units: IndexMap<String, u32>,
+}
+
+impl CostProfileFrame {
+    pub fn total_cost_units(&self) -> u32 {
+        self.end_cost_units - self.start_cost_units
+    }
+
+    pub fn name(&self, address_encoder: Option<&AddressBech32Encoder>) -> String {
+        let blueprint_id = match &self.blueprint_id {
+            Some(blueprint_id) => blueprint_id,
+            None => return self.ident.clone(),
+        };
+        let code = match self.code {
+            Some(CostProfileCode::Native) => " [native]",
+            Some(CostProfileCode::Wasm) => " [wasm]",
+            None => "",
+        };
+        format!(
+            "{}::{}::{}{}",
+            blueprint_id.package_address.to_string(address_encoder),
+            blueprint_id.blueprint_name,
+            self.ident,
+            code
+        )
+    }
+}
+
+/// The execution and finalization cost units of a transaction, attributed to the call frame
+/// stack which consumed them.
+///
+/// The frames are stored in invocation order, starting with the root frame of the transaction,
+/// which is also charged with the costs consumed outside of any invocation (e.g. the payload
+/// validation and the finalization costs). The start and end of each frame are measured in cost
+/// units, which the exports use as their clock.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct CostProfile {
+    pub frames: Vec<CostProfileFrame>,
+}
+
+impl CostProfile {
+    pub fn total_cost_units(&self) -> u32 {
+        self.frames[0].total_cost_units()
+    }
+
+    /// Exports the profile in the folded stacks format of `flamegraph.pl` and `inferno`, with the
+    /// costing entries as the leaves of the stacks.
+    pub fn to_folded_stacks(&self, address_encoder: Option<&AddressBech32Encoder>) -> String {
+        let mut stacks = Vec::<String>::with_capacity(self.frames.len());
+        let mut folded = String::new();
+        for frame in &self.frames {
+            let name = frame.name(address_encoder);
+            let stack = match frame.parent {
+                Some(parent) => format!("{};{}", stacks[parent], name),
+                None => name,
+            };
+            for (costing_entry, cost_units) in &frame.self_cost_units {
+                folded.push_str(&format!("{};{} {}\n", stack, costing_entry, cost_units));
+            }
+            stacks.push(stack);
+        }
+        folded
+    }
+
+    /// Exports the profile as an evented speedscope profile.
+    pub fn to_speedscope_json(&self, address_encoder: Option<&AddressBech32Encoder>) -> String {
+        let mut names = index_set_new();
+        let frame_indices: Vec<usize> = self
+            .frames
+            .iter()
+            .map(|frame| names.insert_full(frame.name(address_encoder)).0)
+            .collect();
+
+        let mut children = vec![Vec::new(); self.frames.len()];
+        for (index, frame) in self.frames.iter().enumerate() {
+            if let Some(parent) = frame.parent {
+                children[parent].push(index);
+            }
+        }
+
+        let mut events = Vec::new();
+        Self::visit(&children, 0, &mut |index, is_open| {
+            let frame = &self.frames[index];
+            events.push(format!(
+                r#"{{"type":"{}","frame":{},"at":{}}}"#,
+                if is_open { "O" } else { "C" },
+                frame_indices[index],
+                if is_open {
+                    frame.start_cost_units
+                } else {
+                    frame.end_cost_units
+                }
+            ));
+        });
+
+        format!(
+            r#"{{"$schema":"https://www.speedscope.app/file-format-schema.json","shared":{{"frames":[{}]}},"profiles":[{{"type":"evented","name":"Cost units","unit":"none","startValue":0,"endValue":{},"events":[{}]}}],"exporter":"radix-engine"}}"#,
+            names
+                .iter()
+                .map(|name| format!(r#"{{"name":{}}}"#, to_json_string(name)))
+                .collect::<Vec<_>>()
+                .join(","),
+            self.total_cost_units(),
+            events.join(",")
+        )
+    }
+
+    /// Exports the profile in the Chrome trace event format, as understood by `chrome://tracing`
+    /// and Perfetto, with a cost unit per microsecond.
+    pub fn to_chrome_trace_json(&self, address_encoder: Option<&AddressBech32Encoder>) -> String {
+        let events: Vec<String> = self
+            .frames
+            .iter()
+            .map(|frame| {
+                format!(
+                    r#"{{"name":{},"cat":"{}","ph":"X","ts":{},"dur":{},"pid":1,"tid":1,"args":{{{}}}}}"#,
+                    to_json_string(&frame.name(address_encoder)),
+                    match frame.code {
+                        Some(CostProfileCode::Native) => "native",
+                        Some(CostProfileCode::Wasm) => "wasm",
+                        None => "none",
+                    },
+                    frame.start_cost_units,
+                    frame.total_cost_units(),
+                    frame
+                        .self_cost_units
+                        .iter()
+                        .map(|(costing_entry, cost_units)| format!(
+                            "{}:{}",
+                            to_json_string(costing_entry),
+                            cost_units
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )
+            })
+            .collect();
+
+        format!(r#"{{"traceEvents":[{}]}}"#, events.join(","))
+    }
+
+    /// Visits the frames depth first, as they are opened and closed.
+    fn visit<F: FnMut(usize, bool)>(children: &[Vec<usize>], index: usize, f: &mut F) {
+        f(index, true);
+        for child in &children[index] {
+            Self::visit(children, *child, f);
+        }
+        f(index, false);
+    }
+}
+
+/// Builds a [`CostProfile`] by tracking the call frame stack of the transaction.
+#[derive(Debug, Clone)]
+pub struct CostProfiler {
+    frames: Vec<CostProfileFrame>,
+    stack: Vec<usize>,
+    consumed_cost_units: u32,
+}
+
+impl Default for CostProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CostProfiler {
+    pub fn new() -> Self {
+        Self {
+            frames: vec![CostProfileFrame {
+                parent: None,
+                blueprint_id: None,
+                ident: "Transaction".to_string(),
+                code: None,
+                start_cost_units: 0,
+                end_cost_units: 0,
+                self_cost_units: index_map_new(),
+            }],
+            stack: vec![0],
+            consumed_cost_units: 0,
+        }
+    }
+
+    pub fn on_invocation_start(&mut self, actor: &Actor) {
+        let (blueprint_id, ident) = match actor {
+            Actor::Method(method_actor) => (
+                Some(method_actor.get_blueprint_id()),
+                method_actor.ident.clone(),
+            ),
+            Actor::Function(FunctionActor {
+                blueprint_id,
+                ident,
+                ..
+            }) => (Some(blueprint_id.clone()), ident.clone()),
+            Actor::BlueprintHook(BlueprintHookActor {
+                blueprint_id, hook, ..
+            }) => (Some(blueprint_id.clone()), format!("{:?}", hook)),
+            Actor::Root => (None, "Root".to_string()),
+        };
+
+        self.frames.push(CostProfileFrame {
+            parent: self.stack.last().cloned(),
+            blueprint_id,
+            ident,
+            code: None,
+            start_cost_units: self.consumed_cost_units,
+            end_cost_units: self.consumed_cost_units,
+            self_cost_units: index_map_new(),
+        });
+        self.stack.push(self.frames.len() - 1);
+    }
+
+    pub fn on_invocation_end(&mut self) {
+        // The root frame is only closed on finalization
+        if self.stack.len() > 1 {
+            let index = self.stack.pop().unwrap();
+            self.frames[index].end_cost_units = self.consumed_cost_units;
+        }
+    }
+
+    pub fn on_execution_cost(&mut self, costing_entry: &ExecutionCostingEntry, cost_units: u32) {
+        let code = match costing_entry {
+            ExecutionCostingEntry::RunNativeCode { .. } => Some(CostProfileCode::Native),
+            ExecutionCostingEntry::RunWasmCode { .. } => Some(CostProfileCode::Wasm),
+            _ => None,
+        };
+        if code.is_some() {
+            self.current_frame().code = code;
+        }
+        self.record(costing_entry.to_trace_key(), cost_units);
+    }
+
+    pub fn on_finalization_cost(
+        &mut self,
+        costing_entry: &FinalizationCostingEntry,
+        cost_units: u32,
+    ) {
+        self.record(costing_entry.to_trace_key(), cost_units);
+    }
+
+    /// Closes the frames left open, e.g. by a failed invocation.
+    pub fn finalize(mut self) -> CostProfile {
+        for index in self.stack.drain(..) {
+            self.frames[index].end_cost_units = self.consumed_cost_units;
+        }
+        CostProfile {
+            frames: self.frames,
+        }
+    }
+
+    fn record(&mut self, key: String, cost_units: u32) {
+        self.consumed_cost_units = self.consumed_cost_units.saturating_add(cost_units);
+        self.current_frame()
+            .self_cost_units
+            .entry(key)
+            .or_default()
+            .add_assign(cost_units);
+    }
+
+    fn current_frame(&mut self) -> &mut CostProfileFrame {
+        let index = *self.stack.last().unwrap();
+        &mut self.frames[index]
+    }
+}
@@ -101,6 +101,9 @@ pub enum ExecutionCostingEntry<'a> {
     QueryActor,
     QueryTransactionHash,
     GenerateRuid,
+    ScanKeyValueStore {
+        entries: usize,
+    },
     EmitEvent {
         size: usize,
     },
@@ -187,6 +190,9 @@ impl<'a> ExecutionCostingEntry<'a> {
             ExecutionCostingEntry::QueryActor => ft.query_actor_cost(),
             ExecutionCostingEntry::QueryTransactionHash => ft.query_transaction_hash_cost(),
             ExecutionCostingEntry::GenerateRuid => ft.generate_ruid_cost(),
+            ExecutionCostingEntry::ScanKeyValueStore { entries } => {
+                ft.scan_key_value_store_cost(*entries)
+            }
             ExecutionCostingEntry::EmitEvent { size } => ft.emit_event_cost(*size),
             ExecutionCostingEntry::EmitLog { size } => ft.emit_log_cost(*size),
             ExecutionCostingEntry::Panic { size } => ft.panic_cost(*size),
@@ -84,6 +84,7 @@ pub struct CostingModule {
     pub execution_cost_breakdown: IndexMap<String, u32>,
     pub finalization_cost_breakdown: IndexMap<String, u32>,
     pub storage_cost_breakdown: IndexMap<StorageType, usize>,
+    pub cost_profiler: Option<CostProfiler>,
 
     pub on_apply_cost: OnApplyCost,
 }
@@ -117,6 +118,10 @@ impl CostingModule {
                 .add_assign(cost_units);
         }
 
+        if let Some(cost_profiler) = &mut self.cost_profiler {
+            cost_profiler.on_execution_cost(&costing_entry, cost_units);
+        }
+
         Ok(())
     }
 
@@ -144,6 +149,10 @@ impl CostingModule {
                 .add_assign(cost_units);
         }
 
+        if let Some(cost_profiler) = &mut self.cost_profiler {
+            cost_profiler.on_execution_cost(&costing_entry, cost_units);
+        }
+
         Ok(())
     }
 
@@ -196,6 +205,10 @@ impl CostingModule {
                 .add_assign(cost_units);
         }
 
+        if let Some(cost_profiler) = &mut self.cost_profiler {
+            cost_profiler.on_finalization_cost(&costing_entry, cost_units);
+        }
+
         Ok(())
     }
 
@@ -277,6 +290,10 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for CostingModule {
         api: &mut Y,
         invocation: &KernelInvocation<Actor>,
     ) -> Result<(), RuntimeError> {
+        if let Some(cost_profiler) = &mut api.kernel_get_system().modules.costing.cost_profiler {
+            cost_profiler.on_invocation_start(&invocation.call_frame_data);
+        }
+
         // Skip invocation costing for transaction processor
         if api.kernel_get_current_depth() == 0 {
             return Ok(());
@@ -357,17 +374,19 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for CostingModule {
         api: &mut Y,
         output: &IndexedScryptoValue,
     ) -> Result<(), RuntimeError> {
-        // Skip invocation costing for transaction processor
-        if api.kernel_get_current_depth() == 0 {
-            return Ok(());
-        }
+        let depth = api.kernel_get_current_depth();
+        let costing = &mut api.kernel_get_system().modules.costing;
 
-        api.kernel_get_system()
-            .modules
-            .costing
-            .apply_execution_cost(ExecutionCostingEntry::AfterInvoke {
+        // Skip invocation costing for transaction processor
+        if depth != 0 {
+            costing.apply_execution_cost(ExecutionCostingEntry::AfterInvoke {
                 output_size: output.len(),
             })?;
+        }
+
+        if let Some(cost_profiler) = &mut costing.cost_profiler {
+            cost_profiler.on_invocation_end();
+        }
 
         Ok(())
     }
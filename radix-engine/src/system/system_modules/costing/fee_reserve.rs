@@ -140,6 +140,8 @@ pub struct SystemLoanFeeReserve {
 
     /// Payments made during the execution of a transaction.
     locked_fees: Vec<(NodeId, LiquidFungibleResource, bool)>,
+    /// The account sponsoring the fees, and the vaults it locked fees from.
+    fee_sponsor: Option<(ComponentAddress, IndexSet<NodeId>)>,
 }
 
 impl Default for SystemLoanFeeReserve {
@@ -247,6 +249,7 @@ impl SystemLoanFeeReserve {
             storage_cost_deferred: index_map_new(),
 
             locked_fees: Vec::new(),
+            fee_sponsor: None,
         }
     }
 
@@ -282,6 +285,17 @@ impl SystemLoanFeeReserve {
         &self.royalty_cost_breakdown
     }
 
+    /// Records the fees locked so far as paid by the sponsor of the transaction, which locks
+    /// its fee before anything else runs.
+    pub fn record_fee_sponsor(&mut self, account: ComponentAddress) {
+        let vaults = self
+            .locked_fees
+            .iter()
+            .map(|(vault_id, _, _)| *vault_id)
+            .collect();
+        self.fee_sponsor = Some((account, vaults));
+    }
+
     fn check_execution_cost_unit_limit(&self, cost_units: u32) -> Result<(), FeeReserveError> {
         if checked_add(self.execution_cost_units_committed, cost_units)?
             > self.execution_cost_unit_limit
@@ -562,6 +576,7 @@ impl FinalizingFeeReserve for SystemLoanFeeReserve {
             total_storage_cost_in_xrd: self.storage_cost_committed,
             total_bad_debt_in_xrd: self.xrd_owed,
             locked_fees: self.locked_fees,
+            fee_sponsor: self.fee_sponsor,
             royalty_cost_breakdown: self.royalty_cost_breakdown,
         }
     }
@@ -25,6 +25,8 @@ pub struct FeeReserveFinalizationSummary {
     pub total_bad_debt_in_xrd: Decimal,
     /// The vaults locked for XRD payment
     pub locked_fees: Vec<(NodeId, LiquidFungibleResource, bool)>,
+    /// The account sponsoring the fees, and the vaults it locked fees from
+    pub fee_sponsor: Option<(ComponentAddress, IndexSet<NodeId>)>,
     /// The royalty cost breakdown
     pub royalty_cost_breakdown: IndexMap<RoyaltyRecipient, Decimal>,
 }
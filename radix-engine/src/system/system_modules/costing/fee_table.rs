@@ -366,6 +366,12 @@ impl FeeTable {
         500
     }
 
+    #[inline]
+    pub fn scan_key_value_store_cost(&self, entries: usize) -> u32 {
+        // Charged on top of the kernel scan, for each scanned entry, whether it has a value or not
+        mul(100, cast(entries))
+    }
+
     #[inline]
     pub fn emit_event_cost(&self, size: usize) -> u32 {
         500 + Self::data_processing_cost(size)
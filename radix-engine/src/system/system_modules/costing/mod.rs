@@ -1,9 +1,11 @@
+mod cost_profile;
 mod costing_entry;
 mod costing_module;
 mod fee_reserve;
 mod fee_summary;
 mod fee_table;
 
+pub use cost_profile::*;
 pub use costing_entry::*;
 pub use costing_module::*;
 pub use fee_reserve::*;
@@ -0,0 +1,283 @@
+use crate::errors::{RuntimeError, SystemModuleError};
+use crate::kernel::call_frame::CallFrameMessage;
+use crate::kernel::kernel_api::{KernelApi, KernelInternalApi, KernelInvocation};
+use crate::kernel::kernel_callback_api::{
+    CloseSubstateEvent, CreateNodeEvent, DropNodeEvent, OpenSubstateEvent, ReadSubstateEvent,
+    WriteSubstateEvent,
+};
+use crate::system::actor::Actor;
+use crate::system::module::{InitSystemModule, SystemModule};
+use crate::system::system_callback::SystemConfig;
+use crate::system::system_callback_api::SystemCallbackObject;
+use crate::system::system_modules::transaction_runtime::Event;
+use crate::types::*;
+#[cfg(not(feature = "alloc"))]
+use sbor::rust::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct SystemModuleExtensionError {
+    pub extension: String,
+    pub message: String,
+}
+
+/// A system module provided by the embedder of the engine, e.g. for custom instrumentation.
+///
+/// Unlike the built-in modules, an extension only observes the transaction: each hook gets the
+/// current call frame depth and read-only views of the kernel events, and cannot access the
+/// kernel, the heap or the track. The hooks are called after those of the built-in modules, and
+/// their errors only fail the transaction if the extension is registered as affecting consensus;
+/// the others are reported in the receipt, see
+/// [`TransactionReceipt::system_module_extension_errors`](crate::transaction::TransactionReceipt).
+pub trait SystemModuleExtension: Debug {
+    fn name(&self) -> String;
+
+    fn on_init(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_teardown(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn before_invoke(
+        &mut self,
+        _depth: usize,
+        _invocation: &KernelInvocation<Actor>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_execution_finish(
+        &mut self,
+        _depth: usize,
+        _message: &CallFrameMessage,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn after_invoke(&mut self, _depth: usize, _output: &IndexedScryptoValue) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_create_node(&mut self, _depth: usize, _event: &CreateNodeEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_drop_node(&mut self, _depth: usize, _event: &DropNodeEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_open_substate(
+        &mut self,
+        _depth: usize,
+        _event: &OpenSubstateEvent,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_read_substate(
+        &mut self,
+        _depth: usize,
+        _event: &ReadSubstateEvent,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_write_substate(
+        &mut self,
+        _depth: usize,
+        _event: &WriteSubstateEvent,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_close_substate(
+        &mut self,
+        _depth: usize,
+        _event: &CloseSubstateEvent,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Called for every application event emitted by the transaction.
+    fn on_event(&mut self, _event: &Event) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// A [`SystemModuleExtension`] shared with the embedder, so that it can read back what it
+/// collected. It is behind a `Mutex` in `std`, so that an [`ExecutionConfig`] can be shared by the
+/// threads executing transactions in parallel, see
+/// [`execute_transactions_in_parallel()`](crate::transaction::execute_transactions_in_parallel).
+///
+/// [`ExecutionConfig`]: crate::transaction::ExecutionConfig
+#[cfg(not(feature = "alloc"))]
+pub type SharedSystemModuleExtension = Arc<Mutex<dyn SystemModuleExtension + Send>>;
+#[cfg(feature = "alloc")]
+pub type SharedSystemModuleExtension = Rc<RefCell<dyn SystemModuleExtension>>;
+
+/// A [`SystemModuleExtension`] registered in the [`ExecutionConfig`](crate::transaction::ExecutionConfig).
+#[derive(Debug, Clone)]
+pub struct RegisteredSystemModuleExtension {
+    pub extension: SharedSystemModuleExtension,
+    /// Whether the errors of the extension fail the transaction. Only set this if every node
+    /// executing the transaction runs the extension, as it then affects the transaction outcome.
+    pub affects_consensus: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionModule {
+    pub extensions: Vec<RegisteredSystemModuleExtension>,
+    /// The errors of the extensions which do not affect consensus
+    pub ignored_errors: Vec<SystemModuleExtensionError>,
+}
+
+impl ExtensionModule {
+    pub fn new(extensions: Vec<RegisteredSystemModuleExtension>) -> Self {
+        Self {
+            extensions,
+            ignored_errors: Vec::new(),
+        }
+    }
+
+    pub fn finalize(self) -> Vec<SystemModuleExtensionError> {
+        self.ignored_errors
+    }
+
+    pub fn on_event(&mut self, event: &Event) -> Result<(), RuntimeError> {
+        self.dispatch(|extension| extension.on_event(event))
+    }
+
+    fn dispatch<F>(&mut self, mut f: F) -> Result<(), RuntimeError>
+    where
+        F: FnMut(&mut dyn SystemModuleExtension) -> Result<(), String>,
+    {
+        for registered in &self.extensions {
+            #[cfg(not(feature = "alloc"))]
+            let mut extension = registered
+                .extension
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            #[cfg(feature = "alloc")]
+            let mut extension = registered.extension.borrow_mut();
+
+            if let Err(message) = f(&mut *extension) {
+                let error = SystemModuleExtensionError {
+                    extension: extension.name(),
+                    message,
+                };
+                if registered.affects_consensus {
+                    return Err(RuntimeError::SystemModuleError(
+                        SystemModuleError::ExtensionError(error),
+                    ));
+                }
+                self.ignored_errors.push(error);
+            }
+        }
+        Ok(())
+    }
+
+    fn dispatch_with_api<Y, V, F>(api: &mut Y, mut f: F) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<SystemConfig<V>>,
+        V: SystemCallbackObject,
+        F: FnMut(&mut dyn SystemModuleExtension, usize) -> Result<(), String>,
+    {
+        let depth = api.kernel_get_current_depth();
+        api.kernel_get_system()
+            .modules
+            .extension
+            .dispatch(|extension| f(extension, depth))
+    }
+}
+
+impl InitSystemModule for ExtensionModule {
+    fn on_init(&mut self) -> Result<(), RuntimeError> {
+        self.dispatch(|extension| extension.on_init())
+    }
+}
+
+impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for ExtensionModule {
+    fn on_teardown<Y: KernelApi<SystemConfig<V>>>(api: &mut Y) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, _| extension.on_teardown())
+    }
+
+    fn before_invoke<Y: KernelApi<SystemConfig<V>>>(
+        api: &mut Y,
+        invocation: &KernelInvocation<Actor>,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.before_invoke(depth, invocation)
+        })
+    }
+
+    fn on_execution_finish<Y: KernelApi<SystemConfig<V>>>(
+        api: &mut Y,
+        message: &CallFrameMessage,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.on_execution_finish(depth, message)
+        })
+    }
+
+    fn after_invoke<Y: KernelApi<SystemConfig<V>>>(
+        api: &mut Y,
+        output: &IndexedScryptoValue,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.after_invoke(depth, output)
+        })
+    }
+
+    fn on_create_node<Y: KernelInternalApi<SystemConfig<V>>>(
+        api: &mut Y,
+        event: &CreateNodeEvent,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.on_create_node(depth, event)
+        })
+    }
+
+    fn on_drop_node<Y: KernelInternalApi<SystemConfig<V>>>(
+        api: &mut Y,
+        event: &DropNodeEvent,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| extension.on_drop_node(depth, event))
+    }
+
+    fn on_open_substate<Y: KernelInternalApi<SystemConfig<V>>>(
+        api: &mut Y,
+        event: &OpenSubstateEvent,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.on_open_substate(depth, event)
+        })
+    }
+
+    fn on_read_substate<Y: KernelInternalApi<SystemConfig<V>>>(
+        api: &mut Y,
+        event: &ReadSubstateEvent,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.on_read_substate(depth, event)
+        })
+    }
+
+    fn on_write_substate<Y: KernelInternalApi<SystemConfig<V>>>(
+        api: &mut Y,
+        event: &WriteSubstateEvent,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.on_write_substate(depth, event)
+        })
+    }
+
+    fn on_close_substate<Y: KernelInternalApi<SystemConfig<V>>>(
+        api: &mut Y,
+        event: &CloseSubstateEvent,
+    ) -> Result<(), RuntimeError> {
+        Self::dispatch_with_api(api, |extension, depth| {
+            extension.on_close_substate(depth, event)
+        })
+    }
+}
@@ -4,25 +4,347 @@ use crate::kernel::kernel_callback_api::{
     CloseSubstateEvent, CreateNodeEvent, DropNodeEvent, OpenSubstateEvent, ReadSubstateEvent,
     WriteSubstateEvent,
 };
+use crate::kernel::substate_io::SubstateDevice;
 use crate::system::actor::Actor;
 use crate::system::module::{InitSystemModule, SystemModule};
 use crate::system::system_callback::SystemConfig;
 use crate::system::system_callback_api::SystemCallbackObject;
 use crate::types::*;
+use crate::utils::to_json_string;
 use crate::{errors::RuntimeError, kernel::kernel_api::KernelApi};
+#[cfg(not(feature = "alloc"))]
 use colored::Colorize;
-use radix_engine_interface::types::SubstateKey;
+use radix_engine_interface::api::LockFlags;
+use radix_engine_interface::types::{SubstateHandle, SubstateKey};
 use sbor::rust::collections::BTreeMap;
+#[cfg(not(feature = "alloc"))]
+use sbor::rust::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone)]
-pub struct KernelTraceModule {}
+/// Where the [`KernelTraceModule`] sends its records.
+#[derive(Debug, Clone, Default)]
+pub enum KernelTraceSink {
+    /// Prints the records to the standard output, coloured and indented by call depth
+    #[default]
+    Print,
+    /// Collects the records in the transaction receipt
+    Receipt,
+    /// Hands each record to the given writer as soon as it happens, so that the records are kept
+    /// even if the transaction panics
+    Writer(SharedKernelTraceWriter),
+}
+
+/// Receives the records of the [`KernelTraceModule`], e.g. to write them to a file.
+///
+/// The writer is responsible for its own IO, and for reporting its errors once the transaction
+/// has been executed.
+pub trait KernelTraceWriter: Debug {
+    fn write(&mut self, record: &KernelTraceRecord);
+}
+
+/// A [`KernelTraceWriter`] shared with the embedder, see
+/// [`SharedSystemModuleExtension`](crate::system::system_modules::extension::SharedSystemModuleExtension).
+#[cfg(not(feature = "alloc"))]
+pub type SharedKernelTraceWriter = Arc<Mutex<dyn KernelTraceWriter + Send>>;
+#[cfg(feature = "alloc")]
+pub type SharedKernelTraceWriter = Rc<RefCell<dyn KernelTraceWriter>>;
 
-#[macro_export]
-macro_rules! log {
-    ( $api: expr, $msg: expr $( , $arg:expr )* ) => {
-        #[cfg(not(feature = "alloc"))]
-        println!("{}[{}] {}", "    ".repeat($api.kernel_get_current_depth()), $api.kernel_get_current_depth(), sbor::rust::format!($msg, $( $arg ),*));
-    };
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub enum KernelTraceEvent {
+    Invoke {
+        /// The debug representation of the invoked actor
+        actor: String,
+        input_size: usize,
+        sent_nodes: Vec<NodeId>,
+        sent_references: Vec<NodeId>,
+    },
+    ExecutionFinish {
+        returned_nodes: Vec<NodeId>,
+        returned_references: Vec<NodeId>,
+    },
+    Return {
+        output_size: usize,
+    },
+    AllocateNodeId {
+        entity_type: EntityType,
+    },
+    CreateNode {
+        node_id: NodeId,
+        substate_keys: BTreeMap<PartitionNumber, Vec<SubstateKey>>,
+        size: usize,
+    },
+    DropNode {
+        node_id: NodeId,
+    },
+    OpenSubstate {
+        node_id: NodeId,
+        partition_number: PartitionNumber,
+        substate_key: SubstateKey,
+        flags: LockFlags,
+    },
+    SubstateOpened {
+        node_id: NodeId,
+        handle: SubstateHandle,
+        size: usize,
+    },
+    ReadSubstate {
+        handle: SubstateHandle,
+        size: usize,
+        device: SubstateDevice,
+    },
+    WriteSubstate {
+        handle: SubstateHandle,
+        size: usize,
+    },
+    CloseSubstate {
+        handle: SubstateHandle,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct KernelTraceRecord {
+    /// The call frame depth of the kernel when the event happened
+    pub depth: usize,
+    pub event: KernelTraceEvent,
+}
+
+impl KernelTraceRecord {
+    pub fn to_json(&self) -> String {
+        let hex_list = |node_ids: &[NodeId]| {
+            node_ids
+                .iter()
+                .map(|node_id| to_json_string(&hex::encode(node_id.as_bytes())))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let node = |node_id: &NodeId| to_json_string(&hex::encode(node_id.as_bytes()));
+
+        let fields = match &self.event {
+            KernelTraceEvent::Invoke {
+                actor,
+                input_size,
+                sent_nodes,
+                sent_references,
+            } => format!(
+                r#""type":"Invoke","actor":{},"input_size":{},"sent_nodes":[{}],"sent_references":[{}]"#,
+                to_json_string(actor),
+                input_size,
+                hex_list(sent_nodes),
+                hex_list(sent_references)
+            ),
+            KernelTraceEvent::ExecutionFinish {
+                returned_nodes,
+                returned_references,
+            } => format!(
+                r#""type":"ExecutionFinish","returned_nodes":[{}],"returned_references":[{}]"#,
+                hex_list(returned_nodes),
+                hex_list(returned_references)
+            ),
+            KernelTraceEvent::Return { output_size } => {
+                format!(r#""type":"Return","output_size":{}"#, output_size)
+            }
+            KernelTraceEvent::AllocateNodeId { entity_type } => format!(
+                r#""type":"AllocateNodeId","entity_type":{}"#,
+                to_json_string(&format!("{:?}", entity_type))
+            ),
+            KernelTraceEvent::CreateNode {
+                node_id,
+                substate_keys,
+                size,
+            } => format!(
+                r#""type":"CreateNode","node_id":{},"substate_keys":{{{}}},"size":{}"#,
+                node(node_id),
+                substate_keys
+                    .iter()
+                    .map(|(partition_number, keys)| format!(
+                        r#""{}":[{}]"#,
+                        partition_number.0,
+                        keys.iter()
+                            .map(|key| to_json_string(&format!("{:?}", key)))
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                size
+            ),
+            KernelTraceEvent::DropNode { node_id } => {
+                format!(r#""type":"DropNode","node_id":{}"#, node(node_id))
+            }
+            KernelTraceEvent::OpenSubstate {
+                node_id,
+                partition_number,
+                substate_key,
+                flags,
+            } => format!(
+                r#""type":"OpenSubstate","node_id":{},"partition_number":{},"substate_key":{},"flags":{}"#,
+                node(node_id),
+                partition_number.0,
+                to_json_string(&format!("{:?}", substate_key)),
+                flags.bits()
+            ),
+            KernelTraceEvent::SubstateOpened {
+                node_id,
+                handle,
+                size,
+            } => format!(
+                r#""type":"SubstateOpened","node_id":{},"handle":{},"size":{}"#,
+                node(node_id),
+                handle,
+                size
+            ),
+            KernelTraceEvent::ReadSubstate {
+                handle,
+                size,
+                device,
+            } => format!(
+                r#""type":"ReadSubstate","handle":{},"size":{},"device":"{:?}""#,
+                handle, size, device
+            ),
+            KernelTraceEvent::WriteSubstate { handle, size } => format!(
+                r#""type":"WriteSubstate","handle":{},"size":{}"#,
+                handle, size
+            ),
+            KernelTraceEvent::CloseSubstate { handle } => {
+                format!(r#""type":"CloseSubstate","handle":{}"#, handle)
+            }
+        };
+
+        format!(r#"{{"depth":{},{}}}"#, self.depth, fields)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    pub fn print(&self) {
+        let message = match &self.event {
+            KernelTraceEvent::Invoke {
+                actor,
+                input_size,
+                sent_nodes,
+                sent_references,
+            } => format!(
+                "{}\n{}Sending nodes: {:?}\n{}Sending refs: {:?}",
+                format!("Invoking: fn = {}, input size = {}", actor, input_size).green(),
+                self.prefix(),
+                sent_nodes,
+                self.prefix(),
+                sent_references
+            ),
+            KernelTraceEvent::ExecutionFinish {
+                returned_nodes,
+                returned_references,
+            } => format!(
+                "Returning nodes: {:?}\n{}Returning refs: {:?}",
+                returned_nodes,
+                self.prefix(),
+                returned_references
+            ),
+            KernelTraceEvent::Return { output_size } => {
+                format!("Exiting: output size = {}", output_size)
+            }
+            KernelTraceEvent::AllocateNodeId { entity_type } => {
+                format!("Allocating node id: entity_type = {:?}", entity_type)
+            }
+            KernelTraceEvent::CreateNode {
+                node_id,
+                substate_keys,
+                size,
+            } => format!(
+                "Creating node: id = {:?}, type = {:?}, substates = {:?}, size = {}",
+                node_id,
+                node_id.entity_type(),
+                substate_keys,
+                size
+            )
+            .red()
+            .to_string(),
+            KernelTraceEvent::DropNode { node_id } => format!("Dropping node: id = {:?}", node_id),
+            KernelTraceEvent::OpenSubstate {
+                node_id,
+                partition_number,
+                substate_key,
+                flags,
+            } => format!(
+                "Locking substate: node id = {:?}, partition_num = {:?}, substate_key = {:?}, flags = {:?}",
+                node_id, partition_number, substate_key, flags
+            ),
+            KernelTraceEvent::SubstateOpened {
+                node_id,
+                handle,
+                size,
+            } => format!(
+                "Substate locked: node id = {:?}, handle = {:?}, size = {}",
+                node_id, handle, size
+            ),
+            KernelTraceEvent::ReadSubstate {
+                handle,
+                size,
+                device,
+            } => format!(
+                "Reading substate: handle = {}, size = {}, device = {:?}",
+                handle, size, device
+            ),
+            KernelTraceEvent::WriteSubstate { handle, size } => {
+                format!("Writing substate: handle = {}, size = {}", handle, size)
+            }
+            KernelTraceEvent::CloseSubstate { handle } => {
+                format!("Substate close: handle = {} ", handle)
+            }
+        };
+
+        println!("{}{}", self.prefix(), message);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn prefix(&self) -> String {
+        format!("{}[{}] ", "    ".repeat(self.depth), self.depth)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KernelTraceModule {
+    pub sink: KernelTraceSink,
+    pub records: Vec<KernelTraceRecord>,
+}
+
+impl KernelTraceModule {
+    pub fn new(sink: KernelTraceSink) -> Self {
+        Self {
+            sink,
+            records: Vec::new(),
+        }
+    }
+
+    fn trace<Y: KernelInternalApi<SystemConfig<V>>, V: SystemCallbackObject>(
+        api: &mut Y,
+        event: KernelTraceEvent,
+    ) {
+        let record = KernelTraceRecord {
+            depth: api.kernel_get_current_depth(),
+            event,
+        };
+        let module = &mut api.kernel_get_system().modules.kernel_trace;
+        match &module.sink {
+            #[cfg(not(feature = "alloc"))]
+            KernelTraceSink::Print => record.print(),
+            #[cfg(feature = "alloc")]
+            KernelTraceSink::Print => {}
+            KernelTraceSink::Receipt => module.records.push(record),
+            #[cfg(not(feature = "alloc"))]
+            KernelTraceSink::Writer(writer) => writer
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .write(&record),
+            #[cfg(feature = "alloc")]
+            KernelTraceSink::Writer(writer) => writer.borrow_mut().write(&record),
+        }
+    }
+
+    /// Returns the records to attach to the receipt, if collected for it.
+    pub fn finalize(self) -> Option<Vec<KernelTraceRecord>> {
+        match self.sink {
+            KernelTraceSink::Receipt => Some(self.records),
+            KernelTraceSink::Print | KernelTraceSink::Writer(_) => None,
+        }
+    }
 }
 
 impl InitSystemModule for KernelTraceModule {
@@ -32,22 +354,20 @@ impl InitSystemModule for KernelTraceModule {
     }
 }
 
-#[allow(unused_variables)] // for no_std
 impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModule {
     fn before_invoke<Y: KernelApi<SystemConfig<V>>>(
         api: &mut Y,
         invocation: &KernelInvocation<Actor>,
     ) -> Result<(), RuntimeError> {
-        let message = format!(
-            "Invoking: fn = {:?}, input size = {}",
-            invocation.call_frame_data,
-            invocation.len(),
-        )
-        .green();
-
-        log!(api, "{}", message);
-        log!(api, "Sending nodes: {:?}", invocation.args.owned_nodes());
-        log!(api, "Sending refs: {:?}", invocation.args.references());
+        Self::trace(
+            api,
+            KernelTraceEvent::Invoke {
+                actor: format!("{:?}", invocation.call_frame_data),
+                input_size: invocation.len(),
+                sent_nodes: invocation.args.owned_nodes().clone(),
+                sent_references: invocation.args.references().clone(),
+            },
+        );
         Ok(())
     }
 
@@ -55,8 +375,13 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
         api: &mut Y,
         message: &CallFrameMessage,
     ) -> Result<(), RuntimeError> {
-        log!(api, "Returning nodes: {:?}", message.move_nodes);
-        log!(api, "Returning refs: {:?}", message.copy_global_references);
+        Self::trace(
+            api,
+            KernelTraceEvent::ExecutionFinish {
+                returned_nodes: message.move_nodes.clone(),
+                returned_references: message.copy_global_references.clone(),
+            },
+        );
         Ok(())
     }
 
@@ -64,7 +389,12 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
         api: &mut Y,
         output: &IndexedScryptoValue,
     ) -> Result<(), RuntimeError> {
-        log!(api, "Exiting: output size = {}", output.len());
+        Self::trace(
+            api,
+            KernelTraceEvent::Return {
+                output_size: output.len(),
+            },
+        );
         Ok(())
     }
 
@@ -72,7 +402,7 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
         api: &mut Y,
         entity_type: EntityType,
     ) -> Result<(), RuntimeError> {
-        log!(api, "Allocating node id: entity_type = {:?}", entity_type);
+        Self::trace(api, KernelTraceEvent::AllocateNodeId { entity_type });
         Ok(())
     }
 
@@ -82,25 +412,25 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
     ) -> Result<(), RuntimeError> {
         match event {
             CreateNodeEvent::Start(node_id, node_module_init) => {
-                let mut module_substate_keys =
-                    BTreeMap::<&PartitionNumber, Vec<&SubstateKey>>::new();
-                for (module_id, m) in *node_module_init {
-                    for (substate_key, _) in m {
-                        module_substate_keys
-                            .entry(module_id)
+                let mut substate_keys = BTreeMap::<PartitionNumber, Vec<SubstateKey>>::new();
+                let mut size = 0;
+                for (partition_number, substates) in *node_module_init {
+                    for (substate_key, value) in substates {
+                        substate_keys
+                            .entry(*partition_number)
                             .or_default()
-                            .push(substate_key);
+                            .push(substate_key.clone());
+                        size += value.len();
                     }
                 }
-                let message = format!(
-                    "Creating node: id = {:?}, type = {:?}, substates = {:?}, module 0 = {:?}",
-                    node_id,
-                    node_id.entity_type(),
-                    module_substate_keys,
-                    node_module_init.get(&PartitionNumber(0))
-                )
-                .red();
-                log!(api, "{}", message);
+                Self::trace(
+                    api,
+                    KernelTraceEvent::CreateNode {
+                        node_id: **node_id,
+                        substate_keys,
+                        size,
+                    },
+                );
             }
             _ => {}
         }
@@ -114,7 +444,7 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
     ) -> Result<(), RuntimeError> {
         match event {
             DropNodeEvent::Start(node_id) => {
-                log!(api, "Dropping node: id = {:?}", node_id);
+                Self::trace(api, KernelTraceEvent::DropNode { node_id: **node_id });
             }
             _ => {}
         }
@@ -132,13 +462,14 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
                 substate_key,
                 flags,
             } => {
-                log!(
+                Self::trace(
                     api,
-                    "Locking substate: node id = {:?}, partition_num = {:?}, substate_key = {:?}, flags = {:?}",
-                    node_id,
-                    partition_num,
-                    substate_key,
-                    flags
+                    KernelTraceEvent::OpenSubstate {
+                        node_id: **node_id,
+                        partition_number: **partition_num,
+                        substate_key: (*substate_key).clone(),
+                        flags: **flags,
+                    },
                 );
             }
             OpenSubstateEvent::IOAccess(..) => {}
@@ -147,11 +478,13 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
                 node_id,
                 size,
             } => {
-                log!(
+                Self::trace(
                     api,
-                    "Substate locked: node id = {:?}, handle = {:?}",
-                    node_id,
-                    handle
+                    KernelTraceEvent::SubstateOpened {
+                        node_id: **node_id,
+                        handle: *handle,
+                        size: *size,
+                    },
                 );
             }
         }
@@ -169,12 +502,13 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
                 value,
                 device,
             } => {
-                log!(
+                Self::trace(
                     api,
-                    "Reading substate: handle = {}, size = {}, device = {:?}",
-                    handle,
-                    value.len(),
-                    device
+                    KernelTraceEvent::ReadSubstate {
+                        handle: *handle,
+                        size: value.len(),
+                        device: *device,
+                    },
                 );
             }
             ReadSubstateEvent::IOAccess(_) => {}
@@ -189,11 +523,12 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
     ) -> Result<(), RuntimeError> {
         match event {
             WriteSubstateEvent::Start { handle, value } => {
-                log!(
+                Self::trace(
                     api,
-                    "Writing substate: handle = {}, size = {}",
-                    handle,
-                    value.len()
+                    KernelTraceEvent::WriteSubstate {
+                        handle: *handle,
+                        size: value.len(),
+                    },
                 );
             }
             _ => {}
@@ -207,8 +542,8 @@ impl<V: SystemCallbackObject> SystemModule<SystemConfig<V>> for KernelTraceModul
         event: &CloseSubstateEvent,
     ) -> Result<(), RuntimeError> {
         match event {
-            CloseSubstateEvent::Start(lock_handle) => {
-                log!(api, "Substate close: handle = {} ", lock_handle);
+            CloseSubstateEvent::Start(handle) => {
+                Self::trace(api, KernelTraceEvent::CloseSubstate { handle: *handle });
             }
         }
         Ok(())
@@ -1,6 +1,7 @@
 pub mod auth;
 pub mod costing;
 pub mod execution_trace;
+pub mod extension;
 pub mod kernel_trace;
 pub mod limits;
 pub mod transaction_runtime;
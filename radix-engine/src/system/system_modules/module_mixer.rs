@@ -14,11 +14,13 @@ use crate::system::module::{InitSystemModule, SystemModule};
 use crate::system::system::SystemService;
 use crate::system::system_callback::SystemConfig;
 use crate::system::system_callback_api::SystemCallbackObject;
-use crate::system::system_modules::auth::AuthModule;
+use crate::system::system_modules::auth::{AuthModule, FeeSponsorAuth, SubintentAuth};
+use crate::system::system_modules::costing::CostProfiler;
 use crate::system::system_modules::costing::CostingModule;
 use crate::system::system_modules::costing::FeeTable;
 use crate::system::system_modules::costing::SystemLoanFeeReserve;
 use crate::system::system_modules::execution_trace::ExecutionTraceModule;
+use crate::system::system_modules::extension::ExtensionModule;
 use crate::system::system_modules::kernel_trace::KernelTraceModule;
 use crate::system::system_modules::limits::{LimitsModule, TransactionLimitsConfig};
 use crate::system::system_modules::transaction_runtime::{Event, TransactionRuntimeModule};
@@ -29,7 +31,7 @@ use paste::paste;
 use radix_engine_interface::api::ModuleId;
 use radix_engine_interface::crypto::Hash;
 use resources_tracker_macro::trace_resources;
-use transaction::model::AuthZoneParams;
+use transaction::model::{AuthZoneParams, ExecutableFeeSponsor, ExecutableSubintent};
 
 bitflags! {
     pub struct EnabledModules: u32 {
@@ -46,6 +48,9 @@ bitflags! {
 
         // Execution trace, for preview only
         const EXECUTION_TRACE = 0x01 << 6;
+
+        // Extensions registered by the embedder
+        const EXTENSION = 0x01 << 7;
     }
 }
 
@@ -88,6 +93,7 @@ pub struct SystemModuleMixer {
     pub(super) auth: AuthModule,
     pub(crate) transaction_runtime: TransactionRuntimeModule,
     pub(super) execution_trace: ExecutionTraceModule,
+    pub(super) extension: ExtensionModule,
 }
 
 // Macro generates default modules dispatches call based on passed function name and arguments.
@@ -114,6 +120,9 @@ macro_rules! internal_call_dispatch {
             if modules.contains(EnabledModules::EXECUTION_TRACE) {
                 ExecutionTraceModule::[< $fn >]($($param, )*)?;
             }
+            if modules.contains(EnabledModules::EXTENSION) {
+                ExtensionModule::[< $fn >]($($param, )*)?;
+            }
             Ok(())
         }
     }};
@@ -133,7 +142,7 @@ impl SystemModuleMixer {
     ) -> Self {
         Self {
             enabled_modules,
-            kernel_trace: KernelTraceModule {},
+            kernel_trace: KernelTraceModule::new(execution_config.kernel_trace_sink.clone()),
             costing: CostingModule {
                 fee_reserve,
                 fee_table,
@@ -145,10 +154,17 @@ impl SystemModuleMixer {
                 execution_cost_breakdown: index_map_new(),
                 finalization_cost_breakdown: index_map_new(),
                 storage_cost_breakdown: index_map_new(),
+                cost_profiler: if execution_config.enable_cost_profile {
+                    Some(CostProfiler::new())
+                } else {
+                    None
+                },
                 on_apply_cost: Default::default(),
             },
             auth: AuthModule {
                 params: auth_zone_params.clone(),
+                subintents: vec![],
+                fee_sponsor: None,
             },
             limits: LimitsModule::new(TransactionLimitsConfig {
                 max_heap_substate_total_bytes: execution_config.max_heap_substate_total_bytes,
@@ -163,6 +179,7 @@ impl SystemModuleMixer {
                 max_panic_message_size: execution_config.max_panic_message_size,
             }),
             execution_trace: ExecutionTraceModule::new(execution_config.max_execution_trace_depth),
+            extension: ExtensionModule::new(execution_config.system_module_extensions.clone()),
             transaction_runtime: TransactionRuntimeModule {
                 network_definition,
                 tx_hash,
@@ -180,8 +197,16 @@ impl SystemModuleMixer {
         CostingModule,
         TransactionRuntimeModule,
         ExecutionTraceModule,
+        KernelTraceModule,
+        ExtensionModule,
     ) {
-        (self.costing, self.transaction_runtime, self.execution_trace)
+        (
+            self.costing,
+            self.transaction_runtime,
+            self.execution_trace,
+            self.kernel_trace,
+            self.extension,
+        )
     }
 }
 
@@ -195,6 +220,11 @@ impl InitSystemModule for SystemModuleMixer {
     fn on_init(&mut self) -> Result<(), RuntimeError> {
         let modules: EnabledModules = self.enabled_modules;
 
+        // Enable extensions
+        if modules.contains(EnabledModules::EXTENSION) {
+            self.extension.on_init()?;
+        }
+
         // Enable execution trace
         if modules.contains(EnabledModules::EXECUTION_TRACE) {
             self.execution_trace.on_init()?;
@@ -438,10 +468,28 @@ impl SystemModuleMixer {
         AuthModule::on_call_method_finish(api, auth_zone)
     }
 
+    /// Gives the signer proofs of the child intents to the transaction processor frames
+    /// running them.
+    pub fn with_subintents(mut self, subintents: &[ExecutableSubintent]) -> Self {
+        self.auth.subintents = subintents.iter().map(SubintentAuth::new).collect();
+        self
+    }
+
+    /// Gives the signer proofs of the fee sponsor to the transaction processor frame locking
+    /// its fee.
+    pub fn with_fee_sponsor(mut self, fee_sponsor: Option<&ExecutableFeeSponsor>) -> Self {
+        self.auth.fee_sponsor = fee_sponsor.map(|fee_sponsor| FeeSponsorAuth {
+            params: fee_sponsor.auth_zone_params().clone(),
+            is_started: false,
+        });
+        self
+    }
+
     pub fn on_call_function<V, Y>(
         api: &mut SystemService<Y, V>,
         blueprint_id: &BlueprintId,
         ident: &str,
+        args: &IndexedScryptoValue,
     ) -> Result<NodeId, RuntimeError>
     where
         V: SystemCallbackObject,
@@ -454,7 +502,7 @@ impl SystemModuleMixer {
             .enabled_modules
             .contains(EnabledModules::AUTH)
         {
-            AuthModule::on_call_function(api, blueprint_id, ident)?
+            AuthModule::on_call_function(api, blueprint_id, ident, args)?
         } else {
             AuthModule::on_call_fn_mock(api, None, btreeset!(), btreeset!())?
         };
@@ -530,6 +578,10 @@ impl SystemModuleMixer {
             }
         }
 
+        if self.enabled_modules.contains(EnabledModules::EXTENSION) {
+            self.extension.on_event(&event)?;
+        }
+
         if self
             .enabled_modules
             .contains(EnabledModules::TRANSACTION_RUNTIME)
@@ -35,6 +35,18 @@ pub enum TrackedSubstateInfo {
     Unmodified,
 }
 
+/// Where a scan of the keys of a partition starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanKeysFrom<'a> {
+    /// The start of the partition, with the tracked substates taken before the stored ones.
+    Start,
+    /// The start of the partition, with the tracked and the stored substates merged in the
+    /// database sort key order.
+    SortedStart,
+    /// The key following the given one, in the database sort key order.
+    SortedAfter(&'a SubstateKey),
+}
+
 /// The interface to be used during boot loading
 /// This interface is different from the CommitableSubstateStore in
 /// that these reads should not be tracked / costed since it will
@@ -148,11 +160,14 @@ pub trait CommitableSubstateStore {
     /// this type.
     /// Otherwise, behavior is undefined.
     ///
+    /// The scan starts at the given [`ScanKeysFrom`]; only the sorted scans can be paginated.
+    ///
     /// Returns list of substate keys and database access info
     fn scan_keys<K: SubstateKeyContent + 'static, E, F: FnMut(IOAccess) -> Result<(), E>>(
         &mut self,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         count: u32,
         on_io_access: &mut F,
     ) -> Result<Vec<SubstateKey>, E>;
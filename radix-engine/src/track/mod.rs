@@ -1,5 +1,6 @@
 pub mod interface;
 pub mod legacy_state_updates;
+pub mod overlay_db;
 pub mod state_updates;
 pub mod track;
 pub mod utils;
@@ -9,5 +10,6 @@ mod test;
 
 pub use interface::*;
 pub use legacy_state_updates::*;
+pub use overlay_db::*;
 pub use state_updates::*;
 pub use track::*;
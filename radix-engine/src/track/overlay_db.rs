@@ -0,0 +1,109 @@
+use crate::track::utils::OverlayingIterator;
+use crate::types::*;
+use radix_engine_store_interface::interface::*;
+
+/// A [`SubstateDatabase`] which layers uncommitted changes over a read-only root database.
+///
+/// Changes are captured via [`CommittableSubstateDatabase::commit()`], and are only visible to the
+/// readers of this overlay - the root database is never written to. This allows executing
+/// transactions against arbitrary starting conditions (e.g. in a preview).
+pub struct SubstateDatabaseOverlay<'a, S: SubstateDatabase> {
+    root: &'a S,
+    overlay: IndexMap<DbPartitionKey, PartitionOverlay>,
+}
+
+/// The changes captured for a single partition.
+#[derive(Default)]
+struct PartitionOverlay {
+    /// Whether the partition was reset, i.e. none of the root's substates are visible anymore.
+    is_reset: bool,
+    /// Upserted ([`Some`]) and deleted ([`None`]) substates.
+    substates: BTreeMap<DbSortKey, Option<DbSubstateValue>>,
+}
+
+impl<'a, S: SubstateDatabase> SubstateDatabaseOverlay<'a, S> {
+    pub fn new(root: &'a S) -> Self {
+        Self {
+            root,
+            overlay: index_map_new(),
+        }
+    }
+
+    pub fn root(&self) -> &S {
+        self.root
+    }
+}
+
+impl<'a, S: SubstateDatabase> SubstateDatabase for SubstateDatabaseOverlay<'a, S> {
+    fn get_substate(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<DbSubstateValue> {
+        let Some(partition_overlay) = self.overlay.get(partition_key) else {
+            return self.root.get_substate(partition_key, sort_key);
+        };
+        match partition_overlay.substates.get(sort_key) {
+            Some(change) => change.clone(),
+            None if partition_overlay.is_reset => None,
+            None => self.root.get_substate(partition_key, sort_key),
+        }
+    }
+
+    fn list_entries_from(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        let Some(partition_overlay) = self.overlay.get(partition_key) else {
+            return self.root.list_entries_from(partition_key, from_sort_key);
+        };
+        let changes = match from_sort_key {
+            Some(from_sort_key) => partition_overlay.substates.range(from_sort_key.clone()..),
+            None => partition_overlay.substates.range(..),
+        }
+        .map(|(sort_key, change)| (sort_key.clone(), change.clone()));
+        if partition_overlay.is_reset {
+            Box::new(changes.filter_map(|(sort_key, change)| change.map(|value| (sort_key, value))))
+        } else {
+            Box::new(OverlayingIterator::new(
+                self.root.list_entries_from(partition_key, from_sort_key),
+                changes,
+            ))
+        }
+    }
+}
+
+impl<'a, S: SubstateDatabase> CommittableSubstateDatabase for SubstateDatabaseOverlay<'a, S> {
+    fn commit(&mut self, database_updates: &DatabaseUpdates) {
+        for (node_key, node_updates) in &database_updates.node_updates {
+            for (partition_num, partition_updates) in &node_updates.partition_updates {
+                let partition_key = DbPartitionKey {
+                    node_key: node_key.clone(),
+                    partition_num: *partition_num,
+                };
+                let partition_overlay = self.overlay.entry(partition_key).or_default();
+                match partition_updates {
+                    PartitionDatabaseUpdates::Delta { substate_updates } => {
+                        for (sort_key, update) in substate_updates {
+                            let change = match update {
+                                DatabaseUpdate::Set(value) => Some(value.clone()),
+                                DatabaseUpdate::Delete => None,
+                            };
+                            partition_overlay.substates.insert(sort_key.clone(), change);
+                        }
+                    }
+                    PartitionDatabaseUpdates::Reset {
+                        new_substate_values,
+                    } => {
+                        partition_overlay.is_reset = true;
+                        partition_overlay.substates = new_substate_values
+                            .iter()
+                            .map(|(sort_key, value)| (sort_key.clone(), Some(value.clone())))
+                            .collect();
+                    }
+                }
+            }
+        }
+    }
+}
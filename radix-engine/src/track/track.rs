@@ -1,6 +1,6 @@
 use crate::kernel::call_frame::TransientSubstates;
 use crate::track::interface::{
-    CommitableSubstateStore, IOAccess, NodeSubstates, TrackedSubstateInfo,
+    CommitableSubstateStore, IOAccess, NodeSubstates, ScanKeysFrom, TrackedSubstateInfo,
 };
 use crate::track::state_updates::*;
 use crate::track::utils::OverlayingResultIterator;
@@ -16,6 +16,7 @@ use radix_engine_store_interface::{
 use sbor::rust::collections::btree_map::Entry;
 use sbor::rust::iter::empty;
 use sbor::rust::mem;
+use sbor::rust::ops::Bound;
 
 use super::interface::{CanonicalPartition, CanonicalSubstateKey, StoreCommit, StoreCommitInfo};
 
@@ -79,6 +80,7 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> Track<'s, S, M> {
     >(
         substate_db: &'x S,
         partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
         on_io_access: &'x mut F,
         canonical_partition: CanonicalPartition,
     ) -> Box<dyn Iterator<Item = Result<(DbSortKey, (SubstateKey, IndexedScryptoValue)), E>> + 'x>
@@ -137,7 +139,7 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> Track<'s, S, M> {
         }
 
         Box::new(TracedIterator {
-            iterator: substate_db.list_entries(partition_key),
+            iterator: substate_db.list_entries_from(partition_key, from_sort_key),
             on_io_access,
             canonical_partition,
             errored_out: false,
@@ -212,6 +214,93 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> Track<'s, S, M> {
         Ok((self.tracked_nodes, self.deleted_partitions))
     }
 
+    /// Scans the keys following the given `after_key` (if any), in the [`DbSortKey`] order (in
+    /// which the tracked and the database entries get merged).
+    /// Unlike [`ScanKeysFrom::Start`] (which takes the tracked substates first), this returns the
+    /// same keys across the pages of a paginated scan, regardless of the order of the writes.
+    fn scan_sorted_keys<K: SubstateKeyContent + 'static, E, F: FnMut(IOAccess) -> Result<(), E>>(
+        &mut self,
+        node_id: &NodeId,
+        partition_number: PartitionNumber,
+        after_key: Option<&SubstateKey>,
+        limit: u32,
+        on_io_access: &mut F,
+    ) -> Result<Vec<SubstateKey>, E> {
+        let limit: usize = limit.try_into().unwrap();
+        let after_db_sort_key = after_key.map(|after_key| M::to_db_sort_key(after_key));
+
+        let tracked_node = self
+            .tracked_nodes
+            .entry(node_id.clone())
+            .or_insert(TrackedNode::new(false));
+        let tracked_partition = tracked_node
+            .tracked_partitions
+            .entry(partition_number)
+            .or_insert(TrackedPartition::new());
+
+        // Optimization, no need to go into database if the node is just created
+        let mut db_values_count = 0u32;
+        let raw_db_entries: Box<
+            dyn Iterator<Item = Result<(DbSortKey, (SubstateKey, IndexedScryptoValue)), E>>,
+        > = if tracked_node.is_new {
+            Box::new(empty())
+        } else {
+            let partition_key = M::to_db_partition_key(node_id, partition_number);
+            Box::new(Self::list_entries_from_db::<E, F, K>(
+                self.substate_db,
+                &partition_key,
+                after_db_sort_key.as_ref(),
+                on_io_access,
+                CanonicalPartition {
+                    node_id: *node_id,
+                    partition_number,
+                },
+            ))
+        };
+        // The database lists entries starting from (and including) the given sort key
+        let db_read_entries = raw_db_entries
+            .inspect(|_| {
+                db_values_count += 1;
+            })
+            .filter(|result| match result {
+                Ok((db_sort_key, _)) => Some(db_sort_key) != after_db_sort_key.as_ref(),
+                Err(_) => true,
+            });
+        let lower_bound = match &after_db_sort_key {
+            Some(after_db_sort_key) => Bound::Excluded(after_db_sort_key),
+            None => Bound::Unbounded,
+        };
+
+        // TODO: Check that substate is not write locked, before use outside of native blueprints
+        let tracked_entry_changes = tracked_partition
+            .substates
+            .range::<DbSortKey, _>((lower_bound, Bound::Unbounded))
+            .map(|(db_sort_key, tracked_substate)| {
+                if let Some(value) = tracked_substate.substate_value.get() {
+                    (
+                        db_sort_key.clone(),
+                        Some((tracked_substate.substate_key.clone(), value.clone())),
+                    )
+                } else {
+                    (db_sort_key.clone(), None)
+                }
+            });
+
+        let mut items = Vec::new();
+        for result in
+            OverlayingResultIterator::new(db_read_entries, tracked_entry_changes).take(limit)
+        {
+            let (_db_sort_key, (substate_key, _substate_value)) = result?;
+            items.push(substate_key);
+        }
+
+        // TODO: cache read substates in Track (and notify upper layer)
+
+        tracked_partition.range_read = u32::max(tracked_partition.range_read, db_values_count);
+
+        Ok(items)
+    }
+
     fn get_tracked_partition(
         &mut self,
         node_id: &NodeId,
@@ -570,9 +659,32 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
         &mut self,
         node_id: &NodeId,
         partition_number: PartitionNumber,
+        from: ScanKeysFrom,
         limit: u32,
         on_io_access: &mut F,
     ) -> Result<Vec<SubstateKey>, E> {
+        match from {
+            ScanKeysFrom::Start => {}
+            ScanKeysFrom::SortedStart => {
+                return self.scan_sorted_keys::<K, E, F>(
+                    node_id,
+                    partition_number,
+                    None,
+                    limit,
+                    on_io_access,
+                );
+            }
+            ScanKeysFrom::SortedAfter(after_key) => {
+                return self.scan_sorted_keys::<K, E, F>(
+                    node_id,
+                    partition_number,
+                    Some(after_key),
+                    limit,
+                    on_io_access,
+                );
+            }
+        }
+
         let limit: usize = limit.try_into().unwrap();
         let mut items = Vec::new();
 
@@ -605,6 +717,7 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
         let mut tracked_iter = IterationCountedIter::new(Self::list_entries_from_db::<E, F, K>(
             self.substate_db,
             &db_partition_key,
+            None,
             on_io_access,
             CanonicalPartition {
                 node_id: *node_id,
@@ -696,6 +809,7 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
                 IterationCountedIter::new(Self::list_entries_from_db::<E, F, K>(
                     self.substate_db,
                     &db_partition_key,
+                    None,
                     on_io_access,
                     CanonicalPartition {
                         node_id: *node_id,
@@ -797,6 +911,7 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
             Box::new(Self::list_entries_from_db::<E, F, SortedKey>(
                 self.substate_db,
                 &partition_key,
+                None,
                 on_io_access,
                 CanonicalPartition {
                     node_id: *node_id,
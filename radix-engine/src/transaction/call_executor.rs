@@ -0,0 +1,188 @@
+use crate::errors::{RejectionReason, RuntimeError};
+use crate::system::system_callback_api::SystemCallbackObject;
+use crate::transaction::*;
+use crate::types::*;
+use radix_engine_interface::blueprints::transaction_processor::InstructionOutput;
+use radix_engine_store_interface::interface::*;
+use transaction::builder::ResolvableArguments;
+use transaction::model::*;
+
+/// The method or function invoked by a [`SimulatedCall`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulatedCallTarget {
+    Method {
+        address: GlobalAddress,
+        method_name: String,
+    },
+    Function {
+        package_address: PackageAddress,
+        blueprint_name: String,
+        function_name: String,
+    },
+}
+
+/// A single read-only invocation, executed without a manifest or a fee payer.
+///
+/// The call runs in a transaction of its own, with preview credit paying for its fees and with
+/// the given [`AuthZoneParams`] as its auth context. None of its writes are ever committed.
+/// Note that the call must not return buckets, as nothing would deposit them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedCall {
+    pub target: SimulatedCallTarget,
+    pub args: ManifestValue,
+    pub auth_zone_params: AuthZoneParams,
+}
+
+impl SimulatedCall {
+    pub fn method(
+        address: impl Into<GlobalAddress>,
+        method_name: impl Into<String>,
+        args: impl ResolvableArguments,
+    ) -> Self {
+        Self {
+            target: SimulatedCallTarget::Method {
+                address: address.into(),
+                method_name: method_name.into(),
+            },
+            args: args.resolve(),
+            auth_zone_params: AuthZoneParams::default(),
+        }
+    }
+
+    pub fn function(
+        package_address: PackageAddress,
+        blueprint_name: impl Into<String>,
+        function_name: impl Into<String>,
+        args: impl ResolvableArguments,
+    ) -> Self {
+        Self {
+            target: SimulatedCallTarget::Function {
+                package_address,
+                blueprint_name: blueprint_name.into(),
+                function_name: function_name.into(),
+            },
+            args: args.resolve(),
+            auth_zone_params: AuthZoneParams::default(),
+        }
+    }
+
+    pub fn with_auth_zone_params(mut self, auth_zone_params: AuthZoneParams) -> Self {
+        self.auth_zone_params = auth_zone_params;
+        self
+    }
+
+    fn to_instruction(&self) -> InstructionV1 {
+        match &self.target {
+            SimulatedCallTarget::Method {
+                address,
+                method_name,
+            } => InstructionV1::CallMethod {
+                address: (*address).into(),
+                method_name: method_name.clone(),
+                args: self.args.clone(),
+            },
+            SimulatedCallTarget::Function {
+                package_address,
+                blueprint_name,
+                function_name,
+            } => InstructionV1::CallFunction {
+                package_address: (*package_address).into(),
+                blueprint_name: blueprint_name.clone(),
+                function_name: function_name.clone(),
+                args: self.args.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulatedCallError {
+    PrepareError(PrepareError),
+    Rejected(RejectionReason),
+    Aborted(AbortReason),
+    Failed(RuntimeError),
+    /// The call instruction did not output a return value.
+    UnexpectedOutput(Option<InstructionOutput>),
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulatedCallResult {
+    /// The scrypto-encoded return value of the call.
+    pub output: Vec<u8>,
+    /// The events emitted by the call, including those of the fee payment.
+    pub application_events: Vec<(EventTypeIdentifier, Vec<u8>)>,
+    pub fee_summary: TransactionFeeSummary,
+}
+
+impl SimulatedCallResult {
+    pub fn output<T: ScryptoDecode>(&self) -> Result<T, DecodeError> {
+        scrypto_decode(&self.output)
+    }
+}
+
+/// Executes a [`SimulatedCall`] against the given database, discarding all of its writes.
+///
+/// The `execution_config` would usually be [`ExecutionConfig::for_preview`], adjusted as needed
+/// (e.g. with a kernel trace).
+pub fn execute_simulated_call<S: SubstateDatabase, V: SystemCallbackObject + Clone>(
+    substate_db: &S,
+    vm: V,
+    execution_config: &ExecutionConfig,
+    call: &SimulatedCall,
+) -> Result<SimulatedCallResult, SimulatedCallError> {
+    let instructions = InstructionsV1(vec![call.to_instruction()])
+        .prepare_partial()
+        .map_err(SimulatedCallError::PrepareError)?;
+    let encoded_instructions = manifest_encode(&instructions.inner.0)
+        .map_err(|e| SimulatedCallError::PrepareError(PrepareError::EncodeError(e)))?;
+    let blobs = index_map_new();
+
+    let executable = Executable::new(
+        &encoded_instructions,
+        &instructions.references,
+        &blobs,
+        ExecutionContext {
+            intent_hash: TransactionIntentHash::NotToCheck {
+                intent_hash: hash(&encoded_instructions),
+            },
+            epoch_range: None,
+            pre_allocated_addresses: vec![],
+            payload_size: encoded_instructions.len(),
+            num_of_signature_validations: 0,
+            auth_zone_params: call.auth_zone_params.clone(),
+            costing_parameters: TransactionCostingParameters {
+                tip_percentage: 0,
+                free_credit_in_xrd: Decimal::try_from(PREVIEW_CREDIT_IN_XRD).unwrap(),
+            },
+        },
+    );
+
+    let receipt = execute_transaction(
+        substate_db,
+        vm,
+        &CostingParameters::default(),
+        execution_config,
+        &executable,
+    );
+
+    let commit = match receipt.result {
+        TransactionResult::Commit(commit) => commit,
+        TransactionResult::Reject(reject) => {
+            return Err(SimulatedCallError::Rejected(reject.reason))
+        }
+        TransactionResult::Abort(abort) => return Err(SimulatedCallError::Aborted(abort.reason)),
+    };
+    let output = match commit.outcome {
+        TransactionOutcome::Success(mut outputs) => match outputs.pop() {
+            Some(InstructionOutput::CallReturn(output)) => output,
+            output => return Err(SimulatedCallError::UnexpectedOutput(output)),
+        },
+        TransactionOutcome::Failure(error) => return Err(SimulatedCallError::Failed(error)),
+    };
+
+    Ok(SimulatedCallResult {
+        output,
+        application_events: commit.application_events,
+        fee_summary: receipt.fee_summary,
+    })
+}
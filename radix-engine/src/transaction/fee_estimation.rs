@@ -0,0 +1,241 @@
+use crate::blueprints::consensus_manager::{
+    ConsensusManagerField, ConsensusManagerStateFieldPayload,
+};
+use crate::errors::{RejectionReason, RuntimeError};
+use crate::system::system_callback_api::SystemCallbackObject;
+use crate::system::system_db_reader::{SystemDatabaseReader, SystemReaderError};
+use crate::transaction::*;
+use crate::types::*;
+use radix_engine_interface::api::ModuleId;
+use radix_engine_interface::blueprints::account::*;
+use radix_engine_interface::network::NetworkDefinition;
+use radix_engine_store_interface::interface::*;
+use transaction::builder::ManifestBuilder;
+use transaction::model::{
+    BlobV1, BlobsV1, DynamicGlobalAddress, InstructionV1, InstructionsV1, IntentV1, MessageV1,
+    PreviewFlags, PreviewIntentV1, TransactionHeaderV1, TransactionManifestV1,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeeEstimationError {
+    /// The manifest already contains an instruction locking a fee.
+    ManifestAlreadyLocksFee,
+    /// The safety margin is negative.
+    InvalidSafetyMargin(Decimal),
+    /// The estimated fee with the safety margin applied overflows.
+    FeeToLockOverflow,
+    SystemReaderError(SystemReaderError),
+    PreviewError(PreviewError),
+    TransactionRejected(RejectionReason),
+    TransactionFailed(RuntimeError),
+    TransactionAborted(AbortReason),
+}
+
+/// The parameters of a [`estimate_fee()`] run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeEstimationParams {
+    /// The account to lock the fee on.
+    pub fee_payer: ComponentAddress,
+    /// The keys which will sign the transaction.
+    pub signer_public_keys: Vec<PublicKey>,
+    /// Whether the preview should assume that all signature proofs are present (e.g. when the
+    /// signers are not known yet).
+    pub assume_all_signature_proofs: bool,
+    pub tip_percentage: u16,
+    /// The fraction of the previewed fee to lock on top of it, to cover the differences between
+    /// the preview and the actual execution (e.g. `dec!("0.1")` locks 10% more). Must not be negative.
+    pub safety_margin: Decimal,
+}
+
+impl FeeEstimationParams {
+    pub fn new(fee_payer: ComponentAddress) -> Self {
+        Self {
+            fee_payer,
+            signer_public_keys: Vec::new(),
+            assume_all_signature_proofs: false,
+            tip_percentage: 0,
+            safety_margin: dec!("0.2"),
+        }
+    }
+
+    pub fn with_signer_public_keys(mut self, signer_public_keys: Vec<PublicKey>) -> Self {
+        self.signer_public_keys = signer_public_keys;
+        self
+    }
+
+    pub fn with_assume_all_signature_proofs(mut self, assume_all_signature_proofs: bool) -> Self {
+        self.assume_all_signature_proofs = assume_all_signature_proofs;
+        self
+    }
+
+    pub fn with_tip_percentage(mut self, tip_percentage: u16) -> Self {
+        self.tip_percentage = tip_percentage;
+        self
+    }
+
+    pub fn with_safety_margin(mut self, safety_margin: Decimal) -> Self {
+        self.safety_margin = safety_margin;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeEstimate {
+    /// The fee summary of the previewed transaction.
+    pub fee_summary: TransactionFeeSummary,
+    /// The total fee of the previewed transaction, see [`TransactionFeeSummary::total_cost()`].
+    pub estimated_fee: Decimal,
+    /// The estimated fee with the safety margin applied.
+    pub fee_to_lock: Decimal,
+}
+
+/// Previews the given manifest (which must not lock a fee itself) with a `lock_fee` call on the
+/// [`FeeEstimationParams.fee_payer`] prepended, and estimates the fee to lock from the result.
+///
+/// The `lock_fee` call is previewed with a zero amount (the fee is paid from the free credit), so
+/// that its own cost is a part of the estimate.
+pub fn estimate_fee<S: SubstateDatabase, V: SystemCallbackObject + Clone>(
+    substate_db: &S,
+    vm: V,
+    network: &NetworkDefinition,
+    manifest: &TransactionManifestV1,
+    params: &FeeEstimationParams,
+) -> Result<FeeEstimate, FeeEstimationError> {
+    if manifest.instructions.iter().any(is_fee_locking_instruction) {
+        return Err(FeeEstimationError::ManifestAlreadyLocksFee);
+    }
+    if params.safety_margin.is_negative() {
+        return Err(FeeEstimationError::InvalidSafetyMargin(
+            params.safety_margin,
+        ));
+    }
+
+    let epoch = SystemDatabaseReader::new(substate_db)
+        .read_typed_object_field::<ConsensusManagerStateFieldPayload>(
+            CONSENSUS_MANAGER.as_node_id(),
+            ModuleId::Main,
+            ConsensusManagerField::State.field_index(),
+        )
+        .map_err(FeeEstimationError::SystemReaderError)?
+        .into_latest()
+        .epoch;
+
+    let preview_manifest = prepend_lock_fee(manifest.clone(), params.fee_payer, Decimal::ZERO);
+    let preview_intent = PreviewIntentV1 {
+        intent: IntentV1 {
+            header: TransactionHeaderV1 {
+                network_id: network.id,
+                start_epoch_inclusive: epoch,
+                end_epoch_exclusive: epoch.next().unwrap_or(epoch),
+                nonce: 0,
+                notary_public_key: PublicKey::Secp256k1(Secp256k1PublicKey([0u8; 33])),
+                notary_is_signatory: false,
+                tip_percentage: params.tip_percentage,
+            },
+            instructions: InstructionsV1(preview_manifest.instructions),
+            blobs: BlobsV1 {
+                blobs: preview_manifest.blobs.into_values().map(BlobV1).collect(),
+            },
+            message: MessageV1::default(),
+        },
+        signer_public_keys: params.signer_public_keys.clone(),
+        flags: PreviewFlags {
+            use_free_credit: true,
+            assume_all_signature_proofs: params.assume_all_signature_proofs,
+            skip_epoch_check: true,
+        },
+    };
+
+    let receipt = execute_preview(substate_db, vm, network, preview_intent, false)
+        .map_err(FeeEstimationError::PreviewError)?;
+    match receipt.result {
+        TransactionResult::Commit(CommitResult {
+            outcome: TransactionOutcome::Success(_),
+            ..
+        }) => {}
+        TransactionResult::Commit(CommitResult {
+            outcome: TransactionOutcome::Failure(error),
+            ..
+        }) => return Err(FeeEstimationError::TransactionFailed(error)),
+        TransactionResult::Reject(RejectResult { reason }) => {
+            return Err(FeeEstimationError::TransactionRejected(reason))
+        }
+        TransactionResult::Abort(AbortResult { reason }) => {
+            return Err(FeeEstimationError::TransactionAborted(reason))
+        }
+    }
+
+    let estimated_fee = receipt.fee_summary.total_cost();
+    let fee_to_lock = Decimal::ONE
+        .checked_add(params.safety_margin)
+        .and_then(|multiplier| estimated_fee.checked_mul(multiplier))
+        .ok_or(FeeEstimationError::FeeToLockOverflow)?;
+
+    Ok(FeeEstimate {
+        fee_summary: receipt.fee_summary,
+        estimated_fee,
+        fee_to_lock,
+    })
+}
+
+/// Estimates the fee of the given manifest (see [`estimate_fee()`]) and returns the manifest
+/// with the [`FeeEstimate.fee_to_lock`] locked on the [`FeeEstimationParams.fee_payer`].
+pub fn lock_estimated_fee<S: SubstateDatabase, V: SystemCallbackObject + Clone>(
+    substate_db: &S,
+    vm: V,
+    network: &NetworkDefinition,
+    manifest: TransactionManifestV1,
+    params: &FeeEstimationParams,
+) -> Result<(TransactionManifestV1, FeeEstimate), FeeEstimationError> {
+    let estimate = estimate_fee(substate_db, vm, network, &manifest, params)?;
+    let manifest = prepend_lock_fee(manifest, params.fee_payer, estimate.fee_to_lock);
+    Ok((manifest, estimate))
+}
+
+fn prepend_lock_fee(
+    manifest: TransactionManifestV1,
+    fee_payer: ComponentAddress,
+    amount: Decimal,
+) -> TransactionManifestV1 {
+    // Locking a fee does not create any buckets or proofs, so the ones referenced by the original
+    // instructions keep their ids
+    let mut builder = ManifestBuilder::new().lock_fee(fee_payer, amount);
+    for blob in manifest.blobs.into_values() {
+        builder.add_blob(blob);
+    }
+    for instruction in manifest.instructions {
+        builder = builder.add_instruction_advanced(instruction).0;
+    }
+    builder.build()
+}
+
+/// Whether the instruction locks a fee on an account or the faucet.
+fn is_fee_locking_instruction(instruction: &InstructionV1) -> bool {
+    match instruction {
+        InstructionV1::CallMethod {
+            address: DynamicGlobalAddress::Static(address),
+            method_name,
+            ..
+        } => {
+            if address.as_node_id() == FAUCET.as_node_id() {
+                // The builder locks the faucet fees with the account method name
+                return method_name == ACCOUNT_LOCK_FEE_IDENT;
+            }
+            match address.as_node_id().entity_type() {
+                Some(
+                    EntityType::GlobalAccount
+                    | EntityType::GlobalVirtualSecp256k1Account
+                    | EntityType::GlobalVirtualEd25519Account,
+                ) => matches!(
+                    method_name.as_str(),
+                    ACCOUNT_LOCK_FEE_IDENT
+                        | ACCOUNT_LOCK_CONTINGENT_FEE_IDENT
+                        | ACCOUNT_LOCK_FEE_AND_WITHDRAW_IDENT
+                        | ACCOUNT_LOCK_FEE_AND_WITHDRAW_NON_FUNGIBLES_IDENT
+                ),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
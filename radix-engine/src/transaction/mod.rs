@@ -1,3 +1,7 @@
+mod call_executor;
+mod fee_estimation;
+#[cfg(feature = "std")]
+mod parallel_executor;
 mod preview_executor;
 mod state_update_summary;
 mod system_structure;
@@ -5,6 +9,10 @@ mod transaction_executor;
 mod transaction_receipt;
 mod transaction_reconciler;
 
+pub use call_executor::*;
+pub use fee_estimation::*;
+#[cfg(feature = "std")]
+pub use parallel_executor::*;
 pub use preview_executor::*;
 pub use state_update_summary::*;
 pub use system_structure::*;
@@ -0,0 +1,644 @@
+use crate::blueprints::consensus_manager::{
+    ConsensusManagerField, ConsensusManagerValidatorRewardsFieldPayload,
+};
+use crate::blueprints::models::FieldPayload;
+use crate::blueprints::resource::{
+    FungibleVaultBalanceFieldPayload, FungibleVaultBalanceFieldSubstate, FungibleVaultField,
+};
+use crate::kernel::kernel_api::KernelInvocation;
+use crate::system::actor::{Actor, MethodType};
+use crate::system::system_callback_api::SystemCallbackObject;
+use crate::system::system_modules::extension::SystemModuleExtension;
+use crate::system::system_substates::FieldSubstate;
+use crate::track::{NodeStateUpdates, PartitionStateUpdates, SubstateDatabaseOverlay};
+use crate::transaction::*;
+use crate::types::*;
+use radix_engine_interface::blueprints::resource::{
+    FungibleVaultLockFeeInput, LiquidFungibleResource, FUNGIBLE_VAULT_LOCK_FEE_IDENT,
+};
+use radix_engine_store_interface::db_key_mapper::{DatabaseKeyMapper, SpreadPrefixKeyMapper};
+use radix_engine_store_interface::interface::*;
+use sbor::rust::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use transaction::model::*;
+
+/// The outcome of executing a batch of transactions in parallel.
+#[derive(Debug, Clone)]
+pub struct ParallelExecutionResult {
+    /// The receipts, in the order of the executed transactions. These are identical to the ones
+    /// of a sequential execution, in which each transaction sees the state updates of all the
+    /// transactions before it.
+    pub receipts: Vec<TransactionReceipt>,
+    /// The indices of the transactions which read state written by an earlier transaction of
+    /// the batch, and thus had to be re-executed.
+    pub re_executed: Vec<usize>,
+}
+
+/// Executes the given transactions, in order, on top of the given database, utilizing all
+/// available cores.
+///
+/// All transactions are first executed speculatively in parallel, against the base state. Then,
+/// in order, the substates read by each transaction are checked against the substates written
+/// by the transactions before it: any transaction which could have observed a different state is
+/// re-executed on top of the preceding state updates.
+///
+/// The substates which every transaction updates to account for its fee, i.e. the validator
+/// rewards and the vaults it only locked fees from, do not conflict: their updates are rebased on
+/// top of the preceding state updates instead, see [`FeeSubstate`].
+///
+/// The [`ExecutionConfig::system_module_extensions`] are never run by the speculative
+/// executions, so that they observe each transaction once, against the state a sequential
+/// execution would see. Hence, if any extension is registered, the transactions are executed
+/// sequentially.
+pub fn execute_transactions_in_parallel<S, V>(
+    substate_db: &S,
+    vm: V,
+    costing_parameters: &CostingParameters,
+    execution_config: &ExecutionConfig,
+    transactions: &[Executable],
+) -> ParallelExecutionResult
+where
+    S: SubstateDatabase + Sync,
+    V: SystemCallbackObject + Clone + Sync,
+{
+    if !execution_config.system_module_extensions.is_empty() {
+        return execute_transactions_sequentially(
+            substate_db,
+            vm,
+            costing_parameters,
+            execution_config,
+            transactions,
+        );
+    }
+
+    let speculative_results = execute_speculatively(
+        substate_db,
+        &vm,
+        costing_parameters,
+        execution_config,
+        transactions,
+    );
+
+    let mut overlay = SubstateDatabaseOverlay::new(substate_db);
+    let mut write_set = WriteSet::default();
+    let mut receipts = Vec::with_capacity(transactions.len());
+    let mut re_executed = Vec::new();
+    for (index, (transaction, speculative_execution)) in
+        transactions.iter().zip(speculative_results).enumerate()
+    {
+        let receipt = match write_set.conflicting_substates(&speculative_execution.read_set) {
+            Some(conflicts) => rebase(substate_db, &overlay, speculative_execution, &conflicts),
+            None => None,
+        };
+        let receipt = match receipt {
+            Some(receipt) => receipt,
+            None => {
+                re_executed.push(index);
+                execute_transaction(
+                    &overlay,
+                    vm.clone(),
+                    costing_parameters,
+                    execution_config,
+                    transaction,
+                )
+            }
+        };
+
+        if let TransactionResult::Commit(commit) = &receipt.result {
+            let database_updates = commit
+                .state_updates
+                .create_database_updates::<SpreadPrefixKeyMapper>();
+            write_set.record(&database_updates);
+            overlay.commit(&database_updates);
+        }
+        receipts.push(receipt);
+    }
+
+    ParallelExecutionResult {
+        receipts,
+        re_executed,
+    }
+}
+
+fn execute_transactions_sequentially<S, V>(
+    substate_db: &S,
+    vm: V,
+    costing_parameters: &CostingParameters,
+    execution_config: &ExecutionConfig,
+    transactions: &[Executable],
+) -> ParallelExecutionResult
+where
+    S: SubstateDatabase,
+    V: SystemCallbackObject + Clone,
+{
+    let mut overlay = SubstateDatabaseOverlay::new(substate_db);
+    let mut receipts = Vec::with_capacity(transactions.len());
+    for transaction in transactions {
+        let receipt = execute_transaction(
+            &overlay,
+            vm.clone(),
+            costing_parameters,
+            execution_config,
+            transaction,
+        );
+        if let TransactionResult::Commit(commit) = &receipt.result {
+            overlay.commit(
+                &commit
+                    .state_updates
+                    .create_database_updates::<SpreadPrefixKeyMapper>(),
+            );
+        }
+        receipts.push(receipt);
+    }
+
+    ParallelExecutionResult {
+        receipts,
+        re_executed: Vec::new(),
+    }
+}
+
+/// Executes the given transactions in parallel (see [`execute_transactions_in_parallel`]) and
+/// commits the state updates of the committed ones, in order.
+pub fn execute_and_commit_transactions_in_parallel<S, V>(
+    substate_db: &mut S,
+    vm: V,
+    costing_parameters: &CostingParameters,
+    execution_config: &ExecutionConfig,
+    transactions: &[Executable],
+) -> ParallelExecutionResult
+where
+    S: SubstateDatabase + CommittableSubstateDatabase + Sync,
+    V: SystemCallbackObject + Clone + Sync,
+{
+    let result = execute_transactions_in_parallel(
+        substate_db,
+        vm,
+        costing_parameters,
+        execution_config,
+        transactions,
+    );
+    for receipt in &result.receipts {
+        if let TransactionResult::Commit(commit) = &receipt.result {
+            substate_db.commit(
+                &commit
+                    .state_updates
+                    .create_database_updates::<SpreadPrefixKeyMapper>(),
+            );
+        }
+    }
+    result
+}
+
+/// A transaction executed against the base state, with what it read and invoked.
+struct SpeculativeExecution {
+    receipt: TransactionReceipt,
+    read_set: ReadSet,
+    invocations: InvocationRecorder,
+}
+
+fn execute_speculatively<S, V>(
+    substate_db: &S,
+    vm: &V,
+    costing_parameters: &CostingParameters,
+    execution_config: &ExecutionConfig,
+    transactions: &[Executable],
+) -> Vec<SpeculativeExecution>
+where
+    S: SubstateDatabase + Sync,
+    V: SystemCallbackObject + Clone + Sync,
+{
+    // Only the invocation recorder observes the speculative executions
+    let mut speculative_config = execution_config.clone();
+    speculative_config.system_module_extensions.clear();
+
+    let results: Vec<Mutex<Option<SpeculativeExecution>>> =
+        transactions.iter().map(|_| Mutex::new(None)).collect();
+    let next_index = AtomicUsize::new(0);
+    let num_threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(transactions.len());
+
+    std::thread::scope(|scope| {
+        for _ in 0..num_threads {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let transaction = match transactions.get(index) {
+                    Some(transaction) => transaction,
+                    None => break,
+                };
+                let recording_db = ReadRecordingSubstateDatabase::new(substate_db);
+                let invocations = Arc::new(Mutex::new(InvocationRecorder::default()));
+                let receipt = execute_transaction(
+                    &recording_db,
+                    vm.clone(),
+                    costing_parameters,
+                    &speculative_config
+                        .clone()
+                        .with_system_module_extension(invocations.clone(), false),
+                    transaction,
+                );
+                let invocations = core::mem::take(&mut *invocations.lock().unwrap());
+                *results[index].lock().unwrap() = Some(SpeculativeExecution {
+                    receipt,
+                    read_set: recording_db.into_read_set(),
+                    invocations,
+                });
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap().unwrap())
+        .collect()
+}
+
+/// Returns the receipt of a speculatively executed transaction as if it was executed on top of
+/// the current state, or `None` if it must be re-executed.
+///
+/// The conflicting substates whose value did not change since the speculative execution read
+/// them are ignored; the others must be [`FeeSubstate`]s, whose updates are rebased.
+fn rebase<S: SubstateDatabase, C: SubstateDatabase>(
+    base_db: &S,
+    current_db: &C,
+    speculative_execution: SpeculativeExecution,
+    conflicts: &IndexSet<(DbPartitionKey, DbSortKey)>,
+) -> Option<TransactionReceipt> {
+    let SpeculativeExecution {
+        mut receipt,
+        invocations,
+        ..
+    } = speculative_execution;
+
+    let mut fee_substates = None;
+    for (partition_key, sort_key) in conflicts {
+        let base = base_db.get_substate(partition_key, sort_key);
+        let current = current_db.get_substate(partition_key, sort_key);
+        if base == current {
+            continue;
+        }
+
+        let TransactionResult::Commit(commit) = &mut receipt.result else {
+            return None;
+        };
+        let (node_id, substate_key, fee_substate) = fee_substates
+            .get_or_insert_with(|| FeeSubstate::of(commit, &invocations))
+            .get(&(partition_key.clone(), sort_key.clone()))?;
+        let NodeStateUpdates::Delta { by_partition } =
+            commit.state_updates.by_node.get_mut(node_id)?;
+        let PartitionStateUpdates::Delta { by_substate } =
+            by_partition.get_mut(&MAIN_BASE_PARTITION)?
+        else {
+            return None;
+        };
+        let DatabaseUpdate::Set(written) = by_substate.get_mut(substate_key)? else {
+            return None;
+        };
+        let rebased = fee_substate.rebase(&base?, written, &current?)?;
+        *written = rebased;
+    }
+
+    Some(receipt)
+}
+
+/// A substate which a transaction only updated to account for its fee, by adding to or taking
+/// from its value, so that the update can be applied on top of a later state.
+#[derive(Debug, Clone, Copy)]
+enum FeeSubstate {
+    /// The validator rewards of the consensus manager, which the fee is added to
+    ValidatorRewards,
+    /// The balance of the validator rewards vault, which the fee is deposited into
+    RewardsVaultBalance,
+    /// The balance of a vault which the given amount of fees was only locked from
+    FeePayingVaultBalance { locked: Decimal },
+}
+
+impl FeeSubstate {
+    /// Returns the fee substates written by the given transaction, keyed by their database keys.
+    fn of(
+        commit: &CommitResult,
+        invocations: &InvocationRecorder,
+    ) -> IndexMap<(DbPartitionKey, DbSortKey), (NodeId, SubstateKey, FeeSubstate)> {
+        let mut fee_substates = index_map_new();
+        let mut insert = |node_id: NodeId, substate_key: SubstateKey, fee_substate| {
+            let partition_key =
+                SpreadPrefixKeyMapper::to_db_partition_key(&node_id, MAIN_BASE_PARTITION);
+            let sort_key = SpreadPrefixKeyMapper::to_db_sort_key(&substate_key);
+            fee_substates.insert(
+                (partition_key, sort_key),
+                (node_id, substate_key, fee_substate),
+            );
+        };
+
+        // The validator rewards, unless the transaction called the consensus manager, which may
+        // have read or distributed them
+        let rewards_key: SubstateKey = ConsensusManagerField::ValidatorRewards.into();
+        let rewards = written_value(commit, CONSENSUS_MANAGER.as_node_id(), &rewards_key)
+            .and_then(|value| decode_field::<ConsensusManagerValidatorRewardsFieldPayload>(value));
+        if let Some((rewards, _)) = rewards {
+            let rewards_vault_id = rewards.into_latest().rewards_vault.0 .0;
+            if !invocations.invoked(CONSENSUS_MANAGER.as_node_id())
+                && !invocations.invoked(&rewards_vault_id)
+            {
+                insert(
+                    CONSENSUS_MANAGER.into_node_id(),
+                    rewards_key,
+                    FeeSubstate::ValidatorRewards,
+                );
+                insert(
+                    rewards_vault_id,
+                    FungibleVaultField::Balance.into(),
+                    FeeSubstate::RewardsVaultBalance,
+                );
+            }
+        }
+
+        // The vaults which the fee was paid from, if fees were their only use
+        for vault_id in commit.fee_source.paying_vaults().keys() {
+            if let Some(locked) = invocations.fee_only_locked_from(vault_id) {
+                insert(
+                    *vault_id,
+                    FungibleVaultField::Balance.into(),
+                    FeeSubstate::FeePayingVaultBalance { locked },
+                );
+            }
+        }
+
+        fee_substates
+    }
+
+    /// Applies the update of the transaction, from the `base` value it read to the `written` one,
+    /// to the `current` value. Returns `None` if the transaction could have behaved differently
+    /// on the current value.
+    fn rebase(&self, base: &[u8], written: &[u8], current: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            FeeSubstate::ValidatorRewards => {
+                let (base, _) = decode_field::<ConsensusManagerValidatorRewardsFieldPayload>(base)?;
+                let (written, lock_status) =
+                    decode_field::<ConsensusManagerValidatorRewardsFieldPayload>(written)?;
+                let (current, _) =
+                    decode_field::<ConsensusManagerValidatorRewardsFieldPayload>(current)?;
+                let (base, written, mut rebased) = (
+                    base.into_latest(),
+                    written.into_latest(),
+                    current.into_latest(),
+                );
+                if base.rewards_vault != rebased.rewards_vault
+                    || base
+                        .proposer_rewards
+                        .keys()
+                        .any(|index| !written.proposer_rewards.contains_key(index))
+                {
+                    return None;
+                }
+                for (index, amount) in written.proposer_rewards {
+                    let added = amount.checked_sub(
+                        base.proposer_rewards
+                            .get(&index)
+                            .cloned()
+                            .unwrap_or_default(),
+                    )?;
+                    let entry = rebased.proposer_rewards.entry(index).or_default();
+                    *entry = entry.checked_add(added)?;
+                }
+                scrypto_encode(&FieldSubstate::new_field(
+                    ConsensusManagerValidatorRewardsFieldPayload::from_content_source(rebased),
+                    lock_status,
+                ))
+                .ok()
+            }
+            FeeSubstate::RewardsVaultBalance => {
+                rebase_vault_balance(base, written, current, Decimal::ZERO)
+            }
+            FeeSubstate::FeePayingVaultBalance { locked } => {
+                rebase_vault_balance(base, written, current, *locked)
+            }
+        }
+    }
+}
+
+/// Rebases the update of a vault balance, provided that the given amount of fees can be locked
+/// from the current balance as it was from the base one.
+fn rebase_vault_balance(
+    base: &[u8],
+    written: &[u8],
+    current: &[u8],
+    locked: Decimal,
+) -> Option<Vec<u8>> {
+    let amount = |value: &[u8]| {
+        scrypto_decode::<FungibleVaultBalanceFieldSubstate>(value)
+            .ok()
+            .map(|substate| substate.into_payload().into_latest().amount())
+    };
+    let base = amount(base)?;
+    let current = amount(current)?;
+    let (written, lock_status) = decode_field::<FungibleVaultBalanceFieldPayload>(written)?;
+    if base < locked || current < locked {
+        return None;
+    }
+
+    let rebased = current.checked_add(written.into_latest().amount().checked_sub(base)?)?;
+    scrypto_encode(&FieldSubstate::new_field(
+        FungibleVaultBalanceFieldPayload::from_content_source(LiquidFungibleResource::new(rebased)),
+        lock_status,
+    ))
+    .ok()
+}
+
+fn decode_field<P: ScryptoDecode>(value: &[u8]) -> Option<(P, LockStatus)> {
+    let substate = scrypto_decode::<FieldSubstate<P>>(value).ok()?;
+    let lock_status = *substate.lock_status();
+    Some((substate.into_payload(), lock_status))
+}
+
+fn written_value<'a>(
+    commit: &'a CommitResult,
+    node_id: &NodeId,
+    substate_key: &SubstateKey,
+) -> Option<&'a [u8]> {
+    let NodeStateUpdates::Delta { by_partition } = commit.state_updates.by_node.get(node_id)?;
+    match by_partition.get(&MAIN_BASE_PARTITION)? {
+        PartitionStateUpdates::Delta { by_substate } => match by_substate.get(substate_key)? {
+            DatabaseUpdate::Set(value) => Some(value),
+            DatabaseUpdate::Delete => None,
+        },
+        PartitionStateUpdates::Batch(_) => None,
+    }
+}
+
+/// A [`SystemModuleExtension`] recording the methods invoked on each node by a transaction, and
+/// the fees locked from each vault.
+#[derive(Debug, Default)]
+struct InvocationRecorder {
+    invoked_methods: IndexMap<NodeId, IndexSet<String>>,
+    locked_fees: IndexMap<NodeId, Option<Decimal>>,
+}
+
+impl InvocationRecorder {
+    fn invoked(&self, node_id: &NodeId) -> bool {
+        self.invoked_methods.contains_key(node_id)
+    }
+
+    /// Returns the fees locked from the given vault, if no other method was invoked on it.
+    fn fee_only_locked_from(&self, vault_id: &NodeId) -> Option<Decimal> {
+        let methods = self.invoked_methods.get(vault_id)?;
+        if methods.len() == 1 && methods.contains(FUNGIBLE_VAULT_LOCK_FEE_IDENT) {
+            self.locked_fees.get(vault_id).cloned().flatten()
+        } else {
+            None
+        }
+    }
+}
+
+impl SystemModuleExtension for InvocationRecorder {
+    fn name(&self) -> String {
+        "InvocationRecorder".to_string()
+    }
+
+    fn before_invoke(
+        &mut self,
+        _depth: usize,
+        invocation: &KernelInvocation<Actor>,
+    ) -> Result<(), String> {
+        let Actor::Method(method) = &invocation.call_frame_data else {
+            return Ok(());
+        };
+        self.invoked_methods
+            .entry(method.node_id)
+            .or_default()
+            .insert(method.ident.clone());
+
+        if method.node_id.is_internal_fungible_vault()
+            && matches!(method.method_type, MethodType::Main)
+            && method.ident == FUNGIBLE_VAULT_LOCK_FEE_IDENT
+        {
+            // An undecodable or overflowing lock leaves the locked fees unknown
+            let input = invocation.args.as_typed::<FungibleVaultLockFeeInput>().ok();
+            let locked = self
+                .locked_fees
+                .entry(method.node_id)
+                .or_insert(Some(Decimal::ZERO));
+            *locked = locked
+                .zip(input)
+                .and_then(|(locked, input)| locked.checked_add(input.amount));
+        }
+        Ok(())
+    }
+}
+
+/// The database reads performed by a transaction, i.e. by its `Track` and receipt creation.
+#[derive(Debug, Default)]
+struct ReadSet {
+    substates: IndexSet<(DbPartitionKey, DbSortKey)>,
+    /// Partitions which were listed, and thus depend on all of their substates.
+    listed_partitions: IndexSet<DbPartitionKey>,
+}
+
+/// The database writes performed by a sequence of transactions.
+#[derive(Debug, Default)]
+struct WriteSet {
+    substates: IndexSet<(DbPartitionKey, DbSortKey)>,
+    partitions: IndexSet<DbPartitionKey>,
+    reset_partitions: IndexSet<DbPartitionKey>,
+}
+
+impl WriteSet {
+    fn record(&mut self, database_updates: &DatabaseUpdates) {
+        for (node_key, node_updates) in &database_updates.node_updates {
+            for (partition_num, partition_updates) in &node_updates.partition_updates {
+                let partition_key = DbPartitionKey {
+                    node_key: node_key.clone(),
+                    partition_num: *partition_num,
+                };
+                match partition_updates {
+                    PartitionDatabaseUpdates::Delta { substate_updates } => {
+                        for sort_key in substate_updates.keys() {
+                            self.substates
+                                .insert((partition_key.clone(), sort_key.clone()));
+                        }
+                    }
+                    PartitionDatabaseUpdates::Reset { .. } => {
+                        self.reset_partitions.insert(partition_key.clone());
+                    }
+                }
+                self.partitions.insert(partition_key);
+            }
+        }
+    }
+
+    /// Returns the substates read by a transaction which were written by the previous ones, or
+    /// `None` if it depends on a whole partition which was written.
+    fn conflicting_substates(
+        &self,
+        read_set: &ReadSet,
+    ) -> Option<IndexSet<(DbPartitionKey, DbSortKey)>> {
+        if read_set
+            .listed_partitions
+            .iter()
+            .any(|partition_key| self.partitions.contains(partition_key))
+            || read_set
+                .substates
+                .iter()
+                .any(|(partition_key, _)| self.reset_partitions.contains(partition_key))
+        {
+            return None;
+        }
+
+        Some(
+            read_set
+                .substates
+                .iter()
+                .filter(|substate| self.substates.contains(*substate))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// A [`SubstateDatabase`] wrapper recording all reads.
+struct ReadRecordingSubstateDatabase<'s, S: SubstateDatabase> {
+    substate_db: &'s S,
+    read_set: RefCell<ReadSet>,
+}
+
+impl<'s, S: SubstateDatabase> ReadRecordingSubstateDatabase<'s, S> {
+    fn new(substate_db: &'s S) -> Self {
+        Self {
+            substate_db,
+            read_set: RefCell::new(ReadSet::default()),
+        }
+    }
+
+    fn into_read_set(self) -> ReadSet {
+        self.read_set.into_inner()
+    }
+}
+
+impl<'s, S: SubstateDatabase> SubstateDatabase for ReadRecordingSubstateDatabase<'s, S> {
+    fn get_substate(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<DbSubstateValue> {
+        self.read_set
+            .borrow_mut()
+            .substates
+            .insert((partition_key.clone(), sort_key.clone()));
+        self.substate_db.get_substate(partition_key, sort_key)
+    }
+
+    fn list_entries_from(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        self.read_set
+            .borrow_mut()
+            .listed_partitions
+            .insert(partition_key.clone());
+        self.substate_db
+            .list_entries_from(partition_key, from_sort_key)
+    }
+}
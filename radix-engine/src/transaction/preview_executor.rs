@@ -1,16 +1,129 @@
+use crate::blueprints::consensus_manager::{
+    ConsensusManagerBlueprint, ConsensusManagerField,
+    ConsensusManagerProposerMilliTimestampFieldPayload,
+    ConsensusManagerProposerMinuteTimestampFieldPayload, ConsensusManagerStateFieldPayload,
+    ProposerMilliTimestampSubstate, ProposerMinuteTimestampSubstate,
+};
+use crate::blueprints::models::FieldPayload;
+use crate::blueprints::resource::{FungibleVaultBalanceFieldPayload, FungibleVaultField};
 use crate::system::system_callback_api::SystemCallbackObject;
+use crate::system::system_db_reader::{
+    SystemDatabaseReader, SystemDatabaseWriter, SystemReaderError,
+};
+use crate::track::SubstateDatabaseOverlay;
 use crate::transaction::TransactionReceipt;
 use crate::transaction::*;
+use crate::types::*;
+use radix_engine_interface::api::ModuleId;
+use radix_engine_interface::blueprints::resource::LiquidFungibleResource;
 use radix_engine_interface::network::NetworkDefinition;
 use radix_engine_store_interface::interface::*;
 use transaction::errors::TransactionValidationError;
-use transaction::model::PreviewIntentV1;
+use transaction::model::{AuthZoneParams, PreviewIntentV1};
 use transaction::validation::NotarizedTransactionValidator;
 use transaction::validation::ValidationConfig;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PreviewError {
     TransactionValidationError(TransactionValidationError),
+    StateOverrideError(StateOverrideError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateOverrideError {
+    NotAFungibleVault(NodeId),
+    InvalidProposerTimestamp(i64),
+    SystemReaderError(SystemReaderError),
+}
+
+impl From<SystemReaderError> for StateOverrideError {
+    fn from(value: SystemReaderError) -> Self {
+        Self::SystemReaderError(value)
+    }
+}
+
+/// The starting conditions to run a preview against, layered over the current state.
+///
+/// The overrides are applied in the order of the fields below, and are never written to the
+/// underlying database. Note that no invariants are maintained across substates, e.g. overriding
+/// a vault balance does not update the total supply of its resource.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PreviewOverrides {
+    /// Arbitrary substate writes.
+    pub substate_overrides: DatabaseUpdates,
+    /// The liquid balances of the given fungible vaults.
+    pub fungible_vault_balances: IndexMap<NodeId, Decimal>,
+    /// The current epoch, as seen by the consensus manager (and the epoch validity check).
+    pub epoch: Option<Epoch>,
+    /// The current proposer timestamp, in millis since the unix epoch.
+    pub proposer_timestamp_ms: Option<i64>,
+    /// Proofs and virtual resources to put in the root auth zone, in addition to the signers of
+    /// the preview intent - e.g. to impersonate other signers or the holder of an admin badge.
+    pub auth_zone_params: AuthZoneParams,
+}
+
+impl PreviewOverrides {
+    pub fn apply<S: SubstateDatabase>(
+        &self,
+        overlay: &mut SubstateDatabaseOverlay<S>,
+    ) -> Result<(), StateOverrideError> {
+        overlay.commit(&self.substate_overrides);
+
+        for (vault_id, amount) in &self.fungible_vault_balances {
+            if !vault_id.is_internal_fungible_vault() {
+                return Err(StateOverrideError::NotAFungibleVault(*vault_id));
+            }
+            SystemDatabaseWriter::new(overlay).write_typed_object_field(
+                vault_id,
+                ModuleId::Main,
+                FungibleVaultField::Balance.field_index(),
+                FungibleVaultBalanceFieldPayload::from_content_source(LiquidFungibleResource::new(
+                    *amount,
+                )),
+            )?;
+        }
+
+        if let Some(epoch) = self.epoch {
+            let mut state = SystemDatabaseReader::new(&*overlay)
+                .read_typed_object_field::<ConsensusManagerStateFieldPayload>(
+                    CONSENSUS_MANAGER.as_node_id(),
+                    ModuleId::Main,
+                    ConsensusManagerField::State.field_index(),
+                )?
+                .into_latest();
+            state.epoch = epoch;
+            SystemDatabaseWriter::new(overlay).write_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::State.field_index(),
+                ConsensusManagerStateFieldPayload::from_content_source(state),
+            )?;
+        }
+
+        if let Some(epoch_milli) = self.proposer_timestamp_ms {
+            let epoch_minute = ConsensusManagerBlueprint::milli_to_minute(epoch_milli)
+                .ok_or(StateOverrideError::InvalidProposerTimestamp(epoch_milli))?;
+            let mut writer = SystemDatabaseWriter::new(overlay);
+            writer.write_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMilliTimestamp.field_index(),
+                ConsensusManagerProposerMilliTimestampFieldPayload::from_content_source(
+                    ProposerMilliTimestampSubstate { epoch_milli },
+                ),
+            )?;
+            writer.write_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMinuteTimestamp.field_index(),
+                ConsensusManagerProposerMinuteTimestampFieldPayload::from_content_source(
+                    ProposerMinuteTimestampSubstate { epoch_minute },
+                ),
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 pub fn execute_preview<S: SubstateDatabase, V: SystemCallbackObject + Clone>(
@@ -19,6 +132,27 @@ pub fn execute_preview<S: SubstateDatabase, V: SystemCallbackObject + Clone>(
     network: &NetworkDefinition,
     preview_intent: PreviewIntentV1,
     with_kernel_trace: bool,
+) -> Result<TransactionReceipt, PreviewError> {
+    execute_preview_with_overrides(
+        substate_db,
+        vm,
+        network,
+        preview_intent,
+        &PreviewOverrides::default(),
+        with_kernel_trace,
+    )
+}
+
+/// Executes a preview against the current state with the given [`PreviewOverrides`] applied.
+///
+/// The overrides live in a [`SubstateDatabaseOverlay`], so the `substate_db` is left untouched.
+pub fn execute_preview_with_overrides<S: SubstateDatabase, V: SystemCallbackObject + Clone>(
+    substate_db: &S,
+    vm: V,
+    network: &NetworkDefinition,
+    preview_intent: PreviewIntentV1,
+    overrides: &PreviewOverrides,
+    with_kernel_trace: bool,
 ) -> Result<TransactionReceipt, PreviewError> {
     let validation_config = ValidationConfig::default(network.id);
 
@@ -28,11 +162,18 @@ pub fn execute_preview<S: SubstateDatabase, V: SystemCallbackObject + Clone>(
         .validate_preview_intent_v1(preview_intent)
         .map_err(PreviewError::TransactionValidationError)?;
 
+    let mut overlay = SubstateDatabaseOverlay::new(substate_db);
+    overrides
+        .apply(&mut overlay)
+        .map_err(PreviewError::StateOverrideError)?;
+
     Ok(execute_transaction(
-        substate_db,
+        &overlay,
         vm,
         &CostingParameters::default(),
         &ExecutionConfig::for_preview(network.clone()).with_kernel_trace(with_kernel_trace),
-        &validated.get_executable(),
+        &validated
+            .get_executable()
+            .with_additional_auth_zone_params(overrides.auth_zone_params.clone()),
     ))
 }
@@ -20,6 +20,10 @@ use crate::system::system_callback_api::SystemCallbackObject;
 use crate::system::system_db_reader::SystemDatabaseReader;
 use crate::system::system_modules::costing::*;
 use crate::system::system_modules::execution_trace::ExecutionTraceModule;
+use crate::system::system_modules::extension::{
+    ExtensionModule, RegisteredSystemModuleExtension, SharedSystemModuleExtension,
+};
+use crate::system::system_modules::kernel_trace::{KernelTraceModule, KernelTraceSink};
 use crate::system::system_modules::transaction_runtime::TransactionRuntimeModule;
 use crate::system::system_modules::{EnabledModules, SystemModuleMixer};
 use crate::system::system_substates::KeyValueEntrySubstate;
@@ -100,6 +104,9 @@ pub struct ExecutionConfig {
     pub enabled_modules: EnabledModules,
     pub abort_when_loan_repaid: bool,
     pub enable_cost_breakdown: bool,
+    pub enable_cost_profile: bool,
+    pub kernel_trace_sink: KernelTraceSink,
+    pub system_module_extensions: Vec<RegisteredSystemModuleExtension>,
     pub max_execution_trace_depth: usize,
     pub max_call_depth: usize,
     pub max_heap_substate_total_bytes: usize,
@@ -124,6 +131,9 @@ impl ExecutionConfig {
             enabled_modules: EnabledModules::for_notarized_transaction(),
             abort_when_loan_repaid: false,
             enable_cost_breakdown: false,
+            enable_cost_profile: false,
+            kernel_trace_sink: KernelTraceSink::Print,
+            system_module_extensions: Vec::new(),
             max_execution_trace_depth: MAX_EXECUTION_TRACE_DEPTH,
             max_call_depth: MAX_CALL_DEPTH,
             max_heap_substate_total_bytes: MAX_HEAP_SUBSTATE_TOTAL_BYTES,
@@ -190,15 +200,61 @@ impl ExecutionConfig {
         self
     }
 
+    /// Records the invocations of the transaction, with the resources passed in and out of them,
+    /// in the execution trace of its commit result.
+    pub fn with_execution_trace(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.enabled_modules.insert(EnabledModules::EXECUTION_TRACE);
+        } else {
+            self.enabled_modules.remove(EnabledModules::EXECUTION_TRACE);
+        }
+        self
+    }
+
+    /// Enables the kernel trace, sending its records to the given sink.
+    pub fn with_kernel_trace_sink(mut self, sink: KernelTraceSink) -> Self {
+        self.enabled_modules.insert(EnabledModules::KERNEL_TRACE);
+        self.kernel_trace_sink = sink;
+        self
+    }
+
+    /// Registers an extension, run after the built-in system modules. Its errors only fail the
+    /// transaction if `affects_consensus` is set.
+    pub fn with_system_module_extension(
+        mut self,
+        extension: SharedSystemModuleExtension,
+        affects_consensus: bool,
+    ) -> Self {
+        self.enabled_modules.insert(EnabledModules::EXTENSION);
+        self.system_module_extensions
+            .push(RegisteredSystemModuleExtension {
+                extension,
+                affects_consensus,
+            });
+        self
+    }
+
     pub fn with_cost_breakdown(mut self, enabled: bool) -> Self {
         self.enable_cost_breakdown = enabled;
         self
     }
 
+    /// Attributes the cost units to the call frames consuming them, see [`CostProfile`].
+    pub fn with_cost_profile(mut self, enabled: bool) -> Self {
+        self.enable_cost_profile = enabled;
+        self
+    }
+
     pub fn up_to_loan_repayment(mut self, enabled: bool) -> Self {
         self.abort_when_loan_repaid = enabled;
         self
     }
+
+    #[cfg(not(feature = "alloc"))]
+    fn prints_kernel_trace(&self) -> bool {
+        self.enabled_modules.contains(EnabledModules::KERNEL_TRACE)
+            && matches!(self.kernel_trace_sink, KernelTraceSink::Print)
+    }
 }
 
 impl<C: SystemCallbackObject> WrappedSystem<C> for SystemConfig<C> {
@@ -253,10 +309,7 @@ where
 
         // Dump executable
         #[cfg(not(feature = "alloc"))]
-        if execution_config
-            .enabled_modules
-            .contains(EnabledModules::KERNEL_TRACE)
-        {
+        if execution_config.prints_kernel_trace() {
             Self::print_executable(&executable);
         }
 
@@ -273,7 +326,7 @@ where
         // We are assuming that intent hash store is ready when epoch manager is ready.
         let current_epoch = Self::read_epoch(&mut track);
         let validation_result = if let Some(current_epoch) = current_epoch {
-            if let Some(range) = executable.epoch_range() {
+            let root_validation_result = if let Some(range) = executable.epoch_range() {
                 Self::validate_epoch_range(
                     current_epoch,
                     range.start_epoch_inclusive,
@@ -288,17 +341,46 @@ where
                 })
             } else {
                 Ok(())
-            }
+            };
+            root_validation_result.and_then(|_| {
+                // Every child intent must also be valid at this epoch, and not previously committed
+                executable.subintents().iter().try_for_each(|subintent| {
+                    let range = subintent.epoch_range();
+                    Self::validate_epoch_range(
+                        current_epoch,
+                        range.start_epoch_inclusive,
+                        range.end_epoch_exclusive,
+                    )?;
+                    Self::validate_intent_hash(
+                        &mut track,
+                        subintent.intent_hash().to_hash(),
+                        range.end_epoch_exclusive,
+                    )
+                })
+            })
         } else {
             Ok(())
         };
 
         // Run manifest
-        let (fee_summary, fee_details, result) = match validation_result {
+        let (
+            fee_summary,
+            fee_details,
+            cost_profile,
+            kernel_trace,
+            system_module_extension_errors,
+            result,
+        ) = match validation_result {
             Ok(()) => {
                 let (
                     interpretation_result,
-                    (mut costing_module, runtime_module, execution_trace_module),
+                    (
+                        mut costing_module,
+                        runtime_module,
+                        execution_trace_module,
+                        kernel_trace_module,
+                        extension_module,
+                    ),
                 ) = self.interpret_manifest::<T>(
                     &mut track,
                     executable,
@@ -309,10 +391,7 @@ where
                 );
 
                 #[cfg(not(feature = "alloc"))]
-                if execution_config
-                    .enabled_modules
-                    .contains(EnabledModules::KERNEL_TRACE)
-                {
+                if execution_config.prints_kernel_trace() {
                     println!("{:-^120}", "Interpretation Results");
                     println!("{:?}", interpretation_result);
                 }
@@ -335,6 +414,12 @@ where
                 } else {
                     None
                 };
+                let cost_profile = costing_module
+                    .cost_profiler
+                    .take()
+                    .map(CostProfiler::finalize);
+                let kernel_trace = kernel_trace_module.finalize();
+                let system_module_extension_errors = extension_module.finalize();
 
                 // Panic if an error is encountered in the system layer or below. The following code
                 // is only enabled when compiling with the standard library since the panic catching
@@ -379,10 +464,19 @@ where
 
                         // Update intent hash status
                         if let Some(next_epoch) = Self::read_epoch(&mut track) {
+                            let intent_hashes = [executable.intent_hash()]
+                                .into_iter()
+                                .chain(
+                                    executable
+                                        .subintents()
+                                        .iter()
+                                        .map(|subintent| subintent.intent_hash()),
+                                )
+                                .collect::<Vec<_>>();
                             Self::update_transaction_tracker(
                                 &mut track,
                                 next_epoch,
-                                executable.intent_hash(),
+                                &intent_hashes,
                                 is_success,
                             );
                         }
@@ -412,6 +506,17 @@ where
                             &application_events,
                         );
 
+                        let sponsor = fee_reserve_finalization.fee_sponsor.as_ref().map(
+                            |(account, vaults)| FeeSponsorPayment {
+                                account: *account,
+                                paying_vaults: paying_vaults
+                                    .iter()
+                                    .filter(|(vault_id, _)| vaults.contains(*vault_id))
+                                    .map(|(vault_id, amount)| (*vault_id, *amount))
+                                    .collect(),
+                            },
+                        );
+
                         let state_update_summary =
                             StateUpdateSummary::new(self.substate_db, &tracked_nodes);
 
@@ -437,10 +542,17 @@ where
                         (
                             fee_reserve_finalization.into(),
                             fee_details,
+                            cost_profile,
+                            kernel_trace,
+                            system_module_extension_errors,
                             TransactionResult::Commit(CommitResult {
                                 state_updates,
                                 state_update_summary,
-                                fee_source: FeeSource { paying_vaults },
+                                fee_source: FeeSource {
+                                    sponsor,
+                                    paying_vaults,
+                                }
+                                .into(),
                                 fee_destination,
                                 outcome: match outcome {
                                     Ok(o) => TransactionOutcome::Success(o),
@@ -463,11 +575,17 @@ where
                     TransactionResultType::Reject(reason) => (
                         costing_module.fee_reserve.finalize().into(),
                         fee_details,
+                        cost_profile,
+                        kernel_trace,
+                        system_module_extension_errors,
                         TransactionResult::Reject(RejectResult { reason }),
                     ),
                     TransactionResultType::Abort(reason) => (
                         costing_module.fee_reserve.finalize().into(),
                         fee_details,
+                        cost_profile,
+                        kernel_trace,
+                        system_module_extension_errors,
                         TransactionResult::Abort(AbortResult { reason }),
                     ),
                 }
@@ -480,6 +598,9 @@ where
                 } else {
                     None
                 },
+                None,
+                None,
+                Vec::new(),
                 TransactionResult::Reject(RejectResult { reason }),
             ),
         };
@@ -498,16 +619,16 @@ where
             transaction_costing_parameters: executable.costing_parameters().clone(),
             fee_summary,
             fee_details,
+            cost_profile,
+            kernel_trace,
+            system_module_extension_errors,
             result,
             resources_usage,
         };
 
         // Dump summary
         #[cfg(not(feature = "alloc"))]
-        if execution_config
-            .enabled_modules
-            .contains(EnabledModules::KERNEL_TRACE)
-        {
+        if execution_config.prints_kernel_trace() {
             Self::print_execution_summary(&receipt);
         }
 
@@ -615,6 +736,8 @@ where
             CostingModule,
             TransactionRuntimeModule,
             ExecutionTraceModule,
+            KernelTraceModule,
+            ExtensionModule,
         ),
     ) {
         let mut id_allocator = IdAllocator::new(executable.intent_hash().to_hash());
@@ -633,7 +756,9 @@ where
                 executable.payload_size(),
                 executable.num_of_signature_validations(),
                 execution_config,
-            ),
+            )
+            .with_subintents(executable.subintents())
+            .with_fee_sponsor(executable.fee_sponsor()),
         };
 
         let mut wrapped_system = T::create(system, init);
@@ -650,6 +775,8 @@ where
                 executable.pre_allocated_addresses(),
                 executable.references(),
                 executable.blobs(),
+                executable.subintents(),
+                executable.fee_sponsor(),
             )
             .and_then(|x| {
                 let system = wrapped_system.system_mut();
@@ -1000,7 +1127,7 @@ where
     fn update_transaction_tracker(
         track: &mut Track<S, SpreadPrefixKeyMapper>,
         next_epoch: Epoch,
-        intent_hash: &TransactionIntentHash,
+        intent_hashes: &[&TransactionIntentHash],
         is_success: bool,
     ) {
         // Read the intent hash store
@@ -1017,36 +1144,38 @@ where
 
         let mut transaction_tracker = transaction_tracker.into_v1();
 
-        // Update the status of the intent hash
-        if let TransactionIntentHash::ToCheck {
-            expiry_epoch,
-            intent_hash,
-        } = intent_hash
-        {
-            if let Some(partition_number) =
-                transaction_tracker.partition_for_expiry_epoch(*expiry_epoch)
+        // Update the status of the intent hashes
+        for intent_hash in intent_hashes {
+            if let TransactionIntentHash::ToCheck {
+                expiry_epoch,
+                intent_hash,
+            } = intent_hash
             {
-                track
-                    .set_substate(
-                        TRANSACTION_TRACKER.into_node_id(),
-                        PartitionNumber(partition_number),
-                        SubstateKey::Map(scrypto_encode(intent_hash).unwrap()),
-                        IndexedScryptoValue::from_typed(&KeyValueEntrySubstate::V1(
-                            KeyValueEntrySubstateV1 {
-                                value: Some(if is_success {
-                                    TransactionStatus::V1(TransactionStatusV1::CommittedSuccess)
-                                } else {
-                                    TransactionStatus::V1(TransactionStatusV1::CommittedFailure)
-                                }),
-                                // TODO: maybe make it immutable, but how does this affect partition deletion?
-                                lock_status: LockStatus::Unlocked,
-                            },
-                        )),
-                        &mut |_| -> Result<(), ()> { Ok(()) },
-                    )
-                    .unwrap();
-            } else {
-                panic!("No partition for an expiry epoch")
+                if let Some(partition_number) =
+                    transaction_tracker.partition_for_expiry_epoch(*expiry_epoch)
+                {
+                    track
+                        .set_substate(
+                            TRANSACTION_TRACKER.into_node_id(),
+                            PartitionNumber(partition_number),
+                            SubstateKey::Map(scrypto_encode(intent_hash).unwrap()),
+                            IndexedScryptoValue::from_typed(&KeyValueEntrySubstate::V1(
+                                KeyValueEntrySubstateV1 {
+                                    value: Some(if is_success {
+                                        TransactionStatus::V1(TransactionStatusV1::CommittedSuccess)
+                                    } else {
+                                        TransactionStatus::V1(TransactionStatusV1::CommittedFailure)
+                                    }),
+                                    // TODO: maybe make it immutable, but how does this affect partition deletion?
+                                    lock_status: LockStatus::Unlocked,
+                                },
+                            )),
+                            &mut |_| -> Result<(), ()> { Ok(()) },
+                        )
+                        .unwrap();
+                } else {
+                    panic!("No partition for an expiry epoch")
+                }
             }
         }
 
@@ -4,6 +4,8 @@ use crate::errors::*;
 use crate::internal_prelude::*;
 use crate::system::system_modules::costing::*;
 use crate::system::system_modules::execution_trace::*;
+use crate::system::system_modules::extension::SystemModuleExtensionError;
+use crate::system::system_modules::kernel_trace::KernelTraceRecord;
 use crate::track::BatchPartitionStateUpdate;
 use crate::track::NodeStateUpdates;
 use crate::track::PartitionStateUpdates;
@@ -35,6 +37,15 @@ pub struct TransactionReceiptV1 {
     /// Transaction fee detail
     /// Available if `ExecutionConfig::enable_cost_breakdown` is enabled
     pub fee_details: Option<TransactionFeeDetails>,
+    /// Transaction cost profile
+    /// Available if `ExecutionConfig::enable_cost_profile` is enabled
+    pub cost_profile: Option<CostProfile>,
+    /// Kernel trace records
+    /// Available if the kernel trace is enabled with `KernelTraceSink::Receipt`
+    pub kernel_trace: Option<Vec<KernelTraceRecord>>,
+    /// Errors of the system module extensions which do not affect consensus, and so did not fail
+    /// the transaction
+    pub system_module_extension_errors: Vec<SystemModuleExtensionError>,
     /// Transaction result
     pub result: TransactionResult,
     /// Hardware resources usage report
@@ -84,7 +95,7 @@ pub struct CommitResult {
     /// Information extracted from the substate updates
     pub state_update_summary: StateUpdateSummary,
     /// The source of transaction fee
-    pub fee_source: FeeSource,
+    pub fee_source: VersionedFeeSource,
     /// The destination of transaction fee
     pub fee_destination: FeeDestination,
     /// Transaction execution outcome
@@ -100,8 +111,68 @@ pub struct CommitResult {
     pub execution_trace: Option<TransactionExecutionTrace>,
 }
 
+define_versioned! {
+    /// The fee source is versioned since fee sponsorship added the fee sponsor to it, so that
+    /// it can evolve without changing the shape of [`TransactionReceiptV1`] again.
+    /// Receipts contain the latest version, which `V1` (without a sponsor) updates to.
+    #[derive(Debug, Clone, ScryptoSbor)]
+    pub enum VersionedFeeSource {
+        previous_versions: [
+            1 => FeeSourceV1: { updates_to: 2 },
+        ],
+        latest_version: {
+            2 => FeeSource = FeeSourceV2,
+        },
+    }
+}
+
+impl VersionedFeeSource {
+    pub fn paying_vaults(&self) -> &IndexMap<NodeId, Decimal> {
+        match self {
+            Self::V1(fee_source) => &fee_source.paying_vaults,
+            Self::V2(fee_source) => &fee_source.paying_vaults,
+        }
+    }
+
+    /// The sponsor of the fees, if any, whose vaults are also in the `paying_vaults`
+    pub fn sponsor(&self) -> Option<&FeeSponsorPayment> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(fee_source) => fee_source.sponsor.as_ref(),
+        }
+    }
+}
+
+impl Default for VersionedFeeSource {
+    fn default() -> Self {
+        FeeSource::default().into()
+    }
+}
+
 #[derive(Debug, Clone, Default, ScryptoSbor)]
-pub struct FeeSource {
+pub struct FeeSourceV1 {
+    pub paying_vaults: IndexMap<NodeId, Decimal>,
+}
+
+#[derive(Debug, Clone, Default, ScryptoSbor)]
+pub struct FeeSourceV2 {
+    pub paying_vaults: IndexMap<NodeId, Decimal>,
+    /// The sponsor of the fees, if any, whose vaults are also in `paying_vaults`
+    pub sponsor: Option<FeeSponsorPayment>,
+}
+
+impl From<FeeSourceV1> for FeeSourceV2 {
+    fn from(value: FeeSourceV1) -> Self {
+        Self {
+            paying_vaults: value.paying_vaults,
+            sponsor: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ScryptoSbor)]
+pub struct FeeSponsorPayment {
+    pub account: ComponentAddress,
     pub paying_vaults: IndexMap<NodeId, Decimal>,
 }
 
@@ -362,6 +433,9 @@ impl TransactionReceipt {
             transaction_costing_parameters: Default::default(),
             fee_summary: Default::default(),
             fee_details: Default::default(),
+            cost_profile: Default::default(),
+            kernel_trace: Default::default(),
+            system_module_extension_errors: Default::default(),
             result: TransactionResult::Commit(commit_result),
             resources_usage: Default::default(),
         }
@@ -2,9 +2,15 @@ use crate::blueprints::consensus_manager::*;
 use crate::blueprints::models::KeyValueEntryContentSource;
 use crate::blueprints::package::*;
 use crate::blueprints::pool::v1::constants::*;
+use crate::blueprints::pool::v1::package::{PoolNativePackage, PoolV1MinorVersion};
+use crate::blueprints::transaction_processor::TransactionProcessorNativePackage;
 use crate::internal_prelude::*;
+use crate::system::attached_modules::role_assignment::RoleAssignmentNativePackage;
 use crate::system::system_db_reader::{ObjectCollectionKey, SystemDatabaseReader};
 use crate::track::{NodeStateUpdates, PartitionStateUpdates, StateUpdates};
+use crate::vm::wasm::{
+    SCRPYTO_VM_CRYPTO_UTILS_MINOR_VERSION, SCRYPTO_VM_COLLECTIONS_MINOR_VERSION,
+};
 use crate::vm::*;
 use radix_engine_common::constants::*;
 use radix_engine_common::crypto::hash;
@@ -12,8 +18,10 @@ use radix_engine_common::math::Decimal;
 use radix_engine_common::prelude::ScopedTypeId;
 use radix_engine_common::prelude::{scrypto_encode, ScryptoCustomTypeKind};
 use radix_engine_common::types::SubstateKey;
+use radix_engine_interface::api::node_modules::auth::ROLE_ASSIGNMENT_BLUEPRINT;
 use radix_engine_interface::api::ObjectModuleId;
 use radix_engine_interface::blueprints::consensus_manager::*;
+use radix_engine_interface::blueprints::transaction_processor::TRANSACTION_PROCESSOR_BLUEPRINT;
 use radix_engine_interface::prelude::*;
 use radix_engine_interface::types::CollectionDescriptor;
 use radix_engine_store_interface::interface::*;
@@ -22,8 +30,18 @@ use sbor::{generate_full_schema, TypeAggregator};
 use utils::indexmap;
 
 pub fn generate_vm_boot_scrypto_minor_version_state_updates() -> StateUpdates {
+    generate_vm_boot_state_updates(SCRPYTO_VM_CRYPTO_UTILS_MINOR_VERSION)
+}
+
+/// Generates the state updates which allow Scrypto packages to import the key-value store
+/// iteration and the index and sorted index collection functions
+pub fn generate_vm_boot_scrypto_collections_state_updates() -> StateUpdates {
+    generate_vm_boot_state_updates(SCRYPTO_VM_COLLECTIONS_MINOR_VERSION)
+}
+
+fn generate_vm_boot_state_updates(scrypto_v1_minor_version: u64) -> StateUpdates {
     let substate = scrypto_encode(&VmBoot::V1 {
-        scrypto_v1_minor_version: 1u64,
+        scrypto_v1_minor_version,
     })
     .unwrap();
 
@@ -290,6 +308,78 @@ pub fn generate_pools_v1_1_state_updates<S: SubstateDatabase>(db: &S) -> StateUp
             )
         });
 
+    // The weighted and stable-swap pools are added by this update, with their definition,
+    // dependencies, royalty config, auth config and schemas taken from the v1.1 package structure
+    let mut new_blueprint_definition_updates = index_map_new();
+    let mut new_blueprint_dependencies_updates = index_map_new();
+    let mut new_blueprint_royalty_config_updates = index_map_new();
+    let mut new_blueprint_auth_config_updates = index_map_new();
+    let new_schema_updates = {
+        let mut package_structure = PackageNativePackage::validate_and_build_package_structure(
+            PoolNativePackage::definition(PoolV1MinorVersion::One),
+            VmType::Native,
+            new_code_id.to_be_bytes().to_vec(),
+            btreemap!(),
+            &VmVersion::default(),
+        )
+        .unwrap();
+
+        for blueprint_name in [
+            WEIGHTED_POOL_BLUEPRINT_IDENT,
+            STABLE_SWAP_POOL_BLUEPRINT_IDENT,
+        ] {
+            let key = SubstateKey::Map(
+                scrypto_encode(&BlueprintVersionKey::new_default(blueprint_name)).unwrap(),
+            );
+            let definition = package_structure
+                .definitions
+                .remove(blueprint_name)
+                .unwrap();
+            let dependencies = package_structure
+                .dependencies
+                .remove(blueprint_name)
+                .unwrap();
+            let royalty_config = package_structure
+                .package_royalties
+                .remove(blueprint_name)
+                .unwrap();
+            let auth_config = package_structure
+                .auth_configs
+                .remove(blueprint_name)
+                .unwrap();
+            new_blueprint_definition_updates.insert(
+                key.clone(),
+                DatabaseUpdate::Set(scrypto_encode(&definition.into_locked_substate()).unwrap()),
+            );
+            new_blueprint_dependencies_updates.insert(
+                key.clone(),
+                DatabaseUpdate::Set(scrypto_encode(&dependencies.into_locked_substate()).unwrap()),
+            );
+            new_blueprint_royalty_config_updates.insert(
+                key.clone(),
+                DatabaseUpdate::Set(
+                    scrypto_encode(&royalty_config.into_locked_substate()).unwrap(),
+                ),
+            );
+            new_blueprint_auth_config_updates.insert(
+                key,
+                DatabaseUpdate::Set(scrypto_encode(&auth_config.into_locked_substate()).unwrap()),
+            );
+        }
+
+        // Note: this also re-writes the (unchanged) schemas of the other pools
+        package_structure
+            .schemas
+            .into_iter()
+            .map(|(schema_hash, schema)| {
+                (
+                    SubstateKey::Map(scrypto_encode(&schema_hash).unwrap()),
+                    DatabaseUpdate::Set(scrypto_encode(&schema.into_locked_substate()).unwrap()),
+                )
+            })
+            .collect::<IndexMap<_, _>>()
+    };
+
     let original_code_partition_number = reader
         .get_partition_of_collection(
             &pool_package_node_id,
@@ -314,6 +404,38 @@ pub fn generate_pools_v1_1_state_updates<S: SubstateDatabase>(db: &S) -> StateUp
         )
         .unwrap();
 
+    let blueprint_dependencies_partition_number = reader
+        .get_partition_of_collection(
+            &pool_package_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::BlueprintVersionDependenciesKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    let blueprint_royalty_config_partition_number = reader
+        .get_partition_of_collection(
+            &pool_package_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::BlueprintVersionRoyaltyConfigKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    let blueprint_auth_config_partition_number = reader
+        .get_partition_of_collection(
+            &pool_package_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::BlueprintVersionAuthConfigKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    let schema_partition_number = reader
+        .get_partition_of_collection(
+            &pool_package_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::SchemaKeyValue.collection_index(),
+        )
+        .unwrap();
+
     StateUpdates {
         by_node: indexmap! {
             pool_package_node_id => NodeStateUpdates::Delta {
@@ -343,6 +465,21 @@ pub fn generate_pools_v1_1_state_updates<S: SubstateDatabase>(db: &S) -> StateUp
                             SubstateKey::Map(scrypto_encode(&multi_resource_pool_blueprint_key).unwrap())
                                 => DatabaseUpdate::Set(scrypto_encode(&multi_resource_pool_blueprint_definition).unwrap()),
                         }
+                        .into_iter()
+                        .chain(new_blueprint_definition_updates)
+                        .collect()
+                    },
+                    blueprint_dependencies_partition_number => PartitionStateUpdates::Delta {
+                        by_substate: new_blueprint_dependencies_updates
+                    },
+                    blueprint_royalty_config_partition_number => PartitionStateUpdates::Delta {
+                        by_substate: new_blueprint_royalty_config_updates
+                    },
+                    blueprint_auth_config_partition_number => PartitionStateUpdates::Delta {
+                        by_substate: new_blueprint_auth_config_updates
+                    },
+                    schema_partition_number => PartitionStateUpdates::Delta {
+                        by_substate: new_schema_updates
                     }
                 }
             }
@@ -362,10 +499,17 @@ pub fn generate_validator_fee_fix_state_updates<S: SubstateDatabase>(db: &S) ->
         )
         .unwrap();
 
-    let mut config = versioned_config.into_latest();
-    config.config.validator_creation_usd_cost = Decimal::from(100);
-
-    let updated_substate = config.into_locked_substate();
+    // The configuration keeps its version (it only gets migrated by the validator jailing update)
+    let updated_substate = match versioned_config {
+        VersionedConsensusManagerConfiguration::V1(mut config) => {
+            config.config.validator_creation_usd_cost = Decimal::from(100);
+            config.into_locked_substate()
+        }
+        VersionedConsensusManagerConfiguration::V2(mut config) => {
+            config.config.validator_creation_usd_cost = Decimal::from(100);
+            config.into_locked_substate()
+        }
+    };
 
     StateUpdates {
         by_node: indexmap!(
@@ -383,3 +527,326 @@ pub fn generate_validator_fee_fix_state_updates<S: SubstateDatabase>(db: &S) ->
         ),
     }
 }
+
+/// Generates the state updates required for the validator jailing protocol update:
+///
+/// * Replaces the Validator blueprint definition (and its auth config) with the one which knows the
+///   [`ValidatorStateV2`], the `unjail` and `apply_reliability` methods and the jailing events.
+/// * Updates the Consensus Manager blueprint definition's configuration field to the schema which
+///   knows the [`ConsensusManagerConfigurationV2`].
+/// * Migrates the Consensus Manager configuration to the [`ConsensusManagerConfigurationV2`], with
+///   the given jailing configuration.
+///
+/// The existing validator states are left in their [`ValidatorStateV1`] version, which is still
+/// valid against the new schema (and gets migrated whenever it is read).
+pub fn generate_validator_jailing_state_updates<S: SubstateDatabase>(
+    db: &S,
+    validator_jailing: Option<ValidatorJailingConfig>,
+) -> StateUpdates {
+    if let Some(validator_jailing) = &validator_jailing {
+        assert!(
+            validator_jailing.is_valid(),
+            "Invalid validator jailing configuration (the slash factor must be between 0 and 1): {:?}",
+            validator_jailing
+        );
+    }
+
+    let reader = SystemDatabaseReader::new(db);
+    let consensus_mgr_pkg_node_id = CONSENSUS_MANAGER_PACKAGE.into_node_id();
+    let consensus_mgr_node_id = CONSENSUS_MANAGER.into_node_id();
+    let consensus_mgr_bp_version_key = BlueprintVersionKey {
+        blueprint: CONSENSUS_MANAGER_BLUEPRINT.to_string(),
+        version: BlueprintVersion::default(),
+    };
+    let validator_bp_version_key = BlueprintVersionKey {
+        blueprint: VALIDATOR_BLUEPRINT.to_string(),
+        version: BlueprintVersion::default(),
+    };
+
+    // Generate the new Validator blueprint definition, auth config and schema substates
+    let (
+        new_validator_definition_substate,
+        new_validator_auth_config_substate,
+        new_validator_schema_substates,
+    ) = {
+        let definition = PackageDefinition {
+            blueprints: indexmap!(
+                CONSENSUS_MANAGER_BLUEPRINT.to_string() => ConsensusManagerBlueprint::definition(),
+                VALIDATOR_BLUEPRINT.to_string() => ValidatorBlueprint::validator_jailing_definition(),
+            ),
+        };
+        let mut package_structure = PackageNativePackage::validate_and_build_package_structure(
+            definition,
+            VmType::Native,
+            CONSENSUS_MANAGER_CODE_ID.to_be_bytes().to_vec(),
+            btreemap!(),
+            &VmVersion::default(),
+        )
+        .unwrap();
+
+        let validator_definition = package_structure
+            .definitions
+            .remove(VALIDATOR_BLUEPRINT)
+            .unwrap();
+        let validator_auth_config = package_structure
+            .auth_configs
+            .remove(VALIDATOR_BLUEPRINT)
+            .unwrap();
+        // Note: this also re-writes the (unchanged) Consensus Manager schema
+        let validator_schema_substates = package_structure
+            .schemas
+            .into_iter()
+            .map(|(schema_hash, schema)| {
+                (
+                    SubstateKey::Map(scrypto_encode(&schema_hash).unwrap()),
+                    DatabaseUpdate::Set(scrypto_encode(&schema.into_locked_substate()).unwrap()),
+                )
+            })
+            .collect::<IndexMap<_, _>>();
+
+        (
+            scrypto_encode(&validator_definition.into_locked_substate()).unwrap(),
+            scrypto_encode(&validator_auth_config.into_locked_substate()).unwrap(),
+            validator_schema_substates,
+        )
+    };
+
+    // Generate the new Consensus Manager configuration schema substate
+    let (new_config_schema_substate, config_type_id, new_config_schema_hash) = {
+        let mut aggregator = TypeAggregator::<ScryptoCustomTypeKind>::new();
+        let config_type_id = aggregator
+            .add_child_type_and_descendents::<ConsensusManagerConfigurationFieldPayload>();
+        let schema = generate_full_schema(aggregator);
+        let schema_hash = schema.generate_schema_hash();
+        let schema_substate = schema.into_locked_substate();
+        (
+            scrypto_encode(&schema_substate).unwrap(),
+            config_type_id,
+            schema_hash,
+        )
+    };
+
+    // Generate the Consensus Manager blueprint definition substate update
+    let updated_consensus_mgr_definition_substate = {
+        let versioned_definition: VersionedPackageBlueprintVersionDefinition = reader
+            .read_object_collection_entry(
+                &consensus_mgr_pkg_node_id,
+                ObjectModuleId::Main,
+                ObjectCollectionKey::KeyValue(
+                    PackageCollection::BlueprintVersionDefinitionKeyValue.collection_index(),
+                    &consensus_mgr_bp_version_key,
+                ),
+            )
+            .unwrap()
+            .unwrap();
+
+        let mut definition = versioned_definition.into_latest();
+
+        let (_, fields) = definition.interface.state.fields.as_mut().unwrap();
+        fields[ConsensusManagerField::Configuration.field_index() as usize].field =
+            BlueprintPayloadDef::Static(ScopedTypeId(new_config_schema_hash, config_type_id));
+
+        scrypto_encode(
+            &VersionedPackageBlueprintVersionDefinition::V1(definition).into_locked_substate(),
+        )
+        .unwrap()
+    };
+
+    // Migrate the Consensus Manager configuration
+    let updated_config_substate = {
+        let versioned_config: VersionedConsensusManagerConfiguration = reader
+            .read_typed_object_field(
+                &consensus_mgr_node_id,
+                ModuleId::Main,
+                ConsensusManagerField::Configuration.field_index(),
+            )
+            .unwrap();
+
+        let mut config = versioned_config.into_latest();
+        config.validator_jailing = validator_jailing;
+
+        scrypto_encode(&config.into_locked_substate()).unwrap()
+    };
+
+    let bp_definition_partition_num = reader
+        .get_partition_of_collection(
+            &consensus_mgr_pkg_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::BlueprintVersionDefinitionKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    let bp_auth_config_partition_num = reader
+        .get_partition_of_collection(
+            &consensus_mgr_pkg_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::BlueprintVersionAuthConfigKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    let schema_partition_num = reader
+        .get_partition_of_collection(
+            &consensus_mgr_pkg_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::SchemaKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    let mut schema_substates = new_validator_schema_substates;
+    schema_substates.insert(
+        SubstateKey::Map(scrypto_encode(&new_config_schema_hash).unwrap()),
+        DatabaseUpdate::Set(new_config_schema_substate),
+    );
+
+    StateUpdates {
+        by_node: indexmap!(
+            consensus_mgr_pkg_node_id => NodeStateUpdates::Delta {
+                by_partition: indexmap! {
+                    bp_definition_partition_num => PartitionStateUpdates::Delta {
+                        by_substate: indexmap! {
+                            SubstateKey::Map(scrypto_encode(&consensus_mgr_bp_version_key).unwrap()) => DatabaseUpdate::Set(
+                                updated_consensus_mgr_definition_substate
+                            ),
+                            SubstateKey::Map(scrypto_encode(&validator_bp_version_key).unwrap()) => DatabaseUpdate::Set(
+                                new_validator_definition_substate
+                            ),
+                        }
+                    },
+                    bp_auth_config_partition_num => PartitionStateUpdates::Delta {
+                        by_substate: indexmap! {
+                            SubstateKey::Map(scrypto_encode(&validator_bp_version_key).unwrap()) => DatabaseUpdate::Set(
+                                new_validator_auth_config_substate
+                            )
+                        }
+                    },
+                    schema_partition_num => PartitionStateUpdates::Delta {
+                        by_substate: schema_substates
+                    }
+                }
+            },
+            consensus_mgr_node_id => NodeStateUpdates::Delta {
+                by_partition: indexmap! {
+                    MAIN_BASE_PARTITION => PartitionStateUpdates::Delta {
+                        by_substate: indexmap! {
+                            SubstateKey::Field(ConsensusManagerField::Configuration.field_index()) => DatabaseUpdate::Set(
+                                updated_config_substate
+                            )
+                        }
+                    },
+                }
+            }
+        ),
+    }
+}
+
+/// Generates the state updates required for the subintents protocol update, which replaces the
+/// Transaction Processor blueprint definition with the one which knows the `run_with_subintents`
+/// and `run_subintent` functions.
+pub fn generate_subintents_state_updates<S: SubstateDatabase>(db: &S) -> StateUpdates {
+    generate_native_blueprint_definition_state_updates(
+        db,
+        TRANSACTION_PROCESSOR_PACKAGE,
+        TRANSACTION_PROCESSOR_BLUEPRINT,
+        TransactionProcessorNativePackage::subintents_definition(),
+        TRANSACTION_PROCESSOR_CODE_ID,
+    )
+}
+
+/// Generates the state updates which make the role assignment module accept the `CountOf` and
+/// `TimeRule` access rule nodes.
+///
+/// Only the role assignment module's own schema is updated, so these nodes can be assigned to a
+/// role through the role assignment module but not yet be passed to other blueprints whose
+/// schemas refer to access rules.
+pub fn generate_access_rule_extensions_state_updates<S: SubstateDatabase>(db: &S) -> StateUpdates {
+    generate_native_blueprint_definition_state_updates(
+        db,
+        ROLE_ASSIGNMENT_MODULE_PACKAGE,
+        ROLE_ASSIGNMENT_BLUEPRINT,
+        RoleAssignmentNativePackage::access_rule_extensions_definition(),
+        ROLE_ASSIGNMENT_CODE_ID,
+    )
+}
+
+/// Replaces the definition and schemas of the default version of a native blueprint.
+fn generate_native_blueprint_definition_state_updates<S: SubstateDatabase>(
+    db: &S,
+    package_address: PackageAddress,
+    blueprint_name: &str,
+    definition: PackageDefinition,
+    code_id: u64,
+) -> StateUpdates {
+    let reader = SystemDatabaseReader::new(db);
+    let pkg_node_id = package_address.into_node_id();
+    let bp_version_key = BlueprintVersionKey {
+        blueprint: blueprint_name.to_string(),
+        version: BlueprintVersion::default(),
+    };
+
+    // Generate the new blueprint definition and schema substates
+    let (new_definition_substate, new_schema_substates) = {
+        let mut package_structure = PackageNativePackage::validate_and_build_package_structure(
+            definition,
+            VmType::Native,
+            code_id.to_be_bytes().to_vec(),
+            btreemap!(),
+            &VmVersion::default(),
+        )
+        .unwrap();
+
+        let blueprint_definition = package_structure
+            .definitions
+            .remove(blueprint_name)
+            .unwrap();
+        let schema_substates = package_structure
+            .schemas
+            .into_iter()
+            .map(|(schema_hash, schema)| {
+                (
+                    SubstateKey::Map(scrypto_encode(&schema_hash).unwrap()),
+                    DatabaseUpdate::Set(scrypto_encode(&schema.into_locked_substate()).unwrap()),
+                )
+            })
+            .collect::<IndexMap<_, _>>();
+
+        (
+            scrypto_encode(&blueprint_definition.into_locked_substate()).unwrap(),
+            schema_substates,
+        )
+    };
+
+    let bp_definition_partition_num = reader
+        .get_partition_of_collection(
+            &pkg_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::BlueprintVersionDefinitionKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    let schema_partition_num = reader
+        .get_partition_of_collection(
+            &pkg_node_id,
+            ObjectModuleId::Main,
+            PackageCollection::SchemaKeyValue.collection_index(),
+        )
+        .unwrap();
+
+    StateUpdates {
+        by_node: indexmap!(
+            pkg_node_id => NodeStateUpdates::Delta {
+                by_partition: indexmap! {
+                    bp_definition_partition_num => PartitionStateUpdates::Delta {
+                        by_substate: indexmap! {
+                            SubstateKey::Map(scrypto_encode(&bp_version_key).unwrap()) => DatabaseUpdate::Set(
+                                new_definition_substate
+                            )
+                        }
+                    },
+                    schema_partition_num => PartitionStateUpdates::Delta {
+                        by_substate: new_schema_substates
+                    }
+                }
+            }
+        ),
+    }
+}
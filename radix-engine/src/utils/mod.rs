@@ -1,6 +1,7 @@
 #[cfg(feature = "coverage")]
 mod coverage;
 mod flash_state_generator;
+mod json;
 mod macros;
 mod native_blueprint_call_validator;
 mod package_extractor;
@@ -9,6 +10,7 @@ mod panics;
 #[cfg(feature = "coverage")]
 pub use coverage::*;
 pub use flash_state_generator::*;
+pub use json::*;
 pub use native_blueprint_call_validator::*;
 pub use package_extractor::*;
 pub use panics::*;
@@ -7,6 +7,7 @@ use crate::vm::VmApi;
 use radix_engine_interface::api::ClientApi;
 use radix_engine_interface::blueprints::package::CodeHash;
 use resources_tracker_macro::trace_resources;
+use sbor::rust::sync::Arc;
 
 pub struct ScryptoVm<W: WasmEngine> {
     pub wasm_engine: W,
@@ -23,6 +24,15 @@ impl<W: WasmEngine + Default> Default for ScryptoVm<W> {
 }
 
 impl<W: WasmEngine> ScryptoVm<W> {
+    /// Keeps the modules compiled by the WASM engine in the given (eg. on-disk) cache, see
+    /// `WasmEngine::with_instrumented_code_cache`.
+    pub fn with_instrumented_code_cache(self, cache: Arc<dyn InstrumentedCodeCache>) -> Self {
+        Self {
+            wasm_engine: self.wasm_engine.with_instrumented_code_cache(cache),
+            wasm_validator_config: self.wasm_validator_config,
+        }
+    }
+
     pub fn create_instance(
         &self,
         package_address: &PackageAddress,
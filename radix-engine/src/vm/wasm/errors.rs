@@ -157,6 +157,13 @@ pub enum WasmRuntimeError {
     InvalidBlsPublicKey(DecodeError),
     InvalidBlsSignature(DecodeError),
     InvalidBlsPublicKeyOrMessage(DecodeError),
+
+    InvalidKeyValueStoreScanCursor(DecodeError),
+
+    InvalidSortedKey(DecodeError),
+
+    /// Host function called while the instance isn't being invoked
+    InstanceNotInvoked,
 }
 
 impl SelfError for WasmRuntimeError {
@@ -0,0 +1,122 @@
+use crate::types::*;
+
+/// Identifies a module compiled by a WASM engine from instrumented code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompiledModuleCacheKey {
+    /// The hash of the instrumented code
+    pub instrumented_code_hash: Hash,
+    /// The fingerprint of the engine and the platform it compiles for
+    pub engine_fingerprint: Hash,
+}
+
+impl CompiledModuleCacheKey {
+    /// Returns a single hash of the whole key, eg. for use as a file name.
+    pub fn to_hash(&self) -> Hash {
+        let mut bytes = Vec::with_capacity(2 * Hash::LENGTH);
+        bytes.extend_from_slice(self.instrumented_code_hash.as_ref());
+        bytes.extend_from_slice(self.engine_fingerprint.as_ref());
+        hash(bytes)
+    }
+}
+
+/// A cache of the modules compiled by the WASM engine from validated and instrumented code,
+/// which may outlive the process.
+///
+/// It is only used when instantiating code which has been validated and instrumented when its
+/// package was published, and never when publishing a package, which always validates the code.
+/// The cached modules are executed as they are, so the cache must be as trusted as the substate
+/// database.
+pub trait InstrumentedCodeCache: Send + Sync {
+    fn get_compiled_module(&self, key: &CompiledModuleCacheKey) -> Option<Vec<u8>>;
+
+    /// Stores a compiled module. Failures are ignored, as the module can always be recompiled.
+    fn insert_compiled_module(&self, key: &CompiledModuleCacheKey, value: &[u8]);
+}
+
+/// An `InstrumentedCodeCache` storing each entry as a file in a directory, eg. next to the
+/// RocksDB database directory.
+#[cfg(feature = "std")]
+pub struct FileSystemInstrumentedCodeCache {
+    root: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileSystemInstrumentedCodeCache {
+    pub fn new(root: std::path::PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    fn entry_path(&self, entry_hash: Hash) -> std::path::PathBuf {
+        self.root.join(entry_hash.to_string())
+    }
+
+    /// Reads an entry, which is prefixed by the hash of its value. Entries whose value doesn't
+    /// match that hash, eg. because they have been truncated, are removed.
+    fn read(&self, entry_hash: Hash) -> Option<Vec<u8>> {
+        let path = self.entry_path(entry_hash);
+        let entry = std::fs::read(&path).ok()?;
+        if entry.len() >= Hash::LENGTH {
+            let (integrity_hash, value) = entry.split_at(Hash::LENGTH);
+            if hash(value).as_ref() == integrity_hash {
+                return Some(value.to_vec());
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+        None
+    }
+
+    fn write(&self, entry_hash: Hash, value: &[u8]) {
+        let mut entry = Vec::with_capacity(Hash::LENGTH + value.len());
+        entry.extend_from_slice(hash(value).as_ref());
+        entry.extend_from_slice(value);
+
+        // Write to a temporary file first, so that readers never observe a partial entry
+        let path = self.entry_path(entry_hash);
+        let temp_path = path.with_extension(format!("{:?}.tmp", std::thread::current().id()));
+        if std::fs::write(&temp_path, entry).is_ok() {
+            if std::fs::rename(&temp_path, &path).is_err() {
+                let _ = std::fs::remove_file(&temp_path);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl InstrumentedCodeCache for FileSystemInstrumentedCodeCache {
+    fn get_compiled_module(&self, key: &CompiledModuleCacheKey) -> Option<Vec<u8>> {
+        self.read(key.to_hash())
+    }
+
+    fn insert_compiled_module(&self, key: &CompiledModuleCacheKey, value: &[u8]) {
+        self.write(key.to_hash(), value)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_system_cache_discards_corrupted_entries() {
+        let root =
+            std::env::temp_dir().join(format!("instrumented-code-cache-{}", std::process::id()));
+        let cache = FileSystemInstrumentedCodeCache::new(root.clone()).unwrap();
+        let key = CompiledModuleCacheKey {
+            instrumented_code_hash: hash("code"),
+            engine_fingerprint: hash("engine"),
+        };
+
+        cache.insert_compiled_module(&key, b"module");
+        assert_eq!(cache.get_compiled_module(&key), Some(b"module".to_vec()));
+
+        // Truncate the entry, eg. as a crash could
+        let path = cache.entry_path(key.to_hash());
+        let entry = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &entry[..entry.len() - 1]).unwrap();
+        assert_eq!(cache.get_compiled_module(&key), None);
+        assert!(!path.exists());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
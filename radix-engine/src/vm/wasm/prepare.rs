@@ -13,6 +13,7 @@ use wasmparser::{ExternalKind, FuncType, Operator, Type, TypeRef, ValType, WasmF
 use super::WasmiModule;
 
 pub const SCRPYTO_VM_CRYPTO_UTILS_MINOR_VERSION: u64 = 1u64;
+pub const SCRYPTO_VM_COLLECTIONS_MINOR_VERSION: u64 = 2u64;
 
 #[derive(Debug)]
 pub struct WasmModule {
@@ -285,6 +286,56 @@ impl WasmModule {
                             ));
                         }
                     }
+                    KEY_VALUE_STORE_SCAN_KEYS_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                ],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    KEY_VALUE_STORE_LEN_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32],
+                                vec![ValType::I32],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
                     ACTOR_OPEN_FIELD_FUNCTION_NAME => {
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -301,6 +352,180 @@ impl WasmModule {
                             ));
                         }
                     }
+                    ACTOR_INDEX_INSERT_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                ],
+                                vec![],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_INDEX_REMOVE_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_INDEX_GET_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_INDEX_SCAN_KEYS_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_SORTED_INDEX_INSERT_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                ],
+                                vec![],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_SORTED_INDEX_REMOVE_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_SORTED_INDEX_SCAN_FUNCTION_NAME => {
+                        if minor_version < SCRYPTO_VM_COLLECTIONS_MINOR_VERSION {
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::ImportNotAllowed(entry.name.to_string()),
+                            ));
+                        }
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                ],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
                     FIELD_ENTRY_READ_FUNCTION_NAME => {
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -1412,6 +1637,32 @@ mod tests {
                 |w| WasmModule::enforce_import_constraints(w, 0u64)
             );
         }
+
+        for name in [
+            KEY_VALUE_STORE_SCAN_KEYS_FUNCTION_NAME,
+            KEY_VALUE_STORE_LEN_FUNCTION_NAME,
+            ACTOR_INDEX_INSERT_FUNCTION_NAME,
+            ACTOR_INDEX_REMOVE_FUNCTION_NAME,
+            ACTOR_INDEX_GET_FUNCTION_NAME,
+            ACTOR_INDEX_SCAN_KEYS_FUNCTION_NAME,
+            ACTOR_SORTED_INDEX_INSERT_FUNCTION_NAME,
+            ACTOR_SORTED_INDEX_REMOVE_FUNCTION_NAME,
+            ACTOR_SORTED_INDEX_SCAN_FUNCTION_NAME,
+        ] {
+            assert_invalid_wasm!(
+                wat.replace("name_to_replace", name),
+                PrepareError::InvalidImport(InvalidImport::ImportNotAllowed(name.to_string())),
+                |w| WasmModule::enforce_import_constraints(
+                    w,
+                    SCRYPTO_VM_COLLECTIONS_MINOR_VERSION - 1
+                )
+            );
+            assert_invalid_wasm!(
+                wat.replace("name_to_replace", name),
+                PrepareError::InvalidImport(InvalidImport::InvalidFunctionType(name.to_string())),
+                |w| WasmModule::enforce_import_constraints(w, SCRYPTO_VM_COLLECTIONS_MINOR_VERSION)
+            );
+        }
     }
 
     #[test]
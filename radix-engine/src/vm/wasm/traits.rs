@@ -1,10 +1,12 @@
 use crate::errors::InvokeError;
 use crate::types::*;
 use crate::vm::wasm::errors::*;
+use crate::vm::wasm::InstrumentedCodeCache;
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::ActorRefHandle;
 use radix_engine_interface::blueprints::package::CodeHash;
 use sbor::rust::boxed::Box;
+use sbor::rust::sync::Arc;
 use sbor::rust::vec::Vec;
 
 /// Represents the runtime that can be invoked by Scrypto modules.
@@ -105,6 +107,18 @@ pub trait WasmRuntime {
         key: Vec<u8>,
     ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
 
+    fn key_value_store_scan_keys(
+        &mut self,
+        node_id: Vec<u8>,
+        after_key: Vec<u8>,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn key_value_store_len(
+        &mut self,
+        node_id: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>>;
+
     fn instance_of(
         &mut self,
         object_id: Vec<u8>,
@@ -127,6 +141,58 @@ pub trait WasmRuntime {
         flags: u32,
     ) -> Result<SubstateHandle, InvokeError<WasmRuntimeError>>;
 
+    fn actor_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>>;
+
+    fn actor_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_index_get(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_index_scan_keys(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_sorted_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sorted_key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>>;
+
+    fn actor_sorted_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sorted_key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_sorted_index_scan(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        after_sorted_key: Vec<u8>,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
     fn field_entry_read(
         &mut self,
         handle: SubstateHandle,
@@ -257,4 +323,14 @@ pub trait WasmEngine {
     ///
     /// The code must have been validated and instrumented!
     fn instantiate(&self, code_hash: CodeHash, instrumented_code: &[u8]) -> Self::WasmInstance;
+
+    /// Keeps the compiled modules in the given cache too, so that they survive restarts.
+    ///
+    /// Engines whose modules can't be serialized don't use the cache.
+    fn with_instrumented_code_cache(self, _cache: Arc<dyn InstrumentedCodeCache>) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
 }
@@ -4,22 +4,22 @@ use crate::types::*;
 use crate::utils::save_coverage_data;
 use crate::vm::wasm::constants::*;
 use crate::vm::wasm::errors::*;
+use crate::vm::wasm::instrumented_code_cache::*;
 use crate::vm::wasm::traits::*;
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::blueprints::package::CodeHash;
+use sbor::rust::marker::PhantomData;
+use sbor::rust::mem::transmute;
+use sbor::rust::ptr;
 use sbor::rust::sync::{Arc, Mutex};
 #[cfg(feature = "radix_engine_tests")]
 use wasmer::ImportObject;
 use wasmer::{
-    imports, Function, HostEnvInitError, Instance, LazyInit, Module, RuntimeError, Store,
+    imports, Function, HostEnvInitError, Instance, LazyInit, Module, RuntimeError, Store, Target,
     Universal, Val, WasmerEnv,
 };
 use wasmer_compiler_singlepass::Singlepass;
 
-// IMPORTANT:
-// The below integration of Wasmer is not yet checked rigorously enough for production use
-// TODO: Address the below issues before considering production use.
-
 /// A `WasmerModule` defines a parsed WASM module, which is a template which can be instantiated.
 ///
 /// Unlike `WasmerInstance`, this is correctly `Send + Sync` - which is good, because this is the
@@ -30,52 +30,68 @@ pub struct WasmerModule {
     code_size_bytes: usize,
 }
 
-/// WARNING - this type should not actually be Send + Sync - it should really store a raw pointer,
-/// not a raw pointer masked as a usize.
+/// An instantiated `WasmerModule`.
 ///
-/// For information on why the pointer is masked, see the docs for `WasmerInstanceEnv`
+/// It's neither `Send` nor `Sync`: the runtime pointer it shares with its host functions is only
+/// valid on the thread which is currently invoking it.
 pub struct WasmerInstance {
     instance: Instance,
 
-    /// This field stores a (masked) runtime pointer to a `Box<dyn WasmRuntime>` which is shared
-    /// by the instance and each WasmerInstanceEnv (every function that requires `env`).
-    ///
-    /// On every call into the WASM (ie every call to `invoke_export`), a `&'a mut System API` is
-    /// wrapped in a temporary `RadixEngineWasmRuntime<'a>` and boxed, and a pointer to the freshly
-    /// created `Box<dyn WasmRuntime>` is written behind the Mutex into this field.
-    ///
-    /// This same Mutex (via Arc cloning) is shared into each `WasmerInstanceEnv`, and so
-    /// when the WASM makes calls back into env, it can read the pointer to the current
-    /// WasmRuntime, and use that to call into the `&mut System API`.
+    /// The runtime pointer shared by the instance and each `WasmerInstanceEnv` (every function
+    /// that requires `env`).
     ///
-    /// For information on why the pointer is masked, see the docs for `WasmerInstanceEnv`
-    runtime_ptr: Arc<Mutex<usize>>,
+    /// On every call into the WASM (ie every call to `invoke_export`), a pointer to the
+    /// `Box<dyn WasmRuntime>` wrapping the `&mut System API` is written behind the Mutex, and
+    /// cleared again once the call returns. When the WASM makes calls back into env, the host
+    /// functions read the pointer to the current WasmRuntime, and use that to call into the
+    /// `&mut System API`.
+    runtime_ptr: Arc<Mutex<WasmerRuntimePtr>>,
+
+    _not_send_sync: PhantomData<*const ()>,
+}
+
+/// A pointer to the `Box<dyn WasmRuntime>` of the ongoing `invoke_export` call, or null if the
+/// instance isn't being invoked.
+///
+/// Wasmer requires host environments to be `Send + Sync` (see `Function::new_native_with_env`),
+/// which raw pointers are not. Implementing both is sound here, because:
+/// * The pointer is only set for the duration of `WasmerInstance::invoke_export`, which holds a
+///   `&mut` borrow of the runtime for that whole time.
+/// * Wasmer runs the host functions of a (single-threaded) WASM call on the calling thread, and
+///   the `WasmerInstance` which owns the call can't be moved to another thread.
+/// * The host functions reject calls made while the pointer is null.
+struct WasmerRuntimePtr(*mut Box<dyn WasmRuntime>);
+
+unsafe impl Send for WasmerRuntimePtr {}
+unsafe impl Sync for WasmerRuntimePtr {}
+
+impl WasmerRuntimePtr {
+    fn null() -> Self {
+        Self(ptr::null_mut())
+    }
+}
+
+/// Clears the runtime pointer of an instance when dropped, so that it doesn't outlive the
+/// `invoke_export` call which set it (including on early returns and panics).
+struct WasmerRuntimePtrGuard<'a>(&'a Mutex<WasmerRuntimePtr>);
+
+impl<'a> Drop for WasmerRuntimePtrGuard<'a> {
+    fn drop(&mut self) {
+        if let Ok(mut ptr) = self.0.lock() {
+            *ptr = WasmerRuntimePtr::null();
+        }
+    }
 }
 
 /// The WasmerInstanceEnv implements WasmerEnv - and this needs to be `Send + Sync` for
 /// Wasmer to work (see `Function::new_native_with_env`).
 ///
-/// This is likely because Wasmer wants to be forward-compatible with multi-threaded WASM,
-/// or that it uses multiple threads internally.
-///
-/// Currently, the SystemAPI is not Sync (and so should not be accessed by multiple threads)
-/// we believe our use of Wasmer does not allow it to call us from multiple threads -
-/// but we need to double-check this.
-///
-/// In any case, we temporarily work around this incompatibility by masking the pointer as a usize.
-///
-/// There are still a number of changes we should consider to improve things:
-/// * `WasmerInstanceEnv` shouldn't contain an Instance - just a memory reference - see
-///    the docs on the `WasmerEnv` trait
-/// * If we instantiate the module just before we call into it, we could potentially pass an actual
-///   `Arc<Mutex<T>>` for `a', T: WasmRuntime<'a>` (wrapping a `&'a mut SystemAPI`) into the WasmerInstanceEnv
-///   on *module instantiation*. In this case, it doesn't need to be on a WasmerInstance at all
-/// * Else at the very least, change this to be a pointer type, and manually implement Sync/Send
+/// See `WasmerRuntimePtr` for why sharing the runtime pointer this way is sound.
 #[derive(Clone)]
 pub struct WasmerInstanceEnv {
     instance: LazyInit<Instance>,
     /// See notes on `WasmerInstance.runtime_ptr`
-    runtime_ptr: Arc<Mutex<usize>>,
+    runtime_ptr: Arc<Mutex<WasmerRuntimePtr>>,
 }
 
 pub struct WasmerEngine {
@@ -87,6 +103,10 @@ pub struct WasmerEngine {
     modules_cache: moka::sync::Cache<CodeHash, Arc<WasmerModule>>,
     #[cfg(feature = "radix_engine_fuzzing")]
     modules_cache: usize,
+    /// The persistent cache of the compiled modules, see `with_instrumented_code_cache`
+    instrumented_code_cache: Option<Arc<dyn InstrumentedCodeCache>>,
+    /// The fingerprint of the compiler and the target the modules are compiled for
+    engine_fingerprint: Hash,
 }
 
 pub fn read_memory(instance: &Instance, ptr: u32, len: u32) -> Result<Vec<u8>, WasmRuntimeError> {
@@ -99,7 +119,7 @@ pub fn read_memory(instance: &Instance, ptr: u32, len: u32) -> Result<Vec<u8>, W
         .map_err(|_| WasmRuntimeError::MemoryAccessError)?;
     let memory_slice = unsafe { memory.data_unchecked() };
     let memory_size = memory_slice.len();
-    if ptr > memory_size || ptr + len > memory_size {
+    if ptr.checked_add(len).map_or(true, |end| end > memory_size) {
         return Err(WasmRuntimeError::MemoryAccessError);
     }
 
@@ -116,7 +136,7 @@ pub fn write_memory(instance: &Instance, ptr: u32, data: &[u8]) -> Result<(), Wa
         .map_err(|_| WasmRuntimeError::MemoryAccessError)?;
     let memory_slice = unsafe { memory.data_unchecked_mut() };
     let memory_size = memory_slice.len();
-    if ptr > memory_size || ptr + len > memory_size {
+    if ptr.checked_add(len).map_or(true, |end| end > memory_size) {
         return Err(WasmRuntimeError::MemoryAccessError);
     }
 
@@ -150,9 +170,17 @@ impl WasmerEnv for WasmerInstanceEnv {
 
 macro_rules! grab_runtime {
     ($env: expr) => {{
-        let instance = unsafe { $env.instance.get_unchecked() };
-        let ptr = $env.runtime_ptr.lock().expect("Runtime ptr unavailable");
-        let runtime: &mut Box<dyn WasmRuntime> = unsafe { &mut *(*ptr as *mut _) };
+        let instance = $env
+            .instance
+            .get_ref()
+            .ok_or(WasmRuntimeError::InstanceNotInvoked)?;
+        let ptr = $env.runtime_ptr.lock().expect("Runtime ptr unavailable").0;
+        if ptr.is_null() {
+            return Err(WasmRuntimeError::InstanceNotInvoked.into());
+        }
+        // SAFETY: a non-null pointer is only ever set by an ongoing `invoke_export` call, see
+        // `WasmerRuntimePtr`.
+        let runtime: &mut Box<dyn WasmRuntime> = unsafe { &mut *ptr };
         (instance, runtime)
     }};
 }
@@ -421,6 +449,35 @@ impl WasmerModule {
                 .map(|buffer| buffer.0)
         }
 
+        pub fn key_value_store_scan_keys(
+            env: &WasmerInstanceEnv,
+            node_id_ptr: u32,
+            node_id_len: u32,
+            after_key_ptr: u32,
+            after_key_len: u32,
+            limit: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime
+                .key_value_store_scan_keys(
+                    read_memory(&instance, node_id_ptr, node_id_len)?,
+                    read_memory(&instance, after_key_ptr, after_key_len)?,
+                    limit,
+                )
+                .map(|buffer| buffer.0)
+        }
+
+        pub fn key_value_store_len(
+            env: &WasmerInstanceEnv,
+            node_id_ptr: u32,
+            node_id_len: u32,
+        ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime.key_value_store_len(read_memory(&instance, node_id_ptr, node_id_len)?)
+        }
+
         pub fn key_value_entry_read(
             env: &WasmerInstanceEnv,
             handle: u32,
@@ -505,6 +562,131 @@ impl WasmerModule {
             runtime.actor_open_field(object_handle, field, flags)
         }
 
+        pub fn actor_index_insert(
+            env: &WasmerInstanceEnv,
+            object_handle: u32,
+            collection_index: u32,
+            key_ptr: u32,
+            key_len: u32,
+            value_ptr: u32,
+            value_len: u32,
+        ) -> Result<(), InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime.actor_index_insert(
+                object_handle,
+                collection_index as u8,
+                read_memory(&instance, key_ptr, key_len)?,
+                read_memory(&instance, value_ptr, value_len)?,
+            )
+        }
+
+        pub fn actor_index_remove(
+            env: &WasmerInstanceEnv,
+            object_handle: u32,
+            collection_index: u32,
+            key_ptr: u32,
+            key_len: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime
+                .actor_index_remove(
+                    object_handle,
+                    collection_index as u8,
+                    read_memory(&instance, key_ptr, key_len)?,
+                )
+                .map(|buffer| buffer.0)
+        }
+
+        pub fn actor_index_get(
+            env: &WasmerInstanceEnv,
+            object_handle: u32,
+            collection_index: u32,
+            key_ptr: u32,
+            key_len: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime
+                .actor_index_get(
+                    object_handle,
+                    collection_index as u8,
+                    read_memory(&instance, key_ptr, key_len)?,
+                )
+                .map(|buffer| buffer.0)
+        }
+
+        pub fn actor_index_scan_keys(
+            env: &WasmerInstanceEnv,
+            object_handle: u32,
+            collection_index: u32,
+            limit: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (_instance, runtime) = grab_runtime!(env);
+
+            runtime
+                .actor_index_scan_keys(object_handle, collection_index as u8, limit)
+                .map(|buffer| buffer.0)
+        }
+
+        pub fn actor_sorted_index_insert(
+            env: &WasmerInstanceEnv,
+            object_handle: u32,
+            collection_index: u32,
+            sorted_key_ptr: u32,
+            sorted_key_len: u32,
+            value_ptr: u32,
+            value_len: u32,
+        ) -> Result<(), InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime.actor_sorted_index_insert(
+                object_handle,
+                collection_index as u8,
+                read_memory(&instance, sorted_key_ptr, sorted_key_len)?,
+                read_memory(&instance, value_ptr, value_len)?,
+            )
+        }
+
+        pub fn actor_sorted_index_remove(
+            env: &WasmerInstanceEnv,
+            object_handle: u32,
+            collection_index: u32,
+            sorted_key_ptr: u32,
+            sorted_key_len: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime
+                .actor_sorted_index_remove(
+                    object_handle,
+                    collection_index as u8,
+                    read_memory(&instance, sorted_key_ptr, sorted_key_len)?,
+                )
+                .map(|buffer| buffer.0)
+        }
+
+        pub fn actor_sorted_index_scan(
+            env: &WasmerInstanceEnv,
+            object_handle: u32,
+            collection_index: u32,
+            after_sorted_key_ptr: u32,
+            after_sorted_key_len: u32,
+            limit: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            runtime
+                .actor_sorted_index_scan(
+                    object_handle,
+                    collection_index as u8,
+                    read_memory(&instance, after_sorted_key_ptr, after_sorted_key_len)?,
+                    limit,
+                )
+                .map(|buffer| buffer.0)
+        }
+
         pub fn actor_get_node_id(
             env: &WasmerInstanceEnv,
             actor_ref_handle: u32,
@@ -817,7 +999,7 @@ impl WasmerModule {
         // env
         let env = WasmerInstanceEnv {
             instance: LazyInit::new(),
-            runtime_ptr: Arc::new(Mutex::new(0)),
+            runtime_ptr: Arc::new(Mutex::new(WasmerRuntimePtr::null())),
         };
 
         // imports
@@ -837,6 +1019,8 @@ impl WasmerModule {
                 KEY_VALUE_STORE_NEW_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_store_new),
                 KEY_VALUE_STORE_OPEN_ENTRY_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_store_open_entry),
                 KEY_VALUE_STORE_REMOVE_ENTRY_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_store_remove_entry),
+                KEY_VALUE_STORE_SCAN_KEYS_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_store_scan_keys),
+                KEY_VALUE_STORE_LEN_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_store_len),
                 KEY_VALUE_ENTRY_READ_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_entry_read),
                 KEY_VALUE_ENTRY_WRITE_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_entry_write),
                 KEY_VALUE_ENTRY_REMOVE_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), key_value_entry_remove),
@@ -845,6 +1029,13 @@ impl WasmerModule {
                 FIELD_ENTRY_WRITE_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), field_entry_write),
                 FIELD_ENTRY_CLOSE_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), field_entry_close),
                 ACTOR_OPEN_FIELD_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_open_field),
+                ACTOR_INDEX_INSERT_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_index_insert),
+                ACTOR_INDEX_REMOVE_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_index_remove),
+                ACTOR_INDEX_GET_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_index_get),
+                ACTOR_INDEX_SCAN_KEYS_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_index_scan_keys),
+                ACTOR_SORTED_INDEX_INSERT_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_sorted_index_insert),
+                ACTOR_SORTED_INDEX_REMOVE_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_sorted_index_remove),
+                ACTOR_SORTED_INDEX_SCAN_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_sorted_index_scan),
                 ACTOR_GET_OBJECT_ID_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_get_node_id),
                 ACTOR_GET_PACKAGE_ADDRESS_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_get_package_address),
                 ACTOR_GET_BLUEPRINT_NAME_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), actor_get_blueprint_name),
@@ -905,6 +1096,7 @@ impl WasmerModule {
         WasmerInstance {
             instance,
             runtime_ptr: env.runtime_ptr,
+            _not_send_sync: PhantomData,
         }
     }
 }
@@ -926,11 +1118,12 @@ impl WasmInstance for WasmerInstance {
         args: Vec<Buffer>,
         runtime: &mut Box<dyn WasmRuntime + 'r>,
     ) -> Result<Vec<u8>, InvokeError<WasmRuntimeError>> {
-        {
-            // set up runtime pointer
-            let mut guard = self.runtime_ptr.lock().expect("Runtime ptr unavailable");
-            *guard = runtime as *mut _ as usize;
-        }
+        // set up runtime pointer, which is cleared by the guard once the call completes.
+        // The `'r` lifetime is erased, which is fine because the pointer never outlives this call.
+        *self.runtime_ptr.lock().expect("Runtime ptr unavailable") = WasmerRuntimePtr(unsafe {
+            transmute::<*mut Box<dyn WasmRuntime + 'r>, *mut Box<dyn WasmRuntime>>(runtime)
+        });
+        let _runtime_ptr_guard = WasmerRuntimePtrGuard(&self.runtime_ptr);
 
         let input: Vec<Val> = args
             .into_iter()
@@ -1000,6 +1193,8 @@ impl Default for WasmerEngine {
 impl WasmerEngine {
     pub fn new(options: WasmerEngineOptions) -> Self {
         let compiler = Singlepass::new();
+        let target = Target::default();
+        let engine_fingerprint = Self::fingerprint(&compiler, &target);
 
         #[cfg(all(not(feature = "radix_engine_fuzzing"), not(feature = "moka")))]
         let modules_cache = RefCell::new(lru::LruCache::new(
@@ -1019,9 +1214,51 @@ impl WasmerEngine {
         let modules_cache = options.max_cache_size;
 
         Self {
-            store: Store::new(&Universal::new(compiler).engine()),
+            store: Store::new(&Universal::new(compiler).target(target).engine()),
             modules_cache,
+            instrumented_code_cache: None,
+            engine_fingerprint,
+        }
+    }
+
+    /// Returns the fingerprint of the artifacts compiled by this engine: the Wasmer version, the
+    /// compiler configuration and the target, including its CPU features, as the artifacts can
+    /// only be loaded by an identical engine.
+    fn fingerprint(compiler: &Singlepass, target: &Target) -> Hash {
+        hash(format!(
+            "wasmer {} {:?} {} {:?}",
+            wasmer::VERSION,
+            compiler,
+            target.triple(),
+            target.cpu_features(),
+        ))
+    }
+
+    fn compile_module(&self, instrumented_code: &[u8]) -> Module {
+        let Some(cache) = &self.instrumented_code_cache else {
+            return Module::new(&self.store, instrumented_code)
+                .expect("Failed to parse WASM module");
+        };
+
+        let key = CompiledModuleCacheKey {
+            instrumented_code_hash: hash(instrumented_code),
+            engine_fingerprint: self.engine_fingerprint,
+        };
+        // Safety: the cached artifacts are as trusted as the substate database, see
+        // `InstrumentedCodeCache`
+        if let Some(module) = cache
+            .get_compiled_module(&key)
+            .and_then(|artifact| unsafe { Module::deserialize(&self.store, &artifact) }.ok())
+        {
+            return module;
+        }
+
+        let module =
+            Module::new(&self.store, instrumented_code).expect("Failed to parse WASM module");
+        if let Ok(artifact) = module.serialize() {
+            cache.insert_compiled_module(&key, &artifact);
         }
+        module
     }
 }
 
@@ -1044,8 +1281,7 @@ impl WasmEngine for WasmerEngine {
         }
 
         let new_module = Arc::new(WasmerModule {
-            module: Module::new(&self.store, instrumented_code)
-                .expect("Failed to parse WASM module"),
+            module: self.compile_module(instrumented_code),
             code_size_bytes: instrumented_code.len(),
         });
 
@@ -1061,4 +1297,11 @@ impl WasmEngine for WasmerEngine {
 
         new_module.instantiate()
     }
+
+    fn with_instrumented_code_cache(self, cache: Arc<dyn InstrumentedCodeCache>) -> Self {
+        Self {
+            instrumented_code_cache: Some(cache),
+            ..self
+        }
+    }
 }
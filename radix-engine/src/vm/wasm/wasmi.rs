@@ -504,6 +504,39 @@ fn key_value_store_remove(
         .map(|buffer| buffer.0)
 }
 
+fn key_value_store_scan_keys(
+    mut caller: Caller<'_, HostState>,
+    node_id_ptr: u32,
+    node_id_len: u32,
+    after_key_ptr: u32,
+    after_key_len: u32,
+    limit: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let node_id = read_memory(caller.as_context_mut(), memory, node_id_ptr, node_id_len)?;
+    let after_key = read_memory(
+        caller.as_context_mut(),
+        memory,
+        after_key_ptr,
+        after_key_len,
+    )?;
+
+    runtime
+        .key_value_store_scan_keys(node_id, after_key, limit)
+        .map(|buffer| buffer.0)
+}
+
+fn key_value_store_len(
+    mut caller: Caller<'_, HostState>,
+    node_id_ptr: u32,
+    node_id_len: u32,
+) -> Result<u32, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let node_id = read_memory(caller.as_context_mut(), memory, node_id_ptr, node_id_len)?;
+
+    runtime.key_value_store_len(node_id)
+}
+
 fn lock_field(
     caller: Caller<'_, HostState>,
     object_handle: u32,
@@ -514,6 +547,132 @@ fn lock_field(
     runtime.actor_open_field(object_handle, field as u8, flags)
 }
 
+fn actor_index_insert(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    key_ptr: u32,
+    key_len: u32,
+    value_ptr: u32,
+    value_len: u32,
+) -> Result<(), InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let key = read_memory(caller.as_context_mut(), memory, key_ptr, key_len)?;
+    let value = read_memory(caller.as_context_mut(), memory, value_ptr, value_len)?;
+
+    runtime.actor_index_insert(object_handle, collection_index as u8, key, value)
+}
+
+fn actor_index_remove(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    key_ptr: u32,
+    key_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let key = read_memory(caller.as_context_mut(), memory, key_ptr, key_len)?;
+
+    runtime
+        .actor_index_remove(object_handle, collection_index as u8, key)
+        .map(|buffer| buffer.0)
+}
+
+fn actor_index_get(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    key_ptr: u32,
+    key_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let key = read_memory(caller.as_context_mut(), memory, key_ptr, key_len)?;
+
+    runtime
+        .actor_index_get(object_handle, collection_index as u8, key)
+        .map(|buffer| buffer.0)
+}
+
+fn actor_index_scan_keys(
+    caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    limit: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (_memory, runtime) = grab_runtime!(caller);
+
+    runtime
+        .actor_index_scan_keys(object_handle, collection_index as u8, limit)
+        .map(|buffer| buffer.0)
+}
+
+fn actor_sorted_index_insert(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    sorted_key_ptr: u32,
+    sorted_key_len: u32,
+    value_ptr: u32,
+    value_len: u32,
+) -> Result<(), InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let sorted_key = read_memory(
+        caller.as_context_mut(),
+        memory,
+        sorted_key_ptr,
+        sorted_key_len,
+    )?;
+    let value = read_memory(caller.as_context_mut(), memory, value_ptr, value_len)?;
+
+    runtime.actor_sorted_index_insert(object_handle, collection_index as u8, sorted_key, value)
+}
+
+fn actor_sorted_index_remove(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    sorted_key_ptr: u32,
+    sorted_key_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let sorted_key = read_memory(
+        caller.as_context_mut(),
+        memory,
+        sorted_key_ptr,
+        sorted_key_len,
+    )?;
+
+    runtime
+        .actor_sorted_index_remove(object_handle, collection_index as u8, sorted_key)
+        .map(|buffer| buffer.0)
+}
+
+fn actor_sorted_index_scan(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    after_sorted_key_ptr: u32,
+    after_sorted_key_len: u32,
+    limit: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+    let after_sorted_key = read_memory(
+        caller.as_context_mut(),
+        memory,
+        after_sorted_key_ptr,
+        after_sorted_key_len,
+    )?;
+
+    runtime
+        .actor_sorted_index_scan(
+            object_handle,
+            collection_index as u8,
+            after_sorted_key,
+            limit,
+        )
+        .map(|buffer| buffer.0)
+}
+
 fn field_lock_read(
     caller: Caller<'_, HostState>,
     handle: u32,
@@ -1208,6 +1367,37 @@ impl WasmiModule {
             },
         );
 
+        let host_key_value_store_scan_keys = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             node_id_ptr: u32,
+             node_id_len: u32,
+             after_key_ptr: u32,
+             after_key_len: u32,
+             limit: u32|
+             -> Result<u64, Trap> {
+                key_value_store_scan_keys(
+                    caller,
+                    node_id_ptr,
+                    node_id_len,
+                    after_key_ptr,
+                    after_key_len,
+                    limit,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
+        let host_key_value_store_len = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             node_id_ptr: u32,
+             node_id_len: u32|
+             -> Result<u32, Trap> {
+                key_value_store_len(caller, node_id_ptr, node_id_len).map_err(|e| e.into())
+            },
+        );
+
         let host_lock_field = Func::wrap(
             store.as_context_mut(),
             |caller: Caller<'_, HostState>,
@@ -1219,6 +1409,130 @@ impl WasmiModule {
             },
         );
 
+        let host_actor_index_insert = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             key_ptr: u32,
+             key_len: u32,
+             value_ptr: u32,
+             value_len: u32|
+             -> Result<(), Trap> {
+                actor_index_insert(
+                    caller,
+                    object_handle,
+                    collection_index,
+                    key_ptr,
+                    key_len,
+                    value_ptr,
+                    value_len,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
+        let host_actor_index_remove = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             key_ptr: u32,
+             key_len: u32|
+             -> Result<u64, Trap> {
+                actor_index_remove(caller, object_handle, collection_index, key_ptr, key_len)
+                    .map_err(|e| e.into())
+            },
+        );
+
+        let host_actor_index_get = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             key_ptr: u32,
+             key_len: u32|
+             -> Result<u64, Trap> {
+                actor_index_get(caller, object_handle, collection_index, key_ptr, key_len)
+                    .map_err(|e| e.into())
+            },
+        );
+
+        let host_actor_index_scan_keys = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             limit: u32|
+             -> Result<u64, Trap> {
+                actor_index_scan_keys(caller, object_handle, collection_index, limit)
+                    .map_err(|e| e.into())
+            },
+        );
+
+        let host_actor_sorted_index_insert = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             sorted_key_ptr: u32,
+             sorted_key_len: u32,
+             value_ptr: u32,
+             value_len: u32|
+             -> Result<(), Trap> {
+                actor_sorted_index_insert(
+                    caller,
+                    object_handle,
+                    collection_index,
+                    sorted_key_ptr,
+                    sorted_key_len,
+                    value_ptr,
+                    value_len,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
+        let host_actor_sorted_index_remove = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             sorted_key_ptr: u32,
+             sorted_key_len: u32|
+             -> Result<u64, Trap> {
+                actor_sorted_index_remove(
+                    caller,
+                    object_handle,
+                    collection_index,
+                    sorted_key_ptr,
+                    sorted_key_len,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
+        let host_actor_sorted_index_scan = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             after_sorted_key_ptr: u32,
+             after_sorted_key_len: u32,
+             limit: u32|
+             -> Result<u64, Trap> {
+                actor_sorted_index_scan(
+                    caller,
+                    object_handle,
+                    collection_index,
+                    after_sorted_key_ptr,
+                    after_sorted_key_len,
+                    limit,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
         let host_field_lock_read = Func::wrap(
             store.as_context_mut(),
             |caller: Caller<'_, HostState>, handle: u32| -> Result<u64, Trap> {
@@ -1498,6 +1812,37 @@ impl WasmiModule {
             host_get_outer_object
         );
         linker_define!(linker, ACTOR_OPEN_FIELD_FUNCTION_NAME, host_lock_field);
+        linker_define!(
+            linker,
+            ACTOR_INDEX_INSERT_FUNCTION_NAME,
+            host_actor_index_insert
+        );
+        linker_define!(
+            linker,
+            ACTOR_INDEX_REMOVE_FUNCTION_NAME,
+            host_actor_index_remove
+        );
+        linker_define!(linker, ACTOR_INDEX_GET_FUNCTION_NAME, host_actor_index_get);
+        linker_define!(
+            linker,
+            ACTOR_INDEX_SCAN_KEYS_FUNCTION_NAME,
+            host_actor_index_scan_keys
+        );
+        linker_define!(
+            linker,
+            ACTOR_SORTED_INDEX_INSERT_FUNCTION_NAME,
+            host_actor_sorted_index_insert
+        );
+        linker_define!(
+            linker,
+            ACTOR_SORTED_INDEX_REMOVE_FUNCTION_NAME,
+            host_actor_sorted_index_remove
+        );
+        linker_define!(
+            linker,
+            ACTOR_SORTED_INDEX_SCAN_FUNCTION_NAME,
+            host_actor_sorted_index_scan
+        );
 
         linker_define!(
             linker,
@@ -1534,6 +1879,16 @@ impl WasmiModule {
             KEY_VALUE_STORE_REMOVE_ENTRY_FUNCTION_NAME,
             host_key_value_store_remove
         );
+        linker_define!(
+            linker,
+            KEY_VALUE_STORE_SCAN_KEYS_FUNCTION_NAME,
+            host_key_value_store_scan_keys
+        );
+        linker_define!(
+            linker,
+            KEY_VALUE_STORE_LEN_FUNCTION_NAME,
+            host_key_value_store_len
+        );
 
         linker_define!(linker, FIELD_ENTRY_READ_FUNCTION_NAME, host_field_lock_read);
         linker_define!(
@@ -1810,6 +2165,8 @@ pub struct WasmiEngineOptions {
     max_cache_size: usize,
 }
 
+/// Unlike the `WasmerEngine`, this keeps compiled modules in memory only, as `wasmi` modules
+/// can't be serialized; see `WasmEngine::with_instrumented_code_cache`.
 pub struct WasmiEngine {
     // This flag disables cache in wasm_instrumenter/wasmi/wasmer to prevent non-determinism when fuzzing
     #[cfg(all(not(feature = "radix_engine_fuzzing"), not(feature = "moka")))]
@@ -309,6 +309,37 @@ where
         self.allocate_buffer(rtn)
     }
 
+    fn key_value_store_scan_keys(
+        &mut self,
+        node_id: Vec<u8>,
+        after_key: Vec<u8>,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let node_id = NodeId(
+            TryInto::<[u8; NodeId::LENGTH]>::try_into(node_id.as_ref())
+                .map_err(|_| WasmRuntimeError::InvalidNodeId)?,
+        );
+        let after_key = scrypto_decode::<Option<Vec<u8>>>(&after_key)
+            .map_err(WasmRuntimeError::InvalidKeyValueStoreScanCursor)?;
+        let keys = self
+            .api
+            .key_value_store_scan_keys(&node_id, after_key.as_ref(), limit)?;
+        let buffer = scrypto_encode(&keys).expect("Failed to encode scanned keys");
+        self.allocate_buffer(buffer)
+    }
+
+    fn key_value_store_len(
+        &mut self,
+        node_id: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+        let node_id = NodeId(
+            TryInto::<[u8; NodeId::LENGTH]>::try_into(node_id.as_ref())
+                .map_err(|_| WasmRuntimeError::InvalidNodeId)?,
+        );
+        let len = self.api.key_value_store_len(&node_id)?;
+        Ok(len)
+    }
+
     fn actor_open_field(
         &mut self,
         object_handle: u32,
@@ -321,6 +352,112 @@ where
         Ok(handle)
     }
 
+    fn actor_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>> {
+        self.api
+            .actor_index_insert(object_handle, collection_index, key, value)?;
+
+        Ok(())
+    }
+
+    fn actor_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let rtn = self
+            .api
+            .actor_index_remove(object_handle, collection_index, key)?;
+        let buffer = scrypto_encode(&rtn).expect("Failed to encode removed value");
+
+        self.allocate_buffer(buffer)
+    }
+
+    fn actor_index_get(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let rtn = self
+            .api
+            .actor_index_get(object_handle, collection_index, key)?;
+        let buffer = scrypto_encode(&rtn).expect("Failed to encode value");
+
+        self.allocate_buffer(buffer)
+    }
+
+    fn actor_index_scan_keys(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let keys = self
+            .api
+            .actor_index_scan_keys(object_handle, collection_index, limit)?;
+        let buffer = scrypto_encode(&keys).expect("Failed to encode scanned keys");
+
+        self.allocate_buffer(buffer)
+    }
+
+    fn actor_sorted_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sorted_key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>> {
+        let sorted_key =
+            scrypto_decode::<SortedKey>(&sorted_key).map_err(WasmRuntimeError::InvalidSortedKey)?;
+        self.api
+            .actor_sorted_index_insert(object_handle, collection_index, sorted_key, value)?;
+
+        Ok(())
+    }
+
+    fn actor_sorted_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sorted_key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let sorted_key =
+            scrypto_decode::<SortedKey>(&sorted_key).map_err(WasmRuntimeError::InvalidSortedKey)?;
+        let rtn =
+            self.api
+                .actor_sorted_index_remove(object_handle, collection_index, &sorted_key)?;
+        let buffer = scrypto_encode(&rtn).expect("Failed to encode removed value");
+
+        self.allocate_buffer(buffer)
+    }
+
+    fn actor_sorted_index_scan(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        after_sorted_key: Vec<u8>,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let after_sorted_key = scrypto_decode::<Option<SortedKey>>(&after_sorted_key)
+            .map_err(WasmRuntimeError::InvalidSortedKey)?;
+        let entries = self.api.actor_sorted_index_scan_after(
+            object_handle,
+            collection_index,
+            after_sorted_key.as_ref(),
+            limit,
+        )?;
+        let buffer = scrypto_encode(&entries).expect("Failed to encode scanned entries");
+
+        self.allocate_buffer(buffer)
+    }
+
     fn field_entry_read(
         &mut self,
         handle: SubstateHandle,
@@ -225,6 +225,13 @@ impl CustomSchema for NoCustomSchema {
         unreachable!("No custom type kinds exist")
     }
 
+    fn custom_type_validation_is_backwards_compatible(
+        _: &Self::CustomTypeValidation,
+        _: &Self::CustomTypeValidation,
+    ) -> bool {
+        unreachable!("No custom type validation")
+    }
+
     fn empty_schema() -> &'static Schema<Self> {
         &EMPTY_SCHEMA
     }
@@ -324,6 +331,23 @@ mod serde_serialization {
             unreachable!("No custom values exist")
         }
     }
+
+    impl DeserializableCustomExtension for NoCustomExtension {
+        type CustomValue = NoCustomValue;
+        type CustomDeserializationContext<'a> = ();
+
+        fn custom_value_kind_from_name(_: &str) -> Option<Self::CustomValueKind> {
+            None
+        }
+
+        fn parse_custom_value<'a>(
+            _: &Self::CustomDeserializationContext<'a>,
+            _: Self::CustomValueKind,
+            _: &str,
+        ) -> Result<Self::CustomValue, String> {
+            unreachable!("No custom values exist")
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,927 @@
+use super::*;
+use crate::rust::fmt;
+use crate::rust::prelude::*;
+use crate::*;
+use serde::de::{DeserializeSeed, Deserializer, Error as _, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+/// The parameters of a deserialization of a serde value (eg JSON) in the
+/// [`SerializationMode::Programmatic`] format back into an SBOR value.
+///
+/// Both the annotated (ie produced with a schema) and the non-annotated format are accepted. Any
+/// name annotations which are present are checked against the schema.
+pub enum DeserializationParameters<'s, 'a, E: DeserializableCustomExtension> {
+    Schemaless {
+        custom_context: E::CustomDeserializationContext<'a>,
+        depth_limit: usize,
+    },
+    WithSchema {
+        custom_context: E::CustomDeserializationContext<'a>,
+        schema: &'s Schema<E::CustomSchema>,
+        type_id: LocalTypeId,
+        depth_limit: usize,
+    },
+}
+
+impl<'s, 'a, E: DeserializableCustomExtension> DeserializationParameters<'s, 'a, E> {
+    pub fn get_context_params(&self) -> (DeserializationContext<'s, 'a, E>, LocalTypeId, usize) {
+        match self {
+            DeserializationParameters::Schemaless {
+                custom_context,
+                depth_limit,
+            } => (
+                DeserializationContext {
+                    schema: E::CustomSchema::empty_schema(),
+                    custom_context: *custom_context,
+                },
+                LocalTypeId::any(),
+                *depth_limit,
+            ),
+            DeserializationParameters::WithSchema {
+                custom_context,
+                schema,
+                type_id,
+                depth_limit,
+            } => (
+                DeserializationContext {
+                    schema: *schema,
+                    custom_context: *custom_context,
+                },
+                *type_id,
+                *depth_limit,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DeserializationContext<'s, 'a, E: DeserializableCustomExtension> {
+    pub schema: &'s Schema<E::CustomSchema>,
+    pub custom_context: E::CustomDeserializationContext<'a>,
+}
+
+/// A value deserialized from its serde representation, together with its encoded payload, which
+/// has been validated against the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializedPayload<E: DeserializableCustomExtension> {
+    pub value: Value<E::CustomValueKind, E::CustomValue>,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeserializationError {
+    /// The serde value does not represent a value of the expected type.
+    /// The path is given in the same format as in the payload validation errors.
+    InvalidValue { path: String, cause: String },
+    /// The value was successfully constructed, but could not be encoded.
+    EncodeError(EncodeError),
+    /// The encoded value is not valid against the schema.
+    ValidationError(String),
+}
+
+impl fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeserializationError::InvalidValue { path, cause } => {
+                write!(f, "[ERROR] value path: {}, cause: {}", path, cause)
+            }
+            DeserializationError::EncodeError(error) => write!(f, "[ERROR] {:?}", error),
+            DeserializationError::ValidationError(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Deserializes a serde value (eg JSON) in the [`SerializationMode::Programmatic`] format into an
+/// SBOR value, and encodes and validates it against the schema.
+///
+/// You can use it with eg serde_json as follows:
+/// ```ignore
+///     let mut json_deserializer = serde_json::Deserializer::from_str(&json);
+///     let deserialized = deserialize_payload(
+///         &mut json_deserializer,
+///         &DeserializationParameters::WithSchema { /* ... */ },
+///     )?;
+/// ```
+pub fn deserialize_payload<'de, D: Deserializer<'de>, E: DeserializableCustomExtension>(
+    deserializer: D,
+    parameters: &DeserializationParameters<'_, '_, E>,
+) -> Result<DeserializedPayload<E>, D::Error> {
+    let serde_value = SerdeValue::deserialize(deserializer)?;
+    deserialize_payload_from_serde_value(&serde_value, parameters).map_err(D::Error::custom)
+}
+
+/// A [`DeserializeSeed`] wrapper of [`deserialize_payload`], so that SBOR values can be
+/// deserialized as parts of larger serde models.
+pub struct PayloadDeserializeSeed<'p, 's, 'a, E: DeserializableCustomExtension> {
+    pub parameters: &'p DeserializationParameters<'s, 'a, E>,
+}
+
+impl<'de, 'p, 's, 'a, E: DeserializableCustomExtension> DeserializeSeed<'de>
+    for PayloadDeserializeSeed<'p, 's, 'a, E>
+{
+    type Value = DeserializedPayload<E>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserialize_payload(deserializer, self.parameters)
+    }
+}
+
+fn deserialize_payload_from_serde_value<E: DeserializableCustomExtension>(
+    serde_value: &SerdeValue,
+    parameters: &DeserializationParameters<'_, '_, E>,
+) -> Result<DeserializedPayload<E>, DeserializationError> {
+    let (context, type_id, depth_limit) = parameters.get_context_params();
+    let mut path = DeserializationPath::new();
+    let value = deserialize_value(&context, serde_value, type_id, &mut path, depth_limit)?;
+
+    let mut payload = Vec::with_capacity(512);
+    let encoder = VecEncoder::<E::CustomValueKind>::new(&mut payload, depth_limit);
+    encoder
+        .encode_payload(&value, E::PAYLOAD_PREFIX)
+        .map_err(DeserializationError::EncodeError)?;
+    validate_payload_against_schema::<E, ()>(&payload, context.schema, type_id, &(), depth_limit)
+        .map_err(|error| {
+            DeserializationError::ValidationError(error.error_message(context.schema))
+        })?;
+
+    Ok(DeserializedPayload { value, payload })
+}
+
+/// Tracks the location of the currently-deserialized value, to be used in error messages.
+struct DeserializationPath {
+    segments: Vec<String>,
+}
+
+impl DeserializationPath {
+    fn new() -> Self {
+        Self { segments: vec![] }
+    }
+
+    fn error(&self, cause: impl Into<String>) -> DeserializationError {
+        DeserializationError::InvalidValue {
+            path: if self.segments.is_empty() {
+                "<root>".to_string()
+            } else {
+                self.segments.join("->")
+            },
+            cause: cause.into(),
+        }
+    }
+}
+
+type DeserializedValue<E> = Value<
+    <E as CustomExtension>::CustomValueKind,
+    <E as DeserializableCustomExtension>::CustomValue,
+>;
+
+fn deserialize_value<E: DeserializableCustomExtension>(
+    context: &DeserializationContext<'_, '_, E>,
+    serde_value: &SerdeValue,
+    type_id: LocalTypeId,
+    path: &mut DeserializationPath,
+    remaining_depth: usize,
+) -> Result<DeserializedValue<E>, DeserializationError> {
+    if remaining_depth == 0 {
+        return Err(path.error("Depth limit exceeded"));
+    }
+    let Some(type_kind) = context.schema.resolve_type_kind(type_id) else {
+        return Err(path.error(format!("Type {:?} not found in the schema", type_id)));
+    };
+    let is_any = matches!(type_kind, TypeKind::Any);
+    let object = SerdeObject::new(serde_value, path)?;
+    let kind_name = object.get_str("kind", path)?;
+    let value_kind = value_kind_from_name::<E>(kind_name)
+        .ok_or_else(|| path.error(format!("Unknown kind \"{}\"", kind_name)))?;
+
+    let schema_type_name = if is_any {
+        None
+    } else {
+        context.schema.resolve_type_name_from_metadata(type_id)
+    };
+    if let Some(type_name) = object.get_optional_str("type_name", path)? {
+        check_annotation("type_name", type_name, schema_type_name, path)?;
+    }
+    let container_name = schema_type_name.unwrap_or(value_kind.value_kind_name());
+
+    match value_kind {
+        ValueKind::Tuple => {
+            let fields = object.get_array("fields", path)?;
+            let field_types = match type_kind {
+                TypeKind::Tuple { field_types } => {
+                    if field_types.len() != fields.len() {
+                        return Err(path.error(format!(
+                            "Expected {} fields, but got {}",
+                            field_types.len(),
+                            fields.len()
+                        )));
+                    }
+                    field_types.clone()
+                }
+                TypeKind::Any => vec![LocalTypeId::any(); fields.len()],
+                _ => return Err(mismatching_kind_error(path, kind_name, type_kind)),
+            };
+            let field_names = context
+                .schema
+                .resolve_matching_tuple_metadata(type_id, fields.len())
+                .field_names
+                .filter(|_| !is_any);
+            let fields = deserialize_fields(
+                context,
+                fields,
+                &field_types,
+                field_names,
+                container_name,
+                path,
+                remaining_depth,
+            )?;
+            Ok(Value::Tuple { fields })
+        }
+        ValueKind::Enum => {
+            let discriminator = object.get_u8("variant_id", path)?;
+            let fields = object.get_array("fields", path)?;
+            let field_types = match type_kind {
+                TypeKind::Enum { variants } => {
+                    let Some(field_types) = variants.get(&discriminator) else {
+                        return Err(path.error(format!("Unknown variant_id {}", discriminator)));
+                    };
+                    if field_types.len() != fields.len() {
+                        return Err(path.error(format!(
+                            "Expected {} fields, but got {}",
+                            field_types.len(),
+                            fields.len()
+                        )));
+                    }
+                    field_types.clone()
+                }
+                TypeKind::Any => vec![LocalTypeId::any(); fields.len()],
+                _ => return Err(mismatching_kind_error(path, kind_name, type_kind)),
+            };
+            let variant_data =
+                context
+                    .schema
+                    .resolve_matching_enum_metadata(type_id, discriminator, fields.len());
+            let (schema_variant_name, field_names) = if is_any {
+                (None, None)
+            } else {
+                (variant_data.variant_name, variant_data.field_names)
+            };
+            if let Some(variant_name) = object.get_optional_str("variant_name", path)? {
+                check_annotation("variant_name", variant_name, schema_variant_name, path)?;
+            }
+            let variant_container_name = match schema_variant_name {
+                Some(variant_name) => {
+                    format!("{}::{{{}|{}}}", container_name, discriminator, variant_name)
+                }
+                None => format!("{}::{{{}}}", container_name, discriminator),
+            };
+            let fields = deserialize_fields(
+                context,
+                fields,
+                &field_types,
+                field_names,
+                &variant_container_name,
+                path,
+                remaining_depth,
+            )?;
+            Ok(Value::Enum {
+                discriminator,
+                fields,
+            })
+        }
+        ValueKind::Array => {
+            let element_type = match type_kind {
+                TypeKind::Array { element_type } => *element_type,
+                TypeKind::Any => LocalTypeId::any(),
+                _ => return Err(mismatching_kind_error(path, kind_name, type_kind)),
+            };
+            if kind_name == BYTES_KIND_NAME {
+                let hex = object.get_str("hex", path)?;
+                let bytes =
+                    hex::decode(hex).map_err(|_| path.error(format!("Invalid hex \"{}\"", hex)))?;
+                return Ok(Value::Array {
+                    element_value_kind: ValueKind::U8,
+                    elements: bytes.into_iter().map(|value| Value::U8 { value }).collect(),
+                });
+            }
+            let element_value_kind = object.get_value_kind::<E>("element_kind", path)?;
+            let elements = object
+                .get_array("elements", path)?
+                .iter()
+                .enumerate()
+                .map(|(index, element)| {
+                    path.segments
+                        .push(format!("{}.[{}]", container_name, index));
+                    let element = deserialize_value(
+                        context,
+                        element,
+                        element_type,
+                        path,
+                        remaining_depth - 1,
+                    )
+                    .and_then(|element| expect_value_kind(element, element_value_kind, path));
+                    path.segments.pop();
+                    element
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::Array {
+                element_value_kind,
+                elements,
+            })
+        }
+        ValueKind::Map => {
+            let (key_type, value_type) = match type_kind {
+                TypeKind::Map {
+                    key_type,
+                    value_type,
+                } => (*key_type, *value_type),
+                TypeKind::Any => (LocalTypeId::any(), LocalTypeId::any()),
+                _ => return Err(mismatching_kind_error(path, kind_name, type_kind)),
+            };
+            let key_value_kind = object.get_value_kind::<E>("key_kind", path)?;
+            let value_value_kind = object.get_value_kind::<E>("value_kind", path)?;
+            let entries = object
+                .get_array("entries", path)?
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    let entry_object = SerdeObject::new(entry, path)?;
+                    path.segments
+                        .push(format!("{}.[{}].Key", container_name, index));
+                    let key = entry_object.get("key", path).and_then(|key| {
+                        deserialize_value(context, key, key_type, path, remaining_depth - 1)
+                    });
+                    let key = key.and_then(|key| expect_value_kind(key, key_value_kind, path));
+                    path.segments.pop();
+                    path.segments
+                        .push(format!("{}.[{}].Value", container_name, index));
+                    let value = entry_object.get("value", path).and_then(|value| {
+                        deserialize_value(context, value, value_type, path, remaining_depth - 1)
+                    });
+                    let value =
+                        value.and_then(|value| expect_value_kind(value, value_value_kind, path));
+                    path.segments.pop();
+                    Ok((key?, value?))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::Map {
+                key_value_kind,
+                value_value_kind,
+                entries,
+            })
+        }
+        ValueKind::Custom(custom_value_kind) => {
+            let value = object.get_str("value", path)?;
+            E::parse_custom_value(&context.custom_context, custom_value_kind, value)
+                .map(|value| Value::Custom { value })
+                .map_err(|cause| path.error(cause))
+        }
+        ValueKind::Bool => match object.get("value", path)? {
+            SerdeValue::Bool(value) => Ok(Value::Bool { value: *value }),
+            _ => Err(path.error("Expected a boolean \"value\"")),
+        },
+        ValueKind::String => Ok(Value::String {
+            value: object.get_str("value", path)?.to_string(),
+        }),
+        ValueKind::I8 => Ok(Value::I8 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::I16 => Ok(Value::I16 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::I32 => Ok(Value::I32 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::I64 => Ok(Value::I64 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::I128 => Ok(Value::I128 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::U8 => Ok(Value::U8 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::U16 => Ok(Value::U16 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::U32 => Ok(Value::U32 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::U64 => Ok(Value::U64 {
+            value: object.get_integer("value", path)?,
+        }),
+        ValueKind::U128 => Ok(Value::U128 {
+            value: object.get_integer("value", path)?,
+        }),
+    }
+}
+
+fn deserialize_fields<E: DeserializableCustomExtension>(
+    context: &DeserializationContext<'_, '_, E>,
+    fields: &[SerdeValue],
+    field_types: &[LocalTypeId],
+    field_names: Option<&[Cow<'static, str>]>,
+    container_name: &str,
+    path: &mut DeserializationPath,
+    remaining_depth: usize,
+) -> Result<Vec<DeserializedValue<E>>, DeserializationError> {
+    fields
+        .iter()
+        .zip(field_types.iter())
+        .enumerate()
+        .map(|(index, (field, field_type))| {
+            let schema_field_name = field_names.map(|field_names| field_names[index].as_ref());
+            path.segments.push(match schema_field_name {
+                Some(field_name) => format!("{}.[{}|{}]", container_name, index, field_name),
+                None => format!("{}.[{}]", container_name, index),
+            });
+            let field_name = SerdeObject::new(field, path)
+                .and_then(|object| object.get_optional_str("field_name", path));
+            let field = field_name
+                .and_then(|field_name| match field_name {
+                    Some(field_name) => {
+                        check_annotation("field_name", field_name, schema_field_name, path)
+                    }
+                    None => Ok(()),
+                })
+                .and_then(|_| {
+                    deserialize_value(context, field, *field_type, path, remaining_depth - 1)
+                });
+            path.segments.pop();
+            field
+        })
+        .collect()
+}
+
+fn check_annotation(
+    annotation: &str,
+    actual: &str,
+    expected: Option<&str>,
+    path: &DeserializationPath,
+) -> Result<(), DeserializationError> {
+    match expected {
+        Some(expected) if expected != actual => Err(path.error(format!(
+            "Mismatching {}: expected \"{}\", but got \"{}\"",
+            annotation, expected, actual
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn expect_value_kind<X: CustomValueKind, Y: CustomValue<X>>(
+    value: Value<X, Y>,
+    expected_value_kind: ValueKind<X>,
+    path: &DeserializationPath,
+) -> Result<Value<X, Y>, DeserializationError> {
+    let actual_value_kind = get_value_kind(&value);
+    if actual_value_kind != expected_value_kind {
+        return Err(path.error(format!(
+            "Expected kind {}, but got {}",
+            expected_value_kind, actual_value_kind
+        )));
+    }
+    Ok(value)
+}
+
+fn get_value_kind<X: CustomValueKind, Y: CustomValue<X>>(value: &Value<X, Y>) -> ValueKind<X> {
+    match value {
+        Value::Bool { .. } => ValueKind::Bool,
+        Value::I8 { .. } => ValueKind::I8,
+        Value::I16 { .. } => ValueKind::I16,
+        Value::I32 { .. } => ValueKind::I32,
+        Value::I64 { .. } => ValueKind::I64,
+        Value::I128 { .. } => ValueKind::I128,
+        Value::U8 { .. } => ValueKind::U8,
+        Value::U16 { .. } => ValueKind::U16,
+        Value::U32 { .. } => ValueKind::U32,
+        Value::U64 { .. } => ValueKind::U64,
+        Value::U128 { .. } => ValueKind::U128,
+        Value::String { .. } => ValueKind::String,
+        Value::Enum { .. } => ValueKind::Enum,
+        Value::Array { .. } => ValueKind::Array,
+        Value::Tuple { .. } => ValueKind::Tuple,
+        Value::Map { .. } => ValueKind::Map,
+        Value::Custom { value } => ValueKind::Custom(value.get_custom_value_kind()),
+    }
+}
+
+fn mismatching_kind_error<C: CustomTypeKind<LocalTypeId>>(
+    path: &DeserializationPath,
+    kind_name: &str,
+    type_kind: &TypeKind<C, LocalTypeId>,
+) -> DeserializationError {
+    path.error(format!(
+        "Kind {} doesn't match the type kind {:?}",
+        kind_name, type_kind
+    ))
+}
+
+/// The kind name used by the serializer for `Array`s of `U8`s (which are output as hex).
+const BYTES_KIND_NAME: &str = "Bytes";
+
+fn value_kind_from_name<E: DeserializableCustomExtension>(
+    name: &str,
+) -> Option<ValueKind<E::CustomValueKind>> {
+    Some(match name {
+        "Bool" => ValueKind::Bool,
+        "I8" => ValueKind::I8,
+        "I16" => ValueKind::I16,
+        "I32" => ValueKind::I32,
+        "I64" => ValueKind::I64,
+        "I128" => ValueKind::I128,
+        "U8" => ValueKind::U8,
+        "U16" => ValueKind::U16,
+        "U32" => ValueKind::U32,
+        "U64" => ValueKind::U64,
+        "U128" => ValueKind::U128,
+        "String" => ValueKind::String,
+        "Enum" => ValueKind::Enum,
+        "Array" | BYTES_KIND_NAME => ValueKind::Array,
+        "Tuple" => ValueKind::Tuple,
+        "Map" => ValueKind::Map,
+        _ => ValueKind::Custom(E::custom_value_kind_from_name(name)?),
+    })
+}
+
+trait ValueKindName {
+    fn value_kind_name(&self) -> &'static str;
+}
+
+impl<X: CustomValueKind> ValueKindName for ValueKind<X> {
+    fn value_kind_name(&self) -> &'static str {
+        match self {
+            ValueKind::Enum => "Enum",
+            ValueKind::Array => "Array",
+            ValueKind::Tuple => "Tuple",
+            ValueKind::Map => "Map",
+            _ => "Value",
+        }
+    }
+}
+
+/// An owned tree of a deserialized serde value.
+///
+/// We need to fully deserialize it before interpreting, since the fields of the JSON objects
+/// output by the serializer may come in any order (eg the `kind` is not guaranteed to be first).
+#[derive(Debug, Clone, PartialEq)]
+enum SerdeValue {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Array(Vec<SerdeValue>),
+    Object(Vec<(String, SerdeValue)>),
+}
+
+impl<'de> Deserialize<'de> for SerdeValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SerdeValueVisitor)
+    }
+}
+
+struct SerdeValueVisitor;
+
+impl<'de> Visitor<'de> for SerdeValueVisitor {
+    type Value = SerdeValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<Err: serde::de::Error>(self, value: bool) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::Bool(value))
+    }
+
+    fn visit_i64<Err: serde::de::Error>(self, value: i64) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::I64(value))
+    }
+
+    fn visit_u64<Err: serde::de::Error>(self, value: u64) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::U64(value))
+    }
+
+    fn visit_f64<Err: serde::de::Error>(self, value: f64) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::F64(value))
+    }
+
+    fn visit_str<Err: serde::de::Error>(self, value: &str) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::String(value.to_string()))
+    }
+
+    fn visit_string<Err: serde::de::Error>(self, value: String) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::String(value))
+    }
+
+    fn visit_unit<Err: serde::de::Error>(self) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::Null)
+    }
+
+    fn visit_none<Err: serde::de::Error>(self) -> Result<Self::Value, Err> {
+        Ok(SerdeValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        SerdeValue::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut elements = Vec::new();
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(SerdeValue::Array(elements))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry::<String, SerdeValue>()? {
+            entries.push(entry);
+        }
+        Ok(SerdeValue::Object(entries))
+    }
+}
+
+struct SerdeObject<'v> {
+    entries: &'v [(String, SerdeValue)],
+}
+
+impl<'v> SerdeObject<'v> {
+    fn new(
+        value: &'v SerdeValue,
+        path: &DeserializationPath,
+    ) -> Result<Self, DeserializationError> {
+        match value {
+            SerdeValue::Object(entries) => Ok(Self { entries }),
+            _ => Err(path.error("Expected an object")),
+        }
+    }
+
+    fn get_optional(&self, key: &str) -> Option<&'v SerdeValue> {
+        self.entries
+            .iter()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value)
+    }
+
+    fn get(
+        &self,
+        key: &str,
+        path: &DeserializationPath,
+    ) -> Result<&'v SerdeValue, DeserializationError> {
+        self.get_optional(key)
+            .ok_or_else(|| path.error(format!("Missing \"{}\"", key)))
+    }
+
+    fn get_optional_str(
+        &self,
+        key: &str,
+        path: &DeserializationPath,
+    ) -> Result<Option<&'v str>, DeserializationError> {
+        match self.get_optional(key) {
+            Some(SerdeValue::String(value)) => Ok(Some(value)),
+            Some(_) => Err(path.error(format!("Expected a string \"{}\"", key))),
+            None => Ok(None),
+        }
+    }
+
+    fn get_str(
+        &self,
+        key: &str,
+        path: &DeserializationPath,
+    ) -> Result<&'v str, DeserializationError> {
+        match self.get(key, path)? {
+            SerdeValue::String(value) => Ok(value),
+            _ => Err(path.error(format!("Expected a string \"{}\"", key))),
+        }
+    }
+
+    fn get_array(
+        &self,
+        key: &str,
+        path: &DeserializationPath,
+    ) -> Result<&'v [SerdeValue], DeserializationError> {
+        match self.get(key, path)? {
+            SerdeValue::Array(elements) => Ok(elements),
+            _ => Err(path.error(format!("Expected an array \"{}\"", key))),
+        }
+    }
+
+    fn get_value_kind<E: DeserializableCustomExtension>(
+        &self,
+        key: &str,
+        path: &DeserializationPath,
+    ) -> Result<ValueKind<E::CustomValueKind>, DeserializationError> {
+        let name = self.get_str(key, path)?;
+        value_kind_from_name::<E>(name)
+            .ok_or_else(|| path.error(format!("Unknown \"{}\": \"{}\"", key, name)))
+    }
+
+    fn get_u8(&self, key: &str, path: &DeserializationPath) -> Result<u8, DeserializationError> {
+        self.get_integer(key, path)
+    }
+
+    /// Integers are output as strings by the Programmatic serialization (in order not to lose
+    /// precision in JSON), but for convenience we also accept plain JSON numbers.
+    fn get_integer<T: FromStr + TryFrom<i64> + TryFrom<u64>>(
+        &self,
+        key: &str,
+        path: &DeserializationPath,
+    ) -> Result<T, DeserializationError> {
+        let parsed = match self.get(key, path)? {
+            SerdeValue::String(value) => value.parse::<T>().ok(),
+            SerdeValue::I64(value) => T::try_from(*value).ok(),
+            SerdeValue::U64(value) => T::try_from(*value).ok(),
+            _ => None,
+        };
+        parsed.ok_or_else(|| {
+            path.error(format!(
+                "Expected \"{}\" to be an integer in range of {}",
+                key,
+                core::any::type_name::<T>()
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")] // Ensures that VS Code runs this module with the features serde tag!
+mod tests {
+    use super::*;
+    use serde_json::{json, Value as JsonValue};
+    use utils::ContextualSerialize;
+
+    #[derive(Sbor, Debug, PartialEq, Eq)]
+    enum TestEnum {
+        UnitVariant,
+        SingleFieldVariant { field: u8 },
+    }
+
+    #[derive(Sbor, Debug, PartialEq, Eq)]
+    struct TestStruct {
+        field1: u64,
+        field2: Vec<String>,
+        field3: Vec<u8>,
+        field4: IndexMap<u16, TestEnum>,
+        field5: TestEnum,
+    }
+
+    fn test_value() -> TestStruct {
+        TestStruct {
+            field1: u64::MAX,
+            field2: vec!["hello".to_string(), "world".to_string()],
+            field3: vec![1, 2, 3],
+            field4: indexmap! {
+                7 => TestEnum::UnitVariant,
+                9 => TestEnum::SingleFieldVariant { field: 3 },
+            },
+            field5: TestEnum::SingleFieldVariant { field: 5 },
+        }
+    }
+
+    fn serialize_programmatic(
+        payload: &[u8],
+        schema: &Schema<NoCustomSchema>,
+        type_id: LocalTypeId,
+    ) -> JsonValue {
+        serde_json::to_value(
+            BasicRawPayload::new_from_valid_slice_with_checks(payload)
+                .unwrap()
+                .serializable(SerializationParameters::WithSchema {
+                    mode: SerializationMode::Programmatic,
+                    custom_context: (),
+                    schema,
+                    type_id,
+                    depth_limit: 64,
+                }),
+        )
+        .unwrap()
+    }
+
+    fn deserialize(
+        json: &JsonValue,
+        schema: &Schema<NoCustomSchema>,
+        type_id: LocalTypeId,
+    ) -> Result<DeserializedPayload<NoCustomExtension>, String> {
+        deserialize_payload(
+            json,
+            &DeserializationParameters::WithSchema {
+                custom_context: (),
+                schema,
+                type_id,
+                depth_limit: 64,
+            },
+        )
+        .map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn programmatic_json_round_trips_with_schema() {
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestStruct, NoCustomSchema>();
+        let payload = basic_encode(&test_value()).unwrap();
+        let json = serialize_programmatic(&payload, &schema, type_id);
+
+        let deserialized = deserialize(&json, &schema, type_id).unwrap();
+
+        assert_eq!(deserialized.payload, payload);
+        assert_eq!(
+            deserialized.value,
+            basic_decode::<BasicValue>(&payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn programmatic_json_round_trips_without_annotations() {
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestStruct, NoCustomSchema>();
+        let payload = basic_encode(&test_value()).unwrap();
+        let json = serde_json::to_value(
+            BasicRawPayload::new_from_valid_slice_with_checks(&payload)
+                .unwrap()
+                .serializable(SerializationParameters::Schemaless {
+                    mode: SerializationMode::Programmatic,
+                    custom_context: (),
+                    depth_limit: 64,
+                }),
+        )
+        .unwrap();
+
+        let deserialized = deserialize(&json, &schema, type_id).unwrap();
+
+        assert_eq!(deserialized.payload, payload);
+    }
+
+    #[test]
+    fn accepts_plain_json_numbers() {
+        let json = json!({
+            "kind": "Tuple",
+            "fields": [
+                { "kind": "U32", "value": 5 },
+                { "kind": "I8", "value": -3 },
+                { "kind": "Enum", "variant_id": 1, "fields": [] },
+            ]
+        });
+        let deserialized = deserialize_payload(
+            &json,
+            &DeserializationParameters::<NoCustomExtension>::Schemaless {
+                custom_context: (),
+                depth_limit: 64,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            deserialized.value,
+            BasicValue::Tuple {
+                fields: vec![
+                    Value::U32 { value: 5 },
+                    Value::I8 { value: -3 },
+                    Value::Enum {
+                        discriminator: 1,
+                        fields: vec![]
+                    },
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn mismatching_field_name_is_reported_with_path() {
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestStruct, NoCustomSchema>();
+        let payload = basic_encode(&test_value()).unwrap();
+        let mut json = serialize_programmatic(&payload, &schema, type_id);
+        json["fields"][1]["field_name"] = json!("wrong_name");
+
+        assert_eq!(
+            deserialize(&json, &schema, type_id),
+            Err("[ERROR] value path: TestStruct.[1|field2], cause: Mismatching field_name: expected \"field2\", but got \"wrong_name\"".to_string())
+        );
+    }
+
+    #[test]
+    fn out_of_range_integer_is_reported_with_path() {
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestStruct, NoCustomSchema>();
+        let payload = basic_encode(&test_value()).unwrap();
+        let mut json = serialize_programmatic(&payload, &schema, type_id);
+        json["fields"][4]["fields"][0]["value"] = json!("256");
+
+        assert_eq!(
+            deserialize(&json, &schema, type_id),
+            Err("[ERROR] value path: TestStruct.[4|field5]->TestEnum::{1|SingleFieldVariant}.[0|field], cause: Expected \"value\" to be an integer in range of u8".to_string())
+        );
+    }
+
+    #[test]
+    fn value_not_matching_schema_is_rejected() {
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestStruct, NoCustomSchema>();
+        let payload = basic_encode(&test_value()).unwrap();
+        let mut json = serialize_programmatic(&payload, &schema, type_id);
+        json["fields"][0] = json!({ "kind": "String", "value": "not a u64" });
+
+        assert!(deserialize(&json, &schema, type_id).is_err());
+    }
+}
@@ -17,3 +17,27 @@ pub trait SerializableCustomExtension: FormattableCustomExtension + Copy {
         value: <Self::CustomTraversal as CustomTraversal>::CustomTerminalValueRef<'de>,
     ) -> CustomTypeSerialization<'a, 't, 'de, 's1, 's2, Self>;
 }
+
+pub trait DeserializableCustomExtension:
+    SerializableCustomExtension + ValidatableCustomExtension<()>
+{
+    type CustomValue: CustomValue<Self::CustomValueKind>
+        + for<'b> Encode<Self::CustomValueKind, VecEncoder<'b, Self::CustomValueKind>>
+        + Debug
+        + Clone
+        + PartialEq
+        + Eq;
+
+    type CustomDeserializationContext<'a>: Default + Copy;
+
+    /// Maps the `kind` of a custom value (as output by the serializer) back to its value kind.
+    fn custom_value_kind_from_name(name: &str) -> Option<Self::CustomValueKind>;
+
+    /// Parses a custom value from its `value` string, as output by the serializer in the
+    /// Programmatic mode.
+    fn parse_custom_value<'a>(
+        context: &Self::CustomDeserializationContext<'a>,
+        custom_value_kind: Self::CustomValueKind,
+        value: &str,
+    ) -> Result<Self::CustomValue, String>;
+}
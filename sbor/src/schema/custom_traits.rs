@@ -53,6 +53,15 @@ pub trait CustomSchema: Debug + Clone + Copy + PartialEq + Eq + 'static {
         type_metadata: &TypeMetadata,
     ) -> Result<(), SchemaValidationError>;
 
+    /// Used when comparing schemas for backwards compatibility.
+    ///
+    /// Returns whether every value accepted by the old custom type validation is also accepted
+    /// by the new one.
+    fn custom_type_validation_is_backwards_compatible(
+        old: &Self::CustomTypeValidation,
+        new: &Self::CustomTypeValidation,
+    ) -> bool;
+
     fn empty_schema() -> &'static Schema<Self>;
 }
 
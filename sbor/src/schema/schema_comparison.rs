@@ -0,0 +1,548 @@
+use crate::rust::prelude::*;
+use crate::*;
+
+/// A reason why a payload valid under the old schema may fail to decode (or validate) under the
+/// new schema, at a given location in the type tree.
+#[derive(Debug, Clone, PartialEq, Eq, Sbor)]
+pub struct SchemaIncompatibility {
+    /// The location of the incompatible type, in the format of `TypeName.[1|field]->ChildType`,
+    /// using the names from the old schema.
+    pub path: String,
+    pub kind: SchemaIncompatibilityKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Sbor)]
+pub enum SchemaIncompatibilityKind {
+    OldTypeNotFound,
+    NewTypeNotFound,
+    /// The value kinds (or custom type kinds) differ, or the new type is narrower than `Any`.
+    TypeKindMismatch {
+        old: String,
+        new: String,
+    },
+    TupleFieldCountMismatch {
+        old: usize,
+        new: usize,
+    },
+    EnumVariantRemoved {
+        variant_id: u8,
+    },
+    EnumVariantFieldCountMismatch {
+        variant_id: u8,
+        old: usize,
+        new: usize,
+    },
+    /// The new [`TypeValidation`] rejects some values which the old one accepted.
+    TypeValidationNarrowed {
+        old: String,
+        new: String,
+    },
+}
+
+impl fmt::Display for SchemaIncompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.path)?;
+        match &self.kind {
+            SchemaIncompatibilityKind::OldTypeNotFound => write!(f, "type not found in old schema"),
+            SchemaIncompatibilityKind::NewTypeNotFound => write!(f, "type not found in new schema"),
+            SchemaIncompatibilityKind::TypeKindMismatch { old, new } => {
+                write!(f, "type kind changed from {} to {}", old, new)
+            }
+            SchemaIncompatibilityKind::TupleFieldCountMismatch { old, new } => {
+                write!(f, "field count changed from {} to {}", old, new)
+            }
+            SchemaIncompatibilityKind::EnumVariantRemoved { variant_id } => {
+                write!(f, "variant {} was removed", variant_id)
+            }
+            SchemaIncompatibilityKind::EnumVariantFieldCountMismatch {
+                variant_id,
+                old,
+                new,
+            } => write!(
+                f,
+                "field count of variant {} changed from {} to {}",
+                variant_id, old, new
+            ),
+            SchemaIncompatibilityKind::TypeValidationNarrowed { old, new } => {
+                write!(f, "validation narrowed from {} to {}", old, new)
+            }
+        }
+    }
+}
+
+/// Checks whether every payload which is valid for the old type (under the old schema) is also
+/// valid for the new type (under the new schema).
+///
+/// This is the compatibility required when upgrading a blueprint: the new schema must still be able
+/// to decode any value which may have been persisted (or emitted) using the old schema. So eg
+/// adding enum variants or widening a [`TypeValidation`] is allowed, but adding or removing fields,
+/// removing enum variants, changing value kinds or narrowing validation isn't.
+///
+/// Type and field names don't affect the payload encoding, and so are not compared.
+pub fn check_backwards_compatibility<S: CustomSchema>(
+    old_schema: &Schema<S>,
+    old_type_id: LocalTypeId,
+    new_schema: &Schema<S>,
+    new_type_id: LocalTypeId,
+) -> Result<(), Vec<SchemaIncompatibility>> {
+    let mut checker = CompatibilityChecker {
+        old_schema,
+        new_schema,
+        visited: index_set_new(),
+        path: vec![],
+        incompatibilities: vec![],
+    };
+    checker.compare_types(old_type_id, new_type_id);
+    if checker.incompatibilities.is_empty() {
+        Ok(())
+    } else {
+        Err(checker.incompatibilities)
+    }
+}
+
+struct CompatibilityChecker<'s, S: CustomSchema> {
+    old_schema: &'s Schema<S>,
+    new_schema: &'s Schema<S>,
+    /// The pairs of types already compared - this also prevents infinite recursion on
+    /// self-referencing types.
+    visited: IndexSet<(LocalTypeId, LocalTypeId)>,
+    path: Vec<String>,
+    incompatibilities: Vec<SchemaIncompatibility>,
+}
+
+impl<'s, S: CustomSchema> CompatibilityChecker<'s, S> {
+    fn compare_types(&mut self, old_type_id: LocalTypeId, new_type_id: LocalTypeId) {
+        if !self.visited.insert((old_type_id, new_type_id)) {
+            return;
+        }
+        let type_label = self.type_label(old_type_id);
+        let Some(old_kind) = self.old_schema.resolve_type_kind(old_type_id) else {
+            return self.report(type_label, SchemaIncompatibilityKind::OldTypeNotFound);
+        };
+        let Some(new_kind) = self.new_schema.resolve_type_kind(new_type_id) else {
+            return self.report(type_label, SchemaIncompatibilityKind::NewTypeNotFound);
+        };
+
+        match (old_kind, new_kind) {
+            (_, TypeKind::Any) => return,
+            (
+                TypeKind::Array {
+                    element_type: old_element_type,
+                },
+                TypeKind::Array {
+                    element_type: new_element_type,
+                },
+            ) => {
+                self.with_child(&type_label, ".[*]".to_string(), |checker| {
+                    checker.compare_types(*old_element_type, *new_element_type)
+                });
+            }
+            (
+                TypeKind::Tuple {
+                    field_types: old_field_types,
+                },
+                TypeKind::Tuple {
+                    field_types: new_field_types,
+                },
+            ) => {
+                if old_field_types.len() != new_field_types.len() {
+                    self.report(
+                        type_label.clone(),
+                        SchemaIncompatibilityKind::TupleFieldCountMismatch {
+                            old: old_field_types.len(),
+                            new: new_field_types.len(),
+                        },
+                    );
+                } else {
+                    let field_names = self
+                        .old_schema
+                        .resolve_type_metadata(old_type_id)
+                        .and_then(|metadata| metadata.get_field_names());
+                    for (index, (old_field_type, new_field_type)) in old_field_types
+                        .iter()
+                        .zip(new_field_types.iter())
+                        .enumerate()
+                    {
+                        let child = field_label(index, field_names);
+                        self.with_child(&type_label, child, |checker| {
+                            checker.compare_types(*old_field_type, *new_field_type)
+                        });
+                    }
+                }
+            }
+            (
+                TypeKind::Enum {
+                    variants: old_variants,
+                },
+                TypeKind::Enum {
+                    variants: new_variants,
+                },
+            ) => {
+                let old_metadata = self.old_schema.resolve_type_metadata(old_type_id);
+                for (variant_id, old_field_types) in old_variants.iter() {
+                    let variant_data = old_metadata.map(|metadata| {
+                        metadata.get_matching_enum_variant_data(*variant_id, old_field_types.len())
+                    });
+                    let variant_label = match variant_data.as_ref().and_then(|d| d.variant_name) {
+                        Some(variant_name) => {
+                            format!("{}::{{{}|{}}}", type_label, variant_id, variant_name)
+                        }
+                        None => format!("{}::{{{}}}", type_label, variant_id),
+                    };
+                    let Some(new_field_types) = new_variants.get(variant_id) else {
+                        self.report(
+                            variant_label,
+                            SchemaIncompatibilityKind::EnumVariantRemoved {
+                                variant_id: *variant_id,
+                            },
+                        );
+                        continue;
+                    };
+                    if old_field_types.len() != new_field_types.len() {
+                        self.report(
+                            variant_label,
+                            SchemaIncompatibilityKind::EnumVariantFieldCountMismatch {
+                                variant_id: *variant_id,
+                                old: old_field_types.len(),
+                                new: new_field_types.len(),
+                            },
+                        );
+                        continue;
+                    }
+                    let field_names = variant_data.as_ref().and_then(|d| d.field_names);
+                    for (index, (old_field_type, new_field_type)) in old_field_types
+                        .iter()
+                        .zip(new_field_types.iter())
+                        .enumerate()
+                    {
+                        let child = field_label(index, field_names);
+                        self.with_child(&variant_label, child, |checker| {
+                            checker.compare_types(*old_field_type, *new_field_type)
+                        });
+                    }
+                }
+            }
+            (
+                TypeKind::Map {
+                    key_type: old_key_type,
+                    value_type: old_value_type,
+                },
+                TypeKind::Map {
+                    key_type: new_key_type,
+                    value_type: new_value_type,
+                },
+            ) => {
+                self.with_child(&type_label, ".[key]".to_string(), |checker| {
+                    checker.compare_types(*old_key_type, *new_key_type)
+                });
+                self.with_child(&type_label, ".[value]".to_string(), |checker| {
+                    checker.compare_types(*old_value_type, *new_value_type)
+                });
+            }
+            (old_kind, new_kind) if old_kind == new_kind => {
+                // Simple and custom type kinds have no child types
+            }
+            (old_kind, new_kind) => {
+                return self.report(
+                    type_label,
+                    SchemaIncompatibilityKind::TypeKindMismatch {
+                        old: type_kind_name(old_kind),
+                        new: type_kind_name(new_kind),
+                    },
+                );
+            }
+        }
+
+        let old_validation = self
+            .old_schema
+            .resolve_type_validation(old_type_id)
+            .unwrap_or(&TypeValidation::None);
+        let new_validation = self
+            .new_schema
+            .resolve_type_validation(new_type_id)
+            .unwrap_or(&TypeValidation::None);
+        if !type_validation_is_backwards_compatible::<S>(old_validation, new_validation) {
+            self.report(
+                type_label,
+                SchemaIncompatibilityKind::TypeValidationNarrowed {
+                    old: format!("{:?}", old_validation),
+                    new: format!("{:?}", new_validation),
+                },
+            );
+        }
+    }
+
+    fn with_child(&mut self, parent_label: &str, child: String, f: impl FnOnce(&mut Self)) {
+        self.path.push(format!("{}{}", parent_label, child));
+        f(self);
+        self.path.pop();
+    }
+
+    fn type_label(&self, old_type_id: LocalTypeId) -> String {
+        match self.old_schema.resolve_type_name_from_metadata(old_type_id) {
+            Some(type_name) => type_name.to_string(),
+            None => self
+                .old_schema
+                .resolve_type_kind(old_type_id)
+                .map(type_kind_name)
+                .unwrap_or_else(|| "?".to_string()),
+        }
+    }
+
+    fn report(&mut self, type_label: String, kind: SchemaIncompatibilityKind) {
+        let mut path = self.path.join("->");
+        if !path.is_empty() {
+            path.push_str("->");
+        }
+        path.push_str(&type_label);
+        self.incompatibilities
+            .push(SchemaIncompatibility { path, kind });
+    }
+}
+
+fn field_label(index: usize, field_names: Option<&[Cow<'static, str>]>) -> String {
+    match field_names.and_then(|names| names.get(index)) {
+        Some(field_name) => format!(".[{}|{}]", index, field_name),
+        None => format!(".[{}]", index),
+    }
+}
+
+fn type_kind_name<C: CustomTypeKind<LocalTypeId>>(type_kind: &TypeKind<C, LocalTypeId>) -> String {
+    let name = match type_kind {
+        TypeKind::Any => "Any",
+        TypeKind::Bool => "Bool",
+        TypeKind::I8 => "I8",
+        TypeKind::I16 => "I16",
+        TypeKind::I32 => "I32",
+        TypeKind::I64 => "I64",
+        TypeKind::I128 => "I128",
+        TypeKind::U8 => "U8",
+        TypeKind::U16 => "U16",
+        TypeKind::U32 => "U32",
+        TypeKind::U64 => "U64",
+        TypeKind::U128 => "U128",
+        TypeKind::String => "String",
+        TypeKind::Array { .. } => "Array",
+        TypeKind::Tuple { .. } => "Tuple",
+        TypeKind::Enum { .. } => "Enum",
+        TypeKind::Map { .. } => "Map",
+        TypeKind::Custom(custom_type_kind) => return format!("{:?}", custom_type_kind),
+    };
+    name.to_string()
+}
+
+/// Returns whether every value accepted by the old validation is also accepted by the new one.
+pub fn type_validation_is_backwards_compatible<S: CustomSchema>(
+    old: &TypeValidation<S::CustomTypeValidation>,
+    new: &TypeValidation<S::CustomTypeValidation>,
+) -> bool {
+    match (old, new) {
+        (_, TypeValidation::None) => true,
+        (TypeValidation::I8(old), TypeValidation::I8(new)) => numeric_is_wider(old, new),
+        (TypeValidation::I16(old), TypeValidation::I16(new)) => numeric_is_wider(old, new),
+        (TypeValidation::I32(old), TypeValidation::I32(new)) => numeric_is_wider(old, new),
+        (TypeValidation::I64(old), TypeValidation::I64(new)) => numeric_is_wider(old, new),
+        (TypeValidation::I128(old), TypeValidation::I128(new)) => numeric_is_wider(old, new),
+        (TypeValidation::U8(old), TypeValidation::U8(new)) => numeric_is_wider(old, new),
+        (TypeValidation::U16(old), TypeValidation::U16(new)) => numeric_is_wider(old, new),
+        (TypeValidation::U32(old), TypeValidation::U32(new)) => numeric_is_wider(old, new),
+        (TypeValidation::U64(old), TypeValidation::U64(new)) => numeric_is_wider(old, new),
+        (TypeValidation::U128(old), TypeValidation::U128(new)) => numeric_is_wider(old, new),
+        (TypeValidation::String(old), TypeValidation::String(new))
+        | (TypeValidation::Array(old), TypeValidation::Array(new))
+        | (TypeValidation::Map(old), TypeValidation::Map(new)) => {
+            bounds_are_wider(&old.min, &new.min, &old.max, &new.max)
+        }
+        (TypeValidation::Custom(old), TypeValidation::Custom(new)) => {
+            S::custom_type_validation_is_backwards_compatible(old, new)
+        }
+        _ => false,
+    }
+}
+
+fn numeric_is_wider<T: PartialOrd>(old: &NumericValidation<T>, new: &NumericValidation<T>) -> bool {
+    bounds_are_wider(&old.min, &new.min, &old.max, &new.max)
+}
+
+fn bounds_are_wider<T: PartialOrd>(
+    old_min: &Option<T>,
+    new_min: &Option<T>,
+    old_max: &Option<T>,
+    new_max: &Option<T>,
+) -> bool {
+    let min_is_wider = match (old_min, new_min) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(old_min), Some(new_min)) => new_min <= old_min,
+    };
+    let max_is_wider = match (old_max, new_max) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(old_max), Some(new_max)) => new_max >= old_max,
+    };
+    min_is_wider && max_is_wider
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod v1 {
+        use super::*;
+
+        #[derive(Sbor)]
+        pub enum Status {
+            Active,
+            Closed { reason: String },
+        }
+
+        #[derive(Sbor)]
+        pub struct Record {
+            pub id: u64,
+            pub owners: Vec<String>,
+            pub status: Status,
+            pub children: Vec<Record>,
+        }
+    }
+
+    mod v2_added_variant {
+        use super::*;
+
+        #[derive(Sbor)]
+        pub enum Status {
+            Active,
+            Closed { reason: String },
+            Archived,
+        }
+
+        #[derive(Sbor)]
+        pub struct Record {
+            pub id: u64,
+            pub owners: Vec<String>,
+            pub status: Status,
+            pub children: Vec<Record>,
+        }
+    }
+
+    mod v2_breaking {
+        use super::*;
+
+        #[derive(Sbor)]
+        pub enum Status {
+            Active,
+        }
+
+        #[derive(Sbor)]
+        pub struct Record {
+            pub id: u32,
+            pub owners: Vec<String>,
+            pub status: Status,
+            pub children: Vec<Record>,
+            pub extra: bool,
+        }
+    }
+
+    fn check<Old: Describe<NoCustomTypeKind>, New: Describe<NoCustomTypeKind>>(
+    ) -> Result<(), Vec<SchemaIncompatibility>> {
+        let (old_type_id, old_schema) =
+            generate_full_schema_from_single_type::<Old, NoCustomSchema>();
+        let (new_type_id, new_schema) =
+            generate_full_schema_from_single_type::<New, NoCustomSchema>();
+        check_backwards_compatibility(old_schema.v1(), old_type_id, new_schema.v1(), new_type_id)
+    }
+
+    #[test]
+    fn identical_schemas_are_compatible() {
+        assert_eq!(check::<v1::Record, v1::Record>(), Ok(()));
+    }
+
+    #[test]
+    fn added_enum_variant_is_compatible() {
+        assert_eq!(check::<v1::Record, v2_added_variant::Record>(), Ok(()));
+    }
+
+    #[test]
+    fn removed_enum_variant_is_not_compatible() {
+        assert_eq!(
+            check::<v2_added_variant::Record, v1::Record>(),
+            Err(vec![SchemaIncompatibility {
+                path: "Record.[2|status]->Status::{2|Archived}".to_string(),
+                kind: SchemaIncompatibilityKind::EnumVariantRemoved { variant_id: 2 },
+            }])
+        );
+    }
+
+    #[test]
+    fn changing_anything_to_any_is_compatible() {
+        assert_eq!(check::<v1::Record, BasicValue>(), Ok(()));
+    }
+
+    #[test]
+    fn breaking_changes_are_all_reported() {
+        let incompatibilities = check::<v1::Record, v2_breaking::Record>().unwrap_err();
+        assert_eq!(
+            incompatibilities,
+            vec![SchemaIncompatibility {
+                path: "Record".to_string(),
+                kind: SchemaIncompatibilityKind::TupleFieldCountMismatch { old: 4, new: 5 },
+            }]
+        );
+
+        let (old_type_id, old_schema) =
+            generate_full_schema_from_single_type::<v1::Status, NoCustomSchema>();
+        let (new_type_id, new_schema) =
+            generate_full_schema_from_single_type::<v2_breaking::Status, NoCustomSchema>();
+        assert_eq!(
+            check_backwards_compatibility(
+                old_schema.v1(),
+                old_type_id,
+                new_schema.v1(),
+                new_type_id,
+            ),
+            Err(vec![SchemaIncompatibility {
+                path: "Status::{1|Closed}".to_string(),
+                kind: SchemaIncompatibilityKind::EnumVariantRemoved { variant_id: 1 },
+            }])
+        );
+        assert_eq!(
+            check::<u64, u32>(),
+            Err(vec![SchemaIncompatibility {
+                path: "U64".to_string(),
+                kind: SchemaIncompatibilityKind::TypeKindMismatch {
+                    old: "U64".to_string(),
+                    new: "U32".to_string(),
+                },
+            }])
+        );
+    }
+
+    #[test]
+    fn narrowed_validation_is_not_compatible() {
+        let old = TypeValidation::<NoCustomTypeValidation>::String(LengthValidation {
+            min: None,
+            max: Some(100),
+        });
+        let wider = TypeValidation::String(LengthValidation {
+            min: None,
+            max: Some(200),
+        });
+        let narrower = TypeValidation::String(LengthValidation {
+            min: Some(1),
+            max: Some(200),
+        });
+        assert!(type_validation_is_backwards_compatible::<NoCustomSchema>(
+            &old, &wider
+        ));
+        assert!(type_validation_is_backwards_compatible::<NoCustomSchema>(
+            &old,
+            &TypeValidation::None
+        ));
+        assert!(!type_validation_is_backwards_compatible::<NoCustomSchema>(
+            &old, &narrower
+        ));
+        assert!(!type_validation_is_backwards_compatible::<NoCustomSchema>(
+            &TypeValidation::None,
+            &old
+        ));
+    }
+}
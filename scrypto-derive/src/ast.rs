@@ -3,7 +3,7 @@ use syn::punctuated::Punctuated;
 use syn::token::{As, Brace, Paren};
 use syn::{
     braced, parenthesized, Attribute, Ident, ItemConst, ItemImpl, ItemMacro, ItemStruct, ItemUse,
-    Path, Result, Token, Visibility,
+    Path, Result, Token, Type, Visibility,
 };
 
 /// Represents a blueprint which is a module with an optional set of attributes
@@ -139,3 +139,38 @@ impl Parse for AliasableType {
         })
     }
 }
+
+pub struct CollectionsInner {
+    pub paren_token: Paren,
+    pub collections: Punctuated<Collection, Token![,]>,
+}
+
+impl Parse for CollectionsInner {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            paren_token: parenthesized!(content in input),
+            collections: content.parse_terminated(Collection::parse)?,
+        })
+    }
+}
+
+/// A collection declaration, such as `orders: SortedIndex<u64, Order>`, optionally preceded by
+/// `#[allow_ownership]` to set `allow_ownership` in the collection schema
+pub struct Collection {
+    pub attributes: Vec<Attribute>,
+    pub ident: Ident,
+    pub colon_token: Token![:],
+    pub ty: Type,
+}
+
+impl Parse for Collection {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            attributes: input.call(Attribute::parse_outer)?,
+            ident: input.parse()?,
+            colon_token: input.parse()?,
+            ty: input.parse()?,
+        })
+    }
+}
@@ -686,6 +686,56 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
         }
     }
 
+    // Collections are numbered in the order of their declaration
+    let mut collection_schemas = Vec::<TokenStream>::new();
+    let mut collection_accessors = Vec::<TokenStream>::new();
+    for attribute in &blueprint.attributes {
+        if attribute.path.is_ident("collections") {
+            let collections_inner = parse2::<ast::CollectionsInner>(attribute.tokens.clone())?;
+            for collection in collections_inner.collections {
+                let collection_index = u8::try_from(collection_schemas.len())
+                    .map_err(|_| Error::new(collection.ident.span(), "Too many collections"))?;
+                let (kind, key_type, value_type) = parse_collection_type(&collection.ty)?;
+                let mut allow_ownership = false;
+                for attribute in &collection.attributes {
+                    if attribute.path.is_ident("allow_ownership") && attribute.tokens.is_empty() {
+                        allow_ownership = true;
+                    } else {
+                        return Err(Error::new(
+                            attribute.span(),
+                            "Only `#[allow_ownership]` is supported on collections",
+                        ));
+                    }
+                }
+                collection_schemas.push(quote! {
+                    BlueprintCollectionSchema::#kind(BlueprintKeyValueSchema {
+                        key: TypeRef::Static(aggregator.add_child_type_and_descendents::<#key_type>()),
+                        value: TypeRef::Static(aggregator.add_child_type_and_descendents::<#value_type>()),
+                        allow_ownership: #allow_ownership,
+                    })
+                });
+
+                let ident = collection.ident;
+                let ty = collection.ty;
+                collection_accessors.push(quote! {
+                    #[allow(dead_code)]
+                    fn #ident() -> #ty {
+                        <#ty>::new(#collection_index)
+                    }
+                });
+            }
+        }
+    }
+    let output_collections = if collection_accessors.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            impl #bp_ident {
+                #(#collection_accessors)*
+            }
+        }
+    };
+
     #[cfg(feature = "no-schema")]
     let output_schema = quote! {};
     #[cfg(not(feature = "no-schema"))]
@@ -754,6 +804,7 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
                         }
                     }
                 } else if attribute.path.is_ident("types") {
+                } else if attribute.path.is_ident("collections") {
                 }
                 // None of the attributes to apply at the top-level of blueprint macros matched. So,
                 // we provide an error to the user that they're using an incorrect attribute macro
@@ -809,9 +860,12 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
                     let type_index = aggregator.add_child_type_and_descendents::<#bp_ident>();
                     fields.push(FieldSchema::static_field(type_index));
 
+                    // Aggregate collections
+                    let collections = vec![#(#collection_schemas),*];
+
                     let state = BlueprintStateSchemaInit {
                         fields,
-                        collections: Vec::new(),
+                        collections,
                     };
 
                     // Aggregate functions
@@ -1055,6 +1109,8 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
 
             #output_original_code
 
+            #output_collections
+
             #methods_struct
 
             #functions_struct
@@ -1367,6 +1423,39 @@ fn generate_dispatcher(bp_ident: &Ident, items: &[ImplItem]) -> Result<Vec<Token
     Ok(functions)
 }
 
+/// Splits a collection type, such as `SortedIndex<u64, Order>`, into its kind, key type and value
+/// type.
+fn parse_collection_type(ty: &Type) -> Result<(Ident, Type, Type)> {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Index" || segment.ident == "SortedIndex" {
+                if let PathArguments::AngleBracketed(arguments) = &segment.arguments {
+                    let types: Vec<&Type> = arguments
+                        .args
+                        .iter()
+                        .filter_map(|argument| match argument {
+                            GenericArgument::Type(ty) => Some(ty),
+                            _ => None,
+                        })
+                        .collect();
+                    if let [key_type, value_type] = types.as_slice() {
+                        return Ok((
+                            segment.ident.clone(),
+                            (*key_type).clone(),
+                            (*value_type).clone(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Err(Error::new(
+        ty.span(),
+        "Collections must be of `Index<K, V>` or `SortedIndex<K, V>` type",
+    ))
+}
+
 fn create_argument_ident(argument: &Pat, index: usize) -> Result<Ident> {
     Ok(match argument {
         // If we have a standard parameter name - use that
@@ -2017,9 +2106,11 @@ mod tests {
                             let type_index = aggregator.add_child_type_and_descendents::<Test>();
                             fields.push(FieldSchema::static_field(type_index));
 
+                            let collections = vec![];
+
                             let state = BlueprintStateSchemaInit {
                                 fields,
-                                collections: Vec::new(),
+                                collections,
                             };
 
                             let functions = {
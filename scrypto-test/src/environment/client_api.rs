@@ -91,6 +91,12 @@ implement_client_api! {
             key: Vec<u8>,
             buffer: Vec<u8>,
         ) -> Result<(), RuntimeError>,
+        actor_index_get: (
+            &mut self,
+            object_handle: ActorStateHandle,
+            collection_index: CollectionIndex,
+            key: Vec<u8>,
+        ) -> Result<Option<Vec<u8>>, RuntimeError>,
         actor_index_remove: (
             &mut self,
             object_handle: ActorStateHandle,
@@ -145,6 +151,13 @@ implement_client_api! {
             collection_index: CollectionIndex,
             count: u32,
         ) -> Result<Vec<(SortedKey, Vec<u8>)>, RuntimeError>,
+        actor_sorted_index_scan_after: (
+            &mut self,
+            object_handle: ActorStateHandle,
+            collection_index: CollectionIndex,
+            after_sorted_key: Option<&SortedKey>,
+            count: u32,
+        ) -> Result<Vec<(SortedKey, Vec<u8>)>, RuntimeError>,
     },
     ClientBlueprintApi: {
         call_function: (
@@ -189,6 +202,13 @@ implement_client_api! {
             node_id: &NodeId,
             key: &Vec<u8>,
         ) -> Result<Vec<u8>, RuntimeError>,
+        key_value_store_scan_keys: (
+            &mut self,
+            node_id: &NodeId,
+            after_key: Option<&Vec<u8>>,
+            limit: u32,
+        ) -> Result<Vec<Vec<u8>>, RuntimeError>,
+        key_value_store_len: (&mut self, node_id: &NodeId) -> Result<u32, RuntimeError>,
     },
     ClientObjectApi: {
         new_object: (
@@ -59,6 +59,22 @@ impl EncapsulatedRadixEngine {
             let state_updates = generate_pools_v1_1_state_updates(&substate_db);
             let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
             substate_db.commit(&db_updates);
+
+            let state_updates = generate_validator_jailing_state_updates(&substate_db, None);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
+
+            let state_updates = generate_subintents_state_updates(&substate_db);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
+
+            let state_updates = generate_vm_boot_scrypto_collections_state_updates();
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
+
+            let state_updates = generate_access_rule_extensions_state_updates(&substate_db);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
         }
 
         // Create the Id allocator we will be using throughout this test
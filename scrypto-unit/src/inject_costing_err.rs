@@ -13,13 +13,13 @@ use radix_engine::system::system_callback::SystemConfig;
 use radix_engine::system::system_callback_api::SystemCallbackObject;
 use radix_engine::system::system_modules::costing::{CostingError, FeeReserveError, OnApplyCost};
 use radix_engine::system::system_modules::execution_trace::{BucketSnapshot, ProofSnapshot};
-use radix_engine::track::{BootStore, NodeSubstates};
+use radix_engine::track::{BootStore, NodeSubstates, ScanKeysFrom};
 use radix_engine::transaction::WrappedSystem;
 use radix_engine::types::*;
 use radix_engine::vm::wasm::DefaultWasmEngine;
 use radix_engine::vm::Vm;
 use radix_engine_store_interface::db_key_mapper::SubstateKeyContent;
-use transaction::prelude::PreAllocatedAddress;
+use transaction::prelude::{ExecutableFeeSponsor, ExecutableSubintent, PreAllocatedAddress};
 
 pub type InjectSystemCostingError<'a, E> =
     InjectCostingError<SystemConfig<Vm<'a, DefaultWasmEngine, E>>>;
@@ -109,6 +109,8 @@ impl<'a, K: KernelCallbackObject + 'a> KernelCallbackObject for InjectCostingErr
         pre_allocated_addresses: &Vec<PreAllocatedAddress>,
         references: &IndexSet<Reference>,
         blobs: &IndexMap<Hash, Vec<u8>>,
+        subintents: &[ExecutableSubintent],
+        fee_sponsor: Option<&ExecutableFeeSponsor>,
     ) -> Result<Vec<u8>, RuntimeError>
     where
         Y: KernelApi<Self>,
@@ -120,6 +122,8 @@ impl<'a, K: KernelCallbackObject + 'a> KernelCallbackObject for InjectCostingErr
             pre_allocated_addresses,
             references,
             blobs,
+            subintents,
+            fee_sponsor,
         )
     }
 
@@ -483,10 +487,11 @@ impl<'a, M: KernelCallbackObject, Y: KernelApi<InjectCostingError<M>>>
         &mut self,
         node_id: &NodeId,
         partition_num: PartitionNumber,
+        from: ScanKeysFrom,
         count: u32,
     ) -> Result<Vec<SubstateKey>, RuntimeError> {
         self.api
-            .kernel_scan_keys::<K>(node_id, partition_num, count)
+            .kernel_scan_keys::<K>(node_id, partition_num, from, count)
     }
 
     fn kernel_drain_substates<K: SubstateKeyContent + 'static>(
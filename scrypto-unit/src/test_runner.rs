@@ -13,12 +13,15 @@ use radix_engine::system::system_callback::SystemConfig;
 use radix_engine::system::system_db_reader::{
     ObjectCollectionKey, SystemDatabaseReader, SystemDatabaseWriter,
 };
+use radix_engine::system::system_modules::kernel_trace::KernelTraceSink;
 use radix_engine::system::system_substates::FieldSubstate;
 use radix_engine::system::type_info::TypeInfoSubstate;
 use radix_engine::transaction::{
-    execute_preview, execute_transaction_with_system, BalanceChange, CommitResult,
-    CostingParameters, ExecutionConfig, PreviewError, TransactionReceipt, TransactionResult,
-    WrappedSystem,
+    execute_preview, execute_preview_with_overrides, execute_simulated_call,
+    execute_transaction_with_system, lock_estimated_fee, BalanceChange, CommitResult,
+    CostingParameters, ExecutionConfig, FeeEstimate, FeeEstimationError, FeeEstimationParams,
+    PreviewError, PreviewOverrides, SimulatedCall, SimulatedCallError, SimulatedCallResult,
+    TransactionReceipt, TransactionResult, WrappedSystem,
 };
 use radix_engine::types::*;
 use radix_engine::utils::*;
@@ -31,7 +34,7 @@ use radix_engine_interface::blueprints::account::ACCOUNT_SECURIFY_IDENT;
 use radix_engine_interface::blueprints::consensus_manager::{
     ConsensusManagerConfig, ConsensusManagerGetCurrentEpochInput,
     ConsensusManagerGetCurrentTimeInputV2, ConsensusManagerNextRoundInput, EpochChangeCondition,
-    LeaderProposalHistory, CONSENSUS_MANAGER_GET_CURRENT_EPOCH_IDENT,
+    LeaderProposalHistory, ValidatorJailingConfig, CONSENSUS_MANAGER_GET_CURRENT_EPOCH_IDENT,
     CONSENSUS_MANAGER_GET_CURRENT_TIME_IDENT, CONSENSUS_MANAGER_NEXT_ROUND_IDENT,
     VALIDATOR_STAKE_AS_OWNER_IDENT,
 };
@@ -351,6 +354,13 @@ pub struct TestRunnerBuilder<E, D> {
     with_seconds_precision_update: bool,
     with_crypto_utils_update: bool,
     with_pools_v1_1: bool,
+    with_validator_jailing_update: bool,
+    with_subintents_update: bool,
+    with_scrypto_collections_update: bool,
+    with_access_rule_extensions_update: bool,
+
+    /// The validator jailing configuration installed by the validator jailing update
+    validator_jailing: Option<ValidatorJailingConfig>,
 }
 
 impl TestRunnerBuilder<NoExtension, InMemorySubstateDatabase> {
@@ -364,6 +374,11 @@ impl TestRunnerBuilder<NoExtension, InMemorySubstateDatabase> {
             with_seconds_precision_update: true,
             with_crypto_utils_update: true,
             with_pools_v1_1: true,
+            with_validator_jailing_update: true,
+            with_subintents_update: true,
+            with_scrypto_collections_update: true,
+            with_access_rule_extensions_update: true,
+            validator_jailing: None,
         }
     }
 }
@@ -384,6 +399,11 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             with_seconds_precision_update: self.with_seconds_precision_update,
             with_crypto_utils_update: self.with_crypto_utils_update,
             with_pools_v1_1: self.with_pools_v1_1,
+            with_validator_jailing_update: self.with_validator_jailing_update,
+            with_subintents_update: self.with_subintents_update,
+            with_scrypto_collections_update: self.with_scrypto_collections_update,
+            with_access_rule_extensions_update: self.with_access_rule_extensions_update,
+            validator_jailing: self.validator_jailing,
         }
     }
 
@@ -410,6 +430,11 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             with_seconds_precision_update: self.with_seconds_precision_update,
             with_crypto_utils_update: self.with_crypto_utils_update,
             with_pools_v1_1: self.with_pools_v1_1,
+            with_validator_jailing_update: self.with_validator_jailing_update,
+            with_subintents_update: self.with_subintents_update,
+            with_scrypto_collections_update: self.with_scrypto_collections_update,
+            with_access_rule_extensions_update: self.with_access_rule_extensions_update,
+            validator_jailing: self.validator_jailing,
         }
     }
 
@@ -423,6 +448,11 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             with_seconds_precision_update: self.with_seconds_precision_update,
             with_crypto_utils_update: self.with_crypto_utils_update,
             with_pools_v1_1: self.with_pools_v1_1,
+            with_validator_jailing_update: self.with_validator_jailing_update,
+            with_subintents_update: self.with_subintents_update,
+            with_scrypto_collections_update: self.with_scrypto_collections_update,
+            with_access_rule_extensions_update: self.with_access_rule_extensions_update,
+            validator_jailing: self.validator_jailing,
         }
     }
 
@@ -441,6 +471,31 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
         self
     }
 
+    pub fn without_validator_jailing_update(mut self) -> Self {
+        self.with_validator_jailing_update = false;
+        self
+    }
+
+    pub fn with_validator_jailing(mut self, validator_jailing: ValidatorJailingConfig) -> Self {
+        self.validator_jailing = Some(validator_jailing);
+        self
+    }
+
+    pub fn without_subintents_update(mut self) -> Self {
+        self.with_subintents_update = false;
+        self
+    }
+
+    pub fn without_scrypto_collections_update(mut self) -> Self {
+        self.with_scrypto_collections_update = false;
+        self
+    }
+
+    pub fn without_access_rule_extensions_update(mut self) -> Self {
+        self.with_access_rule_extensions_update = false;
+        self
+    }
+
     pub fn build_from_snapshot(
         self,
         snapshot: TestRunnerSnapshot,
@@ -557,6 +612,31 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             substate_db.commit(&db_updates);
         }
 
+        if self.with_validator_jailing_update {
+            let state_updates =
+                generate_validator_jailing_state_updates(&substate_db, self.validator_jailing);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
+        }
+
+        if self.with_subintents_update {
+            let state_updates = generate_subintents_state_updates(&substate_db);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
+        }
+
+        if self.with_scrypto_collections_update {
+            let state_updates = generate_vm_boot_scrypto_collections_state_updates();
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
+        }
+
+        if self.with_access_rule_extensions_update {
+            let state_updates = generate_access_rule_extensions_state_updates(&substate_db);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            substate_db.commit(&db_updates);
+        }
+
         let runner = TestRunner {
             scrypto_vm,
             native_vm,
@@ -1622,6 +1702,26 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunner<E, D> {
         )
     }
 
+    /// Executes the manifest with a cost profile attached to the receipt.
+    pub fn execute_manifest_with_cost_profile<T>(
+        &mut self,
+        manifest: TransactionManifestV1,
+        initial_proofs: T,
+    ) -> TransactionReceipt
+    where
+        T: IntoIterator<Item = NonFungibleGlobalId>,
+    {
+        let nonce = self.next_transaction_nonce();
+        self.execute_transaction(
+            TestTransaction::new_from_nonce(manifest, nonce)
+                .prepare()
+                .expect("expected transaction to be preparable")
+                .get_executable(initial_proofs.into_iter().collect()),
+            CostingParameters::default(),
+            ExecutionConfig::for_test_transaction().with_cost_profile(true),
+        )
+    }
+
     pub fn execute_transaction(
         &mut self,
         executable: Executable,
@@ -1643,8 +1743,10 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunner<E, D> {
         mut execution_config: ExecutionConfig,
         init: T::Init,
     ) -> TransactionReceipt {
-        // Override the kernel trace config
-        execution_config = execution_config.with_kernel_trace(self.trace);
+        // Override the kernel trace config, unless its records are captured
+        if matches!(execution_config.kernel_trace_sink, KernelTraceSink::Print) {
+            execution_config = execution_config.with_kernel_trace(self.trace);
+        }
 
         if executable
             .costing_parameters()
@@ -1695,6 +1797,63 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunner<E, D> {
         execute_preview(&self.database, vm, network, preview_intent, self.trace)
     }
 
+    pub fn preview_with_overrides(
+        &mut self,
+        preview_intent: PreviewIntentV1,
+        network: &NetworkDefinition,
+        overrides: &PreviewOverrides,
+    ) -> Result<TransactionReceipt, PreviewError> {
+        let vm = Vm {
+            scrypto_vm: &self.scrypto_vm,
+            native_vm: self.native_vm.clone(),
+        };
+
+        execute_preview_with_overrides(
+            &self.database,
+            vm,
+            network,
+            preview_intent,
+            overrides,
+            self.trace,
+        )
+    }
+
+    pub fn lock_estimated_fee(
+        &mut self,
+        manifest: TransactionManifestV1,
+        params: &FeeEstimationParams,
+    ) -> Result<(TransactionManifestV1, FeeEstimate), FeeEstimationError> {
+        let vm = Vm {
+            scrypto_vm: &self.scrypto_vm,
+            native_vm: self.native_vm.clone(),
+        };
+
+        lock_estimated_fee(
+            &self.database,
+            vm,
+            &NetworkDefinition::simulator(),
+            manifest,
+            params,
+        )
+    }
+
+    pub fn simulate_call(
+        &mut self,
+        call: &SimulatedCall,
+    ) -> Result<SimulatedCallResult, SimulatedCallError> {
+        let vm = Vm {
+            scrypto_vm: &self.scrypto_vm,
+            native_vm: self.native_vm.clone(),
+        };
+
+        execute_simulated_call(
+            &self.database,
+            vm,
+            &ExecutionConfig::for_preview(NetworkDefinition::simulator()),
+            call,
+        )
+    }
+
     pub fn preview_manifest(
         &mut self,
         manifest: TransactionManifestV1,
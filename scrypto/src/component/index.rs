@@ -0,0 +1,137 @@
+use crate::engine::scrypto_env::ScryptoVmV1Api;
+use radix_engine_interface::api::{CollectionIndex, ACTOR_STATE_SELF};
+use radix_engine_interface::data::scrypto::*;
+use radix_engine_interface::types::SortedKey;
+use sbor::rust::marker::PhantomData;
+use sbor::rust::prelude::*;
+
+/// An unordered index collection of the current component, declared with the
+/// `#[collections(...)]` blueprint attribute.
+///
+/// Unlike a [`KeyValueStore`](super::KeyValueStore), an index can be scanned, but its values
+/// are returned by copy rather than as entry references.
+pub struct Index<K: ScryptoEncode + ScryptoDecode, V: ScryptoEncode + ScryptoDecode> {
+    pub collection_index: CollectionIndex,
+    pub key: PhantomData<K>,
+    pub value: PhantomData<V>,
+}
+
+impl<K: ScryptoEncode + ScryptoDecode, V: ScryptoEncode + ScryptoDecode> Index<K, V> {
+    pub const fn new(collection_index: CollectionIndex) -> Self {
+        Self {
+            collection_index,
+            key: PhantomData,
+            value: PhantomData,
+        }
+    }
+
+    /// Inserts an entry, replacing the existing one with the same key.
+    pub fn insert(&self, key: K, value: V) {
+        ScryptoVmV1Api::actor_index_insert(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            scrypto_encode(&key).unwrap(),
+            scrypto_encode(&value).unwrap(),
+        );
+    }
+
+    /// Returns the value of an entry if it exists.
+    pub fn get(&self, key: &K) -> Option<V> {
+        ScryptoVmV1Api::actor_index_get(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            scrypto_encode(key).unwrap(),
+        )
+        .map(|value| scrypto_decode(&value).unwrap())
+    }
+
+    /// Removes an entry and returns its value if it exists.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        ScryptoVmV1Api::actor_index_remove(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            scrypto_encode(key).unwrap(),
+        )
+        .map(|value| scrypto_decode(&value).unwrap())
+    }
+
+    /// Returns up to `limit` keys, in no particular order.
+    pub fn scan_keys(&self, limit: u32) -> Vec<K> {
+        ScryptoVmV1Api::actor_index_scan_keys(ACTOR_STATE_SELF, self.collection_index, limit)
+            .iter()
+            .map(|key| scrypto_decode(key).unwrap())
+            .collect()
+    }
+}
+
+/// A sorted index collection of the current component, declared with the
+/// `#[collections(...)]` blueprint attribute.
+///
+/// Entries are ordered by a `u16` sort prefix. The ones sharing a sort prefix are in no particular
+/// order.
+pub struct SortedIndex<K: ScryptoEncode + ScryptoDecode, V: ScryptoEncode + ScryptoDecode> {
+    pub collection_index: CollectionIndex,
+    pub key: PhantomData<K>,
+    pub value: PhantomData<V>,
+}
+
+impl<K: ScryptoEncode + ScryptoDecode, V: ScryptoEncode + ScryptoDecode> SortedIndex<K, V> {
+    pub const fn new(collection_index: CollectionIndex) -> Self {
+        Self {
+            collection_index,
+            key: PhantomData,
+            value: PhantomData,
+        }
+    }
+
+    /// Inserts an entry under the given sort prefix, replacing the existing one with the same
+    /// sort prefix and key.
+    pub fn insert(&self, sort_prefix: u16, key: K, value: V) {
+        ScryptoVmV1Api::actor_sorted_index_insert(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            &Self::sorted_key(sort_prefix, &key),
+            scrypto_encode(&value).unwrap(),
+        );
+    }
+
+    /// Removes an entry and returns its value if it exists.
+    pub fn remove(&self, sort_prefix: u16, key: &K) -> Option<V> {
+        ScryptoVmV1Api::actor_sorted_index_remove(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            &Self::sorted_key(sort_prefix, key),
+        )
+        .map(|value| scrypto_decode(&value).unwrap())
+    }
+
+    /// Returns up to `limit` first entries, in the ascending order of their sort prefixes.
+    pub fn scan(&self, limit: u32) -> Vec<(u16, K, V)> {
+        self.scan_after(None, limit)
+    }
+
+    /// Returns up to `limit` entries following the given sort prefix and key, in the same order
+    /// as [`scan`](Self::scan). Passing the last entry of a page continues with the next page.
+    pub fn scan_after(&self, after: Option<(u16, &K)>, limit: u32) -> Vec<(u16, K, V)> {
+        let after_sorted_key = after.map(|(sort_prefix, key)| Self::sorted_key(sort_prefix, key));
+        ScryptoVmV1Api::actor_sorted_index_scan(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            after_sorted_key.as_ref(),
+            limit,
+        )
+        .into_iter()
+        .map(|((sort_prefix, key), value)| {
+            (
+                u16::from_be_bytes(sort_prefix),
+                scrypto_decode(&key).unwrap(),
+                scrypto_decode(&value).unwrap(),
+            )
+        })
+        .collect()
+    }
+
+    fn sorted_key(sort_prefix: u16, key: &K) -> SortedKey {
+        (sort_prefix.to_be_bytes(), scrypto_encode(key).unwrap())
+    }
+}
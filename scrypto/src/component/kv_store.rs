@@ -115,6 +115,47 @@ impl<
 
         scrypto_decode(&rtn).unwrap()
     }
+
+    /// Returns up to `limit` keys of this map, in a deterministic order.
+    pub fn iter_keys(&self, limit: u32) -> Vec<K> {
+        Self::decode_keys(ScryptoVmV1Api::kv_store_scan_keys(
+            self.id.as_node_id(),
+            None,
+            limit,
+        ))
+    }
+
+    /// Returns up to `limit` keys of this map which follow the given key, in the order of
+    /// [`KeyValueStore::iter_keys`].
+    ///
+    /// Passing the last key of a previous page reads the next page.
+    pub fn iter_keys_after(&self, after_key: &K, limit: u32) -> Vec<K> {
+        let after_key_payload = scrypto_encode(after_key).unwrap();
+        Self::decode_keys(ScryptoVmV1Api::kv_store_scan_keys(
+            self.id.as_node_id(),
+            Some(&after_key_payload),
+            limit,
+        ))
+    }
+
+    /// Returns the number of entries in this map.
+    ///
+    /// Every entry is visited, so the cost grows with the size of the map.
+    pub fn len(&self) -> u32 {
+        ScryptoVmV1Api::kv_store_len(self.id.as_node_id())
+    }
+
+    /// Returns whether this map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.iter_keys(1).is_empty()
+    }
+
+    fn decode_keys(key_payloads: Vec<Vec<u8>>) -> Vec<K> {
+        key_payloads
+            .iter()
+            .map(|key_payload| scrypto_decode(key_payload).unwrap())
+            .collect()
+    }
 }
 
 //========
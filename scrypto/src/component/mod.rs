@@ -1,4 +1,5 @@
 mod component;
+mod index;
 mod kv_store;
 mod kv_store_data_ref;
 mod object;
@@ -6,6 +7,7 @@ mod package;
 mod stubs;
 
 pub use component::*;
+pub use index::*;
 pub use kv_store::*;
 pub use kv_store_data_ref::*;
 pub use object::*;
@@ -89,6 +89,7 @@ extern_blueprint_internal! {
     {
         fn register(&mut self);
         fn unregister(&mut self);
+        fn unjail(&mut self);
         fn stake_as_owner(&mut self, stake: Bucket) -> Bucket;
         fn stake(&mut self, stake: Bucket) -> Bucket;
         fn unstake(&mut self, stake_unit_bucket: Bucket) -> Bucket;
@@ -113,6 +114,7 @@ extern_blueprint_internal! {
             proposals_missed: u64,
         );
         fn apply_reward(&mut self, xrd_bucket: Bucket, epoch: Epoch);
+        fn apply_reliability(&mut self, is_reliable: bool, epoch: Epoch) -> Option<Bucket>;
     }
 }
 
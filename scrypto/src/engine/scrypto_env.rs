@@ -3,13 +3,13 @@ use radix_engine_common::math::Decimal;
 use radix_engine_common::types::GlobalAddressReservation;
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::key_value_entry_api::KeyValueEntryHandle;
-use radix_engine_interface::api::{ActorRefHandle, FieldValue};
-use radix_engine_interface::api::{AttachedModuleId, FieldIndex, LockFlags};
+use radix_engine_interface::api::{ActorRefHandle, ActorStateHandle, FieldValue};
+use radix_engine_interface::api::{AttachedModuleId, CollectionIndex, FieldIndex, LockFlags};
 use radix_engine_interface::crypto::Hash;
 use radix_engine_interface::data::scrypto::*;
 use radix_engine_interface::types::PackageAddress;
 use radix_engine_interface::types::{BlueprintId, GlobalAddress};
-use radix_engine_interface::types::{Level, NodeId, SubstateHandle};
+use radix_engine_interface::types::{Level, NodeId, SortedKey, SubstateHandle};
 use radix_engine_interface::*;
 use sbor::rust::prelude::*;
 
@@ -194,12 +194,150 @@ impl ScryptoVmV1Api {
         removed
     }
 
+    pub fn kv_store_scan_keys(
+        node_id: &NodeId,
+        after_key: Option<&Vec<u8>>,
+        limit: u32,
+    ) -> Vec<Vec<u8>> {
+        let after_key = scrypto_encode(&after_key).unwrap();
+        let keys = copy_buffer(unsafe {
+            kv_store::kv_store_scan_keys(
+                node_id.as_ref().as_ptr(),
+                node_id.as_ref().len(),
+                after_key.as_ptr(),
+                after_key.len(),
+                limit,
+            )
+        });
+        scrypto_decode(&keys).unwrap()
+    }
+
+    pub fn kv_store_len(node_id: &NodeId) -> u32 {
+        unsafe { kv_store::kv_store_len(node_id.as_ref().as_ptr(), node_id.as_ref().len()) }
+    }
+
     pub fn actor_open_field(object_handle: u32, field: u8, flags: LockFlags) -> SubstateHandle {
         let handle =
             unsafe { actor::actor_open_field(object_handle, u32::from(field), flags.bits()) };
         handle
     }
 
+    pub fn actor_index_insert(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) {
+        unsafe {
+            actor::actor_index_insert(
+                object_handle,
+                u32::from(collection_index),
+                key.as_ptr(),
+                key.len(),
+                value.as_ptr(),
+                value.len(),
+            )
+        }
+    }
+
+    pub fn actor_index_remove(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+    ) -> Option<Vec<u8>> {
+        let removed = copy_buffer(unsafe {
+            actor::actor_index_remove(
+                object_handle,
+                u32::from(collection_index),
+                key.as_ptr(),
+                key.len(),
+            )
+        });
+        scrypto_decode(&removed).unwrap()
+    }
+
+    pub fn actor_index_get(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+    ) -> Option<Vec<u8>> {
+        let value = copy_buffer(unsafe {
+            actor::actor_index_get(
+                object_handle,
+                u32::from(collection_index),
+                key.as_ptr(),
+                key.len(),
+            )
+        });
+        scrypto_decode(&value).unwrap()
+    }
+
+    pub fn actor_index_scan_keys(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        limit: u32,
+    ) -> Vec<Vec<u8>> {
+        let keys = copy_buffer(unsafe {
+            actor::actor_index_scan_keys(object_handle, u32::from(collection_index), limit)
+        });
+        scrypto_decode(&keys).unwrap()
+    }
+
+    pub fn actor_sorted_index_insert(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        sorted_key: &SortedKey,
+        value: Vec<u8>,
+    ) {
+        let sorted_key = scrypto_encode(sorted_key).unwrap();
+        unsafe {
+            actor::actor_sorted_index_insert(
+                object_handle,
+                u32::from(collection_index),
+                sorted_key.as_ptr(),
+                sorted_key.len(),
+                value.as_ptr(),
+                value.len(),
+            )
+        }
+    }
+
+    pub fn actor_sorted_index_remove(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        sorted_key: &SortedKey,
+    ) -> Option<Vec<u8>> {
+        let sorted_key = scrypto_encode(sorted_key).unwrap();
+        let removed = copy_buffer(unsafe {
+            actor::actor_sorted_index_remove(
+                object_handle,
+                u32::from(collection_index),
+                sorted_key.as_ptr(),
+                sorted_key.len(),
+            )
+        });
+        scrypto_decode(&removed).unwrap()
+    }
+
+    pub fn actor_sorted_index_scan(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        after_sorted_key: Option<&SortedKey>,
+        limit: u32,
+    ) -> Vec<(SortedKey, Vec<u8>)> {
+        let after_sorted_key = scrypto_encode(&after_sorted_key).unwrap();
+        let entries = copy_buffer(unsafe {
+            actor::actor_sorted_index_scan(
+                object_handle,
+                u32::from(collection_index),
+                after_sorted_key.as_ptr(),
+                after_sorted_key.len(),
+                limit,
+            )
+        });
+        scrypto_decode(&entries).unwrap()
+    }
+
     pub fn actor_get_object_id(actor_ref_handle: ActorRefHandle) -> NodeId {
         let node_id = copy_buffer(unsafe { actor::actor_get_object_id(actor_ref_handle) });
 
@@ -173,6 +173,67 @@ pub mod actor {
             event_data_len: usize,
             event_flags: u32,
         );
+
+        /// Inserts an entry into an index collection of the current actor
+        pub fn actor_index_insert(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            key_ptr: *const u8,
+            key_len: usize,
+            value_ptr: *const u8,
+            value_len: usize,
+        );
+
+        /// Removes an entry from an index collection of the current actor
+        pub fn actor_index_remove(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            key_ptr: *const u8,
+            key_len: usize,
+        ) -> Buffer;
+
+        /// Reads an entry of an index collection of the current actor
+        pub fn actor_index_get(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            key_ptr: *const u8,
+            key_len: usize,
+        ) -> Buffer;
+
+        /// Reads up to `limit` keys of an index collection of the current actor
+        pub fn actor_index_scan_keys(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            limit: u32,
+        ) -> Buffer;
+
+        /// Inserts an entry into a sorted index collection of the current actor
+        pub fn actor_sorted_index_insert(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            sorted_key_ptr: *const u8,
+            sorted_key_len: usize,
+            value_ptr: *const u8,
+            value_len: usize,
+        );
+
+        /// Removes an entry from a sorted index collection of the current actor
+        pub fn actor_sorted_index_remove(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            sorted_key_ptr: *const u8,
+            sorted_key_len: usize,
+        ) -> Buffer;
+
+        /// Reads up to `limit` entries of a sorted index collection of the current actor, starting
+        /// after the sorted key given as an encoded `Option<SortedKey>`
+        pub fn actor_sorted_index_scan(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            after_sorted_key_ptr: *const u8,
+            after_sorted_key_len: usize,
+            limit: u32,
+        ) -> Buffer;
     }
 }
 
@@ -199,6 +260,19 @@ pub mod kv_store {
             key: *const u8,
             key_len: usize,
         ) -> Buffer;
+
+        /// Reads up to `limit` keys of a key value store, starting after the key given as an
+        /// encoded `Option<Vec<u8>>`
+        pub fn kv_store_scan_keys(
+            key_value_store_id_ptr: *const u8,
+            key_value_store_id_len: usize,
+            after_key_ptr: *const u8,
+            after_key_len: usize,
+            limit: u32,
+        ) -> Buffer;
+
+        /// Counts the entries of a key value store
+        pub fn kv_store_len(key_value_store_id_ptr: *const u8, key_value_store_id_len: usize) -> u32;
     }
 }
 
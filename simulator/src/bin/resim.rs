@@ -5,5 +5,12 @@ use simulator::resim;
 pub fn main() -> Result<(), resim::Error> {
     #[cfg(windows)]
     control::set_virtual_terminal(true).unwrap();
-    resim::run()
+    match resim::run() {
+        // The diagnostic already describes the error, so it is reported on its own
+        Err(resim::Error::CompileError(_, diagnostic)) => {
+            eprintln!("{}", diagnostic);
+            std::process::exit(1);
+        }
+        result => result,
+    }
 }
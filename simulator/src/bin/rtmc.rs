@@ -5,5 +5,12 @@ use simulator::rtmc;
 pub fn main() -> Result<(), rtmc::Error> {
     #[cfg(windows)]
     control::set_virtual_terminal(true).unwrap();
-    rtmc::run()
+    match rtmc::run() {
+        // The diagnostic already describes the error, so it is reported on its own
+        Err(rtmc::Error::CompileError(_, diagnostic)) => {
+            eprintln!("{}", diagnostic);
+            std::process::exit(1);
+        }
+        result => result,
+    }
 }
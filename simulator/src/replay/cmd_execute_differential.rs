@@ -0,0 +1,566 @@
+use super::ledger_transaction_execution::*;
+use super::txn_reader::TxnReader;
+use super::Error;
+use clap::Parser;
+use flate2::read::GzDecoder;
+use flume;
+use radix_engine::system::system_modules::costing::CostProfileFrame;
+use radix_engine::system::system_modules::execution_trace::ExecutionTrace;
+use radix_engine::transaction::{
+    ExecutionConfig, TransactionOutcome, TransactionReceipt, TransactionResult,
+};
+use radix_engine::types::*;
+use radix_engine::vm::wasm::*;
+use radix_engine::vm::ScryptoVm;
+use radix_engine_interface::prelude::NetworkDefinition;
+use radix_engine_store_interface::db_key_mapper::SpreadPrefixKeyMapper;
+use radix_engine_store_interface::interface::CommittableSubstateDatabase;
+use radix_engine_stores::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
+use std::fs::File;
+use std::path::PathBuf;
+use std::thread;
+use tar::Archive;
+
+/// Run transactions in archive on both Wasmi and Wasmer, using RocksDB, and report any divergence
+/// between the two engines.
+///
+/// Both executions record their execution traces and cost profiles, so that divergences can be
+/// traced down to the first diverging invocation. The state updates of the Wasmi execution are
+/// committed. Fails if any transaction diverged.
+#[derive(Parser, Debug)]
+pub struct TxnExecuteDifferential {
+    /// The transaction file, in `.tar.gz` format, with entries sorted
+    pub source: PathBuf,
+    /// Path to a folder for storing state
+    pub database_dir: PathBuf,
+
+    /// The network to use, [mainnet | stokenet]
+    #[clap(short, long)]
+    pub network: Option<String>,
+    /// The max version to execute
+    #[clap(short, long)]
+    pub max_version: Option<u64>,
+}
+
+/// A difference between the receipts of a transaction executed on Wasmi and on Wasmer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionDivergence {
+    /// The transactions were committed/rejected/aborted differently, or failed with different
+    /// errors.
+    Result { wasmi: String, wasmer: String },
+    /// The instruction outputs of successful transactions differ.
+    Outputs,
+    /// The fee summaries differ.
+    Fees { wasmi: String, wasmer: String },
+    /// The execution traces of an invocation differ, e.g. in the resources passed in or out.
+    /// Invocations are numbered depth-first, in the order they were made, and only the first
+    /// diverging one is reported. `None` if an engine made fewer invocations.
+    InvocationTrace {
+        index: usize,
+        wasmi: Option<String>,
+        wasmer: Option<String>,
+    },
+    /// The cost profiles of an invocation differ, numbered as the frames of the cost profile.
+    /// Only the first diverging one is reported.
+    InvocationCosts {
+        index: usize,
+        wasmi: Option<CostProfileFrame>,
+        wasmer: Option<CostProfileFrame>,
+    },
+    /// The committed state updates differ.
+    StateUpdates,
+    /// The emitted events differ.
+    Events,
+    /// One execution is a flash and the other is not.
+    ReceiptType {
+        wasmi: &'static str,
+        wasmer: &'static str,
+    },
+}
+
+impl TxnExecuteDifferential {
+    pub fn run(&self) -> Result<(), Error> {
+        let network = match &self.network {
+            Some(n) => NetworkDefinition::from_str(n).map_err(Error::ParseNetworkError)?,
+            None => NetworkDefinition::mainnet(),
+        };
+
+        let cur_version = {
+            let database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+            let cur_version = database.get_current_version();
+            if cur_version >= self.max_version.unwrap_or(u64::MAX) {
+                return Ok(());
+            }
+            cur_version
+        };
+        let to_version = self.max_version.clone();
+
+        let start = std::time::Instant::now();
+        let (tx, rx) = flume::bounded(10);
+
+        // txn reader
+        let mut txn_reader = if self.source.is_file() {
+            let tar_gz = File::open(&self.source).map_err(Error::IOError)?;
+            let tar = GzDecoder::new(tar_gz);
+            let archive = Archive::new(tar);
+            TxnReader::TransactionFile(archive)
+        } else if self.source.is_dir() {
+            TxnReader::StateManagerDatabaseDir(self.source.clone())
+        } else {
+            return Err(Error::InvalidTransactionSource);
+        };
+        let txn_read_thread_handle =
+            thread::spawn(move || txn_reader.read(cur_version, to_version, tx));
+
+        // txn executor
+        let mut database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+        let txn_write_thread_handle = thread::spawn(move || {
+            let wasmi_vm = ScryptoVm::<WasmiEngine>::default();
+            let wasmer_vm = ScryptoVm::<WasmerEngine>::default();
+            let mut divergent_transactions = 0usize;
+            for tx_payload in rx.iter() {
+                let prepared = prepare_ledger_transaction(&tx_payload);
+                let wasmi_receipt = execute_prepared_ledger_transaction_with_config(
+                    &database,
+                    &wasmi_vm,
+                    &network,
+                    &prepared,
+                    with_invocation_records,
+                );
+                let wasmer_receipt = execute_prepared_ledger_transaction_with_config(
+                    &database,
+                    &wasmer_vm,
+                    &network,
+                    &prepared,
+                    with_invocation_records,
+                );
+                let divergences = find_ledger_divergences(&wasmi_receipt, &wasmer_receipt);
+
+                let database_updates = wasmi_receipt
+                    .into_state_updates()
+                    .create_database_updates::<SpreadPrefixKeyMapper>();
+                database.commit(&database_updates);
+                let new_version = database.get_current_version();
+
+                if !divergences.is_empty() {
+                    divergent_transactions += 1;
+                    println!("Divergence at version {}:", new_version);
+                    for divergence in &divergences {
+                        println!("  {:?}", divergence);
+                    }
+                }
+
+                if new_version < 1000 || new_version % 1000 == 0 {
+                    println!(
+                        "New version: {}, divergent transactions: {}",
+                        new_version, divergent_transactions
+                    );
+                }
+            }
+
+            println!("Time elapsed: {:?}", start.elapsed());
+            println!("State version: {}", database.get_current_version());
+            println!("Divergent transactions: {}", divergent_transactions);
+            divergent_transactions
+        });
+
+        txn_read_thread_handle.join().unwrap()?;
+        let divergent_transactions = txn_write_thread_handle.join().unwrap();
+
+        if divergent_transactions > 0 {
+            return Err(Error::DivergentTransactions(divergent_transactions));
+        }
+        Ok(())
+    }
+}
+
+fn with_invocation_records(config: ExecutionConfig) -> ExecutionConfig {
+    config.with_execution_trace(true).with_cost_profile(true)
+}
+
+/// Compares the receipts of a ledger transaction executed on Wasmi and on Wasmer.
+pub fn find_ledger_divergences(
+    wasmi_receipt: &LedgerTransactionReceipt,
+    wasmer_receipt: &LedgerTransactionReceipt,
+) -> Vec<ExecutionDivergence> {
+    match (wasmi_receipt, wasmer_receipt) {
+        (
+            LedgerTransactionReceipt::Standard(wasmi_receipt),
+            LedgerTransactionReceipt::Standard(wasmer_receipt),
+        ) => find_divergences(wasmi_receipt, wasmer_receipt),
+        (
+            LedgerTransactionReceipt::Flash(wasmi_receipt),
+            LedgerTransactionReceipt::Flash(wasmer_receipt),
+        ) => {
+            if scrypto_encode(&wasmi_receipt.state_updates).unwrap()
+                != scrypto_encode(&wasmer_receipt.state_updates).unwrap()
+            {
+                vec![ExecutionDivergence::StateUpdates]
+            } else {
+                vec![]
+            }
+        }
+        (wasmi_receipt, wasmer_receipt) => vec![ExecutionDivergence::ReceiptType {
+            wasmi: receipt_type(wasmi_receipt),
+            wasmer: receipt_type(wasmer_receipt),
+        }],
+    }
+}
+
+fn receipt_type(receipt: &LedgerTransactionReceipt) -> &'static str {
+    match receipt {
+        LedgerTransactionReceipt::Flash(_) => "Flash",
+        LedgerTransactionReceipt::Standard(_) => "Standard",
+    }
+}
+
+/// Compares the receipts of a transaction executed on Wasmi and on Wasmer.
+pub fn find_divergences(
+    wasmi_receipt: &TransactionReceipt,
+    wasmer_receipt: &TransactionReceipt,
+) -> Vec<ExecutionDivergence> {
+    let mut divergences = Vec::new();
+
+    if scrypto_encode(&wasmi_receipt.fee_summary).unwrap()
+        != scrypto_encode(&wasmer_receipt.fee_summary).unwrap()
+    {
+        divergences.push(ExecutionDivergence::Fees {
+            wasmi: format!("{:?}", wasmi_receipt.fee_summary),
+            wasmer: format!("{:?}", wasmer_receipt.fee_summary),
+        });
+    }
+
+    if let (Some(wasmi_profile), Some(wasmer_profile)) =
+        (&wasmi_receipt.cost_profile, &wasmer_receipt.cost_profile)
+    {
+        let frame_count = wasmi_profile.frames.len().max(wasmer_profile.frames.len());
+        if let Some(index) =
+            (0..frame_count).find(|i| wasmi_profile.frames.get(*i) != wasmer_profile.frames.get(*i))
+        {
+            divergences.push(ExecutionDivergence::InvocationCosts {
+                index,
+                wasmi: wasmi_profile.frames.get(index).cloned(),
+                wasmer: wasmer_profile.frames.get(index).cloned(),
+            });
+        }
+    }
+
+    match (&wasmi_receipt.result, &wasmer_receipt.result) {
+        (TransactionResult::Commit(wasmi_commit), TransactionResult::Commit(wasmer_commit)) => {
+            match (&wasmi_commit.outcome, &wasmer_commit.outcome) {
+                (
+                    TransactionOutcome::Success(wasmi_outputs),
+                    TransactionOutcome::Success(wasmer_outputs),
+                ) => {
+                    if scrypto_encode(wasmi_outputs).unwrap()
+                        != scrypto_encode(wasmer_outputs).unwrap()
+                    {
+                        divergences.push(ExecutionDivergence::Outputs);
+                    }
+                }
+                (wasmi_outcome, wasmer_outcome) => {
+                    let wasmi = format!("{:?}", wasmi_outcome);
+                    let wasmer = format!("{:?}", wasmer_outcome);
+                    if wasmi != wasmer {
+                        divergences.push(ExecutionDivergence::Result { wasmi, wasmer });
+                    }
+                }
+            }
+            if scrypto_encode(&wasmi_commit.state_updates).unwrap()
+                != scrypto_encode(&wasmer_commit.state_updates).unwrap()
+            {
+                divergences.push(ExecutionDivergence::StateUpdates);
+            }
+            if wasmi_commit.application_events != wasmer_commit.application_events {
+                divergences.push(ExecutionDivergence::Events);
+            }
+            if let (Some(wasmi_trace), Some(wasmer_trace)) = (
+                &wasmi_commit.execution_trace,
+                &wasmer_commit.execution_trace,
+            ) {
+                let wasmi_invocations = flatten_execution_traces(&wasmi_trace.execution_traces);
+                let wasmer_invocations = flatten_execution_traces(&wasmer_trace.execution_traces);
+                let invocation_count = wasmi_invocations.len().max(wasmer_invocations.len());
+                if let Some(index) = (0..invocation_count).find(|i| {
+                    wasmi_invocations
+                        .get(*i)
+                        .map(|t| encode_invocation_trace(t))
+                        != wasmer_invocations
+                            .get(*i)
+                            .map(|t| encode_invocation_trace(t))
+                }) {
+                    divergences.push(ExecutionDivergence::InvocationTrace {
+                        index,
+                        wasmi: wasmi_invocations
+                            .get(index)
+                            .map(|t| format_invocation_trace(t)),
+                        wasmer: wasmer_invocations
+                            .get(index)
+                            .map(|t| format_invocation_trace(t)),
+                    });
+                }
+            }
+        }
+        (wasmi_result, wasmer_result) => {
+            let wasmi = format!("{:?}", wasmi_result);
+            let wasmer = format!("{:?}", wasmer_result);
+            if wasmi != wasmer {
+                divergences.push(ExecutionDivergence::Result { wasmi, wasmer });
+            }
+        }
+    }
+
+    divergences
+}
+
+/// Lists the invocations of the execution traces depth-first, in the order they were made.
+fn flatten_execution_traces(traces: &[ExecutionTrace]) -> Vec<&ExecutionTrace> {
+    let mut invocations = Vec::new();
+    let mut stack: Vec<&ExecutionTrace> = traces.iter().rev().collect();
+    while let Some(trace) = stack.pop() {
+        invocations.push(trace);
+        stack.extend(trace.children.iter().rev());
+    }
+    invocations
+}
+
+/// Encodes the trace of an invocation, without the traces of its nested invocations.
+fn encode_invocation_trace(trace: &ExecutionTrace) -> Vec<u8> {
+    scrypto_encode(&(
+        &trace.origin,
+        &trace.kernel_call_depth,
+        &trace.current_frame_actor,
+        &trace.current_frame_depth,
+        &trace.instruction_index,
+        &trace.input,
+        &trace.output,
+    ))
+    .unwrap()
+}
+
+fn format_invocation_trace(trace: &ExecutionTrace) -> String {
+    format!(
+        "{:?}",
+        ExecutionTrace {
+            children: Vec::new(),
+            ..trace.clone()
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radix_engine::errors::RejectionReason;
+    use radix_engine::system::bootstrap::FlashReceipt;
+    use radix_engine::system::system_modules::costing::CostProfile;
+    use radix_engine::system::system_modules::execution_trace::{
+        ApplicationFnIdentifier, ResourceSummary, TraceActor, TraceOrigin,
+    };
+    use radix_engine::transaction::{CommitResult, RejectResult, TransactionExecutionTrace};
+    use radix_engine_interface::blueprints::transaction_processor::InstructionOutput;
+
+    fn receipt(outcome: TransactionOutcome) -> TransactionReceipt {
+        TransactionReceipt::empty_with_commit(CommitResult::empty_with_outcome(outcome))
+    }
+
+    fn receipt_with_invocations(invocations: Vec<ExecutionTrace>) -> TransactionReceipt {
+        let mut receipt = receipt(TransactionOutcome::Success(vec![]));
+        if let TransactionResult::Commit(commit) = &mut receipt.result {
+            commit.execution_trace = Some(TransactionExecutionTrace {
+                execution_traces: invocations,
+                resource_changes: index_map_new(),
+                fee_locks: Default::default(),
+            });
+        }
+        receipt
+    }
+
+    fn invocation(ident: &str, children: Vec<ExecutionTrace>) -> ExecutionTrace {
+        let no_resources = ResourceSummary {
+            buckets: index_map_new(),
+            proofs: index_map_new(),
+        };
+        ExecutionTrace {
+            origin: TraceOrigin::ScryptoMethod(ApplicationFnIdentifier {
+                blueprint_id: BlueprintId::new(&FAUCET_PACKAGE, FAUCET_BLUEPRINT),
+                ident: ident.to_string(),
+            }),
+            kernel_call_depth: 0,
+            current_frame_actor: TraceActor::NonMethod,
+            current_frame_depth: 0,
+            instruction_index: 0,
+            input: no_resources.clone(),
+            output: no_resources,
+            children,
+        }
+    }
+
+    fn cost_profile_frame(ident: &str, end_cost_units: u32) -> CostProfileFrame {
+        CostProfileFrame {
+            parent: None,
+            blueprint_id: None,
+            ident: ident.to_string(),
+            code: None,
+            start_cost_units: 0,
+            end_cost_units,
+            self_cost_units: index_map_new(),
+        }
+    }
+
+    fn flash_receipt() -> FlashReceipt {
+        FlashReceipt {
+            state_updates: Default::default(),
+            state_update_summary: Default::default(),
+            substate_system_structures: Default::default(),
+        }
+    }
+
+    #[test]
+    fn identical_receipts_do_not_diverge() {
+        let outcome = TransactionOutcome::Success(vec![InstructionOutput::None]);
+        assert_eq!(
+            find_divergences(&receipt(outcome.clone()), &receipt(outcome)),
+            vec![]
+        );
+        assert_eq!(
+            find_ledger_divergences(
+                &LedgerTransactionReceipt::Flash(flash_receipt()),
+                &LedgerTransactionReceipt::Flash(flash_receipt())
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn different_outputs_diverge() {
+        let wasmi = receipt(TransactionOutcome::Success(vec![InstructionOutput::None]));
+        let wasmer = receipt(TransactionOutcome::Success(vec![
+            InstructionOutput::CallReturn(scrypto_encode(&()).unwrap()),
+        ]));
+        assert_eq!(
+            find_divergences(&wasmi, &wasmer),
+            vec![ExecutionDivergence::Outputs]
+        );
+    }
+
+    #[test]
+    fn different_fees_diverge() {
+        let wasmi = receipt(TransactionOutcome::Success(vec![]));
+        let mut wasmer = receipt(TransactionOutcome::Success(vec![]));
+        wasmer.fee_summary.total_execution_cost_units_consumed += 1;
+        let divergences = find_divergences(&wasmi, &wasmer);
+        assert_eq!(divergences.len(), 1);
+        assert!(matches!(divergences[0], ExecutionDivergence::Fees { .. }));
+    }
+
+    #[test]
+    fn different_invocation_traces_diverge_at_the_first_different_invocation() {
+        let wasmi = receipt_with_invocations(vec![invocation(
+            "free",
+            vec![invocation("lock_fee", vec![]), invocation("take", vec![])],
+        )]);
+        let wasmer = receipt_with_invocations(vec![invocation(
+            "free",
+            vec![invocation("lock_fee", vec![]), invocation("put", vec![])],
+        )]);
+        let divergences = find_divergences(&wasmi, &wasmer);
+        assert_eq!(divergences.len(), 1);
+        assert!(matches!(
+            divergences[0],
+            ExecutionDivergence::InvocationTrace {
+                index: 2,
+                wasmi: Some(_),
+                wasmer: Some(_),
+            }
+        ));
+
+        let wasmer = receipt_with_invocations(vec![invocation(
+            "free",
+            vec![invocation("lock_fee", vec![])],
+        )]);
+        let divergences = find_divergences(&wasmi, &wasmer);
+        assert_eq!(divergences.len(), 1);
+        assert!(matches!(
+            divergences[0],
+            ExecutionDivergence::InvocationTrace {
+                index: 2,
+                wasmi: Some(_),
+                wasmer: None,
+            }
+        ));
+    }
+
+    #[test]
+    fn different_invocation_costs_diverge_at_the_first_different_invocation() {
+        let mut wasmi = receipt(TransactionOutcome::Success(vec![]));
+        wasmi.cost_profile = Some(CostProfile {
+            frames: vec![
+                cost_profile_frame("root", 30),
+                cost_profile_frame("free", 10),
+                cost_profile_frame("take", 10),
+            ],
+        });
+        let mut wasmer = receipt(TransactionOutcome::Success(vec![]));
+        wasmer.cost_profile = Some(CostProfile {
+            frames: vec![
+                cost_profile_frame("root", 30),
+                cost_profile_frame("free", 11),
+                cost_profile_frame("take", 9),
+            ],
+        });
+        assert_eq!(
+            find_divergences(&wasmi, &wasmer),
+            vec![ExecutionDivergence::InvocationCosts {
+                index: 1,
+                wasmi: Some(cost_profile_frame("free", 10)),
+                wasmer: Some(cost_profile_frame("free", 11)),
+            }]
+        );
+    }
+
+    #[test]
+    fn different_results_diverge() {
+        let wasmi = receipt(TransactionOutcome::Success(vec![]));
+        let mut wasmer = receipt(TransactionOutcome::Success(vec![]));
+        wasmer.result = TransactionResult::Reject(RejectResult {
+            reason: RejectionReason::SuccessButFeeLoanNotRepaid,
+        });
+        let divergences = find_divergences(&wasmi, &wasmer);
+        assert_eq!(divergences.len(), 1);
+        assert!(matches!(divergences[0], ExecutionDivergence::Result { .. }));
+    }
+
+    #[test]
+    fn different_events_diverge() {
+        let wasmi = receipt(TransactionOutcome::Success(vec![]));
+        let mut wasmer = receipt(TransactionOutcome::Success(vec![]));
+        if let TransactionResult::Commit(commit) = &mut wasmer.result {
+            commit.application_events.push((
+                EventTypeIdentifier(
+                    Emitter::Method(FAUCET.into_node_id(), ModuleId::Main),
+                    "Event".to_string(),
+                ),
+                vec![],
+            ));
+        }
+        assert_eq!(
+            find_divergences(&wasmi, &wasmer),
+            vec![ExecutionDivergence::Events]
+        );
+    }
+
+    #[test]
+    fn flash_and_standard_receipts_diverge() {
+        assert_eq!(
+            find_ledger_divergences(
+                &LedgerTransactionReceipt::Flash(flash_receipt()),
+                &LedgerTransactionReceipt::Standard(receipt(TransactionOutcome::Success(vec![])))
+            ),
+            vec![ExecutionDivergence::ReceiptType {
+                wasmi: "Flash",
+                wasmer: "Standard",
+            }]
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use super::Error;
+use clap::Parser;
+use radix_engine_stores::rocks_db::{Options, RocksdbLayout, RocksdbSubstateStore};
+use radix_engine_stores::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
+use std::path::PathBuf;
+
+/// Migrate a `RocksdbSubstateStore` database (e.g. a resim ledger), or a
+/// `RocksDBWithMerkleTreeSubstateStore` database (e.g. a replay database), to the standard column
+/// family layout, with dedicated column families for the most frequently read partitions
+#[derive(Parser, Debug)]
+pub struct DatabaseMigrateLayout {
+    /// Path to the database folder
+    pub database_dir: PathBuf,
+
+    /// Whether the database is a `RocksDBWithMerkleTreeSubstateStore` database
+    #[clap(long)]
+    pub merkle_tree: bool,
+}
+
+impl DatabaseMigrateLayout {
+    pub fn run(&self) -> Result<(), Error> {
+        let start = std::time::Instant::now();
+        let layout = if self.merkle_tree {
+            RocksDBWithMerkleTreeSubstateStore::migrate_layout(
+                &Options::default(),
+                self.database_dir.clone(),
+                true,
+                RocksdbLayout::standard(),
+            )
+            .layout()
+            .clone()
+        } else {
+            RocksdbSubstateStore::migrate_layout(
+                &Options::default(),
+                self.database_dir.clone(),
+                RocksdbLayout::standard(),
+            )
+            .layout()
+            .clone()
+        };
+        println!("Time elapsed: {:?}", start.elapsed());
+        println!("Layout: {:?}", layout);
+        Ok(())
+    }
+}
@@ -8,4 +8,5 @@ pub enum Error {
     InvalidTransactionArchive,
     InvalidTransactionSource,
     InvalidBreakpoints(String),
+    DivergentTransactions(usize),
 }
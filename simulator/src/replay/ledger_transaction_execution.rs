@@ -59,12 +59,30 @@ pub fn prepare_ledger_transaction(tx_payload: &[u8]) -> PreparedLedgerTransactio
     prepared
 }
 
-pub fn execute_prepared_ledger_transaction<S: SubstateDatabase>(
+pub fn execute_prepared_ledger_transaction<S: SubstateDatabase, W: WasmEngine>(
     database: &S,
-    scrypto_vm: &ScryptoVm<DefaultWasmEngine>,
+    scrypto_vm: &ScryptoVm<W>,
     network: &NetworkDefinition,
     prepared: &PreparedLedgerTransaction,
     trace: bool,
+) -> LedgerTransactionReceipt {
+    execute_prepared_ledger_transaction_with_config(
+        database,
+        scrypto_vm,
+        network,
+        prepared,
+        |config| config.with_kernel_trace(trace).with_cost_breakdown(trace),
+    )
+}
+
+/// Executes a ledger transaction with the default execution config of its type, adjusted by
+/// the given function.
+pub fn execute_prepared_ledger_transaction_with_config<S: SubstateDatabase, W: WasmEngine>(
+    database: &S,
+    scrypto_vm: &ScryptoVm<W>,
+    network: &NetworkDefinition,
+    prepared: &PreparedLedgerTransaction,
+    configure: impl Fn(ExecutionConfig) -> ExecutionConfig,
 ) -> LedgerTransactionReceipt {
     match &prepared.inner {
         PreparedLedgerTransactionInner::Genesis(prepared_genesis_tx) => {
@@ -81,9 +99,7 @@ pub fn execute_prepared_ledger_transaction<S: SubstateDatabase>(
                             native_vm: DefaultNativeVm::new(),
                         },
                         &CostingParameters::default(),
-                        &ExecutionConfig::for_genesis_transaction(network.clone())
-                            .with_kernel_trace(trace)
-                            .with_cost_breakdown(trace),
+                        &configure(ExecutionConfig::for_genesis_transaction(network.clone())),
                         &tx.get_executable(btreeset!(AuthAddresses::system_role())),
                     );
                     LedgerTransactionReceipt::Standard(receipt)
@@ -98,9 +114,7 @@ pub fn execute_prepared_ledger_transaction<S: SubstateDatabase>(
                     native_vm: DefaultNativeVm::new(),
                 },
                 &CostingParameters::default(),
-                &ExecutionConfig::for_notarized_transaction(network.clone())
-                    .with_kernel_trace(trace)
-                    .with_cost_breakdown(trace),
+                &configure(ExecutionConfig::for_notarized_transaction(network.clone())),
                 &NotarizedTransactionValidator::new(ValidationConfig::default(network.id))
                     .validate(tx.as_ref().clone())
                     .expect("Transaction validation failure")
@@ -116,9 +130,7 @@ pub fn execute_prepared_ledger_transaction<S: SubstateDatabase>(
                     native_vm: DefaultNativeVm::new(),
                 },
                 &CostingParameters::default(),
-                &ExecutionConfig::for_system_transaction(network.clone())
-                    .with_kernel_trace(trace)
-                    .with_cost_breakdown(trace),
+                &configure(ExecutionConfig::for_system_transaction(network.clone())),
                 &tx.get_executable(),
             );
             LedgerTransactionReceipt::Standard(receipt)
@@ -4,16 +4,22 @@ pub mod txn_reader;
 
 mod cmd_alloc_dump;
 mod cmd_execute;
+#[cfg(feature = "wasmer")]
+mod cmd_execute_differential;
 mod cmd_execute_in_memory;
 mod cmd_measure;
+mod cmd_migrate_layout;
 mod cmd_prepare;
 mod cmd_sync;
 mod error;
 
 pub use cmd_alloc_dump::*;
 pub use cmd_execute::*;
+#[cfg(feature = "wasmer")]
+pub use cmd_execute_differential::*;
 pub use cmd_execute_in_memory::*;
 pub use cmd_measure::*;
+pub use cmd_migrate_layout::*;
 pub use cmd_prepare::*;
 pub use cmd_sync::*;
 pub use error::*;
@@ -33,9 +39,12 @@ pub enum Command {
     Prepare(TxnPrepare),
     Execute(TxnExecute),
     ExecuteInMemory(TxnExecuteInMemory),
+    #[cfg(feature = "wasmer")]
+    ExecuteDifferential(TxnExecuteDifferential),
     Sync(TxnSync),
     Measure(TxnMeasure),
     AllocDump(TxnAllocDump),
+    MigrateLayout(DatabaseMigrateLayout),
 }
 
 pub fn run() -> Result<(), Error> {
@@ -45,8 +54,11 @@ pub fn run() -> Result<(), Error> {
         Command::Prepare(cmd) => cmd.run(),
         Command::Execute(cmd) => cmd.run(),
         Command::ExecuteInMemory(cmd) => cmd.run(),
+        #[cfg(feature = "wasmer")]
+        Command::ExecuteDifferential(cmd) => cmd.run(),
         Command::Sync(cmd) => cmd.sync(),
         Command::Measure(cmd) => cmd.run(),
         Command::AllocDump(cmd) => cmd.run(),
+        Command::MigrateLayout(cmd) => cmd.run(),
     }
 }
@@ -39,6 +39,10 @@ pub struct CallFunction {
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
+
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
 }
 
 impl CallFunction {
@@ -76,8 +80,9 @@ impl CallFunction {
             &self.signing_keys,
             &self.network,
             &self.manifest,
-            self.trace,
+            &self.trace.then(|| self.trace_format.clone()),
             true,
+            &None,
             out,
         )
         .map(|_| ())
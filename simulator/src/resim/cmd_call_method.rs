@@ -37,6 +37,10 @@ pub struct CallMethod {
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
+
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
 }
 
 impl CallMethod {
@@ -73,8 +77,9 @@ impl CallMethod {
             &self.signing_keys,
             &self.network,
             &self.manifest,
-            self.trace,
+            &self.trace.then(|| self.trace_format.clone()),
             true,
+            &None,
             out,
         )
         .map(|_| ())
@@ -32,6 +32,10 @@ pub struct Mint {
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
+
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
 }
 
 impl Mint {
@@ -60,8 +64,9 @@ impl Mint {
             &self.signing_keys,
             &self.network,
             &self.manifest,
-            self.trace,
+            &self.trace.then(|| self.trace_format.clone()),
             true,
+            &None,
             out,
         )
         .map(|_| ())
@@ -25,6 +25,10 @@ pub struct NewAccount {
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
+
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
 }
 
 #[derive(ScryptoSbor, ManifestSbor)]
@@ -47,8 +51,9 @@ impl NewAccount {
             &Some("".to_string()), // explicit empty signer public keys
             &self.network,
             &self.manifest,
-            self.trace,
+            &self.trace.then(|| self.trace_format.clone()),
             false,
+            &None,
             out,
         )?;
 
@@ -85,8 +90,9 @@ impl NewAccount {
                 &Some("".to_string()), // explicit empty signer public keys
                 &self.network,
                 &None,
-                self.trace,
+                &self.trace.then(|| self.trace_format.clone()),
                 false,
+                &None,
                 out,
             )?
             .unwrap();
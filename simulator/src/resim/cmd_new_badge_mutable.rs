@@ -46,6 +46,10 @@ pub struct NewBadgeMutable {
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
+
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
 }
 
 impl NewBadgeMutable {
@@ -90,8 +94,9 @@ impl NewBadgeMutable {
             &self.signing_keys,
             &self.network,
             &self.manifest,
-            self.trace,
+            &self.trace.then(|| self.trace_format.clone()),
             true,
+            &None,
             out,
         )
         .map(|_| ())
@@ -49,6 +49,10 @@ pub struct NewSimpleBadge {
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
+
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
 }
 
 impl NewSimpleBadge {
@@ -97,8 +101,9 @@ impl NewSimpleBadge {
             &self.signing_keys,
             &self.network,
             &self.manifest,
-            self.trace,
+            &self.trace.then(|| self.trace_format.clone()),
             false,
+            &None,
             out,
         )
         .unwrap();
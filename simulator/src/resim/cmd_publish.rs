@@ -46,6 +46,10 @@ pub struct Publish {
     #[clap(short, long)]
     pub trace: bool,
 
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
+
     /// When passed, this argument disables wasm-opt from running on the built wasm.
     #[clap(long)]
     disable_wasm_opt: bool,
@@ -328,8 +332,9 @@ impl Publish {
                 &None,
                 &self.network,
                 &self.manifest,
-                self.trace,
+                &self.trace.then(|| self.trace_format.clone()),
                 false,
+                &None,
                 out,
             )?;
             if let Some(receipt) = receipt {
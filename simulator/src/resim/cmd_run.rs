@@ -1,9 +1,14 @@
 use clap::Parser;
+use radix_engine::system::system_modules::costing::CostProfile;
+use radix_engine::transaction::{lock_estimated_fee, FeeEstimationParams};
 use radix_engine::utils::validate_call_arguments_to_native_components;
-use regex::{Captures, Regex};
 use std::env;
 use std::path::PathBuf;
-use transaction::manifest::BlobProvider;
+use std::str::FromStr;
+use transaction::manifest::{
+    compile_with_macros, render_compile_error, BlobProvider, FileSystemSourceProvider,
+    IsParamProvider,
+};
 
 use crate::resim::*;
 
@@ -28,20 +33,89 @@ pub struct Run {
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
+
+    /// The output of the trace, [print | jsonl=<path>]
+    #[clap(long, default_value = "print")]
+    pub trace_format: TraceFormat,
+
+    /// The path to write a cost profile of the transaction to
+    #[clap(long)]
+    pub profile: Option<PathBuf>,
+
+    /// The format of the cost profile, [folded | speedscope | chrome-trace]
+    #[clap(long, default_value = "folded")]
+    pub profile_format: CostProfileFormat,
+
+    /// Estimate the fee by previewing the manifest, and lock it on the default account (the
+    /// manifest must not lock a fee itself)
+    #[clap(long)]
+    pub auto_fee: bool,
+
+    /// The safety margin to lock on top of the estimated fee when using `--auto-fee`, e.g. 0.1
+    /// for 10%
+    #[clap(long, default_value = "0.2")]
+    pub fee_margin: Decimal,
 }
 
-impl Run {
-    pub fn pre_process_manifest(manifest: &str) -> String {
-        let re = Regex::new(r"\$\{(.+?)\}").unwrap();
-        re.replace_all(manifest, |caps: &Captures| {
-            env::var(&caps[1].trim()).unwrap_or_default()
-        })
-        .into()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostProfileFormat {
+    /// The folded stacks of `flamegraph.pl` and `inferno`
+    Folded,
+    Speedscope,
+    ChromeTrace,
+}
+
+impl FromStr for CostProfileFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "folded" => Ok(Self::Folded),
+            "speedscope" => Ok(Self::Speedscope),
+            "chrome-trace" => Ok(Self::ChromeTrace),
+            _ => Err(format!("Unknown cost profile format: {}", s)),
+        }
     }
+}
 
+/// The file to write the cost profile of a transaction to.
+#[derive(Debug, Clone)]
+pub struct CostProfileOutput {
+    pub path: PathBuf,
+    pub format: CostProfileFormat,
+}
+
+impl CostProfileOutput {
+    pub fn write(
+        &self,
+        cost_profile: &CostProfile,
+        address_encoder: &AddressBech32Encoder,
+    ) -> Result<(), Error> {
+        let address_encoder = Some(address_encoder);
+        let contents = match self.format {
+            CostProfileFormat::Folded => cost_profile.to_folded_stacks(address_encoder),
+            CostProfileFormat::Speedscope => cost_profile.to_speedscope_json(address_encoder),
+            CostProfileFormat::ChromeTrace => cost_profile.to_chrome_trace_json(address_encoder),
+        };
+        std::fs::write(&self.path, contents)
+            .map_err(|err| Error::IOErrorAtPath(err, self.path.clone()))
+    }
+}
+
+/// Provides the `${name}` placeholders of a manifest from the environment, with unset variables
+/// expanded to empty strings.
+#[derive(Default, Debug, Clone)]
+pub struct EnvironmentParamProvider;
+
+impl IsParamProvider for EnvironmentParamProvider {
+    fn get_param(&self, name: &str) -> Option<String> {
+        Some(env::var(name).unwrap_or_default())
+    }
+}
+
+impl Run {
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
         let manifest = std::fs::read_to_string(&self.path).map_err(Error::IOError)?;
-        let pre_processed_manifest = Self::pre_process_manifest(&manifest);
         let network = match &self.network {
             Some(n) => NetworkDefinition::from_str(&n).map_err(Error::ParseNetworkError)?,
             None => NetworkDefinition::simulator(),
@@ -52,25 +126,67 @@ impl Run {
                 blobs.push(std::fs::read(path).map_err(Error::IOError)?);
             }
         }
-        let compiled_manifest = transaction::manifest::compile(
-            &pre_processed_manifest,
+        let source_name = self.path.display().to_string();
+        let compiled_manifest = compile_with_macros(
+            &manifest,
+            &source_name,
             &network,
             BlobProvider::new_with_blobs(blobs),
+            &FileSystemSourceProvider::new(),
+            &EnvironmentParamProvider,
         )
-        .map_err(Error::CompileError)?;
+        .map_err(|err| {
+            let diagnostic = render_compile_error(&source_name, &manifest, &err);
+            Error::CompileError(err, diagnostic)
+        })?;
 
         validate_call_arguments_to_native_components(&compiled_manifest.instructions)
             .map_err(Error::InstructionSchemaValidationError)?;
 
+        let compiled_manifest = if self.auto_fee {
+            self.lock_estimated_fee(compiled_manifest, &network)?
+        } else {
+            compiled_manifest
+        };
+
         handle_manifest(
             compiled_manifest,
             &self.signing_keys,
             &self.network,
             &None,
-            self.trace,
+            &self.trace.then(|| self.trace_format.clone()),
             true,
+            &self.profile.clone().map(|path| CostProfileOutput {
+                path,
+                format: self.profile_format,
+            }),
             out,
         )
         .map(|_| ())
     }
+
+    fn lock_estimated_fee(
+        &self,
+        manifest: TransactionManifestV1,
+        network: &NetworkDefinition,
+    ) -> Result<TransactionManifestV1, Error> {
+        let SimulatorEnvironment {
+            db,
+            scrypto_vm,
+            native_vm,
+        } = SimulatorEnvironment::new()?;
+        let vm = Vm::new(&scrypto_vm, native_vm);
+
+        let signer_public_keys = get_signing_keys(&self.signing_keys)?
+            .into_iter()
+            .map(|private_key| private_key.public_key().into())
+            .collect();
+        let params = FeeEstimationParams::new(get_default_account()?)
+            .with_signer_public_keys(signer_public_keys)
+            .with_safety_margin(self.fee_margin);
+
+        let (manifest, _) = lock_estimated_fee(&db, vm, network, manifest, &params)
+            .map_err(Error::FeeEstimationError)?;
+        Ok(manifest)
+    }
 }
@@ -114,7 +114,7 @@ impl ShowLedger {
         let blobs = vec![];
         let initial_proofs = btreeset![];
         let receipt =
-            handle_system_transaction(instructions, blobs, initial_proofs, false, false, out)?;
+            handle_system_transaction(instructions, blobs, initial_proofs, &None, false, out)?;
         Ok(receipt.expect_commit(true).output(0))
     }
 
@@ -132,7 +132,7 @@ impl ShowLedger {
         let blobs = vec![];
         let initial_proofs = btreeset![];
         let receipt =
-            handle_system_transaction(instructions, blobs, initial_proofs, false, false, out)?;
+            handle_system_transaction(instructions, blobs, initial_proofs, &None, false, out)?;
         Ok(receipt.expect_commit(true).output(0))
     }
 }
@@ -70,6 +70,26 @@ impl SimulatorEnvironment {
             let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
             self.db.commit(&db_updates);
         }
+        {
+            let state_updates = generate_validator_jailing_state_updates(&self.db, None);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            self.db.commit(&db_updates);
+        }
+        {
+            let state_updates = generate_subintents_state_updates(&self.db);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            self.db.commit(&db_updates);
+        }
+        {
+            let state_updates = generate_vm_boot_scrypto_collections_state_updates();
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            self.db.commit(&db_updates);
+        }
+        {
+            let state_updates = generate_access_rule_extensions_state_updates(&self.db);
+            let db_updates = state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
+            self.db.commit(&db_updates);
+        }
     }
 }
 
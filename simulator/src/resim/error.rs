@@ -2,7 +2,7 @@ use std::io;
 use std::path::PathBuf;
 
 use radix_engine::errors::{RejectionReason, RuntimeError};
-use radix_engine::transaction::AbortReason;
+use radix_engine::transaction::{AbortReason, FeeEstimationError};
 use radix_engine::types::{ComponentAddress, NodeId, PackageAddress};
 use radix_engine::utils::ExtractSchemaError;
 use radix_engine::vm::wasm::PrepareError;
@@ -57,9 +57,12 @@ pub enum Error {
 
     TransactionAborted(AbortReason),
 
+    FeeEstimationError(FeeEstimationError),
+
     LedgerDumpError(EntityDumpError),
 
-    CompileError(transaction::manifest::CompileError),
+    /// A manifest compile error, with its diagnostic rendered against the manifest source.
+    CompileError(transaction::manifest::CompileError, String),
 
     DecompileError(transaction::manifest::DecompileError),
 
@@ -62,6 +62,9 @@ use radix_engine::system::bootstrap::Bootstrapper;
 use radix_engine::system::system_db_reader::{
     ObjectCollectionKey, SystemDatabaseReader, SystemDatabaseWriter,
 };
+use radix_engine::system::system_modules::kernel_trace::{
+    KernelTraceRecord, KernelTraceSink, KernelTraceWriter,
+};
 use radix_engine::transaction::ExecutionConfig;
 use radix_engine::transaction::TransactionOutcome;
 use radix_engine::transaction::TransactionReceipt;
@@ -83,7 +86,9 @@ use radix_engine_store_interface::interface::SubstateDatabase;
 use radix_engine_stores::rocks_db::RocksdbSubstateStore;
 use std::env;
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use transaction::manifest::decompile;
 use transaction::model::TestTransaction;
 use transaction::model::{BlobV1, BlobsV1, InstructionV1, InstructionsV1};
@@ -160,11 +165,99 @@ pub fn run() -> Result<(), Error> {
     }
 }
 
+/// The output of the kernel trace of a transaction, [print | jsonl=<path>]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Prints the trace, coloured and indented by call depth
+    Print,
+    /// Writes the trace to the given file, as JSON lines
+    JsonLines(PathBuf),
+}
+
+impl FromStr for TraceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            None if s == "print" => Ok(Self::Print),
+            Some(("jsonl", path)) if !path.is_empty() => Ok(Self::JsonLines(PathBuf::from(path))),
+            _ => Err(format!("Unknown trace format: {}", s)),
+        }
+    }
+}
+
+/// Writes the kernel trace of a transaction to a file, as JSON lines.
+#[derive(Debug)]
+struct JsonLinesTraceWriter {
+    path: PathBuf,
+    file: std::io::BufWriter<fs::File>,
+    error: Option<std::io::Error>,
+}
+
+impl JsonLinesTraceWriter {
+    fn create(path: &PathBuf) -> Result<Self, Error> {
+        let file = fs::File::create(path).map_err(|err| Error::IOErrorAtPath(err, path.clone()))?;
+        Ok(Self {
+            path: path.clone(),
+            file: std::io::BufWriter::new(file),
+            error: None,
+        })
+    }
+
+    /// Flushes the trace, returning the first error encountered while writing it.
+    fn finish(&mut self) -> Result<(), Error> {
+        let result = match self.error.take() {
+            Some(err) => Err(err),
+            None => self.file.flush(),
+        };
+        result.map_err(|err| Error::IOErrorAtPath(err, self.path.clone()))
+    }
+}
+
+impl KernelTraceWriter for JsonLinesTraceWriter {
+    fn write(&mut self, record: &KernelTraceRecord) {
+        if self.error.is_none() {
+            if let Err(err) = writeln!(self.file, "{}", record.to_json()) {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+/// Enables the kernel trace in the given format, if any. Returns the writer of the trace file,
+/// to be finished once the transaction has been executed.
+fn with_trace(
+    execution_config: ExecutionConfig,
+    trace: &Option<TraceFormat>,
+) -> Result<(ExecutionConfig, Option<Arc<Mutex<JsonLinesTraceWriter>>>), Error> {
+    match trace {
+        None => Ok((execution_config, None)),
+        Some(TraceFormat::Print) => Ok((execution_config.with_kernel_trace(true), None)),
+        Some(TraceFormat::JsonLines(path)) => {
+            let writer = Arc::new(Mutex::new(JsonLinesTraceWriter::create(path)?));
+            let execution_config =
+                execution_config.with_kernel_trace_sink(KernelTraceSink::Writer(writer.clone()));
+            Ok((execution_config, Some(writer)))
+        }
+    }
+}
+
+/// Finishes the trace file of a transaction, if written.
+fn finish_trace(trace_writer: Option<Arc<Mutex<JsonLinesTraceWriter>>>) -> Result<(), Error> {
+    match trace_writer {
+        Some(writer) => writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .finish(),
+        None => Ok(()),
+    }
+}
+
 pub fn handle_system_transaction<O: std::io::Write>(
     instructions: Vec<InstructionV1>,
     blobs: Vec<Vec<u8>>,
     initial_proofs: BTreeSet<NonFungibleGlobalId>,
-    trace: bool,
+    trace: &Option<TraceFormat>,
     print_receipt: bool,
     out: &mut O,
 ) -> Result<TransactionReceipt, Error> {
@@ -185,17 +278,21 @@ pub fn handle_system_transaction<O: std::io::Write>(
         pre_allocated_addresses: vec![],
     };
 
+    let (execution_config, trace_writer) = with_trace(
+        ExecutionConfig::for_system_transaction(NetworkDefinition::simulator()),
+        trace,
+    )?;
     let receipt = execute_and_commit_transaction(
         &mut db,
         vm,
         &CostingParameters::default(),
-        &ExecutionConfig::for_system_transaction(NetworkDefinition::simulator())
-            .with_kernel_trace(trace),
+        &execution_config,
         &transaction
             .prepare()
             .map_err(Error::TransactionPrepareError)?
             .get_executable(initial_proofs),
     );
+    finish_trace(trace_writer)?;
 
     if print_receipt {
         let encoder = AddressBech32Encoder::for_simulator();
@@ -217,8 +314,9 @@ pub fn handle_manifest<O: std::io::Write>(
     signing_keys: &Option<String>,
     network: &Option<String>,
     write_manifest: &Option<PathBuf>,
-    trace: bool,
+    trace: &Option<TraceFormat>,
     print_receipt: bool,
+    cost_profile_output: &Option<CostProfileOutput>,
     out: &mut O,
 ) -> Result<Option<TransactionReceipt>, Error> {
     let network = match network {
@@ -259,16 +357,25 @@ pub fn handle_manifest<O: std::io::Write>(
             let nonce = get_nonce()?;
             let transaction = TestTransaction::new_from_nonce(manifest, nonce);
 
+            let (execution_config, trace_writer) =
+                with_trace(ExecutionConfig::for_test_transaction(), trace)?;
             let receipt = execute_and_commit_transaction(
                 &mut db,
                 vm,
                 &CostingParameters::default(),
-                &ExecutionConfig::for_test_transaction().with_kernel_trace(trace),
+                &execution_config.with_cost_profile(cost_profile_output.is_some()),
                 &transaction
                     .prepare()
                     .map_err(Error::TransactionPrepareError)?
                     .get_executable(initial_proofs),
             );
+            finish_trace(trace_writer)?;
+
+            if let (Some(cost_profile_output), Some(cost_profile)) =
+                (cost_profile_output, &receipt.cost_profile)
+            {
+                cost_profile_output.write(cost_profile, &AddressBech32Encoder::new(&network))?;
+            }
 
             if print_receipt {
                 let encoder = AddressBech32Encoder::for_simulator();
@@ -526,13 +633,18 @@ mod tests {
             network: None,
             manifest: None,
             trace: false,
+            trace_format: TraceFormat::Print,
         };
         assert!(new_account.run(&mut out).is_ok());
         let cmd = Show { address: None };
         assert!(cmd.run(&mut out).is_ok());
     }
 
-    fn test_pre_process_manifest() {
+    fn test_environment_params() {
+        use transaction::manifest::lexer::tokenize;
+        use transaction::manifest::preprocessor::preprocess;
+        use transaction::manifest::SourceProvider;
+
         temp_env::with_vars(
             vec![
                 (
@@ -543,11 +655,27 @@ mod tests {
                     "xrd",
                     Some("resource_sim1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqzqu57yag"),
                 ),
+                ("amount", Some("100")),
             ],
             || {
-                let manifest = r#"CALL_METHOD ComponentAddress("${  faucet  }") "free";\nTAKE_ALL_FROM_WORKTOP ResourceAddress("${xrd}") Bucket("bucket1");\n"#;
-                let after = r#"CALL_METHOD ComponentAddress("system_sim1qsqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpql4sktx") "free";\nTAKE_ALL_FROM_WORKTOP ResourceAddress("resource_sim1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqzqu57yag") Bucket("bucket1");\n"#;
-                assert_eq!(Run::pre_process_manifest(manifest), after);
+                let manifest = "DEFINE amount = \"10\";\nCALL_METHOD Address(\"${  faucet  }\") \"free\";\nTAKE_ALL_FROM_WORKTOP Address(\"${xrd}\") Bucket(\"${unset}${amount}\");\n";
+                let expected = "CALL_METHOD Address(\"system_sim1qsqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpql4sktx\") \"free\";\nTAKE_ALL_FROM_WORKTOP Address(\"resource_sim1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqzqu57yag\") Bucket(\"10\");\n";
+                let expand = |manifest: &str| {
+                    preprocess(
+                        tokenize(manifest).unwrap(),
+                        "manifest.rtm",
+                        &SourceProvider::new(),
+                        &EnvironmentParamProvider,
+                    )
+                    .map(|tokens| {
+                        tokens
+                            .into_iter()
+                            .map(|token| token.kind)
+                            .collect::<Vec<_>>()
+                    })
+                };
+                // The `amount` constant takes precedence over the environment variable
+                assert_eq!(expand(manifest), expand(expected));
             },
         );
     }
@@ -581,7 +709,19 @@ mod tests {
     #[test]
     fn serial_resim_command_tests() {
         test_no_value();
-        test_pre_process_manifest();
+        test_environment_params();
         test_set_default_account_validation();
     }
+
+    #[test]
+    fn test_parse_trace_format() {
+        assert_eq!(TraceFormat::from_str("print"), Ok(TraceFormat::Print));
+        assert_eq!(
+            TraceFormat::from_str("jsonl=trace.jsonl"),
+            Ok(TraceFormat::JsonLines(PathBuf::from("trace.jsonl")))
+        );
+        assert!(TraceFormat::from_str("jsonl").is_err());
+        assert!(TraceFormat::from_str("jsonl=").is_err());
+        assert!(TraceFormat::from_str("json").is_err());
+    }
 }
@@ -2,7 +2,9 @@ use clap::Parser;
 use radix_engine::{types::*, utils::*};
 use std::path::PathBuf;
 use std::str::FromStr;
-use transaction::manifest::{compile, BlobProvider};
+use transaction::manifest::{
+    compile_with_macros, render_compile_error, BlobProvider, FileSystemSourceProvider,
+};
 
 /// Radix transaction manifest compiler
 #[derive(Parser, Debug)]
@@ -20,6 +22,10 @@ pub struct Args {
     #[clap(short, long, multiple = true)]
     blobs: Option<Vec<String>>,
 
+    /// The values of the `${name}` placeholders in the manifest, as `name=value`
+    #[clap(long = "param", multiple_occurrences = true)]
+    params: Vec<String>,
+
     /// Input file
     #[clap(required = true)]
     input: PathBuf,
@@ -29,7 +35,9 @@ pub struct Args {
 pub enum Error {
     IoError(std::io::Error),
     EncodeError(sbor::EncodeError),
-    CompileError(transaction::manifest::CompileError),
+    /// A manifest compile error, with its diagnostic rendered against the manifest source.
+    CompileError(transaction::manifest::CompileError, String),
+    InvalidParam(String),
     ParseNetworkError(ParseNetworkError),
     InstructionSchemaValidationError(radix_engine::utils::LocatedInstructionSchemaValidationError),
 }
@@ -48,8 +56,26 @@ pub fn run() -> Result<(), Error> {
             blobs.push(std::fs::read(path).map_err(Error::IoError)?);
         }
     }
-    let transaction = compile(&content, &network, BlobProvider::new_with_blobs(blobs))
-        .map_err(Error::CompileError)?;
+    let mut params = index_map_new();
+    for param in args.params {
+        let (name, value) = param
+            .split_once('=')
+            .ok_or_else(|| Error::InvalidParam(param.clone()))?;
+        params.insert(name.to_string(), value.to_string());
+    }
+    let source_name = args.input.display().to_string();
+    let transaction = compile_with_macros(
+        &content,
+        &source_name,
+        &network,
+        BlobProvider::new_with_blobs(blobs),
+        &FileSystemSourceProvider::new(),
+        &params,
+    )
+    .map_err(|err| {
+        let diagnostic = render_compile_error(&source_name, &content, &err);
+        Error::CompileError(err, diagnostic)
+    })?;
     validate_call_arguments_to_native_components(&transaction.instructions)
         .map_err(Error::InstructionSchemaValidationError)?;
     std::fs::write(
@@ -0,0 +1,263 @@
+use crate::resim::{export_package_schema, export_schema, SimulatorPackageAddress};
+use crate::scrypto::*;
+use crate::utils::*;
+use clap::Parser;
+use radix_engine::types::*;
+use radix_engine_interface::blueprints::package::{
+    BlueprintDefinition, BlueprintPayloadDef, BlueprintVersionKey, PackageDefinition,
+};
+use radix_engine_interface::prelude::Level;
+use radix_engine_interface::schema::{BlueprintCollectionSchema, BlueprintSchemaInit, TypeRef};
+use std::env::current_dir;
+use std::fs;
+use std::path::PathBuf;
+
+/// Check that a Scrypto package can replace a package published on the resim ledger, without
+/// breaking the decoding of existing state, events and function calls
+#[derive(Parser, Debug)]
+pub struct CheckCompatibility {
+    /// The address of the published package to compare against
+    package_address: SimulatorPackageAddress,
+
+    /// The package directory
+    #[clap(long)]
+    path: Option<PathBuf>,
+
+    /// When passed, this argument disables wasm-opt from running on the built wasm.
+    #[clap(long)]
+    disable_wasm_opt: bool,
+
+    /// The max log level, such as ERROR, WARN, INFO, DEBUG and TRACE.
+    /// The default is INFO.
+    #[clap(long)]
+    log_level: Option<Level>,
+}
+
+impl CheckCompatibility {
+    pub fn run(&self) -> Result<(), Error> {
+        let (_, definition_path) = build_package(
+            self.path.clone().unwrap_or(current_dir().unwrap()),
+            false,
+            false,
+            self.disable_wasm_opt,
+            self.log_level.unwrap_or(Level::default()),
+            false,
+        )
+        .map_err(Error::BuildError)?;
+        let new_definition: PackageDefinition =
+            manifest_decode(&fs::read(&definition_path).map_err(Error::IOError)?)
+                .map_err(Error::SborDecodeError)?;
+
+        let package_address = self.package_address.0;
+        let old_definitions = export_package_schema(package_address).map_err(Error::ResimError)?;
+        let mut old_schemas = OldSchemas {
+            package_address,
+            schemas: index_map_new(),
+        };
+
+        let mut incompatibilities = Vec::new();
+        for (key, old_definition) in &old_definitions {
+            let Some(new_definition) = new_definition.blueprints.get(&key.blueprint) else {
+                incompatibilities.push(format!("{}: blueprint was removed", key.blueprint));
+                continue;
+            };
+            check_blueprint(
+                key,
+                old_definition,
+                &new_definition.schema,
+                &mut old_schemas,
+                &mut incompatibilities,
+            )?;
+        }
+
+        if incompatibilities.is_empty() {
+            println!("The package is backwards compatible with the published package.");
+            Ok(())
+        } else {
+            for incompatibility in &incompatibilities {
+                println!("{}", incompatibility);
+            }
+            Err(Error::SchemaIncompatible(incompatibilities.len()))
+        }
+    }
+}
+
+struct OldSchemas {
+    package_address: PackageAddress,
+    schemas: IndexMap<SchemaHash, VersionedScryptoSchema>,
+}
+
+impl OldSchemas {
+    fn get(&mut self, schema_hash: SchemaHash) -> Result<&Schema<ScryptoCustomSchema>, Error> {
+        if !self.schemas.contains_key(&schema_hash) {
+            let schema = export_schema(self.package_address.as_node_id(), schema_hash)
+                .map_err(Error::ResimError)?;
+            self.schemas.insert(schema_hash, schema);
+        }
+        Ok(self.schemas.get(&schema_hash).unwrap().v1())
+    }
+}
+
+/// The direction in which payloads flow between the old and new blueprint versions.
+#[derive(Clone, Copy)]
+enum Direction {
+    /// Payloads created under the old schema (eg persisted state) must decode under the new one.
+    OldToNew,
+    /// Payloads created under the new schema (eg function outputs) must decode under the old one.
+    NewToOld,
+}
+
+struct BlueprintChecker<'a> {
+    blueprint_name: &'a str,
+    new_schema: &'a Schema<ScryptoCustomSchema>,
+    old_schemas: &'a mut OldSchemas,
+    incompatibilities: &'a mut Vec<String>,
+}
+
+impl<'a> BlueprintChecker<'a> {
+    fn report(&mut self, location: String, message: &str) {
+        self.incompatibilities
+            .push(format!("{} {}: {}", self.blueprint_name, location, message));
+    }
+
+    fn check(
+        &mut self,
+        location: String,
+        old: &BlueprintPayloadDef,
+        new: &TypeRef<LocalTypeId>,
+        direction: Direction,
+    ) -> Result<(), Error> {
+        let (schema_hash, old_type_id, new_type_id) = match (old, new) {
+            (BlueprintPayloadDef::Static(ScopedTypeId(schema_hash, old)), TypeRef::Static(new)) => {
+                (*schema_hash, *old, *new)
+            }
+            (BlueprintPayloadDef::Generic(old), TypeRef::Generic(new)) if old == new => {
+                return Ok(());
+            }
+            _ => {
+                self.report(location, "generic type changed");
+                return Ok(());
+            }
+        };
+        let new_schema = self.new_schema;
+        let old_schema = self.old_schemas.get(schema_hash)?;
+        let result = match direction {
+            Direction::OldToNew => {
+                check_backwards_compatibility(old_schema, old_type_id, new_schema, new_type_id)
+            }
+            Direction::NewToOld => {
+                check_backwards_compatibility(new_schema, new_type_id, old_schema, old_type_id)
+            }
+        };
+        if let Err(errors) = result {
+            for error in errors {
+                self.report(location.clone(), &error.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_blueprint(
+    key: &BlueprintVersionKey,
+    old_definition: &BlueprintDefinition,
+    new_schema_init: &BlueprintSchemaInit,
+    old_schemas: &mut OldSchemas,
+    incompatibilities: &mut Vec<String>,
+) -> Result<(), Error> {
+    let old_interface = &old_definition.interface;
+    let mut checker = BlueprintChecker {
+        blueprint_name: &key.blueprint,
+        new_schema: new_schema_init.schema.v1(),
+        old_schemas,
+        incompatibilities,
+    };
+
+    let old_fields = old_interface
+        .state
+        .fields
+        .as_ref()
+        .map(|(_, fields)| fields.as_slice())
+        .unwrap_or_default();
+    for (index, old_field) in old_fields.iter().enumerate() {
+        match new_schema_init.state.fields.get(index) {
+            Some(new_field) => checker.check(
+                format!("field {}", index),
+                &old_field.field,
+                &new_field.field,
+                Direction::OldToNew,
+            )?,
+            None => checker.report(format!("field {}", index), "removed"),
+        }
+    }
+
+    for (index, (_, old_collection)) in old_interface.state.collections.iter().enumerate() {
+        let (old_kv, new_kv) = match (old_collection, new_schema_init.state.collections.get(index))
+        {
+            (
+                BlueprintCollectionSchema::KeyValueStore(old),
+                Some(BlueprintCollectionSchema::KeyValueStore(new)),
+            )
+            | (
+                BlueprintCollectionSchema::Index(old),
+                Some(BlueprintCollectionSchema::Index(new)),
+            )
+            | (
+                BlueprintCollectionSchema::SortedIndex(old),
+                Some(BlueprintCollectionSchema::SortedIndex(new)),
+            ) => (old, new),
+            _ => {
+                checker.report(
+                    format!("collection {}", index),
+                    "removed or changed collection kind",
+                );
+                continue;
+            }
+        };
+        checker.check(
+            format!("collection {} key", index),
+            &old_kv.key,
+            &new_kv.key,
+            Direction::OldToNew,
+        )?;
+        checker.check(
+            format!("collection {} value", index),
+            &old_kv.value,
+            &new_kv.value,
+            Direction::OldToNew,
+        )?;
+    }
+
+    // Removing an event doesn't affect the decoding of the already emitted ones
+    for (event_name, old_event) in &old_interface.events {
+        if let Some(new_event) = new_schema_init.events.event_schema.get(event_name) {
+            checker.check(
+                format!("event {}", event_name),
+                old_event,
+                new_event,
+                Direction::OldToNew,
+            )?;
+        }
+    }
+
+    for (function_name, old_function) in &old_interface.functions {
+        let Some(new_function) = new_schema_init.functions.functions.get(function_name) else {
+            checker.report(format!("function {}", function_name), "removed");
+            continue;
+        };
+        checker.check(
+            format!("function {} input", function_name),
+            &old_function.input,
+            &new_function.input,
+            Direction::OldToNew,
+        )?;
+        checker.check(
+            format!("function {} output", function_name),
+            &old_function.output,
+            &new_function.output,
+            Direction::NewToOld,
+        )?;
+    }
+
+    Ok(())
+}
@@ -15,4 +15,10 @@ pub enum Error {
     PackageAlreadyExists,
 
     CoverageError(CoverageError),
+
+    SborDecodeError(sbor::DecodeError),
+
+    ResimError(crate::resim::Error),
+
+    SchemaIncompatible(usize),
 }
@@ -1,4 +1,5 @@
 mod cmd_build;
+mod cmd_check_compatibility;
 mod cmd_coverage;
 mod cmd_fmt;
 mod cmd_new_package;
@@ -6,6 +7,7 @@ mod cmd_test;
 mod error;
 
 pub use cmd_build::*;
+pub use cmd_check_compatibility::*;
 pub use cmd_coverage::*;
 pub use cmd_fmt::*;
 pub use cmd_new_package::*;
@@ -25,6 +27,7 @@ pub struct ScryptoCli {
 #[derive(Subcommand, Debug)]
 pub enum Command {
     Build(Build),
+    CheckCompatibility(CheckCompatibility),
     Coverage(Coverage),
     Fmt(Fmt),
     NewPackage(NewPackage),
@@ -36,6 +39,7 @@ pub fn run() -> Result<(), Error> {
 
     match cli.command {
         Command::Build(cmd) => cmd.run(),
+        Command::CheckCompatibility(cmd) => cmd.run(),
         Command::Coverage(cmd) => cmd.run(),
         Command::Fmt(cmd) => cmd.run(),
         Command::NewPackage(cmd) => cmd.run(),
@@ -90,6 +90,13 @@ $resim run ./target/temp2.rtm --blobs $blobs
 $resim new-account --manifest ./target/temp3.rtm
 $resim run ./target/temp3.rtm
 
+# Test - run manifest with an automatically locked fee
+cat > ./target/temp4.rtm << EOF
+CALL_METHOD Address("${account}") "withdraw" Address("${token_address}") Decimal("1");
+CALL_METHOD Address("${account2}") "try_deposit_batch_or_abort" Expression("ENTIRE_WORKTOP") None;
+EOF
+$resim run ./target/temp4.rtm --auto-fee
+
 # Test - run manifest with a given set of signing keys
 $resim generate-key-pair
 $resim run ./target/temp2.rtm --blobs $blobs
@@ -11,6 +11,7 @@ use radix_engine_interface::blueprints::account::*;
 use radix_engine_interface::blueprints::consensus_manager::*;
 use radix_engine_interface::blueprints::identity::*;
 use radix_engine_interface::blueprints::package::*;
+use radix_engine_interface::blueprints::pool::*;
 use radix_engine_interface::blueprints::resource::*;
 
 /// A manifest builder for use in tests.
@@ -484,6 +485,24 @@ impl ManifestBuilder {
         })
     }
 
+    /// Runs the child intent at the given index, passing it the arguments, which take the same
+    /// forms as in `call_function`. The value the child yields back is handled like the return
+    /// value of a call.
+    pub fn yield_to_child(self, child_index: u32, arguments: impl ResolvableArguments) -> Self {
+        self.add_instruction(InstructionV1::YieldToChild {
+            child_index,
+            args: arguments.resolve(),
+        })
+    }
+
+    /// Yields the arguments back to the parent intent. This must be the last instruction of a
+    /// subintent manifest.
+    pub fn yield_to_parent(self, arguments: impl ResolvableArguments) -> Self {
+        self.add_instruction(InstructionV1::YieldToParent {
+            args: arguments.resolve(),
+        })
+    }
+
     /// Drops a proof.
     pub fn drop_proof(self, proof: impl ExistingManifestProof) -> Self {
         let proof = proof.mark_consumed(&self.registrar);
@@ -748,6 +767,11 @@ impl ManifestBuilder {
         self.call_method(address, VALIDATOR_UNREGISTER_IDENT, ())
     }
 
+    pub fn unjail_validator(self, validator_address: impl ResolvableComponentAddress) -> Self {
+        let address = validator_address.resolve(&self.registrar);
+        self.call_method(address, VALIDATOR_UNJAIL_IDENT, ())
+    }
+
     pub fn signal_protocol_update_readiness(
         self,
         validator_address: impl ResolvableComponentAddress,
@@ -2014,6 +2038,74 @@ impl ManifestBuilder {
         )
     }
 
+    pub fn create_weighted_pool(
+        self,
+        owner_role: OwnerRole,
+        pool_manager_rule: AccessRule,
+        resource_weights: IndexMap<ResourceAddress, Decimal>,
+        swap_fee: impl ResolvableDecimal,
+    ) -> Self {
+        let swap_fee = swap_fee.resolve();
+        self.call_function(
+            POOL_PACKAGE,
+            WEIGHTED_POOL_BLUEPRINT,
+            WEIGHTED_POOL_INSTANTIATE_IDENT,
+            WeightedPoolInstantiateManifestInput {
+                owner_role,
+                pool_manager_rule,
+                resource_weights,
+                swap_fee,
+                address_reservation: None,
+            },
+        )
+    }
+
+    pub fn create_stable_swap_pool(
+        self,
+        owner_role: OwnerRole,
+        pool_manager_rule: AccessRule,
+        resource_addresses: IndexSet<ResourceAddress>,
+        amplification: impl ResolvableDecimal,
+        swap_fee: impl ResolvableDecimal,
+    ) -> Self {
+        let amplification = amplification.resolve();
+        let swap_fee = swap_fee.resolve();
+        self.call_function(
+            POOL_PACKAGE,
+            STABLE_SWAP_POOL_BLUEPRINT,
+            STABLE_SWAP_POOL_INSTANTIATE_IDENT,
+            StableSwapPoolInstantiateManifestInput {
+                owner_role,
+                pool_manager_rule,
+                resource_addresses,
+                amplification,
+                swap_fee,
+                address_reservation: None,
+            },
+        )
+    }
+
+    /// Swaps the bucket in a weighted or stable-swap pool, which puts the output bucket on the
+    /// worktop.
+    pub fn swap_in_pool(
+        self,
+        pool_address: impl ResolvableComponentAddress,
+        input_bucket: impl ExistingManifestBucket,
+        output_resource_address: impl ResolvableResourceAddress,
+    ) -> Self {
+        let address = pool_address.resolve(&self.registrar);
+        let input_bucket = input_bucket.mark_consumed(&self.registrar);
+        let output_resource_address = output_resource_address.resolve_static(&self.registrar);
+        self.call_method(
+            address,
+            WEIGHTED_POOL_SWAP_IDENT,
+            WeightedPoolSwapManifestInput {
+                input_bucket,
+                output_resource_address,
+            },
+        )
+    }
+
     /// Builds a transaction manifest.
     pub fn build(self) -> TransactionManifestV1 {
         let manifest = TransactionManifestV1 {
@@ -35,6 +35,18 @@ impl TransactionBuilder {
         self
     }
 
+    /// Encrypts the given message for the given decryptors, see [`crate::encryption::encrypt_message`].
+    #[cfg(feature = "message_encryption")]
+    pub fn encrypted_message<R: rand_core::RngCore + rand_core::CryptoRng>(
+        self,
+        plaintext: PlaintextMessageV1,
+        decryptors: &[crate::internal_prelude::PublicKey],
+        rng: &mut R,
+    ) -> Result<Self, crate::errors::MessageEncryptionError> {
+        let encrypted = crate::encryption::encrypt_message(&plaintext, decryptors, rng)?;
+        Ok(self.message(MessageV1::Encrypted(encrypted)))
+    }
+
     pub fn sign<S: Signer>(mut self, signer: &S) -> Self {
         let intent = self.transaction_intent();
         let prepared = intent.prepare().expect("Intent could be prepared");
@@ -115,6 +127,240 @@ impl TransactionBuilder {
     }
 }
 
+/// Builds a subintent, signed by its own signers, for a [`TransactionV2Builder`] to embed.
+pub struct SubintentBuilder {
+    manifest: Option<TransactionManifestV1>,
+    header: Option<SubintentHeaderV2>,
+    message: Option<MessageV1>,
+    intent_signatures: Vec<SignatureWithPublicKeyV1>,
+}
+
+impl SubintentBuilder {
+    pub fn new() -> Self {
+        Self {
+            manifest: None,
+            header: None,
+            message: None,
+            intent_signatures: vec![],
+        }
+    }
+
+    pub fn manifest(mut self, manifest: TransactionManifestV1) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
+    pub fn header(mut self, header: SubintentHeaderV2) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn message(mut self, message: MessageV1) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub fn sign<S: Signer>(mut self, signer: &S) -> Self {
+        let subintent = self.subintent();
+        let prepared = subintent.prepare().expect("Subintent could be prepared");
+        self.intent_signatures
+            .push(signer.sign_with_public_key(&prepared.subintent_hash()));
+        self
+    }
+
+    pub fn multi_sign<S: Signer>(mut self, signers: &[&S]) -> Self {
+        let subintent = self.subintent();
+        let prepared = subintent.prepare().expect("Subintent could be prepared");
+        for signer in signers {
+            self.intent_signatures
+                .push(signer.sign_with_public_key(&prepared.subintent_hash()));
+        }
+        self
+    }
+
+    pub fn build(&self) -> SignedSubintentV2 {
+        SignedSubintentV2 {
+            subintent: self.subintent(),
+            subintent_signatures: IntentSignaturesV1 {
+                signatures: self
+                    .intent_signatures
+                    .clone()
+                    .into_iter()
+                    .map(|sig| IntentSignatureV1(sig))
+                    .collect(),
+            },
+        }
+    }
+
+    fn subintent(&self) -> SubintentV2 {
+        let (instructions, blobs) = self
+            .manifest
+            .clone()
+            .expect("Manifest not specified")
+            .for_intent();
+        SubintentV2 {
+            header: self.header.clone().expect("Header not specified"),
+            instructions,
+            blobs,
+            message: self.message.clone().unwrap_or(MessageV1::None),
+        }
+    }
+}
+
+/// Builds a transaction whose root intent embeds signed subintents, which its manifest runs
+/// with `YIELD_TO_CHILD`, in the order they're added.
+pub struct TransactionV2Builder {
+    manifest: Option<TransactionManifestV1>,
+    header: Option<TransactionHeaderV1>,
+    message: Option<MessageV1>,
+    children: Vec<SignedSubintentV2>,
+    intent_signatures: Vec<SignatureWithPublicKeyV1>,
+    fee_payer: Option<FeePayerV2>,
+    notary_signature: Option<SignatureV1>,
+}
+
+impl TransactionV2Builder {
+    pub fn new() -> Self {
+        Self {
+            manifest: None,
+            header: None,
+            message: None,
+            children: vec![],
+            intent_signatures: vec![],
+            fee_payer: None,
+            notary_signature: None,
+        }
+    }
+
+    pub fn manifest(mut self, manifest: TransactionManifestV1) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
+    pub fn header(mut self, header: TransactionHeaderV1) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn message(mut self, message: MessageV1) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub fn add_child(mut self, child: SignedSubintentV2) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Has the fees paid from the `account` of the `signer`, up to `max_fee` XRD.
+    /// This signs the intent, so must be done after the manifest, header and children are set.
+    pub fn sponsor<S: Signer>(
+        mut self,
+        account: ComponentAddress,
+        max_fee: Decimal,
+        signer: &S,
+    ) -> Self {
+        let intent = self.transaction_intent();
+        let prepared = intent.prepare().expect("Intent could be prepared");
+        let hash = FeeSponsorV2::signable_hash(&prepared.intent_hash(), &account, &max_fee);
+        self.fee_payer = Some(FeePayerV2::Sponsor(FeeSponsorV2 {
+            account,
+            max_fee,
+            signature: signer.sign_with_public_key(&hash),
+        }));
+        self
+    }
+
+    pub fn sign<S: Signer>(mut self, signer: &S) -> Self {
+        let intent = self.transaction_intent();
+        let prepared = intent.prepare().expect("Intent could be prepared");
+        self.intent_signatures
+            .push(signer.sign_with_public_key(&prepared.intent_hash()));
+        self
+    }
+
+    pub fn multi_sign<S: Signer>(mut self, signers: &[&S]) -> Self {
+        let intent = self.transaction_intent();
+        let prepared = intent.prepare().expect("Intent could be prepared");
+        for signer in signers {
+            self.intent_signatures
+                .push(signer.sign_with_public_key(&prepared.intent_hash()));
+        }
+        self
+    }
+
+    pub fn notarize<S: Signer>(mut self, signer: &S) -> Self {
+        let signed_intent = self.signed_transaction_intent();
+        let prepared = signed_intent
+            .prepare()
+            .expect("Signed intent could be prepared");
+        self.notary_signature = Some(
+            signer
+                .sign_with_public_key(&prepared.signed_intent_hash())
+                .signature(),
+        );
+        self
+    }
+
+    pub fn notary_signature(mut self, signature: SignatureV1) -> Self {
+        self.notary_signature = Some(signature);
+        self
+    }
+
+    pub fn build(&self) -> NotarizedTransactionV2 {
+        NotarizedTransactionV2 {
+            signed_intent: self.signed_transaction_intent(),
+            notary_signature: NotarySignatureV1(
+                self.notary_signature.clone().expect("Not notarized"),
+            ),
+        }
+    }
+
+    fn transaction_intent(&self) -> IntentV2 {
+        let (instructions, blobs) = self
+            .manifest
+            .clone()
+            .expect("Manifest not specified")
+            .for_intent();
+        IntentV2 {
+            header: self.header.clone().expect("Header not specified"),
+            instructions,
+            blobs,
+            message: self.message.clone().unwrap_or(MessageV1::None),
+            child_intents: ChildIntentsV2 {
+                children: self
+                    .children
+                    .iter()
+                    .map(|child| child.subintent.clone())
+                    .collect(),
+            },
+        }
+    }
+
+    fn signed_transaction_intent(&self) -> SignedIntentV2 {
+        let intent = self.transaction_intent();
+        SignedIntentV2 {
+            intent,
+            intent_signatures: IntentSignaturesV1 {
+                signatures: self
+                    .intent_signatures
+                    .clone()
+                    .into_iter()
+                    .map(|sig| IntentSignatureV1(sig))
+                    .collect(),
+            },
+            child_intent_signatures: ChildIntentSignaturesV2 {
+                by_child: self
+                    .children
+                    .iter()
+                    .map(|child| child.subintent_signatures.clone())
+                    .collect(),
+            },
+            fee_payer: self.fee_payer.clone().unwrap_or_default(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use radix_engine_common::types::Epoch;
@@ -0,0 +1,394 @@
+use crate::internal_prelude::*;
+use crate::signing::PrivateKey;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes128Gcm, Nonce};
+use aes_kw::KekAes256;
+use blake2::digest::consts::U32;
+use blake2::Blake2b;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::scalar::Scalar;
+use hkdf::SimpleHkdf;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+/// The length of the AES-GCM nonce, which prefixes the `AesGcmPayload`.
+const AES_GCM_NONCE_LENGTH: usize = 12;
+/// The length of the AES-GCM tag, which suffixes the `AesGcmPayload`.
+const AES_GCM_TAG_LENGTH: usize = 16;
+/// The length of the ephemeral (128-bit) AES-GCM symmetric key.
+const SYMMETRIC_KEY_LENGTH: usize = 16;
+/// The length of the (256-bit) AES-KeyWrap KEK.
+const KEY_ENCRYPTING_KEY_LENGTH: usize = 32;
+
+/// Encrypts the given message with "MultiPartyECIES" for the given decryptors, as described on
+/// [`EncryptedMessageV1`].
+///
+/// The `rng` is used to generate the ephemeral symmetric key, the AES-GCM nonce and an ephemeral
+/// Diffie-Hellman key per curve type, so it must be cryptographically secure.
+pub fn encrypt_message<R: RngCore + CryptoRng>(
+    plaintext: &PlaintextMessageV1,
+    decryptors: &[PublicKey],
+    rng: &mut R,
+) -> Result<EncryptedMessageV1, MessageEncryptionError> {
+    if decryptors.is_empty() {
+        return Err(MessageEncryptionError::NoDecryptors);
+    }
+
+    let mut symmetric_key = [0u8; SYMMETRIC_KEY_LENGTH];
+    rng.fill_bytes(&mut symmetric_key);
+    let mut nonce = [0u8; AES_GCM_NONCE_LENGTH];
+    rng.fill_bytes(&mut nonce);
+
+    let payload = manifest_encode(plaintext).map_err(MessageEncryptionError::EncodeError)?;
+    let cipher = Aes128Gcm::new_from_slice(&symmetric_key).expect("Key length is valid");
+    let encrypted = cipher
+        .encrypt(Nonce::from_slice(&nonce), payload.as_slice())
+        .map_err(|_| MessageEncryptionError::PayloadTooLong)?;
+
+    let mut decryptors_by_curve = index_map_new();
+
+    let ed25519_decryptors = decryptors
+        .iter()
+        .filter_map(|public_key| match public_key {
+            PublicKey::Ed25519(public_key) => Some(public_key),
+            PublicKey::Secp256k1(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if !ed25519_decryptors.is_empty() {
+        let ephemeral_private_key = new_ed25519_private_key(rng);
+        let mut wrapped_keys = index_map_new();
+        for public_key in ed25519_decryptors {
+            let shared_secret = x25519_shared_secret(&ephemeral_private_key, public_key).ok_or(
+                MessageEncryptionError::InvalidDecryptorPublicKey(public_key.clone().into()),
+            )?;
+            wrapped_keys.insert(
+                PublicKeyFingerprint::from(PublicKey::from(*public_key)),
+                wrap_symmetric_key(&shared_secret, &symmetric_key),
+            );
+        }
+        decryptors_by_curve.insert(
+            CurveType::Ed25519,
+            DecryptorsByCurve::Ed25519 {
+                dh_ephemeral_public_key: ephemeral_private_key.public_key(),
+                decryptors: wrapped_keys,
+            },
+        );
+    }
+
+    let secp256k1_decryptors = decryptors
+        .iter()
+        .filter_map(|public_key| match public_key {
+            PublicKey::Secp256k1(public_key) => Some(public_key),
+            PublicKey::Ed25519(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if !secp256k1_decryptors.is_empty() {
+        let ephemeral_private_key = new_secp256k1_private_key(rng);
+        let mut wrapped_keys = index_map_new();
+        for public_key in secp256k1_decryptors {
+            let shared_secret = secp256k1_shared_secret(&ephemeral_private_key, public_key).ok_or(
+                MessageEncryptionError::InvalidDecryptorPublicKey(public_key.clone().into()),
+            )?;
+            wrapped_keys.insert(
+                PublicKeyFingerprint::from(PublicKey::from(*public_key)),
+                wrap_symmetric_key(&shared_secret, &symmetric_key),
+            );
+        }
+        decryptors_by_curve.insert(
+            CurveType::Secp256k1,
+            DecryptorsByCurve::Secp256k1 {
+                dh_ephemeral_public_key: ephemeral_private_key.public_key(),
+                decryptors: wrapped_keys,
+            },
+        );
+    }
+
+    Ok(EncryptedMessageV1 {
+        encrypted: AesGcmPayload([nonce.as_slice(), encrypted.as_slice()].concat()),
+        decryptors_by_curve,
+    })
+}
+
+/// Decrypts the given "MultiPartyECIES" message (see [`EncryptedMessageV1`]) using the private
+/// key of one of its decryptors.
+pub fn decrypt_message(
+    encrypted_message: &EncryptedMessageV1,
+    private_key: &PrivateKey,
+) -> Result<PlaintextMessageV1, MessageDecryptionError> {
+    let fingerprint = PublicKeyFingerprint::from(private_key.public_key());
+    let symmetric_key = match private_key {
+        PrivateKey::Ed25519(private_key) => {
+            match encrypted_message
+                .decryptors_by_curve
+                .get(&CurveType::Ed25519)
+            {
+                Some(DecryptorsByCurve::Ed25519 {
+                    dh_ephemeral_public_key,
+                    decryptors,
+                }) => {
+                    let wrapped_key = decryptors
+                        .get(&fingerprint)
+                        .ok_or(MessageDecryptionError::NotADecryptor)?;
+                    let shared_secret = x25519_shared_secret(private_key, dh_ephemeral_public_key)
+                        .ok_or(MessageDecryptionError::InvalidEphemeralPublicKey)?;
+                    unwrap_symmetric_key(&shared_secret, wrapped_key)?
+                }
+                _ => return Err(MessageDecryptionError::NotADecryptor),
+            }
+        }
+        PrivateKey::Secp256k1(private_key) => {
+            match encrypted_message
+                .decryptors_by_curve
+                .get(&CurveType::Secp256k1)
+            {
+                Some(DecryptorsByCurve::Secp256k1 {
+                    dh_ephemeral_public_key,
+                    decryptors,
+                }) => {
+                    let wrapped_key = decryptors
+                        .get(&fingerprint)
+                        .ok_or(MessageDecryptionError::NotADecryptor)?;
+                    let shared_secret =
+                        secp256k1_shared_secret(private_key, dh_ephemeral_public_key)
+                            .ok_or(MessageDecryptionError::InvalidEphemeralPublicKey)?;
+                    unwrap_symmetric_key(&shared_secret, wrapped_key)?
+                }
+                _ => return Err(MessageDecryptionError::NotADecryptor),
+            }
+        }
+    };
+
+    let encrypted = &encrypted_message.encrypted.0;
+    if encrypted.len() < AES_GCM_NONCE_LENGTH + AES_GCM_TAG_LENGTH {
+        return Err(MessageDecryptionError::InvalidPayload);
+    }
+    let (nonce, cipher_and_tag) = encrypted.split_at(AES_GCM_NONCE_LENGTH);
+    let cipher = Aes128Gcm::new_from_slice(&symmetric_key).expect("Key length is valid");
+    let payload = cipher
+        .decrypt(Nonce::from_slice(nonce), cipher_and_tag)
+        .map_err(|_| MessageDecryptionError::InvalidPayload)?;
+    manifest_decode(&payload).map_err(MessageDecryptionError::DecodeError)
+}
+
+fn new_ed25519_private_key<R: RngCore + CryptoRng>(rng: &mut R) -> Ed25519PrivateKey {
+    let mut bytes = [0u8; Ed25519PrivateKey::LENGTH];
+    rng.fill_bytes(&mut bytes);
+    Ed25519PrivateKey::from_bytes(&bytes).expect("Every 32 bytes are a valid Ed25519 key")
+}
+
+fn new_secp256k1_private_key<R: RngCore + CryptoRng>(rng: &mut R) -> Secp256k1PrivateKey {
+    // Retry on the (negligibly unlikely) bytes outside of the curve order
+    loop {
+        let mut bytes = [0u8; Secp256k1PrivateKey::LENGTH];
+        rng.fill_bytes(&mut bytes);
+        if let Ok(private_key) = Secp256k1PrivateKey::from_bytes(&bytes) {
+            return private_key;
+        }
+    }
+}
+
+/// The `x` coordinate of the static Diffie-Hellman shared point (the ASN1 X9.63 variant).
+fn secp256k1_shared_secret(
+    private_key: &Secp256k1PrivateKey,
+    public_key: &Secp256k1PublicKey,
+) -> Option<[u8; 32]> {
+    let secret_key = secp256k1::SecretKey::from_slice(&private_key.to_bytes()).ok()?;
+    let public_key = secp256k1::PublicKey::from_slice(&public_key.0).ok()?;
+    let shared_point = secp256k1::ecdh::shared_secret_point(&public_key, &secret_key);
+    Some(copy_u8_array(&shared_point[..32]))
+}
+
+/// The X25519 shared secret (i.e. the `u` coordinate of the shared Montgomery point), using the
+/// birationally-equivalent X25519 forms of the given Ed25519 keys.
+///
+/// Returns `None` for the all-zero shared secret, i.e. when the public key is a small-order point
+/// (as RFC 7748 recommends checking), since such a secret doesn't depend on the private key.
+fn x25519_shared_secret(
+    private_key: &Ed25519PrivateKey,
+    public_key: &Ed25519PublicKey,
+) -> Option<[u8; 32]> {
+    // The X25519 scalar is the clamped lower half of the SHA-512 of the Ed25519 seed
+    let hash = Sha512::digest(private_key.to_bytes());
+    let mut scalar_bytes: [u8; 32] = copy_u8_array(&hash[..32]);
+    scalar_bytes[0] &= 248;
+    scalar_bytes[31] &= 127;
+    scalar_bytes[31] |= 64;
+    let scalar = Scalar::from_bits(scalar_bytes);
+
+    let point = CompressedEdwardsY(public_key.0)
+        .decompress()?
+        .to_montgomery();
+    let shared_secret = (point * scalar).to_bytes();
+    if shared_secret == [0u8; 32] {
+        return None;
+    }
+    Some(shared_secret)
+}
+
+/// `KEK = HKDF(hash: Blake2b, secret: shared_secret, salt: [], length: 256 bits)`
+fn key_encrypting_key(shared_secret: &[u8; 32]) -> KekAes256 {
+    let mut key_encrypting_key = [0u8; KEY_ENCRYPTING_KEY_LENGTH];
+    SimpleHkdf::<Blake2b<U32>>::new(None, shared_secret)
+        .expand(&[], &mut key_encrypting_key)
+        .expect("KEK length is valid");
+    KekAes256::from(key_encrypting_key)
+}
+
+fn wrap_symmetric_key(
+    shared_secret: &[u8; 32],
+    symmetric_key: &[u8; SYMMETRIC_KEY_LENGTH],
+) -> AesWrapped128BitKey {
+    let mut wrapped_key = [0u8; AesWrapped128BitKey::LENGTH];
+    key_encrypting_key(shared_secret)
+        .wrap(symmetric_key, &mut wrapped_key)
+        .expect("Key lengths are valid");
+    AesWrapped128BitKey(wrapped_key)
+}
+
+fn unwrap_symmetric_key(
+    shared_secret: &[u8; 32],
+    wrapped_key: &AesWrapped128BitKey,
+) -> Result<[u8; SYMMETRIC_KEY_LENGTH], MessageDecryptionError> {
+    let mut symmetric_key = [0u8; SYMMETRIC_KEY_LENGTH];
+    key_encrypting_key(shared_secret)
+        .unwrap(&wrapped_key.0, &mut symmetric_key)
+        .map_err(|_| MessageDecryptionError::InvalidWrappedKey)?;
+    Ok(symmetric_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use sbor::rust::str::FromStr;
+
+    fn plaintext() -> PlaintextMessageV1 {
+        PlaintextMessageV1 {
+            mime_type: "text/plain".to_string(),
+            message: MessageContentsV1::String("Hello, MultiPartyECIES!".to_string()),
+        }
+    }
+
+    fn private_keys() -> Vec<PrivateKey> {
+        vec![
+            Secp256k1PrivateKey::from_u64(1).unwrap().into(),
+            Secp256k1PrivateKey::from_u64(3).unwrap().into(),
+            Ed25519PrivateKey::from_u64(1).unwrap().into(),
+            Ed25519PrivateKey::from_u64(3).unwrap().into(),
+        ]
+    }
+
+    #[test]
+    fn encrypted_message_can_be_decrypted_by_each_decryptor() {
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let decryptors = private_keys()
+            .iter()
+            .map(|private_key| private_key.public_key())
+            .collect::<Vec<_>>();
+
+        let encrypted = encrypt_message(&plaintext(), &decryptors, &mut rng).unwrap();
+
+        assert_eq!(encrypted.decryptors_by_curve.len(), 2);
+        for private_key in private_keys() {
+            assert_eq!(decrypt_message(&encrypted, &private_key), Ok(plaintext()));
+        }
+        assert_eq!(
+            decrypt_message(&encrypted, &Ed25519PrivateKey::from_u64(2).unwrap().into()),
+            Err(MessageDecryptionError::NotADecryptor)
+        );
+    }
+
+    #[test]
+    fn small_order_public_keys_are_rejected() {
+        // The Ed25519 identity point, whose X25519 form gives the all-zero shared secret
+        let mut identity = [0u8; Ed25519PublicKey::LENGTH];
+        identity[0] = 1;
+        let small_order_public_key = Ed25519PublicKey(identity);
+
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        assert_eq!(
+            encrypt_message(&plaintext(), &[small_order_public_key.into()], &mut rng),
+            Err(MessageEncryptionError::InvalidDecryptorPublicKey(
+                small_order_public_key.into()
+            ))
+        );
+
+        let private_key: PrivateKey = Ed25519PrivateKey::from_u64(1).unwrap().into();
+        let mut encrypted =
+            encrypt_message(&plaintext(), &[private_key.public_key()], &mut rng).unwrap();
+        if let Some(DecryptorsByCurve::Ed25519 {
+            dh_ephemeral_public_key,
+            ..
+        }) = encrypted.decryptors_by_curve.get_mut(&CurveType::Ed25519)
+        {
+            *dh_ephemeral_public_key = small_order_public_key;
+        }
+        assert_eq!(
+            decrypt_message(&encrypted, &private_key),
+            Err(MessageDecryptionError::InvalidEphemeralPublicKey)
+        );
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let private_key: PrivateKey = Secp256k1PrivateKey::from_u64(1).unwrap().into();
+        let mut encrypted =
+            encrypt_message(&plaintext(), &[private_key.public_key()], &mut rng).unwrap();
+
+        let last = encrypted.encrypted.0.len() - 1;
+        encrypted.encrypted.0[last] ^= 1;
+
+        assert_eq!(
+            decrypt_message(&encrypted, &private_key),
+            Err(MessageDecryptionError::InvalidPayload)
+        );
+    }
+
+    /// A regression vector, generated by this implementation with:
+    /// * symmetric key `0102..10` and nonce `6566..70`,
+    /// * decryptors with private keys `00..01` (both Secp256k1 and Ed25519),
+    /// * ephemeral Diffie-Hellman private keys `00..02` (both Secp256k1 and Ed25519).
+    ///
+    /// It only guards against unintended changes of the format. It doesn't show interoperability
+    /// with other "MultiPartyECIES" implementations, which needs a vector produced by one of them.
+    #[test]
+    fn regression_vector_can_be_decrypted() {
+        let encrypted = EncryptedMessageV1 {
+            encrypted: AesGcmPayload(hex::decode("65666768696a6b6c6d6e6f70be75773b1ce4e7f2821665764e996b2ab95c2196413f085dffb4c325674c50ae1185039fc48f4d303d9cd847f906771e02b03a87a1fcac084a70f4").unwrap()),
+            decryptors_by_curve: indexmap!(
+                CurveType::Ed25519 => DecryptorsByCurve::Ed25519 {
+                    dh_ephemeral_public_key: Ed25519PublicKey::from_str("7422b9887598068e32c4448a949adb290d0f4e35b9e01b0ee5f1a1e600fe2674").unwrap(),
+                    decryptors: indexmap!(
+                        PublicKeyFingerprint(copy_u8_array(&hex::decode("f91fa8df2486c9ea").unwrap())) =>
+                            AesWrapped128BitKey(copy_u8_array(&hex::decode("24da706014416111d772f7621a3119cd66d5cb3454d621d4").unwrap()))
+                    ),
+                },
+                CurveType::Secp256k1 => DecryptorsByCurve::Secp256k1 {
+                    dh_ephemeral_public_key: Secp256k1PublicKey::from_str("02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5").unwrap(),
+                    decryptors: indexmap!(
+                        PublicKeyFingerprint(copy_u8_array(&hex::decode("a243c1128c2fe737").unwrap())) =>
+                            AesWrapped128BitKey(copy_u8_array(&hex::decode("6e6f90951b95f029389bea9662e1f6121950c856a9fd3740").unwrap()))
+                    ),
+                },
+            ),
+        };
+
+        assert_eq!(
+            manifest_encode(&plaintext()).unwrap(),
+            hex::decode("4d21020c0a746578742f706c61696e2200010c1748656c6c6f2c204d756c74695061727479454349455321").unwrap()
+        );
+        assert_eq!(
+            decrypt_message(
+                &encrypted,
+                &Secp256k1PrivateKey::from_u64(1).unwrap().into()
+            ),
+            Ok(plaintext())
+        );
+        assert_eq!(
+            decrypt_message(&encrypted, &Ed25519PrivateKey::from_u64(1).unwrap().into()),
+            Ok(plaintext())
+        );
+    }
+}
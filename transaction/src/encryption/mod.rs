@@ -0,0 +1,3 @@
+mod message_encryption;
+
+pub use message_encryption::*;
@@ -15,6 +15,8 @@ pub enum SignatureValidationError {
     TooManySignatures,
     InvalidIntentSignature,
     InvalidNotarySignature,
+    InvalidChildIntentSignature,
+    InvalidFeeSponsorSignature,
     DuplicateSigner,
     SerializationError(EncodeError),
 }
@@ -40,6 +42,28 @@ pub enum CallDataValidationError {
     IdValidationError(ManifestIdValidationError),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubintentValidationError {
+    ChildIntentNotFound(u32),
+    ChildIntentYieldedToMoreThanOnce(u32),
+    ChildIntentNotYieldedTo(u32),
+    YieldToChildInsideSubintent,
+    YieldToParentOutsideSubintent,
+    YieldToParentNotLastInstruction,
+    MissingYieldToParent,
+    DuplicateChildIntent(SubintentHash),
+    MismatchingChildIntentSignatures {
+        child_intents: usize,
+        signatures: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeeSponsorValidationError {
+    NotAnAccount(ComponentAddress),
+    InvalidMaxFee(Decimal),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransactionValidationError {
     TransactionTooLarge,
@@ -50,6 +74,8 @@ pub enum TransactionValidationError {
     IdValidationError(ManifestIdValidationError),
     CallDataValidationError(CallDataValidationError),
     InvalidMessage(InvalidMessageError),
+    SubintentValidationError(SubintentValidationError),
+    FeeSponsorValidationError(FeeSponsorValidationError),
 }
 
 impl From<PrepareError> for TransactionValidationError {
@@ -64,6 +90,18 @@ impl From<EncodeError> for TransactionValidationError {
     }
 }
 
+impl From<SubintentValidationError> for TransactionValidationError {
+    fn from(value: SubintentValidationError) -> Self {
+        Self::SubintentValidationError(value)
+    }
+}
+
+impl From<FeeSponsorValidationError> for TransactionValidationError {
+    fn from(value: FeeSponsorValidationError) -> Self {
+        Self::FeeSponsorValidationError(value)
+    }
+}
+
 impl From<InvalidMessageError> for TransactionValidationError {
     fn from(value: InvalidMessageError) -> Self {
         Self::InvalidMessage(value)
@@ -97,3 +135,20 @@ pub enum InvalidMessageError {
         curve_type: CurveType,
     },
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageEncryptionError {
+    NoDecryptors,
+    InvalidDecryptorPublicKey(PublicKey),
+    EncodeError(EncodeError),
+    PayloadTooLong,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageDecryptionError {
+    NotADecryptor,
+    InvalidEphemeralPublicKey,
+    InvalidWrappedKey,
+    InvalidPayload,
+    DecodeError(DecodeError),
+}
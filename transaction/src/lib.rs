@@ -1,5 +1,7 @@
 pub mod builder;
 pub mod data;
+#[cfg(feature = "message_encryption")]
+pub mod encryption;
 pub mod errors;
 pub mod manifest;
 pub mod model;
@@ -18,6 +20,8 @@ pub mod prelude {
 
     // Exports from this crate
     pub use crate::builder::*;
+    #[cfg(feature = "message_encryption")]
+    pub use crate::encryption::{decrypt_message, encrypt_message};
     pub use crate::model::*;
     pub use crate::signing::{PrivateKey, Signer};
 }
@@ -3,10 +3,23 @@ use crate::internal_prelude::*;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CompileError {
     LexerError(lexer::LexerError),
+    PreprocessorError(preprocessor::PreprocessorError),
     ParserError(parser::ParserError),
     GeneratorError(generator::GeneratorError),
 }
 
+impl CompileError {
+    /// The span of the manifest source which caused the error.
+    pub fn span(&self) -> lexer::Span {
+        match self {
+            CompileError::LexerError(err) => err.span,
+            CompileError::PreprocessorError(err) => err.span,
+            CompileError::ParserError(err) => err.span,
+            CompileError::GeneratorError(err) => err.span,
+        }
+    }
+}
+
 pub fn compile<B>(
     s: &str,
     network: &NetworkDefinition,
@@ -14,10 +27,37 @@ pub fn compile<B>(
 ) -> Result<TransactionManifestV1, CompileError>
 where
     B: IsBlobProvider,
+{
+    compile_with_macros(
+        s,
+        "",
+        network,
+        blobs,
+        &SourceProvider::new(),
+        &index_map_new::<String, String>(),
+    )
+}
+
+/// Compiles a manifest which may `INCLUDE` the `sources`, resolved relative to `source_name`, and
+/// whose `${name}` placeholders are filled from the `params`.
+pub fn compile_with_macros<B, S, P>(
+    s: &str,
+    source_name: &str,
+    network: &NetworkDefinition,
+    blobs: B,
+    sources: &S,
+    params: &P,
+) -> Result<TransactionManifestV1, CompileError>
+where
+    B: IsBlobProvider,
+    S: IsSourceProvider,
+    P: IsParamProvider,
 {
     let address_bech32_decoder = AddressBech32Decoder::new(network);
 
     let tokens = lexer::tokenize(s).map_err(CompileError::LexerError)?;
+    let tokens = preprocessor::preprocess(tokens, source_name, sources, params)
+        .map_err(CompileError::PreprocessorError)?;
     let instructions = parser::Parser::new(tokens, parser::PARSER_MAX_DEPTH)
         .parse_manifest()
         .map_err(CompileError::ParserError)?;
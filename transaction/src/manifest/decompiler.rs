@@ -658,6 +658,22 @@ pub fn decompile_instruction<F: fmt::Write>(
                 ))?,
             )
         }
+        InstructionV1::YieldToChild { child_index, args } => {
+            let mut fields = vec![to_manifest_value(child_index)?];
+            if let Value::Tuple { fields: arg_fields } = args {
+                fields.extend(arg_fields.clone());
+            } else {
+                return Err(DecompileError::InvalidArguments);
+            }
+            ("YIELD_TO_CHILD", Value::Tuple { fields })
+        }
+        InstructionV1::YieldToParent { args } => {
+            if let Value::Tuple { .. } = args {
+                ("YIELD_TO_PARENT", args.clone())
+            } else {
+                return Err(DecompileError::InvalidArguments);
+            }
+        }
     };
 
     write!(f, "{}", display_name)?;
@@ -0,0 +1,479 @@
+use crate::internal_prelude::*;
+use crate::manifest::generator::{GeneratorErrorKind, NameResolverError};
+use crate::manifest::lexer::{LexerErrorKind, Span, TokenKind};
+use crate::manifest::parser::{ParserErrorKind, TokenType};
+use crate::manifest::preprocessor::PreprocessorErrorKind;
+use radix_engine_interface::data::manifest::ManifestValueKind;
+
+/// Renders a compilation error as a rustc-like diagnostic, with the offending part of the
+/// manifest source annotated, eg.
+///
+/// ```text
+/// error: invalid global address
+///  --> manifest.rtm:2:13
+///   |
+/// 2 |     Address("resource_sim1abc")
+///   |             ^^^^^^^^^^^^^^^^^^ "resource_sim1abc" is not a valid global address
+/// ```
+///
+/// The `source` must be the exact text which was compiled, as the error spans index into it.
+pub fn render_compile_error(source_name: &str, source: &str, error: &CompileError) -> String {
+    let (title, label) = describe_compile_error(error);
+    render_snippet(source_name, source, error.span(), &title, &label)
+}
+
+fn render_snippet(source_name: &str, source: &str, span: Span, title: &str, label: &str) -> String {
+    let lines: Vec<&str> = source.split('\n').collect();
+    let first_line = span.start.line_number;
+    let last_line = span.end.line_number.max(first_line);
+    let gutter_width = last_line.to_string().len();
+    let gutter = " ".repeat(gutter_width);
+
+    let mut output = String::new();
+    output.push_str(&format!("error: {}\n", title));
+    output.push_str(&format!(
+        "{}--> {}:{}:{}\n",
+        gutter,
+        source_name,
+        first_line,
+        span.start.line_char_index + 1
+    ));
+    output.push_str(&format!("{} |\n", gutter));
+
+    for line_number in first_line..=last_line {
+        let line: String = lines
+            .get(line_number - 1)
+            .map(|line| line.trim_end_matches('\r'))
+            .unwrap_or_default()
+            .chars()
+            .map(|c| if c == '\t' { ' ' } else { c })
+            .collect();
+        let line_length = line.chars().count();
+
+        // The annotated char range of the line, indented lines are annotated from their text
+        let from = if line_number == first_line {
+            span.start.line_char_index
+        } else {
+            line.chars().take_while(|c| c.is_whitespace()).count()
+        };
+        let to = if line_number == last_line {
+            span.end.line_char_index
+        } else {
+            line_length
+        };
+        let width = to.saturating_sub(from).max(1);
+
+        output.push_str(&format!(
+            "{:>width$} | {}\n",
+            line_number,
+            line,
+            width = gutter_width
+        ));
+        output.push_str(&format!(
+            "{} | {}{}",
+            gutter,
+            " ".repeat(from),
+            "^".repeat(width)
+        ));
+        if line_number == last_line {
+            output.push_str(&format!(" {}", label));
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Returns the title and the label of the given error.
+fn describe_compile_error(error: &CompileError) -> (String, String) {
+    match error {
+        CompileError::LexerError(err) => describe_lexer_error(&err.error_kind),
+        CompileError::PreprocessorError(err) => describe_preprocessor_error(&err.error_kind),
+        CompileError::ParserError(err) => describe_parser_error(&err.error_kind),
+        CompileError::GeneratorError(err) => describe_generator_error(&err.error_kind),
+    }
+}
+
+fn describe_lexer_error(error_kind: &LexerErrorKind) -> (String, String) {
+    match error_kind {
+        LexerErrorKind::UnexpectedEof => (
+            "unexpected end of manifest".to_string(),
+            "expected more characters".to_string(),
+        ),
+        LexerErrorKind::UnexpectedChar(c) => (
+            format!("unexpected character {:?}", c),
+            "unexpected character".to_string(),
+        ),
+        LexerErrorKind::InvalidInteger(integer) => (
+            "invalid integer literal".to_string(),
+            format!("{} is out of range", integer),
+        ),
+        LexerErrorKind::InvalidUnicode(code) => (
+            "invalid unicode escape".to_string(),
+            format!("{:#x} is not a valid unicode code point", code),
+        ),
+        LexerErrorKind::UnknownIdentifier(ident) => (
+            "unknown identifier".to_string(),
+            format!("`{}` is not known", ident),
+        ),
+    }
+}
+
+fn describe_preprocessor_error(error_kind: &PreprocessorErrorKind) -> (String, String) {
+    match error_kind {
+        PreprocessorErrorKind::UnexpectedEof => (
+            "unexpected end of manifest".to_string(),
+            "expected more tokens".to_string(),
+        ),
+        PreprocessorErrorKind::UnexpectedToken { expected, actual } => (
+            "unexpected token".to_string(),
+            format!(
+                "expected {}, found {}",
+                describe_token_kind(expected),
+                describe_token_kind(actual)
+            ),
+        ),
+        PreprocessorErrorKind::InvalidConstantName(actual) => (
+            "invalid constant name".to_string(),
+            format!(
+                "expected an identifier, found {}",
+                describe_token_kind(actual)
+            ),
+        ),
+        PreprocessorErrorKind::InvalidIncludePath(actual) => (
+            "invalid include path".to_string(),
+            format!("expected a string, found {}", describe_token_kind(actual)),
+        ),
+        PreprocessorErrorKind::EmptyConstant(name) => (
+            "empty constant".to_string(),
+            format!("`{}` is defined without a value", name),
+        ),
+        PreprocessorErrorKind::DuplicateConstant(name) => (
+            "constant already defined".to_string(),
+            format!("`{}` is already defined as a constant or parameter", name),
+        ),
+        PreprocessorErrorKind::UndefinedPlaceholder(name) => (
+            "undefined placeholder".to_string(),
+            format!("no constant or parameter named `{}` is defined", name),
+        ),
+        PreprocessorErrorKind::IncludeNotFound(path) => (
+            "included manifest not found".to_string(),
+            format!("{:?} could not be found", path),
+        ),
+        PreprocessorErrorKind::RecursiveInclude(path) => (
+            "recursive include".to_string(),
+            format!("{:?} is already being included", path),
+        ),
+        PreprocessorErrorKind::LexerError(error_kind) => describe_lexer_error(error_kind),
+        PreprocessorErrorKind::InIncludedSource { path, error } => {
+            let (title, label) = describe_preprocessor_error(error);
+            (title, format!("{} (in {:?})", label, path))
+        }
+    }
+}
+
+fn describe_parser_error(error_kind: &ParserErrorKind) -> (String, String) {
+    match error_kind {
+        ParserErrorKind::UnexpectedEof => (
+            "unexpected end of manifest".to_string(),
+            "expected more tokens".to_string(),
+        ),
+        ParserErrorKind::UnexpectedToken { expected, actual } => (
+            "unexpected token".to_string(),
+            format!(
+                "expected {}, found {}",
+                describe_token_type(expected),
+                describe_token_kind(actual)
+            ),
+        ),
+        ParserErrorKind::InvalidNumberOfValues { expected, actual } => (
+            "invalid number of values".to_string(),
+            format!("expected {} value(s), found {}", expected, actual),
+        ),
+        ParserErrorKind::InvalidNumberOfTypes { expected, actual } => (
+            "invalid number of types".to_string(),
+            format!("expected {} type(s), found {}", expected, actual),
+        ),
+        ParserErrorKind::InvalidHex(hex) => (
+            "invalid hex".to_string(),
+            format!("{:?} is not valid hex", hex),
+        ),
+        ParserErrorKind::UnknownEnumDiscriminator(discriminator) => (
+            "unknown enum discriminator".to_string(),
+            format!("`{}` is not a known enum discriminator", discriminator),
+        ),
+        ParserErrorKind::MaxDepthExceeded(max_depth) => (
+            "maximum value depth exceeded".to_string(),
+            format!("values can be nested at most {} levels deep", max_depth),
+        ),
+    }
+}
+
+fn describe_token_type(token_type: &TokenType) -> String {
+    match token_type {
+        TokenType::Instruction => "an instruction".to_string(),
+        TokenType::Value => "a value".to_string(),
+        TokenType::ValueKind => "a value kind".to_string(),
+        TokenType::EnumDiscriminator => "an enum discriminator".to_string(),
+        TokenType::Exact(kind) => describe_token_kind(kind),
+    }
+}
+
+fn describe_token_kind(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Ident(ident) => format!("`{}`", ident),
+        TokenKind::Placeholder(name) => format!("`${{{}}}`", name),
+        TokenKind::StringLiteral(value) => format!("{:?}", value),
+        TokenKind::OpenParenthesis => "`(`".to_string(),
+        TokenKind::CloseParenthesis => "`)`".to_string(),
+        TokenKind::LessThan => "`<`".to_string(),
+        TokenKind::GreaterThan => "`>`".to_string(),
+        TokenKind::Comma => "`,`".to_string(),
+        TokenKind::Semicolon => "`;`".to_string(),
+        TokenKind::Equals => "`=`".to_string(),
+        TokenKind::FatArrow => "`=>`".to_string(),
+        literal => format!("{:?}", literal),
+    }
+}
+
+fn describe_generator_error(error_kind: &GeneratorErrorKind) -> (String, String) {
+    match error_kind {
+        GeneratorErrorKind::InvalidAstType {
+            expected_type,
+            actual,
+        } => (
+            "mismatched value kinds".to_string(),
+            format!("expected {:?}, found {:?}", expected_type, actual),
+        ),
+        GeneratorErrorKind::InvalidAstValue {
+            expected_type,
+            actual,
+        } => (
+            "mismatched value kinds".to_string(),
+            format!(
+                "expected {}, found {}",
+                expected_type
+                    .iter()
+                    .map(|kind| format!("{:?}", kind))
+                    .collect::<Vec<_>>()
+                    .join(" or "),
+                describe_value_kind(&actual.value_kind())
+            ),
+        ),
+        GeneratorErrorKind::UnexpectedValue {
+            expected_type,
+            actual,
+        } => (
+            "unexpected value".to_string(),
+            format!(
+                "expected {}, found {}",
+                describe_value_kind(expected_type),
+                describe_value_kind(&actual.value_kind())
+            ),
+        ),
+        GeneratorErrorKind::InvalidPackageAddress(value) => invalid("package address", value),
+        GeneratorErrorKind::InvalidComponentAddress(value) => invalid("component address", value),
+        GeneratorErrorKind::InvalidResourceAddress(value) => invalid("resource address", value),
+        GeneratorErrorKind::InvalidDecimal(value) => invalid("decimal", value),
+        GeneratorErrorKind::InvalidPreciseDecimal(value) => invalid("precise decimal", value),
+        GeneratorErrorKind::InvalidHash(value) => invalid("hash", value),
+        GeneratorErrorKind::InvalidNodeId(value) => invalid("node id", value),
+        GeneratorErrorKind::InvalidVaultId(value) => invalid("vault id", value),
+        GeneratorErrorKind::InvalidNonFungibleLocalId(value) => {
+            invalid("non-fungible local id", value)
+        }
+        GeneratorErrorKind::InvalidNonFungibleGlobalId => (
+            "invalid non-fungible global id".to_string(),
+            "not a valid non-fungible global id".to_string(),
+        ),
+        GeneratorErrorKind::InvalidExpression(value) => invalid("expression", value),
+        GeneratorErrorKind::InvalidComponent(value) => invalid("component", value),
+        GeneratorErrorKind::InvalidKeyValueStore(value) => invalid("key value store", value),
+        GeneratorErrorKind::InvalidBucket(value) => invalid("bucket", value),
+        GeneratorErrorKind::InvalidProof(value) => invalid("proof", value),
+        GeneratorErrorKind::InvalidVault(value) => invalid("vault", value),
+        GeneratorErrorKind::InvalidSecp256k1PublicKey(value) => {
+            invalid("Secp256k1 public key", value)
+        }
+        GeneratorErrorKind::InvalidSecp256k1Signature(value) => {
+            invalid("Secp256k1 signature", value)
+        }
+        GeneratorErrorKind::InvalidEd25519PublicKey(value) => invalid("Ed25519 public key", value),
+        GeneratorErrorKind::InvalidEd25519Signature(value) => invalid("Ed25519 signature", value),
+        GeneratorErrorKind::InvalidBlobHash(value) => invalid("blob hash", value),
+        GeneratorErrorKind::BlobNotFound(value) => (
+            "blob not found".to_string(),
+            format!("no blob with hash {:?} was provided", value),
+        ),
+        GeneratorErrorKind::InvalidBytesHex(value) => invalid("bytes hex", value),
+        GeneratorErrorKind::SborEncodeError(err) => {
+            ("failed to encode value".to_string(), format!("{:?}", err))
+        }
+        GeneratorErrorKind::NameResolverError(err) => describe_name_resolver_error(err),
+        GeneratorErrorKind::IdValidationError(err) => (
+            "invalid use of a bucket, proof or address reservation".to_string(),
+            format!("{:?}", err),
+        ),
+        GeneratorErrorKind::ArgumentEncodingError(err) => (
+            "failed to encode arguments".to_string(),
+            format!("{:?}", err),
+        ),
+        GeneratorErrorKind::ArgumentDecodingError(err) => (
+            "failed to decode arguments".to_string(),
+            format!("{:?}", err),
+        ),
+        GeneratorErrorKind::InvalidGlobalAddress(value) => invalid("global address", value),
+        GeneratorErrorKind::InvalidInternalAddress(value) => invalid("internal address", value),
+        GeneratorErrorKind::InvalidLength {
+            value_type,
+            expected_length,
+            actual,
+        } => (
+            "invalid length".to_string(),
+            format!(
+                "expected a {:?} of length {}, found length {}",
+                value_type, expected_length, actual
+            ),
+        ),
+    }
+}
+
+fn describe_name_resolver_error(err: &NameResolverError) -> (String, String) {
+    match err {
+        NameResolverError::UndefinedBucket(name) => undefined("bucket", name),
+        NameResolverError::UndefinedProof(name) => undefined("proof", name),
+        NameResolverError::UndefinedAddressReservation(name) => {
+            undefined("address reservation", name)
+        }
+        NameResolverError::UndefinedNamedAddress(name) => undefined("named address", name),
+        NameResolverError::NamedAlreadyDefined(name) => (
+            "name already defined".to_string(),
+            format!("{:?} is already defined", name),
+        ),
+    }
+}
+
+fn invalid(what: &str, value: &str) -> (String, String) {
+    (
+        format!("invalid {}", what),
+        format!("{:?} is not a valid {}", value, what),
+    )
+}
+
+fn undefined(what: &str, name: &str) -> (String, String) {
+    (
+        format!("undefined {}", what),
+        format!("no {} named {:?} is defined", what, name),
+    )
+}
+
+fn describe_value_kind(value_kind: &ManifestValueKind) -> String {
+    match value_kind {
+        ManifestValueKind::Custom(custom_value_kind) => format!("{:?}", custom_value_kind),
+        value_kind => format!("{:?}", value_kind),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{compile, BlobProvider};
+
+    fn render(source: &str) -> String {
+        let error = compile(source, &NetworkDefinition::simulator(), BlobProvider::new())
+            .expect_err("Expected the manifest to be invalid");
+        render_compile_error("manifest.rtm", source, &error)
+    }
+
+    #[test]
+    fn test_render_generator_error() {
+        let source = r#"CALL_METHOD
+    Address("component_sim1cqvgx33089ukm2pl97pv4max0x40ruvfy4lt60yvya744cvemygpmu")
+    "lock_fee"
+    Decimal("five");
+"#;
+        assert_eq!(
+            render(source),
+            r#"error: invalid decimal
+ --> manifest.rtm:4:13
+  |
+4 |     Decimal("five");
+  |             ^^^^^^ "five" is not a valid decimal
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_name_resolver_error() {
+        let source = r#"RETURN_TO_WORKTOP
+    Bucket("missing");
+"#;
+        assert_eq!(
+            render(source),
+            r#"error: undefined bucket
+ --> manifest.rtm:2:12
+  |
+2 |     Bucket("missing");
+  |            ^^^^^^^^^ no bucket named "missing" is defined
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_parser_error() {
+        let source = "TAKE_ALL_FROM_WORKTOP\n    Address(\"abc\")\n    Bucket(\"a\")\n";
+        assert_eq!(
+            render(source),
+            r#"error: unexpected end of manifest
+ --> manifest.rtm:3:16
+  |
+3 |     Bucket("a")
+  |                ^ expected more tokens
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_lexer_error() {
+        assert_eq!(
+            render("DROP_ALL_PROOFS;\nDROP_ALL_PROOFS?"),
+            r#"error: unexpected character '?'
+ --> manifest.rtm:2:16
+  |
+2 | DROP_ALL_PROOFS?
+  |                ^ unexpected character
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_preprocessor_error() {
+        assert_eq!(
+            render("DEFINE fee = Decimal(\"10\");\nCALL_METHOD ${account} \"lock_fee\" ${fee};"),
+            r#"error: undefined placeholder
+ --> manifest.rtm:2:13
+  |
+2 | CALL_METHOD ${account} "lock_fee" ${fee};
+  |             ^^^^^^^^^^ no constant or parameter named `account` is defined
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_multi_line_span() {
+        let source = "CALL_METHOD\n    Decimal(\n        \"1\"\n    )\n    \"refill\";";
+        assert_eq!(
+            render(source),
+            r#"error: mismatched value kinds
+ --> manifest.rtm:2:5
+  |
+2 |     Decimal(
+  |     ^^^^^^^^
+3 |         "1"
+  |         ^^^
+4 |     )
+  |     ^ expected Address or PackageAddress or ResourceAddress or ComponentAddress or NamedAddress, found Decimal
+"#
+        );
+    }
+}
@@ -1519,6 +1519,71 @@ CALL_METHOD
         );
     }
 
+    #[test]
+    fn test_macros_decompile_to_flat_manifest() {
+        let network = NetworkDefinition::simulator();
+        let sources = SourceProvider::new_with_sources(vec![(
+            "manifests/lib/fee.rtm".to_string(),
+            r#"
+DEFINE fee = Decimal("500");
+CALL_METHOD Address(${account}) "lock_fee" ${fee};
+"#
+            .to_string(),
+        )]);
+        let params = indexmap!(
+            "account".to_string() => apply_address_replacements("${account_address}"),
+            "xrd".to_string() => apply_address_replacements("${xrd_resource_address}"),
+        );
+        let manifest = r#"
+INCLUDE "lib/fee.rtm";
+DEFINE amount = Decimal("10");
+CALL_METHOD Address(${account}) "withdraw" Address("${xrd}") ${amount};
+TAKE_FROM_WORKTOP Address("${xrd}") ${amount} Bucket("xrd");
+CALL_METHOD Address(${account}) "try_deposit_or_abort" Bucket("xrd");
+"#;
+        let compiled = compile_with_macros(
+            manifest,
+            "manifests/main.rtm",
+            &network,
+            BlobProvider::new(),
+            &sources,
+            &params,
+        )
+        .unwrap();
+
+        let expected_canonical = apply_address_replacements(
+            r##"
+CALL_METHOD
+    Address("${account_address}")
+    "lock_fee"
+    Decimal("500")
+;
+CALL_METHOD
+    Address("${account_address}")
+    "withdraw"
+    Address("${xrd_resource_address}")
+    Decimal("10")
+;
+TAKE_FROM_WORKTOP
+    Address("${xrd_resource_address}")
+    Decimal("10")
+    Bucket("xrd")
+;
+CALL_METHOD
+    Address("${account_address}")
+    "try_deposit_or_abort"
+    Bucket("xrd")
+;
+"##,
+        );
+        let decompiled = decompile(&compiled.instructions, &network).unwrap();
+        assert_eq!(decompiled.trim(), expected_canonical.trim());
+        assert_eq!(
+            compiled,
+            compile(&expected_canonical, &network, BlobProvider::new()).unwrap()
+        );
+    }
+
     fn compile_and_decompile_with_inversion_test(
         name: &str,
         manifest: impl AsRef<str>,
@@ -10,6 +10,26 @@ pub struct Span {
     pub end: Position,
 }
 
+/// Creates a [`Span`] from `(full_index, line_number, line_char_index)` tuples.
+#[cfg(test)]
+#[macro_export]
+macro_rules! span {
+    (start = ($st:expr, $sl:expr, $sc:expr), end = ($et:expr, $el:expr, $ec:expr)) => {
+        $crate::manifest::lexer::Span {
+            start: $crate::manifest::lexer::Position {
+                full_index: $st,
+                line_number: $sl,
+                line_char_index: $sc,
+            },
+            end: $crate::manifest::lexer::Position {
+                full_index: $et,
+                line_number: $el,
+                line_char_index: $ec,
+            },
+        }
+    };
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Position {
     /// A 0-indexed cursor indicating the next unicode char from the start
@@ -40,6 +60,9 @@ pub enum TokenKind {
 
     Ident(String),
 
+    /// A `${name}` placeholder, substituted by the preprocessor
+    Placeholder(String),
+
     /* Punctuations */
     OpenParenthesis,
     CloseParenthesis,
@@ -47,6 +70,7 @@ pub enum TokenKind {
     GreaterThan,
     Comma,
     Semicolon,
+    Equals,
     FatArrow,
 }
 
@@ -57,12 +81,18 @@ pub struct Token {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum LexerError {
+pub enum LexerErrorKind {
     UnexpectedEof,
-    UnexpectedChar(char, Position),
-    InvalidInteger(String, Position),
-    InvalidUnicode(u32, Position),
-    UnknownIdentifier(String, Position),
+    UnexpectedChar(char),
+    InvalidInteger(String),
+    InvalidUnicode(u32),
+    UnknownIdentifier(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexerError {
+    pub error_kind: LexerErrorKind,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +101,8 @@ pub struct Lexer {
     text: Vec<char>,
     /// The current position in the text
     current: Position,
+    /// The position of the last advanced char
+    previous: Position,
 }
 
 pub fn tokenize(s: &str) -> Result<Vec<Token>, LexerError> {
@@ -88,13 +120,15 @@ pub fn tokenize(s: &str) -> Result<Vec<Token>, LexerError> {
 
 impl Lexer {
     pub fn new(text: &str) -> Self {
+        let start = Position {
+            full_index: 0,
+            line_number: 1,
+            line_char_index: 0,
+        };
         Self {
             text: text.chars().collect(),
-            current: Position {
-                full_index: 0,
-                line_number: 1,
-                line_char_index: 0,
-            },
+            current: start,
+            previous: start,
         }
     }
 
@@ -106,11 +140,18 @@ impl Lexer {
         self.text
             .get(self.current.full_index)
             .cloned()
-            .ok_or(LexerError::UnexpectedEof)
+            .ok_or(LexerError {
+                error_kind: LexerErrorKind::UnexpectedEof,
+                span: Span {
+                    start: self.current,
+                    end: self.current,
+                },
+            })
     }
 
     fn advance(&mut self) -> Result<char, LexerError> {
         let c = self.peek()?;
+        self.previous = self.current;
         self.current.full_index += 1;
         if c == '\n' {
             self.current.line_number += 1;
@@ -154,13 +195,14 @@ impl Lexer {
             '-' | '0'..='9' => self.tokenize_number(),
             '"' => self.tokenize_string(),
             'a'..='z' | 'A'..='Z' => self.tokenize_identifier(),
+            '$' => self.tokenize_placeholder(),
             '{' | '}' | '(' | ')' | '<' | '>' | ',' | ';' | '&' | '=' => {
                 self.tokenize_punctuation()
             }
-            _ => Err(LexerError::UnexpectedChar(
-                self.text[self.current.full_index],
-                self.current,
-            )),
+            _ => {
+                self.advance()?;
+                Err(self.unexpected_char())
+            }
         }
         .map(Option::from)
     }
@@ -194,41 +236,41 @@ impl Lexer {
             'i' => match self.advance()? {
                 '1' => match self.advance()? {
                     '2' => match self.advance()? {
-                        '8' => self.parse_int(&s, "i128", TokenKind::I128Literal),
+                        '8' => self.parse_int(start, &s, "i128", TokenKind::I128Literal),
                         _ => Err(self.unexpected_char()),
                     },
-                    '6' => self.parse_int(&s, "i16", TokenKind::I16Literal),
+                    '6' => self.parse_int(start, &s, "i16", TokenKind::I16Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '3' => match self.advance()? {
-                    '2' => self.parse_int(&s, "i32", TokenKind::I32Literal),
+                    '2' => self.parse_int(start, &s, "i32", TokenKind::I32Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '6' => match self.advance()? {
-                    '4' => self.parse_int(&s, "i64", TokenKind::I64Literal),
+                    '4' => self.parse_int(start, &s, "i64", TokenKind::I64Literal),
                     _ => Err(self.unexpected_char()),
                 },
-                '8' => self.parse_int(&s, "i8", TokenKind::I8Literal),
+                '8' => self.parse_int(start, &s, "i8", TokenKind::I8Literal),
                 _ => Err(self.unexpected_char()),
             },
             'u' => match self.advance()? {
                 '1' => match self.advance()? {
                     '2' => match self.advance()? {
-                        '8' => self.parse_int(&s, "u128", TokenKind::U128Literal),
+                        '8' => self.parse_int(start, &s, "u128", TokenKind::U128Literal),
                         _ => Err(self.unexpected_char()),
                     },
-                    '6' => self.parse_int(&s, "u16", TokenKind::U16Literal),
+                    '6' => self.parse_int(start, &s, "u16", TokenKind::U16Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '3' => match self.advance()? {
-                    '2' => self.parse_int(&s, "u32", TokenKind::U32Literal),
+                    '2' => self.parse_int(start, &s, "u32", TokenKind::U32Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '6' => match self.advance()? {
-                    '4' => self.parse_int(&s, "u64", TokenKind::U64Literal),
+                    '4' => self.parse_int(start, &s, "u64", TokenKind::U64Literal),
                     _ => Err(self.unexpected_char()),
                 },
-                '8' => self.parse_int(&s, "u8", TokenKind::U8Literal),
+                '8' => self.parse_int(start, &s, "u8", TokenKind::U8Literal),
                 _ => Err(self.unexpected_char()),
             },
             _ => Err(self.unexpected_char()),
@@ -238,13 +280,18 @@ impl Lexer {
 
     fn parse_int<T: FromStr>(
         &self,
+        start: Position,
         int: &str,
         ty: &str,
         map: fn(T) -> TokenKind,
     ) -> Result<TokenKind, LexerError> {
-        int.parse::<T>()
-            .map(map)
-            .map_err(|_| LexerError::InvalidInteger(format!("{}{}", int, ty), self.current))
+        int.parse::<T>().map(map).map_err(|_| LexerError {
+            error_kind: LexerErrorKind::InvalidInteger(format!("{}{}", int, ty)),
+            span: Span {
+                start,
+                end: self.current,
+            },
+        })
     }
 
     fn tokenize_string(&mut self) -> Result<Token, LexerError> {
@@ -253,6 +300,7 @@ impl Lexer {
 
         let mut s = String::new();
         while self.peek()? != '"' {
+            let char_start = self.current;
             let c = self.advance()?;
             if c == '\\' {
                 // See the JSON string specifications
@@ -279,10 +327,13 @@ impl Lexer {
                                 return Err(self.unexpected_char());
                             }
                         }
-                        s.push(
-                            char::from_u32(unicode)
-                                .ok_or(LexerError::InvalidUnicode(unicode, self.current))?,
-                        );
+                        s.push(char::from_u32(unicode).ok_or(LexerError {
+                            error_kind: LexerErrorKind::InvalidUnicode(unicode),
+                            span: Span {
+                                start: char_start,
+                                end: self.current,
+                            },
+                        })?);
                     }
                     _ => {
                         return Err(self.unexpected_char());
@@ -334,6 +385,25 @@ impl Lexer {
         Ok(self.new_token(kind, start, self.current))
     }
 
+    fn tokenize_placeholder(&mut self) -> Result<Token, LexerError> {
+        let start = self.current;
+        assert_eq!(self.advance()?, '$');
+        if self.advance()? != '{' {
+            return Err(self.unexpected_char());
+        }
+
+        let mut name = String::new();
+        loop {
+            match self.advance()? {
+                '}' if !name.is_empty() => break,
+                c if c.is_ascii_alphanumeric() || c == '_' => name.push(c),
+                _ => return Err(self.unexpected_char()),
+            }
+        }
+
+        Ok(self.new_token(TokenKind::Placeholder(name), start, self.current))
+    }
+
     fn tokenize_punctuation(&mut self) -> Result<Token, LexerError> {
         let start = self.current;
 
@@ -344,10 +414,14 @@ impl Lexer {
             '>' => TokenKind::GreaterThan,
             ',' => TokenKind::Comma,
             ';' => TokenKind::Semicolon,
-            '=' => match self.advance()? {
-                '>' => TokenKind::FatArrow,
-                _ => return Err(self.unexpected_char()),
-            },
+            '=' => {
+                if !self.is_eof() && self.peek()? == '>' {
+                    self.advance()?;
+                    TokenKind::FatArrow
+                } else {
+                    TokenKind::Equals
+                }
+            }
             _ => {
                 return Err(self.unexpected_char());
             }
@@ -363,8 +437,15 @@ impl Lexer {
         }
     }
 
+    /// Reports the last advanced char as unexpected.
     fn unexpected_char(&self) -> LexerError {
-        LexerError::UnexpectedChar(self.text[self.current.full_index - 1], self.current)
+        LexerError {
+            error_kind: LexerErrorKind::UnexpectedChar(self.text[self.previous.full_index]),
+            span: Span {
+                start: self.previous,
+                end: self.current,
+            },
+        }
     }
 }
 
@@ -434,7 +515,13 @@ mod tests {
             "1u8 2u32",
             vec![TokenKind::U8Literal(1), TokenKind::U32Literal(2)]
         );
-        lex_error!("123", LexerError::UnexpectedEof);
+        lex_error!(
+            "123",
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedEof,
+                span: span!(start = (3, 1, 3), end = (3, 1, 3)),
+            }
+        );
     }
 
     #[test]
@@ -457,7 +544,13 @@ mod tests {
                 TokenKind::StringLiteral("abc\r\n\"def🌍".into()),
             ]
         );
-        lex_error!("\"", LexerError::UnexpectedEof);
+        lex_error!(
+            "\"",
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedEof,
+                span: span!(start = (1, 1, 1), end = (1, 1, 1)),
+            }
+        );
     }
 
     #[test]
@@ -534,37 +627,55 @@ mod tests {
     fn test_unexpected_char() {
         lex_error!(
             "1u8 +2u32",
-            LexerError::UnexpectedChar(
-                '+',
-                Position {
-                    full_index: 4,
-                    line_number: 1,
-                    line_char_index: 4
-                }
-            )
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedChar('+'),
+                span: span!(start = (4, 1, 4), end = (5, 1, 5)),
+            }
         );
-
         lex_error!(
-            "x=7",
-            LexerError::UnexpectedChar(
-                '7',
-                Position {
-                    full_index: 3,
-                    line_number: 1,
-                    line_char_index: 3
-                }
-            )
+            "x 1y",
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedChar('y'),
+                span: span!(start = (3, 1, 3), end = (4, 1, 4)),
+            }
         );
         lex_error!(
             "1i128\n 1u64 \n 1i37",
-            LexerError::UnexpectedChar(
-                '7',
-                Position {
-                    full_index: 18,
-                    line_number: 3,
-                    line_char_index: 5
-                }
-            )
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedChar('7'),
+                span: span!(start = (17, 3, 4), end = (18, 3, 5)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_macros() {
+        lex_ok!(
+            r#"DEFINE fee = ${fee_amount}; x=>y"#,
+            vec![
+                TokenKind::Ident("DEFINE".to_string()),
+                TokenKind::Ident("fee".to_string()),
+                TokenKind::Equals,
+                TokenKind::Placeholder("fee_amount".to_string()),
+                TokenKind::Semicolon,
+                TokenKind::Ident("x".to_string()),
+                TokenKind::FatArrow,
+                TokenKind::Ident("y".to_string()),
+            ]
+        );
+        lex_error!(
+            "${}",
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedChar('}'),
+                span: span!(start = (2, 1, 2), end = (3, 1, 3)),
+            }
+        );
+        lex_error!(
+            "$fee",
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedChar('f'),
+                span: span!(start = (1, 1, 1), end = (2, 1, 2)),
+            }
         );
     }
 
@@ -584,14 +695,10 @@ mod tests {
         );
         lex_error!(
             r#""\uDCAC\u1234""#,
-            LexerError::InvalidUnicode(
-                1238580,
-                Position {
-                    full_index: 13,
-                    line_number: 1,
-                    line_char_index: 13
-                }
-            )
+            LexerError {
+                error_kind: LexerErrorKind::InvalidUnicode(1238580),
+                span: span!(start = (1, 1, 1), end = (13, 1, 13)),
+            }
         );
     }
 }
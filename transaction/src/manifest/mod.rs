@@ -2,15 +2,22 @@ pub mod ast;
 pub mod blob_provider;
 pub mod compiler;
 pub mod decompiler;
+pub mod diagnostics;
 #[cfg(feature = "std")]
 pub mod dumper;
 pub mod e2e;
 pub mod generator;
 pub mod lexer;
 pub mod manifest_enums;
+pub mod param_provider;
 pub mod parser;
+pub mod preprocessor;
+pub mod source_provider;
 
 pub use blob_provider::*;
-pub use compiler::{compile, CompileError};
+pub use compiler::{compile, compile_with_macros, CompileError};
 pub use decompiler::{decompile, DecompileError};
+pub use diagnostics::render_compile_error;
 pub use manifest_enums::*;
+pub use param_provider::*;
+pub use source_provider::*;
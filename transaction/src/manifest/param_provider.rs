@@ -0,0 +1,24 @@
+use sbor::prelude::*;
+
+//========
+// Traits
+//========
+
+/// Provides the values of the `${name}` placeholders of a manifest which are not `DEFINE`d
+/// constants. The values are requested as the placeholders are expanded, so only the parameters
+/// actually used by the manifest are looked up.
+pub trait IsParamProvider {
+    /// Returns the value of the parameter with the given name, or [`None`] if there is no such
+    /// parameter.
+    fn get_param(&self, name: &str) -> Option<String>;
+}
+
+//==========================
+// Default Param Provider
+//==========================
+
+impl IsParamProvider for IndexMap<String, String> {
+    fn get_param(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
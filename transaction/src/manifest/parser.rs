@@ -1,5 +1,5 @@
-use crate::manifest::ast::{Instruction, Value, ValueKind};
-use crate::manifest::lexer::{Token, TokenKind};
+use crate::manifest::ast::{Instruction, InstructionWithSpan, Value, ValueKind, ValueWithSpan};
+use crate::manifest::lexer::{Position, Span, Token, TokenKind};
 use crate::manifest::manifest_enums::KNOWN_ENUM_DISCRIMINATORS;
 use radix_engine_interface::data::manifest::MANIFEST_SBOR_V1_MAX_DEPTH;
 
@@ -8,16 +8,31 @@ use radix_engine_interface::data::manifest::MANIFEST_SBOR_V1_MAX_DEPTH;
 pub const PARSER_MAX_DEPTH: usize = MANIFEST_SBOR_V1_MAX_DEPTH - 4;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ParserError {
+pub enum ParserErrorKind {
     UnexpectedEof,
-    UnexpectedToken { expected: TokenType, actual: Token },
-    InvalidNumberOfValues { expected: usize, actual: usize },
-    InvalidNumberOfTypes { expected: usize, actual: usize },
+    UnexpectedToken {
+        expected: TokenType,
+        actual: TokenKind,
+    },
+    InvalidNumberOfValues {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidNumberOfTypes {
+        expected: usize,
+        actual: usize,
+    },
     InvalidHex(String),
     UnknownEnumDiscriminator(String),
     MaxDepthExceeded(usize),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserError {
+    pub error_kind: ParserErrorKind,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenType {
     Instruction,
@@ -61,6 +76,8 @@ pub enum InstructionIdent {
     DropNamedProofs,
     DropAllProofs,
     AllocateGlobalAddress,
+    YieldToChild,
+    YieldToParent,
 
     // ==============
     // Call direct vault method aliases
@@ -157,6 +174,9 @@ impl InstructionIdent {
             "DROP_ALL_PROOFS" => InstructionIdent::DropAllProofs,
             "ALLOCATE_GLOBAL_ADDRESS" => InstructionIdent::AllocateGlobalAddress,
 
+            "YIELD_TO_CHILD" => InstructionIdent::YieldToChild,
+            "YIELD_TO_PARENT" => InstructionIdent::YieldToParent,
+
             // ==============
             // Call direct vault method aliases
             // ==============
@@ -399,11 +419,15 @@ macro_rules! advance_match {
     ( $self:expr, $expected:expr ) => {{
         let token = $self.advance()?;
         if token.kind != $expected {
-            return Err(ParserError::UnexpectedToken {
-                expected: TokenType::Exact($expected),
-                actual: token,
+            return Err(ParserError {
+                error_kind: ParserErrorKind::UnexpectedToken {
+                    expected: TokenType::Exact($expected),
+                    actual: token.kind,
+                },
+                span: token.span,
             });
         }
+        token
     }};
 }
 
@@ -421,7 +445,10 @@ impl Parser {
     fn track_stack_depth_increase(&mut self) -> Result<(), ParserError> {
         self.stack_depth += 1;
         if self.stack_depth > self.max_depth {
-            return Err(ParserError::MaxDepthExceeded(self.max_depth));
+            return Err(ParserError {
+                error_kind: ParserErrorKind::MaxDepthExceeded(self.max_depth),
+                span: self.peek()?.span,
+            });
         }
         Ok(())
     }
@@ -437,10 +464,37 @@ impl Parser {
     }
 
     pub fn peek(&mut self) -> Result<Token, ParserError> {
-        self.tokens
-            .get(self.current)
-            .cloned()
-            .ok_or(ParserError::UnexpectedEof)
+        match self.tokens.get(self.current) {
+            Some(token) => Ok(token.clone()),
+            None => {
+                // Point right after the last token
+                let end = self.previous_end();
+                Err(ParserError {
+                    error_kind: ParserErrorKind::UnexpectedEof,
+                    span: Span { start: end, end },
+                })
+            }
+        }
+    }
+
+    /// The end of the last advanced token, or the start of the text if there is none.
+    fn previous_end(&self) -> Position {
+        match self.current.checked_sub(1).and_then(|i| self.tokens.get(i)) {
+            Some(token) => token.span.end,
+            None => Position {
+                full_index: 0,
+                line_number: 1,
+                line_char_index: 0,
+            },
+        }
+    }
+
+    /// The span from the given start to the end of the last advanced token.
+    fn span_from(&self, start: Position) -> Span {
+        Span {
+            start,
+            end: self.previous_end(),
+        }
     }
 
     pub fn advance(&mut self) -> Result<Token, ParserError> {
@@ -449,8 +503,8 @@ impl Parser {
         Ok(token)
     }
 
-    pub fn parse_manifest(&mut self) -> Result<Vec<Instruction>, ParserError> {
-        let mut instructions = Vec::<Instruction>::new();
+    pub fn parse_manifest(&mut self) -> Result<Vec<InstructionWithSpan>, ParserError> {
+        let mut instructions = Vec::<InstructionWithSpan>::new();
 
         while !self.is_eof() {
             instructions.push(self.parse_instruction()?);
@@ -459,7 +513,7 @@ impl Parser {
         Ok(instructions)
     }
 
-    fn parse_values_till_semicolon(&mut self) -> Result<Vec<Value>, ParserError> {
+    fn parse_values_till_semicolon(&mut self) -> Result<Vec<ValueWithSpan>, ParserError> {
         let mut values = Vec::new();
         while self.peek()?.kind != TokenKind::Semicolon {
             values.push(self.parse_value()?);
@@ -467,22 +521,19 @@ impl Parser {
         Ok(values)
     }
 
-    pub fn parse_instruction(&mut self) -> Result<Instruction, ParserError> {
+    pub fn parse_instruction(&mut self) -> Result<InstructionWithSpan, ParserError> {
         let token = self.advance()?;
         let instruction_ident = match &token.kind {
-            TokenKind::Ident(ident_str) => {
-                InstructionIdent::from_ident(ident_str).ok_or(ParserError::UnexpectedToken {
-                    expected: TokenType::Instruction,
-                    actual: token,
-                })?
-            }
-            _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::Instruction,
-                    actual: token,
-                });
-            }
-        };
+            TokenKind::Ident(ident_str) => InstructionIdent::from_ident(ident_str),
+            _ => None,
+        }
+        .ok_or_else(|| ParserError {
+            error_kind: ParserErrorKind::UnexpectedToken {
+                expected: TokenType::Instruction,
+                actual: token.kind.clone(),
+            },
+            span: token.span,
+        })?;
         let instruction = match instruction_ident {
             InstructionIdent::TakeFromWorktop => Instruction::TakeFromWorktop {
                 resource_address: self.parse_value()?,
@@ -613,6 +664,13 @@ impl Parser {
                 address_reservation: self.parse_value()?,
                 named_address: self.parse_value()?,
             },
+            InstructionIdent::YieldToChild => Instruction::YieldToChild {
+                child_index: self.parse_value()?,
+                args: self.parse_values_till_semicolon()?,
+            },
+            InstructionIdent::YieldToParent => Instruction::YieldToParent {
+                args: self.parse_values_till_semicolon()?,
+            },
 
             /* Call direct vault method aliases */
             InstructionIdent::RecallFromVault => Instruction::RecallFromVault {
@@ -733,10 +791,13 @@ impl Parser {
             },
         };
         advance_match!(self, TokenKind::Semicolon);
-        Ok(instruction)
+        Ok(InstructionWithSpan {
+            instruction,
+            span: self.span_from(token.span.start),
+        })
     }
 
-    pub fn parse_value(&mut self) -> Result<Value, ParserError> {
+    pub fn parse_value(&mut self) -> Result<ValueWithSpan, ParserError> {
         self.track_stack_depth_increase()?;
         let token = self.advance()?;
         let value = match &token.kind {
@@ -756,11 +817,13 @@ impl Parser {
             TokenKind::I128Literal(value) => Value::I128(*value),
             TokenKind::StringLiteral(value) => Value::String(value.clone()),
             TokenKind::Ident(ident_str) => {
-                let value_ident =
-                    SborValueIdent::from_ident(ident_str).ok_or(ParserError::UnexpectedToken {
+                let value_ident = SborValueIdent::from_ident(ident_str).ok_or(ParserError {
+                    error_kind: ParserErrorKind::UnexpectedToken {
                         expected: TokenType::Value,
-                        actual: token,
-                    })?;
+                        actual: token.kind.clone(),
+                    },
+                    span: token.span,
+                })?;
                 match value_ident {
                     SborValueIdent::Enum => self.parse_enum_content()?,
                     SborValueIdent::Array => self.parse_array_content()?,
@@ -805,14 +868,20 @@ impl Parser {
                 }
             }
             _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::Value,
-                    actual: token,
+                return Err(ParserError {
+                    error_kind: ParserErrorKind::UnexpectedToken {
+                        expected: TokenType::Value,
+                        actual: token.kind.clone(),
+                    },
+                    span: token.span,
                 });
             }
         };
         self.track_stack_depth_decrease()?;
-        Ok(value)
+        Ok(ValueWithSpan {
+            value,
+            span: self.span_from(token.span.start),
+        })
     }
 
     pub fn parse_enum_content(&mut self) -> Result<Value, ParserError> {
@@ -823,11 +892,17 @@ impl Parser {
             TokenKind::Ident(discriminator) => KNOWN_ENUM_DISCRIMINATORS
                 .get(discriminator.as_str())
                 .cloned()
-                .ok_or(ParserError::UnknownEnumDiscriminator(discriminator.clone()))?,
+                .ok_or(ParserError {
+                    error_kind: ParserErrorKind::UnknownEnumDiscriminator(discriminator.clone()),
+                    span: discriminator_token.span,
+                })?,
             _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::EnumDiscriminator,
-                    actual: discriminator_token,
+                return Err(ParserError {
+                    error_kind: ParserErrorKind::UnexpectedToken {
+                        expected: TokenType::EnumDiscriminator,
+                        actual: discriminator_token.kind,
+                    },
+                    span: discriminator_token.span,
                 })
             }
         };
@@ -876,7 +951,7 @@ impl Parser {
         &mut self,
         open: TokenKind,
         close: TokenKind,
-    ) -> Result<Vec<Value>, ParserError> {
+    ) -> Result<Vec<ValueWithSpan>, ParserError> {
         advance_match!(self, open);
         let mut values = Vec::new();
         while self.peek()?.kind != close {
@@ -889,13 +964,17 @@ impl Parser {
         Ok(values)
     }
 
-    fn parse_values_one(&mut self) -> Result<Value, ParserError> {
+    fn parse_values_one(&mut self) -> Result<ValueWithSpan, ParserError> {
+        let start = self.peek()?.span.start;
         let values =
             self.parse_values_any(TokenKind::OpenParenthesis, TokenKind::CloseParenthesis)?;
         if values.len() != 1 {
-            Err(ParserError::InvalidNumberOfValues {
-                actual: values.len(),
-                expected: 1,
+            Err(ParserError {
+                error_kind: ParserErrorKind::InvalidNumberOfValues {
+                    actual: values.len(),
+                    expected: 1,
+                },
+                span: self.span_from(start),
             })
         } else {
             Ok(values[0].clone())
@@ -903,7 +982,7 @@ impl Parser {
     }
 
     fn parse_generics(&mut self, n: usize) -> Result<Vec<ValueKind>, ParserError> {
-        advance_match!(self, TokenKind::LessThan);
+        let start = advance_match!(self, TokenKind::LessThan).span.start;
         let mut types = Vec::new();
         while self.peek()?.kind != TokenKind::GreaterThan {
             types.push(self.parse_type()?);
@@ -914,9 +993,12 @@ impl Parser {
         advance_match!(self, TokenKind::GreaterThan);
 
         if types.len() != n {
-            Err(ParserError::InvalidNumberOfTypes {
-                expected: n,
-                actual: types.len(),
+            Err(ParserError {
+                error_kind: ParserErrorKind::InvalidNumberOfTypes {
+                    expected: n,
+                    actual: types.len(),
+                },
+                span: self.span_from(start),
             })
         } else {
             Ok(types)
@@ -927,12 +1009,14 @@ impl Parser {
         let token = self.advance()?;
         let the_type = match &token.kind {
             TokenKind::Ident(ident_str) => {
-                let value_kind_ident = SborValueKindIdent::from_ident(&ident_str).ok_or(
-                    ParserError::UnexpectedToken {
-                        expected: TokenType::ValueKind,
-                        actual: token,
-                    },
-                )?;
+                let value_kind_ident =
+                    SborValueKindIdent::from_ident(&ident_str).ok_or(ParserError {
+                        error_kind: ParserErrorKind::UnexpectedToken {
+                            expected: TokenType::ValueKind,
+                            actual: token.kind.clone(),
+                        },
+                        span: token.span,
+                    })?;
                 match value_kind_ident {
                     // ==============
                     // Simple basic value kinds
@@ -980,9 +1064,12 @@ impl Parser {
                 }
             }
             _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::ValueKind,
-                    actual: token,
+                return Err(ParserError {
+                    error_kind: ParserErrorKind::UnexpectedToken {
+                        expected: TokenType::ValueKind,
+                        actual: token.kind.clone(),
+                    },
+                    span: token.span,
                 });
             }
         };
@@ -993,13 +1080,14 @@ impl Parser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::manifest::lexer::{tokenize, Position, Span};
+    use crate::manifest::lexer::tokenize;
+    use crate::span;
 
     #[macro_export]
     macro_rules! parse_instruction_ok {
         ( $s:expr, $expected:expr ) => {{
             let mut parser = Parser::new(tokenize($s).unwrap()), PARSER_MAX_DEPTH;
-            assert_eq!(parser.parse_instruction(), Ok($expected));
+            assert_eq!(parser.parse_instruction().map(|i| i.instruction), Ok($expected));
             assert!(parser.is_eof());
         }};
     }
@@ -1008,7 +1096,7 @@ mod tests {
     macro_rules! parse_value_ok {
         ( $s:expr, $expected:expr ) => {{
             let mut parser = Parser::new(tokenize($s).unwrap(), PARSER_MAX_DEPTH);
-            assert_eq!(parser.parse_value(), Ok($expected));
+            assert_eq!(parser.parse_value().map(|v| v.value), Ok($expected));
             assert!(parser.is_eof());
         }};
     }
@@ -1028,6 +1116,14 @@ mod tests {
         }};
     }
 
+    /// A value spanning the given char range of a single line manifest.
+    fn value(value: Value, start: usize, end: usize) -> ValueWithSpan {
+        ValueWithSpan {
+            value,
+            span: span!(start = (start, 1, start), end = (end, 1, end)),
+        }
+    }
+
     #[test]
     fn test_literals() {
         parse_value_ok!(r#"true"#, Value::Bool(true));
@@ -1049,7 +1145,13 @@ mod tests {
     fn test_enum() {
         parse_value_ok!(
             r#"Enum<0u8>("Hello", 123u8)"#,
-            Value::Enum(0, vec![Value::String("Hello".into()), Value::U8(123)],)
+            Value::Enum(
+                0,
+                vec![
+                    value(Value::String("Hello".into()), 10, 17),
+                    value(Value::U8(123), 19, 24)
+                ],
+            )
         );
         parse_value_ok!(r#"Enum<0u8>()"#, Value::Enum(0, Vec::new()));
         parse_value_ok!(
@@ -1059,7 +1161,13 @@ mod tests {
         // Check we allow trailing commas
         parse_value_ok!(
             r#"Enum<0u8>("Hello", 123u8,)"#,
-            Value::Enum(0, vec![Value::String("Hello".into()), Value::U8(123)],)
+            Value::Enum(
+                0,
+                vec![
+                    value(Value::String("Hello".into()), 10, 17),
+                    value(Value::U8(123), 19, 24)
+                ],
+            )
         );
     }
 
@@ -1067,13 +1175,19 @@ mod tests {
     fn test_array() {
         parse_value_ok!(
             r#"Array<U8>(1u8, 2u8)"#,
-            Value::Array(ValueKind::U8, vec![Value::U8(1), Value::U8(2)])
+            Value::Array(
+                ValueKind::U8,
+                vec![value(Value::U8(1), 10, 13), value(Value::U8(2), 15, 18)]
+            )
         );
         parse_value_ok!(r#"Array<U8>()"#, Value::Array(ValueKind::U8, vec![]));
         // Check we allow trailing commas
         parse_value_ok!(
             r#"Array<U8>(1u8, 2u8,)"#,
-            Value::Array(ValueKind::U8, vec![Value::U8(1), Value::U8(2)])
+            Value::Array(
+                ValueKind::U8,
+                vec![value(Value::U8(1), 10, 13), value(Value::U8(2), 15, 18)]
+            )
         );
     }
 
@@ -1082,16 +1196,19 @@ mod tests {
         parse_value_ok!(r#"Tuple()"#, Value::Tuple(vec![]));
         parse_value_ok!(
             r#"Tuple("Hello", 123u8)"#,
-            Value::Tuple(vec![Value::String("Hello".into()), Value::U8(123),])
+            Value::Tuple(vec![
+                value(Value::String("Hello".into()), 6, 13),
+                value(Value::U8(123), 15, 20),
+            ])
         );
         parse_value_ok!(
             r#"Tuple(1u8, 2u8)"#,
-            Value::Tuple(vec![Value::U8(1), Value::U8(2)])
+            Value::Tuple(vec![value(Value::U8(1), 6, 9), value(Value::U8(2), 11, 14)])
         );
         // Check we allow trailing commas
         parse_value_ok!(
             r#"Tuple(1u8, 2u8,)"#,
-            Value::Tuple(vec![Value::U8(1), Value::U8(2)])
+            Value::Tuple(vec![value(Value::U8(1), 6, 9), value(Value::U8(2), 11, 14)])
         );
     }
 
@@ -1102,7 +1219,10 @@ mod tests {
             Value::Map(
                 ValueKind::String,
                 ValueKind::U8,
-                vec![(Value::String("Hello".into()), Value::U8(123))]
+                vec![(
+                    value(Value::String("Hello".into()), 16, 23),
+                    value(Value::U8(123), 27, 32)
+                )]
             )
         );
         parse_value_ok!(
@@ -1111,8 +1231,14 @@ mod tests {
                 ValueKind::String,
                 ValueKind::U8,
                 vec![
-                    (Value::String("Hello".into()), Value::U8(123)),
-                    (Value::String("world!".into()), Value::U8(1)),
+                    (
+                        value(Value::String("Hello".into()), 16, 23),
+                        value(Value::U8(123), 27, 32)
+                    ),
+                    (
+                        value(Value::String("world!".into()), 34, 42),
+                        value(Value::U8(1), 46, 49)
+                    ),
                 ]
             )
         );
@@ -1123,42 +1249,79 @@ mod tests {
                 ValueKind::String,
                 ValueKind::U8,
                 vec![
-                    (Value::String("Hello".into()), Value::U8(123)),
-                    (Value::String("world!".into()), Value::U8(1)),
+                    (
+                        value(Value::String("Hello".into()), 16, 23),
+                        value(Value::U8(123), 27, 32)
+                    ),
+                    (
+                        value(Value::String("world!".into()), 34, 42),
+                        value(Value::U8(1), 46, 49)
+                    ),
                 ]
             )
         );
     }
 
+    #[test]
+    fn test_spans() {
+        let mut parser = Parser::new(
+            tokenize("CALL_METHOD\n    Address(\"abc\")\n    \"refill\";").unwrap(),
+            PARSER_MAX_DEPTH,
+        );
+        let instruction = parser.parse_instruction().unwrap();
+        assert_eq!(
+            instruction.span,
+            span!(start = (0, 1, 0), end = (44, 3, 13))
+        );
+        match instruction.instruction {
+            Instruction::CallMethod {
+                address,
+                method_name,
+                ..
+            } => {
+                assert_eq!(address.span, span!(start = (16, 2, 4), end = (30, 2, 18)));
+                match address.value {
+                    Value::Address(inner) => {
+                        assert_eq!(inner.span, span!(start = (24, 2, 12), end = (29, 2, 17)))
+                    }
+                    _ => panic!("Expected an address"),
+                }
+                assert_eq!(
+                    method_name.span,
+                    span!(start = (35, 3, 4), end = (43, 3, 12))
+                );
+            }
+            _ => panic!("Expected a CALL_METHOD"),
+        }
+    }
+
     #[test]
     fn test_failures() {
-        parse_value_error!(r#"Enum<0u8"#, ParserError::UnexpectedEof);
+        parse_value_error!(
+            r#"Enum<0u8"#,
+            ParserError {
+                error_kind: ParserErrorKind::UnexpectedEof,
+                span: span!(start = (8, 1, 8), end = (8, 1, 8)),
+            }
+        );
         parse_value_error!(
             r#"Enum<0u8)"#,
-            ParserError::UnexpectedToken {
-                expected: TokenType::Exact(TokenKind::GreaterThan),
-                actual: Token {
-                    kind: TokenKind::CloseParenthesis,
-                    span: Span {
-                        start: Position {
-                            full_index: 8,
-                            line_number: 1,
-                            line_char_index: 8,
-                        },
-                        end: Position {
-                            full_index: 9,
-                            line_number: 1,
-                            line_char_index: 9,
-                        }
-                    }
+            ParserError {
+                error_kind: ParserErrorKind::UnexpectedToken {
+                    expected: TokenType::Exact(TokenKind::GreaterThan),
+                    actual: TokenKind::CloseParenthesis,
                 },
+                span: span!(start = (8, 1, 8), end = (9, 1, 9)),
             }
         );
         parse_value_error!(
             r#"Address("abc", "def")"#,
-            ParserError::InvalidNumberOfValues {
-                actual: 2,
-                expected: 1
+            ParserError {
+                error_kind: ParserErrorKind::InvalidNumberOfValues {
+                    actual: 2,
+                    expected: 1
+                },
+                span: span!(start = (7, 1, 7), end = (21, 1, 21)),
             }
         );
     }
@@ -1176,9 +1339,13 @@ mod tests {
         }
 
         // Should actually be an error not a panic
+        let start = PARSER_MAX_DEPTH * 6;
         parse_value_error!(
             &value_string,
-            ParserError::MaxDepthExceeded(PARSER_MAX_DEPTH)
+            ParserError {
+                error_kind: ParserErrorKind::MaxDepthExceeded(PARSER_MAX_DEPTH),
+                span: span!(start = (start, 1, start), end = (start + 5, 1, start + 5)),
+            }
         );
     }
 
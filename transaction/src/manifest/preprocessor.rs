@@ -0,0 +1,538 @@
+use crate::internal_prelude::*;
+use crate::manifest::lexer::{tokenize, LexerErrorKind, Position, Span, Token, TokenKind};
+use crate::manifest::param_provider::IsParamProvider;
+use crate::manifest::source_provider::IsSourceProvider;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreprocessorErrorKind {
+    UnexpectedEof,
+    UnexpectedToken {
+        expected: TokenKind,
+        actual: TokenKind,
+    },
+    InvalidConstantName(TokenKind),
+    InvalidIncludePath(TokenKind),
+    EmptyConstant(String),
+    DuplicateConstant(String),
+    UndefinedPlaceholder(String),
+    IncludeNotFound(String),
+    RecursiveInclude(String),
+    LexerError(LexerErrorKind),
+    /// An error within an included source, reported at the `INCLUDE` statement
+    InIncludedSource {
+        path: String,
+        error: Box<PreprocessorErrorKind>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreprocessorError {
+    pub error_kind: PreprocessorErrorKind,
+    pub span: Span,
+}
+
+/// Expands the macros of a tokenized manifest, producing a flat manifest for the parser:
+/// * `DEFINE name = <tokens>;` defines a constant, which a `${name}` placeholder expands to.
+/// * `INCLUDE "path";` splices in the source at `path`, resolved relative to the including source.
+/// * Any other `${name}` placeholder is substituted by the string literal of the `name` parameter,
+///   which is requested from the `params` provider only then. A constant takes precedence over
+///   a parameter of the same name.
+///
+/// Within string literals, `${name}` is replaced by the text of the `name` parameter (or of a
+/// constant defined as a single string literal), and left as is if there is no such value.
+///
+/// `DEFINE` and `INCLUDE` are only recognized at the start of a statement. Substituted and
+/// included tokens take the span of the placeholder or the `INCLUDE` statement, so that all spans
+/// index into the source being preprocessed.
+pub fn preprocess<S: IsSourceProvider, P: IsParamProvider>(
+    tokens: Vec<Token>,
+    source_name: &str,
+    sources: &S,
+    params: &P,
+) -> Result<Vec<Token>, PreprocessorError> {
+    let mut preprocessor = Preprocessor {
+        sources,
+        params,
+        constants: index_map_new(),
+        include_stack: vec![source_name.to_string()],
+    };
+    let mut output = Vec::new();
+    preprocessor.expand(tokens, &mut output)?;
+    Ok(output)
+}
+
+/// Resolves an `INCLUDE` path relative to the directory of the including source. The `.` and `..`
+/// segments are normalized so that every source has a single name.
+pub fn resolve_include_path(from: &str, path: &str) -> String {
+    let (absolute, base) = if path.starts_with('/') {
+        (true, "")
+    } else {
+        (
+            from.starts_with('/'),
+            from.rfind('/')
+                .map(|index| &from[..index])
+                .unwrap_or_default(),
+        )
+    };
+
+    let mut segments = Vec::new();
+    for segment in base.split('/').chain(path.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if matches!(segments.last(), Some(last) if *last != "..") {
+                    segments.pop();
+                } else if !absolute {
+                    segments.push("..");
+                }
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    let joined = segments.join("/");
+    if absolute {
+        format!("/{}", joined)
+    } else {
+        joined
+    }
+}
+
+struct Preprocessor<'a, S: IsSourceProvider, P: IsParamProvider> {
+    sources: &'a S,
+    params: &'a P,
+    constants: IndexMap<String, Vec<Token>>,
+    /// The names of the sources being expanded, the innermost last
+    include_stack: Vec<String>,
+}
+
+impl<'a, S: IsSourceProvider, P: IsParamProvider> Preprocessor<'a, S, P> {
+    fn expand(
+        &mut self,
+        tokens: Vec<Token>,
+        output: &mut Vec<Token>,
+    ) -> Result<(), PreprocessorError> {
+        let mut cursor = Cursor::new(tokens);
+        let mut at_statement_start = true;
+        while let Some(token) = cursor.next() {
+            if at_statement_start {
+                if let TokenKind::Ident(ident) = &token.kind {
+                    match ident.as_str() {
+                        "DEFINE" => {
+                            self.define(&mut cursor)?;
+                            continue;
+                        }
+                        "INCLUDE" => {
+                            self.include(token.span, &mut cursor, output)?;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            at_statement_start = token.kind == TokenKind::Semicolon;
+            self.substitute(token, output)?;
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, cursor: &mut Cursor) -> Result<(), PreprocessorError> {
+        let name_token = cursor.advance()?;
+        let name = match name_token.kind {
+            TokenKind::Ident(name) => name,
+            actual => {
+                return Err(PreprocessorError {
+                    error_kind: PreprocessorErrorKind::InvalidConstantName(actual),
+                    span: name_token.span,
+                });
+            }
+        };
+        if self.constants.contains_key(&name) {
+            return Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::DuplicateConstant(name),
+                span: name_token.span,
+            });
+        }
+        cursor.advance_exact(TokenKind::Equals)?;
+
+        let mut body = Vec::new();
+        loop {
+            let token = cursor.advance()?;
+            if token.kind == TokenKind::Semicolon {
+                break;
+            }
+            self.substitute(token, &mut body)?;
+        }
+        if body.is_empty() {
+            return Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::EmptyConstant(name),
+                span: name_token.span,
+            });
+        }
+
+        self.constants.insert(name, body);
+        Ok(())
+    }
+
+    fn include(
+        &mut self,
+        start: Span,
+        cursor: &mut Cursor,
+        output: &mut Vec<Token>,
+    ) -> Result<(), PreprocessorError> {
+        let path_token = cursor.advance()?;
+        let path = match path_token.kind {
+            TokenKind::StringLiteral(path) => path,
+            actual => {
+                return Err(PreprocessorError {
+                    error_kind: PreprocessorErrorKind::InvalidIncludePath(actual),
+                    span: path_token.span,
+                });
+            }
+        };
+        let end = cursor.advance_exact(TokenKind::Semicolon)?;
+        let span = Span {
+            start: start.start,
+            end: end.span.end,
+        };
+
+        let name = resolve_include_path(self.include_stack.last().unwrap(), &path);
+        if self.include_stack.contains(&name) {
+            return Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::RecursiveInclude(name),
+                span,
+            });
+        }
+        let source = self.sources.get_source(&name).ok_or(PreprocessorError {
+            error_kind: PreprocessorErrorKind::IncludeNotFound(name.clone()),
+            span,
+        })?;
+
+        let in_included_source = |error_kind| PreprocessorError {
+            error_kind: PreprocessorErrorKind::InIncludedSource {
+                path: name.clone(),
+                error: Box::new(error_kind),
+            },
+            span,
+        };
+        let tokens = tokenize(&source)
+            .map_err(|err| in_included_source(PreprocessorErrorKind::LexerError(err.error_kind)))?;
+        let mut included = Vec::new();
+        self.include_stack.push(name.clone());
+        let result = self.expand(tokens, &mut included);
+        self.include_stack.pop();
+        result.map_err(|err| in_included_source(err.error_kind))?;
+
+        output.extend(included.into_iter().map(|token| Token {
+            kind: token.kind,
+            span,
+        }));
+        Ok(())
+    }
+
+    fn substitute(&self, token: Token, output: &mut Vec<Token>) -> Result<(), PreprocessorError> {
+        match token.kind {
+            TokenKind::Placeholder(name) => {
+                if let Some(body) = self.constants.get(&name) {
+                    output.extend(body.iter().map(|substituted| Token {
+                        kind: substituted.kind.clone(),
+                        span: token.span,
+                    }));
+                } else if let Some(value) = self.params.get_param(&name) {
+                    output.push(Token {
+                        kind: TokenKind::StringLiteral(value),
+                        span: token.span,
+                    });
+                } else {
+                    return Err(PreprocessorError {
+                        error_kind: PreprocessorErrorKind::UndefinedPlaceholder(name),
+                        span: token.span,
+                    });
+                }
+            }
+            TokenKind::StringLiteral(value) => output.push(Token {
+                kind: TokenKind::StringLiteral(self.interpolate(&value)),
+                span: token.span,
+            }),
+            _ => output.push(token),
+        }
+        Ok(())
+    }
+
+    fn interpolate(&self, value: &str) -> String {
+        let mut interpolated = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            let end = match rest[start..].find('}') {
+                Some(length) => start + length + 1,
+                None => break,
+            };
+            let placeholder = &rest[start..end];
+            let name = placeholder[2..placeholder.len() - 1].trim();
+            interpolated.push_str(&rest[..start]);
+            match self.resolve_text(name) {
+                Some(text) => interpolated.push_str(&text),
+                None => interpolated.push_str(placeholder),
+            }
+            rest = &rest[end..];
+        }
+        interpolated.push_str(rest);
+        interpolated
+    }
+
+    fn resolve_text(&self, name: &str) -> Option<String> {
+        match self.constants.get(name).map(|body| body.as_slice()) {
+            Some(
+                [Token {
+                    kind: TokenKind::StringLiteral(value),
+                    ..
+                }],
+            ) => Some(value.clone()),
+            Some(_) => None,
+            None => self.params.get_param(name),
+        }
+    }
+}
+
+struct Cursor {
+    tokens: vec::IntoIter<Token>,
+    /// The end of the last advanced token
+    previous_end: Position,
+}
+
+impl Cursor {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens: tokens.into_iter(),
+            previous_end: Position {
+                full_index: 0,
+                line_number: 1,
+                line_char_index: 0,
+            },
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.next()?;
+        self.previous_end = token.span.end;
+        Some(token)
+    }
+
+    fn advance(&mut self) -> Result<Token, PreprocessorError> {
+        self.next().ok_or(PreprocessorError {
+            error_kind: PreprocessorErrorKind::UnexpectedEof,
+            span: Span {
+                start: self.previous_end,
+                end: self.previous_end,
+            },
+        })
+    }
+
+    fn advance_exact(&mut self, expected: TokenKind) -> Result<Token, PreprocessorError> {
+        let token = self.advance()?;
+        if token.kind != expected {
+            return Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::UnexpectedToken {
+                    expected,
+                    actual: token.kind,
+                },
+                span: token.span,
+            });
+        }
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::source_provider::SourceProvider;
+    use crate::span;
+
+    fn preprocess_kinds(
+        s: &str,
+        sources: &SourceProvider,
+        params: &IndexMap<String, String>,
+    ) -> Result<Vec<TokenKind>, PreprocessorError> {
+        preprocess(tokenize(s).unwrap(), "manifests/main.rtm", sources, params)
+            .map(|tokens| tokens.into_iter().map(|token| token.kind).collect())
+    }
+
+    fn expected_kinds(s: &str) -> Vec<TokenKind> {
+        tokenize(s)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_constants_and_params() {
+        let params = indexmap!("account".to_string() => "account_sim1abc".to_string());
+        assert_eq!(
+            preprocess_kinds(
+                r#"
+                DEFINE fee = Decimal("10");
+                DEFINE owner = "${account}";
+                CALL_METHOD Address(${account}) "lock_fee" ${fee};
+                CALL_METHOD Address("${owner}") "deposit" "${unknown}";
+                "#,
+                &SourceProvider::new(),
+                &params,
+            ),
+            Ok(expected_kinds(
+                r#"
+                CALL_METHOD Address("account_sim1abc") "lock_fee" Decimal("10");
+                CALL_METHOD Address("account_sim1abc") "deposit" "${unknown}";
+                "#
+            ))
+        );
+    }
+
+    /// Records the requested parameters, providing each as its upper-cased name.
+    struct RecordingParamProvider(RefCell<Vec<String>>);
+
+    impl IsParamProvider for RecordingParamProvider {
+        fn get_param(&self, name: &str) -> Option<String> {
+            self.0.borrow_mut().push(name.to_string());
+            Some(name.to_uppercase())
+        }
+    }
+
+    #[test]
+    fn test_params_are_requested_lazily() {
+        let params = RecordingParamProvider(RefCell::new(Vec::new()));
+        assert_eq!(
+            preprocess(
+                tokenize(
+                    r#"
+                    DEFINE owner = "${account}";
+                    CALL_METHOD Address("${owner}") "deposit" ${bucket};
+                    "#
+                )
+                .unwrap(),
+                "manifests/main.rtm",
+                &SourceProvider::new(),
+                &params,
+            )
+            .map(|tokens| tokens.into_iter().map(|token| token.kind).collect()),
+            Ok(expected_kinds(
+                r#"CALL_METHOD Address("ACCOUNT") "deposit" "BUCKET";"#
+            ))
+        );
+        assert_eq!(
+            params.0.into_inner(),
+            vec!["account".to_string(), "bucket".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_include_is_resolved_relative_to_the_including_source() {
+        let sources = SourceProvider::new_with_sources(vec![
+            (
+                "manifests/lib/fee.rtm".to_string(),
+                r#"INCLUDE "../account.rtm"; CALL_METHOD ${account} "lock_fee" Decimal("10");"#
+                    .to_string(),
+            ),
+            (
+                "manifests/account.rtm".to_string(),
+                r#"DEFINE account = Address("account_sim1abc");"#.to_string(),
+            ),
+        ]);
+        assert_eq!(
+            preprocess_kinds(
+                r#"INCLUDE "lib/fee.rtm"; DROP_ALL_PROOFS;"#,
+                &sources,
+                &index_map_new()
+            ),
+            Ok(expected_kinds(
+                r#"CALL_METHOD Address("account_sim1abc") "lock_fee" Decimal("10"); DROP_ALL_PROOFS;"#
+            ))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let sources = SourceProvider::new_with_sources(vec![
+            (
+                "manifests/a.rtm".to_string(),
+                r#"INCLUDE "b.rtm";"#.to_string(),
+            ),
+            (
+                "manifests/b.rtm".to_string(),
+                r#"INCLUDE "a.rtm";"#.to_string(),
+            ),
+        ]);
+        assert_eq!(
+            preprocess_kinds("DROP_PROOF ${proof};", &sources, &index_map_new()),
+            Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::UndefinedPlaceholder("proof".to_string()),
+                span: span!(start = (11, 1, 11), end = (19, 1, 19)),
+            })
+        );
+        assert_eq!(
+            preprocess_kinds(
+                "DEFINE x = 1u8;\nDEFINE x = 2u8;",
+                &sources,
+                &index_map_new()
+            ),
+            Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::DuplicateConstant("x".to_string()),
+                span: span!(start = (23, 2, 7), end = (24, 2, 8)),
+            })
+        );
+        assert_eq!(
+            preprocess_kinds("DEFINE x 1u8;", &sources, &index_map_new()),
+            Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::UnexpectedToken {
+                    expected: TokenKind::Equals,
+                    actual: TokenKind::U8Literal(1),
+                },
+                span: span!(start = (9, 1, 9), end = (12, 1, 12)),
+            })
+        );
+        assert_eq!(
+            preprocess_kinds(r#"INCLUDE "a.rtm";"#, &sources, &index_map_new()),
+            Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::InIncludedSource {
+                    path: "manifests/a.rtm".to_string(),
+                    error: Box::new(PreprocessorErrorKind::InIncludedSource {
+                        path: "manifests/b.rtm".to_string(),
+                        error: Box::new(PreprocessorErrorKind::RecursiveInclude(
+                            "manifests/a.rtm".to_string()
+                        )),
+                    }),
+                },
+                span: span!(start = (0, 1, 0), end = (16, 1, 16)),
+            })
+        );
+        assert_eq!(
+            preprocess_kinds(r#"INCLUDE "c.rtm";"#, &sources, &index_map_new()),
+            Err(PreprocessorError {
+                error_kind: PreprocessorErrorKind::IncludeNotFound("manifests/c.rtm".to_string()),
+                span: span!(start = (0, 1, 0), end = (16, 1, 16)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_resolve_include_path() {
+        assert_eq!(resolve_include_path("main.rtm", "lib.rtm"), "lib.rtm");
+        assert_eq!(
+            resolve_include_path("a/b/main.rtm", "./c/lib.rtm"),
+            "a/b/c/lib.rtm"
+        );
+        assert_eq!(
+            resolve_include_path("a/b/main.rtm", "../../../lib.rtm"),
+            "../lib.rtm"
+        );
+        assert_eq!(
+            resolve_include_path("/a/main.rtm", "../../lib.rtm"),
+            "/lib.rtm"
+        );
+        assert_eq!(
+            resolve_include_path("a/main.rtm", "/lib/x.rtm"),
+            "/lib/x.rtm"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use sbor::prelude::*;
+
+//========
+// Traits
+//========
+
+/// Provides the manifest sources which may be `INCLUDE`d by a manifest.
+pub trait IsSourceProvider {
+    /// Returns the source with the given name, which is a path already resolved relative to the
+    /// including source.
+    fn get_source(&self, name: &str) -> Option<String>;
+}
+
+//=========================
+// Default Source Provider
+//=========================
+
+#[derive(Default, Debug, Clone)]
+pub struct SourceProvider(BTreeMap<String, String>);
+
+impl SourceProvider {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn new_with_sources(sources: Vec<(String, String)>) -> Self {
+        Self(sources.into_iter().collect())
+    }
+
+    pub fn add_source(&mut self, name: String, source: String) {
+        self.0.insert(name, source);
+    }
+}
+
+impl IsSourceProvider for SourceProvider {
+    fn get_source(&self, name: &str) -> Option<String> {
+        self.0.get(name).cloned()
+    }
+}
+
+//=============================
+// File System Source Provider
+//=============================
+
+#[cfg(feature = "std")]
+#[derive(Default, Debug, Clone)]
+pub struct FileSystemSourceProvider;
+
+#[cfg(feature = "std")]
+impl FileSystemSourceProvider {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[cfg(feature = "std")]
+impl IsSourceProvider for FileSystemSourceProvider {
+    fn get_source(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(name).ok()
+    }
+}
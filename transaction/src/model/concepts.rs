@@ -13,6 +13,17 @@ pub trait HasIntentHash {
     fn intent_hash(&self) -> IntentHash;
 }
 
+define_raw_transaction_payload!(RawSubintent);
+define_wrapped_hash!(
+    /// A hash of a subintent, which is signed separately from the intent which embeds it.
+    /// Like an intent hash, each subintent hash can only be committed once.
+    SubintentHash
+);
+
+pub trait HasSubintentHash {
+    fn subintent_hash(&self) -> SubintentHash;
+}
+
 define_raw_transaction_payload!(RawSignedIntent);
 define_wrapped_hash!(SignedIntentHash);
 
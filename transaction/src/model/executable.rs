@@ -1,4 +1,5 @@
 use crate::internal_prelude::*;
+use radix_engine_interface::blueprints::account::{AccountLockFeeInput, ACCOUNT_LOCK_FEE_IDENT};
 
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor, Default)]
 pub struct AuthZoneParams {
@@ -77,6 +78,118 @@ impl Default for TransactionCostingParameters {
     }
 }
 
+/// Executable form of a child intent, which the transaction processor runs when the root
+/// manifest yields to it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExecutableSubintent<'a> {
+    pub(crate) encoded_instructions: &'a [u8],
+    pub(crate) references: IndexSet<Reference>,
+    pub(crate) blobs: &'a IndexMap<Hash, Vec<u8>>,
+    pub(crate) intent_hash: TransactionIntentHash,
+    pub(crate) epoch_range: EpochRange,
+    pub(crate) auth_zone_params: AuthZoneParams,
+}
+
+impl<'a> ExecutableSubintent<'a> {
+    pub fn new(
+        encoded_instructions: &'a [u8],
+        references: &IndexSet<Reference>,
+        blobs: &'a IndexMap<Hash, Vec<u8>>,
+        intent_hash: TransactionIntentHash,
+        epoch_range: EpochRange,
+        auth_zone_params: AuthZoneParams,
+    ) -> Self {
+        let mut references = references.clone();
+
+        for proof in &auth_zone_params.initial_proofs {
+            references.insert(proof.resource_address().clone().into());
+        }
+        for resource in &auth_zone_params.virtual_resources {
+            references.insert(resource.clone().into());
+        }
+
+        Self {
+            encoded_instructions,
+            references,
+            blobs,
+            intent_hash,
+            epoch_range,
+            auth_zone_params,
+        }
+    }
+
+    pub fn intent_hash(&self) -> &TransactionIntentHash {
+        &self.intent_hash
+    }
+
+    pub fn epoch_range(&self) -> &EpochRange {
+        &self.epoch_range
+    }
+
+    pub fn blobs(&self) -> &IndexMap<Hash, Vec<u8>> {
+        &self.blobs
+    }
+
+    pub fn encoded_instructions(&self) -> &[u8] {
+        &self.encoded_instructions
+    }
+
+    pub fn references(&self) -> &IndexSet<Reference> {
+        &self.references
+    }
+
+    pub fn auth_zone_params(&self) -> &AuthZoneParams {
+        &self.auth_zone_params
+    }
+}
+
+/// The sponsor of the fees of a transaction, whose fee is locked before the manifest runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutableFeeSponsor {
+    pub(crate) account: ComponentAddress,
+    pub(crate) max_fee: Decimal,
+    pub(crate) encoded_instructions: Vec<u8>,
+    pub(crate) auth_zone_params: AuthZoneParams,
+}
+
+impl ExecutableFeeSponsor {
+    pub fn new(
+        account: ComponentAddress,
+        max_fee: Decimal,
+        auth_zone_params: AuthZoneParams,
+    ) -> Self {
+        let instructions = vec![InstructionV1::CallMethod {
+            address: account.into(),
+            method_name: ACCOUNT_LOCK_FEE_IDENT.to_string(),
+            args: to_manifest_value_and_unwrap!(&AccountLockFeeInput { amount: max_fee }),
+        }];
+
+        Self {
+            account,
+            max_fee,
+            encoded_instructions: manifest_encode(&instructions).unwrap(),
+            auth_zone_params,
+        }
+    }
+
+    pub fn account(&self) -> &ComponentAddress {
+        &self.account
+    }
+
+    pub fn max_fee(&self) -> Decimal {
+        self.max_fee
+    }
+
+    /// The instructions locking the fee, which run with the sponsor's auth zone only
+    pub fn encoded_instructions(&self) -> &[u8] {
+        &self.encoded_instructions
+    }
+
+    pub fn auth_zone_params(&self) -> &AuthZoneParams {
+        &self.auth_zone_params
+    }
+}
+
 /// Executable form of transaction, post stateless validation.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Executable<'a> {
@@ -84,6 +197,8 @@ pub struct Executable<'a> {
     pub(crate) references: IndexSet<Reference>,
     pub(crate) blobs: &'a IndexMap<Hash, Vec<u8>>,
     pub(crate) context: ExecutionContext,
+    pub(crate) subintents: Vec<ExecutableSubintent<'a>>,
+    pub(crate) fee_sponsor: Option<ExecutableFeeSponsor>,
 }
 
 impl<'a> Executable<'a> {
@@ -116,7 +231,52 @@ impl<'a> Executable<'a> {
             references,
             blobs,
             context,
+            subintents: vec![],
+            fee_sponsor: None,
+        }
+    }
+
+    /// Adds the child intents, in the order the root manifest refers to them.
+    ///
+    /// Their references are added to the references of the transaction, as the transaction
+    /// processor passes them on to each child.
+    pub fn with_subintents(mut self, subintents: Vec<ExecutableSubintent<'a>>) -> Self {
+        for subintent in &subintents {
+            self.references.extend(subintent.references.iter().cloned());
+        }
+        self.subintents = subintents;
+        self
+    }
+
+    /// Sets the sponsor locking the fees of the transaction before the manifest runs.
+    pub fn with_fee_sponsor(mut self, fee_sponsor: ExecutableFeeSponsor) -> Self {
+        self.references.insert(fee_sponsor.account.clone().into());
+        for proof in &fee_sponsor.auth_zone_params.initial_proofs {
+            self.references
+                .insert(proof.resource_address().clone().into());
+        }
+        self.fee_sponsor = Some(fee_sponsor);
+        self
+    }
+
+    /// Adds proofs and virtual resources to the root auth zone, e.g. to impersonate signers or
+    /// badge holders during a preview.
+    pub fn with_additional_auth_zone_params(mut self, auth_zone_params: AuthZoneParams) -> Self {
+        for proof in &auth_zone_params.initial_proofs {
+            self.references
+                .insert(proof.resource_address().clone().into());
         }
+        for resource in &auth_zone_params.virtual_resources {
+            self.references.insert(resource.clone().into());
+        }
+        let params = &mut self.context.auth_zone_params;
+        params
+            .initial_proofs
+            .extend(auth_zone_params.initial_proofs);
+        params
+            .virtual_resources
+            .extend(auth_zone_params.virtual_resources);
+        self
     }
 
     pub fn intent_hash(&self) -> &TransactionIntentHash {
@@ -171,4 +331,12 @@ impl<'a> Executable<'a> {
     pub fn num_of_signature_validations(&self) -> usize {
         self.context.num_of_signature_validations
     }
+
+    pub fn subintents(&self) -> &[ExecutableSubintent<'a>] {
+        &self.subintents
+    }
+
+    pub fn fee_sponsor(&self) -> Option<&ExecutableFeeSponsor> {
+        self.fee_sponsor.as_ref()
+    }
 }
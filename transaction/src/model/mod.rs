@@ -3,6 +3,7 @@ mod executable;
 mod hash;
 mod preparation;
 mod v1;
+mod v2;
 mod versioned;
 
 pub use concepts::*;
@@ -10,6 +11,7 @@ pub use executable::*;
 pub use hash::*;
 pub use preparation::*;
 pub use v1::*;
+pub use v2::*;
 pub use versioned::*;
 
 #[cfg(test)]
@@ -230,7 +232,9 @@ Enum<3u8>(
                         tip_percentage: 4,
                         free_credit_in_xrd: dec!(0)
                     }
-                }
+                },
+                subintents: vec![],
+                fee_sponsor: None,
             }
         );
 
@@ -5,6 +5,7 @@ use sbor::*;
 pub enum ValueType {
     Blob,
     Attachment,
+    ChildIntent,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -54,6 +55,12 @@ impl<'a> TransactionDecoder<'a> {
         Ok(())
     }
 
+    /// For a struct in an array, whose value kind is part of the array header
+    pub fn read_struct_body_header(&mut self, length: usize) -> Result<(), PrepareError> {
+        self.0.read_and_check_size(length)?;
+        Ok(())
+    }
+
     pub fn read_enum_header(&mut self) -> Result<(u8, usize), PrepareError> {
         self.0.read_and_check_value_kind(ValueKind::Enum)?;
         let discriminator = self.0.read_discriminator()?;
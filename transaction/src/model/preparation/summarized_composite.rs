@@ -24,6 +24,17 @@ impl ConcatenatedDigest {
         T::prepare_into_concatenated_digest(decoder, digest)
     }
 
+    /// Creates a digest which matches `prepare_from_transaction_child_struct`, for a child which is
+    /// an element of an SBOR array, and so is encoded without its value kind
+    pub fn prepare_from_transaction_child_struct_body<T: TuplePreparable>(
+        decoder: &mut TransactionDecoder,
+        discriminator: TransactionDiscriminator,
+    ) -> Result<(T, Summary), PrepareError> {
+        let digest = HashAccumulator::new()
+            .update(&[TRANSACTION_HASHABLE_PAYLOAD_PREFIX, discriminator as u8]);
+        T::prepare_body_into_concatenated_digest(decoder, digest)
+    }
+
     pub fn prepare_from_sbor_array<T: ArrayPreparable, const MAX_LENGTH: usize>(
         decoder: &mut TransactionDecoder,
         accumulator: HashAccumulator,
@@ -115,6 +126,12 @@ pub trait TuplePreparable: Sized {
         decoder: &mut TransactionDecoder,
         accumulator: HashAccumulator,
     ) -> Result<(Self, Summary), PrepareError>;
+
+    /// As `prepare_into_concatenated_digest`, but where the value kind has already been read
+    fn prepare_body_into_concatenated_digest(
+        decoder: &mut TransactionDecoder,
+        accumulator: HashAccumulator,
+    ) -> Result<(Self, Summary), PrepareError>;
 }
 
 pub trait EnumPreparable: Sized {
@@ -159,6 +176,38 @@ macro_rules! prepare_tuple {
                 };
                 Ok((($($var_name,)*), summary))
             }
+
+            #[allow(unused_mut)]
+            fn prepare_body_into_concatenated_digest(decoder: &mut TransactionDecoder, mut accumulator: HashAccumulator) -> Result<(Self, Summary), PrepareError> {
+                decoder.track_stack_depth_increase()?;
+                decoder.read_struct_body_header($n)?;
+
+                // NOTE: We purposefully don't take the effective_length from the size of the SBOR type header
+                // This is because the SBOR value header isn't included in the hash...
+                // And we want to protect against non-determinism in the effective_length due to a different serializations of the SBOR value header.
+                // Whilst we believe the SBOR value header to currently be unique (eg we don't allow trailing bytes in the encoded size) - I'd rather not rely on that.
+                // So just assume it's 2 here (1 byte for value kind + 1 byte for length if length sufficiently short)
+                let mut effective_length = 2usize;
+                let mut total_bytes_hashed = 0usize;
+
+                $(
+                    let $var_name = <$type_name>::prepare_as_full_body_child(decoder)?;
+                    effective_length = effective_length.checked_add($var_name.get_summary().effective_length).ok_or(PrepareError::LengthOverflow)?;
+                    total_bytes_hashed = total_bytes_hashed.checked_add($var_name.get_summary().total_bytes_hashed).ok_or(PrepareError::LengthOverflow)?;
+                    accumulator = accumulator.update($var_name.get_summary().hash);
+                )*
+
+                decoder.track_stack_depth_decrease()?;
+
+                total_bytes_hashed = total_bytes_hashed.checked_add(accumulator.input_length()).ok_or(PrepareError::LengthOverflow)?;
+
+                let summary = Summary {
+                    effective_length,
+                    total_bytes_hashed,
+                    hash: accumulator.finalize(),
+                };
+                Ok((($($var_name,)*), summary))
+            }
         }
 
         impl<$($type_name: TransactionFullChildPreparable,)*> EnumPreparable for ($($type_name,)*) {
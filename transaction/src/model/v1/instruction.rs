@@ -698,6 +698,22 @@ pub enum InstructionV1 {
         package_address: PackageAddress,
         blueprint_name: String,
     },
+
+    //==============
+    // Subintents
+    //==============
+    /// Runs the child intent at the given index, passing it the args. The child's worktop
+    /// receives the buckets of the args, and the value it yields back is handled like the
+    /// return value of a call.
+    #[sbor(discriminator(INSTRUCTION_YIELD_TO_CHILD_DISCRIMINATOR))]
+    YieldToChild {
+        child_index: u32,
+        args: ManifestValue,
+    },
+
+    /// Ends a subintent, yielding the args back to its parent.
+    #[sbor(discriminator(INSTRUCTION_YIELD_TO_PARENT_DISCRIMINATOR))]
+    YieldToParent { args: ManifestValue },
 }
 
 //===============================================================
@@ -766,3 +782,9 @@ pub const INSTRUCTION_CALL_DIRECT_VAULT_METHOD_DISCRIMINATOR: u8 = 0x45;
 pub const INSTRUCTION_DROP_NAMED_PROOFS_DISCRIMINATOR: u8 = 0x52;
 pub const INSTRUCTION_DROP_ALL_PROOFS_DISCRIMINATOR: u8 = 0x50;
 pub const INSTRUCTION_ALLOCATE_GLOBAL_ADDRESS_DISCRIMINATOR: u8 = 0x51;
+
+//==============
+// Subintents
+//==============
+pub const INSTRUCTION_YIELD_TO_CHILD_DISCRIMINATOR: u8 = 0x60;
+pub const INSTRUCTION_YIELD_TO_PARENT_DISCRIMINATOR: u8 = 0x61;
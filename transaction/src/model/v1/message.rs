@@ -167,5 +167,5 @@ impl AesWrapped128BitKey {
 
 pub type PreparedMessageV1 = SummarizedRawFullBody<MessageV1>;
 
-// TODO: Add tests with a canonical implementation of message encryption/decryption,
+// See `crate::encryption` for the canonical implementation of message encryption/decryption,
 // and corresponding test vectors for other implementers.
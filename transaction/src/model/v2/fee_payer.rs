@@ -0,0 +1,45 @@
+use super::*;
+use crate::internal_prelude::*;
+
+//=================================================================================
+// NOTE:
+// See versioned.rs for tests and a demonstration for the calculation of hashes etc
+//=================================================================================
+
+/// Who pays the fees of a transaction.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub enum FeePayerV2 {
+    /// The fees are locked by the instructions of the manifest.
+    None,
+    /// The fees are locked from the account of a sponsor, before the manifest runs.
+    Sponsor(FeeSponsorV2),
+}
+
+impl Default for FeePayerV2 {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// A sponsor-signed commitment to pay up to `max_fee` XRD from `account` for an intent.
+///
+/// The `signature` is over [`FeeSponsorV2::signable_hash`], so the sponsor commits to the
+/// intent being sponsored as well as the account and the maximum fee.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct FeeSponsorV2 {
+    pub account: ComponentAddress,
+    pub max_fee: Decimal,
+    pub signature: SignatureWithPublicKeyV1,
+}
+
+impl FeeSponsorV2 {
+    pub fn signable_hash(
+        intent_hash: &IntentHash,
+        account: &ComponentAddress,
+        max_fee: &Decimal,
+    ) -> Hash {
+        hash(manifest_encode(&(intent_hash.0, account, max_fee)).unwrap())
+    }
+}
+
+pub type PreparedFeePayerV2 = SummarizedRawFullBody<FeePayerV2>;
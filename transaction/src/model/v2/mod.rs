@@ -0,0 +1,17 @@
+use super::*;
+
+mod child_intents;
+mod fee_payer;
+mod intent;
+mod notarized_transaction;
+mod signed_intent;
+mod subintent;
+mod validated_notarized_transaction;
+
+pub use child_intents::*;
+pub use fee_payer::*;
+pub use intent::*;
+pub use notarized_transaction::*;
+pub use signed_intent::*;
+pub use subintent::*;
+pub use validated_notarized_transaction::*;
@@ -0,0 +1,86 @@
+use super::*;
+use crate::internal_prelude::*;
+
+//=================================================================================
+// NOTE:
+// See versioned.rs for tests and a demonstration for the calculation of hashes etc
+//=================================================================================
+
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct SignedIntentV2 {
+    pub intent: IntentV2,
+    pub intent_signatures: IntentSignaturesV1,
+    pub child_intent_signatures: ChildIntentSignaturesV2,
+    pub fee_payer: FeePayerV2,
+}
+
+impl TransactionPayload for SignedIntentV2 {
+    type Versioned = SborFixedEnumVariant<{ TransactionDiscriminator::V2SignedIntent as u8 }, Self>;
+    type Prepared = PreparedSignedIntentV2;
+    type Raw = RawSignedIntent;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PreparedSignedIntentV2 {
+    pub intent: PreparedIntentV2,
+    pub intent_signatures: PreparedIntentSignaturesV1,
+    pub child_intent_signatures: PreparedChildIntentSignaturesV2,
+    pub fee_payer: PreparedFeePayerV2,
+    pub summary: Summary,
+}
+
+impl HasSummary for PreparedSignedIntentV2 {
+    fn get_summary(&self) -> &Summary {
+        &self.summary
+    }
+}
+
+impl TransactionFullChildPreparable for PreparedSignedIntentV2 {
+    fn prepare_as_full_body_child(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as an child, it's SBOR encoded as a struct
+        let ((intent, intent_signatures, child_intent_signatures, fee_payer), summary) =
+            ConcatenatedDigest::prepare_from_transaction_child_struct(
+                decoder,
+                TransactionDiscriminator::V2SignedIntent,
+            )?;
+        Ok(Self {
+            intent,
+            intent_signatures,
+            child_intent_signatures,
+            fee_payer,
+            summary,
+        })
+    }
+}
+
+impl TransactionPayloadPreparable for PreparedSignedIntentV2 {
+    type Raw = RawSignedIntent;
+
+    fn prepare_for_payload(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as full payload, it's SBOR encoded as an enum
+        let ((intent, intent_signatures, child_intent_signatures, fee_payer), summary) =
+            ConcatenatedDigest::prepare_from_transaction_payload_enum(
+                decoder,
+                TransactionDiscriminator::V2SignedIntent,
+            )?;
+        Ok(Self {
+            intent,
+            intent_signatures,
+            child_intent_signatures,
+            fee_payer,
+            summary,
+        })
+    }
+}
+
+impl HasIntentHash for PreparedSignedIntentV2 {
+    fn intent_hash(&self) -> IntentHash {
+        self.intent.intent_hash()
+    }
+}
+
+impl HasSignedIntentHash for PreparedSignedIntentV2 {
+    fn signed_intent_hash(&self) -> SignedIntentHash {
+        SignedIntentHash::from_hash(self.summary.hash)
+    }
+}
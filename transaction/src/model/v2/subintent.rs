@@ -0,0 +1,124 @@
+use super::*;
+use crate::internal_prelude::*;
+
+//=================================================================================
+// NOTE:
+// See versioned.rs for tests and a demonstration for the calculation of hashes etc
+//=================================================================================
+
+/// The header of a subintent. Unlike a transaction header, it has no notary or tip, as these
+/// are the responsibility of the root intent.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct SubintentHeaderV2 {
+    pub network_id: u8,
+    pub start_epoch_inclusive: Epoch,
+    pub end_epoch_exclusive: Epoch,
+    pub nonce: u32,
+}
+
+pub type PreparedSubintentHeaderV2 = SummarizedRawFullBody<SubintentHeaderV2>;
+
+/// An intent which is signed separately from the transaction, and is run when the root
+/// intent (or another subintent) yields to it. Its manifest must end with a `YIELD_TO_PARENT`.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct SubintentV2 {
+    pub header: SubintentHeaderV2,
+    pub instructions: InstructionsV1,
+    pub blobs: BlobsV1,
+    pub message: MessageV1,
+}
+
+impl TransactionPayload for SubintentV2 {
+    type Versioned = SborFixedEnumVariant<{ TransactionDiscriminator::V2Subintent as u8 }, Self>;
+    type Prepared = PreparedSubintentV2;
+    type Raw = RawSubintent;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PreparedSubintentV2 {
+    pub header: PreparedSubintentHeaderV2,
+    pub instructions: PreparedInstructionsV1,
+    pub blobs: PreparedBlobsV1,
+    pub message: PreparedMessageV1,
+    pub summary: Summary,
+}
+
+impl HasSummary for PreparedSubintentV2 {
+    fn get_summary(&self) -> &Summary {
+        &self.summary
+    }
+}
+
+impl TransactionFullChildPreparable for PreparedSubintentV2 {
+    fn prepare_as_full_body_child(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as an child, it's SBOR encoded as a struct
+        let ((header, instructions, blobs, message), summary) =
+            ConcatenatedDigest::prepare_from_transaction_child_struct(
+                decoder,
+                TransactionDiscriminator::V2Subintent,
+            )?;
+        Ok(Self {
+            header,
+            instructions,
+            blobs,
+            message,
+            summary,
+        })
+    }
+}
+
+impl TransactionChildBodyPreparable for PreparedSubintentV2 {
+    fn prepare_as_inner_body_child(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded in the child intents array, it's SBOR encoded as a struct without its value kind
+        let ((header, instructions, blobs, message), summary) =
+            ConcatenatedDigest::prepare_from_transaction_child_struct_body(
+                decoder,
+                TransactionDiscriminator::V2Subintent,
+            )?;
+        Ok(Self {
+            header,
+            instructions,
+            blobs,
+            message,
+            summary,
+        })
+    }
+
+    fn value_kind() -> ManifestValueKind {
+        ManifestValueKind::Tuple
+    }
+}
+
+impl TransactionPayloadPreparable for PreparedSubintentV2 {
+    type Raw = RawSubintent;
+
+    fn prepare_for_payload(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as full payload, it's SBOR encoded as an enum
+        let ((header, instructions, blobs, message), summary) =
+            ConcatenatedDigest::prepare_from_transaction_payload_enum(
+                decoder,
+                TransactionDiscriminator::V2Subintent,
+            )?;
+        Ok(Self {
+            header,
+            instructions,
+            blobs,
+            message,
+            summary,
+        })
+    }
+}
+
+impl HasSubintentHash for PreparedSubintentV2 {
+    fn subintent_hash(&self) -> SubintentHash {
+        SubintentHash::from_hash(self.summary.hash)
+    }
+}
+
+/// A subintent together with the signatures of its signers, as passed between the parties
+/// of a transaction before it's composed into the root intent.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct SignedSubintentV2 {
+    pub subintent: SubintentV2,
+    pub subintent_signatures: IntentSignaturesV1,
+}
@@ -19,6 +19,10 @@ pub enum TransactionDiscriminator {
     V1Preview = V1_PREVIEW_TRANSACTION,
     V1Ledger = V1_LEDGER_TRANSACTION,
     V1Flash = V1_FLASH_TRANSACTION,
+    V2Subintent = V2_SUBINTENT,
+    V2Intent = V2_INTENT,
+    V2SignedIntent = V2_SIGNED_INTENT,
+    V2Notarized = V2_NOTARIZED_TRANSACTION,
 }
 
 const V1_INTENT: u8 = 1;
@@ -29,6 +33,10 @@ const V1_ROUND_UPDATE_TRANSACTION: u8 = 5;
 const V1_PREVIEW_TRANSACTION: u8 = 6;
 const V1_LEDGER_TRANSACTION: u8 = 7;
 const V1_FLASH_TRANSACTION: u8 = 8;
+const V2_SUBINTENT: u8 = 9;
+const V2_INTENT: u8 = 10;
+const V2_SIGNED_INTENT: u8 = 11;
+const V2_NOTARIZED_TRANSACTION: u8 = 12;
 
 // TODO - change this to use #[flatten] when REP-84 is out
 /// An enum of a variety of different transaction payload types
@@ -60,6 +68,33 @@ pub enum VersionedTransactionPayload {
         pre_allocated_addresses: Vec<PreAllocatedAddress>,
         hash_for_execution: Hash,
     },
+    #[sbor(discriminator(V2_SUBINTENT))]
+    SubintentV2 {
+        header: SubintentHeaderV2,
+        instructions: InstructionsV1,
+        blobs: BlobsV1,
+        message: MessageV1,
+    },
+    #[sbor(discriminator(V2_INTENT))]
+    IntentV2 {
+        header: TransactionHeaderV1,
+        instructions: InstructionsV1,
+        blobs: BlobsV1,
+        message: MessageV1,
+        child_intents: ChildIntentsV2,
+    },
+    #[sbor(discriminator(V2_SIGNED_INTENT))]
+    SignedIntentV2 {
+        intent: IntentV2,
+        intent_signatures: IntentSignaturesV1,
+        child_intent_signatures: ChildIntentSignaturesV2,
+        fee_payer: FeePayerV2,
+    },
+    #[sbor(discriminator(V2_NOTARIZED_TRANSACTION))]
+    NotarizedTransactionV2 {
+        signed_intent: SignedIntentV2,
+        notary_signature: NotarySignatureV1,
+    },
 }
 
 #[cfg(test)]
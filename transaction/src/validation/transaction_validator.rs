@@ -132,11 +132,153 @@ impl TransactionValidator<PreparedNotarizedTransactionV1> for NotarizedTransacti
     }
 }
 
+/// The kind of intent a manifest belongs to, which determines the yield instructions it may use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ManifestIntentKind {
+    /// A V1 intent, which has no child intents
+    V1,
+    /// A V2 root intent, which must yield to each of its child intents exactly once
+    Root { child_count: usize },
+    /// A child intent, which must end by yielding to its parent
+    Subintent,
+}
+
 impl NotarizedTransactionValidator {
     pub fn new(config: ValidationConfig) -> Self {
         Self { config }
     }
 
+    /// The V2 counterpart of `validate_from_payload_bytes`, for a transaction with child intents
+    pub fn validate_v2_from_payload_bytes(
+        &self,
+        payload_bytes: &[u8],
+    ) -> Result<ValidatedNotarizedTransactionV2, TransactionValidationError> {
+        if payload_bytes.len() > self.config.max_notarized_payload_size {
+            return Err(TransactionValidationError::TransactionTooLarge);
+        }
+
+        let prepared = PreparedNotarizedTransactionV2::prepare_from_payload(payload_bytes)?;
+        self.validate_v2(prepared)
+    }
+
+    pub fn validate_v2(
+        &self,
+        transaction: PreparedNotarizedTransactionV2,
+    ) -> Result<ValidatedNotarizedTransactionV2, TransactionValidationError> {
+        self.validate_intent_v2(&transaction.signed_intent.intent)?;
+
+        let child_intents = transaction
+            .signed_intent
+            .intent
+            .child_intents
+            .children
+            .len();
+        let signatures = transaction
+            .signed_intent
+            .child_intent_signatures
+            .inner
+            .by_child
+            .len();
+        if child_intents != signatures {
+            return Err(SubintentValidationError::MismatchingChildIntentSignatures {
+                child_intents,
+                signatures,
+            }
+            .into());
+        }
+
+        let encoded_instructions =
+            manifest_encode(&transaction.signed_intent.intent.instructions.inner.0)?;
+
+        let fee_sponsor = self.validate_fee_payer_v2(
+            &transaction.signed_intent.intent.intent_hash(),
+            &transaction.signed_intent.fee_payer.inner,
+        )?;
+
+        let (signer_keys, child_signer_keys) = self
+            .validate_signatures_v2(&transaction)
+            .map_err(TransactionValidationError::SignatureValidationError)?;
+
+        let mut subintents = Vec::new();
+        for (child, signer_keys) in transaction
+            .signed_intent
+            .intent
+            .child_intents
+            .children
+            .iter()
+            .zip(child_signer_keys)
+        {
+            subintents.push(ValidatedSubintentV2 {
+                encoded_instructions: manifest_encode(&child.instructions.inner.0)?,
+                signer_keys,
+            });
+        }
+
+        let num_of_signature_validations = transaction
+            .signed_intent
+            .intent_signatures
+            .inner
+            .signatures
+            .len()
+            + 1
+            + fee_sponsor.iter().count()
+            + transaction
+                .signed_intent
+                .child_intent_signatures
+                .inner
+                .by_child
+                .iter()
+                .map(|signatures| signatures.signatures.len())
+                .sum::<usize>();
+
+        Ok(ValidatedNotarizedTransactionV2 {
+            prepared: transaction,
+            encoded_instructions,
+            signer_keys,
+            subintents,
+            fee_sponsor,
+            num_of_signature_validations,
+        })
+    }
+
+    /// Validates the sponsor of the fees, if any, returning its signer key
+    pub fn validate_fee_payer_v2(
+        &self,
+        intent_hash: &IntentHash,
+        fee_payer: &FeePayerV2,
+    ) -> Result<Option<ValidatedFeeSponsorV2>, TransactionValidationError> {
+        let sponsor = match fee_payer {
+            FeePayerV2::None => return Ok(None),
+            FeePayerV2::Sponsor(sponsor) => sponsor,
+        };
+
+        let is_account = matches!(
+            sponsor.account.as_node_id().entity_type(),
+            Some(EntityType::GlobalAccount)
+                | Some(EntityType::GlobalVirtualSecp256k1Account)
+                | Some(EntityType::GlobalVirtualEd25519Account)
+        );
+        if !is_account {
+            return Err(FeeSponsorValidationError::NotAnAccount(sponsor.account).into());
+        }
+        if !sponsor.max_fee.is_positive() {
+            return Err(FeeSponsorValidationError::InvalidMaxFee(sponsor.max_fee).into());
+        }
+
+        let hash = FeeSponsorV2::signable_hash(intent_hash, &sponsor.account, &sponsor.max_fee);
+        let signer_key = recover(&hash, &sponsor.signature)
+            .filter(|public_key| verify(&hash, public_key, &sponsor.signature.signature()))
+            .ok_or(TransactionValidationError::SignatureValidationError(
+                SignatureValidationError::InvalidFeeSponsorSignature,
+            ))?;
+
+        Ok(Some(ValidatedFeeSponsorV2 {
+            account: sponsor.account,
+            max_fee: sponsor.max_fee,
+            signer_key,
+        }))
+    }
+
     pub fn validate_preview_intent_v1(
         &self,
         preview_intent: PreviewIntentV1,
@@ -169,12 +311,67 @@ impl NotarizedTransactionValidator {
         return Ok(());
     }
 
+    pub fn validate_intent_v2(
+        &self,
+        intent: &PreparedIntentV2,
+    ) -> Result<(), TransactionValidationError> {
+        self.validate_header_v1(&intent.header.inner)
+            .map_err(TransactionValidationError::HeaderValidationError)?;
+
+        self.validate_message_v1(&intent.message.inner)?;
+
+        let children = &intent.child_intents.children;
+        Self::validate_instructions(
+            &intent.instructions.inner.0,
+            ManifestIntentKind::Root {
+                child_count: children.len(),
+            },
+        )?;
+
+        let mut subintent_hashes = index_set_new();
+        for child in children {
+            self.validate_subintent_v2(child)?;
+
+            let subintent_hash = child.subintent_hash();
+            if !subintent_hashes.insert(subintent_hash) {
+                return Err(SubintentValidationError::DuplicateChildIntent(subintent_hash).into());
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub fn validate_subintent_v2(
+        &self,
+        subintent: &PreparedSubintentV2,
+    ) -> Result<(), TransactionValidationError> {
+        self.validate_subintent_header_v2(&subintent.header.inner)
+            .map_err(TransactionValidationError::HeaderValidationError)?;
+
+        self.validate_message_v1(&subintent.message.inner)?;
+
+        Self::validate_instructions(
+            &subintent.instructions.inner.0,
+            ManifestIntentKind::Subintent,
+        )?;
+
+        return Ok(());
+    }
+
     pub fn validate_instructions_v1(
         instructions: &[InstructionV1],
+    ) -> Result<(), TransactionValidationError> {
+        Self::validate_instructions(instructions, ManifestIntentKind::V1)
+    }
+
+    pub fn validate_instructions(
+        instructions: &[InstructionV1],
+        intent_kind: ManifestIntentKind,
     ) -> Result<(), TransactionValidationError> {
         // semantic analysis
         let mut id_validator = ManifestValidator::new();
-        for inst in instructions {
+        let mut yielded_children = index_set_new();
+        for (index, inst) in instructions.iter().enumerate() {
             match inst {
                 InstructionV1::TakeAllFromWorktop { .. } => {
                     let _ = id_validator.new_bucket();
@@ -274,6 +471,61 @@ impl NotarizedTransactionValidator {
                     let _ = id_validator.new_address_reservation();
                     id_validator.new_named_address();
                 }
+                InstructionV1::YieldToChild { child_index, args } => {
+                    let child_count = match intent_kind {
+                        ManifestIntentKind::V1 => 0,
+                        ManifestIntentKind::Root { child_count } => child_count,
+                        ManifestIntentKind::Subintent => {
+                            return Err(SubintentValidationError::YieldToChildInsideSubintent.into())
+                        }
+                    };
+                    if *child_index as usize >= child_count {
+                        return Err(
+                            SubintentValidationError::ChildIntentNotFound(*child_index).into()
+                        );
+                    }
+                    if !yielded_children.insert(*child_index) {
+                        return Err(SubintentValidationError::ChildIntentYieldedToMoreThanOnce(
+                            *child_index,
+                        )
+                        .into());
+                    }
+                    Self::validate_call_args(&args, &mut id_validator)
+                        .map_err(TransactionValidationError::CallDataValidationError)?;
+                }
+                InstructionV1::YieldToParent { args } => {
+                    if intent_kind != ManifestIntentKind::Subintent {
+                        return Err(SubintentValidationError::YieldToParentOutsideSubintent.into());
+                    }
+                    if index + 1 != instructions.len() {
+                        return Err(
+                            SubintentValidationError::YieldToParentNotLastInstruction.into()
+                        );
+                    }
+                    Self::validate_call_args(&args, &mut id_validator)
+                        .map_err(TransactionValidationError::CallDataValidationError)?;
+                }
+            }
+        }
+
+        match intent_kind {
+            ManifestIntentKind::V1 => {}
+            ManifestIntentKind::Root { child_count } => {
+                for child_index in 0..child_count as u32 {
+                    if !yielded_children.contains(&child_index) {
+                        return Err(
+                            SubintentValidationError::ChildIntentNotYieldedTo(child_index).into(),
+                        );
+                    }
+                }
+            }
+            ManifestIntentKind::Subintent => {
+                if !matches!(
+                    instructions.last(),
+                    Some(InstructionV1::YieldToParent { .. })
+                ) {
+                    return Err(SubintentValidationError::MissingYieldToParent.into());
+                }
             }
         }
 
@@ -311,6 +563,30 @@ impl NotarizedTransactionValidator {
         Ok(())
     }
 
+    pub fn validate_subintent_header_v2(
+        &self,
+        header: &SubintentHeaderV2,
+    ) -> Result<(), HeaderValidationError> {
+        // network
+        if header.network_id != self.config.network_id {
+            return Err(HeaderValidationError::InvalidNetwork);
+        }
+
+        // epoch
+        if header.end_epoch_exclusive <= header.start_epoch_inclusive {
+            return Err(HeaderValidationError::InvalidEpochRange);
+        }
+        let max_end_epoch = header
+            .start_epoch_inclusive
+            .after(self.config.max_epoch_range)
+            .ok_or(HeaderValidationError::InvalidEpochRange)?;
+        if header.end_epoch_exclusive > max_end_epoch {
+            return Err(HeaderValidationError::InvalidEpochRange);
+        }
+
+        Ok(())
+    }
+
     pub fn validate_signatures_v1(
         &self,
         transaction: &PreparedNotarizedTransactionV1,
@@ -362,6 +638,77 @@ impl NotarizedTransactionValidator {
         Ok(signers.into_iter().collect())
     }
 
+    /// Returns the signers of the root intent, and the signers of each child intent
+    pub fn validate_signatures_v2(
+        &self,
+        transaction: &PreparedNotarizedTransactionV2,
+    ) -> Result<(Vec<PublicKey>, Vec<Vec<PublicKey>>), SignatureValidationError> {
+        let signed_intent = &transaction.signed_intent;
+
+        // verify intent signature
+        let mut signers = Self::recover_intent_signers(
+            &transaction.intent_hash().into_hash(),
+            &signed_intent.intent_signatures.inner.signatures,
+            SignatureValidationError::InvalidIntentSignature,
+        )?;
+
+        let header = &signed_intent.intent.header.inner;
+
+        if header.notary_is_signatory {
+            signers.insert(header.notary_public_key);
+        }
+
+        // verify child intent signatures
+        let children = &signed_intent.intent.child_intents.children;
+        let child_signatures = &signed_intent.child_intent_signatures.inner.by_child;
+        let mut child_signers = Vec::with_capacity(children.len());
+        for (child, signatures) in children.iter().zip(child_signatures) {
+            let signers = Self::recover_intent_signers(
+                &child.subintent_hash().into_hash(),
+                &signatures.signatures,
+                SignatureValidationError::InvalidChildIntentSignature,
+            )?;
+            child_signers.push(signers.into_iter().collect());
+        }
+
+        // verify notary signature
+        let signed_intent_hash = transaction.signed_intent_hash().into_hash();
+        if !verify(
+            &signed_intent_hash,
+            &header.notary_public_key,
+            &transaction.notary_signature.inner.0,
+        ) {
+            return Err(SignatureValidationError::InvalidNotarySignature);
+        }
+
+        Ok((signers.into_iter().collect(), child_signers))
+    }
+
+    fn recover_intent_signers(
+        hash: &Hash,
+        signatures: &[IntentSignatureV1],
+        invalid_signature_error: SignatureValidationError,
+    ) -> Result<IndexSet<PublicKey>, SignatureValidationError> {
+        if signatures.len() > MAX_NUMBER_OF_INTENT_SIGNATURES {
+            return Err(SignatureValidationError::TooManySignatures);
+        }
+
+        let mut signers = index_set_new();
+        for signature in signatures {
+            let public_key = recover(hash, &signature.0).ok_or(invalid_signature_error.clone())?;
+
+            if !verify(hash, &public_key, &signature.0.signature()) {
+                return Err(invalid_signature_error);
+            }
+
+            if !signers.insert(public_key) {
+                return Err(SignatureValidationError::DuplicateSigner);
+            }
+        }
+
+        Ok(signers)
+    }
+
     pub fn validate_call_args(
         value: &ManifestValue,
         id_validator: &mut ManifestValidator,
@@ -884,4 +1231,192 @@ mod tests {
             ))
         );
     }
+
+    fn create_subintent(manifest: TransactionManifestV1, signer: u64) -> SignedSubintentV2 {
+        SubintentBuilder::new()
+            .header(SubintentHeaderV2 {
+                network_id: NetworkDefinition::simulator().id,
+                start_epoch_inclusive: Epoch::of(0),
+                end_epoch_exclusive: Epoch::of(40),
+                nonce: 5,
+            })
+            .manifest(manifest)
+            .sign(&Secp256k1PrivateKey::from_u64(signer).unwrap())
+            .build()
+    }
+
+    fn create_transaction_v2(
+        manifest: TransactionManifestV1,
+        children: Vec<SignedSubintentV2>,
+    ) -> NotarizedTransactionV2 {
+        let sk_notary = Secp256k1PrivateKey::from_u64(66).unwrap();
+
+        let mut builder = TransactionV2Builder::new()
+            .header(TransactionHeaderV1 {
+                network_id: NetworkDefinition::simulator().id,
+                start_epoch_inclusive: Epoch::of(0),
+                end_epoch_exclusive: Epoch::of(40),
+                nonce: 123,
+                notary_public_key: sk_notary.public_key().into(),
+                notary_is_signatory: false,
+                tip_percentage: 5,
+            })
+            .manifest(manifest);
+        for child in children {
+            builder = builder.add_child(child);
+        }
+
+        builder
+            .sign(&Secp256k1PrivateKey::from_u64(55).unwrap())
+            .notarize(&sk_notary)
+            .build()
+    }
+
+    fn validate_v2(
+        transaction: &NotarizedTransactionV2,
+    ) -> Result<ValidatedNotarizedTransactionV2, TransactionValidationError> {
+        NotarizedTransactionValidator::new(ValidationConfig::simulator())
+            .validate_v2_from_payload_bytes(&transaction.to_payload_bytes().unwrap())
+    }
+
+    #[test]
+    fn test_valid_transaction_with_subintent() {
+        let child = create_subintent(
+            ManifestBuilder::new()
+                .take_all_from_worktop(XRD, "bucket")
+                .then(|builder| {
+                    let bucket = builder.bucket("bucket");
+                    builder.yield_to_parent(manifest_args!(bucket))
+                })
+                .build(),
+            77,
+        );
+        let transaction = create_transaction_v2(
+            ManifestBuilder::new()
+                .take_all_from_worktop(XRD, "bucket")
+                .then(|builder| {
+                    let bucket = builder.bucket("bucket");
+                    builder.yield_to_child(0, manifest_args!(bucket))
+                })
+                .build(),
+            vec![child],
+        );
+
+        let validated = validate_v2(&transaction).expect("Should be valid");
+        assert_eq!(validated.subintents.len(), 1);
+        assert_eq!(
+            validated.subintents[0].signer_keys,
+            vec![Secp256k1PrivateKey::from_u64(77)
+                .unwrap()
+                .public_key()
+                .into()]
+        );
+        assert_eq!(validated.num_of_signature_validations, 3);
+    }
+
+    #[test]
+    fn test_invalid_yields() {
+        let yielding_child =
+            || create_subintent(ManifestBuilder::new().yield_to_parent(()).build(), 77);
+
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new().build(),
+                vec![yielding_child()]
+            ))
+            .map(|_| ()),
+            Err(SubintentValidationError::ChildIntentNotYieldedTo(0).into())
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new().yield_to_child(1, ()).build(),
+                vec![yielding_child()]
+            ))
+            .map(|_| ()),
+            Err(SubintentValidationError::ChildIntentNotFound(1).into())
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new()
+                    .yield_to_child(0, ())
+                    .yield_to_child(0, ())
+                    .build(),
+                vec![yielding_child()]
+            ))
+            .map(|_| ()),
+            Err(SubintentValidationError::ChildIntentYieldedToMoreThanOnce(0).into())
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new().yield_to_parent(()).build(),
+                vec![]
+            ))
+            .map(|_| ()),
+            Err(SubintentValidationError::YieldToParentOutsideSubintent.into())
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new().yield_to_child(0, ()).build(),
+                vec![create_subintent(ManifestBuilder::new().build(), 77)]
+            ))
+            .map(|_| ()),
+            Err(SubintentValidationError::MissingYieldToParent.into())
+        );
+    }
+
+    #[test]
+    fn test_invalid_fee_sponsor() {
+        let sponsor_key = Secp256k1PrivateKey::from_u64(77).unwrap();
+        let sponsor_account =
+            ComponentAddress::virtual_account_from_public_key(&sponsor_key.public_key());
+        let sponsored_transaction = |account: ComponentAddress, max_fee: Decimal| {
+            let sk_notary = Secp256k1PrivateKey::from_u64(66).unwrap();
+            TransactionV2Builder::new()
+                .header(TransactionHeaderV1 {
+                    network_id: NetworkDefinition::simulator().id,
+                    start_epoch_inclusive: Epoch::of(0),
+                    end_epoch_exclusive: Epoch::of(40),
+                    nonce: 123,
+                    notary_public_key: sk_notary.public_key().into(),
+                    notary_is_signatory: true,
+                    tip_percentage: 5,
+                })
+                .manifest(ManifestBuilder::new().build())
+                .sponsor(account, max_fee, &sponsor_key)
+                .notarize(&sk_notary)
+                .build()
+        };
+
+        let validated = validate_v2(&sponsored_transaction(sponsor_account, dec!(10)))
+            .expect("Should be valid");
+        assert_eq!(
+            validated.fee_sponsor,
+            Some(ValidatedFeeSponsorV2 {
+                account: sponsor_account,
+                max_fee: dec!(10),
+                signer_key: sponsor_key.public_key().into(),
+            })
+        );
+        assert_eq!(validated.num_of_signature_validations, 2);
+
+        assert_eq!(
+            validate_v2(&sponsored_transaction(FAUCET, dec!(10))).map(|_| ()),
+            Err(FeeSponsorValidationError::NotAnAccount(FAUCET).into())
+        );
+        assert_eq!(
+            validate_v2(&sponsored_transaction(sponsor_account, dec!(0))).map(|_| ()),
+            Err(FeeSponsorValidationError::InvalidMaxFee(dec!(0)).into())
+        );
+
+        let mut transaction = sponsored_transaction(sponsor_account, dec!(10));
+        if let FeePayerV2::Sponsor(sponsor) = &mut transaction.signed_intent.fee_payer {
+            sponsor.max_fee = dec!(1000);
+        }
+        assert_eq!(
+            validate_v2(&transaction).map(|_| ()),
+            Err(TransactionValidationError::SignatureValidationError(
+                SignatureValidationError::InvalidFeeSponsorSignature
+            ))
+        );
+    }
 }